@@ -0,0 +1,271 @@
+//! Versioned backups of files jigolo overwrites.
+//!
+//! Before jigolo replaces a file on disk it copies the previous content to
+//! `~/.config/jigolo/backups/<encoded path>/<unix millis>.md`. The encoded
+//! directory name is reversible, so backups of files that have since been
+//! deleted can still be listed and restored.
+
+use anyhow::Context;
+use anyhow::Result;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::SystemTime;
+use std::time::UNIX_EPOCH;
+
+use crate::fsutil::decode_path;
+use crate::fsutil::encode_path;
+
+/// Oldest backups beyond this count are pruned when a new one is created.
+pub const MAX_BACKUPS_PER_FILE: usize = 20;
+
+/// One stored version of a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Backup {
+    /// Location of the backup copy.
+    pub path: PathBuf,
+    /// Creation time in milliseconds since the Unix epoch.
+    pub created_millis: u64,
+}
+
+impl Backup {
+    /// Returns the creation time formatted for display.
+    pub fn timestamp(&self) -> String {
+        format_timestamp(self.created_millis)
+    }
+}
+
+/// Returns the backups directory inside jigolo's config directory.
+pub fn backups_dir_in(config_dir: &Path) -> PathBuf {
+    config_dir.join("backups")
+}
+
+/// Returns the directory holding all backups of `file`.
+pub fn file_backup_dir(backups: &Path, file: &Path) -> PathBuf {
+    backups.join(encode_path(file))
+}
+
+/// Copies the current content of `file` into the backup store.
+///
+/// Returns `Ok(None)` when the file does not exist or its content matches the
+/// newest backup, so repeated saves without changes do not pile up copies.
+pub fn create_backup(backups: &Path, file: &Path) -> Result<Option<PathBuf>> {
+    let content = match fs::read_to_string(file) {
+        Ok(content) => content,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(None),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", file.display()));
+        }
+    };
+
+    let existing = list_backups(backups, file)?;
+    if let Some(newest) = existing.first()
+        && fs::read_to_string(&newest.path).is_ok_and(|prev| prev == content)
+    {
+        return Ok(None);
+    }
+
+    let dir = file_backup_dir(backups, file);
+    fs::create_dir_all(&dir)
+        .with_context(|| format!("failed to create directory {}", dir.display()))?;
+
+    let mut millis = now_millis();
+    let mut path = dir.join(format!("{millis}.md"));
+    while path.exists() {
+        millis += 1;
+        path = dir.join(format!("{millis}.md"));
+    }
+    fs::write(&path, content).with_context(|| format!("failed to write {}", path.display()))?;
+
+    for stale in existing.iter().skip(MAX_BACKUPS_PER_FILE - 1) {
+        let _ = fs::remove_file(&stale.path);
+    }
+
+    Ok(Some(path))
+}
+
+/// Lists the backups of `file`, newest first. A file without backups yields
+/// an empty list.
+pub fn list_backups(backups: &Path, file: &Path) -> Result<Vec<Backup>> {
+    let dir = file_backup_dir(backups, file);
+    let entries = match fs::read_dir(&dir) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", dir.display()));
+        }
+    };
+
+    let mut result: Vec<Backup> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| {
+            let path = entry.path();
+            let millis = path.file_stem()?.to_str()?.parse::<u64>().ok()?;
+            Some(Backup {
+                path,
+                created_millis: millis,
+            })
+        })
+        .collect();
+    result.sort_unstable_by_key(|b| std::cmp::Reverse(b.created_millis));
+    Ok(result)
+}
+
+/// Returns files under `root` that have backups but no longer exist on disk.
+pub fn deleted_files_with_backups(backups: &Path, root: &Path) -> Result<Vec<PathBuf>> {
+    let entries = match fs::read_dir(backups) {
+        Ok(entries) => entries,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", backups.display()));
+        }
+    };
+
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(|entry| entry.ok())
+        .filter_map(|entry| decode_path(entry.file_name().to_str()?))
+        .filter(|path| path.starts_with(root) && !path.exists())
+        .collect();
+    files.sort_unstable();
+    Ok(files)
+}
+
+/// Formats milliseconds since the Unix epoch as `YYYY-MM-DD HH:MM:SS UTC`.
+pub fn format_timestamp(millis: u64) -> String {
+    let secs = millis / 1000;
+    let days = (secs / 86_400) as i64;
+    let rem = secs % 86_400;
+    let (year, month, day) = civil_from_days(days);
+    format!(
+        "{year:04}-{month:02}-{day:02} {:02}:{:02}:{:02} UTC",
+        rem / 3600,
+        (rem % 3600) / 60,
+        rem % 60
+    )
+}
+
+/// Returns the current time in milliseconds since the Unix epoch.
+pub fn now_millis() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |d| d.as_millis() as u64)
+}
+
+/// Converts days since 1970-01-01 to a (year, month, day) civil date.
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    // Howard Hinnant's days_from_civil inverse.
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z.rem_euclid(146_097);
+    let yoe = (doe - doe / 1460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = (doy - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = yoe + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn create_backup_of_missing_file_is_none() {
+        let tmp = TempDir::new().unwrap();
+        let result = create_backup(tmp.path(), &tmp.path().join("nope.md")).unwrap();
+        assert!(result.is_none());
+    }
+
+    #[test]
+    fn create_backup_copies_content() {
+        let tmp = TempDir::new().unwrap();
+        let backups = tmp.path().join("backups");
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "v1").unwrap();
+
+        let backup = create_backup(&backups, &file).unwrap().unwrap();
+
+        assert_eq!(fs::read_to_string(backup).unwrap(), "v1");
+        assert_eq!(list_backups(&backups, &file).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn unchanged_content_is_not_backed_up_twice() {
+        let tmp = TempDir::new().unwrap();
+        let backups = tmp.path().join("backups");
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "same").unwrap();
+
+        create_backup(&backups, &file).unwrap();
+        let second = create_backup(&backups, &file).unwrap();
+
+        assert!(second.is_none());
+        assert_eq!(list_backups(&backups, &file).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn list_backups_is_newest_first() {
+        let tmp = TempDir::new().unwrap();
+        let backups = tmp.path().join("backups");
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "v1").unwrap();
+        create_backup(&backups, &file).unwrap();
+        fs::write(&file, "v2").unwrap();
+        create_backup(&backups, &file).unwrap();
+
+        let list = list_backups(&backups, &file).unwrap();
+
+        assert_eq!(list.len(), 2);
+        assert!(list[0].created_millis > list[1].created_millis);
+        assert_eq!(fs::read_to_string(&list[0].path).unwrap(), "v2");
+    }
+
+    #[test]
+    fn old_backups_are_pruned() {
+        let tmp = TempDir::new().unwrap();
+        let backups = tmp.path().join("backups");
+        let file = tmp.path().join("CLAUDE.md");
+        for i in 0..MAX_BACKUPS_PER_FILE + 3 {
+            fs::write(&file, format!("v{i}")).unwrap();
+            create_backup(&backups, &file).unwrap();
+        }
+
+        let list = list_backups(&backups, &file).unwrap();
+
+        assert_eq!(list.len(), MAX_BACKUPS_PER_FILE);
+        let newest = fs::read_to_string(&list[0].path).unwrap();
+        assert_eq!(newest, format!("v{}", MAX_BACKUPS_PER_FILE + 2));
+    }
+
+    #[test]
+    fn deleted_files_are_listed_under_root() {
+        let tmp = TempDir::new().unwrap();
+        let backups = tmp.path().join("backups");
+        let root = tmp.path().join("root");
+        fs::create_dir_all(&root).unwrap();
+        let kept = root.join("CLAUDE.md");
+        let gone = root.join("gone.md");
+        fs::write(&kept, "k").unwrap();
+        fs::write(&gone, "g").unwrap();
+        create_backup(&backups, &kept).unwrap();
+        create_backup(&backups, &gone).unwrap();
+        fs::remove_file(&gone).unwrap();
+
+        let deleted = deleted_files_with_backups(&backups, &root).unwrap();
+
+        assert_eq!(deleted, vec![gone]);
+    }
+
+    #[test]
+    fn format_timestamp_epoch_and_known_date() {
+        assert_eq!(format_timestamp(0), "1970-01-01 00:00:00 UTC");
+        // 2024-02-29 12:34:56 UTC
+        assert_eq!(
+            format_timestamp(1_709_210_096_000),
+            "2024-02-29 12:34:56 UTC"
+        );
+    }
+}
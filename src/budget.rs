@@ -0,0 +1,283 @@
+//! Context budget: how much of the model's context the files Claude Code
+//! loads for a project take up.
+
+use std::cmp::Reverse;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::model::SourceRoot;
+use crate::tokens::Tokenizer;
+use crate::tokens::format_tokens;
+
+/// Files above this many tokens are flagged when the config sets no
+/// threshold. About 40k characters, where Claude Code starts warning about
+/// a large CLAUDE.md.
+pub const DEFAULT_OVERSIZED_TOKENS: u64 = 10_000;
+
+/// Size of one file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetEntry {
+    pub path: PathBuf,
+    pub bytes: u64,
+    pub lines: usize,
+    pub tokens: u64,
+}
+
+impl BudgetEntry {
+    /// Measures the file at `path`. Files that cannot be read count as empty.
+    pub fn measure(path: &Path, tokenizer: &dyn Tokenizer) -> Self {
+        let text = fs::read_to_string(path).unwrap_or_default();
+        Self {
+            path: path.to_path_buf(),
+            bytes: text.len() as u64,
+            lines: text.lines().count(),
+            tokens: tokenizer.count(&text),
+        }
+    }
+}
+
+/// Files of one kind, such as the project's CLAUDE.md files.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BudgetGroup {
+    pub title: String,
+    pub entries: Vec<BudgetEntry>,
+}
+
+impl BudgetGroup {
+    /// Combined bytes, lines and tokens of the group's files.
+    pub fn totals(&self) -> (u64, usize, u64) {
+        self.entries.iter().fold((0, 0, 0), |(b, l, t), e| {
+            (b + e.bytes, l + e.lines, t + e.tokens)
+        })
+    }
+}
+
+/// Every group plus the per-file threshold above which a file is oversized.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Budget {
+    pub groups: Vec<BudgetGroup>,
+    pub oversized_tokens: u64,
+    /// Thresholds set by projects' `.jigolo.toml`, for the files under each
+    /// directory. The deepest directory holding a file wins.
+    pub limits: Vec<(PathBuf, u64)>,
+}
+
+impl Budget {
+    /// Measures the global CLAUDE.md, the CLAUDE.md files under `roots`
+    /// (other than the global one) and the `settings` files.
+    pub fn build(
+        global: Option<&Path>,
+        roots: &[SourceRoot],
+        settings: &[PathBuf],
+        tokenizer: &dyn Tokenizer,
+        oversized_tokens: u64,
+    ) -> Self {
+        let measure_all = |paths: Vec<&Path>| -> Vec<BudgetEntry> {
+            paths
+                .into_iter()
+                .map(|path| BudgetEntry::measure(path, tokenizer))
+                .collect()
+        };
+        let project: Vec<&Path> = roots
+            .iter()
+            .flat_map(|root| root.file_paths())
+            .map(PathBuf::as_path)
+            .filter(|file| Some(*file) != global)
+            .collect();
+        let groups = vec![
+            BudgetGroup {
+                title: "Global CLAUDE.md".to_string(),
+                entries: measure_all(global.into_iter().collect()),
+            },
+            BudgetGroup {
+                title: "Project CLAUDE.md files".to_string(),
+                entries: measure_all(project),
+            },
+            BudgetGroup {
+                title: "Settings files".to_string(),
+                entries: measure_all(settings.iter().map(PathBuf::as_path).collect()),
+            },
+        ];
+        Self {
+            groups,
+            oversized_tokens,
+            limits: Vec::new(),
+        }
+    }
+
+    /// The threshold for the file at `path`.
+    pub fn limit_for(&self, path: &Path) -> u64 {
+        self.limits
+            .iter()
+            .filter(|(dir, _)| path.starts_with(dir))
+            .max_by_key(|(dir, _)| dir.components().count())
+            .map_or(self.oversized_tokens, |(_, limit)| *limit)
+    }
+
+    /// Whether `entry` is above its threshold.
+    pub fn is_oversized(&self, entry: &BudgetEntry) -> bool {
+        entry.tokens > self.limit_for(&entry.path)
+    }
+
+    /// Combined bytes, lines and tokens of every group.
+    pub fn totals(&self) -> (u64, usize, u64) {
+        self.groups
+            .iter()
+            .map(BudgetGroup::totals)
+            .fold((0, 0, 0), |(b, l, t), (gb, gl, gt)| {
+                (b + gb, l + gl, t + gt)
+            })
+    }
+
+    /// Files above the threshold, largest first.
+    pub fn oversized(&self) -> Vec<&BudgetEntry> {
+        let mut entries: Vec<&BudgetEntry> = self
+            .groups
+            .iter()
+            .flat_map(|group| &group.entries)
+            .filter(|entry| self.is_oversized(entry))
+            .collect();
+        entries.sort_unstable_by_key(|entry| Reverse(entry.tokens));
+        entries
+    }
+}
+
+/// Formats a byte count compactly: `512 B`, `6.1 KB`, `1.2 MB`.
+pub fn format_bytes(bytes: u64) -> String {
+    match bytes {
+        0..1_024 => format!("{bytes} B"),
+        1_024..1_048_576 => format!("{:.1} KB", bytes as f64 / 1_024.0),
+        _ => format!("{:.1} MB", bytes as f64 / 1_048_576.0),
+    }
+}
+
+/// One-line sense of a file's scale: `1,243 lines · 8.4 KB · ~2.1k tok`.
+/// `more_lines` marks a line count taken from only the start of the file.
+pub fn size_summary(lines: usize, more_lines: bool, bytes: u64, tokens: u64) -> String {
+    let plus = if more_lines { "+" } else { "" };
+    let noun = if lines == 1 && !more_lines {
+        "line"
+    } else {
+        "lines"
+    };
+    format!(
+        "{}{plus} {noun} · {} · ~{}",
+        group_digits(lines),
+        format_bytes(bytes),
+        format_tokens(tokens)
+    )
+}
+
+/// `1243` as `1,243`.
+fn group_digits(n: usize) -> String {
+    let digits = n.to_string();
+    let mut grouped = String::new();
+    for (i, digit) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i).is_multiple_of(3) {
+            grouped.push(',');
+        }
+        grouped.push(digit);
+    }
+    grouped
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::model::DiscoveredFile;
+    use crate::tokens::ByteHeuristic;
+
+    #[test]
+    fn budget_groups_files_and_flags_oversized() {
+        let tmp = TempDir::new().unwrap();
+        let global = tmp.path().join("global.md");
+        let small = tmp.path().join("CLAUDE.md");
+        let big = tmp.path().join("sub/CLAUDE.md");
+        let settings = tmp.path().join("settings.json");
+        fs::create_dir_all(tmp.path().join("sub")).unwrap();
+        fs::write(&global, "a\nb\n").unwrap();
+        fs::write(&small, "x".repeat(40)).unwrap();
+        fs::write(&big, "y\n".repeat(200)).unwrap();
+        fs::write(&settings, "{}").unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![
+                DiscoveredFile::new(global.clone()),
+                DiscoveredFile::new(small),
+                DiscoveredFile::new(big.clone()),
+            ],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+
+        let budget = Budget::build(Some(&global), &roots, &[settings], &ByteHeuristic, 50);
+
+        assert_eq!(budget.groups[0].entries.len(), 1);
+        assert_eq!(budget.groups[1].entries.len(), 2);
+        assert_eq!(budget.groups[1].totals(), (440, 201, 110));
+        assert_eq!(budget.totals(), (446, 204, 112));
+        let oversized: Vec<&Path> = budget
+            .oversized()
+            .iter()
+            .map(|e| e.path.as_path())
+            .collect();
+        assert_eq!(oversized, [big.as_path()]);
+    }
+
+    #[test]
+    fn project_limits_override_the_threshold_under_their_directory() {
+        let tmp = TempDir::new().unwrap();
+        let strict = tmp.path().join("strict");
+        let file = strict.join("CLAUDE.md");
+        let other = tmp.path().join("CLAUDE.md");
+        fs::create_dir_all(&strict).unwrap();
+        fs::write(&file, "x".repeat(80)).unwrap();
+        fs::write(&other, "x".repeat(80)).unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![
+                DiscoveredFile::new(other),
+                DiscoveredFile::new(file.clone()),
+            ],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+
+        let mut budget = Budget::build(None, &roots, &[], &ByteHeuristic, 50);
+        assert!(budget.oversized().is_empty());
+        budget.limits = vec![(tmp.path().to_path_buf(), 100), (strict, 10)];
+
+        assert_eq!(budget.limit_for(&file), 10);
+        let oversized: Vec<&Path> = budget
+            .oversized()
+            .iter()
+            .map(|e| e.path.as_path())
+            .collect();
+        assert_eq!(oversized, [file.as_path()]);
+    }
+
+    #[test]
+    fn bytes_are_compact() {
+        assert_eq!(format_bytes(512), "512 B");
+        assert_eq!(format_bytes(6_246), "6.1 KB");
+        assert_eq!(format_bytes(1_300_000), "1.2 MB");
+    }
+
+    #[test]
+    fn size_summary_groups_thousands() {
+        assert_eq!(
+            size_summary(1_243, false, 8_602, 2_150),
+            "1,243 lines · 8.4 KB · ~2.1k tok"
+        );
+        assert_eq!(size_summary(1, false, 4, 1), "1 line · 4 B · ~1 tok");
+        assert_eq!(
+            size_summary(999, true, 2_000_000, 500_000),
+            "999+ lines · 1.9 MB · ~500.0k tok"
+        );
+    }
+}
@@ -0,0 +1,136 @@
+//! Copying text to the system clipboard.
+//!
+//! The platform's clipboard tool is tried first (`pbcopy`, `clip`,
+//! `wl-copy`, `xclip`, `xsel`). When none of them works, which is the usual
+//! case over SSH, the text is sent to the terminal as an OSC 52 escape
+//! sequence; most modern terminals put it on the local clipboard. Consoles
+//! that would print the sequence as text instead get an error.
+
+use std::env;
+use std::io;
+use std::io::Write;
+use std::process::Command;
+use std::process::Stdio;
+
+/// How the text reached the clipboard.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CopyMethod {
+    /// Through the named clipboard program.
+    Program(&'static str),
+    /// Through an OSC 52 escape sequence written to the terminal.
+    Osc52,
+}
+
+impl CopyMethod {
+    /// Short description for status messages.
+    pub fn describe(self) -> String {
+        match self {
+            CopyMethod::Program(name) => name.to_string(),
+            CopyMethod::Osc52 => "terminal, OSC 52".to_string(),
+        }
+    }
+}
+
+/// Copies `text` to the clipboard, returning how it got there. Fails only if
+/// no clipboard program worked and OSC 52 is either not `osc52` capable or
+/// the terminal could not be written to.
+pub fn copy_to_clipboard(text: &str, osc52: bool) -> io::Result<CopyMethod> {
+    for (program, args) in clipboard_programs() {
+        if pipe_to(program, args, text).is_ok() {
+            return Ok(CopyMethod::Program(program));
+        }
+    }
+    if !osc52 {
+        return Err(io::Error::other(
+            "no clipboard tool worked and this terminal does not support OSC 52",
+        ));
+    }
+    let mut stdout = io::stdout();
+    stdout.write_all(osc52_sequence(text).as_bytes())?;
+    stdout.flush()?;
+    Ok(CopyMethod::Osc52)
+}
+
+/// Clipboard programs worth trying on this platform, in order.
+fn clipboard_programs() -> Vec<(&'static str, &'static [&'static str])> {
+    if cfg!(target_os = "macos") {
+        return vec![("pbcopy", &[])];
+    }
+    if cfg!(windows) {
+        return vec![("clip", &[])];
+    }
+    let mut programs: Vec<(&'static str, &'static [&'static str])> = Vec::new();
+    if env::var_os("WAYLAND_DISPLAY").is_some() {
+        programs.push(("wl-copy", &[]));
+    }
+    if env::var_os("DISPLAY").is_some() {
+        programs.push(("xclip", &["-selection", "clipboard"]));
+        programs.push(("xsel", &["--clipboard", "--input"]));
+    }
+    programs
+}
+
+/// Runs `program` with `text` on its stdin and waits for it to succeed.
+fn pipe_to(program: &str, args: &[&str], text: &str) -> io::Result<()> {
+    let mut child = Command::new(program)
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::null())
+        .stderr(Stdio::null())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(text.as_bytes())?;
+    }
+    let status = child.wait()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(io::Error::other(format!("{program} exited with {status}")))
+    }
+}
+
+/// The OSC 52 sequence asking the terminal to set its clipboard to `text`.
+pub fn osc52_sequence(text: &str) -> String {
+    format!("\x1b]52;c;{}\x07", base64_encode(text.as_bytes()))
+}
+
+/// Standard base64 with padding.
+fn base64_encode(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+    let mut out = String::with_capacity(bytes.len().div_ceil(3) * 4);
+    for chunk in bytes.chunks(3) {
+        let b = [
+            chunk[0],
+            chunk.get(1).copied().unwrap_or(0),
+            chunk.get(2).copied().unwrap_or(0),
+        ];
+        let n = (u32::from(b[0]) << 16) | (u32::from(b[1]) << 8) | u32::from(b[2]);
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(char::from(ALPHABET[(n >> (18 - 6 * i) & 0x3f) as usize]));
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_matches_reference_vectors() {
+        assert_eq!(base64_encode(b""), "");
+        assert_eq!(base64_encode(b"f"), "Zg==");
+        assert_eq!(base64_encode(b"fo"), "Zm8=");
+        assert_eq!(base64_encode(b"foo"), "Zm9v");
+        assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+    }
+
+    #[test]
+    fn osc52_wraps_encoded_text() {
+        assert_eq!(osc52_sequence("hi"), "\x1b]52;c;aGk=\x07");
+    }
+}
@@ -0,0 +1,80 @@
+//! HTML comments in a CLAUDE.md.
+//!
+//! Comments (`<!-- ... -->`) are notes for people editing the file, and
+//! markers tools leave to record where text came from. Hiding them shows
+//! the file as the instructions it carries.
+
+/// `text` without its HTML comments, and how many lines were dropped
+/// because nothing but a comment was on them. Text around a comment on the
+/// same line is kept. Comments may span lines; ones inside fenced code
+/// blocks are examples and left alone.
+pub fn strip_html_comments(text: &str) -> (String, usize) {
+    let mut out = String::new();
+    let mut hidden = 0;
+    let mut in_comment = false;
+    let mut in_fence = false;
+    for line in text.lines() {
+        let trimmed = line.trim_start();
+        let fence = !in_comment && (trimmed.starts_with("```") || trimmed.starts_with("~~~"));
+        if fence {
+            in_fence = !in_fence;
+        }
+        if fence || in_fence {
+            out.push_str(line);
+            out.push('\n');
+            continue;
+        }
+
+        let mut kept = String::new();
+        let mut touched = in_comment;
+        let mut rest = line;
+        loop {
+            if in_comment {
+                let Some(end) = rest.find("-->") else {
+                    break;
+                };
+                rest = &rest[end + 3..];
+                in_comment = false;
+            } else {
+                let Some(start) = rest.find("<!--") else {
+                    kept.push_str(rest);
+                    break;
+                };
+                kept.push_str(&rest[..start]);
+                rest = &rest[start + 4..];
+                in_comment = true;
+                touched = true;
+            }
+        }
+        if touched && kept.trim().is_empty() {
+            hidden += 1;
+            continue;
+        }
+        out.push_str(&kept);
+        out.push('\n');
+    }
+    (out, hidden)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn comment_lines_are_dropped_and_counted() {
+        let text = "# Rules\n<!-- source: team.md -->\n- Use tabs\n<!--\nold rule\n-->\n- Test first <!-- added 2024 -->\n";
+        let (shown, hidden) = strip_html_comments(text);
+
+        assert_eq!(shown, "# Rules\n- Use tabs\n- Test first \n");
+        assert_eq!(hidden, 4);
+    }
+
+    #[test]
+    fn comments_in_code_blocks_are_kept() {
+        let text = "```html\n<!-- example -->\n```\n<!-- note -->\nText\n";
+        let (shown, hidden) = strip_html_comments(text);
+
+        assert_eq!(shown, "```html\n<!-- example -->\n```\nText\n");
+        assert_eq!(hidden, 1);
+    }
+}
@@ -1,4 +1,6 @@
 use crate::library::Snippet;
+use crate::outline::parse_headings;
+use crate::outline::section_ranges;
 
 /// Concatenates selected snippets in selection order, separated by double newlines.
 ///
@@ -8,20 +10,94 @@ pub fn compose_snippets(snippets: &[Snippet], selected: &[usize]) -> String {
     selected
         .iter()
         .filter_map(|&i| snippets.get(i))
-        .map(|s| s.content.as_str())
+        .map(snippet_block)
         .collect::<Vec<_>>()
         .join("\n\n")
 }
 
+/// The text `snippet` contributes to a CLAUDE.md: its content, wrapped in a
+/// code fence when its format calls for one and it is not fenced already.
+pub fn snippet_block(snippet: &Snippet) -> String {
+    let content = snippet.content.trim_matches('\n');
+    match snippet.format().fence_language() {
+        Some(language) if !content.trim_start().starts_with("```") => {
+            format!("```{language}\n{content}\n```")
+        }
+        _ => content.to_string(),
+    }
+}
+
+/// Returns `content` with `snippet` inserted as its own block after line
+/// `after_line` (0-based), or at the end when `after_line` is `None` or past
+/// the last line. Blank lines separate the block from neighbouring text.
+/// CRLF line endings and a missing final newline are kept as they were.
+pub fn insert_snippet(content: &str, after_line: Option<usize>, snippet: &str) -> String {
+    let newline = if content.contains("\r\n") {
+        "\r\n"
+    } else {
+        "\n"
+    };
+    let mut lines: Vec<&str> = content.lines().collect();
+    let at = after_line.map_or(lines.len(), |line| (line + 1).min(lines.len()));
+
+    let mut block = Vec::new();
+    if at > 0 && !lines[at - 1].trim().is_empty() {
+        block.push("");
+    }
+    block.extend(snippet.trim_matches('\n').lines());
+    if at < lines.len() && !lines[at].trim().is_empty() {
+        block.push("");
+    }
+    lines.splice(at..at, block);
+
+    let mut out = lines.join(newline);
+    if content.is_empty() || content.ends_with('\n') {
+        out.push_str(newline);
+    }
+    out
+}
+
+/// Returns `content` with `snippet` appended as its own block at the end of
+/// the section whose heading is on line `heading_line` (0-based), after its
+/// subsections, or at the end of the file when `heading_line` is `None` or
+/// holds no heading.
+pub fn append_to_section(content: &str, heading_line: Option<usize>, snippet: &str) -> String {
+    let lines: Vec<&str> = content.lines().collect();
+    let headings = parse_headings(content);
+    let section = heading_line.and_then(|line| {
+        let index = headings.iter().position(|h| h.line == line)?;
+        section_ranges(&headings, lines.len())
+            .into_iter()
+            .nth(index)
+    });
+    let Some(section) = section else {
+        return insert_snippet(content, None, snippet);
+    };
+    // After the last non-blank line, so trailing blank lines stay between
+    // the section and the next heading.
+    let last = lines[section.clone()]
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(section.start, |offset| section.start + offset);
+    insert_snippet(content, Some(last), snippet)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::library::SnippetFormat;
 
     fn snippet(title: &str, content: &str) -> Snippet {
         Snippet {
             title: title.to_string(),
             content: content.to_string(),
             source: String::new(),
+            format: None,
+            archived: false,
+            tags: Vec::new(),
+            lines: None,
+            created: None,
+            scope: Default::default(),
         }
     }
 
@@ -87,4 +163,77 @@ mod tests {
             "line 1\nline 2\n\nline 3\nline 4"
         );
     }
+
+    #[test]
+    fn insert_after_line_adds_blank_separators() {
+        assert_eq!(
+            insert_snippet("# Title\nBody\n", Some(0), "new"),
+            "# Title\n\nnew\n\nBody\n"
+        );
+    }
+
+    #[test]
+    fn insert_next_to_blank_lines_adds_no_extra_ones() {
+        assert_eq!(
+            insert_snippet("# Title\n\nBody\n", Some(0), "new\n"),
+            "# Title\n\nnew\n\nBody\n"
+        );
+    }
+
+    #[test]
+    fn insert_keeps_crlf_and_a_missing_final_newline() {
+        assert_eq!(
+            insert_snippet("# Title\r\nBody\r\n", Some(0), "new\n"),
+            "# Title\r\n\r\nnew\r\n\r\nBody\r\n"
+        );
+        assert_eq!(insert_snippet("# Title", None, "a\nb"), "# Title\n\na\nb");
+        assert_eq!(
+            insert_snippet("a\r\nb", Some(0), "x"),
+            "a\r\n\r\nx\r\n\r\nb"
+        );
+    }
+
+    #[test]
+    fn insert_without_line_appends() {
+        assert_eq!(
+            insert_snippet("# Title\n", None, "a\nb"),
+            "# Title\n\na\nb\n"
+        );
+        assert_eq!(insert_snippet("x\n", Some(99), "y"), "x\n\ny\n");
+        assert_eq!(insert_snippet("", None, "y"), "y\n");
+    }
+
+    #[test]
+    fn append_to_section_goes_after_its_subsections() {
+        let text = "# Rules\n- tabs\n\n## Style\nshort\n\n# Build\nmake\n";
+
+        assert_eq!(
+            append_to_section(text, Some(0), "- new"),
+            "# Rules\n- tabs\n\n## Style\nshort\n\n- new\n\n# Build\nmake\n"
+        );
+        assert_eq!(
+            append_to_section(text, None, "- new"),
+            "# Rules\n- tabs\n\n## Style\nshort\n\n# Build\nmake\n\n- new\n"
+        );
+    }
+
+    #[test]
+    fn code_formats_are_fenced() {
+        let mut json = snippet("Settings", "{\"a\": 1}");
+        json.format = Some(SnippetFormat::Json);
+        let shell = snippet("Build", "cargo build\n");
+        let prose = snippet("Rule", "Keep functions short.");
+
+        assert_eq!(snippet_block(&json), "```json\n{\"a\": 1}\n```");
+        assert_eq!(snippet_block(&shell), "```sh\ncargo build\n```");
+        assert_eq!(snippet_block(&prose), "Keep functions short.");
+    }
+
+    #[test]
+    fn already_fenced_content_is_not_fenced_again() {
+        let mut fenced = snippet("Build", "```bash\nmake\n```");
+        fenced.format = Some(SnippetFormat::Shell);
+
+        assert_eq!(snippet_block(&fenced), "```bash\nmake\n```");
+    }
 }
@@ -6,11 +6,18 @@ use anyhow::Context;
 use anyhow::Result;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::BTreeMap;
 use std::env;
 use std::fs;
 use std::io::ErrorKind;
 use std::path::Path;
 use std::path::PathBuf;
+use std::time::Duration;
+
+use crate::git::find_git_repo;
+use crate::lint::RuleLevel;
+use crate::tokens::Tokenizer;
+use crate::tokens::TokenizerKind;
 
 /// User preferences persisted across sessions.
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
@@ -24,6 +31,146 @@ pub struct Config {
     /// Default maximum scan depth (overrides the built-in default of 3).
     #[serde(default)]
     pub default_depth: Option<usize>,
+    /// Quiet period in milliseconds before file-system changes are applied.
+    #[serde(default)]
+    pub watch_debounce_ms: Option<u64>,
+    /// Heading that snippets exported as memories are appended under.
+    #[serde(default)]
+    pub memory_heading: Option<String>,
+    /// Files larger than this many kilobytes are previewed only in part.
+    #[serde(default)]
+    pub preview_max_kb: Option<u64>,
+    /// Start with files nested under the git repository they belong to.
+    #[serde(default)]
+    pub group_by_repo: Option<bool>,
+    /// Token estimator: `"bytes"` (the default) or `"words"`.
+    #[serde(default)]
+    pub tokenizer: Option<TokenizerKind>,
+    /// Files above this many tokens are flagged in the context budget.
+    #[serde(default)]
+    pub oversized_tokens: Option<u64>,
+    /// Key binding overrides: key context (`files_tree`, `library`, ...) to
+    /// key (`"ctrl+f"`, `"g g"`) to action name (`"open_file_finder"`, or
+    /// `"none"` to unbind). Checked when the TUI starts.
+    #[serde(default)]
+    pub keymap: Option<BTreeMap<String, BTreeMap<String, String>>>,
+    /// Theme colour overrides: role (`active_border`, `help_key`, ...) to
+    /// colour (`"cyan"`, `"#ff8800"`, `"white on blue"`).
+    #[serde(default)]
+    pub colors: Option<BTreeMap<String, String>>,
+    /// Directory or git repository URL of team templates for `jigolo init`.
+    #[serde(default)]
+    pub template_source: Option<String>,
+    /// Editor command files are opened in, over `$VISUAL` and `$EDITOR`.
+    #[serde(default)]
+    pub editor: Option<String>,
+    /// Names of the files scans pick up, `*` matching within a name;
+    /// `CLAUDE.md` and `CLAUDE.local.md` when unset.
+    #[serde(default)]
+    pub file_patterns: Option<Vec<String>>,
+    /// External tools `X` runs on the selected file: name to command, with
+    /// `{file}` for the file's path or the path added at the end.
+    #[serde(default)]
+    pub tools: Option<BTreeMap<String, String>>,
+    /// Lint rule name to `"off"`, `"warning"` or `"error"`, overriding the
+    /// rule's own severity.
+    #[serde(default)]
+    pub lint_rules: Option<BTreeMap<String, RuleLevel>>,
+    /// Names of the directories scans never enter, instead of the built-in
+    /// list (`node_modules`, `.git`, `target`, ...).
+    #[serde(default)]
+    pub skip_dirs: Option<Vec<String>>,
+    /// Directories skipped on top of `skip_dirs`, as in a project's
+    /// `skip_dirs`.
+    #[serde(default)]
+    pub exclude: Option<Vec<String>>,
+    /// Directories scanned even when skipped, as in a project's `include`.
+    #[serde(default)]
+    pub include: Option<Vec<String>>,
+}
+
+/// Preview size cap used when the config does not set `preview_max_kb`.
+pub const DEFAULT_PREVIEW_MAX_KB: u64 = 64;
+
+impl Config {
+    /// Returns the watcher debounce window, falling back to the default.
+    pub fn watch_debounce(&self) -> Duration {
+        Duration::from_millis(
+            self.watch_debounce_ms
+                .unwrap_or(crate::watch::DEFAULT_DEBOUNCE_MS),
+        )
+    }
+
+    /// Returns the memory section heading, falling back to the default.
+    pub fn memory_heading(&self) -> &str {
+        self.memory_heading
+            .as_deref()
+            .unwrap_or(crate::memory::DEFAULT_MEMORY_HEADING)
+    }
+
+    /// Returns the preview size cap in kilobytes, falling back to the default.
+    pub fn preview_max_kb(&self) -> u64 {
+        self.preview_max_kb.unwrap_or(DEFAULT_PREVIEW_MAX_KB)
+    }
+
+    /// Returns the configured token estimator, falling back to the default.
+    pub fn tokenizer(&self) -> &'static dyn Tokenizer {
+        self.tokenizer.unwrap_or_default().tokenizer()
+    }
+
+    /// Returns the oversized-file threshold in tokens, falling back to the
+    /// default.
+    pub fn oversized_tokens(&self) -> u64 {
+        self.oversized_tokens
+            .unwrap_or(crate::budget::DEFAULT_OVERSIZED_TOKENS)
+    }
+}
+
+/// Per-project config file, kept at the root of a repository.
+pub const PROJECT_CONFIG_FILE: &str = ".jigolo.toml";
+
+/// Scan settings for one project, read from its `.jigolo.toml` and applied
+/// over [`Config`] only when that project is scanned.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
+pub struct ProjectConfig {
+    /// Scan depth, instead of `default_depth`. `--depth` still wins.
+    #[serde(default)]
+    pub depth: Option<usize>,
+    /// Directories to skip on top of the skip list: a name skips every
+    /// directory called that, a path with `/` the one directory under the
+    /// project root. `*` matches within one path component.
+    #[serde(default)]
+    pub skip_dirs: Vec<String>,
+    /// Directories scanned even when a skip rule would prune them or a
+    /// directory above them: a name every directory called that, a path
+    /// with `/` the one under the project root, with `*` wildcards.
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Files of this project above this many tokens are flagged in the
+    /// context budget, instead of `oversized_tokens`.
+    #[serde(default)]
+    pub oversized_tokens: Option<u64>,
+    /// Lint rule levels for this project's files, over `lint_rules`.
+    #[serde(default)]
+    pub lint_rules: BTreeMap<String, RuleLevel>,
+}
+
+/// The project config for a scan of `root`: the `.jigolo.toml` in `root`
+/// itself, or else at the top of the git repository holding it, with the
+/// directory it was found in. `None` when there is neither.
+pub fn find_project_config(root: &Path) -> Option<(PathBuf, Result<ProjectConfig>)> {
+    let repo_root = find_git_repo(root).map(|repo| repo.root);
+    let base = [Some(root.to_path_buf()), repo_root]
+        .into_iter()
+        .flatten()
+        .find(|dir| dir.join(PROJECT_CONFIG_FILE).is_file())?;
+    let path = base.join(PROJECT_CONFIG_FILE);
+    let config = fs::read_to_string(&path)
+        .with_context(|| format!("failed to read {}", path.display()))
+        .and_then(|text| {
+            toml::from_str(&text).with_context(|| format!("failed to parse {}", path.display()))
+        });
+    Some((base, config))
 }
 
 /// Returns the default config file path using the `HOME` environment
@@ -35,7 +182,18 @@ pub fn config_path() -> Option<PathBuf> {
 
 /// Returns the config file path relative to a given home directory.
 pub fn config_path_in(home: &Path) -> PathBuf {
-    home.join(".config").join("jigolo").join("config.toml")
+    config_dir_in(home).join("config.toml")
+}
+
+/// Returns jigolo's config directory using the `HOME` environment variable.
+pub fn config_dir() -> Option<PathBuf> {
+    let home = env::var("HOME").ok()?;
+    Some(config_dir_in(&PathBuf::from(home)))
+}
+
+/// Returns jigolo's config directory relative to a given home directory.
+pub fn config_dir_in(home: &Path) -> PathBuf {
+    home.join(".config").join("jigolo")
 }
 
 /// Loads configuration from the default path, returning `Config::default()`
@@ -76,6 +234,11 @@ mod tests {
         assert_eq!(config.theme, None);
         assert_eq!(config.default_paths, None);
         assert_eq!(config.default_depth, None);
+        assert_eq!(config.watch_debounce_ms, None);
+        assert_eq!(config.memory_heading, None);
+        assert_eq!(config.preview_max_kb, None);
+        assert_eq!(config.preview_max_kb(), DEFAULT_PREVIEW_MAX_KB);
+        assert_eq!(config.group_by_repo, None);
     }
 
     #[test]
@@ -88,6 +251,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn config_dir_in_returns_expected_path() {
+        let home = PathBuf::from("/home/testuser");
+        assert_eq!(
+            config_dir_in(&home),
+            PathBuf::from("/home/testuser/.config/jigolo")
+        );
+    }
+
     #[test]
     fn load_config_from_missing_file_returns_default() {
         let dir = TempDir::new().unwrap();
@@ -106,17 +278,69 @@ mod tests {
 theme = "light"
 default_paths = ["/a", "/b"]
 default_depth = 5
+watch_debounce_ms = 500
+memory_heading = '## Notes'
+preview_max_kb = 16
+group_by_repo = true
+
+[colors]
+active_border = "light blue"
+
+[lint_rules]
+oversized-file = "off"
 "#,
         )
         .unwrap();
 
         let config = load_config_from(&path).unwrap();
         assert_eq!(config.theme.as_deref(), Some("light"));
+        assert_eq!(
+            config.colors.as_ref().and_then(|c| c.get("active_border")),
+            Some(&"light blue".to_string())
+        );
         assert_eq!(
             config.default_paths,
             Some(vec![PathBuf::from("/a"), PathBuf::from("/b")])
         );
         assert_eq!(config.default_depth, Some(5));
+        assert_eq!(config.watch_debounce_ms, Some(500));
+        assert_eq!(config.watch_debounce(), Duration::from_millis(500));
+        assert_eq!(config.memory_heading(), "## Notes");
+        assert_eq!(config.preview_max_kb(), 16);
+        assert_eq!(config.group_by_repo, Some(true));
+        assert_eq!(
+            config.lint_rules.unwrap()["oversized-file"],
+            crate::lint::RuleLevel::Off
+        );
+    }
+
+    #[test]
+    fn load_config_from_parses_keymap_section() {
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("config.toml");
+        fs::write(
+            &path,
+            r#"
+[keymap.files_tree]
+"ctrl+f" = "open_file_finder"
+"g g" = "move_top"
+"#,
+        )
+        .unwrap();
+
+        let config = load_config_from(&path).unwrap();
+
+        let files_tree = &config.keymap.unwrap()["files_tree"];
+        assert_eq!(files_tree["ctrl+f"], "open_file_finder");
+        assert_eq!(files_tree["g g"], "move_top");
+    }
+
+    #[test]
+    fn watch_debounce_defaults_when_unset() {
+        assert_eq!(
+            Config::default().watch_debounce(),
+            Duration::from_millis(crate::watch::DEFAULT_DEBOUNCE_MS)
+        );
     }
 
     #[test]
@@ -141,6 +365,29 @@ default_depth = 5
         assert_eq!(config, Config::default());
     }
 
+    #[test]
+    fn project_config_is_found_at_the_repository_root() {
+        let dir = TempDir::new().unwrap();
+        let repo = dir.path().join("repo");
+        fs::create_dir_all(repo.join(".git")).unwrap();
+        fs::create_dir_all(repo.join("packages/web")).unwrap();
+        fs::write(
+            repo.join(PROJECT_CONFIG_FILE),
+            "depth = 6\nskip_dirs = [\"fixtures\"]\noversized_tokens = 4000\n",
+        )
+        .unwrap();
+
+        let (base, config) = find_project_config(&repo.join("packages/web")).unwrap();
+
+        assert_eq!(base, repo);
+        let config = config.unwrap();
+        assert_eq!(config.depth, Some(6));
+        assert_eq!(config.skip_dirs, ["fixtures"]);
+        assert!(config.include.is_empty());
+        assert_eq!(config.oversized_tokens, Some(4000));
+        assert!(find_project_config(dir.path()).is_none());
+    }
+
     #[test]
     fn load_config_from_invalid_toml_returns_error() {
         let dir = TempDir::new().unwrap();
@@ -0,0 +1,240 @@
+//! Line-based text diffs for previewing changes before they are written.
+//!
+//! The algorithm trims the common prefix and suffix, then runs a classic LCS
+//! table over the remaining lines. CLAUDE.md files are small, but a size cap
+//! keeps pathological inputs from allocating a huge table: past the cap the
+//! changed region is shown as a plain remove-then-add block.
+
+use std::path::Path;
+
+/// Largest LCS table (old lines × new lines) computed before falling back.
+const MAX_LCS_CELLS: usize = 4_000_000;
+
+/// One line of a diff.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DiffLine {
+    /// Present in both texts.
+    Context(String),
+    /// Only in the old text.
+    Removed(String),
+    /// Only in the new text.
+    Added(String),
+    /// A run of unchanged lines hidden by [`collapse_context`].
+    Skipped(usize),
+}
+
+/// Returns the full line diff turning `old` into `new`.
+pub fn diff_lines(old: &str, new: &str) -> Vec<DiffLine> {
+    let old: Vec<&str> = old.lines().collect();
+    let new: Vec<&str> = new.lines().collect();
+
+    let prefix = old
+        .iter()
+        .zip(new.iter())
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old[prefix..]
+        .iter()
+        .rev()
+        .zip(new[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let old_mid = &old[prefix..old.len() - suffix];
+    let new_mid = &new[prefix..new.len() - suffix];
+
+    let mut out: Vec<DiffLine> = old[..prefix]
+        .iter()
+        .map(|l| DiffLine::Context((*l).to_string()))
+        .collect();
+    out.extend(diff_middle(old_mid, new_mid));
+    out.extend(
+        old[old.len() - suffix..]
+            .iter()
+            .map(|l| DiffLine::Context((*l).to_string())),
+    );
+    out
+}
+
+/// Returns true if the diff contains any added or removed line.
+pub fn has_changes(lines: &[DiffLine]) -> bool {
+    lines
+        .iter()
+        .any(|l| matches!(l, DiffLine::Added(_) | DiffLine::Removed(_)))
+}
+
+/// Replaces unchanged runs further than `context` lines from any change with a
+/// single [`DiffLine::Skipped`] marker.
+pub fn collapse_context(lines: Vec<DiffLine>, context: usize) -> Vec<DiffLine> {
+    let changed: Vec<usize> = lines
+        .iter()
+        .enumerate()
+        .filter(|(_, l)| !matches!(l, DiffLine::Context(_)))
+        .map(|(i, _)| i)
+        .collect();
+
+    let near_change = |i: usize| changed.iter().any(|&c| i.abs_diff(c) <= context);
+
+    let mut out = Vec::new();
+    let mut skipped = 0;
+    for (i, line) in lines.into_iter().enumerate() {
+        if matches!(line, DiffLine::Context(_)) && !near_change(i) {
+            skipped += 1;
+            continue;
+        }
+        if skipped > 0 {
+            out.push(DiffLine::Skipped(skipped));
+            skipped = 0;
+        }
+        out.push(line);
+    }
+    if skipped > 0 {
+        out.push(DiffLine::Skipped(skipped));
+    }
+    out
+}
+
+/// Renders the change of `path` from `old` to `new` as a unified-style
+/// diff with `context` unchanged lines around each change, or an empty
+/// string when nothing changes.
+pub fn unified_diff(path: &Path, old: &str, new: &str, context: usize) -> String {
+    let lines = diff_lines(old, new);
+    if !has_changes(&lines) {
+        return String::new();
+    }
+    let mut out = format!("--- {0}\n+++ {0}\n", path.display());
+    for line in collapse_context(lines, context) {
+        match line {
+            DiffLine::Context(text) => out.push_str(&format!(" {text}\n")),
+            DiffLine::Removed(text) => out.push_str(&format!("-{text}\n")),
+            DiffLine::Added(text) => out.push_str(&format!("+{text}\n")),
+            DiffLine::Skipped(n) => out.push_str(&format!("@@ {n} unchanged lines @@\n")),
+        }
+    }
+    out
+}
+
+/// Diffs the region between the common prefix and suffix.
+fn diff_middle(old: &[&str], new: &[&str]) -> Vec<DiffLine> {
+    let removed = old.iter().map(|l| DiffLine::Removed((*l).to_string()));
+    let added = new.iter().map(|l| DiffLine::Added((*l).to_string()));
+    if old.is_empty() || new.is_empty() || old.len() * new.len() > MAX_LCS_CELLS {
+        return removed.chain(added).collect();
+    }
+
+    // lcs[i][j] = LCS length of old[i..] and new[j..]
+    let width = new.len() + 1;
+    let mut lcs = vec![0usize; (old.len() + 1) * width];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i * width + j] = if old[i] == new[j] {
+                lcs[(i + 1) * width + j + 1] + 1
+            } else {
+                lcs[(i + 1) * width + j].max(lcs[i * width + j + 1])
+            };
+        }
+    }
+
+    let mut out = Vec::with_capacity(old.len() + new.len());
+    let (mut i, mut j) = (0, 0);
+    while i < old.len() && j < new.len() {
+        if old[i] == new[j] {
+            out.push(DiffLine::Context(old[i].to_string()));
+            i += 1;
+            j += 1;
+        } else if lcs[(i + 1) * width + j] >= lcs[i * width + j + 1] {
+            out.push(DiffLine::Removed(old[i].to_string()));
+            i += 1;
+        } else {
+            out.push(DiffLine::Added(new[j].to_string()));
+            j += 1;
+        }
+    }
+    out.extend(old[i..].iter().map(|l| DiffLine::Removed((*l).to_string())));
+    out.extend(new[j..].iter().map(|l| DiffLine::Added((*l).to_string())));
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ctx(s: &str) -> DiffLine {
+        DiffLine::Context(s.to_string())
+    }
+
+    fn add(s: &str) -> DiffLine {
+        DiffLine::Added(s.to_string())
+    }
+
+    fn del(s: &str) -> DiffLine {
+        DiffLine::Removed(s.to_string())
+    }
+
+    #[test]
+    fn identical_texts_have_no_changes() {
+        let lines = diff_lines("a\nb", "a\nb");
+        assert_eq!(lines, vec![ctx("a"), ctx("b")]);
+        assert!(!has_changes(&lines));
+    }
+
+    #[test]
+    fn changed_middle_line() {
+        let lines = diff_lines("a\nb\nc", "a\nx\nc");
+        assert_eq!(lines, vec![ctx("a"), del("b"), add("x"), ctx("c")]);
+    }
+
+    #[test]
+    fn insertion_and_deletion() {
+        assert_eq!(
+            diff_lines("a\nc", "a\nb\nc"),
+            vec![ctx("a"), add("b"), ctx("c")]
+        );
+        assert_eq!(
+            diff_lines("a\nb\nc", "a\nc"),
+            vec![ctx("a"), del("b"), ctx("c")]
+        );
+    }
+
+    #[test]
+    fn empty_old_text_is_all_added() {
+        assert_eq!(diff_lines("", "a\nb"), vec![add("a"), add("b")]);
+    }
+
+    #[test]
+    fn lcs_keeps_shared_lines_in_the_middle() {
+        let lines = diff_lines("x\na\ny", "p\na\nq");
+        assert_eq!(
+            lines,
+            vec![del("x"), add("p"), ctx("a"), del("y"), add("q")]
+        );
+    }
+
+    #[test]
+    fn collapse_context_hides_distant_lines() {
+        let old = "1\n2\n3\n4\n5\n6\n7";
+        let new = "1\n2\n3\nX\n5\n6\n7";
+        let collapsed = collapse_context(diff_lines(old, new), 1);
+        assert_eq!(
+            collapsed,
+            vec![
+                DiffLine::Skipped(2),
+                ctx("3"),
+                del("4"),
+                add("X"),
+                ctx("5"),
+                DiffLine::Skipped(2),
+            ]
+        );
+    }
+
+    #[test]
+    fn unified_diff_marks_lines_and_is_empty_without_changes() {
+        let path = Path::new("CLAUDE.md");
+        assert_eq!(
+            unified_diff(path, "1\n2\n3\n4\n", "1\n2\n3\nX\n", 1),
+            "--- CLAUDE.md\n+++ CLAUDE.md\n@@ 2 unchanged lines @@\n 3\n-4\n+X\n"
+        );
+        assert_eq!(unified_diff(path, "same\n", "same\n", 3), "");
+    }
+}
@@ -1,10 +1,19 @@
+use std::collections::HashMap;
+use std::collections::HashSet;
+use std::collections::hash_map::Entry;
 use std::env;
+use std::io::ErrorKind;
 use std::path::Path;
 use std::path::PathBuf;
 
 use walkdir::DirEntry;
 use walkdir::WalkDir;
 
+use crate::config::find_project_config;
+use crate::model::DiscoveredFile;
+use crate::model::SourceRoot;
+use crate::warnings::warn;
+
 /// Directories that will never contain CLAUDE.md files.
 /// Using `filter_entry()` prunes entire subtrees — this is the critical
 /// performance optimisation. Without it, scanning a home directory with
@@ -41,31 +50,679 @@ pub fn find_global_claude_file_in(home: &Path) -> Option<PathBuf> {
     path.exists().then_some(path)
 }
 
+/// The root shown for the global CLAUDE.md when no scan found it: its
+/// `~/.claude` directory holding just that file, with the user's own
+/// agents, commands and skills.
+pub fn global_root() -> Option<SourceRoot> {
+    let global_path = find_global_claude_file()?;
+    let claude_dir = global_path.parent()?.to_path_buf();
+    Some(SourceRoot {
+        context_files: context_files_in(&claude_dir),
+        path: claude_dir,
+        files: vec![discovered_file(global_path)],
+        misnamed_files: Vec::new(),
+        truncated: false,
+    })
+}
+
+/// The file at `path` with its size, line count and modification time, or
+/// only its path, with a warning, when they cannot be read.
+pub fn discovered_file(path: PathBuf) -> DiscoveredFile {
+    DiscoveredFile::read(&path).unwrap_or_else(|err| {
+        warn(format!("cannot read {}: {err}", path.display()));
+        DiscoveredFile::new(path)
+    })
+}
+
+/// How the tree names the root of `path`: `Global (~/.claude)` for the
+/// directory holding the global CLAUDE.md, `global_dir`, and the path
+/// otherwise.
+pub fn root_name(path: &Path, global_dir: Option<&Path>) -> String {
+    if global_dir == Some(path) {
+        "Global (~/.claude)".to_string()
+    } else {
+        path.display().to_string()
+    }
+}
+
 /// Default maximum directory depth for scanning.
 pub const DEFAULT_MAX_DEPTH: usize = 3;
 
-/// Finds all `CLAUDE.md` files under `root`, up to `max_depth` levels deep.
+/// The project memory file Claude Code reads.
+pub const CLAUDE_FILE_NAME: &str = "CLAUDE.md";
+
+/// Personal, git-ignored project memory. Claude Code still reads it, but it
+/// is deprecated in favour of `@`-imports from CLAUDE.md.
+pub const LOCAL_CLAUDE_FILE_NAME: &str = "CLAUDE.local.md";
+
+/// File names the scan picks up unless the config or `--pattern` says
+/// otherwise.
+pub const DEFAULT_FILE_PATTERNS: &[&str] = &[CLAUDE_FILE_NAME, LOCAL_CLAUDE_FILE_NAME];
+
+/// Endings editors and people leave on copies of a file.
+const LEFTOVER_SUFFIXES: &[&str] = &[".bak", ".backup", ".old", ".orig", ".txt", "~"];
+
+/// The name Claude Code would read `path` under, when `path` looks like a
+/// CLAUDE.md or CLAUDE.local.md under the wrong name: another case
+/// (`claude.md`, `CLAUDE.MD`) or a leftover ending (`CLAUDE.md.bak`).
+/// Claude Code ignores such files. `None` for correctly named files and
+/// anything else.
+pub fn canonical_name_for(path: &Path) -> Option<&'static str> {
+    let name = path.file_name()?.to_str()?;
+    if name == CLAUDE_FILE_NAME || name == LOCAL_CLAUDE_FILE_NAME {
+        return None;
+    }
+    let lower = name.to_lowercase();
+    [LOCAL_CLAUDE_FILE_NAME, CLAUDE_FILE_NAME]
+        .into_iter()
+        .find(|canonical| {
+            lower
+                .strip_prefix(&canonical.to_lowercase())
+                .is_some_and(|rest| rest.is_empty() || LEFTOVER_SUFFIXES.contains(&rest))
+        })
+}
+
+/// Returns true if `path` has one of the default names, `CLAUDE.md` or
+/// `CLAUDE.local.md`. Whether a scan picks a file up depends on its
+/// patterns; ask [`ScanRules::matches_name`] for that.
+pub fn is_claude_file(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name == CLAUDE_FILE_NAME || name == LOCAL_CLAUDE_FILE_NAME)
+}
+
+/// Returns true if `path` names a `CLAUDE.local.md` file.
+pub fn is_local_claude_file(path: &Path) -> bool {
+    path.file_name()
+        .is_some_and(|name| name == LOCAL_CLAUDE_FILE_NAME)
+}
+
+/// Returns true if `entry` is a directory below the scan root that is the
+/// top of its own git work tree (a nested checkout, submodule or worktree).
+pub fn is_nested_repo(entry: &DirEntry) -> bool {
+    entry.depth() > 0 && entry.file_type().is_dir() && entry.path().join(".git").exists()
+}
+
+/// Finds all `CLAUDE.md` and `CLAUDE.local.md` files under `root`, up to
+/// `max_depth` levels deep.
 ///
-/// Silently skips broken symlinks, permission errors, and other IO failures.
+/// Silently skips broken symlinks and symlink loops; unreadable
+/// directories and other IO failures are skipped with a warning.
 pub fn find_claude_files(root: &Path, max_depth: usize) -> Vec<PathBuf> {
-    let mut files: Vec<PathBuf> = WalkDir::new(root)
-        .follow_links(true)
-        .max_depth(max_depth)
-        .into_iter()
-        .filter_entry(should_descend)
-        .filter_map(|result| result.ok())
-        .filter(|entry| entry.file_type().is_file())
-        .filter(|entry| entry.file_name() == "CLAUDE.md")
-        .map(|entry| entry.into_path())
-        .collect();
+    scan(root, &ScanRules::new(max_depth, false))
+}
+
+/// Like [`find_claude_files`], but does not descend into nested git
+/// repositories: their files belong to a different project.
+pub fn find_claude_files_stopping_at_repos(root: &Path, max_depth: usize) -> Vec<PathBuf> {
+    scan(root, &ScanRules::new(max_depth, true))
+}
+
+/// Warns about an entry the walk could not read, unless it is a broken
+/// symlink: those are common and harmless.
+fn warn_skipped(err: walkdir::Error) {
+    if err
+        .io_error()
+        .is_some_and(|io| io.kind() == ErrorKind::NotFound)
+    {
+        return;
+    }
+    let path = err.path().map(Path::to_path_buf).unwrap_or_default();
+    match err.io_error() {
+        Some(io) => warn(format!("skipped {}: {io}", path.display())),
+        None => warn(format!("skipped {}: {err}", path.display())),
+    }
+}
+
+/// How a root directory is scanned, kept so it can be scanned again the
+/// same way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ScanOptions {
+    pub max_depth: usize,
+    /// Whether to skip nested git repositories.
+    pub stop_at_repos: bool,
+    /// Whether `max_depth` was given with `--depth`, so a project's
+    /// `.jigolo.toml` does not change it.
+    pub pinned_depth: bool,
+    /// Whether the global `~/.claude` root is added when no scan finds the
+    /// global CLAUDE.md; off with `--no-global`.
+    pub include_global: bool,
+    /// Names of the files picked up, `*` matching within a name; the
+    /// [`DEFAULT_FILE_PATTERNS`] when empty.
+    pub patterns: Vec<String>,
+    /// Names of the directories never entered, instead of [`SKIP_DIRS`].
+    pub skip_list: Option<Vec<String>>,
+    /// Directories skipped on top of the skip list, as in a project's
+    /// `skip_dirs`.
+    pub exclude: Vec<String>,
+    /// Directories scanned even when a skip rule would prune them, as in a
+    /// project's `include`.
+    pub include: Vec<String>,
+    /// Most CLAUDE.md files picked up under one root; the walk stops there.
+    pub max_files: Option<usize>,
+}
+
+impl Default for ScanOptions {
+    fn default() -> Self {
+        Self {
+            max_depth: DEFAULT_MAX_DEPTH,
+            stop_at_repos: false,
+            pinned_depth: false,
+            include_global: true,
+            patterns: Vec::new(),
+            skip_list: None,
+            exclude: Vec::new(),
+            include: Vec::new(),
+            max_files: None,
+        }
+    }
+}
+
+impl ScanOptions {
+    /// Finds the CLAUDE.md files under `root` with these options.
+    pub fn find(&self, root: &Path) -> Vec<PathBuf> {
+        scan(root, &self.rules(root))
+    }
+
+    /// Finds the misnamed CLAUDE.md files under `root` with these options.
+    pub fn find_misnamed(&self, root: &Path) -> Vec<PathBuf> {
+        misnamed_files(root, &self.rules(root))
+    }
+
+    /// Finds the agents, commands and skills under `root` with these
+    /// options.
+    pub fn find_context(&self, root: &Path) -> Vec<ContextFile> {
+        context_files(root, &self.rules(root))
+    }
+
+    /// Says which of `roots` the scan stopped in at
+    /// [`max_files`](Self::max_files), so files may be missing from them.
+    pub fn file_limit_note(&self, roots: &[SourceRoot]) -> Option<String> {
+        let max = self.max_files?;
+        let stopped: Vec<String> = roots
+            .iter()
+            .filter(|root| root.truncated)
+            .map(|root| root.path.display().to_string())
+            .collect();
+        if stopped.is_empty() {
+            return None;
+        }
+        Some(format!(
+            "Stopped at --max-files {max} in {}; files may be missing.",
+            stopped.join(", ")
+        ))
+    }
+
+    /// Scans `root` for its CLAUDE.md files, agents, commands and skills,
+    /// and misnamed files, in a single walk that stops at
+    /// [`max_files`](Self::max_files).
+    pub fn scan_root(&self, root: &Path) -> SourceRoot {
+        self.scan_root_reporting(root, |_| {})
+    }
+
+    /// Like [`scan_root`](Self::scan_root), passing each CLAUDE.md file to
+    /// `found` as the walk reaches it, so the caller can show files before
+    /// the walk ends.
+    pub fn scan_root_reporting(&self, root: &Path, mut found: impl FnMut(&Path)) -> SourceRoot {
+        let walked = walk_root(root, &self.rules(root), &mut found);
+        SourceRoot {
+            path: root.to_path_buf(),
+            context_files: walked.context_files(),
+            files: walked.files.into_iter().map(discovered_file).collect(),
+            misnamed_files: walked.misnamed,
+            truncated: walked.truncated,
+        }
+    }
+
+    /// The rules scanning `root` follows: these options with the project
+    /// config of `root` applied. A config that cannot be read is ignored.
+    pub fn rules(&self, root: &Path) -> ScanRules {
+        let mut rules = ScanRules::new(self.max_depth, self.stop_at_repos);
+        rules.patterns.clone_from(&self.patterns);
+        rules.skip_list.clone_from(&self.skip_list);
+        rules.skip_dirs.clone_from(&self.exclude);
+        rules.include.clone_from(&self.include);
+        rules.max_files = self.max_files;
+        if let Some((base, Ok(project))) = find_project_config(root) {
+            if !self.pinned_depth
+                && let Some(depth) = project.depth
+            {
+                rules.max_depth = depth;
+            }
+            rules.skip_from = root
+                .strip_prefix(&base)
+                .map_or(0, |p| p.components().count());
+            rules.base = base;
+            rules.skip_dirs.extend(project.skip_dirs);
+            rules.include.extend(project.include);
+        }
+        rules
+    }
+}
+
+/// Which directories one scan walks: the skip list plus the skip and
+/// include patterns of the config, the command line and a project config.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ScanRules {
+    pub max_depth: usize,
+    pub stop_at_repos: bool,
+    /// Directory the patterns are relative to; empty without a project
+    /// config, when paths are taken relative to the scanned root.
+    pub base: PathBuf,
+    /// Leading components of paths under `base`, above the scanned root,
+    /// that skip rules leave alone: scanning inside a skipped directory on
+    /// purpose still works.
+    pub skip_from: usize,
+    /// Names of the directories never entered; [`SKIP_DIRS`] when `None`.
+    pub skip_list: Option<Vec<String>>,
+    pub skip_dirs: Vec<String>,
+    pub include: Vec<String>,
+    /// Names of the files picked up; the [`DEFAULT_FILE_PATTERNS`] when
+    /// empty.
+    pub patterns: Vec<String>,
+    /// Most files a walk picks up; it stops at the next one.
+    pub max_files: Option<usize>,
+}
+
+impl ScanRules {
+    /// Rules with only the built-in skip list.
+    pub fn new(max_depth: usize, stop_at_repos: bool) -> Self {
+        Self {
+            max_depth,
+            stop_at_repos,
+            ..Self::default()
+        }
+    }
+
+    /// Every entry under `root` these rules reach, in name order. Symlinks
+    /// are followed, with two exceptions that keep each directory to one
+    /// walk under its own path: a link to a directory inside `root` is
+    /// left for that directory's own place in the walk, and a directory
+    /// reached again through a link from outside is walked only the first
+    /// time. Symlink loops are skipped silently, as their directory has
+    /// been walked already.
+    fn walk<'a>(&'a self, root: &'a Path) -> impl Iterator<Item = DirEntry> + 'a {
+        let root_resolved = resolved(root);
+        let mut visited: HashSet<PathBuf> = HashSet::new();
+        WalkDir::new(root)
+            .follow_links(true)
+            .max_depth(self.max_depth)
+            .sort_by_file_name()
+            .into_iter()
+            .filter_entry(move |entry| {
+                if !entry.file_type().is_dir() {
+                    return true;
+                }
+                let target = resolved(entry.path());
+                let links_inside = entry.depth() > 0
+                    && entry.path_is_symlink()
+                    && target.starts_with(&root_resolved);
+                !links_inside
+                    && self.descends(&self.relative(root, entry.path()))
+                    && !(self.stop_at_repos && is_nested_repo(entry))
+                    && visited.insert(target)
+            })
+            .filter_map(|result| match result {
+                Ok(entry) => Some(entry),
+                Err(err) if err.loop_ancestor().is_some() => None,
+                Err(err) => {
+                    warn_skipped(err);
+                    None
+                }
+            })
+            .filter(move |entry| {
+                !entry.file_type().is_file()
+                    || entry
+                        .path()
+                        .parent()
+                        .is_none_or(|dir| self.keeps_files_in(&self.relative(root, dir)))
+            })
+    }
+
+    /// Components of `path` below `base`, or below `root` without one.
+    fn relative(&self, root: &Path, path: &Path) -> Vec<String> {
+        let base = if self.base.as_os_str().is_empty() {
+            root
+        } else {
+            &self.base
+        };
+        path.strip_prefix(base)
+            .unwrap_or(path)
+            .components()
+            .map(|c| c.as_os_str().to_string_lossy().into_owned())
+            .collect()
+    }
+
+    fn descends(&self, dir: &[String]) -> bool {
+        !self.skipped(dir) || self.included(dir) || self.leads_to_include(dir)
+    }
+
+    /// Whether a file called `name` is picked up.
+    pub fn matches_name(&self, name: &str) -> bool {
+        if self.patterns.is_empty() {
+            DEFAULT_FILE_PATTERNS.contains(&name)
+        } else {
+            self.patterns
+                .iter()
+                .any(|pattern| glob_match(pattern, name))
+        }
+    }
+
+    fn keeps_files_in(&self, dir: &[String]) -> bool {
+        !self.skipped(dir) || self.included(dir)
+    }
+
+    /// Whether `dir` or a directory above it is skipped.
+    fn skipped(&self, dir: &[String]) -> bool {
+        (self.skip_from + 1..=dir.len()).any(|len| self.is_skip_dir(&dir[..len]))
+    }
+
+    fn is_skip_dir(&self, dir: &[String]) -> bool {
+        let Some(name) = dir.last() else {
+            return false;
+        };
+        let listed = match &self.skip_list {
+            Some(list) => list.iter().any(|pattern| glob_match(pattern, name)),
+            None => SKIP_DIRS.contains(&name.as_str()),
+        };
+        listed
+            || self.skip_dirs.iter().any(|pattern| {
+                if pattern.contains('/') {
+                    let parts = pattern_parts(pattern);
+                    parts.len() == dir.len() && components_match(&parts, dir)
+                } else {
+                    glob_match(pattern, name)
+                }
+            })
+    }
+
+    /// Whether `dir` is an included directory or inside one. A pattern
+    /// without `/` includes every directory of that name.
+    fn included(&self, dir: &[String]) -> bool {
+        self.include.iter().any(|pattern| {
+            if !pattern.contains('/') {
+                return dir.iter().any(|name| glob_match(pattern, name));
+            }
+            let parts = pattern_parts(pattern);
+            parts.len() <= dir.len() && components_match(&parts, &dir[..parts.len()])
+        })
+    }
+
+    /// Whether an included directory lies below `dir`.
+    fn leads_to_include(&self, dir: &[String]) -> bool {
+        self.include.iter().any(|pattern| {
+            let parts = pattern_parts(pattern);
+            parts.len() > dir.len() && components_match(&parts[..dir.len()], dir)
+        })
+    }
+}
+
+/// `path` with symlinks resolved, or as it is when that fails.
+fn resolved(path: &Path) -> PathBuf {
+    path.canonicalize().unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Files found by a walk, one per file they resolve to. The first path
+/// found is kept, unless it is a symlink and a later one is the file
+/// itself: edits, backups and the load order should use the real path.
+#[derive(Debug, Default)]
+struct UniqueFiles {
+    paths: Vec<PathBuf>,
+    /// Position in `paths` of each resolved file, and whether the path
+    /// kept for it is a symlink.
+    found: HashMap<PathBuf, (usize, bool)>,
+}
+
+impl UniqueFiles {
+    /// Whether the file of `entry` was found before.
+    fn has(&self, entry: &DirEntry) -> bool {
+        self.found.contains_key(&resolved(entry.path()))
+    }
+
+    /// Adds the file of `entry`, returning its path unless it was found
+    /// before.
+    fn add(&mut self, entry: DirEntry) -> Option<&Path> {
+        let is_link = entry.path_is_symlink();
+        match self.found.entry(resolved(entry.path())) {
+            Entry::Occupied(mut found) => {
+                let (index, kept_link) = *found.get();
+                if kept_link && !is_link {
+                    self.paths[index] = entry.into_path();
+                    found.insert((index, false));
+                }
+                None
+            }
+            Entry::Vacant(slot) => {
+                slot.insert((self.paths.len(), is_link));
+                self.paths.push(entry.into_path());
+                self.paths.last().map(PathBuf::as_path)
+            }
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.paths.len()
+    }
 
+    /// The paths, sorted.
+    fn into_sorted(self) -> Vec<PathBuf> {
+        let mut paths = self.paths;
+        paths.sort_unstable();
+        paths
+    }
+}
+
+/// The components of a `/`-separated pattern.
+fn pattern_parts(pattern: &str) -> Vec<&str> {
+    pattern
+        .split('/')
+        .filter(|part| !part.is_empty() && *part != ".")
+        .collect()
+}
+
+fn components_match(patterns: &[&str], names: &[String]) -> bool {
+    patterns
+        .iter()
+        .zip(names)
+        .all(|(pattern, name)| glob_match(pattern, name))
+}
+
+/// Whether `name` matches `pattern`, where `*` stands for any run of
+/// characters.
+fn glob_match(pattern: &str, name: &str) -> bool {
+    let Some((first, rest)) = pattern.split_once('*') else {
+        return pattern == name;
+    };
+    let Some(mut remaining) = name.strip_prefix(first) else {
+        return false;
+    };
+    let mut pieces: Vec<&str> = rest.split('*').collect();
+    let last = pieces.pop().unwrap_or_default();
+    for piece in pieces {
+        match remaining.find(piece) {
+            Some(at) => remaining = &remaining[at + piece.len()..],
+            None => return false,
+        }
+    }
+    remaining.len() >= last.len() && remaining.ends_with(last)
+}
+
+/// Kind of Claude Code context kept in a `.claude` directory beside the
+/// CLAUDE.md files.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ContextKind {
+    /// Subagent definition, `.claude/agents/<name>.md`.
+    Agent,
+    /// Custom slash command, `.claude/commands/<name>.md`, possibly in a
+    /// namespace subdirectory.
+    Command,
+    /// Skill, `.claude/skills/<name>/SKILL.md`.
+    Skill,
+}
+
+impl ContextKind {
+    /// Every kind, in the order the tree lists them.
+    pub const ALL: [ContextKind; 3] =
+        [ContextKind::Agent, ContextKind::Command, ContextKind::Skill];
+
+    /// Plural name, as the tree labels the kind.
+    pub fn label(self) -> &'static str {
+        match self {
+            ContextKind::Agent => "Agents",
+            ContextKind::Command => "Commands",
+            ContextKind::Skill => "Skills",
+        }
+    }
+
+    /// Singular lowercase name, as `jigolo --list` badges the files.
+    pub fn name(self) -> &'static str {
+        match self {
+            ContextKind::Agent => "agent",
+            ContextKind::Command => "command",
+            ContextKind::Skill => "skill",
+        }
+    }
+
+    /// The subdirectory of `.claude` holding this kind.
+    pub fn dir_name(self) -> &'static str {
+        match self {
+            ContextKind::Agent => "agents",
+            ContextKind::Command => "commands",
+            ContextKind::Skill => "skills",
+        }
+    }
+}
+
+/// An agent, command or skill file.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct ContextFile {
+    pub kind: ContextKind,
+    pub path: PathBuf,
+}
+
+/// Finds the agents, commands and skills of every `.claude` directory
+/// under `root`, up to `max_depth` levels deep, in kind then path order.
+pub fn find_context_files(root: &Path, max_depth: usize, stop_at_repos: bool) -> Vec<ContextFile> {
+    context_files(root, &ScanRules::new(max_depth, stop_at_repos))
+}
+
+fn context_files(root: &Path, rules: &ScanRules) -> Vec<ContextFile> {
+    walk_root(root, rules, &mut |_| {}).context_files()
+}
+
+/// The agents, commands and skills in the `.claude` directory `dot_claude`,
+/// in kind then path order.
+pub fn context_files_in(dot_claude: &Path) -> Vec<ContextFile> {
+    let mut files = Vec::new();
+    for kind in ContextKind::ALL {
+        let dir = dot_claude.join(kind.dir_name());
+        // Commands may sit one namespace deep; agents and skills may not.
+        let depth = match kind {
+            ContextKind::Agent => 1,
+            ContextKind::Command | ContextKind::Skill => 2,
+        };
+        let found = WalkDir::new(&dir)
+            .follow_links(true)
+            .min_depth(1)
+            .max_depth(depth)
+            .into_iter()
+            .filter_map(|result| result.map_err(warn_skipped).ok())
+            .filter(|entry| entry.file_type().is_file())
+            .filter(|entry| match kind {
+                ContextKind::Agent | ContextKind::Command => {
+                    entry.path().extension().is_some_and(|ext| ext == "md")
+                }
+                ContextKind::Skill => entry.depth() == 2 && entry.file_name() == "SKILL.md",
+            })
+            .map(|entry| ContextFile {
+                kind,
+                path: entry.into_path(),
+            });
+        files.extend(found);
+    }
     files.sort_unstable();
     files
 }
 
+/// Finds the files under `root` that [`canonical_name_for`] takes for
+/// misnamed CLAUDE.md files, up to `max_depth` levels deep.
+pub fn find_misnamed_files(root: &Path, max_depth: usize, stop_at_repos: bool) -> Vec<PathBuf> {
+    misnamed_files(root, &ScanRules::new(max_depth, stop_at_repos))
+}
+
+fn misnamed_files(root: &Path, rules: &ScanRules) -> Vec<PathBuf> {
+    walk_root(root, rules, &mut |_| {}).misnamed
+}
+
+fn scan(root: &Path, rules: &ScanRules) -> Vec<PathBuf> {
+    walk_root(root, rules, &mut |_| {}).files
+}
+
+/// What one walk of a root finds.
+#[derive(Debug, Default)]
+struct Walked {
+    files: Vec<PathBuf>,
+    misnamed: Vec<PathBuf>,
+    /// The `.claude` directories, holding agents, commands and skills.
+    dot_claude: Vec<PathBuf>,
+    /// Whether the walk stopped at `max_files` with files left.
+    truncated: bool,
+}
+
+impl Walked {
+    /// The agents, commands and skills of the `.claude` directories, in
+    /// kind then path order.
+    fn context_files(&self) -> Vec<ContextFile> {
+        let mut files: Vec<ContextFile> = self
+            .dot_claude
+            .iter()
+            .flat_map(|dir| context_files_in(dir))
+            .collect();
+        files.sort_unstable();
+        files
+    }
+}
+
+/// Walks `root` once for the files [`scan`], [`misnamed_files`] and
+/// [`context_files`] each pick out. The walk stops at the first file past
+/// `max_files`, so the other kinds are bounded by it too. Each CLAUDE.md
+/// file goes to `found` when it is first reached.
+fn walk_root(root: &Path, rules: &ScanRules, found: &mut dyn FnMut(&Path)) -> Walked {
+    let mut walked = Walked::default();
+    let mut files = UniqueFiles::default();
+    let mut misnamed = UniqueFiles::default();
+    for entry in rules.walk(root) {
+        if entry.file_type().is_dir() {
+            if entry.file_name() == ".claude" {
+                walked.dot_claude.push(entry.into_path());
+            }
+            continue;
+        }
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        if rules.matches_name(&entry.file_name().to_string_lossy()) {
+            if rules.max_files.is_some_and(|max| files.len() >= max) && !files.has(&entry) {
+                walked.truncated = true;
+                break;
+            }
+            if let Some(path) = files.add(entry) {
+                found(path);
+            }
+        } else if canonical_name_for(entry.path()).is_some() {
+            misnamed.add(entry);
+        }
+    }
+    walked.files = files.into_sorted();
+    walked.misnamed = misnamed.into_sorted();
+    walked
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::warnings::capture;
+    use crate::warnings::release;
+    use crate::warnings::take;
     use std::fs;
     use tempfile::TempDir;
 
@@ -86,6 +743,85 @@ mod tests {
         assert!(files.iter().all(|f| f.file_name().unwrap() == "CLAUDE.md"));
     }
 
+    #[test]
+    fn finds_local_claude_files() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        fs::write(root.join("CLAUDE.md"), "shared").unwrap();
+        fs::write(root.join("CLAUDE.local.md"), "mine").unwrap();
+
+        let files = find_claude_files(root, DEFAULT_MAX_DEPTH);
+
+        assert_eq!(
+            files,
+            vec![root.join("CLAUDE.local.md"), root.join("CLAUDE.md")]
+        );
+        assert!(is_local_claude_file(&files[0]));
+        assert!(!is_local_claude_file(&files[1]));
+    }
+
+    #[test]
+    fn file_patterns_choose_the_names_picked_up() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        for name in [
+            "CLAUDE.md",
+            "CLAUDE.local.md",
+            "AGENTS.md",
+            "team.claude.md",
+            "claude.md",
+        ] {
+            fs::write(root.join(name), "").unwrap();
+        }
+        let options = ScanOptions {
+            patterns: vec![
+                "CLAUDE.md".to_string(),
+                "AGENTS.md".to_string(),
+                "*.claude.md".to_string(),
+                "claude.md".to_string(),
+            ],
+            ..ScanOptions::default()
+        };
+
+        assert_eq!(
+            options.find(root),
+            vec![
+                root.join("AGENTS.md"),
+                root.join("CLAUDE.md"),
+                root.join("claude.md"),
+                root.join("team.claude.md"),
+            ]
+        );
+        assert!(options.find_misnamed(root).is_empty());
+        assert_eq!(ScanOptions::default().find(root).len(), 2);
+    }
+
+    #[test]
+    fn misnamed_files_are_found_with_their_canonical_name() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        fs::create_dir_all(root.join("a")).unwrap();
+        for name in ["CLAUDE.md", "claude.md", "notes.md", "CLAUDE.md.swp"] {
+            fs::write(root.join(name), "").unwrap();
+        }
+        fs::write(root.join("a/CLAUDE.MD.bak"), "").unwrap();
+        fs::write(root.join("a/Claude.local.md"), "").unwrap();
+
+        let found = find_misnamed_files(root, DEFAULT_MAX_DEPTH, false);
+
+        assert_eq!(
+            found,
+            vec![
+                root.join("a/CLAUDE.MD.bak"),
+                root.join("a/Claude.local.md"),
+                root.join("claude.md"),
+            ]
+        );
+        assert_eq!(canonical_name_for(&found[0]), Some(CLAUDE_FILE_NAME));
+        assert_eq!(canonical_name_for(&found[1]), Some(LOCAL_CLAUDE_FILE_NAME));
+        assert_eq!(canonical_name_for(Path::new("/p/CLAUDE.md")), None);
+    }
+
     #[test]
     fn returns_empty_for_no_claude_files() {
         let tmp = TempDir::new().unwrap();
@@ -110,6 +846,184 @@ mod tests {
         assert_eq!(files.len(), 1);
     }
 
+    #[test]
+    fn stopping_at_repos_skips_nested_checkouts() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        fs::create_dir_all(root.join(".git")).unwrap();
+        fs::write(root.join("CLAUDE.md"), "root").unwrap();
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join("docs/CLAUDE.md"), "same repo").unwrap();
+        fs::create_dir_all(root.join("libs/other/.git")).unwrap();
+        fs::write(root.join("libs/other/CLAUDE.md"), "other repo").unwrap();
+        fs::create_dir_all(root.join("worktree")).unwrap();
+        fs::write(root.join("worktree/.git"), "gitdir: elsewhere").unwrap();
+        fs::write(root.join("worktree/CLAUDE.md"), "worktree").unwrap();
+
+        assert_eq!(find_claude_files(root, DEFAULT_MAX_DEPTH).len(), 4);
+        assert_eq!(
+            find_claude_files_stopping_at_repos(root, DEFAULT_MAX_DEPTH),
+            vec![root.join("CLAUDE.md"), root.join("docs/CLAUDE.md")]
+        );
+    }
+
+    #[test]
+    fn project_config_adds_skip_dirs_and_includes() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        for dir in [
+            "fixtures",
+            "docs/generated",
+            "docs/guide",
+            "vendor/ours",
+            "vendor/theirs",
+        ] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+            fs::write(root.join(dir).join("CLAUDE.md"), dir).unwrap();
+        }
+        fs::write(
+            root.join(crate::config::PROJECT_CONFIG_FILE),
+            "skip_dirs = [\"fix*\", \"docs/generated\"]\ninclude = [\"vendor/ours\"]\n",
+        )
+        .unwrap();
+
+        let files = ScanOptions::default().find(root);
+
+        assert_eq!(
+            files,
+            vec![
+                root.join("docs/guide/CLAUDE.md"),
+                root.join("vendor/ours/CLAUDE.md"),
+            ]
+        );
+        assert_eq!(find_claude_files(root, DEFAULT_MAX_DEPTH).len(), 3);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn symlinked_directories_and_files_are_found_once_under_their_real_path() {
+        use std::os::unix::fs::symlink;
+
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        fs::create_dir_all(root.join("real/sub")).unwrap();
+        fs::write(root.join("real/CLAUDE.md"), "real").unwrap();
+        fs::write(root.join("real/sub/CLAUDE.md"), "sub").unwrap();
+        symlink(root.join("real"), root.join("alias")).unwrap();
+        symlink(root, root.join("real/sub/loop")).unwrap();
+        fs::create_dir_all(root.join("linked")).unwrap();
+        symlink(root.join("real/CLAUDE.md"), root.join("linked/CLAUDE.md")).unwrap();
+        let outside = TempDir::new().unwrap();
+        fs::write(outside.path().join("CLAUDE.md"), "outside").unwrap();
+        symlink(outside.path(), root.join("shared")).unwrap();
+        symlink(outside.path(), root.join("twice")).unwrap();
+
+        capture();
+        let files = find_claude_files(root, 10);
+        let warnings = take();
+        release();
+
+        // `alias` and `linked` come before `real` in name order, but the
+        // real paths win; a directory outside is walked once, first link
+        // first.
+        assert_eq!(
+            files,
+            vec![
+                root.join("real/CLAUDE.md"),
+                root.join("real/sub/CLAUDE.md"),
+                root.join("shared/CLAUDE.md"),
+            ]
+        );
+        let link = root.join("real/sub/loop").display().to_string();
+        assert!(
+            !warnings.iter().any(|warning| warning.contains(&link)),
+            "{warnings:?}"
+        );
+    }
+
+    #[test]
+    fn max_files_stops_the_walk_and_is_reported() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        for dir in ["a", "b", "c"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+            fs::write(root.join(dir).join("CLAUDE.md"), dir).unwrap();
+        }
+        let options = ScanOptions {
+            max_files: Some(2),
+            ..ScanOptions::default()
+        };
+
+        let mut reported = Vec::new();
+        let scanned = options.scan_root_reporting(root, |file| reported.push(file.to_path_buf()));
+
+        assert_eq!(scanned.files.len(), 2);
+        assert_eq!(
+            scanned.file_paths().collect::<Vec<_>>(),
+            [&reported[0], &reported[1]]
+        );
+        assert_eq!(
+            options.file_limit_note(&[scanned]),
+            Some(format!(
+                "Stopped at --max-files 2 in {}; files may be missing.",
+                root.display()
+            ))
+        );
+        let exactly = ScanOptions {
+            max_files: Some(3),
+            ..ScanOptions::default()
+        };
+        let all = exactly.scan_root(root);
+        assert_eq!(all.files.len(), 3);
+        assert!(!all.truncated);
+        assert_eq!(exactly.file_limit_note(&[all]), None);
+    }
+
+    #[test]
+    fn options_replace_the_skip_list_exclude_and_include() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        for dir in ["build", "fixtures", "pkg/vendor", "vendor"] {
+            fs::create_dir_all(root.join(dir)).unwrap();
+            fs::write(root.join(dir).join("CLAUDE.md"), dir).unwrap();
+        }
+
+        let options = ScanOptions {
+            exclude: vec!["fixtures".to_string()],
+            include: vec!["vendor".to_string()],
+            ..ScanOptions::default()
+        };
+        assert_eq!(
+            options.find(root),
+            vec![
+                root.join("pkg/vendor/CLAUDE.md"),
+                root.join("vendor/CLAUDE.md"),
+            ]
+        );
+
+        let options = ScanOptions {
+            skip_list: Some(vec!["fix*".to_string()]),
+            ..ScanOptions::default()
+        };
+        assert_eq!(
+            options.find(root),
+            vec![
+                root.join("build/CLAUDE.md"),
+                root.join("pkg/vendor/CLAUDE.md"),
+                root.join("vendor/CLAUDE.md"),
+            ]
+        );
+    }
+
+    #[test]
+    fn glob_matches_within_a_name() {
+        assert!(glob_match("fix*", "fixtures"));
+        assert!(glob_match("*.cache", "build.cache"));
+        assert!(glob_match("a*b*c", "aXbYc"));
+        assert!(!glob_match("a*b", "ab-a"));
+        assert!(!glob_match("docs", "docs2"));
+    }
+
     #[test]
     fn find_global_claude_file_returns_path_when_exists() {
         let tmp = TempDir::new().unwrap();
@@ -125,6 +1039,14 @@ mod tests {
         );
     }
 
+    #[test]
+    fn the_global_root_is_named_for_the_tree() {
+        let global = Path::new("/home/me/.claude");
+        assert_eq!(root_name(global, Some(global)), "Global (~/.claude)");
+        assert_eq!(root_name(Path::new("/p"), Some(global)), "/p");
+        assert_eq!(root_name(global, None), "/home/me/.claude");
+    }
+
     #[test]
     fn find_global_claude_file_returns_none_when_missing() {
         let tmp = TempDir::new().unwrap();
@@ -206,4 +1128,46 @@ mod tests {
             "Results should be sorted alphabetically."
         );
     }
+
+    #[test]
+    fn finds_agents_commands_and_skills_by_kind() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path();
+        let claude = root.join("app/.claude");
+        fs::create_dir_all(claude.join("agents")).unwrap();
+        fs::create_dir_all(claude.join("commands/git")).unwrap();
+        fs::create_dir_all(claude.join("skills/pdf/scripts")).unwrap();
+        fs::write(claude.join("agents/reviewer.md"), "agent").unwrap();
+        fs::write(claude.join("agents/notes.txt"), "not an agent").unwrap();
+        fs::write(claude.join("commands/deploy.md"), "command").unwrap();
+        fs::write(claude.join("commands/git/pr.md"), "namespaced").unwrap();
+        fs::write(claude.join("skills/pdf/SKILL.md"), "skill").unwrap();
+        fs::write(claude.join("skills/pdf/scripts/README.md"), "support").unwrap();
+        fs::create_dir_all(root.join("node_modules/x/.claude/agents")).unwrap();
+        fs::write(root.join("node_modules/x/.claude/agents/a.md"), "skip").unwrap();
+
+        let found = find_context_files(root, DEFAULT_MAX_DEPTH, false);
+
+        assert_eq!(
+            found,
+            vec![
+                ContextFile {
+                    kind: ContextKind::Agent,
+                    path: claude.join("agents/reviewer.md"),
+                },
+                ContextFile {
+                    kind: ContextKind::Command,
+                    path: claude.join("commands/deploy.md"),
+                },
+                ContextFile {
+                    kind: ContextKind::Command,
+                    path: claude.join("commands/git/pr.md"),
+                },
+                ContextFile {
+                    kind: ContextKind::Skill,
+                    path: claude.join("skills/pdf/SKILL.md"),
+                },
+            ]
+        );
+    }
 }
@@ -0,0 +1,105 @@
+//! Crash-safe drafts of unsaved editor buffers.
+//!
+//! While a file is being edited, its unsaved buffer is periodically written
+//! to `~/.config/jigolo/drafts/<encoded path>.md`. The draft is removed when
+//! the file is saved or the changes are discarded, so a draft that survives
+//! means the previous session ended without either.
+
+use anyhow::Context;
+use anyhow::Result;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::fsutil::encode_path;
+use crate::fsutil::write_atomic;
+
+/// Returns the drafts directory inside jigolo's config directory.
+pub fn drafts_dir_in(config_dir: &Path) -> PathBuf {
+    config_dir.join("drafts")
+}
+
+/// Returns the draft location for `file`.
+pub fn draft_path(drafts: &Path, file: &Path) -> PathBuf {
+    drafts.join(format!("{}.md", encode_path(file)))
+}
+
+/// Writes `content` as the draft of `file`, creating the drafts directory.
+pub fn write_draft(drafts: &Path, file: &Path, content: &str) -> Result<()> {
+    fs::create_dir_all(drafts)
+        .with_context(|| format!("failed to create directory {}", drafts.display()))?;
+    let path = draft_path(drafts, file);
+    write_atomic(&path, content).with_context(|| format!("failed to write {}", path.display()))
+}
+
+/// Reads the draft of `file`, returning `None` when there is none.
+pub fn read_draft(drafts: &Path, file: &Path) -> Result<Option<String>> {
+    let path = draft_path(drafts, file);
+    match fs::read_to_string(&path) {
+        Ok(content) => Ok(Some(content)),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(None),
+        Err(err) => Err(err).with_context(|| format!("failed to read {}", path.display())),
+    }
+}
+
+/// Deletes the draft of `file`. A missing draft is not an error.
+pub fn remove_draft(drafts: &Path, file: &Path) -> Result<()> {
+    let path = draft_path(drafts, file);
+    match fs::remove_file(&path) {
+        Ok(()) => Ok(()),
+        Err(err) if err.kind() == ErrorKind::NotFound => Ok(()),
+        Err(err) => Err(err).with_context(|| format!("failed to remove {}", path.display())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn draft_round_trip() {
+        let tmp = TempDir::new().unwrap();
+        let drafts = tmp.path().join("drafts");
+        let file = Path::new("/work/CLAUDE.md");
+
+        write_draft(&drafts, file, "unsaved").unwrap();
+
+        assert_eq!(
+            read_draft(&drafts, file).unwrap().as_deref(),
+            Some("unsaved")
+        );
+    }
+
+    #[test]
+    fn missing_draft_reads_as_none() {
+        let tmp = TempDir::new().unwrap();
+        let file = Path::new("/work/CLAUDE.md");
+        assert!(read_draft(tmp.path(), file).unwrap().is_none());
+    }
+
+    #[test]
+    fn remove_draft_deletes_and_tolerates_missing() {
+        let tmp = TempDir::new().unwrap();
+        let file = Path::new("/work/CLAUDE.md");
+        write_draft(tmp.path(), file, "x").unwrap();
+
+        remove_draft(tmp.path(), file).unwrap();
+        remove_draft(tmp.path(), file).unwrap();
+
+        assert!(read_draft(tmp.path(), file).unwrap().is_none());
+    }
+
+    #[test]
+    fn drafts_of_different_files_do_not_collide() {
+        let tmp = TempDir::new().unwrap();
+        let a = Path::new("/a/CLAUDE.md");
+        let b = Path::new("/b/CLAUDE.md");
+        write_draft(tmp.path(), a, "a").unwrap();
+        write_draft(tmp.path(), b, "b").unwrap();
+
+        assert_eq!(read_draft(tmp.path(), a).unwrap().as_deref(), Some("a"));
+        assert_eq!(read_draft(tmp.path(), b).unwrap().as_deref(), Some("b"));
+    }
+}
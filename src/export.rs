@@ -0,0 +1,155 @@
+//! `jigolo export`: the discovered context files as one document.
+//!
+//! Markdown output puts each file under a `# <path>` header so the result
+//! can be read or diffed as a whole; JSON output is an array of
+//! `{root, path, content}` objects for scripts.
+
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+
+use anyhow::Context;
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Serialize;
+
+use crate::model::SourceRoot;
+
+/// Output format of `jigolo export`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum ExportFormat {
+    /// Files concatenated, each under a `# <path>` header.
+    #[default]
+    Md,
+    /// A JSON array of `{root, path, content}` objects.
+    Json,
+}
+
+/// One exported file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ExportedFile {
+    /// The scanned directory the file was found under.
+    pub root: String,
+    pub path: String,
+    pub content: String,
+}
+
+/// Reads every file of `roots`, in tree order. Files that cannot be read
+/// are returned as errors alongside the ones that could.
+pub fn collect_files(roots: &[SourceRoot]) -> (Vec<ExportedFile>, Vec<anyhow::Error>) {
+    let mut files = Vec::new();
+    let mut errors = Vec::new();
+    for root in roots {
+        for path in root.file_paths() {
+            match read(path) {
+                Ok(content) => files.push(ExportedFile {
+                    root: root.path.display().to_string(),
+                    path: path.display().to_string(),
+                    content,
+                }),
+                Err(err) => errors.push(err),
+            }
+        }
+    }
+    (files, errors)
+}
+
+/// Renders `files` in `format`.
+pub fn render(files: &[ExportedFile], format: ExportFormat) -> Result<String> {
+    match format {
+        ExportFormat::Md => Ok(render_markdown(files)),
+        ExportFormat::Json => {
+            let mut out =
+                serde_json::to_string_pretty(files).context("failed to serialize the files")?;
+            out.push('\n');
+            Ok(out)
+        }
+    }
+}
+
+/// Each file under a `# <path>` header, separated by blank lines.
+fn render_markdown(files: &[ExportedFile]) -> String {
+    let mut out = String::new();
+    for (i, file) in files.iter().enumerate() {
+        if i > 0 {
+            out.push('\n');
+        }
+        let _ = writeln!(out, "# {}\n", file.path);
+        out.push_str(&file.content);
+        if !file.content.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+fn read(path: &Path) -> Result<String> {
+    fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::model::DiscoveredFile;
+
+    fn sample_files() -> Vec<ExportedFile> {
+        vec![
+            ExportedFile {
+                root: "/p".to_string(),
+                path: "/p/CLAUDE.md".to_string(),
+                content: "# Project\n".to_string(),
+            },
+            ExportedFile {
+                root: "/p".to_string(),
+                path: "/p/sub/CLAUDE.md".to_string(),
+                content: "no newline".to_string(),
+            },
+        ]
+    }
+
+    #[test]
+    fn markdown_puts_each_file_under_its_path() {
+        let out = render(&sample_files(), ExportFormat::Md).unwrap();
+        assert_eq!(
+            out,
+            "# /p/CLAUDE.md\n\n# Project\n\n# /p/sub/CLAUDE.md\n\nno newline\n"
+        );
+    }
+
+    #[test]
+    fn json_lists_root_path_and_content() {
+        let out = render(&sample_files(), ExportFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&out).unwrap();
+        assert_eq!(value[1]["path"], "/p/sub/CLAUDE.md");
+        assert_eq!(value[1]["root"], "/p");
+        assert_eq!(value[0]["content"], "# Project\n");
+    }
+
+    #[test]
+    fn unreadable_files_are_reported_and_skipped() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "rules\n").unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![
+                DiscoveredFile::new(file),
+                DiscoveredFile::new(PathBuf::from("/missing/CLAUDE.md")),
+            ],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+
+        let (files, errors) = collect_files(&roots);
+
+        assert_eq!(files.len(), 1);
+        assert_eq!(files[0].content, "rules\n");
+        assert_eq!(errors.len(), 1);
+        assert!(errors[0].to_string().contains("/missing/CLAUDE.md"));
+    }
+}
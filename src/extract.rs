@@ -0,0 +1,130 @@
+//! Extracting a section of a CLAUDE.md into a document of its own.
+//!
+//! The section moves to a new file and an `@`-import of it takes its place,
+//! so Claude Code still reads the same instructions while the CLAUDE.md
+//! itself gets shorter.
+
+use std::ops::Range;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Result;
+use anyhow::bail;
+
+/// The file changes that extract one section.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SectionExtraction {
+    /// The file the section is taken out of.
+    pub file: PathBuf,
+    /// Content of `file` with the import in place of the section.
+    pub content: String,
+    /// New document receiving the section.
+    pub doc: PathBuf,
+    /// Content of `doc`.
+    pub doc_content: String,
+}
+
+/// Where a section titled `title` is extracted to unless another path is
+/// given, relative to the file's directory: `docs/<slug>.md`.
+pub fn default_extract_path(title: &str) -> String {
+    let slug = title
+        .to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|word| !word.is_empty())
+        .collect::<Vec<_>>()
+        .join("-");
+    if slug.is_empty() {
+        "docs/section.md".to_string()
+    } else {
+        format!("docs/{slug}.md")
+    }
+}
+
+/// Works out how to extract the `lines` of `file`, whose content is `text`,
+/// to `target`, a path relative to the file's directory, without touching
+/// the disk. The blank lines ending the section stay where they are. Fails
+/// if `target` is not a relative path below that directory, or the document
+/// already exists.
+pub fn plan_section_extraction(
+    file: &Path,
+    text: &str,
+    lines: Range<usize>,
+    target: &str,
+) -> Result<SectionExtraction> {
+    let relative = Path::new(target);
+    if target.is_empty()
+        || !relative
+            .components()
+            .all(|component| matches!(component, Component::Normal(_) | Component::CurDir))
+    {
+        bail!("{target} is not a path below the file's directory");
+    }
+    let Some(dir) = file.parent() else {
+        bail!("{} has no parent directory", file.display());
+    };
+    let doc = dir.join(relative);
+    if doc.exists() {
+        bail!("{} already exists", doc.display());
+    }
+
+    let all: Vec<&str> = text.lines().collect();
+    let section = all.get(lines.clone()).unwrap_or_default();
+    let kept = section
+        .iter()
+        .rposition(|line| !line.trim().is_empty())
+        .map_or(0, |last| last + 1);
+    let import = format!("@{target}");
+    let mut new_lines: Vec<&str> = all[..lines.start.min(all.len())].to_vec();
+    new_lines.push(&import);
+    new_lines.extend(&section[kept..]);
+    new_lines.extend(all.get(lines.end..).unwrap_or_default());
+    let mut content = new_lines.join("\n");
+    if text.ends_with('\n') {
+        content.push('\n');
+    }
+
+    Ok(SectionExtraction {
+        file: file.to_path_buf(),
+        content,
+        doc,
+        doc_content: format!("{}\n", section[..kept].join("\n")),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn default_path_is_a_slug_under_docs() {
+        assert_eq!(default_extract_path("Build & Test"), "docs/build-test.md");
+        assert_eq!(default_extract_path("???"), "docs/section.md");
+    }
+
+    #[test]
+    fn the_section_is_replaced_by_an_import_of_its_new_file() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        let text = "# Project\n\n## Build\nmake\n\n## Style\ntabs\n";
+
+        let plan = plan_section_extraction(&file, text, 2..5, "docs/build.md").unwrap();
+
+        assert_eq!(plan.doc, tmp.path().join("docs/build.md"));
+        assert_eq!(plan.doc_content, "## Build\nmake\n");
+        assert_eq!(
+            plan.content,
+            "# Project\n\n@docs/build.md\n\n## Style\ntabs\n"
+        );
+
+        fs::create_dir(tmp.path().join("docs")).unwrap();
+        fs::write(tmp.path().join("docs/build.md"), "").unwrap();
+        assert!(plan_section_extraction(&file, text, 2..5, "docs/build.md").is_err());
+        assert!(plan_section_extraction(&file, text, 2..5, "../build.md").is_err());
+        assert!(plan_section_extraction(&file, text, 2..5, "/tmp/build.md").is_err());
+    }
+}
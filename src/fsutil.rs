@@ -0,0 +1,350 @@
+use std::fmt;
+use std::fs;
+use std::fs::File;
+use std::io;
+use std::io::Read;
+use std::io::Write;
+#[cfg(unix)]
+use std::os::unix::fs::MetadataExt;
+use std::path::Path;
+use std::path::PathBuf;
+#[cfg(unix)]
+use std::sync::OnceLock;
+
+/// The start of a file, read by [`read_capped`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CappedRead {
+    pub text: String,
+    /// Size of the whole file in bytes.
+    pub total_bytes: u64,
+    /// True if `text` is only a prefix of the file.
+    pub truncated: bool,
+}
+
+/// Reads at most `max_bytes` of `path` as UTF-8. A truncated read ends at
+/// the last complete line that fits (or the last whole character if the
+/// first line alone is too long), so the preview never shows a torn line.
+pub fn read_capped(path: &Path, max_bytes: u64) -> io::Result<CappedRead> {
+    let file = File::open(path)?;
+    let total_bytes = file.metadata()?.len();
+    let mut bytes = Vec::new();
+    // One byte past the cap tells a file of exactly `max_bytes` apart from a
+    // longer one, even if the metadata size is stale.
+    file.take(max_bytes.saturating_add(1))
+        .read_to_end(&mut bytes)?;
+
+    let truncated = bytes.len() as u64 > max_bytes;
+    if truncated {
+        bytes.truncate(max_bytes as usize);
+        if let Some(newline) = bytes.iter().rposition(|&b| b == b'\n') {
+            bytes.truncate(newline + 1);
+        }
+    }
+    let text = match String::from_utf8(bytes) {
+        Ok(text) => text,
+        Err(err) => {
+            let utf8 = err.utf8_error();
+            // Only a character cut in half by the cap is forgivable.
+            if !truncated || utf8.error_len().is_some() {
+                return Err(io::Error::new(io::ErrorKind::InvalidData, utf8));
+            }
+            let mut bytes = err.into_bytes();
+            bytes.truncate(utf8.valid_up_to());
+            String::from_utf8(bytes).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))?
+        }
+    };
+    Ok(CappedRead {
+        text,
+        total_bytes,
+        truncated,
+    })
+}
+
+/// Why a file cannot be written, as found by [`check_writable`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReadOnly {
+    /// The file has no write permission.
+    File,
+    /// The file belongs to another user.
+    NotOwner,
+    /// The directory holding the file does not allow new files, which an
+    /// atomic write needs.
+    Directory,
+}
+
+impl fmt::Display for ReadOnly {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            ReadOnly::File => "the file is read-only",
+            ReadOnly::NotOwner => "the file belongs to another user",
+            ReadOnly::Directory => "its directory is not writable",
+        })
+    }
+}
+
+/// Checks whether [`write_atomic`] may replace `path`, from permissions alone.
+/// A missing file only needs a writable directory; a missing directory is
+/// left for the write itself to report.
+///
+/// A file without write bits counts as read-only even for root, the way
+/// editors treat it: the bits say the file is not meant to change.
+pub fn check_writable(path: &Path) -> Result<(), ReadOnly> {
+    if let Ok(meta) = fs::metadata(path) {
+        if meta.permissions().readonly() {
+            return Err(ReadOnly::File);
+        }
+        #[cfg(unix)]
+        {
+            if let Some(uid) = current_uid()
+                && uid != 0
+            {
+                if meta.uid() != uid && meta.mode() & 0o022 == 0 {
+                    return Err(ReadOnly::NotOwner);
+                }
+                if meta.uid() == uid && meta.mode() & 0o200 == 0 {
+                    return Err(ReadOnly::File);
+                }
+            }
+        }
+    }
+
+    let parent = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    let Ok(dir) = fs::metadata(parent) else {
+        return Ok(());
+    };
+    #[cfg(unix)]
+    {
+        if let Some(uid) = current_uid()
+            && uid != 0
+        {
+            let bits = if dir.uid() == uid { 0o200 } else { 0o022 };
+            if dir.mode() & bits == 0 {
+                return Err(ReadOnly::Directory);
+            }
+        }
+    }
+    #[cfg(not(unix))]
+    if dir.permissions().readonly() {
+        return Err(ReadOnly::Directory);
+    }
+    Ok(())
+}
+
+/// The effective user id, read off a fresh temp file so no `unsafe` call is
+/// needed. Computed once.
+#[cfg(unix)]
+fn current_uid() -> Option<u32> {
+    static UID: OnceLock<Option<u32>> = OnceLock::new();
+    *UID.get_or_init(|| {
+        let file = tempfile::tempfile().ok()?;
+        file.metadata().ok().map(|meta| meta.uid())
+    })
+}
+
+/// Writes `contents` to `path` atomically: the data goes to a temp file in the
+/// same directory which is then renamed over the target, so readers never see
+/// a half-written file. A read-only target is refused rather than replaced,
+/// since the rename would otherwise succeed regardless of its permissions.
+pub fn write_atomic(path: &Path, contents: &str) -> io::Result<()> {
+    if let Err(read_only) = check_writable(path) {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{} cannot be written: {read_only}", path.display()),
+        ));
+    }
+    let parent = match path.parent() {
+        Some(p) if !p.as_os_str().is_empty() => p,
+        _ => Path::new("."),
+    };
+    let mut tmp = tempfile::NamedTempFile::new_in(parent)?;
+    tmp.write_all(contents.as_bytes())?;
+    tmp.flush()?;
+    tmp.persist(path).map_err(|e| e.error)?;
+    Ok(())
+}
+
+/// A path typed into a prompt, with a leading `~` replaced by `$HOME`.
+/// `None` if it needs `$HOME` and that is not set.
+pub fn expand_home(raw: &str) -> Option<PathBuf> {
+    if !raw.starts_with('~') {
+        return Some(PathBuf::from(raw));
+    }
+    let home = std::env::var("HOME").ok()?;
+    Some(PathBuf::from(raw.replacen('~', &home, 1)))
+}
+
+/// Encodes a path into a single file or directory name (`/` becomes `%2F`).
+pub fn encode_path(path: &Path) -> String {
+    let raw = path.to_string_lossy();
+    let mut out = String::with_capacity(raw.len());
+    for c in raw.chars() {
+        match c {
+            '%' => out.push_str("%25"),
+            '/' => out.push_str("%2F"),
+            '\\' => out.push_str("%5C"),
+            ':' => out.push_str("%3A"),
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+/// Reverses [`encode_path`]. Returns `None` for malformed names.
+pub fn decode_path(name: &str) -> Option<PathBuf> {
+    let mut out = String::with_capacity(name.len());
+    let mut rest = name;
+    while let Some(pos) = rest.find('%') {
+        out.push_str(&rest[..pos]);
+        let code = rest.get(pos + 1..pos + 3)?;
+        out.push(match code {
+            "25" => '%',
+            "2F" => '/',
+            "5C" => '\\',
+            "3A" => ':',
+            _ => return None,
+        });
+        rest = &rest[pos + 3..];
+    }
+    out.push_str(rest);
+    Some(PathBuf::from(out))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn read_capped_returns_small_file_whole() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("a.md");
+        fs::write(&path, "one\ntwo\n").unwrap();
+
+        let read = read_capped(&path, 1024).unwrap();
+
+        assert_eq!(read.text, "one\ntwo\n");
+        assert!(!read.truncated);
+        assert_eq!(read.total_bytes, 8);
+    }
+
+    #[test]
+    fn read_capped_cuts_at_last_full_line() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("a.md");
+        fs::write(&path, "one\ntwo\nthree\n").unwrap();
+
+        let read = read_capped(&path, 10).unwrap();
+
+        assert_eq!(read.text, "one\ntwo\n");
+        assert!(read.truncated);
+        assert_eq!(read.total_bytes, 14);
+    }
+
+    #[test]
+    fn read_capped_file_of_exactly_cap_is_not_truncated() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("a.md");
+        fs::write(&path, "abcd").unwrap();
+
+        let read = read_capped(&path, 4).unwrap();
+
+        assert_eq!(read.text, "abcd");
+        assert!(!read.truncated);
+    }
+
+    #[test]
+    fn read_capped_never_splits_a_character() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("a.md");
+        // 'é' is two bytes; a 2-byte cap lands in the middle of the second.
+        fs::write(&path, "aéé").unwrap();
+
+        let read = read_capped(&path, 2).unwrap();
+
+        assert_eq!(read.text, "a");
+        assert!(read.truncated);
+    }
+
+    #[test]
+    fn read_capped_rejects_invalid_utf8() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("a.md");
+        fs::write(&path, [0x66, 0xff, 0x66]).unwrap();
+
+        let err = read_capped(&path, 1024).unwrap_err();
+
+        assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn write_atomic_creates_file() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("out.md");
+
+        write_atomic(&path, "hello").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "hello");
+    }
+
+    #[test]
+    fn write_atomic_replaces_existing_content() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("out.md");
+        fs::write(&path, "old and longer").unwrap();
+
+        write_atomic(&path, "new").unwrap();
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "new");
+    }
+
+    #[test]
+    fn read_only_file_is_reported_and_not_replaced() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("CLAUDE.md");
+        fs::write(&path, "keep").unwrap();
+        let mut perms = fs::metadata(&path).unwrap().permissions();
+        perms.set_readonly(true);
+        fs::set_permissions(&path, perms).unwrap();
+
+        assert_eq!(check_writable(&path), Err(ReadOnly::File));
+        let err = write_atomic(&path, "replaced").unwrap_err();
+        assert_eq!(err.kind(), io::ErrorKind::PermissionDenied);
+        assert!(err.to_string().contains("the file is read-only"));
+        assert_eq!(fs::read_to_string(&path).unwrap(), "keep");
+    }
+
+    #[test]
+    fn writable_and_missing_files_pass_the_check() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("CLAUDE.md");
+        assert_eq!(check_writable(&path), Ok(()));
+        fs::write(&path, "x").unwrap();
+        assert_eq!(check_writable(&path), Ok(()));
+    }
+
+    #[test]
+    fn write_atomic_fails_for_missing_directory() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("missing").join("out.md");
+
+        assert!(write_atomic(&path, "x").is_err());
+    }
+
+    #[test]
+    fn encode_decode_round_trip() {
+        let path = Path::new("/home/me/100%/CLAUDE.md");
+        let encoded = encode_path(path);
+        assert!(!encoded.contains('/'));
+        assert_eq!(decode_path(&encoded).unwrap(), path);
+    }
+
+    #[test]
+    fn decode_rejects_malformed_escape() {
+        assert!(decode_path("abc%ZZ").is_none());
+        assert!(decode_path("abc%2").is_none());
+    }
+}
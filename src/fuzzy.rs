@@ -0,0 +1,77 @@
+//! Fuzzy matching for the file finder.
+
+/// Bonus for a character matched right after the previous match.
+const CONSECUTIVE_BONUS: i64 = 5;
+/// Bonus for a character matched at the start of a path segment or word.
+const BOUNDARY_BONUS: i64 = 8;
+
+/// Scores how well `query` matches `candidate`, or returns `None` unless all
+/// of the query's characters appear in `candidate` in order. Case and
+/// whitespace in the query are ignored. Higher is better: runs of adjacent
+/// characters and matches at the start of a segment (after `/`, `-`, `_`,
+/// `.` or a space) score extra, and characters skipped between matches cost
+/// one point each.
+pub fn fuzzy_score(query: &str, candidate: &str) -> Option<i64> {
+    let haystack: Vec<char> = candidate.chars().map(fold_case).collect();
+    let mut score = 0;
+    let mut next = 0;
+    let mut previous: Option<usize> = None;
+
+    for wanted in query.chars().filter(|c| !c.is_whitespace()).map(fold_case) {
+        let found = (next..haystack.len()).find(|&i| haystack[i] == wanted)?;
+        score += 1;
+        match previous {
+            Some(prev) if prev + 1 == found => score += CONSECUTIVE_BONUS,
+            Some(prev) => score -= (found - prev - 1) as i64,
+            None => {}
+        }
+        if found == 0 || matches!(haystack[found - 1], '/' | '-' | '_' | '.' | ' ') {
+            score += BOUNDARY_BONUS;
+        }
+        previous = Some(found);
+        next = found + 1;
+    }
+    Some(score)
+}
+
+fn fold_case(c: char) -> char {
+    c.to_lowercase().next().unwrap_or(c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn characters_must_appear_in_order() {
+        assert!(fuzzy_score("api", "/work/api/CLAUDE.md").is_some());
+        assert_eq!(fuzzy_score("ipa", "/work/api/x.md"), None);
+        assert_eq!(fuzzy_score("zzz", "/work/api/CLAUDE.md"), None);
+    }
+
+    #[test]
+    fn empty_query_matches_everything() {
+        assert_eq!(fuzzy_score("", "/a/CLAUDE.md"), Some(0));
+        assert_eq!(fuzzy_score("  ", "/a/CLAUDE.md"), Some(0));
+    }
+
+    #[test]
+    fn matching_ignores_case() {
+        assert!(fuzzy_score("claude", "/a/CLAUDE.md").is_some());
+        assert!(fuzzy_score("WEB", "/code/web/CLAUDE.md").is_some());
+    }
+
+    #[test]
+    fn contiguous_match_beats_scattered() {
+        let contiguous = fuzzy_score("web", "/code/web/CLAUDE.md").unwrap();
+        let scattered = fuzzy_score("web", "/code/wide-eb/CLAUDE.md").unwrap();
+        assert!(contiguous > scattered);
+    }
+
+    #[test]
+    fn segment_start_beats_mid_word() {
+        let segment = fuzzy_score("api", "/code/api/CLAUDE.md").unwrap();
+        let mid_word = fuzzy_score("api", "/code/rapid/CLAUDE.md").unwrap();
+        assert!(segment > mid_word);
+    }
+}
@@ -0,0 +1,75 @@
+//! Locating the git work tree a path belongs to.
+//!
+//! Only the filesystem is consulted: a directory holding a `.git` entry is a
+//! work tree root. `.git` may be a file, as in linked worktrees and
+//! submodules, so both kinds count.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+/// A git work tree.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct GitRepo {
+    /// Top-level directory of the work tree.
+    pub root: PathBuf,
+}
+
+impl GitRepo {
+    /// The repository's name: the last component of its root directory.
+    pub fn name(&self) -> String {
+        self.root
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_else(|| self.root.display().to_string())
+    }
+}
+
+/// Returns the innermost work tree containing `path`, or `None` if `path`
+/// is not inside one.
+pub fn find_git_repo(path: &Path) -> Option<GitRepo> {
+    path.ancestors()
+        .find(|dir| dir.join(".git").exists())
+        .map(|dir| GitRepo {
+            root: dir.to_path_buf(),
+        })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs;
+    use tempfile::TempDir;
+
+    #[test]
+    fn file_inside_repo_finds_root() {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path().join("proj");
+        fs::create_dir_all(repo.join(".git")).unwrap();
+        fs::create_dir_all(repo.join("sub")).unwrap();
+
+        let found = find_git_repo(&repo.join("sub/CLAUDE.md")).unwrap();
+
+        assert_eq!(found.root, repo);
+        assert_eq!(found.name(), "proj");
+    }
+
+    #[test]
+    fn nested_repo_wins_over_outer() {
+        let tmp = TempDir::new().unwrap();
+        let outer = tmp.path().join("outer");
+        let inner = outer.join("vendor/inner");
+        fs::create_dir_all(outer.join(".git")).unwrap();
+        fs::create_dir_all(&inner).unwrap();
+        // Submodules and linked worktrees have a `.git` file.
+        fs::write(inner.join(".git"), "gitdir: ../../.git/modules/inner").unwrap();
+
+        assert_eq!(find_git_repo(&inner.join("CLAUDE.md")).unwrap().root, inner);
+        assert_eq!(find_git_repo(&outer.join("CLAUDE.md")).unwrap().root, outer);
+    }
+
+    #[test]
+    fn bare_directory_has_no_repo() {
+        let tmp = TempDir::new().unwrap();
+        assert_eq!(find_git_repo(&tmp.path().join("CLAUDE.md")), None);
+    }
+}
@@ -0,0 +1,174 @@
+//! A journal of the changes jigolo makes to files.
+//!
+//! Every file jigolo writes, creates, moves or deletes is noted in
+//! `~/.config/jigolo/journal.jsonl`, one JSON object per line: when, what
+//! was done, to which file, and how many bytes the file gained or lost.
+//! Unlike git history it covers every repository at once, and files outside
+//! any. `jigolo journal` and the TUI's history panel list it.
+
+use std::fs;
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::io::Write as _;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::backup::format_timestamp;
+use crate::backup::now_millis;
+use crate::budget::format_bytes;
+
+/// One change to a file.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct JournalEntry {
+    /// When the change was made, in milliseconds since the Unix epoch.
+    pub millis: u64,
+    /// What was done: `edit`, `create`, `write`, `restore`, `migrate`,
+    /// `move`, `rename` or `delete`.
+    pub action: String,
+    /// The file changed; for a move, where it went.
+    pub path: PathBuf,
+    /// Where a moved file came from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<PathBuf>,
+    /// Bytes the file gained, negative when it shrank.
+    pub delta: i64,
+}
+
+impl JournalEntry {
+    /// One line for listings: time, action, byte delta and path.
+    pub fn summary(&self) -> String {
+        let mut line = format!(
+            "{}  {:<8} {:>9}  {}",
+            format_timestamp(self.millis),
+            self.action,
+            format_delta(self.delta),
+            self.path.display()
+        );
+        if let Some(from) = &self.from {
+            line.push_str(&format!(" (from {})", from.display()));
+        }
+        line
+    }
+
+    /// Whether the entry is about `path` or, for a directory, a file under
+    /// it.
+    pub fn concerns(&self, path: &Path) -> bool {
+        self.path.starts_with(path) || self.from.as_deref().is_some_and(|f| f.starts_with(path))
+    }
+}
+
+/// The journal file inside jigolo's config directory.
+pub fn journal_path_in(config_dir: &Path) -> PathBuf {
+    config_dir.join("journal.jsonl")
+}
+
+/// Size of `path` in bytes, 0 when it does not exist. Taken before a change
+/// so [`record`] can work out the delta.
+pub fn file_size(path: &Path) -> u64 {
+    fs::metadata(path).map_or(0, |meta| meta.len())
+}
+
+/// Appends a change of `path` to `journal`, the file now having changed
+/// from `bytes_before` to its current size.
+pub fn record(journal: &Path, action: &str, path: &Path, bytes_before: u64) -> Result<()> {
+    append(
+        journal,
+        &JournalEntry {
+            millis: now_millis(),
+            action: action.to_string(),
+            path: path.to_path_buf(),
+            from: None,
+            delta: file_size(path) as i64 - bytes_before as i64,
+        },
+    )
+}
+
+/// Appends `entry` to `journal`, creating it when needed.
+pub fn append(journal: &Path, entry: &JournalEntry) -> Result<()> {
+    if let Some(dir) = journal.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    let mut line = serde_json::to_string(entry).context("failed to encode the journal entry")?;
+    line.push('\n');
+    OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(journal)
+        .and_then(|mut file| file.write_all(line.as_bytes()))
+        .with_context(|| format!("failed to write {}", journal.display()))
+}
+
+/// Every entry of `journal`, oldest first. A missing journal is empty, and
+/// lines that cannot be read are skipped.
+pub fn read_journal(journal: &Path) -> Result<Vec<JournalEntry>> {
+    let text = match fs::read_to_string(journal) {
+        Ok(text) => text,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(err) => {
+            return Err(err).with_context(|| format!("failed to read {}", journal.display()));
+        }
+    };
+    Ok(text
+        .lines()
+        .filter_map(|line| serde_json::from_str(line).ok())
+        .collect())
+}
+
+/// A byte delta with its sign: `+1.2 KB`, `-40 B`, `±0 B`.
+fn format_delta(delta: i64) -> String {
+    let sign = match delta {
+        0 => "±",
+        d if d > 0 => "+",
+        _ => "-",
+    };
+    format!("{sign}{}", format_bytes(delta.unsigned_abs()))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn changes_are_appended_with_their_byte_delta() {
+        let tmp = TempDir::new().unwrap();
+        let journal = journal_path_in(&tmp.path().join("config"));
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "0123456789").unwrap();
+
+        let before = file_size(&file);
+        fs::write(&file, "0123").unwrap();
+        record(&journal, "edit", &file, before).unwrap();
+        record(&journal, "create", &tmp.path().join("new.md"), 0).unwrap();
+        fs::write(
+            &journal,
+            fs::read_to_string(&journal).unwrap() + "not json\n",
+        )
+        .unwrap();
+
+        let entries = read_journal(&journal).unwrap();
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].action, "edit");
+        assert_eq!(entries[0].delta, -6);
+        assert!(entries[0].summary().contains("edit     "));
+        assert!(entries[0].summary().contains("-6 B"));
+        assert!(entries[0].concerns(tmp.path()));
+        assert!(!entries[0].concerns(&tmp.path().join("new.md")));
+    }
+
+    #[test]
+    fn missing_journal_is_empty() {
+        let tmp = TempDir::new().unwrap();
+        assert!(
+            read_journal(&tmp.path().join("none.jsonl"))
+                .unwrap()
+                .is_empty()
+        );
+    }
+}
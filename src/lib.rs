@@ -1,45 +1,255 @@
+pub mod backup;
+pub mod budget;
+pub mod clipboard;
+pub mod comments;
 pub mod compose;
 pub mod config;
+pub mod diff;
 pub mod discovery;
+pub mod drafts;
+pub mod export;
+pub mod extract;
+pub mod fsutil;
+pub mod fuzzy;
+pub mod git;
+pub mod journal;
 pub mod library;
+pub mod lint;
+pub mod memory;
+pub mod merge;
+pub mod migrate;
 pub mod model;
+pub mod outline;
+pub mod report;
+pub mod search;
+pub mod session;
 pub mod settings;
+pub mod state;
+pub mod templates;
+pub mod terminal;
+pub mod tokens;
+pub mod topics;
+pub mod trim;
 pub mod tui;
+pub mod warnings;
+pub mod watch;
+
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
 
 use clap::Parser;
+use ratatui::crossterm::event::DisableMouseCapture;
+use ratatui::crossterm::event::EnableMouseCapture;
+use ratatui::crossterm::event::KeyboardEnhancementFlags;
+use ratatui::crossterm::event::PopKeyboardEnhancementFlags;
+use ratatui::crossterm::event::PushKeyboardEnhancementFlags;
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::supports_keyboard_enhancement;
 
+use crate::budget::format_bytes;
+use crate::config::Config;
+use crate::config::PROJECT_CONFIG_FILE;
+use crate::config::find_project_config;
 use crate::config::load_config;
+use crate::diff::unified_diff;
+use crate::discovery::DEFAULT_FILE_PATTERNS;
 use crate::discovery::DEFAULT_MAX_DEPTH;
-use crate::discovery::find_claude_files;
-use crate::discovery::find_global_claude_file;
+use crate::discovery::ScanOptions;
+use crate::discovery::global_root;
+use crate::export::ExportFormat;
+use crate::git::find_git_repo;
+use crate::lint::LintFormat;
+use crate::lint::Severity;
 use crate::model::Cli;
+use crate::model::Command;
 use crate::model::ExitOutcome;
+use crate::model::LibraryCommand;
+use crate::model::SnippetsCommand;
 use crate::model::SourceRoot;
+use crate::tokens::Tokenizer;
+use crate::tokens::format_tokens;
 use crate::tui::app::App;
 
+/// Unchanged lines shown around each change in a `--dry-run` diff.
+const DRY_RUN_CONTEXT_LINES: usize = 3;
+
 pub fn run() -> ExitOutcome {
     let cli = Cli::parse();
-    let config = load_config().unwrap_or_default();
-
-    // CLI args override config; config overrides built-in defaults.
-    let is_default_paths = cli.paths.len() == 1 && cli.paths[0] == std::path::Path::new(".");
-    let paths = if is_default_paths {
-        config
-            .default_paths
-            .as_deref()
-            .unwrap_or(&cli.paths)
-            .to_vec()
+    let mut config = load_config().unwrap_or_default();
+    if cli.theme.is_some() {
+        config.theme = cli.theme.clone();
+    }
+
+    match &cli.command {
+        Some(Command::Library {
+            command: Some(command),
+        }) => return run_library_command(command),
+        Some(Command::Library { command: None }) => {
+            let mut app = App::new(Vec::new(), &config);
+            app.enter_library_screen();
+            run_tui(&mut app);
+            return ExitOutcome::Success;
+        }
+        Some(Command::Snippets {
+            command: SnippetsCommand::Grep { term, json },
+        }) => return grep_library(term, *json),
+        Some(Command::Report { output }) => return print_report(output.as_deref()),
+        Some(Command::Journal { path }) => return print_journal(path.as_deref()),
+        Some(Command::Init {
+            dir,
+            template,
+            list,
+            update,
+            dry_run,
+        }) => return init(dir, template.as_deref(), *list, *update, *dry_run, &config),
+        Some(Command::Session { dir, output }) => {
+            return print_session(dir, output.as_deref(), &config);
+        }
+        Some(Command::Which { dir, json }) => return print_chain(dir, *json),
+        Some(Command::Export {
+            paths,
+            format,
+            depth,
+            stop_at_repo_boundaries,
+        }) => {
+            let paths = resolve_paths(paths, cli.repo_root, &config);
+            let scan_options = scan_options(*depth, *stop_at_repo_boundaries, &cli, &config);
+            let Some(roots) = scan_roots(&paths, &scan_options) else {
+                return ExitOutcome::AllPathsFailed;
+            };
+            return print_export(&roots, *format);
+        }
+        Some(Command::Topics {
+            paths,
+            format,
+            top,
+            depth,
+            stop_at_repo_boundaries,
+        }) => {
+            let paths = resolve_paths(paths, cli.repo_root, &config);
+            let scan_options = scan_options(*depth, *stop_at_repo_boundaries, &cli, &config);
+            let Some(roots) = scan_roots(&paths, &scan_options) else {
+                return ExitOutcome::AllPathsFailed;
+            };
+            return print_topics(&roots, *format, *top);
+        }
+        Some(Command::Lint {
+            paths,
+            format,
+            strict,
+            depth,
+            stop_at_repo_boundaries,
+        }) => {
+            let paths = resolve_paths(paths, cli.repo_root, &config);
+            let scan_options = scan_options(*depth, *stop_at_repo_boundaries, &cli, &config);
+            let Some(roots) = scan_roots(&paths, &scan_options) else {
+                return ExitOutcome::AllPathsFailed;
+            };
+            return print_lint(&roots, *format, *strict, &config);
+        }
+        None => {}
+    }
+
+    let paths = resolve_paths(&cli.paths, cli.repo_root, &config);
+    let scan_options = scan_options(cli.depth, cli.stop_at_repo_boundaries, &cli, &config);
+    if cli.list {
+        let Some(roots) = scan_roots(&paths, &scan_options) else {
+            return ExitOutcome::AllPathsFailed;
+        };
+        let stats = cli.stats.then(|| config.tokenizer());
+        print_list(&roots, stats, &scan_options);
     } else {
-        cli.paths.clone()
+        let Some(dirs) = scan_dirs(&paths) else {
+            return ExitOutcome::AllPathsFailed;
+        };
+        let mut app = App::new(Vec::new(), &config);
+        app.scan_options = scan_options;
+        // The scan starts before the TUI opens, and may already warn.
+        warnings::capture();
+        // Started deep inside a repository, offer to scan all of it.
+        let repo_root = match dirs.as_slice() {
+            [dir] if !cli.repo_root && paths == [Path::new(".")] => repo_root_above(dir),
+            _ => None,
+        };
+        app.scan_in_background(dirs);
+        app.load_ui_state();
+        if let Some(root) = repo_root {
+            app.offer_repo_root(root);
+        }
+        run_tui(&mut app);
+        app.save_ui_state();
+    }
+
+    ExitOutcome::Success
+}
+
+/// The directories to scan: `paths` from the command line, or the config's
+/// `default_paths` when only the default `.` was given. With `repo_root`
+/// each one is replaced by the top of the git repository holding it.
+fn resolve_paths(paths: &[PathBuf], repo_root: bool, config: &Config) -> Vec<PathBuf> {
+    let is_default_paths = paths.len() == 1 && paths[0] == Path::new(".");
+    let paths = match &config.default_paths {
+        Some(defaults) if is_default_paths => defaults.clone(),
+        _ => paths.to_vec(),
     };
-    let depth = cli
-        .depth
-        .or(config.default_depth)
-        .unwrap_or(DEFAULT_MAX_DEPTH);
+    if !repo_root {
+        return paths;
+    }
+    let mut roots: Vec<PathBuf> = Vec::new();
+    for path in paths {
+        let root = repo_root_above(&path).unwrap_or(path);
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+    roots
+}
 
-    let mut roots: Vec<SourceRoot> = Vec::new();
-    let mut failed_count: usize = 0;
+/// The top of the git repository holding `path`, when that is above it.
+fn repo_root_above(path: &Path) -> Option<PathBuf> {
+    let path = path.canonicalize().ok()?;
+    let repo = find_git_repo(&path)?;
+    (repo.root != path).then_some(repo.root)
+}
 
+/// Scan options from the command line, falling back to the config and then
+/// to the built-in defaults.
+fn scan_options(
+    depth: Option<usize>,
+    stop_at_repos: bool,
+    cli: &Cli,
+    config: &Config,
+) -> ScanOptions {
+    let mut file_patterns = config.file_patterns.clone().unwrap_or_else(|| {
+        DEFAULT_FILE_PATTERNS
+            .iter()
+            .map(|pattern| pattern.to_string())
+            .collect()
+    });
+    file_patterns.extend(cli.patterns.iter().cloned());
+    let mut exclude = config.exclude.clone().unwrap_or_default();
+    exclude.extend(cli.excludes.iter().cloned());
+    let mut include = config.include.clone().unwrap_or_default();
+    include.extend(cli.includes.iter().cloned());
+    ScanOptions {
+        max_depth: depth.or(config.default_depth).unwrap_or(DEFAULT_MAX_DEPTH),
+        stop_at_repos,
+        pinned_depth: depth.is_some(),
+        include_global: !cli.no_global,
+        patterns: file_patterns,
+        skip_list: config.skip_dirs.clone(),
+        exclude,
+        include,
+        max_files: cli.max_files,
+    }
+}
+
+/// Scans `paths` for CLAUDE.md files and agents, commands and skills,
+/// warning about paths that are not directories, and unless `--no-global`
+/// adds the global CLAUDE.md as its own root, with the user's own agents, commands and
+/// skills, when no scan found it. `None` when every path failed.
+fn scan_roots(paths: &[PathBuf], scan_options: &ScanOptions) -> Option<Vec<SourceRoot>> {
     eprintln!(
         "Scanning {} {}...",
         paths.len(),
@@ -50,67 +260,454 @@ pub fn run() -> ExitOutcome {
         }
     );
 
-    for path in &paths {
+    let mut roots: Vec<SourceRoot> = Vec::new();
+    for dir in scan_dirs(paths)? {
+        if let Some((base, Err(err))) = find_project_config(&dir) {
+            warnings::warn(format!(
+                "ignoring {}: {err:#}",
+                base.join(PROJECT_CONFIG_FILE).display()
+            ));
+        }
+        roots.push(scan_options.scan_root(&dir));
+    }
+
+    if let Some(global) = global_root().filter(|_| scan_options.include_global) {
+        let already_found = roots
+            .iter()
+            .any(|root| root.has_file(&global.files[0].path));
+        if !already_found {
+            roots.insert(0, global);
+        }
+    }
+    Some(roots)
+}
+
+/// The directories among `paths` that can be scanned, canonicalized, with
+/// a warning for each one that cannot. `None` when every path failed.
+fn scan_dirs(paths: &[PathBuf]) -> Option<Vec<PathBuf>> {
+    let mut dirs: Vec<PathBuf> = Vec::new();
+    let mut failed_count: usize = 0;
+    for path in paths {
         if !path.exists() {
-            eprintln!("Warning: path does not exist: {}", path.display());
+            warnings::warn(format!("path does not exist: {}", path.display()));
             failed_count += 1;
             continue;
         }
         if !path.is_dir() {
-            eprintln!("Warning: not a directory: {}", path.display());
+            warnings::warn(format!("not a directory: {}", path.display()));
             failed_count += 1;
             continue;
         }
 
-        let canonical = path.canonicalize().unwrap_or_else(|_| path.clone());
-        let files = find_claude_files(&canonical, depth);
-        roots.push(SourceRoot {
-            path: canonical,
-            files,
-        });
+        dirs.push(path.canonicalize().unwrap_or_else(|_| path.clone()));
+    }
+
+    if dirs.is_empty() && failed_count > 0 {
+        return None;
+    }
+    Some(dirs)
+}
+
+/// Runs the TUI until the user quits, restoring the terminal afterwards.
+fn run_tui(app: &mut App) {
+    warnings::capture();
+    let mut terminal = ratatui::init();
+    report::record_panics();
+    // Without mouse reports the TUI still works, by keyboard alone.
+    let _ = execute!(io::stdout(), EnableMouseCapture);
+    // Tells Ctrl-I from Tab where the terminal can; elsewhere Ctrl-N does.
+    let enhanced = supports_keyboard_enhancement().unwrap_or(false)
+        && execute!(
+            io::stdout(),
+            PushKeyboardEnhancementFlags(KeyboardEnhancementFlags::DISAMBIGUATE_ESCAPE_CODES)
+        )
+        .is_ok();
+    let result = app.run(&mut terminal);
+    if enhanced {
+        let _ = execute!(io::stdout(), PopKeyboardEnhancementFlags);
+    }
+    let _ = execute!(io::stdout(), DisableMouseCapture);
+    ratatui::restore();
+    // What the TUI had no chance to show, and what it showed and is gone.
+    for warning in app.warnings.iter().cloned().chain(warnings::release()) {
+        eprintln!("Warning: {warning}");
+    }
+    if let Err(err) = result {
+        let message = format!("TUI error: {err}");
+        eprintln!("{message}");
+        let _ = report::record_error(&message);
+    }
+}
+
+/// Prints the bug report, or writes it to `output`.
+fn print_report(output: Option<&Path>) -> ExitOutcome {
+    let report = report::build_report();
+    let Some(path) = output else {
+        print!("{report}");
+        return ExitOutcome::Success;
+    };
+    match fsutil::write_atomic(path, &report) {
+        Ok(()) => {
+            eprintln!("Wrote the report to {}.", path.display());
+            ExitOutcome::Success
+        }
+        Err(err) => {
+            eprintln!("Error: cannot write {}: {err}", path.display());
+            ExitOutcome::Failed
+        }
+    }
+}
+
+/// Prints the changes jigolo made to files, only those concerning `path`
+/// when given.
+fn print_journal(path: Option<&Path>) -> ExitOutcome {
+    let Some(config_dir) = config::config_dir() else {
+        eprintln!("Error: cannot find the config directory: HOME not set.");
+        return ExitOutcome::Failed;
+    };
+    let entries = match journal::read_journal(&journal::journal_path_in(&config_dir)) {
+        Ok(entries) => entries,
+        Err(err) => {
+            eprintln!("Error: {err:#}");
+            return ExitOutcome::Failed;
+        }
+    };
+    // Deleted files cannot be canonicalized, so fall back to the absolute path.
+    let path = path.map(|path| {
+        path.canonicalize()
+            .or_else(|_| std::path::absolute(path))
+            .unwrap_or_else(|_| path.to_path_buf())
+    });
+    let mut printed = 0;
+    for entry in &entries {
+        if path.as_deref().is_none_or(|path| entry.concerns(path)) {
+            println!("{}", entry.summary());
+            printed += 1;
+        }
+    }
+    if printed == 0 {
+        eprintln!("No changes recorded.");
+    }
+    ExitOutcome::Success
+}
+
+/// Creates `dir/CLAUDE.md` from the template called `name`, looked up in
+/// the team templates first and then the built-in ones, or lists them all
+/// with `list`. With `dry_run` the file is printed as a diff instead.
+fn init(
+    dir: &Path,
+    name: Option<&str>,
+    list: bool,
+    update: bool,
+    dry_run: bool,
+    config: &Config,
+) -> ExitOutcome {
+    let team = match &config.template_source {
+        Some(source) => match templates::team_templates(source, update) {
+            Ok(team) => team,
+            Err(err) => {
+                warnings::warn(format!("cannot load the templates from {source}: {err:#}"));
+                Vec::new()
+            }
+        },
+        None => Vec::new(),
+    };
+    if list {
+        println!("Built-in templates:");
+        for template in templates::BUILTIN_TEMPLATES {
+            println!("  {:<16} {}", template.name, template.description);
+        }
+        if let Some(source) = &config.template_source {
+            println!("Team templates ({source}):");
+            for template in &team {
+                println!("  {:<16} {}", template.name, template.description);
+            }
+        }
+        return ExitOutcome::Success;
+    }
+
+    let name = name.unwrap_or("Starter");
+    let content = team
+        .iter()
+        .find(|template| template.name.eq_ignore_ascii_case(name))
+        .map(|template| template.content.clone())
+        .or_else(|| templates::find_template(name).map(|template| template.content.to_string()));
+    let Some(content) = content else {
+        eprintln!("Error: no template called {name}; `jigolo init --list` shows them all.");
+        return ExitOutcome::Failed;
+    };
+    if !dir.is_dir() {
+        eprintln!("Error: not a directory: {}", dir.display());
+        return ExitOutcome::AllPathsFailed;
+    }
+    let path = dir.join(discovery::CLAUDE_FILE_NAME);
+    if path.exists() {
+        eprintln!("Error: {} already exists.", path.display());
+        return ExitOutcome::Failed;
+    }
+    if dry_run {
+        print!(
+            "{}",
+            unified_diff(&path, "", &content, DRY_RUN_CONTEXT_LINES)
+        );
+        eprintln!("Would create {} from the {name} template.", path.display());
+        return ExitOutcome::Success;
     }
+    match fsutil::write_atomic(&path, &content) {
+        Ok(()) => {
+            if let Some(config_dir) = config::config_dir() {
+                let path = path.canonicalize().unwrap_or_else(|_| path.clone());
+                let _ = journal::record(&journal::journal_path_in(&config_dir), "create", &path, 0);
+            }
+            eprintln!("Created {} from the {name} template.", path.display());
+            ExitOutcome::Success
+        }
+        Err(err) => {
+            eprintln!("Error: cannot write {}: {err}", path.display());
+            ExitOutcome::Failed
+        }
+    }
+}
 
-    if roots.is_empty() && failed_count > 0 {
+/// Prints what a session started in `dir` loads, or writes it to `output`.
+fn print_session(dir: &Path, output: Option<&Path>, config: &Config) -> ExitOutcome {
+    if !dir.is_dir() {
+        eprintln!("Error: not a directory: {}", dir.display());
         return ExitOutcome::AllPathsFailed;
     }
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    let document = session::render_session(&session::simulate_session(&dir), config.tokenizer());
+    let Some(path) = output else {
+        print!("{document}");
+        return ExitOutcome::Success;
+    };
+    match fsutil::write_atomic(path, &document) {
+        Ok(()) => {
+            eprintln!("Wrote the session to {}.", path.display());
+            ExitOutcome::Success
+        }
+        Err(err) => {
+            eprintln!("Error: cannot write {}: {err}", path.display());
+            ExitOutcome::Failed
+        }
+    }
+}
+
+/// Prints the memory files a session in `dir` loads, in load order.
+fn print_chain(dir: &Path, json: bool) -> ExitOutcome {
+    if !dir.is_dir() {
+        eprintln!("Error: not a directory: {}", dir.display());
+        return ExitOutcome::AllPathsFailed;
+    }
+    let dir = dir.canonicalize().unwrap_or_else(|_| dir.to_path_buf());
+    match session::render_chain(&session::simulate_session(&dir), json) {
+        Ok(out) => {
+            print!("{out}");
+            ExitOutcome::Success
+        }
+        Err(err) => {
+            eprintln!("Error: {err:#}");
+            ExitOutcome::Failed
+        }
+    }
+}
+
+/// Prints the files of `roots` in `format`. Unreadable files are reported
+/// on stderr and left out.
+fn print_export(roots: &[SourceRoot], format: ExportFormat) -> ExitOutcome {
+    let (files, errors) = export::collect_files(roots);
+    for err in &errors {
+        warnings::warn(format!("{err:#}"));
+    }
+    if files.is_empty() {
+        eprintln!("No CLAUDE.md files found.");
+    }
+    match export::render(&files, format) {
+        Ok(out) => {
+            print!("{out}");
+            ExitOutcome::Success
+        }
+        Err(err) => {
+            eprintln!("Error: {err:#}");
+            ExitOutcome::Failed
+        }
+    }
+}
 
-    if let Some(global_path) = find_global_claude_file() {
-        let already_found = roots.iter().any(|root| root.files.contains(&global_path));
-        if !already_found && let Some(claude_dir) = global_path.parent() {
-            roots.insert(
-                0,
-                SourceRoot {
-                    path: claude_dir.to_path_buf(),
-                    files: vec![global_path],
-                },
+/// Prints the `top` topics of the files of `roots`, per root and overall,
+/// in `format`. Unreadable files are reported on stderr and left out.
+fn print_topics(roots: &[SourceRoot], format: ExportFormat, top: usize) -> ExitOutcome {
+    let (files, errors) = export::collect_files(roots);
+    for err in &errors {
+        warnings::warn(format!("{err:#}"));
+    }
+    if files.is_empty() {
+        eprintln!("No CLAUDE.md files found.");
+    }
+    match topics::render(&topics::topic_report(&files, top), format) {
+        Ok(out) => {
+            print!("{out}");
+            ExitOutcome::Success
+        }
+        Err(err) => {
+            eprintln!("Error: {err:#}");
+            ExitOutcome::Failed
+        }
+    }
+}
+
+/// Lints the CLAUDE.md and misnamed files of `roots` and prints the
+/// findings in `format`, with a summary on stderr. Errors, and with
+/// `strict` warnings too, make the run fail.
+fn print_lint(
+    roots: &[SourceRoot],
+    format: LintFormat,
+    strict: bool,
+    config: &Config,
+) -> ExitOutcome {
+    let files = lint::lint_targets(roots, config);
+    let findings = lint::lint_files(&files, config.tokenizer());
+    match lint::render_findings(&findings, format) {
+        Ok(out) => print!("{out}"),
+        Err(err) => {
+            eprintln!("Error: {err:#}");
+            return ExitOutcome::Failed;
+        }
+    }
+    let errors = findings
+        .iter()
+        .filter(|(_, finding)| finding.severity == Severity::Error)
+        .count();
+    let warnings = findings.len() - errors;
+    let noun = if files.len() == 1 { "file" } else { "files" };
+    eprintln!(
+        "Checked {} {noun}: {errors} error(s), {warnings} warning(s).",
+        files.len()
+    );
+    if errors > 0 || (strict && warnings > 0) {
+        ExitOutcome::ProblemsFound
+    } else {
+        ExitOutcome::Success
+    }
+}
+
+/// Exports the library to a bundle or imports one into it. A dry run prints
+/// the diff of the file that would be written instead.
+fn run_library_command(command: &LibraryCommand) -> ExitOutcome {
+    let Some(path) = library::library_path() else {
+        eprintln!("Cannot determine library path.");
+        return ExitOutcome::Failed;
+    };
+    let result = library::load_library(&path).and_then(|lib| match command {
+        LibraryCommand::Export {
+            file,
+            dry_run: true,
+        } => {
+            let (contents, count) = library::bundle_contents(&lib, file)?;
+            let old = std::fs::read_to_string(file).unwrap_or_default();
+            print!(
+                "{}",
+                unified_diff(file, &old, &contents, DRY_RUN_CONTEXT_LINES)
             );
+            let noun = if count == 1 { "snippet" } else { "snippets" };
+            Ok(format!(
+                "Would export {count} {noun} to {}.",
+                file.display()
+            ))
+        }
+        LibraryCommand::Export {
+            file,
+            dry_run: false,
+        } => {
+            let count = library::export_bundle(&lib, file)?;
+            let noun = if count == 1 { "snippet" } else { "snippets" };
+            Ok(format!("Exported {count} {noun} to {}.", file.display()))
+        }
+        LibraryCommand::Import { file, dry_run } => {
+            let bundle = library::read_bundle(file)?;
+            let (merged, summary) = library::import_bundle(&lib, &bundle);
+            if *dry_run {
+                let old = library::serialize_library(&lib)?;
+                let new = library::serialize_library(&merged)?;
+                print!("{}", unified_diff(&path, &old, &new, DRY_RUN_CONTEXT_LINES));
+                return Ok(format!("Dry run: {summary}"));
+            }
+            if summary.added > 0 {
+                library::save_library(&merged, &path)?;
+            }
+            Ok(summary.to_string())
+        }
+    });
+    match result {
+        Ok(message) => {
+            println!("{message}");
+            ExitOutcome::Success
+        }
+        Err(err) => {
+            eprintln!("Error: {err:#}");
+            ExitOutcome::Failed
         }
     }
+}
 
-    if cli.list {
-        print_list(&roots);
+/// Prints the library snippets matching `term`, one per line as
+/// `id<TAB>title<TAB>format`, or as a JSON array.
+fn grep_library(term: &str, json: bool) -> ExitOutcome {
+    let Some(path) = library::library_path() else {
+        eprintln!("Cannot determine library path.");
+        return ExitOutcome::Failed;
+    };
+    let lib = match library::load_library(&path) {
+        Ok(lib) => lib,
+        Err(err) => {
+            eprintln!("Error: {err:#}");
+            return ExitOutcome::Failed;
+        }
+    };
+
+    let matches = library::grep_snippets(&lib, term);
+    if json {
+        match serde_json::to_string_pretty(&matches) {
+            Ok(out) => println!("{out}"),
+            Err(err) => {
+                eprintln!("Error: {err}");
+                return ExitOutcome::Failed;
+            }
+        }
     } else {
-        let mut terminal = ratatui::init();
-        let mut app = App::new(roots, &config);
-        let result = app.run(&mut terminal);
-        ratatui::restore();
-        if let Err(err) = result {
-            eprintln!("TUI error: {err}");
+        for m in &matches {
+            println!("{}\t{}\t{}", m.id, m.title, m.format.name());
         }
     }
 
-    ExitOutcome::Success
+    if matches.is_empty() {
+        ExitOutcome::NoMatches
+    } else {
+        ExitOutcome::Success
+    }
 }
 
-fn print_list(roots: &[SourceRoot]) {
+fn print_list(roots: &[SourceRoot], stats: Option<&dyn Tokenizer>, scan_options: &ScanOptions) {
     let total: usize = roots.iter().map(|r| r.file_count()).sum();
+    let misnamed: usize = roots.iter().map(|r| r.misnamed_files.len()).sum();
 
-    if total == 0 {
+    if total == 0 && misnamed == 0 {
         println!("No CLAUDE.md files found.");
     } else {
-        for root in roots {
+        let rows: Vec<RootStats> = stats
+            .map(|tokenizer| roots.iter().map(|r| RootStats::of(r, tokenizer)).collect())
+            .unwrap_or_default();
+        for (i, root) in roots.iter().enumerate() {
             println!();
             print!("{root}");
+            if let Some(row) = rows.get(i) {
+                println!(
+                    "  = {} · {} lines · {}",
+                    format_bytes(row.bytes),
+                    row.lines,
+                    format_tokens(row.tokens)
+                );
+            }
         }
         println!(
             "Found {} CLAUDE.md {} in {} {}.",
@@ -123,5 +720,67 @@ fn print_list(roots: &[SourceRoot]) {
                 "directories"
             }
         );
+        if let Some(note) = scan_options.file_limit_note(roots) {
+            println!("{note}");
+        }
+        if !rows.is_empty() {
+            println!();
+            print_stats_table(roots, &rows);
+        }
+    }
+}
+
+/// Size of one scanned root, summed the way the TUI tree sums it.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+struct RootStats {
+    files: usize,
+    bytes: u64,
+    lines: usize,
+    tokens: u64,
+}
+
+impl RootStats {
+    fn of(root: &SourceRoot, tokenizer: &dyn Tokenizer) -> Self {
+        Self {
+            files: root.file_count(),
+            bytes: root.total_bytes(),
+            lines: root.total_lines(),
+            tokens: root.total_tokens(tokenizer),
+        }
+    }
+}
+
+/// One row per root and a total row, in aligned columns.
+fn print_stats_table(roots: &[SourceRoot], rows: &[RootStats]) {
+    let width = roots
+        .iter()
+        .map(|root| root.path.display().to_string().chars().count())
+        .max()
+        .unwrap_or(0)
+        .max(5);
+    let line = |label: &str, row: &RootStats| {
+        println!(
+            "{label:<width$}  {:>5}  {:>9}  {:>7}  {:>10}",
+            row.files,
+            format_bytes(row.bytes),
+            row.lines,
+            format_tokens(row.tokens)
+        );
+    };
+    println!(
+        "{:<width$}  {:>5}  {:>9}  {:>7}  {:>10}",
+        "Root", "Files", "Size", "Lines", "Tokens"
+    );
+    for (root, row) in roots.iter().zip(rows) {
+        line(&root.path.display().to_string(), row);
     }
+    let total = rows
+        .iter()
+        .fold(RootStats::default(), |sum, row| RootStats {
+            files: sum.files + row.files,
+            bytes: sum.bytes + row.bytes,
+            lines: sum.lines + row.lines,
+            tokens: sum.tokens + row.tokens,
+        });
+    line("Total", &total);
 }
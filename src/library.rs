@@ -1,8 +1,11 @@
 use anyhow::Context;
 use anyhow::Result;
+use anyhow::bail;
 use serde::Deserialize;
 use serde::Serialize;
+use std::collections::BTreeSet;
 use std::env;
+use std::fmt;
 use std::fs;
 use std::io::ErrorKind;
 use std::path::Path;
@@ -14,6 +17,168 @@ pub struct Snippet {
     pub content: String,
     #[serde(default)]
     pub source: String,
+    /// What kind of text `content` is. Missing in libraries written before
+    /// formats existed; [`Snippet::format`] infers it then.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<SnippetFormat>,
+    /// Archived snippets are kept but hidden from the Library list; they are
+    /// listed, and can be restored, in its Archive view.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub archived: bool,
+    /// Labels given in the Library, matched by its filter.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+    /// Lines of `source` the snippet was taken from, 1-based and inclusive.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub lines: Option<(usize, usize)>,
+    /// When the snippet was saved, in milliseconds since the Unix epoch.
+    /// Missing for snippets saved before it was recorded.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub created: Option<u64>,
+    /// Which library file the snippet lives in. Not stored: it is the file
+    /// the snippet was loaded from.
+    #[serde(skip)]
+    pub scope: LibraryScope,
+}
+
+/// The library file a snippet is kept in.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum LibraryScope {
+    /// `~/.config/jigolo/library.toml`, shared by every project.
+    #[default]
+    Global,
+    /// The `.jigolo/library.toml` of the project, shared through its
+    /// repository.
+    Project,
+}
+
+impl LibraryScope {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Global => "global",
+            Self::Project => "project",
+        }
+    }
+}
+
+impl Snippet {
+    /// The stored format, or the one inferred from `content` if none is.
+    pub fn format(&self) -> SnippetFormat {
+        self.format
+            .unwrap_or_else(|| SnippetFormat::infer(&self.content))
+    }
+
+    /// The project the snippet was taken from: the directory holding its
+    /// source file, or the one above if that was a `.claude` directory.
+    /// `None` when the source is unknown.
+    pub fn project(&self) -> Option<&Path> {
+        let dir = Path::new(&self.source)
+            .parent()
+            .filter(|dir| !dir.as_os_str().is_empty())?;
+        if dir.file_name().is_some_and(|name| name == ".claude") {
+            dir.parent()
+        } else {
+            Some(dir)
+        }
+    }
+}
+
+/// The kind of text a snippet holds. Decides how it is highlighted in
+/// previews and whether it is fenced when inserted into a CLAUDE.md.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SnippetFormat {
+    Markdown,
+    Shell,
+    Json,
+    Text,
+}
+
+/// First words that mark a line as a shell command.
+const SHELL_COMMANDS: &[&str] = &[
+    "cargo", "npm", "npx", "pnpm", "yarn", "git", "make", "cd", "export", "docker", "python",
+    "pip", "go", "ls", "mkdir", "rm", "cp", "mv", "curl", "sudo", "brew", "uv",
+];
+
+impl SnippetFormat {
+    /// Every format, in the order `f` cycles through them.
+    pub const ALL: [SnippetFormat; 4] = [
+        SnippetFormat::Markdown,
+        SnippetFormat::Shell,
+        SnippetFormat::Json,
+        SnippetFormat::Text,
+    ];
+
+    /// Guesses the format of `content`: JSON if it parses as an object or
+    /// array, shell if it has a shebang or every line is a prompt (`$ `) or
+    /// starts with a common command, Markdown if any line looks like a
+    /// heading, list item or fence, and plain text otherwise.
+    pub fn infer(content: &str) -> Self {
+        let trimmed = content.trim();
+        if (trimmed.starts_with('{') || trimmed.starts_with('['))
+            && serde_json::from_str::<serde_json::Value>(trimmed).is_ok()
+        {
+            return SnippetFormat::Json;
+        }
+
+        let lines: Vec<&str> = trimmed
+            .lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .collect();
+        let is_command = |line: &&str| {
+            line.starts_with("$ ")
+                || line
+                    .split_whitespace()
+                    .next()
+                    .is_some_and(|word| SHELL_COMMANDS.contains(&word))
+        };
+        if trimmed.starts_with("#!") || (!lines.is_empty() && lines.iter().all(is_command)) {
+            return SnippetFormat::Shell;
+        }
+
+        let is_markdown = |line: &&str| {
+            line.starts_with('#')
+                || line.starts_with("- ")
+                || line.starts_with("* ")
+                || line.starts_with("```")
+                || line.starts_with("> ")
+                || line
+                    .split_once(". ")
+                    .is_some_and(|(n, _)| !n.is_empty() && n.chars().all(|c| c.is_ascii_digit()))
+        };
+        if lines.iter().any(is_markdown) {
+            SnippetFormat::Markdown
+        } else {
+            SnippetFormat::Text
+        }
+    }
+
+    /// The lowercase name used in the library file and the UI.
+    pub fn name(self) -> &'static str {
+        match self {
+            SnippetFormat::Markdown => "markdown",
+            SnippetFormat::Shell => "shell",
+            SnippetFormat::Json => "json",
+            SnippetFormat::Text => "text",
+        }
+    }
+
+    /// The info string of the code fence wrapping this format in a
+    /// CLAUDE.md, or `None` if it is inserted as-is.
+    pub fn fence_language(self) -> Option<&'static str> {
+        match self {
+            SnippetFormat::Shell => Some("sh"),
+            SnippetFormat::Json => Some("json"),
+            SnippetFormat::Markdown | SnippetFormat::Text => None,
+        }
+    }
+
+    /// The format after this one in [`SnippetFormat::ALL`], wrapping around.
+    pub fn next(self) -> Self {
+        let index = Self::ALL.iter().position(|f| *f == self).unwrap_or(0);
+        Self::ALL[(index + 1) % Self::ALL.len()]
+    }
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq, Eq)]
@@ -22,6 +187,119 @@ pub struct SnippetLibrary {
     pub snippets: Vec<Snippet>,
 }
 
+impl SnippetLibrary {
+    /// Whether a snippet other than the one at `except` is titled `title`,
+    /// ignoring case. Titles must stay unique so they name one snippet.
+    pub fn has_title(&self, title: &str, except: Option<usize>) -> bool {
+        let title = title.to_lowercase();
+        self.snippets
+            .iter()
+            .enumerate()
+            .any(|(i, s)| Some(i) != except && s.title.to_lowercase() == title)
+    }
+
+    /// `title` if it is free, otherwise the first free `title (2)`,
+    /// `title (3)`, ... See [`has_title`](Self::has_title).
+    pub fn unique_title(&self, title: &str, except: Option<usize>) -> String {
+        if !self.has_title(title, except) {
+            return title.to_string();
+        }
+        (2..)
+            .map(|n| format!("{title} ({n})"))
+            .find(|candidate| !self.has_title(candidate, except))
+            .unwrap_or_else(|| title.to_string())
+    }
+
+    /// The snippets at `indices`, in library order. Indices past the end
+    /// are ignored.
+    pub fn subset(&self, indices: &BTreeSet<usize>) -> SnippetLibrary {
+        SnippetLibrary {
+            snippets: indices
+                .iter()
+                .filter_map(|&i| self.snippets.get(i))
+                .cloned()
+                .collect(),
+        }
+    }
+
+    /// Removes the snippets at `indices` in one go, so every index refers
+    /// to the library as it was. Returns how many were removed.
+    pub fn remove_snippets(&mut self, indices: &BTreeSet<usize>) -> usize {
+        let before = self.snippets.len();
+        let mut i = 0;
+        self.snippets.retain(|_| {
+            let keep = !indices.contains(&i);
+            i += 1;
+            keep
+        });
+        before - self.snippets.len()
+    }
+
+    /// Archives, or with `archived` false restores, the snippets at
+    /// `indices`. Returns how many changed.
+    pub fn set_archived(&mut self, indices: &BTreeSet<usize>, archived: bool) -> usize {
+        self.snippets
+            .iter_mut()
+            .enumerate()
+            .filter(|(i, s)| indices.contains(i) && s.archived != archived)
+            .map(|(_, s)| s.archived = archived)
+            .count()
+    }
+
+    /// Adds `tag` to the snippets at `indices` that do not have it yet,
+    /// ignoring case. Returns how many gained it.
+    pub fn tag_snippets(&mut self, indices: &BTreeSet<usize>, tag: &str) -> usize {
+        let lower = tag.to_lowercase();
+        self.snippets
+            .iter_mut()
+            .enumerate()
+            .filter(|(i, s)| {
+                indices.contains(i) && !s.tags.iter().any(|t| t.to_lowercase() == lower)
+            })
+            .map(|(_, s)| s.tags.push(tag.to_string()))
+            .count()
+    }
+}
+
+/// A snippet found by [`grep_snippets`], as printed by `jigolo snippets grep`.
+#[derive(Debug, Clone, Serialize, PartialEq, Eq)]
+pub struct SnippetMatch<'a> {
+    /// 1-based position of the snippet in the library.
+    pub id: usize,
+    pub title: &'a str,
+    pub format: SnippetFormat,
+    pub source: &'a str,
+    pub content: &'a str,
+    pub archived: bool,
+}
+
+/// Returns the snippets of `lib` whose title, content or one of whose tags
+/// contains `term`, ignoring case, in library order. Archived snippets are
+/// included.
+pub fn grep_snippets<'a>(lib: &'a SnippetLibrary, term: &str) -> Vec<SnippetMatch<'a>> {
+    let term = term.to_lowercase();
+    lib.snippets
+        .iter()
+        .enumerate()
+        .filter(|(_, s)| {
+            s.title.to_lowercase().contains(&term)
+                || s.content.to_lowercase().contains(&term)
+                || s.tags.iter().any(|t| t.to_lowercase().contains(&term))
+        })
+        .map(|(i, s)| SnippetMatch {
+            id: i + 1,
+            title: &s.title,
+            format: s.format(),
+            source: &s.source,
+            content: &s.content,
+            archived: s.archived,
+        })
+        .collect()
+}
+
+/// Where a project keeps its own snippets, relative to its root.
+pub const PROJECT_LIBRARY_PATH: &str = ".jigolo/library.toml";
+
 pub fn library_path() -> Option<PathBuf> {
     let home = env::var("HOME").ok()?;
     Some(library_path_in(&PathBuf::from(home)))
@@ -47,18 +325,215 @@ pub fn load_library(path: &Path) -> Result<SnippetLibrary> {
     }
 }
 
+/// The project library for the scan root `root`: the
+/// [`PROJECT_LIBRARY_PATH`] of `root` or of the nearest directory above it
+/// that has one, or else `root`'s, where one would be created.
+pub fn project_library_path(root: &Path) -> PathBuf {
+    root.ancestors()
+        .map(|dir| dir.join(PROJECT_LIBRARY_PATH))
+        .find(|path| path.is_file())
+        .unwrap_or_else(|| root.join(PROJECT_LIBRARY_PATH))
+}
+
+/// Loads the library at `global` followed by the snippets of the project
+/// library at `project`, which are marked [`LibraryScope::Project`].
+pub fn load_library_set(global: &Path, project: Option<&Path>) -> Result<SnippetLibrary> {
+    let mut lib = load_library(global)?;
+    if let Some(project) = project {
+        let local = load_library(project)?;
+        lib.snippets
+            .extend(local.snippets.into_iter().map(|snippet| Snippet {
+                scope: LibraryScope::Project,
+                ..snippet
+            }));
+    }
+    Ok(lib)
+}
+
+/// Saves each snippet of `lib` to the library file of its scope. Without a
+/// project library every snippet goes to `global`, and the project library
+/// is only created once it has snippets.
+pub fn save_library_set(lib: &SnippetLibrary, global: &Path, project: Option<&Path>) -> Result<()> {
+    let Some(project) = project else {
+        return save_library(lib, global);
+    };
+    let (local, shared): (Vec<Snippet>, Vec<Snippet>) = lib
+        .snippets
+        .iter()
+        .cloned()
+        .partition(|snippet| snippet.scope == LibraryScope::Project);
+    save_library(&SnippetLibrary { snippets: shared }, global)?;
+    if !local.is_empty() || project.exists() {
+        save_library(&SnippetLibrary { snippets: local }, project)?;
+    }
+    Ok(())
+}
+
+/// Returns the TOML written to disk for `lib`.
+pub fn serialize_library(lib: &SnippetLibrary) -> Result<String> {
+    toml::to_string_pretty(lib).context("failed to serialize library")
+}
+
+/// Union of two libraries: every snippet of `theirs`, followed by the
+/// snippets of `mine` that `theirs` does not already contain.
+pub fn merge_libraries(theirs: &SnippetLibrary, mine: &SnippetLibrary) -> SnippetLibrary {
+    let mut merged = theirs.clone();
+    for snippet in &mine.snippets {
+        if !merged.snippets.contains(snippet) {
+            // A different snippet under a taken title keeps both, renamed.
+            let title = merged.unique_title(&snippet.title, None);
+            merged.snippets.push(Snippet {
+                title,
+                ..snippet.clone()
+            });
+        }
+    }
+    merged
+}
+
 pub fn save_library(lib: &SnippetLibrary, path: &Path) -> Result<()> {
     if let Some(parent) = path.parent() {
         fs::create_dir_all(parent)
             .with_context(|| format!("failed to create directory {}", parent.display()))?;
     }
-    let contents = toml::to_string_pretty(lib).context("failed to serialize library")?;
+    let contents = serialize_library(lib)?;
     fs::write(path, contents).with_context(|| format!("failed to write {}", path.display()))?;
     Ok(())
 }
 
+/// File format of a snippet bundle, chosen by the file's extension:
+/// `.json` is JSON, anything else the library's own TOML.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BundleFormat {
+    Toml,
+    Json,
+}
+
+impl BundleFormat {
+    pub fn for_path(path: &Path) -> Self {
+        match path.extension().and_then(|ext| ext.to_str()) {
+            Some(ext) if ext.eq_ignore_ascii_case("json") => Self::Json,
+            _ => Self::Toml,
+        }
+    }
+}
+
+/// Writes the snippets of `lib` that are not archived to the bundle at
+/// `path`, for sharing with `jigolo library import`. Returns how many were
+/// written.
+pub fn export_bundle(lib: &SnippetLibrary, path: &Path) -> Result<usize> {
+    let (contents, count) = bundle_contents(lib, path)?;
+    crate::fsutil::write_atomic(path, &contents)
+        .with_context(|| format!("failed to write {}", path.display()))?;
+    Ok(count)
+}
+
+/// The text [`export_bundle`] writes to `path`, and how many snippets it
+/// holds.
+pub fn bundle_contents(lib: &SnippetLibrary, path: &Path) -> Result<(String, usize)> {
+    let bundle = SnippetLibrary {
+        snippets: lib
+            .snippets
+            .iter()
+            .filter(|s| !s.archived)
+            .cloned()
+            .collect(),
+    };
+    let contents = match BundleFormat::for_path(path) {
+        BundleFormat::Toml => serialize_library(&bundle)?,
+        BundleFormat::Json => {
+            serde_json::to_string_pretty(&bundle).context("failed to serialize library")? + "\n"
+        }
+    };
+    Ok((contents, bundle.snippets.len()))
+}
+
+/// Reads a bundle written by [`export_bundle`].
+pub fn read_bundle(path: &Path) -> Result<SnippetLibrary> {
+    let contents =
+        fs::read_to_string(path).with_context(|| format!("failed to read {}", path.display()))?;
+    match BundleFormat::for_path(path) {
+        BundleFormat::Toml => {
+            toml::from_str(&contents).with_context(|| format!("failed to parse {}", path.display()))
+        }
+        BundleFormat::Json => serde_json::from_str(&contents)
+            .with_context(|| format!("failed to parse {}", path.display())),
+    }
+}
+
+/// What [`import_bundle`] did with the snippets of a bundle.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ImportSummary {
+    /// Snippets added to the library, including renamed ones.
+    pub added: usize,
+    /// Added snippets whose title was taken, stored as `title (2)` etc.
+    pub renamed: usize,
+    /// Snippets skipped because the library already has their content.
+    pub duplicates: usize,
+}
+
+impl fmt::Display for ImportSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let noun = if self.added == 1 {
+            "snippet"
+        } else {
+            "snippets"
+        };
+        write!(f, "Imported {} {noun}", self.added)?;
+        let mut notes = Vec::new();
+        if self.renamed > 0 {
+            notes.push(format!("{} renamed", self.renamed));
+        }
+        if self.duplicates > 0 {
+            let noun = if self.duplicates == 1 {
+                "duplicate"
+            } else {
+                "duplicates"
+            };
+            notes.push(format!("{} {noun} skipped", self.duplicates));
+        }
+        if !notes.is_empty() {
+            write!(f, " ({})", notes.join(", "))?;
+        }
+        write!(f, ".")
+    }
+}
+
+/// Adds the snippets of `bundle` to `lib`. A snippet whose content the
+/// library already holds, archived or not, is a duplicate and skipped; one
+/// whose title is taken by different content is added under a free title.
+pub fn import_bundle(
+    lib: &SnippetLibrary,
+    bundle: &SnippetLibrary,
+) -> (SnippetLibrary, ImportSummary) {
+    let mut merged = lib.clone();
+    let mut summary = ImportSummary::default();
+    for snippet in &bundle.snippets {
+        let content = snippet.content.trim();
+        if merged.snippets.iter().any(|s| s.content.trim() == content) {
+            summary.duplicates += 1;
+            continue;
+        }
+        let title = merged.unique_title(&snippet.title, None);
+        if title != snippet.title {
+            summary.renamed += 1;
+        }
+        merged.snippets.push(Snippet {
+            title,
+            archived: false,
+            ..snippet.clone()
+        });
+        summary.added += 1;
+    }
+    (merged, summary)
+}
+
+/// Adds `snippet` to the library at `path`. Fails if its title is taken.
 pub fn append_snippet(snippet: Snippet, path: &Path) -> Result<()> {
     let mut lib = load_library(path)?;
+    if lib.has_title(&snippet.title, None) {
+        bail!("a snippet titled \"{}\" already exists", snippet.title);
+    }
     lib.snippets.push(snippet);
     save_library(&lib, path)
 }
@@ -72,8 +547,12 @@ pub fn delete_snippet(index: usize, path: &Path) -> Result<()> {
     Ok(())
 }
 
+/// Retitles snippet `index`. Fails if another snippet has the title.
 pub fn rename_snippet(index: usize, new_title: &str, path: &Path) -> Result<()> {
     let mut lib = load_library(path)?;
+    if lib.has_title(new_title, Some(index)) {
+        bail!("a snippet titled \"{new_title}\" already exists");
+    }
     if index < lib.snippets.len() {
         lib.snippets[index].title = new_title.to_string();
         save_library(&lib, path)?;
@@ -91,9 +570,67 @@ mod tests {
             title: title.to_string(),
             content: "some content".to_string(),
             source: "/path/to/CLAUDE.md".to_string(),
+            format: None,
+            archived: false,
+            tags: Vec::new(),
+            lines: None,
+            created: None,
+            scope: Default::default(),
         }
     }
 
+    #[test]
+    fn project_is_source_directory_above_dot_claude() {
+        let mut snippet = sample_snippet("A");
+        assert_eq!(snippet.project(), Some(Path::new("/path/to")));
+        snippet.source = "/code/api/.claude/CLAUDE.md".to_string();
+        assert_eq!(snippet.project(), Some(Path::new("/code/api")));
+        snippet.source = String::new();
+        assert_eq!(snippet.project(), None);
+    }
+
+    #[test]
+    fn unique_title_suffixes_taken_titles() {
+        let lib = SnippetLibrary {
+            snippets: vec![sample_snippet("Build"), sample_snippet("build (2)")],
+        };
+
+        assert!(lib.has_title("BUILD", None));
+        assert!(!lib.has_title("Build", Some(0)));
+        assert_eq!(lib.unique_title("Build", None), "Build (3)");
+        assert_eq!(lib.unique_title("Build", Some(0)), "Build");
+        assert_eq!(lib.unique_title("Test", None), "Test");
+    }
+
+    #[test]
+    fn append_and_rename_refuse_taken_titles() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("library.toml");
+        append_snippet(sample_snippet("A"), &path).unwrap();
+        append_snippet(sample_snippet("B"), &path).unwrap();
+
+        assert!(append_snippet(sample_snippet("a"), &path).is_err());
+        assert!(rename_snippet(1, "A", &path).is_err());
+        assert_eq!(load_library(&path).unwrap().snippets.len(), 2);
+    }
+
+    #[test]
+    fn merge_renames_different_snippet_with_taken_title() {
+        let theirs = SnippetLibrary {
+            snippets: vec![sample_snippet("A")],
+        };
+        let mut other = sample_snippet("A");
+        other.content = "different".to_string();
+        let mine = SnippetLibrary {
+            snippets: vec![sample_snippet("A"), other],
+        };
+
+        let merged = merge_libraries(&theirs, &mine);
+
+        let titles: Vec<&str> = merged.snippets.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["A", "A (2)"]);
+    }
+
     #[test]
     fn round_trip_save_and_load() {
         let tmp = TempDir::new().unwrap();
@@ -269,4 +806,217 @@ content = "body"
         let lib = load_library(&path).unwrap();
         assert_eq!(lib.snippets[0].title, "Only");
     }
+
+    #[test]
+    fn merge_keeps_theirs_and_adds_only_new_snippets_of_mine() {
+        let theirs = SnippetLibrary {
+            snippets: vec![sample_snippet("A"), sample_snippet("B")],
+        };
+        let mine = SnippetLibrary {
+            snippets: vec![sample_snippet("A"), sample_snippet("C")],
+        };
+
+        let merged = merge_libraries(&theirs, &mine);
+
+        let titles: Vec<&str> = merged.snippets.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["A", "B", "C"]);
+    }
+
+    #[test]
+    fn format_is_inferred_from_content() {
+        assert_eq!(
+            SnippetFormat::infer("{\"model\": \"opus\"}"),
+            SnippetFormat::Json
+        );
+        assert_eq!(
+            SnippetFormat::infer("cargo build\ncargo test --workspace"),
+            SnippetFormat::Shell
+        );
+        assert_eq!(SnippetFormat::infer("$ make lint"), SnippetFormat::Shell);
+        assert_eq!(
+            SnippetFormat::infer("## Style\n- Prefer iterators"),
+            SnippetFormat::Markdown
+        );
+        assert_eq!(
+            SnippetFormat::infer("Always run the tests first."),
+            SnippetFormat::Text
+        );
+        assert_eq!(SnippetFormat::infer("{ not json"), SnippetFormat::Text);
+    }
+
+    #[test]
+    fn stored_format_wins_and_round_trips() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("library.toml");
+        let mut snippet = sample_snippet("Commands");
+        snippet.content = "cargo test".to_string();
+        snippet.format = Some(SnippetFormat::Text);
+
+        append_snippet(snippet, &path).unwrap();
+
+        let lib = load_library(&path).unwrap();
+        assert!(
+            fs::read_to_string(&path)
+                .unwrap()
+                .contains("format = \"text\"")
+        );
+        assert_eq!(lib.snippets[0].format(), SnippetFormat::Text);
+    }
+
+    #[test]
+    fn snippet_without_format_is_not_written_with_one() {
+        let lib = SnippetLibrary {
+            snippets: vec![sample_snippet("Plain")],
+        };
+
+        let output = serialize_library(&lib).unwrap();
+
+        assert!(!output.contains("format"));
+        assert_eq!(lib.snippets[0].format(), SnippetFormat::Text);
+    }
+
+    #[test]
+    fn grep_matches_title_or_content_ignoring_case() {
+        let mut deploy = sample_snippet("Deploy");
+        deploy.content = "Run MAKE release".to_string();
+        let lib = SnippetLibrary {
+            snippets: vec![
+                sample_snippet("Style"),
+                deploy,
+                sample_snippet("Make targets"),
+            ],
+        };
+
+        let ids: Vec<usize> = grep_snippets(&lib, "make").iter().map(|m| m.id).collect();
+
+        assert_eq!(ids, [2, 3]);
+        assert!(grep_snippets(&lib, "nothing").is_empty());
+    }
+
+    #[test]
+    fn bundles_round_trip_as_toml_and_json_without_archived_snippets() {
+        let dir = TempDir::new().unwrap();
+        let mut old = sample_snippet("Old");
+        old.archived = true;
+        let lib = SnippetLibrary {
+            snippets: vec![sample_snippet("Style"), old],
+        };
+
+        for name in ["team.toml", "team.json"] {
+            let path = dir.path().join(name);
+            assert_eq!(export_bundle(&lib, &path).unwrap(), 1);
+            let bundle = read_bundle(&path).unwrap();
+            assert_eq!(bundle.snippets, [sample_snippet("Style")]);
+        }
+        let json = fs::read_to_string(dir.path().join("team.json")).unwrap();
+        assert!(json.trim_start().starts_with('{'));
+    }
+
+    #[test]
+    fn import_skips_duplicate_content_and_renames_taken_titles() {
+        let lib = SnippetLibrary {
+            snippets: vec![sample_snippet("Style")],
+        };
+        let mut other = sample_snippet("Style");
+        other.content = "Use tabs".to_string();
+        let mut archived = sample_snippet("Build");
+        archived.content = "make".to_string();
+        archived.archived = true;
+        let bundle = SnippetLibrary {
+            snippets: vec![sample_snippet("Copy of style"), other, archived],
+        };
+
+        let (merged, summary) = import_bundle(&lib, &bundle);
+
+        let titles: Vec<&str> = merged.snippets.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["Style", "Style (2)", "Build"]);
+        assert!(!merged.snippets[2].archived);
+        assert_eq!(
+            summary,
+            ImportSummary {
+                added: 2,
+                renamed: 1,
+                duplicates: 1,
+            }
+        );
+        assert_eq!(
+            summary.to_string(),
+            "Imported 2 snippets (1 renamed, 1 duplicate skipped)."
+        );
+    }
+
+    #[test]
+    fn index_sets_remove_tag_and_pick_snippets_in_one_go() {
+        let mut lib = SnippetLibrary {
+            snippets: ["A", "B", "C", "D"].map(sample_snippet).to_vec(),
+        };
+        let marked = BTreeSet::from([1, 3, 9]);
+
+        let subset = lib.subset(&marked);
+        let titles: Vec<&str> = subset.snippets.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["B", "D"]);
+
+        assert_eq!(lib.tag_snippets(&marked, "Rust"), 2);
+        assert_eq!(lib.tag_snippets(&BTreeSet::from([0, 1]), "rust"), 1);
+        assert_eq!(lib.snippets[1].tags, ["Rust"]);
+        assert_eq!(lib.snippets[0].tags, ["rust"]);
+        assert_eq!(grep_snippets(&lib, "RUST").len(), 3);
+
+        assert_eq!(lib.set_archived(&marked, true), 2);
+        assert_eq!(lib.remove_snippets(&marked), 2);
+        let titles: Vec<&str> = lib.snippets.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["A", "C"]);
+        assert!(lib.snippets.iter().all(|s| !s.archived));
+    }
+
+    #[test]
+    fn tags_are_stored_only_when_present() {
+        let mut lib = SnippetLibrary {
+            snippets: vec![sample_snippet("A")],
+        };
+        assert!(!serialize_library(&lib).unwrap().contains("tags"));
+
+        lib.snippets[0].tags.push("style".to_string());
+        let toml = serialize_library(&lib).unwrap();
+        assert!(toml.contains("tags = [\"style\"]"));
+        let read: SnippetLibrary = toml::from_str(&toml).unwrap();
+        assert_eq!(read, lib);
+    }
+
+    #[test]
+    fn project_snippets_load_after_global_ones_and_save_back_to_their_file() {
+        let tmp = TempDir::new().unwrap();
+        let global = tmp.path().join("global.toml");
+        let root = tmp.path().join("repo");
+        let nested = root.join("crates/app");
+        fs::create_dir_all(&nested).unwrap();
+        assert_eq!(
+            project_library_path(&nested),
+            nested.join(PROJECT_LIBRARY_PATH)
+        );
+        let project = root.join(PROJECT_LIBRARY_PATH);
+        save_library(
+            &SnippetLibrary {
+                snippets: vec![sample_snippet("Team")],
+            },
+            &project,
+        )
+        .unwrap();
+        assert_eq!(project_library_path(&nested), project);
+
+        let mut lib = load_library_set(&global, Some(&project)).unwrap();
+        assert_eq!(lib.snippets[0].scope, LibraryScope::Project);
+        lib.snippets.insert(0, sample_snippet("Mine"));
+        save_library_set(&lib, &global, Some(&project)).unwrap();
+
+        assert_eq!(load_library(&global).unwrap().snippets[0].title, "Mine");
+        assert_eq!(load_library(&project).unwrap().snippets[0].title, "Team");
+        let elsewhere = nested.join(PROJECT_LIBRARY_PATH);
+        lib.snippets.truncate(1);
+        save_library_set(&lib, &global, Some(&elsewhere)).unwrap();
+        assert!(
+            !elsewhere.exists(),
+            "an unused project library is not created"
+        );
+    }
 }
@@ -0,0 +1,711 @@
+//! Checks for problems in Claude context files.
+//!
+//! Each rule has a stable kebab-case name so findings can be referred to in
+//! output and, later, configured or suppressed individually. [`lint_file`]
+//! checks one file's text as the content pane shows it; [`lint_files`] adds
+//! the rules that need the file on disk or the files around it, for
+//! `jigolo lint`.
+
+use std::collections::BTreeMap;
+use std::collections::HashMap;
+use std::env;
+use std::fmt;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use clap::ValueEnum;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::config::Config;
+use crate::config::find_project_config;
+use crate::discovery::canonical_name_for;
+use crate::discovery::is_local_claude_file;
+use crate::merge::split_sections;
+use crate::model::SourceRoot;
+use crate::session::import_references;
+use crate::session::resolve_import;
+use crate::tokens::Tokenizer;
+use crate::tokens::format_tokens;
+
+/// Rule flagging `CLAUDE.local.md` files.
+pub const RULE_DEPRECATED_LOCAL_FILE: &str = "deprecated-local-file";
+
+/// Rule flagging files Claude Code ignores because of their name, such as
+/// `claude.md` or `CLAUDE.md.bak`.
+pub const RULE_MISNAMED_FILE: &str = "misnamed-file";
+
+/// Rule flagging `@path` imports that point at no file.
+pub const RULE_MISSING_IMPORT: &str = "missing-import";
+
+/// Rule flagging Markdown links to local files that do not exist.
+pub const RULE_BROKEN_LINK: &str = "broken-link";
+
+/// Rule flagging files above their token limit.
+pub const RULE_OVERSIZED_FILE: &str = "oversized-file";
+
+/// Rule flagging files with nothing but whitespace in them.
+pub const RULE_EMPTY_FILE: &str = "empty-file";
+
+/// Rule flagging files that are not valid UTF-8.
+pub const RULE_NON_UTF8: &str = "non-utf8";
+
+/// Rule flagging files that cannot be read at all.
+pub const RULE_UNREADABLE_FILE: &str = "unreadable-file";
+
+/// Rule flagging sections repeated word for word from a CLAUDE.md in a
+/// directory above, which Claude Code loads as well.
+pub const RULE_DUPLICATE_SECTION: &str = "duplicate-section";
+
+/// Output format of `jigolo lint`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, ValueEnum)]
+pub enum LintFormat {
+    /// One `path:line: severity[rule]: message` line per finding.
+    #[default]
+    Text,
+    /// A JSON array of `{path, line, rule, severity, message}` objects.
+    Json,
+}
+
+/// How serious a finding is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+impl fmt::Display for Severity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Severity::Warning => f.write_str("warning"),
+            Severity::Error => f.write_str("error"),
+        }
+    }
+}
+
+/// What the config sets a rule to: `off`, or the severity of its findings.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleLevel {
+    Off,
+    Warning,
+    Error,
+}
+
+/// Opens a comment that turns rules off for the file holding it, as in
+/// `<!-- jigolo-lint: disable=broken-link,empty-file -->`.
+pub const SUPPRESS_MARKER: &str = "jigolo-lint:";
+
+/// A file to lint with [`lint_files`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LintTarget {
+    pub path: PathBuf,
+    /// Token count above which the file is oversized.
+    pub oversized_tokens: u64,
+    /// Rules set to another level than their own, by name.
+    pub rules: BTreeMap<String, RuleLevel>,
+}
+
+/// A problem found in a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Finding {
+    /// Name of the rule that produced the finding.
+    pub rule: &'static str,
+    pub severity: Severity,
+    /// 1-based line the finding points at, or `None` for the whole file.
+    pub line: Option<usize>,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    /// Formats as `warning[rule]: message`; callers prefix the location.
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}[{}]: {}", self.severity, self.rule, self.message)
+    }
+}
+
+/// Runs every rule against `content`, the text of the file at `path`.
+pub fn lint_file(path: &Path, content: &str) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if is_local_claude_file(path) {
+        findings.push(Finding {
+            rule: RULE_DEPRECATED_LOCAL_FILE,
+            severity: Severity::Warning,
+            line: None,
+            message: "CLAUDE.local.md is deprecated; import a separate file from CLAUDE.md \
+                      or use .claude/settings.local.json"
+                .to_string(),
+        });
+    }
+    if let Some(canonical) = canonical_name_for(path) {
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        findings.push(Finding {
+            rule: RULE_MISNAMED_FILE,
+            severity: Severity::Error,
+            line: None,
+            message: format!("Claude Code ignores {name}; rename it to {canonical}"),
+        });
+    }
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let base = path.parent().unwrap_or(Path::new(""));
+    for (index, target) in import_references(content) {
+        if !resolve_import(home.as_deref(), base, &target).is_file() {
+            // An `@~/` import is personal: teammates without the file are
+            // expected, so it only warns.
+            let personal = target.starts_with("~/");
+            findings.push(Finding {
+                rule: RULE_MISSING_IMPORT,
+                severity: if personal {
+                    Severity::Warning
+                } else {
+                    Severity::Error
+                },
+                line: Some(index + 1),
+                message: if personal {
+                    format!("@{target} points at no file in this home directory")
+                } else {
+                    format!("@{target} points at no file")
+                },
+            });
+        }
+    }
+    for (index, target) in local_links(content) {
+        if !resolve_import(home.as_deref(), base, &target).exists() {
+            findings.push(Finding {
+                rule: RULE_BROKEN_LINK,
+                severity: Severity::Warning,
+                line: Some(index + 1),
+                message: format!("link to {target} points at no file"),
+            });
+        }
+    }
+    findings.sort_by_key(|finding| finding.line);
+    findings
+}
+
+/// Lints every file of `files`. On top of [`lint_file`], files are checked
+/// for being unreadable, empty, not UTF-8 or too large, and for sections that a file
+/// of `files` in a directory above already has. Each file's rule levels
+/// and suppression comments are applied. Findings come file by file, in
+/// the order of `files`.
+pub fn lint_files(files: &[LintTarget], tokenizer: &dyn Tokenizer) -> Vec<(PathBuf, Finding)> {
+    let mut texts: Vec<Option<String>> = Vec::new();
+    let mut findings = Vec::new();
+    for LintTarget {
+        path,
+        oversized_tokens: limit,
+        ..
+    } in files
+    {
+        let mut found = Vec::new();
+        let text = match fs::read(path) {
+            Ok(bytes) => match String::from_utf8(bytes) {
+                Ok(text) => Some(text),
+                Err(err) => {
+                    let valid = &err.as_bytes()[..err.utf8_error().valid_up_to()];
+                    found.push(Finding {
+                        rule: RULE_NON_UTF8,
+                        severity: Severity::Error,
+                        line: Some(valid.iter().filter(|&&b| b == b'\n').count() + 1),
+                        message: "the file is not valid UTF-8".to_string(),
+                    });
+                    None
+                }
+            },
+            Err(err) => {
+                found.push(Finding {
+                    rule: RULE_UNREADABLE_FILE,
+                    severity: Severity::Error,
+                    line: None,
+                    message: format!("cannot read the file: {err}"),
+                });
+                None
+            }
+        };
+        if let Some(text) = &text {
+            if text.trim().is_empty() {
+                found.push(Finding {
+                    rule: RULE_EMPTY_FILE,
+                    severity: Severity::Warning,
+                    line: None,
+                    message: "the file is empty".to_string(),
+                });
+            }
+            let tokens = tokenizer.count(text);
+            if tokens > *limit {
+                found.push(Finding {
+                    rule: RULE_OVERSIZED_FILE,
+                    severity: Severity::Warning,
+                    line: None,
+                    message: format!(
+                        "{} is above the limit of {}",
+                        format_tokens(tokens),
+                        format_tokens(*limit)
+                    ),
+                });
+            }
+            found.extend(lint_file(path, text));
+        }
+        texts.push(text);
+        findings.extend(found.into_iter().map(|finding| (path.clone(), finding)));
+    }
+
+    for (i, LintTarget { path, .. }) in files.iter().enumerate() {
+        let Some(text) = &texts[i] else {
+            continue;
+        };
+        let dir = project_dir(path);
+        for (j, LintTarget { path: above, .. }) in files.iter().enumerate() {
+            let above_dir = project_dir(above);
+            if i == j || above_dir == dir || !dir.starts_with(above_dir) {
+                continue;
+            }
+            let Some(above_text) = &texts[j] else {
+                continue;
+            };
+            for (line, heading) in repeated_sections(text, above_text) {
+                findings.push((
+                    path.clone(),
+                    Finding {
+                        rule: RULE_DUPLICATE_SECTION,
+                        severity: Severity::Warning,
+                        line: Some(line + 1),
+                        message: format!("{heading} repeats the section in {}", above.display()),
+                    },
+                ));
+            }
+        }
+    }
+    let disabled: Vec<Vec<String>> = texts
+        .iter()
+        .map(|text| text.as_deref().map(disabled_rules).unwrap_or_default())
+        .collect();
+    // Keep the order of `files`, stably, with each file's findings by line.
+    let order = |path: &Path| files.iter().position(|file| file.path == path);
+    findings.retain_mut(|(path, finding)| {
+        order(path).is_none_or(|i| apply_rule_level(finding, &files[i].rules, &disabled[i]))
+    });
+    findings.sort_by_key(|(path, finding)| (order(path), finding.line));
+    findings
+}
+
+/// The directory a CLAUDE.md applies to: its own, or the one above for a
+/// `.claude/CLAUDE.md`.
+fn project_dir(path: &Path) -> &Path {
+    let dir = path.parent().unwrap_or(Path::new(""));
+    if dir.file_name().is_some_and(|name| name == ".claude") {
+        dir.parent().unwrap_or(dir)
+    } else {
+        dir
+    }
+}
+
+/// The 0-based line and heading of each section of `text` that `above`
+/// has with the same heading and the same body.
+fn repeated_sections(text: &str, above: &str) -> Vec<(usize, String)> {
+    let above = split_sections(above);
+    let lines: Vec<&str> = text.lines().collect();
+    split_sections(text)
+        .into_iter()
+        .filter(|section| !section.heading.is_empty())
+        .filter(|section| {
+            above
+                .iter()
+                .any(|other| other.text.trim_end() == section.text.trim_end())
+        })
+        .filter_map(|section| {
+            let line = lines
+                .iter()
+                .position(|line| line.trim() == section.heading)?;
+            Some((line, section.heading))
+        })
+        .collect()
+}
+
+/// The files of `roots` that get linted, each once: the CLAUDE.md and
+/// misnamed files, with the oversized limit and rule levels of their
+/// project config over those of `config`.
+pub fn lint_targets(roots: &[SourceRoot], config: &Config) -> Vec<LintTarget> {
+    let mut files: Vec<LintTarget> = Vec::new();
+    for root in roots {
+        let limit = match find_project_config(&root.path) {
+            Some((_, Ok(project))) => project.oversized_tokens,
+            _ => None,
+        }
+        .unwrap_or_else(|| config.oversized_tokens());
+        let rules = rule_levels_for(&root.path, config);
+        for file in root.file_paths().chain(&root.misnamed_files) {
+            if !files.iter().any(|target| target.path == *file) {
+                files.push(LintTarget {
+                    path: file.clone(),
+                    oversized_tokens: limit,
+                    rules: rules.clone(),
+                });
+            }
+        }
+    }
+    files
+}
+
+/// The rule levels for a file in `dir`: those of its project config over
+/// those of `config`.
+pub fn rule_levels_for(dir: &Path, config: &Config) -> BTreeMap<String, RuleLevel> {
+    let mut rules = config.lint_rules.clone().unwrap_or_default();
+    if let Some((_, Ok(project))) = find_project_config(dir) {
+        rules.extend(project.lint_rules);
+    }
+    rules
+}
+
+/// The rules the `<!-- jigolo-lint: disable=… -->` comments of `text` turn
+/// off, comma-separated.
+pub fn disabled_rules(text: &str) -> Vec<String> {
+    text.match_indices(SUPPRESS_MARKER)
+        .filter_map(|(at, _)| {
+            let rest = text[at + SUPPRESS_MARKER.len()..].trim_start();
+            let list = rest.strip_prefix("disable=")?;
+            let end = list.find("-->").unwrap_or(list.len());
+            Some(
+                list[..end]
+                    .split(',')
+                    .map(|rule| rule.trim().to_string())
+                    .filter(|rule| !rule.is_empty())
+                    .collect::<Vec<_>>(),
+            )
+        })
+        .flatten()
+        .collect()
+}
+
+/// Drops the findings of [`lint_file`] for rules that `rules` turns off or
+/// the comments of `text`, the file as it is on disk, disable, and gives
+/// the rest the severity `rules` sets.
+pub fn apply_rule_levels(
+    findings: &mut Vec<Finding>,
+    rules: &BTreeMap<String, RuleLevel>,
+    text: &str,
+) {
+    let disabled = disabled_rules(text);
+    findings.retain_mut(|finding| apply_rule_level(finding, rules, &disabled));
+}
+
+/// Sets `finding` to the level `rules` gives its rule. False when the rule
+/// is off there or in `disabled`.
+fn apply_rule_level(
+    finding: &mut Finding,
+    rules: &BTreeMap<String, RuleLevel>,
+    disabled: &[String],
+) -> bool {
+    if disabled.iter().any(|rule| rule == finding.rule) {
+        return false;
+    }
+    match rules.get(finding.rule) {
+        Some(RuleLevel::Off) => false,
+        Some(RuleLevel::Warning) => {
+            finding.severity = Severity::Warning;
+            true
+        }
+        Some(RuleLevel::Error) => {
+            finding.severity = Severity::Error;
+            true
+        }
+        None => true,
+    }
+}
+
+/// How many findings of each severity a file has.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct FindingCounts {
+    pub errors: usize,
+    pub warnings: usize,
+}
+
+/// Counts the findings of [`lint_files`] per file. Files without findings
+/// are left out.
+pub fn count_findings(findings: &[(PathBuf, Finding)]) -> HashMap<PathBuf, FindingCounts> {
+    let mut counts: HashMap<PathBuf, FindingCounts> = HashMap::new();
+    for (path, finding) in findings {
+        let count = counts.entry(path.clone()).or_default();
+        match finding.severity {
+            Severity::Error => count.errors += 1,
+            Severity::Warning => count.warnings += 1,
+        }
+    }
+    counts
+}
+
+/// Renders the findings of [`lint_files`] in `format`.
+pub fn render_findings(findings: &[(PathBuf, Finding)], format: LintFormat) -> Result<String> {
+    match format {
+        LintFormat::Text => {
+            let mut out = String::new();
+            for (path, finding) in findings {
+                let _ = match finding.line {
+                    Some(line) => writeln!(out, "{}:{line}: {finding}", path.display()),
+                    None => writeln!(out, "{}: {finding}", path.display()),
+                };
+            }
+            Ok(out)
+        }
+        LintFormat::Json => {
+            let entries: Vec<JsonFinding> = findings
+                .iter()
+                .map(|(path, finding)| JsonFinding {
+                    path: path.display().to_string(),
+                    line: finding.line,
+                    rule: finding.rule,
+                    severity: finding.severity,
+                    message: &finding.message,
+                })
+                .collect();
+            let mut out =
+                serde_json::to_string_pretty(&entries).context("failed to encode the findings")?;
+            out.push('\n');
+            Ok(out)
+        }
+    }
+}
+
+/// A finding as `jigolo lint --format json` prints it.
+#[derive(Debug, Serialize)]
+struct JsonFinding<'a> {
+    path: String,
+    line: Option<usize>,
+    rule: &'static str,
+    severity: Severity,
+    message: &'a str,
+}
+
+/// The targets of Markdown links to local files in `text`, with the 0-based
+/// line each is on. Links to web pages and anchors are left out, and so are
+/// links in code blocks and code spans. Fragments are dropped.
+fn local_links(text: &str) -> Vec<(usize, String)> {
+    let mut links = Vec::new();
+    let mut in_fence = false;
+    for (index, line) in text.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        for prose in line.split('`').step_by(2) {
+            for (at, _) in prose.match_indices("](") {
+                let Some((inside, _)) = prose[at + 2..].split_once(')') else {
+                    continue;
+                };
+                let target = inside.split_whitespace().next().unwrap_or("");
+                let target = target.trim_start_matches('<').trim_end_matches('>');
+                let target = target.split('#').next().unwrap_or("");
+                let is_url = target
+                    .split_once(':')
+                    .is_some_and(|(scheme, _)| scheme.chars().all(|c| c.is_ascii_alphanumeric()));
+                if !target.is_empty() && !is_url {
+                    links.push((index, target.to_string()));
+                }
+            }
+        }
+    }
+    links
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn findings_are_counted_per_file_and_severity() {
+        let finding = |severity| Finding {
+            rule: RULE_EMPTY_FILE,
+            severity,
+            line: None,
+            message: String::new(),
+        };
+        let a = PathBuf::from("/p/a.md");
+        let b = PathBuf::from("/p/b.md");
+        let counts = count_findings(&[
+            (a.clone(), finding(Severity::Error)),
+            (a.clone(), finding(Severity::Warning)),
+            (b.clone(), finding(Severity::Warning)),
+            (b.clone(), finding(Severity::Warning)),
+        ]);
+
+        assert_eq!(
+            counts[&a],
+            FindingCounts {
+                errors: 1,
+                warnings: 1
+            }
+        );
+        assert_eq!(
+            counts[&b],
+            FindingCounts {
+                errors: 0,
+                warnings: 2
+            }
+        );
+    }
+
+    #[test]
+    fn local_file_is_flagged() {
+        let findings = lint_file(Path::new("/p/CLAUDE.local.md"), "notes");
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, RULE_DEPRECATED_LOCAL_FILE);
+        assert_eq!(findings[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn misnamed_file_is_flagged_with_its_canonical_name() {
+        let findings = lint_file(Path::new("/p/claude.md"), "notes");
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, RULE_MISNAMED_FILE);
+        assert_eq!(
+            findings[0].message,
+            "Claude Code ignores claude.md; rename it to CLAUDE.md"
+        );
+    }
+
+    #[test]
+    fn regular_file_is_clean() {
+        assert!(lint_file(Path::new("/p/CLAUDE.md"), "notes").is_empty());
+    }
+
+    #[test]
+    fn missing_imports_and_broken_links_are_flagged_on_their_lines() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        std::fs::write(tmp.path().join("style.md"), "tabs").unwrap();
+        let content = "See @style.md and @gone.md\n\
+                       [Style](style.md#tabs) and [old](docs/old.md \"Old\")\n\
+                       [site](https://example.com) [top](#top) `[x](nope.md)`\n";
+
+        let findings = lint_file(&tmp.path().join("CLAUDE.md"), content);
+
+        let found: Vec<(&str, Option<usize>)> = findings.iter().map(|f| (f.rule, f.line)).collect();
+        assert_eq!(
+            found,
+            [(RULE_MISSING_IMPORT, Some(1)), (RULE_BROKEN_LINK, Some(2))]
+        );
+        assert_eq!(findings[1].message, "link to docs/old.md points at no file");
+    }
+
+    #[test]
+    fn missing_personal_imports_only_warn() {
+        let content = "@~/.claude/jigolo-lint-test-nowhere/notes.md\n";
+
+        let findings = lint_file(Path::new("/p/CLAUDE.md"), content);
+
+        assert_eq!(findings.len(), 1);
+        assert_eq!(findings[0].rule, RULE_MISSING_IMPORT);
+        assert_eq!(findings[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn files_are_checked_for_size_emptiness_encoding_and_repeated_sections() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let root = tmp.path().join("CLAUDE.md");
+        let sub = tmp.path().join("sub/CLAUDE.md");
+        let empty = tmp.path().join("empty/CLAUDE.md");
+        let binary = tmp.path().join("bin/CLAUDE.md");
+        for dir in ["sub", "empty", "bin"] {
+            fs::create_dir_all(tmp.path().join(dir)).unwrap();
+        }
+        fs::write(&root, "# Rules\n\n## Build\nmake\n").unwrap();
+        fs::write(&sub, "# Sub\n\n## Build\nmake\n\n## Test\nmake test\n").unwrap();
+        fs::write(&empty, "\n  \n").unwrap();
+        fs::write(&binary, b"ok\n\xff\xfe").unwrap();
+        let missing = tmp.path().join("gone/CLAUDE.md");
+        let files: Vec<LintTarget> = [&root, &sub, &empty, &binary, &missing]
+            .into_iter()
+            .map(|path| LintTarget {
+                path: path.clone(),
+                oversized_tokens: 5,
+                rules: BTreeMap::new(),
+            })
+            .collect();
+
+        let findings = lint_files(&files, &crate::tokens::ByteHeuristic);
+
+        let found: Vec<(&Path, &str, Option<usize>)> = findings
+            .iter()
+            .map(|(path, f)| (path.as_path(), f.rule, f.line))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                (root.as_path(), RULE_OVERSIZED_FILE, None),
+                (sub.as_path(), RULE_OVERSIZED_FILE, None),
+                (sub.as_path(), RULE_DUPLICATE_SECTION, Some(3)),
+                (empty.as_path(), RULE_EMPTY_FILE, None),
+                (binary.as_path(), RULE_NON_UTF8, Some(2)),
+                (missing.as_path(), RULE_UNREADABLE_FILE, None),
+            ]
+        );
+    }
+
+    #[test]
+    fn rule_levels_and_suppression_comments_change_findings() {
+        let content = "<!-- jigolo-lint: disable=broken-link -->\n\
+                       @gone.md [old](old.md)\n";
+        let mut findings = lint_file(Path::new("/p/CLAUDE.md"), content);
+        assert_eq!(findings.len(), 2);
+
+        let rules = BTreeMap::from([(RULE_MISSING_IMPORT.to_string(), RuleLevel::Warning)]);
+        apply_rule_levels(&mut findings, &rules, content);
+
+        let found: Vec<(&str, Severity)> = findings.iter().map(|f| (f.rule, f.severity)).collect();
+        assert_eq!(found, [(RULE_MISSING_IMPORT, Severity::Warning)]);
+        assert_eq!(
+            disabled_rules("<!-- jigolo-lint: disable= a, b-->\n<!-- jigolo-lint: disable=c -->"),
+            ["a", "b", "c"]
+        );
+
+        let rules = BTreeMap::from([(RULE_MISSING_IMPORT.to_string(), RuleLevel::Off)]);
+        apply_rule_levels(&mut findings, &rules, "");
+        assert!(findings.is_empty());
+    }
+
+    #[test]
+    fn findings_render_as_text_or_json() {
+        let findings = vec![(
+            PathBuf::from("/p/CLAUDE.md"),
+            Finding {
+                rule: RULE_MISSING_IMPORT,
+                severity: Severity::Error,
+                line: Some(2),
+                message: "@x.md points at no file".to_string(),
+            },
+        )];
+
+        assert_eq!(
+            render_findings(&findings, LintFormat::Text).unwrap(),
+            "/p/CLAUDE.md:2: error[missing-import]: @x.md points at no file\n"
+        );
+        let json: serde_json::Value =
+            serde_json::from_str(&render_findings(&findings, LintFormat::Json).unwrap()).unwrap();
+        assert_eq!(json[0]["severity"], "error");
+        assert_eq!(json[0]["line"], 2);
+    }
+
+    #[test]
+    fn display_shows_severity_rule_and_message() {
+        let finding = Finding {
+            rule: "r",
+            severity: Severity::Error,
+            line: Some(3),
+            message: "bad".to_string(),
+        };
+        assert_eq!(finding.to_string(), "error[r]: bad");
+    }
+}
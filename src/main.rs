@@ -6,6 +6,8 @@ use jigolo::run;
 fn main() {
     match run() {
         ExitOutcome::Success => {}
-        ExitOutcome::AllPathsFailed => process::exit(1),
+        ExitOutcome::AllPathsFailed | ExitOutcome::NoMatches => process::exit(1),
+        ExitOutcome::Failed => process::exit(2),
+        ExitOutcome::ProblemsFound => process::exit(3),
     }
 }
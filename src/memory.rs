@@ -0,0 +1,205 @@
+//! Appending snippets to CLAUDE.md as memory entries.
+//!
+//! Claude Code's `#` shortcut records memories as bullet points in CLAUDE.md.
+//! Snippets exported from jigolo follow the same shape and are collected
+//! under a dedicated heading (`# Memories` unless configured otherwise), so
+//! they sit alongside the entries Claude adds itself.
+
+/// Heading used when the config does not set `memory_heading`.
+pub const DEFAULT_MEMORY_HEADING: &str = "# Memories";
+
+/// Formats snippet text as a memory bullet. Text that is already a bullet
+/// list is kept as is; anything else becomes one bullet with continuation
+/// lines indented beneath it.
+pub fn format_memory_entry(snippet: &str) -> String {
+    let lines: Vec<&str> = snippet
+        .trim_matches('\n')
+        .lines()
+        .map(str::trim_end)
+        .collect();
+    let is_list = lines
+        .iter()
+        .filter(|l| !l.trim().is_empty())
+        .all(|l| is_bullet(l.trim_start()));
+    if is_list && !lines.is_empty() {
+        return lines.join("\n");
+    }
+
+    lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| match (i, line.is_empty()) {
+            (0, _) => format!("- {line}"),
+            (_, true) => String::new(),
+            (_, false) => format!("  {line}"),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Returns `content` with `snippet` appended as a memory entry at the end of
+/// the `heading` section, creating the section at the end of the file if it
+/// does not exist yet.
+pub fn append_memory(content: &str, heading: &str, snippet: &str) -> String {
+    let entry = format_memory_entry(snippet);
+    let lines: Vec<&str> = content.lines().collect();
+    let heading = heading.trim();
+    let level = heading_level(heading).unwrap_or(1);
+
+    let fenced = fenced_mask(&lines);
+    let level_at = |i: usize| {
+        if fenced[i] {
+            None
+        } else {
+            heading_level(lines[i])
+        }
+    };
+
+    let start = (0..lines.len()).find(|&i| level_at(i).is_some() && lines[i].trim() == heading);
+    let Some(start) = start else {
+        let body = content.trim_end_matches('\n');
+        return if body.is_empty() {
+            format!("{heading}\n\n{entry}\n")
+        } else {
+            format!("{body}\n\n{heading}\n\n{entry}\n")
+        };
+    };
+
+    // The section ends at the next heading of the same or a higher level.
+    let end = (start + 1..lines.len())
+        .find(|&i| level_at(i).is_some_and(|lvl| lvl <= level))
+        .unwrap_or(lines.len());
+    // Insert after the last non-blank line of the section.
+    let insert_at = lines[start + 1..end]
+        .iter()
+        .rposition(|l| !l.trim().is_empty())
+        .map_or(start + 1, |offset| start + 1 + offset + 1);
+
+    let mut out: Vec<String> = lines[..insert_at]
+        .iter()
+        .map(|l| (*l).to_string())
+        .collect();
+    if insert_at == start + 1 {
+        out.push(String::new());
+    }
+    out.push(entry);
+    if insert_at < lines.len() {
+        out.push(String::new());
+        out.extend(
+            lines[insert_at..]
+                .iter()
+                .skip_while(|l| l.trim().is_empty())
+                .map(|l| (*l).to_string()),
+        );
+    }
+    let mut result = out.join("\n");
+    result.push('\n');
+    result
+}
+
+/// Returns the ATX heading level of `line` (`## x` is 2), if it is one.
+fn heading_level(line: &str) -> Option<usize> {
+    let hashes = line.chars().take_while(|&c| c == '#').count();
+    let rest = &line[hashes..];
+    (hashes > 0 && hashes <= 6 && (rest.is_empty() || rest.starts_with(' '))).then_some(hashes)
+}
+
+/// Marks lines inside fenced code blocks (fence lines included), where `#`
+/// starts a comment rather than a heading.
+fn fenced_mask(lines: &[&str]) -> Vec<bool> {
+    let mut in_fence = false;
+    lines
+        .iter()
+        .map(|l| {
+            let is_fence = l.trim_start().starts_with("```");
+            let masked = in_fence || is_fence;
+            if is_fence {
+                in_fence = !in_fence;
+            }
+            masked
+        })
+        .collect()
+}
+
+fn is_bullet(line: &str) -> bool {
+    line.starts_with("- ") || line.starts_with("* ") || line == "-" || line == "*"
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn single_line_becomes_bullet() {
+        assert_eq!(
+            format_memory_entry("Use cargo nextest"),
+            "- Use cargo nextest"
+        );
+    }
+
+    #[test]
+    fn multi_line_is_indented_under_one_bullet() {
+        assert_eq!(
+            format_memory_entry("Run tests\nwith --release\n"),
+            "- Run tests\n  with --release"
+        );
+    }
+
+    #[test]
+    fn existing_list_is_kept() {
+        assert_eq!(format_memory_entry("- a\n- b"), "- a\n- b");
+    }
+
+    #[test]
+    fn creates_section_at_end_when_missing() {
+        let out = append_memory("# Project\n\nIntro\n", "# Memories", "Remember this");
+        assert_eq!(out, "# Project\n\nIntro\n\n# Memories\n\n- Remember this\n");
+    }
+
+    #[test]
+    fn creates_section_in_empty_file() {
+        assert_eq!(append_memory("", "# Memories", "x"), "# Memories\n\n- x\n");
+    }
+
+    #[test]
+    fn appends_to_end_of_existing_section() {
+        let content = "# Memories\n\n- first\n\n# Other\n\ntext\n";
+        let out = append_memory(content, "# Memories", "second");
+        assert_eq!(out, "# Memories\n\n- first\n- second\n\n# Other\n\ntext\n");
+    }
+
+    #[test]
+    fn subheadings_stay_inside_section() {
+        let content = "# Memories\n\n## Old\n- a\n# Next\n";
+        let out = append_memory(content, "# Memories", "b");
+        assert_eq!(out, "# Memories\n\n## Old\n- a\n- b\n\n# Next\n");
+    }
+
+    #[test]
+    fn empty_section_gets_blank_line_after_heading() {
+        let out = append_memory("# Memories\n", "# Memories", "x");
+        assert_eq!(out, "# Memories\n\n- x\n");
+    }
+
+    #[test]
+    fn custom_heading_level_is_respected() {
+        let content = "# Top\n## Notes\n- a\n## Later\n";
+        let out = append_memory(content, "## Notes", "b");
+        assert_eq!(out, "# Top\n## Notes\n- a\n- b\n\n## Later\n");
+    }
+
+    #[test]
+    fn hash_comments_in_code_blocks_are_not_headings() {
+        let content = "# Memories\n\n```bash\n# build\n```\n";
+        let out = append_memory(content, "# Memories", "x");
+        assert_eq!(out, "# Memories\n\n```bash\n# build\n```\n- x\n");
+    }
+
+    #[test]
+    fn heading_level_detection() {
+        assert_eq!(heading_level("# A"), Some(1));
+        assert_eq!(heading_level("### A"), Some(3));
+        assert_eq!(heading_level("#hashtag"), None);
+        assert_eq!(heading_level("text"), None);
+    }
+}
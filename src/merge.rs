@@ -0,0 +1,278 @@
+//! Merging two CLAUDE.md files section by section.
+//!
+//! Both files are split at their headings and the sections are paired up
+//! by heading, so the same `## Build` in each file lines up even when the
+//! files order their sections differently. Each pair is then kept from
+//! one file, from both, or dropped, and the merged file is put together in
+//! the order of the first file, with sections only the second file has
+//! following the section they came after there.
+
+use crate::outline::parse_headings;
+
+/// One section of a file: a heading and the lines up to the next heading,
+/// or the text before the first heading.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// The heading line as written, such as `## Build`; empty for the text
+    /// before the first heading.
+    pub heading: String,
+    /// The whole section, heading line included, ending in a newline.
+    pub text: String,
+}
+
+impl Section {
+    /// What sections are paired by: the heading's level and title, ignoring
+    /// case and surrounding spaces.
+    fn key(&self) -> String {
+        self.heading.trim().to_lowercase()
+    }
+
+    /// The section without its heading line.
+    fn body(&self) -> &str {
+        if self.heading.is_empty() {
+            return &self.text;
+        }
+        self.text.split_once('\n').map_or("", |(_, body)| body)
+    }
+}
+
+/// How a pair of sections compares.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SectionStatus {
+    /// Both files have the section with the same text.
+    Same,
+    OnlyA,
+    OnlyB,
+    /// Both files have the section, with different text.
+    Conflict,
+}
+
+/// Which text of a pair goes into the merged file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MergeChoice {
+    A,
+    B,
+    /// A's section followed by the body of B's.
+    Both,
+    /// Neither: the section is left out.
+    Drop,
+}
+
+/// A section of either file or both, with what to keep of it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MergeItem {
+    pub a: Option<Section>,
+    pub b: Option<Section>,
+    pub choice: MergeChoice,
+}
+
+impl MergeItem {
+    pub fn status(&self) -> SectionStatus {
+        match (&self.a, &self.b) {
+            (Some(a), Some(b)) if a.text.trim_end() == b.text.trim_end() => SectionStatus::Same,
+            (Some(_), Some(_)) => SectionStatus::Conflict,
+            (Some(_), None) => SectionStatus::OnlyA,
+            (None, _) => SectionStatus::OnlyB,
+        }
+    }
+
+    /// The heading shown for the item, `(top of file)` for text before the
+    /// first heading.
+    pub fn heading(&self) -> &str {
+        let heading = self
+            .a
+            .as_ref()
+            .or(self.b.as_ref())
+            .map_or("", |section| section.heading.as_str());
+        if heading.is_empty() {
+            "(top of file)"
+        } else {
+            heading
+        }
+    }
+
+    /// The text this item adds to the merged file.
+    fn merged(&self) -> String {
+        match (self.choice, &self.a, &self.b) {
+            (MergeChoice::Drop, _, _) => String::new(),
+            (MergeChoice::Both, Some(a), Some(b)) => {
+                let mut text = a.text.clone();
+                if !text.ends_with("\n\n") && !b.body().starts_with('\n') {
+                    text.push('\n');
+                }
+                text.push_str(b.body());
+                text
+            }
+            (MergeChoice::A, Some(a), _) | (MergeChoice::Both, Some(a), None) => a.text.clone(),
+            (MergeChoice::B, _, Some(b)) | (MergeChoice::Both, None, Some(b)) => b.text.clone(),
+            _ => String::new(),
+        }
+    }
+}
+
+/// Splits `text` at every heading outside code blocks.
+pub fn split_sections(text: &str) -> Vec<Section> {
+    let lines: Vec<&str> = text.lines().collect();
+    let headings = parse_headings(text);
+    let mut starts: Vec<usize> = headings.iter().map(|h| h.line).collect();
+    if starts.first() != Some(&0) {
+        starts.insert(0, 0);
+    }
+    starts
+        .iter()
+        .enumerate()
+        .filter_map(|(i, &start)| {
+            let end = starts.get(i + 1).copied().unwrap_or(lines.len());
+            let chunk = &lines[start..end];
+            if chunk.iter().all(|line| line.trim().is_empty()) {
+                return None;
+            }
+            let is_heading = headings.iter().any(|h| h.line == start);
+            Some(Section {
+                heading: if is_heading {
+                    chunk[0].trim().to_string()
+                } else {
+                    String::new()
+                },
+                text: chunk.iter().map(|line| format!("{line}\n")).collect(),
+            })
+        })
+        .collect()
+}
+
+/// Pairs the sections of `a` and `b` by heading, in merged order. Sections
+/// both files have start out taken from A, and sections only one file has
+/// start out kept.
+pub fn align_sections(a: &str, b: &str) -> Vec<MergeItem> {
+    let a_sections = split_sections(a);
+    let mut b_sections: Vec<Option<Section>> = split_sections(b).into_iter().map(Some).collect();
+
+    let mut paired: Vec<MergeItem> = Vec::new();
+    // The A section each B section is paired with.
+    let mut partner_of_b: Vec<Option<usize>> = vec![None; b_sections.len()];
+    for (i, section) in a_sections.into_iter().enumerate() {
+        let partner = b_sections
+            .iter()
+            .position(|b| b.as_ref().is_some_and(|b| b.key() == section.key()));
+        if let Some(j) = partner {
+            partner_of_b[j] = Some(i);
+        }
+        paired.push(MergeItem {
+            a: Some(section),
+            b: partner.and_then(|j| b_sections[j].take()),
+            choice: MergeChoice::A,
+        });
+    }
+
+    // Sections only B has, by the slot they go in: slot 0 before everything,
+    // slot `i + 1` after A's section `i`, the last paired one before them
+    // in B.
+    let mut unpaired: Vec<Vec<Section>> = vec![Vec::new(); paired.len() + 1];
+    let mut slot = 0;
+    for (section, partner) in b_sections.into_iter().zip(partner_of_b) {
+        match (section, partner) {
+            (Some(section), _) => unpaired[slot].push(section),
+            (None, Some(i)) => slot = i + 1,
+            (None, None) => {}
+        }
+    }
+
+    let only_b = |section: Section| MergeItem {
+        a: None,
+        b: Some(section),
+        choice: MergeChoice::B,
+    };
+    let mut slots = unpaired.into_iter();
+    let mut items: Vec<MergeItem> = slots
+        .next()
+        .unwrap_or_default()
+        .into_iter()
+        .map(only_b)
+        .collect();
+    for (item, after) in paired.into_iter().zip(slots) {
+        items.push(item);
+        items.extend(after.into_iter().map(only_b));
+    }
+    items
+}
+
+/// The merged file for the choices made in `items`.
+pub fn merged_text(items: &[MergeItem]) -> String {
+    let mut out = String::new();
+    for item in items {
+        let text = item.merged();
+        if text.is_empty() {
+            continue;
+        }
+        if !out.is_empty() && !out.ends_with("\n\n") && text.starts_with('#') {
+            out.push('\n');
+        }
+        out.push_str(&text);
+    }
+    let trimmed = out.trim_end();
+    if trimmed.is_empty() {
+        String::new()
+    } else {
+        format!("{trimmed}\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn headings(items: &[MergeItem]) -> Vec<(&str, SectionStatus)> {
+        items
+            .iter()
+            .map(|item| (item.heading(), item.status()))
+            .collect()
+    }
+
+    #[test]
+    fn sections_split_at_headings_outside_code() {
+        let text = "Intro\n\n# Rules\n- tabs\n```sh\n# comment\n```\n## Build\nmake\n";
+        let sections = split_sections(text);
+
+        let names: Vec<&str> = sections.iter().map(|s| s.heading.as_str()).collect();
+        assert_eq!(names, ["", "# Rules", "## Build"]);
+        assert_eq!(sections[1].text, "# Rules\n- tabs\n```sh\n# comment\n```\n");
+    }
+
+    #[test]
+    fn sections_pair_by_heading_and_keep_b_only_ones_near_their_neighbours() {
+        let a = "# Rules\n- tabs\n\n## Build\nmake\n\n## Style\nshort\n";
+        let b =
+            "# Rules\n- tabs\n\n## Build\ncargo build\n\n## Deploy\nship it\n\n## style\nshort\n";
+
+        let items = align_sections(a, b);
+
+        assert_eq!(
+            headings(&items),
+            [
+                ("# Rules", SectionStatus::Same),
+                ("## Build", SectionStatus::Conflict),
+                ("## Deploy", SectionStatus::OnlyB),
+                ("## Style", SectionStatus::Conflict),
+            ]
+        );
+    }
+
+    #[test]
+    fn merged_text_follows_the_choices() {
+        let a = "# Rules\n- tabs\n\n## Build\nmake\n";
+        let b = "# Rules\n- tabs\n\n## Build\ncargo build\n\n## Deploy\nship it\n";
+        let mut items = align_sections(a, b);
+
+        assert_eq!(
+            merged_text(&items),
+            "# Rules\n- tabs\n\n## Build\nmake\n\n## Deploy\nship it\n"
+        );
+
+        items[1].choice = MergeChoice::Both;
+        items[2].choice = MergeChoice::Drop;
+        assert_eq!(
+            merged_text(&items),
+            "# Rules\n- tabs\n\n## Build\nmake\n\ncargo build\n"
+        );
+    }
+}
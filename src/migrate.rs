@@ -0,0 +1,198 @@
+//! Migrating deprecated `CLAUDE.local.md` files.
+//!
+//! The content moves to a document under `~/.claude/` and the sibling
+//! CLAUDE.md gains an `@~/`-import of it, which is how Claude Code now
+//! expects personal instructions to be pulled in. Keeping the document in
+//! the home directory keeps it out of the project, and so out of version
+//! control, as the local file was.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+
+use crate::discovery::CLAUDE_FILE_NAME;
+use crate::discovery::is_local_claude_file;
+
+/// Directory, relative to the home directory, that the content of migrated
+/// local files goes to.
+pub const LOCAL_DOCS_DIR: &str = ".claude/local-instructions";
+
+/// The file changes that migrate one `CLAUDE.local.md`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LocalMigration {
+    /// The `CLAUDE.local.md` being retired.
+    pub local: PathBuf,
+    /// New document receiving the local file's content.
+    pub doc: PathBuf,
+    /// How `claude` imports `doc`: `~/` and its path under the home
+    /// directory.
+    pub import: String,
+    /// Sibling CLAUDE.md that imports `doc`; may not exist yet.
+    pub claude: PathBuf,
+    /// Current content of `claude` (empty if it does not exist).
+    pub claude_before: String,
+    /// Content of `claude` with the import added.
+    pub claude_after: String,
+}
+
+/// Works out how to migrate `local` into a document under `home` without
+/// touching the disk. Fails if `local` is not a `CLAUDE.local.md` or the
+/// target document already exists.
+pub fn plan_local_migration(local: &Path, home: &Path) -> Result<LocalMigration> {
+    if !is_local_claude_file(local) {
+        bail!("{} is not a CLAUDE.local.md file", local.display());
+    }
+    let Some(dir) = local.parent() else {
+        bail!("{} has no parent directory", local.display());
+    };
+    let name = local_doc_name(dir);
+    let doc = home.join(LOCAL_DOCS_DIR).join(&name);
+    let import = format!("~/{LOCAL_DOCS_DIR}/{name}");
+    if doc.exists() {
+        bail!("{} already exists", doc.display());
+    }
+
+    let claude = dir.join(CLAUDE_FILE_NAME);
+    let claude_before = match fs::read_to_string(&claude) {
+        Ok(content) => content,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => {
+            return Err(err).with_context(|| format!("Failed to read {}", claude.display()));
+        }
+    };
+    let claude_after = add_import(&claude_before, &import);
+
+    Ok(LocalMigration {
+        local: local.to_path_buf(),
+        doc,
+        import,
+        claude,
+        claude_before,
+        claude_after,
+    })
+}
+
+/// File name of the migrated document for a local file in `dir`: its path
+/// with the separators turned into dashes, as Claude Code names the
+/// directories of its projects, so each directory gets its own.
+fn local_doc_name(dir: &Path) -> String {
+    let flat: String = dir
+        .to_string_lossy()
+        .chars()
+        .map(|c| {
+            if matches!(c, '/' | '\\' | ':') {
+                '-'
+            } else {
+                c
+            }
+        })
+        .collect();
+    format!("{flat}.md")
+}
+
+/// Returns `content` with an `@target` import line appended, unless a line
+/// importing `target` is already present.
+pub fn add_import(content: &str, target: &str) -> String {
+    let import = format!("@{target}");
+    if content.lines().any(|line| line.trim() == import) {
+        return content.to_string();
+    }
+    let body = content.trim_end_matches('\n');
+    if body.is_empty() {
+        format!("{import}\n")
+    } else {
+        format!("{body}\n\n{import}\n")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn import_is_appended_after_blank_line() {
+        assert_eq!(
+            add_import("# Project\n", ".claude/x.md"),
+            "# Project\n\n@.claude/x.md\n"
+        );
+    }
+
+    #[test]
+    fn import_into_empty_file() {
+        assert_eq!(add_import("", "x.md"), "@x.md\n");
+    }
+
+    #[test]
+    fn existing_import_is_not_duplicated() {
+        let content = "# P\n\n@x.md\n";
+        assert_eq!(add_import(content, "x.md"), content);
+    }
+
+    #[test]
+    fn plan_moves_content_under_home_and_imports_it() {
+        let tmp = TempDir::new().unwrap();
+        let home = TempDir::new().unwrap();
+        let local = tmp.path().join("CLAUDE.local.md");
+        fs::write(&local, "mine").unwrap();
+        fs::write(tmp.path().join("CLAUDE.md"), "# Shared\n").unwrap();
+
+        let plan = plan_local_migration(&local, home.path()).unwrap();
+
+        let name = local_doc_name(tmp.path());
+        assert!(!name.contains('/'));
+        assert_eq!(
+            plan.doc,
+            home.path().join(".claude/local-instructions").join(&name)
+        );
+        assert_eq!(plan.import, format!("~/.claude/local-instructions/{name}"));
+        assert_eq!(plan.claude, tmp.path().join("CLAUDE.md"));
+        assert_eq!(plan.claude_before, "# Shared\n");
+        assert_eq!(plan.claude_after, format!("# Shared\n\n@{}\n", plan.import));
+    }
+
+    #[test]
+    fn each_directory_gets_its_own_doc() {
+        assert_eq!(local_doc_name(Path::new("/home/u/api")), "-home-u-api.md");
+        assert_ne!(
+            local_doc_name(Path::new("/a/b")),
+            local_doc_name(Path::new("/a/c"))
+        );
+    }
+
+    #[test]
+    fn plan_handles_missing_claude_md() {
+        let tmp = TempDir::new().unwrap();
+        let home = TempDir::new().unwrap();
+        let local = tmp.path().join("CLAUDE.local.md");
+        fs::write(&local, "mine").unwrap();
+
+        let plan = plan_local_migration(&local, home.path()).unwrap();
+
+        assert_eq!(plan.claude_before, "");
+        assert_eq!(plan.claude_after, format!("@{}\n", plan.import));
+    }
+
+    #[test]
+    fn plan_refuses_to_overwrite_existing_doc() {
+        let tmp = TempDir::new().unwrap();
+        let home = TempDir::new().unwrap();
+        let local = tmp.path().join("CLAUDE.local.md");
+        fs::write(&local, "mine").unwrap();
+        let doc = plan_local_migration(&local, home.path()).unwrap().doc;
+        fs::create_dir_all(doc.parent().unwrap()).unwrap();
+        fs::write(&doc, "taken").unwrap();
+
+        assert!(plan_local_migration(&local, home.path()).is_err());
+    }
+
+    #[test]
+    fn plan_rejects_regular_files() {
+        assert!(plan_local_migration(Path::new("/p/CLAUDE.md"), Path::new("/h")).is_err());
+    }
+}
@@ -1,12 +1,33 @@
 use std::fmt;
+use std::fs;
+use std::fs::Metadata;
+use std::io;
+use std::path::Path;
 use std::path::PathBuf;
+use std::time::UNIX_EPOCH;
 
 use clap::Parser;
+use clap::Subcommand;
+
+use crate::backup::format_timestamp;
+use crate::budget::format_bytes;
+use crate::discovery::CLAUDE_FILE_NAME;
+use crate::discovery::ContextFile;
+use crate::discovery::canonical_name_for;
+use crate::discovery::is_local_claude_file;
+use crate::export::ExportFormat;
+use crate::lint::LintFormat;
+use crate::tokens::Tokenizer;
+use crate::tokens::format_tokens;
+use crate::topics::DEFAULT_TOP_TOPICS;
 
 /// A TUI for managing Claude Code context files
 #[derive(Parser, Debug)]
-#[command(version, about)]
+#[command(version, about, args_conflicts_with_subcommands = true)]
 pub struct Cli {
+    #[command(subcommand)]
+    pub command: Option<Command>,
+
     /// Directories to search for CLAUDE.md files
     #[arg(default_value = ".")]
     pub paths: Vec<PathBuf>,
@@ -15,9 +36,288 @@ pub struct Cli {
     #[arg(long)]
     pub list: bool,
 
+    /// With --list, show the size, lines and tokens of each root and a
+    /// summary table
+    #[arg(long, requires = "list")]
+    pub stats: bool,
+
     /// Maximum directory depth to scan [default: 3]
-    #[arg(long)]
+    #[arg(long, visible_alias = "max-depth")]
     pub depth: Option<usize>,
+
+    /// Stop scanning a directory after this many CLAUDE.md files, for very
+    /// large trees
+    #[arg(long, global = true, value_name = "N")]
+    pub max_files: Option<usize>,
+
+    /// Don't descend into nested git repositories under a scanned directory
+    #[arg(long)]
+    pub stop_at_repo_boundaries: bool,
+
+    /// Color theme, overriding the config file
+    #[arg(long, global = true, value_parser = ["dark", "light"])]
+    pub theme: Option<String>,
+
+    /// Don't add ~/.claude/CLAUDE.md as a root of its own
+    #[arg(long, global = true)]
+    pub no_global: bool,
+
+    /// Scan from the top of the git repository holding each path, instead
+    /// of from the path itself
+    #[arg(long, global = true)]
+    pub repo_root: bool,
+
+    /// Skip directories with this name, or this path under the scanned
+    /// directory, on top of the built-in list; repeatable
+    #[arg(long = "exclude", global = true, value_name = "DIR")]
+    pub excludes: Vec<String>,
+
+    /// Scan directories with this name or path even when they would be
+    /// skipped, like `vendor`; repeatable
+    #[arg(long = "include", global = true, value_name = "DIR")]
+    pub includes: Vec<String>,
+
+    /// Also pick up files with this name (`*` matches within a name), on
+    /// top of CLAUDE.md and CLAUDE.local.md; repeatable
+    #[arg(long = "pattern", global = true, value_name = "NAME")]
+    pub patterns: Vec<String>,
+}
+
+/// Subcommands that replace the default scan-and-browse behaviour.
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Open the TUI on the snippet library without scanning any directories,
+    /// or share snippets through a bundle file
+    Library {
+        #[command(subcommand)]
+        command: Option<LibraryCommand>,
+    },
+    /// Work with the snippet library from the shell
+    Snippets {
+        #[command(subcommand)]
+        command: SnippetsCommand,
+    },
+    /// Print the discovered CLAUDE.md files as one Markdown document or as
+    /// JSON, without starting the TUI
+    Export {
+        /// Directories to search for CLAUDE.md files
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ExportFormat::Md)]
+        format: ExportFormat,
+
+        /// Maximum directory depth to scan [default: 3]
+        #[arg(long, visible_alias = "max-depth")]
+        depth: Option<usize>,
+
+        /// Don't descend into nested git repositories under a scanned directory
+        #[arg(long)]
+        stop_at_repo_boundaries: bool,
+    },
+    /// Check the discovered CLAUDE.md files for common problems, exiting
+    /// with status 3 when there are errors
+    Lint {
+        /// Directories to search for CLAUDE.md files
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = LintFormat::Text)]
+        format: LintFormat,
+
+        /// Exit with status 3 on warnings too
+        #[arg(long)]
+        strict: bool,
+
+        /// Maximum directory depth to scan [default: 3]
+        #[arg(long, visible_alias = "max-depth")]
+        depth: Option<usize>,
+
+        /// Don't descend into nested git repositories under a scanned directory
+        #[arg(long)]
+        stop_at_repo_boundaries: bool,
+    },
+    /// Print the topics the discovered CLAUDE.md files mention most, per
+    /// root and overall, as Markdown or JSON
+    Topics {
+        /// Directories to search for CLAUDE.md files
+        #[arg(default_value = ".")]
+        paths: Vec<PathBuf>,
+
+        /// Output format
+        #[arg(long, value_enum, default_value_t = ExportFormat::Md)]
+        format: ExportFormat,
+
+        /// Topics listed for each root and overall
+        #[arg(long, default_value_t = DEFAULT_TOP_TOPICS)]
+        top: usize,
+
+        /// Maximum directory depth to scan [default: 3]
+        #[arg(long, visible_alias = "max-depth")]
+        depth: Option<usize>,
+
+        /// Don't descend into nested git repositories under a scanned directory
+        #[arg(long)]
+        stop_at_repo_boundaries: bool,
+    },
+    /// Print what a Claude Code session started in DIR loads: the CLAUDE.md
+    /// files in load order with their imports, and the effective settings
+    Session {
+        /// Directory the session starts in; it needs no CLAUDE.md of its own
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+
+        /// Write the document to this file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+    /// Print the CLAUDE.md files that apply to DIR, one per line in the
+    /// order Claude Code loads them
+    Which {
+        /// Directory to list the context chain of
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+
+        /// Print a JSON array saying why each file is loaded
+        #[arg(long)]
+        json: bool,
+    },
+    /// Create a CLAUDE.md in DIR from a built-in template or one from the
+    /// configured template source
+    Init {
+        /// Directory to create the CLAUDE.md in
+        #[arg(default_value = ".")]
+        dir: PathBuf,
+
+        /// Template to start from [default: Starter]
+        #[arg(long, short)]
+        template: Option<String>,
+
+        /// List the available templates with their descriptions instead
+        #[arg(long)]
+        list: bool,
+
+        /// Pull the template repository again before using it
+        #[arg(long)]
+        update: bool,
+
+        /// Print the file that would be created instead of creating it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// List the changes jigolo made to files, oldest first
+    Journal {
+        /// Only list changes to this file or to files under this directory
+        path: Option<PathBuf>,
+    },
+    /// Print a Markdown bug report to attach to a GitHub issue
+    Report {
+        /// Write the report to this file instead of stdout
+        #[arg(long, short)]
+        output: Option<PathBuf>,
+    },
+}
+
+/// `jigolo library` subcommands.
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum LibraryCommand {
+    /// Write the library's snippets, except archived ones, to FILE as TOML,
+    /// or as JSON when FILE ends in .json
+    Export {
+        file: PathBuf,
+
+        /// Print the diff of FILE instead of writing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+    /// Add the snippets of a bundle written by `library export`, skipping
+    /// ones the library already has
+    Import {
+        file: PathBuf,
+
+        /// Print the diff of the library file instead of writing it
+        #[arg(long)]
+        dry_run: bool,
+    },
+}
+
+/// `jigolo snippets` subcommands.
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum SnippetsCommand {
+    /// Print the snippets whose title, content or a tag contains TERM (ignoring case)
+    Grep {
+        term: String,
+        /// Print matches as a JSON array
+        #[arg(long)]
+        json: bool,
+    },
+}
+
+/// A CLAUDE.md file found by a scan, with its size, line count and
+/// modification time as the scan saw them.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+pub struct DiscoveredFile {
+    pub path: PathBuf,
+    /// Size in bytes; 0 when the file cannot be read.
+    pub size: u64,
+    /// Number of lines; 0 when the file cannot be read.
+    pub lines: usize,
+    /// Last modification in milliseconds since the Unix epoch, when known.
+    pub modified: Option<u64>,
+}
+
+impl DiscoveredFile {
+    /// A file known only by its path, before its details are read.
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            size: 0,
+            lines: 0,
+            modified: None,
+        }
+    }
+
+    /// Takes the size and modification time of the file at `path` from its
+    /// metadata and reads it to count its lines.
+    pub fn read(path: &Path) -> io::Result<Self> {
+        let metadata = fs::metadata(path)?;
+        let bytes = fs::read(path)?;
+        let lines = bytes.split(|&b| b == b'\n').count()
+            - usize::from(bytes.is_empty() || bytes.ends_with(b"\n"));
+        Ok(Self {
+            path: path.to_path_buf(),
+            size: metadata.len(),
+            lines,
+            modified: modified_millis(&metadata),
+        })
+    }
+
+    /// Whether `metadata` shows the file as the scan saw it, so its details
+    /// need not be read again.
+    pub fn is_current(&self, metadata: &Metadata) -> bool {
+        self.size == metadata.len() && self.modified == modified_millis(metadata)
+    }
+
+    /// `12 lines · 1.3 KB · modified 2024-05-01 09:30:00 UTC`.
+    pub fn details(&self) -> String {
+        let noun = if self.lines == 1 { "line" } else { "lines" };
+        let summary = format!("{} {noun} · {}", self.lines, format_bytes(self.size));
+        match self.modified {
+            Some(millis) => format!("{summary} · modified {}", format_timestamp(millis)),
+            None => summary,
+        }
+    }
+}
+
+/// Last modification of a file in milliseconds since the Unix epoch.
+fn modified_millis(metadata: &Metadata) -> Option<u64> {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map(|since| since.as_millis() as u64)
 }
 
 /// One of the root directories provided by the user, with all CLAUDE.md files found within it.
@@ -25,14 +325,67 @@ pub struct Cli {
 pub struct SourceRoot {
     /// The root directory path (as provided by the user)
     pub path: PathBuf,
-    /// Full paths to all discovered CLAUDE.md files within this root
-    pub files: Vec<PathBuf>,
+    /// All discovered CLAUDE.md files within this root
+    pub files: Vec<DiscoveredFile>,
+    /// Agents, commands and skills found in `.claude` directories within
+    /// this root.
+    pub context_files: Vec<ContextFile>,
+    /// Files that look like a CLAUDE.md under the wrong name, which Claude
+    /// Code ignores.
+    pub misnamed_files: Vec<PathBuf>,
+    /// Whether the scan stopped at `--max-files` with files of this root
+    /// left unwalked.
+    pub truncated: bool,
 }
 
 impl SourceRoot {
     pub fn file_count(&self) -> usize {
         self.files.len()
     }
+
+    /// Whether `path` is one of the discovered files.
+    pub fn has_file(&self, path: &Path) -> bool {
+        self.files.iter().any(|file| file.path == path)
+    }
+
+    /// The discovered file at `path`, if it is one.
+    pub fn file(&self, path: &Path) -> Option<&DiscoveredFile> {
+        self.files.iter().find(|file| file.path == path)
+    }
+
+    /// Paths of the discovered files.
+    pub fn file_paths(&self) -> impl Iterator<Item = &PathBuf> {
+        self.files.iter().map(|file| &file.path)
+    }
+
+    /// Combined size of the files in bytes, as scanned. Files that could
+    /// not be read count as empty.
+    pub fn total_bytes(&self) -> u64 {
+        self.files.iter().map(|file| file.size).sum()
+    }
+
+    /// Combined line count of the files, as scanned. Files that could not
+    /// be read count as empty.
+    pub fn total_lines(&self) -> usize {
+        self.files.iter().map(|file| file.lines).sum()
+    }
+
+    /// Combined token estimate of the files.
+    pub fn total_tokens(&self, tokenizer: &dyn Tokenizer) -> u64 {
+        self.file_paths()
+            .map(|file| tokenizer.count_file(file))
+            .sum()
+    }
+
+    /// Compact summary for the tree: `4 files · 6.1k tok`.
+    pub fn summary(&self, tokenizer: &dyn Tokenizer) -> String {
+        let count = self.file_count();
+        format!(
+            "{count} {} · {}",
+            if count == 1 { "file" } else { "files" },
+            format_tokens(self.total_tokens(tokenizer))
+        )
+    }
 }
 
 impl fmt::Display for SourceRoot {
@@ -40,9 +393,29 @@ impl fmt::Display for SourceRoot {
         let count = self.file_count();
         let label = if count == 1 { "file" } else { "files" };
         writeln!(f, "{} ({} {})", self.path.display(), count, label)?;
-        for file in &self.files {
+        for file in self.file_paths() {
+            let relative = file.strip_prefix(&self.path).unwrap_or(file);
+            if is_local_claude_file(file) {
+                writeln!(f, "  {} [local, deprecated]", relative.display())?;
+            } else {
+                writeln!(f, "  {}", relative.display())?;
+            }
+        }
+        for context in &self.context_files {
+            let relative = context
+                .path
+                .strip_prefix(&self.path)
+                .unwrap_or(&context.path);
+            writeln!(f, "  {} [{}]", relative.display(), context.kind.name())?;
+        }
+        for file in &self.misnamed_files {
             let relative = file.strip_prefix(&self.path).unwrap_or(file);
-            writeln!(f, "  {}", relative.display())?;
+            let canonical = canonical_name_for(file).unwrap_or(CLAUDE_FILE_NAME);
+            writeln!(
+                f,
+                "  {} [misnamed, ignored; rename to {canonical}]",
+                relative.display()
+            )?;
         }
         Ok(())
     }
@@ -53,11 +426,20 @@ impl fmt::Display for SourceRoot {
 pub enum ExitOutcome {
     Success,
     AllPathsFailed,
+    /// A search ran but found nothing, like `grep`.
+    NoMatches,
+    /// Something other than the scanned paths failed, e.g. reading the
+    /// library.
+    Failed,
+    /// A check ran and found problems, like a failing linter. Has an exit
+    /// code of its own so CI can tell it from a run that could not check.
+    ProblemsFound,
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::tokens::ByteHeuristic;
     use clap::CommandFactory;
 
     #[test]
@@ -65,14 +447,103 @@ mod tests {
         Cli::command().debug_assert();
     }
 
+    #[test]
+    fn library_subcommand_parses() {
+        let cli = Cli::try_parse_from(["jigolo", "library"]).unwrap();
+        assert_eq!(cli.command, Some(Command::Library { command: None }));
+    }
+
+    #[test]
+    fn library_import_parses_file() {
+        let cli = Cli::try_parse_from(["jigolo", "library", "import", "team.json"]).unwrap();
+        assert_eq!(
+            cli.command,
+            Some(Command::Library {
+                command: Some(LibraryCommand::Import {
+                    file: PathBuf::from("team.json"),
+                    dry_run: false,
+                }),
+            })
+        );
+    }
+
+    #[test]
+    fn snippets_grep_parses_term_and_json_flag() {
+        let cli = Cli::try_parse_from(["jigolo", "snippets", "grep", "cargo", "--json"]).unwrap();
+        assert_eq!(
+            cli.command,
+            Some(Command::Snippets {
+                command: SnippetsCommand::Grep {
+                    term: "cargo".to_string(),
+                    json: true,
+                },
+            })
+        );
+    }
+
+    #[test]
+    fn export_parses_paths_and_format() {
+        let cli =
+            Cli::try_parse_from(["jigolo", "export", "--format", "json", "/a", "/b"]).unwrap();
+        assert_eq!(
+            cli.command,
+            Some(Command::Export {
+                paths: vec![PathBuf::from("/a"), PathBuf::from("/b")],
+                format: ExportFormat::Json,
+                depth: None,
+                stop_at_repo_boundaries: false,
+            })
+        );
+    }
+
+    #[test]
+    fn topics_parses_top_and_defaults_it() {
+        let cli = Cli::try_parse_from(["jigolo", "topics", "--top", "3", "/a"]).unwrap();
+        assert_eq!(
+            cli.command,
+            Some(Command::Topics {
+                paths: vec![PathBuf::from("/a")],
+                format: ExportFormat::Md,
+                top: 3,
+                depth: None,
+                stop_at_repo_boundaries: false,
+            })
+        );
+        let cli = Cli::try_parse_from(["jigolo", "topics"]).unwrap();
+        assert!(matches!(
+            cli.command,
+            Some(Command::Topics {
+                top: DEFAULT_TOP_TOPICS,
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn theme_flag_is_accepted_after_subcommands() {
+        let cli = Cli::try_parse_from(["jigolo", "library", "--theme", "light"]).unwrap();
+        assert_eq!(cli.theme.as_deref(), Some("light"));
+        assert!(Cli::try_parse_from(["jigolo", "--theme", "solarized"]).is_err());
+    }
+
+    #[test]
+    fn plain_paths_have_no_subcommand() {
+        let cli = Cli::try_parse_from(["jigolo", "./library", "/tmp"]).unwrap();
+        assert!(cli.command.is_none());
+        assert_eq!(cli.paths.len(), 2);
+    }
+
     #[test]
     fn source_root_display_shows_count_and_relative_paths() {
         let root = SourceRoot {
             path: PathBuf::from("/tmp/test"),
             files: vec![
-                PathBuf::from("/tmp/test/CLAUDE.md"),
-                PathBuf::from("/tmp/test/sub/CLAUDE.md"),
+                DiscoveredFile::new(PathBuf::from("/tmp/test/CLAUDE.md")),
+                DiscoveredFile::new(PathBuf::from("/tmp/test/sub/CLAUDE.md")),
             ],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
         };
         let output = format!("{root}");
         assert!(output.contains("2 files"));
@@ -80,11 +551,68 @@ mod tests {
         assert!(output.contains("sub/CLAUDE.md"));
     }
 
+    #[test]
+    fn source_root_display_marks_local_files() {
+        let root = SourceRoot {
+            path: PathBuf::from("/tmp/test"),
+            files: vec![DiscoveredFile::new(PathBuf::from(
+                "/tmp/test/CLAUDE.local.md",
+            ))],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        };
+        let output = format!("{root}");
+        assert!(output.contains("CLAUDE.local.md [local, deprecated]"));
+    }
+
+    #[test]
+    fn summary_counts_files_and_tokens() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        std::fs::write(&file, "x".repeat(4_000)).unwrap();
+        let root = SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![
+                DiscoveredFile::read(&file).unwrap(),
+                DiscoveredFile::new(tmp.path().join("missing/CLAUDE.md")),
+            ],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        };
+
+        assert_eq!(root.summary(&ByteHeuristic), "2 files · 1.0k tok");
+        assert_eq!(root.total_lines(), 1);
+    }
+
+    #[test]
+    fn discovered_files_carry_size_lines_and_modification_time() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let path = tmp.path().join("CLAUDE.md");
+        std::fs::write(&path, "# Rules\n\nBe brief.\n").unwrap();
+
+        let file = DiscoveredFile::read(&path).unwrap();
+
+        assert_eq!(file.path, path);
+        assert_eq!((file.size, file.lines), (19, 3));
+        assert!(file.modified.is_some());
+        assert!(file.is_current(&std::fs::metadata(&path).unwrap()));
+        assert!(file.details().starts_with("3 lines · 19 B · modified 20"));
+
+        assert!(DiscoveredFile::read(&tmp.path().join("gone.md")).is_err());
+        let missing = DiscoveredFile::new(tmp.path().join("gone.md"));
+        assert_eq!(missing.details(), "0 lines · 0 B");
+    }
+
     #[test]
     fn source_root_display_singular_file() {
         let root = SourceRoot {
             path: PathBuf::from("/tmp/test"),
-            files: vec![PathBuf::from("/tmp/test/CLAUDE.md")],
+            files: vec![DiscoveredFile::new(PathBuf::from("/tmp/test/CLAUDE.md"))],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
         };
         let output = format!("{root}");
         assert!(output.contains("1 file)"));
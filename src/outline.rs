@@ -0,0 +1,112 @@
+//! Markdown heading outline of a CLAUDE.md.
+
+use std::ops::Range;
+
+/// One ATX heading (`#` to `######`).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heading {
+    /// Number of `#`s, 1 to 6.
+    pub level: usize,
+    pub title: String,
+    /// 0-based line number.
+    pub line: usize,
+}
+
+/// Returns the headings of `text` in order. Lines inside fenced code blocks
+/// are skipped, so shell comments in examples are not taken for headings.
+pub fn parse_headings(text: &str) -> Vec<Heading> {
+    let mut headings = Vec::new();
+    let mut in_fence = false;
+    for (line, raw) in text.lines().enumerate() {
+        let trimmed = raw.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        let level = trimmed.chars().take_while(|&c| c == '#').count();
+        let rest = &trimmed[level..];
+        if (1..=6).contains(&level) && (rest.is_empty() || rest.starts_with(' ')) {
+            headings.push(Heading {
+                level,
+                title: rest.trim().trim_end_matches('#').trim_end().to_string(),
+                line,
+            });
+        }
+    }
+    headings
+}
+
+/// Index of the heading whose section contains `line`: the last heading at
+/// or above it. `None` when `line` comes before the first heading.
+pub fn section_at(headings: &[Heading], line: usize) -> Option<usize> {
+    headings.iter().rposition(|h| h.line <= line)
+}
+
+/// The lines each heading's section covers, in the order of `headings`: from
+/// the heading up to the next heading of the same or a higher level, so a
+/// section includes its subsections. `line_count` is the length of the text.
+pub fn section_ranges(headings: &[Heading], line_count: usize) -> Vec<Range<usize>> {
+    headings
+        .iter()
+        .enumerate()
+        .map(|(i, heading)| {
+            let end = headings[i + 1..]
+                .iter()
+                .find(|next| next.level <= heading.level)
+                .map_or(line_count, |next| next.line);
+            heading.line..end.max(heading.line)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_atx_headings_with_levels_and_lines() {
+        let text = "intro\n# Project\n\n## Build ##\ntext\n####### too deep\n#hashtag\n### Tests";
+
+        let headings = parse_headings(text);
+
+        let summary: Vec<(usize, &str, usize)> = headings
+            .iter()
+            .map(|h| (h.level, h.title.as_str(), h.line))
+            .collect();
+        assert_eq!(
+            summary,
+            [(1, "Project", 1), (2, "Build", 3), (3, "Tests", 7)]
+        );
+    }
+
+    #[test]
+    fn fenced_code_is_not_outlined() {
+        let text = "# Setup\n```sh\n# install deps\nmake\n```\n## After";
+
+        let titles: Vec<String> = parse_headings(text).into_iter().map(|h| h.title).collect();
+
+        assert_eq!(titles, ["Setup", "After"]);
+    }
+
+    #[test]
+    fn section_is_last_heading_at_or_above_line() {
+        let headings = parse_headings("preamble\n# A\ntext\n## B\ntext");
+
+        assert_eq!(section_at(&headings, 0), None);
+        assert_eq!(section_at(&headings, 1), Some(0));
+        assert_eq!(section_at(&headings, 2), Some(0));
+        assert_eq!(section_at(&headings, 4), Some(1));
+    }
+
+    #[test]
+    fn sections_include_their_subsections() {
+        let headings = parse_headings("# A\ntext\n## B\ntext\n### C\n## D\ntext");
+
+        let ranges = section_ranges(&headings, 7);
+
+        assert_eq!(ranges, [0..7, 2..5, 4..5, 5..7]);
+    }
+}
@@ -0,0 +1,114 @@
+//! Full-text search across discovered CLAUDE.md files.
+
+use std::fs;
+use std::path::PathBuf;
+
+/// One line containing the search term.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchHit {
+    /// 0-based line number.
+    pub line: usize,
+    /// The line's text, with surrounding whitespace trimmed.
+    pub text: String,
+}
+
+/// Every hit in one file, in line order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileHits {
+    pub file: PathBuf,
+    pub hits: Vec<SearchHit>,
+}
+
+/// A file's path and text, read once to be searched many times.
+pub type FileText = (PathBuf, String);
+
+/// Reads the text of each of `files` to search it with [`search_texts`].
+/// Files that cannot be read are left out.
+pub fn read_texts(files: &[PathBuf]) -> Vec<FileText> {
+    files
+        .iter()
+        .filter_map(|file| Some((file.clone(), fs::read_to_string(file).ok()?)))
+        .collect()
+}
+
+/// Searches `texts` for lines containing `term`, ignoring case. Files with
+/// no hits are left out. An empty or all-whitespace term matches nothing.
+pub fn search_texts(texts: &[FileText], term: &str) -> Vec<FileHits> {
+    let term = term.trim().to_lowercase();
+    if term.is_empty() {
+        return Vec::new();
+    }
+    texts
+        .iter()
+        .filter_map(|(file, content)| {
+            let hits: Vec<SearchHit> = content
+                .lines()
+                .enumerate()
+                .filter(|(_, line)| line.to_lowercase().contains(&term))
+                .map(|(line, text)| SearchHit {
+                    line,
+                    text: text.trim().to_string(),
+                })
+                .collect();
+            (!hits.is_empty()).then(|| FileHits {
+                file: file.clone(),
+                hits,
+            })
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    #[test]
+    fn finds_lines_in_each_file_ignoring_case() {
+        let tmp = TempDir::new().unwrap();
+        let a = tmp.path().join("a.md");
+        let b = tmp.path().join("b.md");
+        let c = tmp.path().join("c.md");
+        fs::write(&a, "# A\nRun Cargo test\n  cargo fmt  \n").unwrap();
+        fs::write(&b, "nothing here\n").unwrap();
+        fs::write(&c, "use CARGO\n").unwrap();
+
+        let results = search_texts(&read_texts(&[a.clone(), b, c.clone()]), "cargo");
+
+        assert_eq!(
+            results,
+            [
+                FileHits {
+                    file: a,
+                    hits: vec![
+                        SearchHit {
+                            line: 1,
+                            text: "Run Cargo test".to_string(),
+                        },
+                        SearchHit {
+                            line: 2,
+                            text: "cargo fmt".to_string(),
+                        },
+                    ],
+                },
+                FileHits {
+                    file: c,
+                    hits: vec![SearchHit {
+                        line: 0,
+                        text: "use CARGO".to_string(),
+                    }],
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn blank_term_and_unreadable_files_give_nothing() {
+        let tmp = TempDir::new().unwrap();
+        let a = tmp.path().join("a.md");
+        fs::write(&a, "text\n").unwrap();
+
+        assert!(search_texts(&read_texts(std::slice::from_ref(&a)), "  ").is_empty());
+        assert!(read_texts(&[tmp.path().join("missing.md")]).is_empty());
+    }
+}
@@ -0,0 +1,522 @@
+//! What a Claude Code session started in a directory loads.
+//!
+//! Claude Code reads the user's `~/.claude/CLAUDE.md`, then the
+//! `CLAUDE.md`, `.claude/CLAUDE.md` and `CLAUDE.local.md` of every directory
+//! from the top of the filesystem down to the one it starts in, following
+//! `@path` imports in each up to [`MAX_IMPORT_DEPTH`] hops deep. Settings
+//! come from `~/.claude` and the project's `.claude` directory.
+//! [`simulate_session_in`] gathers all of that for any directory, whether or
+//! not it holds a CLAUDE.md itself, and [`render_session`] writes it out as
+//! one Markdown document.
+
+use std::collections::BTreeSet;
+use std::env;
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::settings::SettingsCollection;
+use crate::settings::discover_settings_files_in;
+use crate::settings::effective_settings;
+use crate::settings::format_effective_settings_with_map;
+use crate::tokens::Tokenizer;
+use crate::tokens::format_tokens;
+
+/// How many imports deep Claude Code follows `@path` references.
+pub const MAX_IMPORT_DEPTH: usize = 5;
+
+/// Memory files read in each directory on the way down, in load order.
+const DIRECTORY_FILES: &[(&str, LoadReason)] = &[
+    ("CLAUDE.md", LoadReason::Project),
+    (".claude/CLAUDE.md", LoadReason::Project),
+    ("CLAUDE.local.md", LoadReason::Local),
+];
+
+/// Why a file is part of the session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LoadReason {
+    /// The user's `~/.claude/CLAUDE.md`.
+    User,
+    /// A `CLAUDE.md` in the start directory or one above it.
+    Project,
+    /// A `CLAUDE.local.md` in the start directory or one above it.
+    Local,
+    /// Pulled in by an `@path` reference in `from`.
+    Import { from: PathBuf },
+}
+
+impl LoadReason {
+    /// Short description for the document: `project memory`,
+    /// `imported by /p/CLAUDE.md`.
+    pub fn describe(&self) -> String {
+        match self {
+            LoadReason::User => "user memory".to_string(),
+            LoadReason::Project => "project memory".to_string(),
+            LoadReason::Local => "local memory".to_string(),
+            LoadReason::Import { from } => format!("imported by `{}`", from.display()),
+        }
+    }
+
+    /// One word for scripts: `user`, `project`, `local` or `import`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            LoadReason::User => "user",
+            LoadReason::Project => "project",
+            LoadReason::Local => "local",
+            LoadReason::Import { .. } => "import",
+        }
+    }
+}
+
+/// One memory file of the session.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoadedFile {
+    pub path: PathBuf,
+    pub reason: LoadReason,
+    pub content: String,
+}
+
+/// An `@path` reference that points at no file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MissingImport {
+    /// The file holding the reference.
+    pub from: PathBuf,
+    /// The reference as written, without the `@`.
+    pub target: String,
+}
+
+/// An `@path` reference of a file, with the path it points at.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Import {
+    /// The reference as written, without the `@`.
+    pub target: String,
+    pub path: PathBuf,
+}
+
+/// Everything a session started in `dir` loads.
+#[derive(Debug, Clone)]
+pub struct SessionContext {
+    pub dir: PathBuf,
+    /// Memory files in the order Claude Code reads them.
+    pub files: Vec<LoadedFile>,
+    pub missing_imports: Vec<MissingImport>,
+    /// Directory whose `.claude` settings files apply.
+    pub project: PathBuf,
+    pub settings: SettingsCollection,
+}
+
+/// Simulates a session in `dir` using the `HOME` environment variable.
+pub fn simulate_session(dir: &Path) -> SessionContext {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    simulate_session_in(home.as_deref(), dir)
+}
+
+/// Simulates a session in `dir` with `home` as the user's home directory.
+pub fn simulate_session_in(home: Option<&Path>, dir: &Path) -> SessionContext {
+    let mut loader = Loader {
+        home,
+        seen: BTreeSet::new(),
+        files: Vec::new(),
+        missing_imports: Vec::new(),
+    };
+    if let Some(home) = home {
+        loader.load(&home.join(".claude").join("CLAUDE.md"), LoadReason::User, 0);
+    }
+    let mut chain: Vec<&Path> = dir.ancestors().collect();
+    chain.reverse();
+    for ancestor in chain {
+        // Claude Code stops below the filesystem root.
+        if ancestor.parent().is_none() {
+            continue;
+        }
+        for (name, reason) in DIRECTORY_FILES {
+            loader.load(&ancestor.join(name), reason.clone(), 0);
+        }
+    }
+
+    let project = settings_project(home, dir);
+    SessionContext {
+        dir: dir.to_path_buf(),
+        files: loader.files,
+        missing_imports: loader.missing_imports,
+        settings: discover_settings_files_in(home, &project),
+        project,
+    }
+}
+
+/// The directory a session starts in to read `file` as one of its own
+/// memory files: the one holding it, or the one above its `.claude`.
+pub fn start_dir_for(file: &Path) -> Option<&Path> {
+    let dir = file.parent()?;
+    if dir.file_name().is_some_and(|name| name == ".claude") {
+        dir.parent()
+    } else {
+        Some(dir)
+    }
+}
+
+/// A memory file as `jigolo which --json` prints it.
+#[derive(Debug, Serialize)]
+struct JsonLoadedFile {
+    path: String,
+    reason: &'static str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    imported_by: Option<String>,
+}
+
+/// Lists the memory files of `session` in load order, one path per line,
+/// or as a JSON array that also says why each is loaded.
+pub fn render_chain(session: &SessionContext, json: bool) -> Result<String> {
+    if !json {
+        return Ok(session
+            .files
+            .iter()
+            .map(|file| format!("{}\n", file.path.display()))
+            .collect());
+    }
+    let entries: Vec<JsonLoadedFile> = session
+        .files
+        .iter()
+        .map(|file| JsonLoadedFile {
+            path: file.path.display().to_string(),
+            reason: file.reason.kind(),
+            imported_by: match &file.reason {
+                LoadReason::Import { from } => Some(from.display().to_string()),
+                _ => None,
+            },
+        })
+        .collect();
+    let mut out =
+        serde_json::to_string_pretty(&entries).context("failed to encode the context chain")?;
+    out.push('\n');
+    Ok(out)
+}
+
+/// Renders `session` as a Markdown document: the files in load order with
+/// their token cost, missing imports, the effective settings and then the
+/// text of every file.
+pub fn render_session(session: &SessionContext, tokenizer: &dyn Tokenizer) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# Session started in {}\n", session.dir.display());
+
+    let total: u64 = session
+        .files
+        .iter()
+        .map(|file| tokenizer.count(&file.content))
+        .sum();
+    if session.files.is_empty() {
+        out.push_str("No memory files are loaded here.\n");
+    } else {
+        let _ = writeln!(
+            out,
+            "Claude Code loads {} memory {}, in this order ({} in total):\n",
+            session.files.len(),
+            if session.files.len() == 1 {
+                "file"
+            } else {
+                "files"
+            },
+            format_tokens(total)
+        );
+        for (i, file) in session.files.iter().enumerate() {
+            let _ = writeln!(
+                out,
+                "{}. `{}` — {} · {}",
+                i + 1,
+                file.path.display(),
+                file.reason.describe(),
+                format_tokens(tokenizer.count(&file.content))
+            );
+        }
+    }
+    if !session.missing_imports.is_empty() {
+        out.push_str("\nImports that point at no file, which Claude Code skips:\n\n");
+        for missing in &session.missing_imports {
+            let _ = writeln!(
+                out,
+                "- `@{}` in `{}`",
+                missing.target,
+                missing.from.display()
+            );
+        }
+    }
+
+    out.push_str("\n## Settings\n\n");
+    if session.settings.files.is_empty() {
+        out.push_str("No settings files apply.\n");
+    } else {
+        for file in &session.settings.files {
+            let _ = writeln!(out, "- {}: `{}`", file.label, file.path.display());
+        }
+        let (lines, _) = format_effective_settings_with_map(&effective_settings(&session.settings));
+        let _ = writeln!(out, "\n```text\n{}\n```", lines.join("\n"));
+    }
+
+    for (i, file) in session.files.iter().enumerate() {
+        let _ = writeln!(out, "\n## {}. {}\n", i + 1, file.path.display());
+        out.push_str(&file.content);
+        if !file.content.ends_with('\n') {
+            out.push('\n');
+        }
+    }
+    out
+}
+
+/// Reads memory files and follows their imports.
+#[derive(Debug)]
+struct Loader<'a> {
+    home: Option<&'a Path>,
+    seen: BTreeSet<PathBuf>,
+    files: Vec<LoadedFile>,
+    missing_imports: Vec<MissingImport>,
+}
+
+impl Loader<'_> {
+    /// Adds `path` when it is a readable file not loaded before, then the
+    /// files it imports, `depth` being the number of imports that led here.
+    fn load(&mut self, path: &Path, reason: LoadReason, depth: usize) {
+        if self.seen.contains(path) || !path.is_file() {
+            return;
+        }
+        let Ok(content) = fs::read_to_string(path) else {
+            return;
+        };
+        self.seen.insert(path.to_path_buf());
+        let targets = import_targets(&content);
+        self.files.push(LoadedFile {
+            path: path.to_path_buf(),
+            reason,
+            content,
+        });
+        if depth >= MAX_IMPORT_DEPTH {
+            return;
+        }
+        let base = path.parent().unwrap_or(Path::new(""));
+        for target in targets {
+            let resolved = resolve_import(self.home, base, &target);
+            if resolved.is_file() {
+                let from = path.to_path_buf();
+                self.load(&resolved, LoadReason::Import { from }, depth + 1);
+            } else {
+                self.missing_imports.push(MissingImport {
+                    from: path.to_path_buf(),
+                    target,
+                });
+            }
+        }
+    }
+}
+
+/// `target` as a path: under `home` for `~/`, as is when absolute, and
+/// relative to `base`, the importing file's directory, otherwise.
+pub fn resolve_import(home: Option<&Path>, base: &Path, target: &str) -> PathBuf {
+    let joined = match (target.strip_prefix("~/"), home) {
+        (Some(rest), Some(home)) => home.join(rest),
+        _ => base.join(target),
+    };
+    normalize(&joined)
+}
+
+/// The `@path` references in `text`, the content of the file at `path`,
+/// each with the path it resolves to. Whether that path exists is left to
+/// the caller.
+pub fn file_imports(home: Option<&Path>, path: &Path, text: &str) -> Vec<Import> {
+    let base = path.parent().unwrap_or(Path::new(""));
+    import_targets(text)
+        .into_iter()
+        .map(|target| Import {
+            path: resolve_import(home, base, &target),
+            target,
+        })
+        .collect()
+}
+
+/// The `@path` references in `text`, in order. Only words starting with
+/// `@` count, so e-mail addresses do not; references in code blocks and
+/// code spans are examples and skipped. Trailing punctuation is dropped.
+pub fn import_targets(text: &str) -> Vec<String> {
+    import_references(text)
+        .into_iter()
+        .map(|(_, target)| target)
+        .collect()
+}
+
+/// Like [`import_targets`], with the 0-based line each reference is on.
+pub fn import_references(text: &str) -> Vec<(usize, String)> {
+    let mut targets = Vec::new();
+    let mut in_fence = false;
+    for (index, line) in text.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if in_fence {
+            continue;
+        }
+        // Odd pieces between backticks are code spans.
+        for prose in line.split('`').step_by(2) {
+            for word in prose.split_whitespace() {
+                let Some(target) = word.strip_prefix('@') else {
+                    continue;
+                };
+                let target = target.trim_end_matches(['.', ',', ';', ':', '!', '?', ')']);
+                if !target.is_empty() {
+                    targets.push((index, target.to_string()));
+                }
+            }
+        }
+    }
+    targets
+}
+
+/// The directory whose `.claude/settings.json` and `settings.local.json`
+/// apply in `dir`: the nearest one at or above it that has either, leaving
+/// out the home directory, whose `.claude` holds the user's own settings.
+/// `dir` itself when there is none.
+fn settings_project(home: Option<&Path>, dir: &Path) -> PathBuf {
+    dir.ancestors()
+        .filter(|ancestor| Some(*ancestor) != home)
+        .find(|ancestor| {
+            let claude = ancestor.join(".claude");
+            claude.join("settings.json").is_file() || claude.join("settings.local.json").is_file()
+        })
+        .unwrap_or(dir)
+        .to_path_buf()
+}
+
+/// `path` with `.` and `..` components worked out, without touching the
+/// filesystem.
+fn normalize(path: &Path) -> PathBuf {
+    let mut out = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !out.pop() {
+                    out.push(component);
+                }
+            }
+            other => out.push(other),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::tokens::ByteHeuristic;
+
+    fn write(path: &Path, text: &str) {
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, text).unwrap();
+    }
+
+    #[test]
+    fn imports_skip_code_and_addresses() {
+        let text = "See @docs/style.md, and @~/shared.md.\nMail me@x.io\n`@not/this`\n```\n@nor/this\n```\n";
+        assert_eq!(import_targets(text), ["docs/style.md", "~/shared.md"]);
+    }
+
+    #[test]
+    fn file_imports_resolve_against_the_file_and_home() {
+        let imports = file_imports(
+            Some(Path::new("/home/me")),
+            Path::new("/p/docs/CLAUDE.md"),
+            "@../rules.md @~/shared.md @/etc/team.md\n",
+        );
+
+        let paths: Vec<&Path> = imports.iter().map(|i| i.path.as_path()).collect();
+        assert_eq!(
+            paths,
+            [
+                Path::new("/p/rules.md"),
+                Path::new("/home/me/shared.md"),
+                Path::new("/etc/team.md"),
+            ]
+        );
+        assert_eq!(imports[1].target, "~/shared.md");
+    }
+
+    #[test]
+    fn session_loads_chain_imports_and_settings() {
+        let tmp = TempDir::new().unwrap();
+        let home = tmp.path().join("home");
+        let project = tmp.path().join("project");
+        let dir = project.join("src/deep");
+        fs::create_dir_all(&dir).unwrap();
+        write(&home.join(".claude/CLAUDE.md"), "user rules\n");
+        write(&project.join("CLAUDE.md"), "project @docs/a.md @gone.md\n");
+        write(&project.join("docs/a.md"), "a imports @../CLAUDE.md\n");
+        write(&project.join("src/CLAUDE.local.md"), "local\n");
+        write(&project.join("other/CLAUDE.md"), "not on the chain\n");
+        write(
+            &project.join(".claude/settings.json"),
+            r#"{"model": "opus"}"#,
+        );
+
+        let session = simulate_session_in(Some(&home), &dir);
+
+        let loaded: Vec<(&Path, &LoadReason)> = session
+            .files
+            .iter()
+            .map(|file| (file.path.as_path(), &file.reason))
+            .collect();
+        assert_eq!(
+            loaded,
+            [
+                (home.join(".claude/CLAUDE.md").as_path(), &LoadReason::User),
+                (project.join("CLAUDE.md").as_path(), &LoadReason::Project),
+                (
+                    project.join("docs/a.md").as_path(),
+                    &LoadReason::Import {
+                        from: project.join("CLAUDE.md")
+                    }
+                ),
+                (
+                    project.join("src/CLAUDE.local.md").as_path(),
+                    &LoadReason::Local
+                ),
+            ]
+        );
+        assert_eq!(
+            session.missing_imports,
+            [MissingImport {
+                from: project.join("CLAUDE.md"),
+                target: "gone.md".to_string(),
+            }]
+        );
+        assert_eq!(session.project, project);
+
+        let doc = render_session(&session, &ByteHeuristic);
+        assert!(doc.starts_with(&format!("# Session started in {}\n", dir.display())));
+        assert!(doc.contains("Claude Code loads 4 memory files"));
+        assert!(doc.contains("- `@gone.md` in `"));
+        assert!(doc.contains("  Model: opus"));
+        assert!(doc.contains("\n## 3. "));
+    }
+
+    #[test]
+    fn import_chains_stop_at_the_depth_limit() {
+        let tmp = TempDir::new().unwrap();
+        let dir = tmp.path().join("p");
+        write(&dir.join("CLAUDE.md"), "@1.md\n");
+        for i in 1..=MAX_IMPORT_DEPTH + 1 {
+            write(&dir.join(format!("{i}.md")), &format!("@{}.md\n", i + 1));
+        }
+
+        let session = simulate_session_in(None, &dir);
+
+        assert_eq!(session.files.len(), MAX_IMPORT_DEPTH + 1);
+        assert!(session.missing_imports.is_empty());
+    }
+}
@@ -1,3 +1,4 @@
+use std::collections::BTreeMap;
 use std::fs;
 use std::path::Path;
 use std::path::PathBuf;
@@ -114,16 +115,7 @@ pub fn format_settings_with_map(collection: &SettingsCollection) -> (Vec<String>
         };
 
         let before = lines.len();
-        for &key in ORDERED_SETTINGS_KEYS {
-            if let Some(val) = obj.get(key) {
-                format_key_value(key, val, &mut lines);
-            }
-        }
-        for (key, val) in obj {
-            if !ORDERED_SETTINGS_KEYS.contains(&key.as_str()) {
-                format_key_value(key, val, &mut lines);
-            }
-        }
+        format_object(obj, &mut lines, None);
         let added = lines.len() - before;
         for _ in 0..added {
             line_map.push(Some(i));
@@ -133,6 +125,37 @@ pub fn format_settings_with_map(collection: &SettingsCollection) -> (Vec<String>
     (lines, line_map)
 }
 
+/// Formats the effective settings as a single section, each resolved line
+/// ending in the label of the file it came from.
+pub fn format_effective_settings_with_map(
+    effective: &EffectiveSettings,
+) -> (Vec<String>, SettingsLineMap) {
+    let mut lines = vec!["▾ Effective (later files override earlier ones)".to_string()];
+    if let Some(obj) = effective.value.as_object() {
+        format_object(obj, &mut lines, Some(effective));
+    }
+    let line_map = vec![Some(0); lines.len()];
+    (lines, line_map)
+}
+
+/// Formats the keys of one settings object, known keys first.
+fn format_object(
+    obj: &serde_json::Map<String, serde_json::Value>,
+    lines: &mut Vec<String>,
+    effective: Option<&EffectiveSettings>,
+) {
+    for &key in ORDERED_SETTINGS_KEYS {
+        if let Some(val) = obj.get(key) {
+            format_key_value(key, val, lines, effective);
+        }
+    }
+    for (key, val) in obj {
+        if !ORDERED_SETTINGS_KEYS.contains(&key.as_str()) {
+            format_key_value(key, val, lines, effective);
+        }
+    }
+}
+
 /// Format settings collection into display lines for the TUI.
 pub fn format_settings(collection: &SettingsCollection) -> Vec<String> {
     let (lines, _) = format_settings_with_map(collection);
@@ -172,6 +195,135 @@ pub fn merge_settings(collection: &SettingsCollection) -> serde_json::Value {
     serde_json::Value::Object(result)
 }
 
+/// Which settings file each part of the effective settings came from, keyed
+/// by its path: `["model"]`, `["env", "LOG"]`, `["permissions", "allow",
+/// "Bash"]` or `["hooks", "preCommit", "0"]`.
+pub type Origins = BTreeMap<Vec<String>, String>;
+
+/// The merged settings and where each resolved key came from.
+#[derive(Debug, Clone, Default)]
+pub struct EffectiveSettings {
+    /// The result of [`merge_settings`].
+    pub value: serde_json::Value,
+    /// File labels by key path.
+    pub origins: Origins,
+}
+
+impl EffectiveSettings {
+    /// Label of the file `path` resolved from. Top-level keys that several
+    /// files add to list every contributing file, in precedence order.
+    pub fn origin(&self, path: &[&str]) -> Option<&str> {
+        let key: Vec<String> = path.iter().map(|part| part.to_string()).collect();
+        self.origins.get(&key).map(String::as_str)
+    }
+}
+
+/// Merges the files as [`merge_settings`] does, following Claude Code's
+/// precedence (Global, then Project, then Project Local, each overriding the
+/// one before), and records which file every resolved key came from.
+pub fn effective_settings(collection: &SettingsCollection) -> EffectiveSettings {
+    let mut origins = Origins::new();
+    let mut contributors: BTreeMap<String, Vec<String>> = BTreeMap::new();
+    let mut hook_counts: BTreeMap<String, usize> = BTreeMap::new();
+
+    for file in &collection.files {
+        let Some(obj) = file.value.as_object() else {
+            continue;
+        };
+        let label = file.label.as_str();
+
+        for (key, val) in obj {
+            let adds = match (key.as_str(), val) {
+                ("permissions", serde_json::Value::Object(perms)) => {
+                    for (category, items) in perms {
+                        match items.as_array() {
+                            Some(items) => {
+                                for item in items {
+                                    let item = display_scalar(item);
+                                    let path = ["permissions", category.as_str(), item.as_str()];
+                                    record_origin(&mut origins, &path, label, false);
+                                }
+                            }
+                            None => {
+                                let path = ["permissions", category.as_str()];
+                                record_origin(&mut origins, &path, label, true);
+                            }
+                        }
+                    }
+                    true
+                }
+                ("hooks", serde_json::Value::Object(events)) => {
+                    for (event, hooks) in events {
+                        match hooks.as_array() {
+                            Some(hooks) => {
+                                let count = hook_counts.entry(event.clone()).or_insert(0);
+                                for _ in hooks {
+                                    let index = count.to_string();
+                                    let path = ["hooks", event.as_str(), index.as_str()];
+                                    record_origin(&mut origins, &path, label, true);
+                                    *count += 1;
+                                }
+                            }
+                            None => {
+                                record_origin(&mut origins, &["hooks", event], label, true);
+                            }
+                        }
+                    }
+                    true
+                }
+                ("permissions" | "hooks", _) => continue,
+                ("plugins", serde_json::Value::Array(items)) => {
+                    for item in items {
+                        let item = display_scalar(item);
+                        record_origin(&mut origins, &["plugins", &item], label, false);
+                    }
+                    true
+                }
+                ("plugins", _) => false,
+                (_, serde_json::Value::Object(entries)) => {
+                    for sub_key in entries.keys() {
+                        record_origin(&mut origins, &[key, sub_key], label, true);
+                    }
+                    true
+                }
+                _ => false,
+            };
+            let labels = contributors.entry(key.clone()).or_default();
+            if !adds {
+                labels.clear();
+            }
+            if !labels.iter().any(|l| l == label) {
+                labels.push(label.to_string());
+            }
+        }
+    }
+
+    for (key, labels) in contributors {
+        origins.insert(vec![key], labels.join(", "));
+    }
+    EffectiveSettings {
+        value: merge_settings(collection),
+        origins,
+    }
+}
+
+/// Notes that `path` came from `label`. Without `replace`, the first file
+/// to set the path keeps it, matching how lists are deduplicated.
+fn record_origin(origins: &mut Origins, path: &[&str], label: &str, replace: bool) {
+    let key: Vec<String> = path.iter().map(|part| part.to_string()).collect();
+    if replace || !origins.contains_key(&key) {
+        origins.insert(key, label.to_string());
+    }
+}
+
+/// The `  ← Label` suffix for `path`, or nothing outside the effective view.
+fn origin_suffix(effective: Option<&EffectiveSettings>, path: &[&str]) -> String {
+    effective
+        .and_then(|effective| effective.origin(path))
+        .map(|label| format!("  ← {label}"))
+        .unwrap_or_default()
+}
+
 fn merge_permissions(
     result: &mut serde_json::Map<String, serde_json::Value>,
     val: &serde_json::Value,
@@ -256,36 +408,88 @@ fn merge_object(
     }
 }
 
-fn format_key_value(key: &str, val: &serde_json::Value, lines: &mut Vec<String>) {
+fn format_key_value(
+    key: &str,
+    val: &serde_json::Value,
+    lines: &mut Vec<String>,
+    effective: Option<&EffectiveSettings>,
+) {
+    let origin = origin_suffix(effective, &[key]);
     match key {
         "model" => {
-            lines.push(format!("  Model: {}", display_scalar(val)));
+            lines.push(format!("  Model: {}{origin}", display_scalar(val)));
         }
         "defaultMode" => {
-            lines.push(format!("  Default Mode: {}", display_scalar(val)));
+            lines.push(format!("  Default Mode: {}{origin}", display_scalar(val)));
         }
         "thinking" => {
-            lines.push(format!("  Thinking: {}", display_scalar(val)));
+            lines.push(format!("  Thinking: {}{origin}", display_scalar(val)));
         }
         "permissions" => {
-            format_permissions(val, lines);
+            format_permissions(val, lines, effective);
         }
         "mcpServers" => {
-            format_mcp_servers(val, lines);
+            format_mcp_servers(val, lines, effective);
         }
         "hooks" => {
-            format_hooks(val, lines);
+            format_hooks(val, lines, effective);
         }
         "plugins" => {
-            format_plugins(val, lines);
+            format_plugins(val, lines, effective);
         }
         "env" => {
-            format_env(val, lines);
+            format_env(val, lines, effective);
         }
         _ => {
-            lines.push(format!("  {key}: {}", format_inline(val)));
+            lines.push(format!("  {key}: {}{origin}", format_inline(val)));
+        }
+    }
+}
+
+/// The JSON keys a display line from [`format_settings_with_map`] stands
+/// for: `"  ▾ Permissions (allow):"` gives `permissions` and `allow`,
+/// `"    LOG=debug"` gives `LOG`. Section headers give nothing.
+pub fn display_line_keys(line: &str) -> Vec<String> {
+    let text = line.trim_start();
+    if line.len() == text.len() {
+        return Vec::new();
+    }
+    let text = text.trim_start_matches(['▾', '▸']).trim();
+    let text = text.split("  ← ").next().unwrap_or(text);
+    let label = text.split([':', '=']).next().unwrap_or(text).trim();
+    if let Some(category) = label
+        .strip_prefix("Permissions (")
+        .and_then(|rest| rest.strip_suffix(')'))
+    {
+        return vec!["permissions".to_string(), category.to_string()];
+    }
+    let key = match label {
+        "Model" => "model",
+        "Default Mode" => "defaultMode",
+        "Thinking" => "thinking",
+        "Permissions" => "permissions",
+        "MCP Servers" => "mcpServers",
+        "Hooks" => "hooks",
+        "Plugins" => "plugins",
+        "Env" => "env",
+        other => other,
+    };
+    vec![key.to_string()]
+}
+
+/// The 1-based line of `text` where `keys` appear in order, each searched
+/// for as the start of a JSON string after the one before. Keys that cannot
+/// be found are skipped, so the result is the closest match found.
+pub fn find_json_line(text: &str, keys: &[String]) -> Option<usize> {
+    let mut found = None;
+    let mut from = 0;
+    for key in keys {
+        if let Some(offset) = text[from..].find(&format!("\"{key}")) {
+            from += offset;
+            found = Some(from);
         }
     }
+    found.map(|at| text[..at].matches('\n').count() + 1)
 }
 
 fn display_scalar(val: &serde_json::Value) -> String {
@@ -312,11 +516,16 @@ fn format_inline(val: &serde_json::Value) -> String {
     }
 }
 
-fn format_permissions(val: &serde_json::Value, lines: &mut Vec<String>) {
+fn format_permissions(
+    val: &serde_json::Value,
+    lines: &mut Vec<String>,
+    effective: Option<&EffectiveSettings>,
+) {
     let obj = match val.as_object() {
         Some(o) => o,
         None => {
-            lines.push(format!("  Permissions: {}", format_inline(val)));
+            let origin = origin_suffix(effective, &["permissions"]);
+            lines.push(format!("  Permissions: {}{origin}", format_inline(val)));
             return;
         }
     };
@@ -330,7 +539,9 @@ fn format_permissions(val: &serde_json::Value, lines: &mut Vec<String>) {
             }
             lines.push(format!("  ▾ Permissions ({category}):"));
             for item in arr {
-                lines.push(format!("    {}", display_scalar(item)));
+                let item = display_scalar(item);
+                let origin = origin_suffix(effective, &["permissions", category, &item]);
+                lines.push(format!("    {item}{origin}"));
             }
         }
     }
@@ -338,22 +549,32 @@ fn format_permissions(val: &serde_json::Value, lines: &mut Vec<String>) {
     // Other permission keys
     for (key, val) in obj {
         if !["allow", "ask", "deny"].contains(&key.as_str()) {
-            lines.push(format!("  Permissions ({key}): {}", format_inline(val)));
+            let origin = origin_suffix(effective, &["permissions", key]);
+            lines.push(format!(
+                "  Permissions ({key}): {}{origin}",
+                format_inline(val)
+            ));
         }
     }
 }
 
-fn format_mcp_servers(val: &serde_json::Value, lines: &mut Vec<String>) {
+fn format_mcp_servers(
+    val: &serde_json::Value,
+    lines: &mut Vec<String>,
+    effective: Option<&EffectiveSettings>,
+) {
     let obj = match val.as_object() {
         Some(o) => o,
         None => {
-            lines.push(format!("  MCP Servers: {}", format_inline(val)));
+            let origin = origin_suffix(effective, &["mcpServers"]);
+            lines.push(format!("  MCP Servers: {}{origin}", format_inline(val)));
             return;
         }
     };
 
     lines.push("  ▾ MCP Servers:".to_string());
     for (name, config) in obj {
+        let origin = origin_suffix(effective, &["mcpServers", name]);
         if let Some(cmd) = config.get("command") {
             let args = config
                 .get("args")
@@ -361,21 +582,29 @@ fn format_mcp_servers(val: &serde_json::Value, lines: &mut Vec<String>) {
                 .map(|arr| arr.iter().map(display_scalar).collect::<Vec<_>>().join(" "))
                 .unwrap_or_default();
             if args.is_empty() {
-                lines.push(format!("    {name}: {}", display_scalar(cmd)));
+                lines.push(format!("    {name}: {}{origin}", display_scalar(cmd)));
             } else {
-                lines.push(format!("    {name}: {} {args}", display_scalar(cmd)));
+                lines.push(format!(
+                    "    {name}: {} {args}{origin}",
+                    display_scalar(cmd)
+                ));
             }
         } else {
-            lines.push(format!("    {name}: {}", format_inline(config)));
+            lines.push(format!("    {name}: {}{origin}", format_inline(config)));
         }
     }
 }
 
-fn format_hooks(val: &serde_json::Value, lines: &mut Vec<String>) {
+fn format_hooks(
+    val: &serde_json::Value,
+    lines: &mut Vec<String>,
+    effective: Option<&EffectiveSettings>,
+) {
     let obj = match val.as_object() {
         Some(o) => o,
         None => {
-            lines.push(format!("  Hooks: {}", format_inline(val)));
+            let origin = origin_suffix(effective, &["hooks"]);
+            lines.push(format!("  Hooks: {}{origin}", format_inline(val)));
             return;
         }
     };
@@ -383,46 +612,64 @@ fn format_hooks(val: &serde_json::Value, lines: &mut Vec<String>) {
     lines.push("  ▾ Hooks:".to_string());
     for (event, hook_config) in obj {
         if let Some(arr) = hook_config.as_array() {
-            for hook in arr {
+            for (index, hook) in arr.iter().enumerate() {
                 let cmd = hook
                     .get("command")
                     .map(display_scalar)
                     .unwrap_or_else(|| format_inline(hook));
-                lines.push(format!("    {event}: {cmd}"));
+                let origin = origin_suffix(effective, &["hooks", event, &index.to_string()]);
+                lines.push(format!("    {event}: {cmd}{origin}"));
             }
         } else {
-            lines.push(format!("    {event}: {}", format_inline(hook_config)));
+            let origin = origin_suffix(effective, &["hooks", event]);
+            lines.push(format!(
+                "    {event}: {}{origin}",
+                format_inline(hook_config)
+            ));
         }
     }
 }
 
-fn format_plugins(val: &serde_json::Value, lines: &mut Vec<String>) {
+fn format_plugins(
+    val: &serde_json::Value,
+    lines: &mut Vec<String>,
+    effective: Option<&EffectiveSettings>,
+) {
     let arr = match val.as_array() {
         Some(a) => a,
         None => {
-            lines.push(format!("  Plugins: {}", format_inline(val)));
+            let origin = origin_suffix(effective, &["plugins"]);
+            lines.push(format!("  Plugins: {}{origin}", format_inline(val)));
             return;
         }
     };
 
     lines.push("  ▾ Plugins:".to_string());
     for plugin in arr {
-        lines.push(format!("    {}", display_scalar(plugin)));
+        let plugin = display_scalar(plugin);
+        let origin = origin_suffix(effective, &["plugins", &plugin]);
+        lines.push(format!("    {plugin}{origin}"));
     }
 }
 
-fn format_env(val: &serde_json::Value, lines: &mut Vec<String>) {
+fn format_env(
+    val: &serde_json::Value,
+    lines: &mut Vec<String>,
+    effective: Option<&EffectiveSettings>,
+) {
     let obj = match val.as_object() {
         Some(o) => o,
         None => {
-            lines.push(format!("  Env: {}", format_inline(val)));
+            let origin = origin_suffix(effective, &["env"]);
+            lines.push(format!("  Env: {}{origin}", format_inline(val)));
             return;
         }
     };
 
     lines.push("  ▾ Env:".to_string());
     for (key, val) in obj {
-        lines.push(format!("    {key}={}", display_scalar(val)));
+        let origin = origin_suffix(effective, &["env", key]);
+        lines.push(format!("    {key}={}{origin}", display_scalar(val)));
     }
 }
 
@@ -791,4 +1038,96 @@ mod tests {
         let merged = merge_settings(&collection);
         assert_eq!(merged.get("model").unwrap().as_str().unwrap(), "opus");
     }
+
+    #[test]
+    fn effective_settings_records_where_keys_came_from() {
+        let collection = two_file_collection(
+            r#"{"model":"haiku","defaultMode":"plan","env":{"LOG":"info"},"permissions":{"allow":["Read"]}}"#,
+            r#"{"model":"opus","env":{"LOG":"debug","CI":"1"},"permissions":{"allow":["Read","Bash"]}}"#,
+        );
+        let effective = effective_settings(&collection);
+
+        assert_eq!(effective.value, merge_settings(&collection));
+        assert_eq!(effective.origin(&["model"]), Some("Project"));
+        assert_eq!(effective.origin(&["defaultMode"]), Some("Global"));
+        assert_eq!(effective.origin(&["env", "LOG"]), Some("Project"));
+        assert_eq!(effective.origin(&["env"]), Some("Global, Project"));
+        assert_eq!(
+            effective.origin(&["permissions", "allow", "Read"]),
+            Some("Global")
+        );
+        assert_eq!(
+            effective.origin(&["permissions", "allow", "Bash"]),
+            Some("Project")
+        );
+    }
+
+    #[test]
+    fn effective_settings_numbers_concatenated_hooks() {
+        let collection = two_file_collection(
+            r#"{"hooks":{"preCommit":[{"command":"fmt"}]}}"#,
+            r#"{"hooks":{"preCommit":[{"command":"lint"}]}}"#,
+        );
+        let effective = effective_settings(&collection);
+
+        assert_eq!(
+            effective.origin(&["hooks", "preCommit", "0"]),
+            Some("Global")
+        );
+        assert_eq!(
+            effective.origin(&["hooks", "preCommit", "1"]),
+            Some("Project")
+        );
+    }
+
+    #[test]
+    fn effective_format_annotates_each_line() {
+        let collection = two_file_collection(
+            r#"{"model":"haiku","plugins":["a"]}"#,
+            r#"{"model":"opus","plugins":["b"]}"#,
+        );
+        let (lines, line_map) =
+            format_effective_settings_with_map(&effective_settings(&collection));
+
+        assert!(lines[0].starts_with("▾ Effective"));
+        assert!(lines.contains(&"  Model: opus  ← Project".to_string()));
+        assert!(lines.contains(&"    a  ← Global".to_string()));
+        assert!(lines.contains(&"    b  ← Project".to_string()));
+        assert_eq!(line_map.len(), lines.len());
+    }
+
+    #[test]
+    fn per_file_format_has_no_origins() {
+        let collection = two_file_collection(r#"{"model":"haiku"}"#, r#"{"model":"opus"}"#);
+        let lines = format_settings(&collection);
+        assert!(lines.iter().all(|line| !line.contains('←')));
+    }
+
+    #[test]
+    fn display_lines_map_back_to_json_keys() {
+        assert!(display_line_keys("▾ Project (/p/.claude/settings.json)").is_empty());
+        assert_eq!(display_line_keys("  Model: opus"), ["model"]);
+        assert_eq!(
+            display_line_keys("  ▾ Permissions (allow):"),
+            ["permissions", "allow"]
+        );
+        assert_eq!(display_line_keys("    LOG=debug  ← Global"), ["LOG"]);
+        assert_eq!(display_line_keys("    preCommit: make lint"), ["preCommit"]);
+    }
+
+    #[test]
+    fn json_line_follows_keys_in_order() {
+        let text = "{\n  \"env\": {\"model\": \"x\"},\n  \"permissions\": {\n    \"allow\": [\n      \"Bash\"\n    ]\n  }\n}\n";
+        let keys = |keys: &[&str]| keys.iter().map(|k| k.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            find_json_line(text, &keys(&["permissions", "allow", "Bash"])),
+            Some(5)
+        );
+        assert_eq!(
+            find_json_line(text, &keys(&["permissions", "missing"])),
+            Some(3)
+        );
+        assert_eq!(find_json_line(text, &keys(&["missing"])), None);
+    }
 }
@@ -0,0 +1,88 @@
+//! Where the TUI was left, kept between runs.
+//!
+//! On exit the TUI writes `~/.config/jigolo/state.toml`: the selected file,
+//! the tree nodes that were open, the focused pane, where the content pane
+//! was scrolled to, and the recently viewed files. The next run restores
+//! whatever of it still fits the tree it scans.
+
+use std::fs;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::path::PathBuf;
+
+use anyhow::Context;
+use anyhow::Result;
+use serde::Deserialize;
+use serde::Serialize;
+
+use crate::fsutil::write_atomic;
+
+/// The saved state of the TUI.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UiState {
+    /// The file selected in the tree.
+    pub selected: Option<PathBuf>,
+    /// Identifiers of every open tree node.
+    pub open: Vec<Vec<String>>,
+    /// The focused pane: `files`, `content` or `outline`.
+    pub pane: String,
+    /// Line of the content cursor, 0-based.
+    pub cursor: usize,
+    /// First line shown in the content pane.
+    pub scroll: u16,
+    /// Files most recently shown in the content pane, newest first.
+    pub recent_files: Vec<PathBuf>,
+}
+
+/// The state file inside jigolo's config directory.
+pub fn state_path_in(config_dir: &Path) -> PathBuf {
+    config_dir.join("state.toml")
+}
+
+/// Reads the state at `path`. A missing file is the default state.
+pub fn load_state(path: &Path) -> Result<UiState> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == ErrorKind::NotFound => return Ok(UiState::default()),
+        Err(err) => return Err(err).with_context(|| format!("failed to read {}", path.display())),
+    };
+    toml::from_str(&text).with_context(|| format!("failed to parse {}", path.display()))
+}
+
+/// Writes `state` to `path`, creating its directory.
+pub fn save_state(path: &Path, state: &UiState) -> Result<()> {
+    if let Some(dir) = path.parent() {
+        fs::create_dir_all(dir).with_context(|| format!("failed to create {}", dir.display()))?;
+    }
+    let text = toml::to_string(state).context("failed to encode the UI state")?;
+    write_atomic(path, &text).with_context(|| format!("failed to write {}", path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn state_survives_a_round_trip_and_starts_out_empty() {
+        let tmp = TempDir::new().unwrap();
+        let path = state_path_in(&tmp.path().join("config"));
+        assert_eq!(load_state(&path).unwrap(), UiState::default());
+
+        let state = UiState {
+            selected: Some(PathBuf::from("/p/CLAUDE.md")),
+            open: vec![vec!["/p".to_string()]],
+            pane: "content".to_string(),
+            cursor: 12,
+            scroll: 4,
+            recent_files: vec![PathBuf::from("/p/CLAUDE.md")],
+        };
+        save_state(&path, &state).unwrap();
+        assert_eq!(load_state(&path).unwrap(), state);
+
+        fs::write(&path, "cursor = \"twelve\"").unwrap();
+        assert!(load_state(&path).is_err());
+    }
+}
@@ -0,0 +1,224 @@
+//! Built-in starting points for new CLAUDE.md files.
+//!
+//! Templates are offered alongside library snippets when creating a new
+//! file from the TUI, so the first buffer is never completely empty unless
+//! the user asks for it. `jigolo init` also offers the templates of a team
+//! template source: a directory or a git repository of Markdown files,
+//! which is cloned into jigolo's config directory on first use.
+
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use anyhow::Context;
+use anyhow::Result;
+use anyhow::bail;
+
+use crate::config::config_dir;
+use crate::fsutil::encode_path;
+use crate::fsutil::expand_home;
+
+/// A named, built-in CLAUDE.md template.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Template {
+    /// Short name shown in the picker.
+    pub name: &'static str,
+    /// One-line description shown next to the name.
+    pub description: &'static str,
+    /// Initial buffer content.
+    pub content: &'static str,
+}
+
+/// Templates offered when creating a new CLAUDE.md, in display order.
+pub const BUILTIN_TEMPLATES: &[Template] = &[
+    Template {
+        name: "Blank",
+        description: "Empty file",
+        content: "",
+    },
+    Template {
+        name: "Starter",
+        description: "Overview, commands, conventions and boundaries",
+        content: "# Project\n\n\
+                  One-paragraph overview of what this project does.\n\n\
+                  ## Commands\n\n\
+                  ```bash\n\
+                  # build, test and lint commands\n\
+                  ```\n\n\
+                  ## Conventions\n\n\
+                  - \n\n\
+                  ## Boundaries\n\n\
+                  **NEVER:**\n\
+                  - \n",
+    },
+    Template {
+        name: "Subdirectory",
+        description: "Scoped notes for a single package or directory",
+        content: "# Scope\n\n\
+                  What lives in this directory and how it relates to the rest of the repo.\n\n\
+                  ## Rules\n\n\
+                  - \n",
+    },
+];
+
+/// Returns the built-in template with the given name (case-insensitive).
+pub fn find_template(name: &str) -> Option<&'static Template> {
+    BUILTIN_TEMPLATES
+        .iter()
+        .find(|t| t.name.eq_ignore_ascii_case(name))
+}
+
+/// A template read from the team template source.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TeamTemplate {
+    /// The file name without `.md`.
+    pub name: String,
+    /// One-line description, from the comment on the file's first line.
+    pub description: String,
+    /// Initial file content.
+    pub content: String,
+}
+
+/// The templates of `source`, the config's `template_source`: a directory,
+/// or the URL of a git repository kept checked out under
+/// `~/.config/jigolo/template-cache`. The checkout is cloned on first use
+/// and pulled again when `update` is set.
+pub fn team_templates(source: &str, update: bool) -> Result<Vec<TeamTemplate>> {
+    let dir = expand_home(source).context("cannot expand ~: HOME not set")?;
+    if dir.is_dir() {
+        return templates_in(&dir);
+    }
+    let cache = config_dir()
+        .context("cannot find the template cache: HOME not set")?
+        .join("template-cache");
+    let checkout = checkout_dir(&cache, source);
+    if !checkout.is_dir() {
+        fs::create_dir_all(&cache)
+            .with_context(|| format!("failed to create {}", cache.display()))?;
+        let target = checkout.to_string_lossy().into_owned();
+        git(&["clone", "--quiet", "--depth", "1", source, &target])?;
+    } else if update {
+        let target = checkout.to_string_lossy().into_owned();
+        git(&["-C", &target, "pull", "--quiet", "--ff-only"])?;
+    }
+    templates_in(&checkout)
+}
+
+/// Where the checkout of the template repository `source` is kept.
+fn checkout_dir(cache: &Path, source: &str) -> PathBuf {
+    cache.join(encode_path(Path::new(source)))
+}
+
+/// Runs git with `args`, failing with its error output when it fails.
+fn git(args: &[&str]) -> Result<()> {
+    let output = Command::new("git")
+        .args(args)
+        .output()
+        .context("failed to run git")?;
+    if !output.status.success() {
+        bail!(
+            "git {} failed: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// The templates in `dir`: one per `.md` file other than a README, named
+/// after the file and sorted by name. A first line that is an HTML comment,
+/// `<!-- description -->`, is the template's description and is left out
+/// of its content.
+pub fn templates_in(dir: &Path) -> Result<Vec<TeamTemplate>> {
+    let entries = fs::read_dir(dir).with_context(|| format!("failed to read {}", dir.display()))?;
+    let mut templates = Vec::new();
+    for entry in entries {
+        let path = entry
+            .with_context(|| format!("failed to read {}", dir.display()))?
+            .path();
+        let Some(name) = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| name.strip_suffix(".md"))
+        else {
+            continue;
+        };
+        if name.eq_ignore_ascii_case("README") || !path.is_file() {
+            continue;
+        }
+        let text = fs::read_to_string(&path)
+            .with_context(|| format!("failed to read {}", path.display()))?;
+        let (description, content) = match text.split_once('\n') {
+            Some((first, rest)) if first.trim().starts_with("<!--") => (
+                first
+                    .trim()
+                    .trim_start_matches("<!--")
+                    .trim_end_matches("-->")
+                    .trim()
+                    .to_string(),
+                rest.trim_start_matches('\n').to_string(),
+            ),
+            _ => (String::new(), text),
+        };
+        templates.push(TeamTemplate {
+            name: name.to_string(),
+            description,
+            content,
+        });
+    }
+    templates.sort_unstable_by(|a, b| a.name.cmp(&b.name));
+    Ok(templates)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn first_template_is_blank() {
+        assert_eq!(BUILTIN_TEMPLATES[0].name, "Blank");
+        assert!(BUILTIN_TEMPLATES[0].content.is_empty());
+    }
+
+    #[test]
+    fn template_names_are_unique() {
+        let mut names: Vec<&str> = BUILTIN_TEMPLATES.iter().map(|t| t.name).collect();
+        names.sort_unstable();
+        names.dedup();
+        assert_eq!(names.len(), BUILTIN_TEMPLATES.len());
+    }
+
+    #[test]
+    fn find_template_is_case_insensitive() {
+        let template = find_template("starter").unwrap();
+        assert_eq!(template.name, "Starter");
+        assert!(template.content.starts_with("# Project"));
+    }
+
+    #[test]
+    fn find_template_returns_none_for_unknown() {
+        assert!(find_template("nope").is_none());
+    }
+
+    #[test]
+    fn team_templates_are_the_markdown_files_of_the_source_directory() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        fs::write(
+            tmp.path().join("service.md"),
+            "<!-- Rust service with CI -->\n\n# Service\n",
+        )
+        .unwrap();
+        fs::write(tmp.path().join("library.md"), "# Library\n").unwrap();
+        fs::write(tmp.path().join("README.md"), "How to add templates").unwrap();
+        fs::write(tmp.path().join("notes.txt"), "not a template").unwrap();
+
+        let templates = team_templates(&tmp.path().display().to_string(), false).unwrap();
+
+        let names: Vec<&str> = templates.iter().map(|t| t.name.as_str()).collect();
+        assert_eq!(names, ["library", "service"]);
+        assert_eq!(templates[1].description, "Rust service with CI");
+        assert_eq!(templates[1].content, "# Service\n");
+        assert_eq!(templates[0].description, "");
+    }
+}
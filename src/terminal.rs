@@ -0,0 +1,413 @@
+//! What the terminal we run in can display and understand.
+//!
+//! Modern terminals, Windows Terminal included, handle Unicode box drawing
+//! and OSC 52 clipboard sequences. The legacy Windows console (conhost)
+//! renders box drawing with raster fonts as garbage and prints OSC 52 as
+//! text, so there the TUI falls back to ASCII borders and leaves the
+//! clipboard to `clip`.
+//!
+//! Colours are treated the same way: styles are drawn as the theme and the
+//! markdown renderer ask for, then mapped down to the palette the terminal
+//! has, and italics are dropped where they would show up as reverse video
+//! or not at all.
+
+use std::env;
+
+use ratatui::buffer::Buffer;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+
+/// The basic ANSI colours, in palette order.
+const ANSI_COLORS: [Color; 16] = [
+    Color::Black,
+    Color::Red,
+    Color::Green,
+    Color::Yellow,
+    Color::Blue,
+    Color::Magenta,
+    Color::Cyan,
+    Color::Gray,
+    Color::DarkGray,
+    Color::LightRed,
+    Color::LightGreen,
+    Color::LightYellow,
+    Color::LightBlue,
+    Color::LightMagenta,
+    Color::LightCyan,
+    Color::White,
+];
+
+/// How many colours the terminal can show.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum ColorDepth {
+    /// The 16 basic ANSI colours.
+    Ansi16,
+    /// The xterm 256-colour palette.
+    Ansi256,
+    /// 24-bit RGB.
+    TrueColor,
+}
+
+/// Terminal features detected at startup.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Unicode borders, tree arrows and symbols render correctly.
+    pub unicode: bool,
+    /// OSC 52 clipboard sequences are understood rather than printed.
+    pub osc52: bool,
+    /// Colours beyond this depth are mapped to the nearest one it has.
+    pub colors: ColorDepth,
+    /// Italic text renders as italic.
+    pub italic: bool,
+}
+
+impl Default for Capabilities {
+    fn default() -> Self {
+        Self {
+            unicode: true,
+            osc52: true,
+            colors: ColorDepth::TrueColor,
+            italic: true,
+        }
+    }
+}
+
+impl Capabilities {
+    /// Detects the capabilities of the current terminal from the
+    /// environment.
+    pub fn detect() -> Self {
+        Self::from_env(cfg!(windows), |name| env::var(name).ok())
+    }
+
+    /// Detects capabilities from environment variables read through `var`.
+    /// `windows` selects the Windows rules, where a console that identifies
+    /// as nothing in particular is taken to be the legacy one.
+    pub fn from_env(windows: bool, var: impl Fn(&str) -> Option<String>) -> Self {
+        let set = |name: &str| var(name).is_some_and(|value| !value.is_empty());
+        let term = var("TERM").unwrap_or_default();
+
+        let legacy_console = windows
+            && !set("WT_SESSION")
+            && !set("TERM_PROGRAM")
+            && var("ConEmuANSI").as_deref() != Some("ON")
+            && term.is_empty();
+        let dumb = term == "dumb";
+        let forced_ascii =
+            var("JIGOLO_ASCII").is_some_and(|value| value != "0" && !value.is_empty());
+
+        let colorterm = var("COLORTERM").unwrap_or_default();
+        let program = var("TERM_PROGRAM").unwrap_or_default();
+        let colors = if matches!(colorterm.as_str(), "truecolor" | "24bit")
+            || set("WT_SESSION")
+            || matches!(program.as_str(), "iTerm.app" | "WezTerm" | "vscode")
+            || term.ends_with("-direct")
+        {
+            ColorDepth::TrueColor
+        } else if term.contains("256color") || program == "Apple_Terminal" {
+            ColorDepth::Ansi256
+        } else {
+            ColorDepth::Ansi16
+        };
+        // The Linux console and plain screen show italics as reverse video
+        let italic = !legacy_console && !dumb && term != "linux" && term != "screen";
+
+        Self {
+            unicode: !legacy_console && !dumb && !forced_ascii,
+            osc52: !legacy_console && !dumb && term != "linux",
+            colors,
+            italic,
+        }
+    }
+
+    /// Whether drawing needs any of the fallbacks in [`degrade`].
+    pub fn is_limited(&self) -> bool {
+        !self.unicode || !self.italic || self.colors < ColorDepth::TrueColor
+    }
+}
+
+/// Maps `color` to the nearest colour `depth` can show. Named colours and
+/// the terminal default are kept, since every terminal has them.
+pub fn degrade_color(color: Color, depth: ColorDepth) -> Color {
+    match (color, depth) {
+        (_, ColorDepth::TrueColor) => color,
+        (Color::Rgb(r, g, b), ColorDepth::Ansi256) => Color::Indexed(nearest_indexed(r, g, b)),
+        (Color::Rgb(r, g, b), ColorDepth::Ansi16) => nearest_ansi(r, g, b),
+        (Color::Indexed(index), ColorDepth::Ansi16) => match ANSI_COLORS.get(usize::from(index)) {
+            Some(named) => *named,
+            None => {
+                let (r, g, b) = indexed_rgb(index);
+                nearest_ansi(r, g, b)
+            }
+        },
+        _ => color,
+    }
+}
+
+/// Applies the colour, italic and ASCII fallbacks `caps` calls for to every
+/// cell of `buf`.
+pub fn degrade(buf: &mut Buffer, caps: Capabilities) {
+    if !caps.is_limited() {
+        return;
+    }
+    for cell in buf.content.iter_mut() {
+        cell.fg = degrade_color(cell.fg, caps.colors);
+        cell.bg = degrade_color(cell.bg, caps.colors);
+        if !caps.italic {
+            cell.modifier.remove(Modifier::ITALIC);
+        }
+        if !caps.unicode
+            && let Some(ascii) = ascii_fallback(cell.symbol())
+        {
+            cell.set_symbol(ascii);
+        }
+    }
+}
+
+/// The xterm 256-colour index closest to an RGB colour, from the 6×6×6
+/// cube or the grey ramp.
+fn nearest_indexed(r: u8, g: u8, b: u8) -> u8 {
+    let step = |n: u8| -> u8 {
+        match n {
+            0..=47 => 0,
+            48..=114 => 1,
+            _ => (n - 35) / 40,
+        }
+    };
+    let cube = 16 + 36 * step(r) + 6 * step(g) + step(b);
+    let average = (u16::from(r) + u16::from(g) + u16::from(b)) / 3;
+    let gray = if average > 238 {
+        255
+    } else {
+        232 + (average.saturating_sub(3) / 10) as u8
+    };
+    [cube, gray]
+        .into_iter()
+        .min_by_key(|index| distance((r, g, b), indexed_rgb(*index)))
+        .unwrap_or(cube)
+}
+
+/// The basic ANSI colour closest to an RGB colour.
+fn nearest_ansi(r: u8, g: u8, b: u8) -> Color {
+    ANSI_COLORS
+        .into_iter()
+        .min_by_key(|color| distance((r, g, b), color_rgb(*color).unwrap_or((0, 0, 0))))
+        .unwrap_or(Color::Reset)
+}
+
+/// Squared distance between two RGB colours.
+fn distance(a: (u8, u8, u8), b: (u8, u8, u8)) -> u32 {
+    let d = |x: u8, y: u8| (i32::from(x) - i32::from(y)).unsigned_abs().pow(2);
+    d(a.0, b.0) + d(a.1, b.1) + d(a.2, b.2)
+}
+
+/// RGB of `color`, or `None` for the terminal's default.
+pub fn color_rgb(color: Color) -> Option<(u8, u8, u8)> {
+    let rgb = match color {
+        Color::Reset => return None,
+        Color::Black => (0x00, 0x00, 0x00),
+        Color::Red => (0xcd, 0x31, 0x31),
+        Color::Green => (0x0d, 0xbc, 0x79),
+        Color::Yellow => (0xe5, 0xe5, 0x10),
+        Color::Blue => (0x24, 0x72, 0xc8),
+        Color::Magenta => (0xbc, 0x3f, 0xbc),
+        Color::Cyan => (0x11, 0xa8, 0xcd),
+        Color::Gray => (0xcc, 0xcc, 0xcc),
+        Color::DarkGray => (0x66, 0x66, 0x66),
+        Color::LightRed => (0xf1, 0x4c, 0x4c),
+        Color::LightGreen => (0x23, 0xd1, 0x8b),
+        Color::LightYellow => (0xf5, 0xf5, 0x43),
+        Color::LightBlue => (0x3b, 0x8e, 0xea),
+        Color::LightMagenta => (0xd6, 0x70, 0xd6),
+        Color::LightCyan => (0x29, 0xb8, 0xdb),
+        Color::White => (0xff, 0xff, 0xff),
+        Color::Rgb(r, g, b) => (r, g, b),
+        Color::Indexed(index) => indexed_rgb(index),
+    };
+    Some(rgb)
+}
+
+/// RGB of an entry in the xterm 256-colour palette.
+pub fn indexed_rgb(index: u8) -> (u8, u8, u8) {
+    let level = |n: u8| if n == 0 { 0 } else { 55 + n * 40 };
+    match index {
+        0..=15 => color_rgb(ANSI_COLORS[usize::from(index)]).unwrap_or((0, 0, 0)),
+        16..=231 => {
+            let n = index - 16;
+            (level(n / 36), level((n / 6) % 6), level(n % 6))
+        }
+        232..=255 => {
+            let gray = 8 + (index - 232) * 10;
+            (gray, gray, gray)
+        }
+    }
+}
+
+/// The ASCII stand-in for a Unicode symbol that legacy consoles cannot
+/// draw, or `None` to keep `symbol` as it is. Every stand-in is as wide as
+/// the symbol it replaces.
+pub fn ascii_fallback(symbol: &str) -> Option<&'static str> {
+    let mut chars = symbol.chars();
+    let (Some(c), None) = (chars.next(), chars.next()) else {
+        return None;
+    };
+    let ascii = match c {
+        '─' | '━' | '═' | '╌' | '┄' => "-",
+        '│' | '┃' | '║' | '╎' | '┆' => "|",
+        '\u{2500}'..='\u{257f}' => "+",
+        '\u{2580}'..='\u{259f}' => "#",
+        '▶' | '▸' | '→' => ">",
+        '◀' | '←' => "<",
+        '▼' | '▾' | '↓' => "v",
+        '▲' | '↑' => "^",
+        '…' => ".",
+        '·' | '•' => "*",
+        '–' | '—' => "-",
+        '⚠' => "!",
+        '✓' | '✔' => "x",
+        '✗' | '✘' => "X",
+        _ => return None,
+    };
+    Some(ascii)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use ratatui::layout::Rect;
+    use ratatui::style::Style;
+    use ratatui::widgets::Block;
+    use ratatui::widgets::Borders;
+    use ratatui::widgets::Widget;
+
+    use super::*;
+
+    fn detect(windows: bool, vars: &[(&str, &str)]) -> Capabilities {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        Capabilities::from_env(windows, |name| vars.get(name).cloned())
+    }
+
+    #[test]
+    fn legacy_windows_console_gets_ascii_and_no_osc52() {
+        let caps = detect(true, &[]);
+        assert!(!caps.unicode);
+        assert!(!caps.osc52);
+        assert!(!caps.italic);
+        assert_eq!(caps.colors, ColorDepth::Ansi16);
+
+        assert_eq!(
+            detect(true, &[("WT_SESSION", "abc")]),
+            Capabilities::default()
+        );
+        for vars in [[("ConEmuANSI", "ON")], [("TERM", "xterm-256color")]] {
+            let caps = detect(true, &vars);
+            assert!(caps.unicode && caps.osc52);
+        }
+    }
+
+    #[test]
+    fn unix_terminals_keep_unicode_unless_told_otherwise() {
+        assert!(detect(false, &[]).unicode);
+        assert!(!detect(false, &[("TERM", "linux")]).osc52);
+        assert!(detect(false, &[("TERM", "linux")]).unicode);
+        assert!(!detect(false, &[("JIGOLO_ASCII", "1")]).unicode);
+        assert!(detect(false, &[("JIGOLO_ASCII", "0")]).unicode);
+        assert_eq!(
+            detect(false, &[("TERM", "dumb")]),
+            Capabilities {
+                unicode: false,
+                osc52: false,
+                colors: ColorDepth::Ansi16,
+                italic: false,
+            }
+        );
+    }
+
+    #[test]
+    fn color_depth_comes_from_colorterm_and_term() {
+        let depth = |vars: &[(&str, &str)]| detect(false, vars).colors;
+        assert_eq!(depth(&[("COLORTERM", "truecolor")]), ColorDepth::TrueColor);
+        assert_eq!(
+            depth(&[("TERM_PROGRAM", "iTerm.app")]),
+            ColorDepth::TrueColor
+        );
+        assert_eq!(depth(&[("TERM", "xterm-256color")]), ColorDepth::Ansi256);
+        assert_eq!(depth(&[("TERM", "xterm")]), ColorDepth::Ansi16);
+        assert!(!detect(false, &[("TERM", "screen")]).italic);
+        assert!(detect(false, &[("TERM", "screen-256color")]).italic);
+    }
+
+    #[test]
+    fn colors_map_to_the_nearest_in_the_palette() {
+        let orange = Color::Rgb(255, 135, 0);
+        assert_eq!(degrade_color(orange, ColorDepth::TrueColor), orange);
+        assert_eq!(
+            degrade_color(orange, ColorDepth::Ansi256),
+            Color::Indexed(208)
+        );
+        assert_eq!(
+            degrade_color(Color::Rgb(20, 20, 20), ColorDepth::Ansi256),
+            Color::Indexed(233)
+        );
+        assert_eq!(
+            degrade_color(Color::Rgb(240, 80, 80), ColorDepth::Ansi16),
+            Color::LightRed
+        );
+        assert_eq!(
+            degrade_color(Color::Indexed(2), ColorDepth::Ansi16),
+            Color::Green
+        );
+        assert_eq!(
+            degrade_color(Color::Indexed(21), ColorDepth::Ansi16),
+            Color::Blue
+        );
+        assert_eq!(degrade_color(Color::Cyan, ColorDepth::Ansi16), Color::Cyan);
+    }
+
+    #[test]
+    fn indexed_colors_follow_xterm_palette() {
+        assert_eq!(indexed_rgb(1), (0xcd, 0x31, 0x31));
+        assert_eq!(indexed_rgb(196), (255, 0, 0));
+        assert_eq!(indexed_rgb(232), (8, 8, 8));
+    }
+
+    #[test]
+    fn italics_are_dropped_where_unsupported() {
+        let area = Rect::new(0, 0, 2, 1);
+        let mut buf = Buffer::empty(area);
+        buf.set_style(area, Style::default().add_modifier(Modifier::ITALIC));
+        let caps = Capabilities {
+            italic: false,
+            ..Capabilities::default()
+        };
+
+        degrade(&mut buf, caps);
+
+        assert!(!buf[(0, 0)].modifier.contains(Modifier::ITALIC));
+    }
+
+    #[test]
+    fn ascii_fallback_draws_borders_with_plain_characters() {
+        let area = Rect::new(0, 0, 4, 3);
+        let mut buf = Buffer::empty(area);
+        Block::default()
+            .borders(Borders::ALL)
+            .render(area, &mut buf);
+        let caps = Capabilities {
+            unicode: false,
+            ..Capabilities::default()
+        };
+
+        degrade(&mut buf, caps);
+
+        let rows: Vec<String> = (0..3)
+            .map(|y| (0..4).map(|x| buf[(x, y)].symbol()).collect())
+            .collect();
+        assert_eq!(rows, ["+--+", "|  |", "+--+"]);
+        assert_eq!(ascii_fallback("a"), None);
+        assert_eq!(ascii_fallback("▶"), Some(">"));
+    }
+}
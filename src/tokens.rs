@@ -0,0 +1,150 @@
+//! Token estimation.
+//!
+//! Context files are paid for in tokens, but an exact count needs the
+//! model's tokenizer. Estimates go through the [`Tokenizer`] trait so the
+//! backend can be swapped in config (`tokenizer = "words"`) without touching
+//! the places that show counts. Both built-in backends are heuristics that
+//! need no data files; an exact backend only has to implement the trait.
+//!
+//! Exact backends (tiktoken's `cl100k`/`o200k`, Anthropic's counts) are not
+//! here yet: they need new dependencies, which are waiting on sign-off.
+//! They would go behind an optional cargo feature so the default binary
+//! stays small.
+
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+use serde::Deserialize;
+use serde::Serialize;
+
+/// Counts the tokens a piece of text costs.
+pub trait Tokenizer: fmt::Debug {
+    /// Estimated token count of `text`.
+    fn count(&self, text: &str) -> u64;
+
+    /// Estimated token count of the file at `path`. Files that cannot be
+    /// read count as empty.
+    fn count_file(&self, path: &Path) -> u64 {
+        fs::read_to_string(path).map_or(0, |text| self.count(&text))
+    }
+}
+
+/// About four bytes per token. Fast and needs only the file size, which is
+/// close enough to compare files with each other.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ByteHeuristic;
+
+impl Tokenizer for ByteHeuristic {
+    fn count(&self, text: &str) -> u64 {
+        estimate_tokens(text.len() as u64)
+    }
+
+    fn count_file(&self, path: &Path) -> u64 {
+        fs::metadata(path).map_or(0, |meta| estimate_tokens(meta.len()))
+    }
+}
+
+/// Mimics how BPE tokenizers split text: a word is one token per four
+/// characters (at least one), and each punctuation mark or symbol is a token
+/// of its own. Closer than [`ByteHeuristic`] for symbol-heavy files such as
+/// command lists and code blocks, at the cost of reading every file.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct WordHeuristic;
+
+impl Tokenizer for WordHeuristic {
+    fn count(&self, text: &str) -> u64 {
+        let mut tokens = 0;
+        let mut word_len: u64 = 0;
+        for c in text.chars() {
+            if c.is_alphanumeric() {
+                word_len += 1;
+                continue;
+            }
+            tokens += word_len.div_ceil(4);
+            word_len = 0;
+            if !c.is_whitespace() {
+                tokens += 1;
+            }
+        }
+        tokens + word_len.div_ceil(4)
+    }
+}
+
+/// The tokenizer backends that can be picked in config. Only heuristics so
+/// far; an exact backend gets a variant here, behind its cargo feature.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TokenizerKind {
+    /// [`ByteHeuristic`].
+    #[default]
+    Bytes,
+    /// [`WordHeuristic`].
+    Words,
+}
+
+impl TokenizerKind {
+    /// The backend for this kind.
+    pub fn tokenizer(self) -> &'static dyn Tokenizer {
+        match self {
+            TokenizerKind::Bytes => &ByteHeuristic,
+            TokenizerKind::Words => &WordHeuristic,
+        }
+    }
+}
+
+/// Rough token count of `bytes` of Markdown: about four bytes per token.
+pub fn estimate_tokens(bytes: u64) -> u64 {
+    bytes.div_ceil(4)
+}
+
+/// Formats a token count compactly: `850 tok`, `6.1k tok`, `1.2M tok`.
+pub fn format_tokens(tokens: u64) -> String {
+    match tokens {
+        0..1_000 => format!("{tokens} tok"),
+        1_000..1_000_000 => format!("{:.1}k tok", tokens as f64 / 1_000.0),
+        _ => format!("{:.1}M tok", tokens as f64 / 1_000_000.0),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn token_counts_are_compact() {
+        assert_eq!(estimate_tokens(10), 3);
+        assert_eq!(format_tokens(850), "850 tok");
+        assert_eq!(format_tokens(6_140), "6.1k tok");
+        assert_eq!(format_tokens(1_250_000), "1.2M tok");
+    }
+
+    #[test]
+    fn byte_heuristic_counts_text_and_files_alike() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "x".repeat(10)).unwrap();
+
+        assert_eq!(ByteHeuristic.count(&"x".repeat(10)), 3);
+        assert_eq!(ByteHeuristic.count_file(&file), 3);
+        assert_eq!(ByteHeuristic.count_file(&tmp.path().join("missing")), 0);
+    }
+
+    #[test]
+    fn word_heuristic_splits_words_and_symbols() {
+        assert_eq!(WordHeuristic.count("Use cargo"), 3);
+        assert_eq!(WordHeuristic.count("- `cargo test`"), 6);
+        assert_eq!(WordHeuristic.count(""), 0);
+    }
+
+    #[test]
+    fn kind_parses_from_config_names() {
+        #[derive(Debug, Deserialize)]
+        struct Wrapper {
+            tokenizer: TokenizerKind,
+        }
+        let parsed: Wrapper = toml::from_str("tokenizer = \"words\"").unwrap();
+        assert_eq!(parsed.tokenizer, TokenizerKind::Words);
+        assert_eq!(TokenizerKind::Words.tokenizer().count("a b"), 2);
+    }
+}
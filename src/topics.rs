@@ -0,0 +1,265 @@
+//! `jigolo topics`: the words the discovered CLAUDE.md files keep coming
+//! back to, per root and over all of them.
+//!
+//! Each file is split into lowercase words, leaving out code blocks, short
+//! words and common English ones. A topic counts the files that mention it
+//! and how often it is mentioned in all, so "testing" in 18 files ranks
+//! above a word repeated 30 times in one.
+
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+
+use anyhow::Context;
+use anyhow::Result;
+use serde::Serialize;
+
+use crate::export::ExportFormat;
+use crate::export::ExportedFile;
+
+/// Topics listed per root and overall when `--top` is not given.
+pub const DEFAULT_TOP_TOPICS: usize = 10;
+
+/// Shortest word counted as a topic.
+const MIN_TOPIC_LEN: usize = 3;
+
+/// Words too common to say anything about the guidance.
+const STOP_WORDS: &[&str] = &[
+    "about", "after", "all", "also", "always", "and", "any", "are", "because", "been", "before",
+    "being", "but", "can", "could", "did", "does", "doing", "don", "each", "else", "etc", "for",
+    "from", "had", "has", "have", "here", "how", "into", "its", "just", "like", "make", "may",
+    "more", "most", "must", "need", "never", "new", "not", "now", "only", "other", "our", "out",
+    "over", "should", "some", "such", "than", "that", "the", "their", "them", "then", "there",
+    "these", "they", "this", "those", "through", "too", "under", "use", "used", "using", "very",
+    "want", "was", "way", "were", "what", "when", "where", "which", "while", "who", "why", "will",
+    "with", "without", "would", "you", "your",
+];
+
+/// One topic and how much it comes up.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Topic {
+    pub topic: String,
+    /// Files mentioning it at least once.
+    pub files: usize,
+    /// Times it is mentioned in all of them.
+    pub mentions: usize,
+}
+
+/// The top topics of a set of files.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TopicGroup {
+    /// The scanned directory, or `None` for all roots together.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub root: Option<String>,
+    /// Files the topics were counted over.
+    pub files: usize,
+    pub topics: Vec<Topic>,
+}
+
+/// The topic report: all roots together, then each root.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TopicReport {
+    pub overall: TopicGroup,
+    pub roots: Vec<TopicGroup>,
+}
+
+/// The topic words of `text`, in order, leaving out fenced code blocks.
+pub fn topic_words(text: &str) -> Vec<String> {
+    let mut words = Vec::new();
+    let mut in_code = false;
+    for line in text.lines() {
+        if line.trim_start().starts_with("```") {
+            in_code = !in_code;
+            continue;
+        }
+        if in_code {
+            continue;
+        }
+        for word in line.split(|c: char| !c.is_alphanumeric() && c != '-') {
+            let word = word.trim_matches('-').to_lowercase();
+            if word.chars().count() >= MIN_TOPIC_LEN
+                && word.chars().any(char::is_alphabetic)
+                && !STOP_WORDS.contains(&word.as_str())
+            {
+                words.push(word);
+            }
+        }
+    }
+    words
+}
+
+/// The `top` topics of `texts`, most files first, then most mentions, then
+/// by name.
+fn top_topics<'a>(texts: impl Iterator<Item = &'a str>, top: usize) -> (usize, Vec<Topic>) {
+    let mut counts: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    let mut file_count = 0;
+    for text in texts {
+        file_count += 1;
+        let words = topic_words(text);
+        let distinct: BTreeSet<&String> = words.iter().collect();
+        for word in &distinct {
+            counts.entry((*word).clone()).or_default().0 += 1;
+        }
+        for word in &words {
+            if let Some(count) = counts.get_mut(word) {
+                count.1 += 1;
+            }
+        }
+    }
+    let mut topics: Vec<Topic> = counts
+        .into_iter()
+        .map(|(topic, (files, mentions))| Topic {
+            topic,
+            files,
+            mentions,
+        })
+        .collect();
+    topics.sort_by(|a, b| {
+        b.files
+            .cmp(&a.files)
+            .then(b.mentions.cmp(&a.mentions))
+            .then_with(|| a.topic.cmp(&b.topic))
+    });
+    topics.truncate(top);
+    (file_count, topics)
+}
+
+/// Counts the `top` topics of `files`, overall and for each root in the
+/// order the roots first appear.
+pub fn topic_report(files: &[ExportedFile], top: usize) -> TopicReport {
+    let (count, topics) = top_topics(files.iter().map(|file| file.content.as_str()), top);
+    let overall = TopicGroup {
+        root: None,
+        files: count,
+        topics,
+    };
+    let mut roots: Vec<&str> = Vec::new();
+    for file in files {
+        if !roots.contains(&file.root.as_str()) {
+            roots.push(&file.root);
+        }
+    }
+    let roots = roots
+        .into_iter()
+        .map(|root| {
+            let texts = files
+                .iter()
+                .filter(|file| file.root == root)
+                .map(|file| file.content.as_str());
+            let (count, topics) = top_topics(texts, top);
+            TopicGroup {
+                root: Some(root.to_string()),
+                files: count,
+                topics,
+            }
+        })
+        .collect();
+    TopicReport { overall, roots }
+}
+
+/// Renders `report` in `format`.
+pub fn render(report: &TopicReport, format: ExportFormat) -> Result<String> {
+    match format {
+        ExportFormat::Md => Ok(render_markdown(report)),
+        ExportFormat::Json => {
+            let mut out = serde_json::to_string_pretty(report)
+                .context("failed to serialize the topic report")?;
+            out.push('\n');
+            Ok(out)
+        }
+    }
+}
+
+/// A table of topics under a heading for all roots, then one per root.
+fn render_markdown(report: &TopicReport) -> String {
+    let mut out = String::from("# Topics\n");
+    for group in std::iter::once(&report.overall).chain(&report.roots) {
+        let name = group.root.as_deref().unwrap_or("All roots");
+        let files = if group.files == 1 { "file" } else { "files" };
+        let _ = writeln!(out, "\n## {name} ({} {files})\n", group.files);
+        if group.topics.is_empty() {
+            out.push_str("No topics.\n");
+            continue;
+        }
+        out.push_str("| Topic | Files | Mentions |\n| --- | --- | --- |\n");
+        for topic in &group.topics {
+            let _ = writeln!(
+                out,
+                "| {} | {} | {} |",
+                topic.topic, topic.files, topic.mentions
+            );
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(root: &str, content: &str) -> ExportedFile {
+        ExportedFile {
+            root: root.to_string(),
+            path: format!("{root}/CLAUDE.md"),
+            content: content.to_string(),
+        }
+    }
+
+    #[test]
+    fn words_leave_out_code_short_and_common_words() {
+        let text =
+            "# Testing\nAlways run the tests with cargo-nextest.\n```\nignored code\n```\nOK 42";
+        assert_eq!(
+            topic_words(text),
+            ["testing", "run", "tests", "cargo-nextest"]
+        );
+    }
+
+    #[test]
+    fn topics_rank_by_files_then_mentions() {
+        let files = vec![
+            file("/a", "Testing first. Testing always. Formatting."),
+            file("/a", "Testing matters."),
+            file("/b", "Formatting, formatting, formatting."),
+        ];
+
+        let report = topic_report(&files, 2);
+
+        assert_eq!(report.overall.files, 3);
+        assert_eq!(
+            report.overall.topics,
+            [
+                Topic {
+                    topic: "formatting".to_string(),
+                    files: 2,
+                    mentions: 4,
+                },
+                Topic {
+                    topic: "testing".to_string(),
+                    files: 2,
+                    mentions: 3,
+                },
+            ]
+        );
+        assert_eq!(report.roots.len(), 2);
+        assert_eq!(report.roots[0].root.as_deref(), Some("/a"));
+        assert_eq!(report.roots[0].topics[0].topic, "testing");
+        assert_eq!(report.roots[1].files, 1);
+    }
+
+    #[test]
+    fn markdown_and_json_list_each_group() {
+        let report = topic_report(&[file("/a", "Testing.")], 5);
+
+        let md = render(&report, ExportFormat::Md).unwrap();
+        assert!(md.contains("## All roots (1 file)\n\n| Topic | Files | Mentions |"));
+        assert!(md.contains("## /a (1 file)"));
+        assert!(md.contains("| testing | 1 | 1 |"));
+
+        let json = render(&report, ExportFormat::Json).unwrap();
+        let value: serde_json::Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["overall"]["topics"][0]["topic"], "testing");
+        assert_eq!(value["roots"][0]["root"], "/a");
+        assert!(value["overall"].get("root").is_none());
+    }
+}
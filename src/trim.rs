@@ -0,0 +1,355 @@
+//! Trim suggestions: sections of a CLAUDE.md that cost tokens without
+//! telling Claude much.
+//!
+//! Each section (a heading up to the next heading of any level) is checked
+//! for three signs of low information: most of its lines also appear in
+//! another file or elsewhere in the same file, it points at paths that no
+//! longer exist or calls itself outdated, or it is mostly generic advice.
+//! Flagged sections are ranked by their token cost weighted by those signs,
+//! so the most expensive dead weight comes first.
+
+use std::cmp::Reverse;
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
+
+use crate::outline::parse_headings;
+use crate::tokens::Tokenizer;
+
+/// Lines shorter than this after normalising are too generic to count as
+/// duplicates (`## Notes`, `- Yes`).
+const MIN_DUPLICATE_LEN: usize = 12;
+
+/// Words that mark a section as out of date, matched case-insensitively.
+const STALE_MARKERS: &[&str] = &["deprecated", "no longer", "obsolete", "outdated", "todo"];
+
+/// Phrases that carry little instruction on their own.
+const VAGUE_PHRASES: &[&str] = &[
+    "best practice",
+    "as needed",
+    "when appropriate",
+    "as appropriate",
+    "if necessary",
+    "where possible",
+    "be careful",
+    "make sure to",
+    "try to",
+    "clean code",
+    "good code",
+    "high quality",
+    "properly",
+];
+
+/// Why a section was suggested for trimming.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TrimReason {
+    /// Most lines also appear in this file.
+    Duplicate(PathBuf),
+    /// Mentions a path that does not exist, or a marker like "deprecated".
+    Stale(String),
+    /// Mostly generic advice.
+    Vague,
+}
+
+impl TrimReason {
+    /// How much the reason raises a section's rank.
+    fn weight(&self) -> u64 {
+        match self {
+            TrimReason::Duplicate(_) => 3,
+            TrimReason::Stale(_) => 2,
+            TrimReason::Vague => 1,
+        }
+    }
+}
+
+impl fmt::Display for TrimReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TrimReason::Duplicate(path) => write!(f, "duplicated in {}", path.display()),
+            TrimReason::Stale(what) => write!(f, "stale: {what}"),
+            TrimReason::Vague => f.write_str("vague"),
+        }
+    }
+}
+
+/// A section suggested for trimming.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TrimCandidate {
+    /// Heading text of the section.
+    pub title: String,
+    /// 0-based lines of the section, heading included.
+    pub lines: Range<usize>,
+    pub tokens: u64,
+    pub reasons: Vec<TrimReason>,
+}
+
+impl TrimCandidate {
+    /// Rank of the candidate: its token cost times the weight of its reasons.
+    pub fn score(&self) -> u64 {
+        self.tokens * self.reasons.iter().map(TrimReason::weight).sum::<u64>()
+    }
+}
+
+/// Finds the sections of `text`, the content of `file`, worth trimming,
+/// highest score first. `others` are the other context files, checked for
+/// duplicated lines.
+pub fn find_candidates(
+    file: &Path,
+    text: &str,
+    others: &[(PathBuf, String)],
+    tokenizer: &dyn Tokenizer,
+) -> Vec<TrimCandidate> {
+    let lines: Vec<&str> = text.lines().collect();
+    let elsewhere = line_index(others);
+    let dir = file.parent().unwrap_or(Path::new(""));
+
+    let headings = parse_headings(text);
+    let mut candidates: Vec<TrimCandidate> = headings
+        .iter()
+        .enumerate()
+        .filter_map(|(i, heading)| {
+            let end = headings.get(i + 1).map_or(lines.len(), |next| next.line);
+            let range = heading.line..end;
+            let body = &lines[heading.line + 1..end];
+            let mut reasons = Vec::new();
+            if let Some(other) = duplicate_of(file, &lines, range.clone(), &elsewhere) {
+                reasons.push(TrimReason::Duplicate(other));
+            }
+            if let Some(what) = staleness(dir, body) {
+                reasons.push(TrimReason::Stale(what));
+            }
+            if is_vague(body) {
+                reasons.push(TrimReason::Vague);
+            }
+            if reasons.is_empty() {
+                return None;
+            }
+            Some(TrimCandidate {
+                title: heading.title.clone(),
+                tokens: tokenizer.count(&lines[range.clone()].join("\n")),
+                lines: range,
+                reasons,
+            })
+        })
+        .collect();
+    candidates.sort_unstable_by_key(|candidate| Reverse(candidate.score()));
+    candidates
+}
+
+/// `text` without the lines in `ranges`. Keeps the final newline if `text`
+/// had one.
+pub fn remove_lines(text: &str, ranges: &[Range<usize>]) -> String {
+    let mut out: String = text
+        .lines()
+        .enumerate()
+        .filter(|(i, _)| !ranges.iter().any(|range| range.contains(i)))
+        .map(|(_, line)| format!("{line}\n"))
+        .collect();
+    if !text.ends_with('\n') {
+        out.pop();
+    }
+    out
+}
+
+/// Normalised line → first of `files` containing it.
+fn line_index(files: &[(PathBuf, String)]) -> HashMap<String, &Path> {
+    let mut index = HashMap::new();
+    for (path, text) in files {
+        for line in text.lines().filter_map(normalize) {
+            index.entry(line).or_insert(path.as_path());
+        }
+    }
+    index
+}
+
+/// Where most of the section's lines also appear: another file from
+/// `elsewhere`, or `file` itself when they repeat outside the section.
+/// `None` unless at least half of its meaningful lines are repeated.
+fn duplicate_of(
+    file: &Path,
+    lines: &[&str],
+    section: Range<usize>,
+    elsewhere: &HashMap<String, &Path>,
+) -> Option<PathBuf> {
+    let outside: Vec<String> = lines
+        .iter()
+        .enumerate()
+        .filter(|(i, _)| !section.contains(i))
+        .filter_map(|(_, line)| normalize(line))
+        .collect();
+    let mut counts: HashMap<&Path, usize> = HashMap::new();
+    let mut meaningful = 0;
+    for line in lines[section.start + 1..section.end]
+        .iter()
+        .filter_map(|line| normalize(line))
+    {
+        meaningful += 1;
+        if let Some(path) = elsewhere.get(&line) {
+            *counts.entry(path).or_default() += 1;
+        } else if outside.contains(&line) {
+            *counts.entry(file).or_default() += 1;
+        }
+    }
+    let repeated: usize = counts.values().sum();
+    if meaningful == 0 || repeated * 2 < meaningful {
+        return None;
+    }
+    counts
+        .into_iter()
+        .max_by_key(|&(path, count)| (count, Reverse(path)))
+        .map(|(path, _)| path.to_path_buf())
+}
+
+/// A line lowercased, without list markers and with whitespace collapsed,
+/// or `None` if too short to compare.
+fn normalize(line: &str) -> Option<String> {
+    let trimmed = line
+        .trim()
+        .trim_start_matches(['-', '*', '+', '>'])
+        .trim_start_matches(|c: char| c.is_ascii_digit())
+        .trim_start_matches('.');
+    let words: Vec<&str> = trimmed.split_whitespace().collect();
+    let normalized = words.join(" ").to_lowercase();
+    (normalized.chars().count() >= MIN_DUPLICATE_LEN).then_some(normalized)
+}
+
+/// The first sign that `body` is out of date: a backticked path that does
+/// not exist relative to `dir`, or a stale marker word.
+fn staleness(dir: &Path, body: &[&str]) -> Option<String> {
+    for line in body {
+        for span in line.split('`').skip(1).step_by(2) {
+            if looks_like_path(span) && !dir.join(span).exists() {
+                return Some(format!("`{span}` does not exist"));
+            }
+        }
+    }
+    let lower = body.join("\n").to_lowercase();
+    STALE_MARKERS
+        .iter()
+        .find(|marker| contains_word(&lower, marker))
+        .map(|marker| format!("says \"{marker}\""))
+}
+
+/// True for relative paths with a directory part, such as `src/main.rs`.
+/// Commands, URLs, globs and placeholders are left alone.
+fn looks_like_path(span: &str) -> bool {
+    span.contains('/')
+        && !span.starts_with(['/', '~', '$'])
+        && !span.contains("://")
+        && span
+            .chars()
+            .all(|c| c.is_alphanumeric() || matches!(c, '/' | '.' | '_' | '-'))
+}
+
+/// True when `needle` appears in `haystack` between word boundaries.
+fn contains_word(haystack: &str, needle: &str) -> bool {
+    haystack.match_indices(needle).any(|(start, _)| {
+        let before = haystack[..start].chars().next_back();
+        let after = haystack[start + needle.len()..].chars().next();
+        !before.is_some_and(char::is_alphanumeric) && !after.is_some_and(char::is_alphanumeric)
+    })
+}
+
+/// True when at least a third of the section's non-blank lines are generic
+/// advice.
+fn is_vague(body: &[&str]) -> bool {
+    let content: Vec<String> = body
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.to_lowercase())
+        .collect();
+    let vague = content
+        .iter()
+        .filter(|line| {
+            VAGUE_PHRASES
+                .iter()
+                .any(|phrase| contains_word(line, phrase))
+        })
+        .count();
+    vague > 0 && vague * 3 >= content.len()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::tokens::ByteHeuristic;
+
+    #[test]
+    fn duplicated_section_names_the_other_file() {
+        let text = "# Project\n\n## Commands\n- Run cargo test before pushing\n- Format with cargo fmt --all\n\n## Notes\nThis project is unique.\n";
+        let other = (
+            PathBuf::from("/home/CLAUDE.md"),
+            "- run cargo test before pushing\n* Format with cargo fmt   --all\n".to_string(),
+        );
+
+        let candidates = find_candidates(Path::new("/p/CLAUDE.md"), text, &[other], &ByteHeuristic);
+
+        assert_eq!(candidates.len(), 1);
+        assert_eq!(candidates[0].title, "Commands");
+        assert_eq!(candidates[0].lines, 2..6);
+        assert_eq!(
+            candidates[0].reasons,
+            [TrimReason::Duplicate(PathBuf::from("/home/CLAUDE.md"))]
+        );
+    }
+
+    #[test]
+    fn repeats_within_the_file_count_as_duplicates() {
+        let text = "## A\n- Always run the linter first\n## B\n- Always run the linter first\n";
+        let candidates = find_candidates(Path::new("/p/CLAUDE.md"), text, &[], &ByteHeuristic);
+
+        assert_eq!(candidates.len(), 2);
+        assert!(
+            candidates
+                .iter()
+                .all(|c| c.reasons == [TrimReason::Duplicate(PathBuf::from("/p/CLAUDE.md"))])
+        );
+    }
+
+    #[test]
+    fn missing_paths_and_markers_are_stale() {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir_all(tmp.path().join("src")).unwrap();
+        fs::write(tmp.path().join("src/main.rs"), "").unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        let text = "## Layout\nEntry point is `src/main.rs`.\n## Old\nHandlers live in `src/handlers/mod.rs`.\n## Api\nThe v1 API is deprecated.\n## Todos\nNothing here is a todolist.\n";
+
+        let candidates = find_candidates(&file, text, &[], &ByteHeuristic);
+        let titles: Vec<&str> = candidates.iter().map(|c| c.title.as_str()).collect();
+
+        assert_eq!(titles.len(), 2);
+        assert!(titles.contains(&"Old") && titles.contains(&"Api"));
+        let old = candidates.iter().find(|c| c.title == "Old").unwrap();
+        assert_eq!(
+            old.reasons,
+            [TrimReason::Stale(
+                "`src/handlers/mod.rs` does not exist".to_string()
+            )]
+        );
+    }
+
+    #[test]
+    fn generic_advice_is_vague_and_ranked_by_cost() {
+        let text = format!(
+            "## Style\n- Write clean code.\n- Follow best practices.\n## Short\nTry to be nice.\n## Tail\n{}\n",
+            "Real instructions. ".repeat(10)
+        );
+        let candidates = find_candidates(Path::new("/p/CLAUDE.md"), &text, &[], &ByteHeuristic);
+
+        let titles: Vec<&str> = candidates.iter().map(|c| c.title.as_str()).collect();
+        assert_eq!(titles, ["Style", "Short"]);
+        assert!(candidates[0].score() > candidates[1].score());
+    }
+
+    #[test]
+    fn remove_lines_drops_ranges_and_keeps_final_newline() {
+        assert_eq!(remove_lines("a\nb\nc\nd\n", &[1..2, 3..4]), "a\nc\n");
+        assert_eq!(remove_lines("a\nb\nc", &[0..1, 2..3]), "b");
+    }
+}
@@ -0,0 +1,571 @@
+//! Key handling is a two-stage pipeline. The [`KeyMap`](super::keymap::KeyMap)
+//! turns a key press into an [`Action`]; [`App::update`] applies the action to
+//! in-memory state and returns the [`Effect`]s it needs (reading or writing
+//! files, loading the library), which [`App::run_effect`] then performs.
+//! Keeping the two apart lets anything that produces actions — remapped keys,
+//! macros, a command palette, tests — drive the app without a terminal.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use ratatui::crossterm::event::KeyEvent;
+
+use super::app::App;
+use super::app::Mode;
+use super::app::Pane;
+use super::app::Screen;
+use super::compose::ComposePane;
+use super::diff_preview::PreviewAction;
+use super::keymap::KeyContext;
+use super::picker::PickerAction;
+use crate::merge::MergeChoice;
+
+/// Something the user asked for, independent of the key that triggered it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Action {
+    Quit,
+    OpenFileFinder,
+    OpenGlobalSearch,
+    /// Show recently viewed file `n` (0-based).
+    JumpRecent(usize),
+    /// Go back to where the cursor was before the last jump.
+    JumpBack,
+    /// Go forward again to where `JumpBack` left.
+    JumpForward,
+    /// Pick one of the recently viewed files to show.
+    OpenRecentFiles,
+    /// List the messages shown in the status bar this session.
+    OpenMessages,
+    /// Show how many tokens the loaded files cost together.
+    OpenBudget,
+    /// Ask for a directory and show what a session started there loads.
+    SimulateSession,
+    SwitchScreen(Screen),
+    ToggleTheme,
+    /// Leave the current screen for the Files screen.
+    Back,
+    TogglePane,
+    MoveUp,
+    MoveDown,
+    MoveLeft,
+    MoveRight,
+    PageUp,
+    PageDown,
+    /// Jump to the first line.
+    MoveTop,
+    /// Jump to the last line.
+    MoveBottom,
+    /// Fold or unfold the section under the cursor.
+    ToggleFold,
+    /// Accept the open prompt, picker or preview.
+    Confirm,
+    /// Decline a preview (as opposed to closing it with `Cancel`).
+    Reject,
+    /// Close the open prompt, picker or preview.
+    Cancel,
+    StartSelection,
+    SaveSelection,
+    /// Copy the selected lines to the clipboard.
+    CopySelection,
+    /// Move the selected lines to another file.
+    MoveSelection,
+    /// Copy the whole shown file to the clipboard.
+    CopyFile,
+    Edit,
+    /// Open the file in `$EDITOR`, outside the TUI.
+    OpenInEditor,
+    Save,
+    NewFile,
+    OpenBackups,
+    MigrateLocal,
+    LoadFullContent,
+    ToggleRepoGroups,
+    Rescan,
+    ToggleMergedView,
+    ToggleOutline,
+    /// Show the rendered Markdown beside the raw text.
+    ToggleSplitPreview,
+    /// Hide HTML comments in the content pane, or show them again.
+    ToggleComments,
+    /// Mark or unmark the snippet under the cursor in Compose or the
+    /// Library.
+    ToggleMark,
+    Export,
+    Delete,
+    Rename,
+    /// Rename a misnamed CLAUDE.md to the name Claude Code reads.
+    FixName,
+    ExportMemory,
+    InsertSnippet,
+    /// Append the selected snippet to a discovered file, under a heading.
+    AppendSnippet,
+    /// Show the file the selected snippet was taken from.
+    OpenSnippetSource,
+    /// Step the selected snippet to the next format.
+    CycleFormat,
+    /// Type a tag for the marked snippets, or the selected one.
+    Tag,
+    /// Type a filter narrowing the Library list.
+    Filter,
+    /// Group the Library list by the project snippets came from, or stop.
+    GroupByProject,
+    /// Switch the Library between its snippets and the archived ones.
+    ToggleArchiveView,
+    /// Save the current view as text and SVG.
+    Snapshot,
+    /// Suggest sections of the selected file to trim.
+    Trim,
+    /// List the changes jigolo made to the selected file.
+    History,
+    /// Open the menu of what can be done with the line under the cursor.
+    QuickActions,
+    /// Move the section under the cursor to a new file, imported in its
+    /// place.
+    ExtractSection,
+    /// Expand the imports on the cursor's line in place, or collapse them.
+    ToggleImport,
+    /// Expand every import of the shown file in place, or collapse them.
+    ToggleAllImports,
+    /// Show the next file with lint findings.
+    NextFinding,
+    /// Put the last inserted snippet into the selected file, in the same
+    /// kind of place.
+    RepeatInsert,
+    /// Pick one of the configured external tools to run on the file.
+    RunTool,
+    /// Wrap long lines in the content pane, or cut them at the border.
+    ToggleWrap,
+    /// Show or hide where the shown file sits in the load order.
+    ToggleLoadOrder,
+    /// Move the chosen items into the library instead of deleting them.
+    Archive,
+    /// Merge another file into the selected one, section by section.
+    Merge,
+    /// Keep this version of the merge section under the cursor.
+    TakeSection(MergeChoice),
+    /// Add the snippets of a shared bundle file to the library.
+    Import,
+    /// Revert the last library change.
+    Undo,
+    /// Reapply the last undone library change.
+    Redo,
+    /// A key typed into a text prompt or the editor.
+    Input(KeyEvent),
+}
+
+/// Work an action needs done outside of plain state changes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Effect {
+    LoadSelectedContent,
+    LoadFullContent,
+    /// Rebuild the file tree with repository grouping switched.
+    ToggleRepoGroups,
+    /// Scan the roots again for added and removed files.
+    Rescan,
+    OpenSettings,
+    OpenCompose,
+    OpenLibrary,
+    RebuildSettings,
+    EditFile,
+    EditSettings,
+    /// Open the settings file under the cursor in `$EDITOR`.
+    OpenSettingsInEditor,
+    EditSnippet,
+    SaveEdit,
+    CloseEditor,
+    SaveSelection,
+    RenameSnippet,
+    /// Ask whether to archive or delete the selected snippet.
+    RemoveSnippet,
+    /// Archive the selected snippet, or restore it in the Archive view.
+    ToggleSnippetArchived,
+    /// Add the tag typed in to the marked snippets, or the selected one.
+    TagSnippets,
+    /// Write the library to the bundle path typed in.
+    ExportLibrary,
+    /// Import the bundle at the path typed in.
+    ImportLibrary,
+    UndoLibraryChange,
+    RedoLibraryChange,
+    ExportComposed,
+    StartNewFile,
+    SubmitNewFilePath,
+    /// Preview deleting the selected file.
+    StartDeleteFile,
+    /// Prompt for a new path for the selected file.
+    StartMoveFile,
+    SubmitMovePath,
+    /// Preview moving the chosen lines under the heading typed in.
+    SubmitMoveHeading,
+    /// Preview extracting the chosen section to the path typed in.
+    SubmitExtractPath,
+    /// Give the selected misnamed file its canonical name.
+    FixFileName,
+    OpenBackups,
+    OpenBudget,
+    /// List sections of the selected file worth trimming.
+    OpenTrim,
+    OpenHistory,
+    /// Ask which file to merge into the selected one.
+    StartMerge,
+    /// Write the merge to the new file typed in.
+    WriteMergeToNewFile,
+    /// Show the session started in the directory typed in.
+    SimulateSession,
+    /// Write the shown session to the path typed in.
+    ExportSession,
+    MigrateLocal,
+    ExportMemory,
+    InsertSnippet,
+    AppendSnippet,
+    CycleSnippetFormat,
+    /// Select `file` on the Files screen and show its content.
+    ShowFile(PathBuf),
+    /// Like `ShowFile`, with the content cursor on 0-based `line`.
+    ShowLine {
+        file: PathBuf,
+        line: usize,
+    },
+    /// Search every file for the global search query.
+    RunGlobalSearch,
+    /// Save the current view to the working directory.
+    Snapshot,
+    /// Put `text` on the system clipboard.
+    Copy(String),
+    /// Put the whole of `file` on the system clipboard.
+    CopyFile(PathBuf),
+    /// Act on entry `index` of a confirmed picker.
+    Pick {
+        action: PickerAction,
+        index: usize,
+    },
+    AcceptPreview(PreviewAction),
+    RejectPreview(PreviewAction),
+}
+
+impl App {
+    /// Returns the key context for the current screen, mode and pane.
+    pub(crate) fn key_context(&self) -> KeyContext {
+        match self.mode {
+            Mode::Edit => return KeyContext::Editor,
+            Mode::Picker => return KeyContext::Picker,
+            Mode::DiffPreview => return KeyContext::DiffPreview,
+            Mode::Budget => return KeyContext::Budget,
+            Mode::Trim => return KeyContext::Trim,
+            Mode::Session => return KeyContext::Session,
+            Mode::Merge => return KeyContext::Merge,
+            Mode::History => return KeyContext::History,
+            Mode::Messages => return KeyContext::Messages,
+            Mode::FileFinder => return KeyContext::FileFinder,
+            Mode::GlobalSearch => return KeyContext::GlobalSearch,
+            Mode::TitleInput
+            | Mode::RenameInput
+            | Mode::ExportPath
+            | Mode::NewFilePath
+            | Mode::MovePath
+            | Mode::MoveHeading
+            | Mode::ExtractPath
+            | Mode::LibraryFilter
+            | Mode::BundleExportPath
+            | Mode::BundleImportPath
+            | Mode::TagInput
+            | Mode::SessionDir
+            | Mode::SessionExportPath
+            | Mode::MergePath => {
+                return KeyContext::TextInput;
+            }
+            Mode::VisualSelect => return KeyContext::VisualSelect,
+            Mode::Normal => {}
+        }
+        match self.screen {
+            Screen::Files if self.active_pane == Pane::Content => KeyContext::FilesContent,
+            Screen::Files if self.active_pane == Pane::Outline => KeyContext::FilesOutline,
+            Screen::Files => KeyContext::FilesTree,
+            Screen::Settings => KeyContext::Settings,
+            Screen::Compose
+                if self
+                    .compose_state
+                    .as_ref()
+                    .is_some_and(|cs| cs.active_pane == ComposePane::Preview) =>
+            {
+                KeyContext::ComposePreview
+            }
+            Screen::Compose => KeyContext::ComposeList,
+            Screen::Library => KeyContext::Library,
+        }
+    }
+
+    /// Applies `action` and performs the effects it asks for.
+    pub fn dispatch(&mut self, action: Action) {
+        for effect in self.update(action) {
+            self.run_effect(effect);
+        }
+    }
+
+    /// Applies `action` to in-memory state and returns the effects still to
+    /// be run. Performs no IO.
+    pub(crate) fn update(&mut self, action: Action) -> Vec<Effect> {
+        match action {
+            Action::Quit => {
+                self.exit = true;
+                return Vec::new();
+            }
+            Action::SwitchScreen(screen) => {
+                return match screen {
+                    Screen::Files => {
+                        self.screen = Screen::Files;
+                        Vec::new()
+                    }
+                    Screen::Settings => vec![Effect::OpenSettings],
+                    Screen::Compose => vec![Effect::OpenCompose],
+                    Screen::Library => vec![Effect::OpenLibrary],
+                };
+            }
+            Action::ToggleTheme => {
+                self.theme = self.theme.toggle();
+                self.apply_color_overrides();
+                self.rebuild_tree();
+                return Vec::new();
+            }
+            Action::OpenFileFinder => {
+                self.open_file_finder();
+                return Vec::new();
+            }
+            Action::JumpRecent(index) => return self.jump_to_recent(index),
+            Action::JumpBack => {
+                self.jump_back();
+                return Vec::new();
+            }
+            Action::JumpForward => {
+                self.jump_forward();
+                return Vec::new();
+            }
+            Action::OpenRecentFiles => {
+                self.open_recent_files();
+                return Vec::new();
+            }
+            Action::OpenMessages => {
+                self.open_messages();
+                return Vec::new();
+            }
+            Action::OpenBudget => return vec![Effect::OpenBudget],
+            Action::Snapshot => return vec![Effect::Snapshot],
+            _ => {}
+        }
+
+        match self.key_context() {
+            KeyContext::Editor => self.update_editor(action),
+            KeyContext::Picker => self.update_picker(action),
+            KeyContext::DiffPreview => self.update_diff_preview(action),
+            KeyContext::Budget => self.update_budget(action),
+            KeyContext::Trim => self.update_trim(action),
+            KeyContext::Session => self.update_session(action),
+            KeyContext::Merge => self.update_merge(action),
+            KeyContext::History => self.update_history(action),
+            KeyContext::Messages => self.update_messages(action),
+            KeyContext::FileFinder => self.update_file_finder(action),
+            KeyContext::GlobalSearch => self.update_global_search(action),
+            KeyContext::TextInput => self.update_text_input(action),
+            KeyContext::VisualSelect => self.update_visual_select(action),
+            KeyContext::FilesTree | KeyContext::FilesContent => self.update_files(action),
+            KeyContext::FilesOutline => self.update_outline(action),
+            KeyContext::Settings => self.update_settings(action),
+            KeyContext::ComposeList | KeyContext::ComposePreview => self.update_compose(action),
+            KeyContext::Library => self.update_library(action),
+            KeyContext::Any | KeyContext::Global => Vec::new(),
+        }
+    }
+
+    /// Applies an action in one of the single-line prompts.
+    fn update_text_input(&mut self, action: Action) -> Vec<Effect> {
+        match (self.mode, action) {
+            (Mode::LibraryFilter, Action::Input(key)) => {
+                self.text_input.handle_edit_key(key.code);
+                let query = self.text_input.text().to_string();
+                self.set_library_filter(query);
+            }
+            (Mode::LibraryFilter, Action::Confirm) => {
+                self.text_input.clear();
+                self.mode = Mode::Normal;
+            }
+            (Mode::LibraryFilter, Action::Cancel) => {
+                self.text_input.clear();
+                self.set_library_filter(String::new());
+                self.mode = Mode::Normal;
+            }
+            (_, Action::Input(key)) => {
+                self.text_input.handle_edit_key(key.code);
+            }
+            (Mode::TitleInput, Action::Confirm) => return vec![Effect::SaveSelection],
+            (Mode::TitleInput, Action::Cancel) => {
+                self.text_input.clear();
+                self.mode = Mode::VisualSelect;
+            }
+            (Mode::RenameInput, Action::Confirm) => return vec![Effect::RenameSnippet],
+            (Mode::ExportPath, Action::Confirm) => return vec![Effect::ExportComposed],
+            (Mode::NewFilePath, Action::Confirm) => return vec![Effect::SubmitNewFilePath],
+            (Mode::NewFilePath, Action::Cancel) => self.reset_to_normal(),
+            (Mode::MovePath, Action::Confirm) => return vec![Effect::SubmitMovePath],
+            (Mode::MovePath, Action::Cancel) => self.reset_to_normal(),
+            (Mode::MoveHeading, Action::Confirm) => return vec![Effect::SubmitMoveHeading],
+            (Mode::ExtractPath, Action::Confirm) => return vec![Effect::SubmitExtractPath],
+            (Mode::ExtractPath, Action::Cancel) => {
+                self.pending_extraction = None;
+                self.reset_to_normal();
+            }
+            (Mode::MoveHeading, Action::Cancel) => {
+                self.pending_line_move = None;
+                self.reset_to_normal();
+            }
+            (Mode::BundleExportPath, Action::Confirm) => return vec![Effect::ExportLibrary],
+            (Mode::BundleImportPath, Action::Confirm) => return vec![Effect::ImportLibrary],
+            (Mode::TagInput, Action::Confirm) => return vec![Effect::TagSnippets],
+            (Mode::SessionDir, Action::Confirm) => return vec![Effect::SimulateSession],
+            (Mode::SessionExportPath, Action::Confirm) => return vec![Effect::ExportSession],
+            (Mode::SessionExportPath, Action::Cancel) => {
+                self.text_input.clear();
+                self.mode = Mode::Session;
+            }
+            (Mode::MergePath, Action::Confirm) => return vec![Effect::WriteMergeToNewFile],
+            (Mode::MergePath, Action::Cancel) => {
+                self.text_input.clear();
+                self.mode = Mode::Merge;
+            }
+            (_, Action::Cancel) => {
+                self.text_input.clear();
+                self.mode = Mode::Normal;
+            }
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    /// Performs one effect returned by [`update`](Self::update).
+    pub(crate) fn run_effect(&mut self, effect: Effect) {
+        match effect {
+            Effect::LoadSelectedContent => self.load_selected_content(),
+            Effect::LoadFullContent => self.load_full_content(),
+            Effect::ToggleRepoGroups => self.set_repo_grouping(!self.group_by_repo),
+            Effect::Rescan => self.rescan(),
+            Effect::OpenSettings => self.switch_to_settings(),
+            Effect::OpenCompose => self.enter_compose_screen(),
+            Effect::OpenLibrary => self.enter_library_screen(),
+            Effect::RebuildSettings => self.rebuild_settings_display(),
+            Effect::EditFile => self.enter_edit_mode(),
+            Effect::EditSettings => self.enter_settings_edit_mode(),
+            Effect::OpenSettingsInEditor => self.open_settings_in_editor(),
+            Effect::EditSnippet => self.enter_snippet_edit(),
+            Effect::SaveEdit => self.save_edit(),
+            Effect::CloseEditor => self.finalize_exit_edit(),
+            Effect::SaveSelection => self.save_current_snippet(),
+            Effect::RenameSnippet => self.rename_library_snippet(),
+            Effect::RemoveSnippet => self.start_snippet_removal(),
+            Effect::ToggleSnippetArchived => self.toggle_snippet_archived(),
+            Effect::TagSnippets => self.tag_library_snippets(),
+            Effect::ExportLibrary => self.export_library_bundle(),
+            Effect::ImportLibrary => self.import_library_bundle(),
+            Effect::UndoLibraryChange => self.undo_library_change(),
+            Effect::RedoLibraryChange => self.redo_library_change(),
+            Effect::ExportComposed => self.execute_export(),
+            Effect::StartNewFile => self.start_new_file(),
+            Effect::SubmitNewFilePath => self.submit_new_file_path(),
+            Effect::StartDeleteFile => self.start_delete_file(),
+            Effect::StartMoveFile => self.start_move_file(),
+            Effect::SubmitMovePath => self.submit_move_path(),
+            Effect::SubmitMoveHeading => self.submit_move_heading(),
+            Effect::SubmitExtractPath => self.submit_extract_path(),
+            Effect::FixFileName => self.fix_misnamed_file(),
+            Effect::OpenBackups => self.open_backups(),
+            Effect::OpenBudget => self.open_budget(),
+            Effect::OpenTrim => self.open_trim(),
+            Effect::OpenHistory => self.open_history(),
+            Effect::StartMerge => self.start_merge(),
+            Effect::WriteMergeToNewFile => self.write_merge_to_new_file(),
+            Effect::SimulateSession => self.simulate_session_from_input(),
+            Effect::ExportSession => self.export_session_from_input(),
+            Effect::MigrateLocal => self.start_local_migration(),
+            Effect::ExportMemory => self.start_memory_export(),
+            Effect::AppendSnippet => self.start_snippet_append(),
+            Effect::InsertSnippet => self.preview_snippet_insert(self.library_selected),
+            Effect::CycleSnippetFormat => self.cycle_snippet_format(),
+            Effect::ShowFile(file) => self.show_file(&file),
+            Effect::ShowLine { file, line } => self.show_line(&file, line),
+            Effect::RunGlobalSearch => self.run_global_search(),
+            Effect::Snapshot => self.save_snapshot(Path::new(".")),
+            Effect::Copy(text) => self.copy_to_clipboard(&text),
+            Effect::CopyFile(file) => self.copy_file_to_clipboard(&file),
+            Effect::Pick { action, index } => self.run_picker_action(action, index),
+            Effect::AcceptPreview(action) => self.accept_preview(action),
+            Effect::RejectPreview(action) => self.reject_preview(action),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::Pane;
+    use crate::tui::app::Screen;
+    use crate::tui::app::test_helpers::sample_roots;
+
+    use super::Action;
+    use super::Effect;
+
+    #[test]
+    fn navigation_needs_no_effects_beyond_loading() {
+        let mut app = App::new(sample_roots(), &Config::default());
+
+        assert_eq!(app.update(Action::TogglePane), vec![]);
+        assert_eq!(app.active_pane, Pane::Content);
+        assert_eq!(app.update(Action::TogglePane), vec![]);
+        assert_eq!(
+            app.update(Action::MoveDown),
+            vec![Effect::LoadSelectedContent]
+        );
+    }
+
+    #[test]
+    fn switching_to_library_is_an_effect() {
+        let mut app = App::new(vec![], &Config::default());
+
+        let effects = app.update(Action::SwitchScreen(Screen::Library));
+
+        assert_eq!(effects, vec![Effect::OpenLibrary]);
+        assert_eq!(app.screen, Screen::Files, "update alone does no loading");
+    }
+
+    #[test]
+    fn update_does_not_write_files() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "saved").unwrap();
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_edit_mode_for(&file);
+
+        let effects = app.update(Action::Save);
+
+        assert_eq!(effects, vec![Effect::SaveEdit]);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "saved");
+    }
+
+    #[test]
+    fn dispatch_drives_the_app_without_keys() {
+        let mut app = App::new(sample_roots(), &Config::default());
+
+        app.dispatch(Action::TogglePane);
+        app.dispatch(Action::StartSelection);
+        assert_eq!(app.mode, Mode::VisualSelect);
+
+        app.dispatch(Action::Cancel);
+        assert_eq!(app.mode, Mode::Normal);
+
+        app.dispatch(Action::Quit);
+        assert!(app.exit);
+    }
+}
@@ -1,9 +1,17 @@
 use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
+use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::fs;
 use std::io;
+use std::ops::Range;
 use std::path::Path;
 use std::path::PathBuf;
+use std::sync::mpsc::Receiver;
+use std::time::Duration;
+use std::time::Instant;
 
 use ratatui::DefaultTerminal;
 use ratatui::Frame;
@@ -11,7 +19,7 @@ use ratatui::crossterm::event;
 use ratatui::crossterm::event::Event;
 use ratatui::crossterm::event::KeyCode;
 use ratatui::crossterm::event::KeyEvent;
-use ratatui::crossterm::event::KeyModifiers;
+use ratatui::crossterm::event::KeyEventKind;
 use ratatui::layout::Constraint;
 use ratatui::layout::Direction;
 use ratatui::layout::Layout;
@@ -24,14 +32,86 @@ use tui_textarea::TextArea;
 use tui_tree_widget::TreeItem;
 use tui_tree_widget::TreeState;
 
+use crate::budget::size_summary;
+use crate::config::Config;
+use crate::discovery::CLAUDE_FILE_NAME;
+use crate::discovery::ContextKind;
+use crate::discovery::ScanOptions;
+use crate::discovery::canonical_name_for;
+use crate::discovery::discovered_file;
+use crate::discovery::find_global_claude_file;
+use crate::discovery::is_claude_file;
+use crate::discovery::is_local_claude_file;
+use crate::discovery::root_name;
+use crate::fsutil::ReadOnly;
+use crate::fsutil::check_writable;
+use crate::fsutil::read_capped;
+use crate::git::GitRepo;
+use crate::git::find_git_repo;
 use crate::library::SnippetLibrary;
+use crate::lint::Finding;
+use crate::lint::FindingCounts;
+use crate::lint::apply_rule_levels;
+use crate::lint::lint_file;
+use crate::lint::rule_levels_for;
+use crate::model::DiscoveredFile;
 use crate::model::SourceRoot;
 use crate::settings::SettingsCollection;
 use crate::settings::SettingsLineMap;
+use crate::state::UiState;
+use crate::terminal::Capabilities;
+use crate::terminal::degrade;
+use crate::tokens::Tokenizer;
+use crate::tokens::format_tokens;
+use crate::tui::extract::PendingExtraction;
+use crate::tui::files::hidden_comments_message;
+use crate::tui::imports::import_items;
+use crate::tui::imports::is_missing_import_id;
+use crate::tui::imports::missing_import_path;
+use crate::tui::jumps::JumpList;
+use crate::tui::keymap::KeyBinding;
+use crate::tui::keymap::KeyMap;
+use crate::tui::keymap::KeyResolution;
+use crate::tui::keymap::SEQUENCE_TIMEOUT;
+use crate::tui::line_move::PendingLineMove;
+use crate::tui::load_order::load_order_line;
+use crate::tui::memory::SnippetInsert;
+use crate::tui::messages::LoggedMessage;
+use crate::tui::messages::MessageLevel;
+use crate::tui::messages::MessagesView;
+use crate::tui::mouse::PaneAreas;
+use crate::tui::render_cache::RenderCache;
+use crate::tui::scanner::BackgroundScan;
 use crate::tui::theme::Theme;
+use crate::tui::tools::RunningTool;
+use crate::tui::undo::LibraryUndo;
+use crate::tui::watcher::FileWatcher;
 
 pub type TreeId = String;
 
+/// How long the event loop waits for input before running periodic work.
+const TICK_RATE: Duration = Duration::from_millis(250);
+
+/// Most queued input events handled between two redraws, so a long paste
+/// still shows progress and lets periodic work run.
+const MAX_EVENTS_PER_FRAME: usize = 256;
+
+/// Tree badge marking deprecated `CLAUDE.local.md` files.
+const LOCAL_BADGE: &str = " [local]";
+
+/// Tree badge marking files a `file_patterns` entry or `--pattern` picked
+/// up, which Claude Code does not read by that name.
+const PATTERN_BADGE: &str = " [pattern]";
+
+/// Tree badge for roots and files outside any git work tree.
+const NO_REPO_BADGE: &str = " [no git]";
+
+/// Id of the node holding a root's misnamed CLAUDE.md files.
+const MISNAMED_GROUP: &str = "[misnamed]";
+
+/// Id and label of the group collecting files outside any git work tree.
+const NO_REPO_GROUP: &str = "(no git)";
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Screen {
     Files,
@@ -44,6 +124,8 @@ pub enum Screen {
 pub enum Pane {
     FileList,
     Content,
+    /// Heading outline of the shown file, when `outline_visible`.
+    Outline,
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -54,11 +136,82 @@ pub enum Mode {
     RenameInput,
     Edit,
     ExportPath,
+    NewFilePath,
+    /// Typing where the selected file moves to.
+    MovePath,
+    /// Typing the heading put over lines moved to another file.
+    MoveHeading,
+    /// Typing the file a section is extracted to.
+    ExtractPath,
+    Picker,
+    DiffPreview,
+    FileFinder,
+    GlobalSearch,
+    /// Typing the filter that narrows the Library list.
+    LibraryFilter,
+    /// Typing the file the library is exported to.
+    BundleExportPath,
+    /// Typing the bundle file to import into the library.
+    BundleImportPath,
+    /// Typing a tag for the marked or selected snippets.
+    TagInput,
+    /// Context budget dashboard.
+    Budget,
+    /// Checklist of sections suggested for trimming.
+    Trim,
+    /// Typing the directory to simulate a session in.
+    SessionDir,
+    /// Simulated session document.
+    Session,
+    /// Typing the file the simulated session is written to.
+    SessionExportPath,
+    /// Sections of two files paired up for merging.
+    Merge,
+    /// Typing the new file the merge is written to.
+    MergePath,
+    /// Changes jigolo made to the selected file or directory.
+    History,
+    /// Messages shown in the status bar this session.
+    Messages,
+}
+
+/// The scroll that puts line `cursor` in view, moving as little as possible
+/// from `scroll`, for a pane `height` rows tall. `wrapped_rows` holds the
+/// rows each line takes, or is empty when every line takes one.
+pub(crate) fn scroll_to_show(
+    cursor: usize,
+    scroll: u16,
+    height: u16,
+    wrapped_rows: &[usize],
+) -> u16 {
+    let (scroll, height) = (scroll as usize, height as usize);
+    if cursor < scroll {
+        return cursor as u16;
+    }
+    if height == 0 {
+        return scroll as u16;
+    }
+    if wrapped_rows.is_empty() {
+        return cursor.saturating_sub(height - 1).max(scroll) as u16;
+    }
+    // Move the top down until the rows from it through the cursor's fit.
+    let rows_of = |line: usize| wrapped_rows.get(line).copied().unwrap_or(1);
+    let mut first = scroll;
+    let mut rows: usize = (first..=cursor).map(rows_of).sum();
+    while first < cursor && rows > height {
+        rows -= rows_of(first);
+        first += 1;
+    }
+    first as u16
 }
 
 #[derive(Debug)]
 pub struct ContentState {
+    /// Text shown in the pane. Change it through
+    /// [`set_text`](Self::set_text) so the render cache notices.
     pub text: Option<String>,
+    /// Bumped whenever `text` changes; part of the render cache key.
+    pub revision: u64,
     pub scroll: u16,
     pub cursor: usize,
     pub visual_anchor: Option<usize>,
@@ -66,19 +219,54 @@ pub struct ContentState {
     /// border. The event loop always draws before handling input, so this is
     /// populated before any key handler runs.
     pub viewport_height: u16,
+    /// Size in bytes of the shown file when `text` holds only its start.
+    pub truncated_from: Option<u64>,
+    /// Lines of the file left out of `text` because they held only HTML
+    /// comments, when [`App::hide_comments`] is on.
+    pub hidden_comment_lines: usize,
+    /// Lines of `text` taken from imported files expanded in place, one
+    /// range per import.
+    pub inlined_imports: Vec<Range<usize>>,
+    /// Lines, size and tokens of the shown file, kept in the status bar.
+    pub summary: Option<String>,
+    /// Where the shown file sits in the load order, while
+    /// [`App::show_load_order`] is on.
+    pub load_order: Option<String>,
+    /// Captured during draw() while [`App::wrap_lines`] is on — screen rows
+    /// each line of `text` takes. Empty when lines are not wrapped.
+    pub(crate) wrapped_rows: Vec<usize>,
+    /// Styled lines from the last draw.
+    pub(crate) render_cache: RenderCache,
+    /// Rendered Markdown lines from the last draw of the split preview.
+    pub(crate) preview_cache: RenderCache,
 }
 
 impl ContentState {
     pub(crate) fn new() -> Self {
         Self {
             text: None,
+            revision: 0,
             scroll: 0,
             cursor: 0,
             visual_anchor: None,
             viewport_height: 0,
+            truncated_from: None,
+            hidden_comment_lines: 0,
+            inlined_imports: Vec::new(),
+            summary: None,
+            load_order: None,
+            wrapped_rows: Vec::new(),
+            render_cache: RenderCache::default(),
+            preview_cache: RenderCache::default(),
         }
     }
 
+    /// Replaces the shown text, invalidating cached rendering.
+    pub fn set_text(&mut self, text: Option<String>) {
+        self.text = text;
+        self.revision = self.revision.wrapping_add(1);
+    }
+
     pub fn line_count(&self) -> usize {
         self.text.as_ref().map_or(0, |t| t.lines().count())
     }
@@ -111,25 +299,70 @@ impl ContentState {
         self.ensure_cursor_visible();
     }
 
+    /// Moves the cursor to `line`, clamped to the text, and scrolls it into
+    /// view.
+    pub fn jump_to_line(&mut self, line: usize) {
+        self.cursor = line.min(self.max_cursor());
+        self.ensure_cursor_visible();
+    }
+
     fn ensure_cursor_visible(&mut self) {
-        let scroll = self.scroll as usize;
-        let vh = self.viewport_height as usize;
-        if self.cursor < scroll {
-            self.scroll = self.cursor as u16;
-        } else if vh > 0 && self.cursor >= scroll + vh {
-            self.scroll = (self.cursor - vh + 1) as u16;
+        self.scroll = scroll_to_show(
+            self.cursor,
+            self.scroll,
+            self.viewport_height,
+            &self.wrapped_rows,
+        );
+    }
+
+    /// The line shown `row` rows below the top of the pane, counting the
+    /// rows wrapped lines take. `None` below the end of the text.
+    pub(crate) fn line_at_row(&self, row: usize) -> Option<usize> {
+        let count = self.line_count();
+        let mut line = self.scroll as usize;
+        let mut rows_left = row;
+        while line < count {
+            let rows = self.wrapped_rows.get(line).copied().unwrap_or(1);
+            if rows_left < rows {
+                return Some(line);
+            }
+            rows_left -= rows;
+            line += 1;
+        }
+        None
+    }
+
+    /// Height of the text and the row the top of the pane is on, in lines,
+    /// or in screen rows when lines are wrapped. For the scrollbar.
+    pub(crate) fn scroll_position(&self) -> (usize, usize) {
+        if self.wrapped_rows.is_empty() {
+            return (self.line_count(), self.scroll as usize);
         }
+        let scroll = (self.scroll as usize).min(self.wrapped_rows.len());
+        (
+            self.wrapped_rows.iter().sum(),
+            self.wrapped_rows[..scroll].iter().sum(),
+        )
     }
 
     fn load_text(&mut self, raw: String) {
-        // Ratatui does not expand tab characters — it treats '\t' as a single-width
-        // glyph while the terminal may jump to the next tab stop, causing width
-        // mismatches and leftover characters when redrawing. Replace with spaces.
-        let text = raw.replace('\t', "    ");
-        self.text = Some(text);
+        self.set_text(Some(expand_tabs(&raw)));
         self.scroll = 0;
         self.cursor = 0;
         self.visual_anchor = None;
+        self.truncated_from = None;
+        self.hidden_comment_lines = 0;
+        self.inlined_imports.clear();
+        self.summary = None;
+        self.load_order = None;
+    }
+
+    /// Whether `text` is the file exactly as it is on disk: whole, with its
+    /// comments and without imports expanded, so its lines can be changed.
+    pub fn shows_file_as_is(&self) -> bool {
+        self.truncated_from.is_none()
+            && self.hidden_comment_lines == 0
+            && self.inlined_imports.is_empty()
     }
 
     pub fn selection_range(&self) -> Option<(usize, usize)> {
@@ -149,6 +382,13 @@ impl ContentState {
     }
 }
 
+/// Ratatui does not expand tab characters — it treats '\t' as a single-width
+/// glyph while the terminal may jump to the next tab stop, causing width
+/// mismatches and leftover characters when redrawing. Replace with spaces.
+fn expand_tabs(raw: &str) -> String {
+    raw.replace('\t', "    ")
+}
+
 #[derive(Debug, Default)]
 pub struct SettingsState {
     pub lines: Vec<String>,
@@ -181,6 +421,23 @@ impl SettingsState {
         }
     }
 
+    /// Moves the cursor to the first line.
+    pub fn cursor_top(&mut self) {
+        self.cursor = 0;
+        self.ensure_cursor_visible();
+    }
+
+    /// Moves the cursor to the last visible line.
+    pub fn cursor_bottom(&mut self) {
+        if let Some(last) = (0..self.lines.len())
+            .rev()
+            .find(|&i| self.is_line_visible(i))
+        {
+            self.cursor = last;
+            self.ensure_cursor_visible();
+        }
+    }
+
     pub fn cursor_page_down(&mut self) {
         let page = (self.viewport_height as usize).max(1);
         for _ in 0..page {
@@ -345,6 +602,12 @@ pub struct EditState {
     /// Cached dirty flag. `None` means the cache is stale and must be recomputed.
     /// Using `Cell` allows `is_dirty()` to keep `&self` (needed for Debug and draw).
     pub(crate) dirty_cache: Cell<Option<bool>>,
+    /// When the buffer first changed after the last draft write. `None`
+    /// means the autosaved draft is up to date.
+    pub(crate) draft_changed_at: Option<Instant>,
+    /// First line shown in the split preview, kept in step with the
+    /// editor's own scrolling.
+    pub(crate) preview_top: usize,
 }
 
 impl std::fmt::Debug for EditState {
@@ -369,6 +632,17 @@ impl EditState {
         dirty
     }
 
+    /// Returns the buffer as it would be written to disk, restoring the
+    /// original trailing newline.
+    pub fn buffer_text(&self) -> String {
+        let joined = self.textarea.lines().join("\n");
+        if self.had_trailing_newline {
+            format!("{joined}\n")
+        } else {
+            joined
+        }
+    }
+
     /// Invalidate the dirty cache. Must be called after any textarea mutation.
     pub fn invalidate_dirty_cache(&self) {
         self.dirty_cache.set(None);
@@ -387,7 +661,31 @@ pub struct App {
     pub text_input: super::text_input::TextInput,
     pub status_message: Option<String>,
     pub library: Option<SnippetLibrary>,
+    /// Project library loaded after the global one into `library`, found
+    /// from the selected root. `None` without roots.
+    pub project_library: Option<PathBuf>,
+    /// Index into the whole library, even while a filter hides some
+    /// snippets, so actions always reach the snippet shown as selected.
     pub library_selected: usize,
+    /// Narrows the Library list to snippets whose title, content or a tag contains
+    /// it, ignoring case. Empty shows every snippet.
+    pub library_filter: String,
+    /// Whether the Library list is grouped by the project snippets came from.
+    pub library_grouped: bool,
+    /// Projects whose snippets are hidden in the grouped Library list.
+    pub library_collapsed: HashSet<String>,
+    /// Project header under the Library cursor, when the cursor is on a
+    /// header rather than a snippet.
+    pub library_project: Option<String>,
+    /// Whether the Library lists archived snippets instead of the others.
+    pub library_archive: bool,
+    /// Snippets marked with Space for a bulk delete, export or tag, by
+    /// index into the whole library.
+    pub library_marked: BTreeSet<usize>,
+    /// Library states the last changes replaced, most recent last.
+    pub(crate) library_undo: Vec<LibraryUndo>,
+    /// Library states undone, most recent last.
+    pub(crate) library_redo: Vec<LibraryUndo>,
     pub settings_state: SettingsState,
     pub settings_collection: Option<SettingsCollection>,
     pub edit_state: Option<EditState>,
@@ -395,11 +693,121 @@ pub struct App {
     /// When editing a library snippet, tracks the index being edited.
     pub editing_snippet_index: Option<usize>,
     pub theme: Theme,
+    /// What the terminal can display; drawing falls back to ASCII, fewer
+    /// colours and no italics where it cannot.
+    pub capabilities: Capabilities,
+    /// Scanned roots backing `tree_items`; kept so the tree can be rebuilt.
+    pub(crate) roots: Vec<SourceRoot>,
+    /// Modal picker overlay, open while in `Mode::Picker`.
+    pub picker: Option<super::picker::Picker>,
+    /// Path of a file being created in the editor that does not exist on disk yet.
+    pub new_file_path: Option<PathBuf>,
+    /// Modal diff preview, open while in `Mode::DiffPreview`.
+    pub diff_preview: Option<super::diff_preview::DiffPreview>,
+    /// Context budget dashboard, open while in `Mode::Budget`.
+    pub budget_view: Option<super::budget::BudgetView>,
+    /// Simulated session, open while in `Mode::Session`.
+    pub session_view: Option<super::session::SessionView>,
+    /// Trim checklist, open while in `Mode::Trim`.
+    pub trim_view: Option<super::trim::TrimView>,
+    /// Merge of two files, open while in `Mode::Merge`.
+    pub merge_view: Option<super::merge::MergeView>,
+    /// Journal entries, open while in `Mode::History`.
+    pub history_view: Option<super::journal::HistoryView>,
+    /// File to hand to `$EDITOR` before the next frame is drawn.
+    pub external_edit: Option<super::external::ExternalEdit>,
+    /// The external tool running on a file, present until it is done.
+    pub running_tool: Option<RunningTool>,
+    /// jigolo's config directory, home of the backup store. `None` disables
+    /// backups; unit tests start with `None` so they never touch `$HOME`.
+    pub data_dir: Option<PathBuf>,
+    /// The user's home directory, where migrated `CLAUDE.local.md` content
+    /// goes. Unit tests start with `None` so they never touch `$HOME`.
+    pub home_dir: Option<PathBuf>,
+    /// Configuration the app was started with.
+    pub config: Config,
+    /// Lint findings for the file shown in the content pane.
+    pub findings: Vec<Finding>,
+    /// Why the file shown in the content pane cannot be written, if it
+    /// cannot.
+    pub read_only: Option<ReadOnly>,
+    /// Key bindings consulted by `handle_key_event`.
+    pub keymap: KeyMap,
+    /// Keys of a sequence typed so far, such as the first `g` of `g g`,
+    /// and when the last of them was pressed.
+    pub pending_keys: Vec<KeyEvent>,
+    pub(crate) pending_since: Option<Instant>,
+    /// Whether files are nested under the git repository they belong to.
+    pub group_by_repo: bool,
+    /// Fuzzy file finder overlay, open while in `Mode::FileFinder`.
+    pub finder: Option<super::finder::FileFinder>,
+    /// Full-text search overlay, open while in `Mode::GlobalSearch`.
+    pub search: Option<super::search::GlobalSearch>,
+    /// How the roots were scanned, reused by rescans.
+    pub scan_options: ScanOptions,
+    /// The scan filling in the roots after startup, while it runs.
+    pub background_scan: Option<BackgroundScan>,
+    /// The lint of every discovered file, while it runs.
+    pub background_lint: Option<Receiver<Vec<(PathBuf, Finding)>>>,
+    /// Findings per file from the last background lint, shown as badges
+    /// in the tree.
+    pub lint_counts: HashMap<PathBuf, FindingCounts>,
+    /// Files written or changed on disk since the last tick, to lint again.
+    pub relint_files: Vec<PathBuf>,
+    /// Files linted again by the running lint, whose findings are reported
+    /// when they changed.
+    pub linted_files: Vec<PathBuf>,
+    /// Polls the roots for files changed on disk. `None` in unit tests so
+    /// ticks never touch the filesystem unless a test opts in.
+    pub watcher: Option<FileWatcher>,
+    /// Files most recently shown in the content pane, newest first.
+    pub recent_files: Vec<PathBuf>,
+    /// Positions jumped away from, for `Ctrl-O` and `Ctrl-I`.
+    pub jumps: JumpList,
+    /// Library snippets put into files this session, newest first, for `&`
+    /// and the quick actions menu.
+    pub insert_history: Vec<SnippetInsert>,
+    /// Whether the heading outline is shown beside the content.
+    pub outline_visible: bool,
+    /// Whether the rendered Markdown is shown beside the raw text, in the
+    /// content pane and in the editor.
+    pub split_preview: bool,
+    /// Whether HTML comments are left out of the content pane.
+    pub hide_comments: bool,
+    /// Whether long lines in the content pane wrap onto the rows below
+    /// instead of being cut at the border.
+    pub wrap_lines: bool,
+    /// Whether the content pane starts with where the shown file sits in
+    /// the load order.
+    pub show_load_order: bool,
+    /// Captured during draw() — where the panes that take clicks are.
+    pub(crate) pane_areas: PaneAreas,
+    /// Every warning shown in the status bar this session, oldest first,
+    /// printed again on exit.
+    pub warnings: Vec<String>,
+    /// Messages shown in the status bar this session, oldest first.
+    pub messages: VecDeque<LoggedMessage>,
+    /// The status message last added to `messages`, so a message is
+    /// logged once however many frames show it.
+    pub(crate) logged_status: Option<String>,
+    /// The message log, open while in `Mode::Messages`.
+    pub messages_view: Option<MessagesView>,
+    /// Lines chosen to move to another file while their heading is typed.
+    pub(crate) pending_line_move: Option<PendingLineMove>,
+    /// Section chosen for extraction while its new path is typed.
+    pub(crate) pending_extraction: Option<PendingExtraction>,
+    /// State saved by the last run, waiting for the scan to find its file.
+    pub(crate) pending_restore: Option<UiState>,
+    /// Imports expanded in place in the content pane, as pairs of the
+    /// importing file and the imported one.
+    pub(crate) expanded_imports: HashSet<(PathBuf, PathBuf)>,
 }
 
 impl App {
-    pub fn new(roots: Vec<SourceRoot>, config: &crate::config::Config) -> Self {
-        let tree_items = build_tree_items(&roots);
+    pub fn new(roots: Vec<SourceRoot>, config: &Config) -> Self {
+        let theme = Theme::named(config.theme.as_deref());
+        let tree_items =
+            build_tree_items(&roots, false, config.tokenizer(), &HashMap::new(), &theme);
         let mut tree_state = TreeState::default();
 
         // Open all root nodes by default
@@ -413,7 +821,7 @@ impl App {
             if let Some(first_file) = first_root.files.first() {
                 tree_state.select(vec![
                     first_root.path.display().to_string(),
-                    first_file.display().to_string(),
+                    first_file.path.display().to_string(),
                 ]);
             } else {
                 tree_state.select_first();
@@ -433,36 +841,251 @@ impl App {
             text_input: super::text_input::TextInput::default(),
             status_message: None,
             library: None,
+            project_library: None,
             library_selected: 0,
+            library_filter: String::new(),
+            library_grouped: false,
+            library_collapsed: HashSet::new(),
+            library_project: None,
+            library_archive: false,
+            library_marked: BTreeSet::new(),
+            library_undo: Vec::new(),
+            library_redo: Vec::new(),
             settings_state: SettingsState::default(),
             settings_collection: None,
             edit_state: None,
             compose_state: None,
             editing_snippet_index: None,
-            theme: match config.theme.as_deref() {
-                Some("light") => Theme::light(),
-                _ => Theme::dark(),
+            theme,
+            capabilities: if cfg!(test) {
+                Capabilities::default()
+            } else {
+                Capabilities::detect()
+            },
+            roots,
+            picker: None,
+            new_file_path: None,
+            diff_preview: None,
+            budget_view: None,
+            external_edit: None,
+            running_tool: None,
+            trim_view: None,
+            merge_view: None,
+            history_view: None,
+            session_view: None,
+            data_dir: if cfg!(test) {
+                None
+            } else {
+                crate::config::config_dir()
             },
+            home_dir: if cfg!(test) {
+                None
+            } else {
+                std::env::var_os("HOME").map(PathBuf::from)
+            },
+            config: config.clone(),
+            findings: Vec::new(),
+            read_only: None,
+            keymap: KeyMap::default(),
+            pending_keys: Vec::new(),
+            pending_since: None,
+            group_by_repo: false,
+            finder: None,
+            search: None,
+            scan_options: ScanOptions::default(),
+            background_scan: None,
+            background_lint: None,
+            lint_counts: HashMap::new(),
+            relint_files: Vec::new(),
+            linted_files: Vec::new(),
+            watcher: if cfg!(test) {
+                None
+            } else {
+                Some(FileWatcher::new(config.watch_debounce()))
+            },
+            recent_files: Vec::new(),
+            jumps: JumpList::default(),
+            insert_history: Vec::new(),
+            outline_visible: false,
+            split_preview: false,
+            hide_comments: false,
+            wrap_lines: false,
+            show_load_order: true,
+            pane_areas: PaneAreas::default(),
+            warnings: Vec::new(),
+            messages: VecDeque::new(),
+            logged_status: None,
+            messages_view: None,
+            pending_line_move: None,
+            pending_extraction: None,
+            pending_restore: None,
+            expanded_imports: HashSet::new(),
         };
 
+        if config.group_by_repo.unwrap_or(false) {
+            app.set_repo_grouping(true);
+        }
         app.load_selected_content();
+        if let Some(overrides) = &config.keymap {
+            let (keymap, errors) = KeyMap::with_overrides(overrides);
+            app.keymap = keymap;
+            if let Some(first) = errors.first() {
+                let more = match errors.len() {
+                    1 => String::new(),
+                    n => format!(" (and {} more)", n - 1),
+                };
+                app.status_message = Some(format!("Keymap config: {first}{more}"));
+            }
+        }
+        if let Some(errors) = app.apply_color_overrides()
+            && app.status_message.is_none()
+        {
+            app.status_message = Some(format!("Colors config: {errors}"));
+        }
+        if app.config.colors.is_some() {
+            // The tree was built before the overrides recoloured the theme.
+            app.rebuild_tree();
+        }
         app
     }
 
+    /// Recolours the theme from the config's `[colors]` table. Returns the
+    /// problems found, summarised, if any.
+    pub(crate) fn apply_color_overrides(&mut self) -> Option<String> {
+        let colors = self.config.colors.as_ref()?;
+        let (theme, errors) = self.theme.clone().with_overrides(colors);
+        self.theme = theme;
+        let first = errors.first()?;
+        Some(match errors.len() {
+            1 => first.clone(),
+            n => format!("{first} (and {} more)", n - 1),
+        })
+    }
+
     pub fn run(&mut self, terminal: &mut DefaultTerminal) -> io::Result<()> {
         while !self.exit {
             terminal.draw(|frame| self.draw(frame))?;
-            self.handle_events()?;
+            // Poll with a timeout so periodic work runs even without input.
+            // Input that queued up meanwhile, such as a paste, is handled
+            // before the next redraw rather than one frame per key.
+            let mut timeout = TICK_RATE;
+            let mut handled = 0;
+            while handled < MAX_EVENTS_PER_FRAME && event::poll(timeout)? {
+                self.handle_events()?;
+                handled += 1;
+                if self.exit || self.external_edit.is_some() {
+                    break;
+                }
+                timeout = Duration::ZERO;
+            }
+            if let Some(edit) = self.external_edit.take() {
+                self.run_external_edit(terminal, edit)?;
+            }
+            self.tick(Instant::now());
+            self.show_warnings(crate::warnings::take());
+            self.log_status();
         }
         Ok(())
     }
 
+    /// Shows the newest of `warnings` in the status bar, with a count of
+    /// the others, and keeps them all for the exit.
+    pub(crate) fn show_warnings(&mut self, warnings: Vec<String>) {
+        let Some(newest) = warnings.last() else {
+            return;
+        };
+        self.status_message = Some(match warnings.len() {
+            1 => format!("Warning: {newest}"),
+            count => format!("Warning: {newest} (and {} more)", count - 1),
+        });
+        self.warnings.extend(warnings);
+    }
+
+    /// Runs periodic background work between input events.
+    pub(crate) fn tick(&mut self, now: Instant) {
+        self.autosave_draft(now);
+        if self.is_scanning() {
+            self.receive_scan_results();
+        } else {
+            self.watch_files(now);
+        }
+        self.start_due_lint();
+        self.receive_lint_results();
+        self.receive_tool_result();
+        if self
+            .pending_since
+            .is_some_and(|since| now.duration_since(since) >= SEQUENCE_TIMEOUT)
+        {
+            self.flush_pending_keys();
+        }
+    }
+
     pub(crate) fn help_line(&self) -> Line<'static> {
         let key_style = self.theme.help_key;
         let desc_style = self.theme.help_desc;
         let sep = Span::styled("  ", desc_style);
 
+        let pending: Vec<String> = self
+            .pending_keys
+            .iter()
+            .map(|key| KeyBinding::from(key).to_string())
+            .collect();
+        let pending = pending.join(" ");
         let pairs: Vec<(&str, &str)> = match self.screen {
+            _ if !self.pending_keys.is_empty() => {
+                vec![(pending.as_str(), "…"), ("Esc", "Cancel")]
+            }
+            _ if self.mode == Mode::Picker => {
+                vec![("↑/↓", "Navigate"), ("Enter", "Choose"), ("Esc", "Cancel")]
+            }
+            _ if self.mode == Mode::FileFinder || self.mode == Mode::GlobalSearch => {
+                vec![("↑/↓", "Navigate"), ("Enter", "Open"), ("Esc", "Cancel")]
+            }
+            _ if self.mode == Mode::DiffPreview => {
+                vec![
+                    ("↑/↓", "Scroll"),
+                    ("y", "Accept"),
+                    ("n", "Reject"),
+                    ("Esc", "Cancel"),
+                ]
+            }
+            _ if self.mode == Mode::Budget => vec![("↑/↓", "Scroll"), ("Esc", "Close")],
+            _ if self.mode == Mode::History => vec![("↑/↓", "Scroll"), ("Esc", "Close")],
+            _ if self.mode == Mode::Messages => vec![("↑/↓", "Scroll"), ("Esc", "Close")],
+            _ if self.mode == Mode::Session => {
+                vec![
+                    ("↑/↓", "Scroll"),
+                    ("Y", "Copy"),
+                    ("w", "Write to file"),
+                    ("Esc", "Close"),
+                ]
+            }
+            _ if self.mode == Mode::SessionExportPath => {
+                vec![("Enter", "Write"), ("Esc", "Back")]
+            }
+            _ if self.mode == Mode::Merge => {
+                vec![
+                    ("↑/↓", "Navigate"),
+                    ("a/b", "Take A/B"),
+                    ("B", "Both"),
+                    ("x", "Drop"),
+                    ("w", "Write to A"),
+                    ("n", "New file"),
+                    ("Esc", "Cancel"),
+                ]
+            }
+            _ if self.mode == Mode::MergePath => {
+                vec![("Enter", "Write"), ("Esc", "Back")]
+            }
+            _ if self.mode == Mode::Trim => {
+                vec![
+                    ("↑/↓", "Navigate"),
+                    ("Space", "Check"),
+                    ("d", "Remove"),
+                    ("a", "Archive"),
+                    ("Esc", "Close"),
+                ]
+            }
             Screen::Compose if self.mode == Mode::ExportPath => {
                 vec![("Enter", "Export"), ("Esc", "Cancel")]
             }
@@ -490,6 +1113,7 @@ impl App {
             Screen::Settings => {
                 vec![
                     ("e", "Edit"),
+                    ("o", "$EDITOR"),
                     ("m", "Merge"),
                     ("↑/↓", "Scroll"),
                     ("←/→", "Fold"),
@@ -498,26 +1122,79 @@ impl App {
                 ]
             }
             Screen::Files => match self.mode {
+                Mode::Normal if self.active_pane == Pane::Outline => {
+                    vec![
+                        ("↑/↓", "Heading"),
+                        ("Enter", "Go to section"),
+                        ("Tab", "Files"),
+                        ("o", "Hide outline"),
+                    ]
+                }
                 Mode::Normal if self.active_pane == Pane::Content => {
                     vec![
                         ("q", "Quit"),
                         ("Tab", "Files"),
                         ("↑/↓", "Scroll"),
                         ("e", "Edit"),
+                        ("E", "$EDITOR"),
                         ("v", "Select"),
+                        (".", "Actions"),
+                        ("x", "Extract"),
+                        ("i", "Expand import"),
+                        ("Y", "Copy"),
+                        ("F", "Load all"),
+                        ("o", "Outline"),
+                        ("p", "Split preview"),
+                        ("c", "Comments"),
+                        ("w", "Wrap"),
+                        ("L", "Load order"),
+                        ("/", "Search"),
                         ("T", "Theme"),
                     ]
                 }
+                Mode::Normal
+                    if self
+                        .selected_file()
+                        .is_some_and(|file| canonical_name_for(&file).is_some()) =>
+                {
+                    vec![
+                        ("q", "Quit"),
+                        ("Tab", "Content"),
+                        ("↑/↓", "Navigate"),
+                        ("N", "Fix name"),
+                        ("R", "Move"),
+                        ("D", "Delete"),
+                        ("/", "Search"),
+                    ]
+                }
                 Mode::Normal => {
                     vec![
                         ("q", "Quit"),
                         ("Tab", "Content"),
                         ("↑/↓", "Navigate"),
+                        ("n", "New"),
+                        ("R", "Move"),
+                        ("D", "Delete"),
+                        ("m", "Merge"),
+                        ("b", "Backups"),
+                        ("H", "History"),
+                        ("!", "Next finding"),
+                        ("M", "Migrate local"),
+                        ("g", "Group by repo"),
+                        ("F5", "Rescan"),
+                        ("S", "Session"),
+                        ("/", "Search"),
                         ("T", "Theme"),
                     ]
                 }
                 Mode::VisualSelect => {
-                    vec![("↑/↓", "Extend"), ("s", "Save"), ("Esc", "Cancel")]
+                    vec![
+                        ("↑/↓", "Extend"),
+                        ("y", "Copy"),
+                        ("s", "Save"),
+                        ("m", "Move"),
+                        ("Esc", "Cancel"),
+                    ]
                 }
                 Mode::TitleInput => {
                     vec![("Enter", "Save"), ("Esc", "Cancel")]
@@ -528,16 +1205,91 @@ impl App {
                 Mode::RenameInput | Mode::ExportPath => {
                     vec![("Enter", "Export"), ("Esc", "Cancel")]
                 }
+                Mode::NewFilePath => {
+                    vec![("Enter", "Next"), ("Esc", "Cancel")]
+                }
+                Mode::MovePath | Mode::MoveHeading => {
+                    vec![("Enter", "Move"), ("Esc", "Cancel")]
+                }
+                Mode::ExtractPath => {
+                    vec![("Enter", "Extract"), ("Esc", "Cancel")]
+                }
+                Mode::SessionDir => {
+                    vec![("Enter", "Simulate"), ("Esc", "Cancel")]
+                }
+                Mode::Picker
+                | Mode::DiffPreview
+                | Mode::Budget
+                | Mode::History
+                | Mode::Messages
+                | Mode::Session
+                | Mode::SessionExportPath
+                | Mode::Merge
+                | Mode::MergePath
+                | Mode::Trim
+                | Mode::FileFinder
+                | Mode::GlobalSearch
+                | Mode::LibraryFilter
+                | Mode::BundleExportPath
+                | Mode::BundleImportPath
+                | Mode::TagInput => {
+                    Vec::new() // handled above
+                }
             },
             Screen::Library if self.mode == Mode::RenameInput => {
                 vec![("Enter", "Save"), ("Esc", "Cancel")]
             }
+            Screen::Library if self.mode == Mode::LibraryFilter => {
+                vec![("Enter", "Keep filter"), ("Esc", "Clear")]
+            }
+            Screen::Library if self.mode == Mode::BundleExportPath => {
+                vec![("Enter", "Export"), ("Esc", "Cancel")]
+            }
+            Screen::Library if self.mode == Mode::BundleImportPath => {
+                vec![("Enter", "Import"), ("Esc", "Cancel")]
+            }
+            Screen::Library if self.mode == Mode::TagInput => {
+                vec![("Enter", "Tag"), ("Esc", "Cancel")]
+            }
+            Screen::Library if !self.library_marked.is_empty() => {
+                vec![
+                    ("Space", "Mark"),
+                    ("d", "Remove marked"),
+                    ("w", "Export marked"),
+                    ("t", "Tag marked"),
+                    ("Esc", "Clear marks"),
+                ]
+            }
+            Screen::Library if self.library_archive => {
+                vec![
+                    ("↑/↓", "Navigate"),
+                    ("a", "Restore"),
+                    ("d", "Delete"),
+                    ("/", "Filter"),
+                    ("A", "Library"),
+                    ("Esc", "Back"),
+                ]
+            }
             Screen::Library => {
                 vec![
                     ("↑/↓", "Navigate"),
+                    ("Space", "Mark"),
                     ("e", "Edit"),
                     ("r", "Rename"),
                     ("d", "Delete"),
+                    ("a", "Archive"),
+                    ("A", "Archived"),
+                    ("m", "Memory"),
+                    ("i", "Insert"),
+                    ("p", "Append to file"),
+                    ("o", "Source"),
+                    ("f", "Format"),
+                    ("t", "Tag"),
+                    ("/", "Filter"),
+                    ("g", "Group"),
+                    ("w", "Export"),
+                    ("I", "Import"),
+                    ("u", "Undo"),
                     ("q", "Quit"),
                 ]
             }
@@ -556,10 +1308,8 @@ impl App {
 
     pub(crate) fn draw(&mut self, frame: &mut Frame) {
         // Vertical layout: tab_bar + main area + optional input/status bar + help bar
-        let has_input_or_status = self.mode == Mode::TitleInput
-            || self.mode == Mode::RenameInput
-            || self.mode == Mode::ExportPath
-            || self.status_message.is_some();
+        let status = self.status_text().map(str::to_string);
+        let has_input_or_status = self.is_text_input_mode() || status.is_some();
 
         let mut constraints = vec![Constraint::Length(1), Constraint::Min(3)];
         if has_input_or_status {
@@ -579,6 +1329,7 @@ impl App {
         self.draw_tab_bar(frame, tab_area);
 
         // Main content area — route by screen
+        self.pane_areas = PaneAreas::default();
         match self.screen {
             Screen::Files => self.draw_files_screen(frame, main_area),
             Screen::Settings => self.draw_settings_screen(frame, main_area),
@@ -589,13 +1340,24 @@ impl App {
         // Input/status bar (when active, Files screen only)
         if has_input_or_status {
             let bar_area = vertical[2];
-            if self.mode == Mode::TitleInput
-                || self.mode == Mode::RenameInput
-                || self.mode == Mode::ExportPath
-            {
+            if self.is_text_input_mode() {
                 let bar_title = match self.mode {
                     Mode::RenameInput => "Rename snippet",
                     Mode::ExportPath => "Export path",
+                    Mode::NewFilePath => "New CLAUDE.md in directory (relative to root)",
+                    Mode::MovePath => "Move to (relative to root)",
+                    Mode::MoveHeading => "Heading over the moved lines (optional)",
+                    Mode::ExtractPath => "Extract the section to (relative to the file)",
+                    Mode::LibraryFilter => "Filter snippets by title, content or tag",
+                    Mode::BundleExportPath if !self.library_marked.is_empty() => {
+                        "Export marked snippets to (.toml or .json)"
+                    }
+                    Mode::BundleExportPath => "Export library to (.toml or .json)",
+                    Mode::BundleImportPath => "Import snippets from",
+                    Mode::TagInput => "Tag snippets with",
+                    Mode::SessionDir => "Simulate a session started in",
+                    Mode::SessionExportPath => "Write the session to",
+                    Mode::MergePath => "Write the merged file to",
                     _ => "Snippet title",
                 };
                 let input_widget = Paragraph::new(self.text_input.text()).block(
@@ -608,17 +1370,83 @@ impl App {
                 let cursor_x = bar_area.x + 1 + self.text_input.cursor() as u16;
                 let cursor_y = bar_area.y + 1;
                 frame.set_cursor_position((cursor_x, cursor_y));
-            } else if let Some(msg) = &self.status_message {
+            } else if let Some(msg) = &status {
                 let status_widget = Paragraph::new(msg.as_str())
+                    .style(self.message_style(MessageLevel::of(msg)))
                     .block(Block::default().borders(Borders::ALL).title("Status"));
                 frame.render_widget(status_widget, bar_area);
             }
         }
 
+        // Modal overlays draw last so they sit above the screen content
+        if self.mode == Mode::Picker {
+            self.draw_picker(frame, main_area);
+        }
+        if self.mode == Mode::DiffPreview {
+            self.draw_diff_preview(frame, main_area);
+        }
+        if self.mode == Mode::Budget {
+            self.draw_budget(frame, main_area);
+        }
+        if self.mode == Mode::History {
+            self.draw_history(frame, main_area);
+        }
+        if self.mode == Mode::Messages {
+            self.draw_messages(frame, main_area);
+        }
+        if matches!(self.mode, Mode::Session | Mode::SessionExportPath) {
+            self.draw_session(frame, main_area);
+        }
+        if self.mode == Mode::Trim {
+            self.draw_trim(frame, main_area);
+        }
+        if matches!(self.mode, Mode::Merge | Mode::MergePath) {
+            self.draw_merge(frame, main_area);
+        }
+        if self.mode == Mode::FileFinder {
+            self.draw_file_finder(frame, main_area);
+        }
+        if self.mode == Mode::GlobalSearch {
+            self.draw_global_search(frame, main_area);
+        }
+
         // Help bar (always visible, last slot)
         let help_area = vertical[vertical.len() - 1];
         let help = Paragraph::new(self.help_line());
         frame.render_widget(help, help_area);
+
+        degrade(frame.buffer_mut(), self.capabilities);
+    }
+
+    /// What the status bar shows: the last message, or else the size of the
+    /// file in the content pane.
+    pub(crate) fn status_text(&self) -> Option<&str> {
+        self.status_message.as_deref().or_else(|| {
+            (self.screen == Screen::Files)
+                .then_some(self.content.summary.as_deref())
+                .flatten()
+        })
+    }
+
+    /// Returns true for modes that show the single-line input bar.
+    fn is_text_input_mode(&self) -> bool {
+        matches!(
+            self.mode,
+            Mode::TitleInput
+                | Mode::RenameInput
+                | Mode::ExportPath
+                | Mode::NewFilePath
+                | Mode::MovePath
+                | Mode::MoveHeading
+                | Mode::ExtractPath
+                | Mode::LibraryFilter
+                | Mode::BundleExportPath
+                | Mode::BundleImportPath
+                | Mode::TagInput
+                | Mode::SessionDir
+                | Mode::SessionExportPath
+                | Mode::MergePath
+        )
     }
 
     fn draw_tab_bar(&self, frame: &mut Frame, area: ratatui::layout::Rect) {
@@ -643,27 +1471,276 @@ impl App {
     }
 
     pub(crate) fn load_selected_content(&mut self) {
-        let selected = self.tree_state.selected();
-        if selected.len() < 2 {
-            self.content.text = None;
+        let Some(path) = self.selected_file() else {
+            if let Some(missing) = self
+                .tree_state
+                .selected()
+                .last()
+                .and_then(|id| missing_import_path(id))
+            {
+                self.status_message = Some(format!("Broken import: {missing} does not exist."));
+            }
+            self.content.set_text(None);
+            self.content.truncated_from = None;
+            self.content.summary = None;
+            self.content.load_order = None;
+            self.findings.clear();
+            self.read_only = None;
             self.content.scroll = 0;
             self.content.cursor = 0;
             self.content.visual_anchor = None;
             return;
+        };
+        self.note_recent(&path);
+        self.load_file_content(&path);
+        if self.content.hidden_comment_lines > 0 {
+            self.status_message = Some(hidden_comments_message(self.content.hidden_comment_lines));
+        } else {
+            self.status_message.clone_from(&self.content.summary);
         }
+    }
 
-        let file_path = selected.last().cloned();
-        if let Some(path_str) = file_path {
-            self.load_file_content(&PathBuf::from(path_str));
+    /// Every discovered file, in tree order of their roots.
+    pub(crate) fn discovered_files(&self) -> Vec<PathBuf> {
+        self.roots
+            .iter()
+            .flat_map(|root| root.file_paths().cloned())
+            .collect()
+    }
+
+    /// Returns the selected file, or the imported file when an import under
+    /// it is selected. `None` when a root, repo group or missing import is
+    /// selected.
+    pub(crate) fn selected_file(&self) -> Option<PathBuf> {
+        let selected = self.tree_state.selected();
+        let nested = self.group_by_repo || selected.get(1).is_some_and(|id| is_file_group_id(id));
+        let file_depth = if nested { 3 } else { 2 };
+        if selected.len() < file_depth {
+            return None;
         }
+        let id = selected.last()?;
+        (!is_missing_import_id(id)).then(|| PathBuf::from(id))
     }
 
+    /// Shows `path` in the content pane. Files over the configured preview
+    /// size are cut short until [`load_full_content`](Self::load_full_content).
     pub(crate) fn load_file_content(&mut self, path: &Path) {
-        let text = match fs::read_to_string(path) {
-            Ok(text) => text,
-            Err(err) => format!("Error reading {}: {err}", path.display()),
+        let max_bytes = self.config.preview_max_kb().saturating_mul(1024);
+        match read_capped(path, max_bytes) {
+            Ok(read) => {
+                self.read_only = check_writable(path).err();
+                let tokens = if read.truncated {
+                    self.config.tokenizer().count_file(path)
+                } else {
+                    self.config.tokenizer().count(&read.text)
+                };
+                let summary = size_summary(
+                    read.text.lines().count(),
+                    read.truncated,
+                    read.total_bytes,
+                    tokens,
+                );
+                let (text, hidden) = self.comments_filtered(read.text.clone());
+                let mut findings = self.lint_shown(path, &read.text, &text);
+                let (text, inlined) = self.imports_inlined(path, text, &mut findings);
+                self.findings = findings;
+                self.content.load_text(text);
+                self.content.hidden_comment_lines = hidden;
+                self.content.inlined_imports = inlined;
+                self.content.summary = Some(summary);
+                self.refresh_discovered_file(path);
+                self.content.load_order = self.show_load_order.then(|| load_order_line(path));
+                if read.truncated {
+                    self.content.truncated_from = Some(read.total_bytes);
+                }
+            }
+            Err(err) => {
+                self.findings.clear();
+                self.read_only = None;
+                self.content
+                    .load_text(format!("Error reading {}: {err}", path.display()));
+            }
+        }
+    }
+
+    /// Replaces a size-capped preview with the whole file, keeping the
+    /// cursor and scroll position.
+    pub(crate) fn load_full_content(&mut self) {
+        if self.content.truncated_from.is_none() {
+            return;
+        }
+        let path = PathBuf::from(self.current_source_path());
+        match fs::read_to_string(&path) {
+            Ok(text) => {
+                self.content.summary = Some(size_summary(
+                    text.lines().count(),
+                    false,
+                    text.len() as u64,
+                    self.config.tokenizer().count(&text),
+                ));
+                let (shown, hidden) = self.comments_filtered(text.clone());
+                let mut findings = self.lint_shown(&path, &text, &shown);
+                let (text, inlined) = self.imports_inlined(&path, shown, &mut findings);
+                self.findings = findings;
+                self.content.set_text(Some(expand_tabs(&text)));
+                self.content.hidden_comment_lines = hidden;
+                self.content.inlined_imports = inlined;
+                self.content.truncated_from = None;
+            }
+            Err(err) => {
+                self.status_message = Some(format!("Error reading {}: {err}", path.display()));
+            }
+        }
+    }
+
+    /// Lints `shown`, the text of `path` as the content pane shows it, so
+    /// findings point at the lines on screen. Rule levels and suppression
+    /// comments apply as for `jigolo lint`, the comments read from `raw`,
+    /// the file as it is on disk.
+    fn lint_shown(&self, path: &Path, raw: &str, shown: &str) -> Vec<Finding> {
+        let mut findings = lint_file(path, shown);
+        let dir = path.parent().unwrap_or(path);
+        apply_rule_levels(&mut findings, &rule_levels_for(dir, &self.config), raw);
+        findings
+    }
+
+    /// Rebuilds `tree_items` from `roots`, keeping the current selection.
+    pub(crate) fn rebuild_tree(&mut self) {
+        self.tree_items = build_tree_items(
+            &self.roots,
+            self.group_by_repo,
+            self.config.tokenizer(),
+            &self.lint_counts,
+            &self.theme,
+        );
+    }
+
+    /// Turns nesting files under their git repository on or off. Every group
+    /// starts expanded and the selected file stays selected.
+    pub(crate) fn set_repo_grouping(&mut self, on: bool) {
+        let selected_file = self.selected_file();
+        self.group_by_repo = on;
+        self.rebuild_tree();
+        if on {
+            for root in &self.tree_items {
+                for group in root.children() {
+                    self.tree_state
+                        .open(vec![root.identifier().clone(), group.identifier().clone()]);
+                }
+            }
+        }
+        let selection = selected_file
+            .and_then(|file| self.tree_path(&file))
+            .or_else(|| {
+                self.tree_state
+                    .selected()
+                    .first()
+                    .cloned()
+                    .map(|root| vec![root])
+            });
+        if let Some(selection) = selection {
+            self.tree_state.select(selection);
+        }
+    }
+
+    /// Returns the tree identifiers leading to `file`, or `None` if it is
+    /// not under any root.
+    pub(crate) fn tree_path(&self, file: &Path) -> Option<Vec<TreeId>> {
+        let root = self
+            .roots
+            .iter()
+            .filter(|root| file.starts_with(&root.path))
+            .max_by_key(|root| root.path.as_os_str().len())?;
+        let mut path = vec![root.path.display().to_string()];
+        if let Some(context) = root.context_files.iter().find(|c| c.path == file) {
+            path.push(context_group_id(context.kind));
+        } else if root.misnamed_files.iter().any(|m| m == file) {
+            path.push(MISNAMED_GROUP.to_string());
+        } else if self.group_by_repo {
+            path.push(repo_group_id(find_git_repo(file).as_ref()));
+        }
+        path.push(file.display().to_string());
+        Some(path)
+    }
+
+    /// Adds a newly created file to the root that contains it, rebuilds the
+    /// tree and selects the file. Files outside every root are ignored.
+    pub(crate) fn register_file(&mut self, path: &Path) {
+        let Some(root) = self
+            .roots
+            .iter_mut()
+            .filter(|root| path.starts_with(&root.path))
+            .max_by_key(|root| root.path.as_os_str().len())
+        else {
+            return;
         };
-        self.content.load_text(text);
+        if !root.has_file(path) {
+            root.files.push(discovered_file(path.to_path_buf()));
+            root.files.sort_unstable();
+        }
+        self.rebuild_tree();
+        self.select_tree_file(path);
+    }
+
+    /// Whether a scan with the app's options picks up a file named like
+    /// `path`: a `CLAUDE.md` or `CLAUDE.local.md`, or a match of `--pattern`.
+    pub(crate) fn is_scanned_name(&self, path: &Path) -> bool {
+        let dir = path.parent().unwrap_or(path);
+        path.file_name()
+            .and_then(|name| name.to_str())
+            .is_some_and(|name| self.scan_options.rules(dir).matches_name(name))
+    }
+
+    /// Selects `file` in the tree, expanding the nodes above it. Returns
+    /// false if the file is not under any root.
+    pub(crate) fn select_tree_file(&mut self, file: &Path) -> bool {
+        let Some(selection) = self.tree_path(file) else {
+            return false;
+        };
+        for depth in 1..selection.len() {
+            self.tree_state.open(selection[..depth].to_vec());
+        }
+        self.tree_state.select(selection);
+        true
+    }
+
+    /// Reads the size, line count and modification time of `path` again,
+    /// if it is a discovered file that changed since, so they stay current.
+    fn refresh_discovered_file(&mut self, path: &Path) {
+        let Ok(metadata) = fs::metadata(path) else {
+            return;
+        };
+        for root in &mut self.roots {
+            if let Some(file) = root.files.iter_mut().find(|file| file.path == path)
+                && !file.is_current(&metadata)
+                && let Ok(read) = DiscoveredFile::read(path)
+            {
+                *file = read;
+            }
+        }
+    }
+
+    /// Removes a file that no longer exists from its root and rebuilds the
+    /// tree, selecting the root if the file was selected.
+    pub(crate) fn unregister_file(&mut self, path: &Path) {
+        for root in &mut self.roots {
+            root.files.retain(|f| f.path != path);
+        }
+        self.rebuild_tree();
+        if self.selected_file().is_some_and(|file| file == path) {
+            let root = self.tree_state.selected()[..1].to_vec();
+            self.tree_state.select(root);
+        }
+    }
+
+    /// Mode to return to when a picker or diff preview closes: the editor if
+    /// one is open (saving a snippet can raise a prompt), otherwise Normal.
+    pub(crate) fn mode_after_modal(&self) -> Mode {
+        if self.edit_state.is_some() {
+            Mode::Edit
+        } else {
+            Mode::Normal
+        }
     }
 
     pub(crate) fn reset_to_normal(&mut self) {
@@ -681,103 +1758,298 @@ impl App {
     }
 
     fn handle_events(&mut self) -> io::Result<()> {
-        if let Event::Key(key_event) = event::read()? {
-            self.handle_key_event(key_event);
+        match event::read()? {
+            // Windows reports releases too; only presses are acted on
+            Event::Key(key_event) if key_event.kind == KeyEventKind::Press => {
+                self.handle_key_event(key_event);
+            }
+            Event::Mouse(mouse_event) => self.handle_mouse_event(mouse_event),
+            _ => {}
         }
         Ok(())
     }
 
+    /// Translates a key press into an action via the keymap and applies it.
+    /// A key that starts a longer sequence waits for the rest of it.
     pub fn handle_key_event(&mut self, key_event: KeyEvent) {
-        // Clear transient status on any keypress
+        // Clear transient status on any keypress; it stays in the log.
+        self.log_status();
         self.status_message = None;
+        self.logged_status = None;
 
-        // Ctrl-C always exits regardless of mode
-        if key_event.code == KeyCode::Char('c')
-            && key_event.modifiers.contains(KeyModifiers::CONTROL)
-        {
-            self.exit = true;
+        if !self.pending_keys.is_empty() && key_event.code == KeyCode::Esc {
+            self.pending_keys.clear();
+            self.pending_since = None;
             return;
         }
-
-        // Screen switching and theme toggle only in Normal mode
-        if self.mode == Mode::Normal {
-            match key_event.code {
-                KeyCode::Char('1') => {
-                    self.screen = Screen::Files;
-                    return;
-                }
-                KeyCode::Char('2') => {
-                    self.switch_to_settings();
-                    return;
-                }
-                KeyCode::Char('3') => {
-                    self.enter_compose_screen();
-                    return;
-                }
-                KeyCode::Char('4') => {
-                    self.enter_library_screen();
-                    return;
-                }
-                KeyCode::Char('T') => {
-                    self.theme = self.theme.toggle();
-                    return;
-                }
-                _ => {}
+        let context = self.key_context();
+        let mut keys = std::mem::take(&mut self.pending_keys);
+        keys.push(key_event);
+        self.pending_since = None;
+        match self
+            .keymap
+            .resolve_keys(context, self.mode == Mode::Normal, &keys)
+        {
+            KeyResolution::Action(action) => self.dispatch(action),
+            KeyResolution::Pending => {
+                self.pending_keys = keys;
+                self.pending_since = Some(Instant::now());
             }
+            // The sequence broke off: the keys before act on their own,
+            // then the new key starts afresh.
+            KeyResolution::Unbound if keys.len() > 1 => {
+                keys.pop();
+                self.pending_keys = keys;
+                self.flush_pending_keys();
+                self.handle_key_event(key_event);
+            }
+            KeyResolution::Unbound => {}
         }
+    }
 
-        // Edit mode handles its own keys regardless of screen
-        if self.mode == Mode::Edit {
-            self.handle_edit_key(key_event);
-            return;
-        }
-
-        match self.screen {
-            Screen::Files => match self.mode {
-                Mode::Normal => self.handle_normal_key(key_event),
-                Mode::VisualSelect => self.handle_visual_select_key(key_event),
-                Mode::TitleInput => self.handle_title_input_key(key_event),
-                Mode::Edit => {}                           // handled above
-                Mode::RenameInput | Mode::ExportPath => {} // not used on Files screen
-            },
-            Screen::Settings => self.handle_settings_key(key_event),
-            Screen::Compose => match self.mode {
-                Mode::Normal => self.handle_compose_key(key_event),
-                Mode::ExportPath => self.handle_export_path_key(key_event),
-                _ => {}
-            },
-            Screen::Library => match self.mode {
-                Mode::Normal => self.handle_library_key(key_event),
-                Mode::RenameInput => self.handle_library_rename_key(key_event),
-                _ => {}
-            },
+    /// Runs whatever the pending keys are bound to on their own, if
+    /// anything, and forgets them.
+    pub(crate) fn flush_pending_keys(&mut self) {
+        let keys = std::mem::take(&mut self.pending_keys);
+        self.pending_since = None;
+        if let Some(action) =
+            self.keymap
+                .complete(self.key_context(), self.mode == Mode::Normal, &keys)
+        {
+            self.dispatch(action);
         }
     }
 }
 
-pub fn build_tree_items(roots: &[SourceRoot]) -> Vec<TreeItem<'static, TreeId>> {
+/// Builds the file tree. Roots are badged with the git repository they sit
+/// in; files are badged only when their repository differs from the root's.
+/// With `group_by_repo`, files are nested under one node per repository.
+/// Files in `lint` are badged with their findings. Colours come from
+/// `theme`.
+pub fn build_tree_items(
+    roots: &[SourceRoot],
+    group_by_repo: bool,
+    tokenizer: &dyn Tokenizer,
+    lint: &HashMap<PathBuf, FindingCounts>,
+    theme: &Theme,
+) -> Vec<TreeItem<'static, TreeId>> {
+    let global_file = find_global_claude_file();
+    let global_dir = global_file.as_deref().and_then(Path::parent);
     roots
         .iter()
         .filter_map(|root| {
             let root_id = root.path.display().to_string();
-            let children: Vec<TreeItem<'static, TreeId>> = root
-                .files
+            let root_repo = find_git_repo(&root.path);
+            let files: Vec<(&PathBuf, Option<GitRepo>)> = root
+                .file_paths()
+                .map(|file| (file, find_git_repo(file)))
+                .collect();
+            let mut children = if group_by_repo {
+                repo_group_items(root, files, tokenizer, lint, theme)
+            } else {
+                files
+                    .into_iter()
+                    .map(|(file, repo)| {
+                        let badge = (repo != root_repo).then(|| repo_badge(repo.as_ref(), theme));
+                        file_item(root, file, badge, tokenizer, lint.get(file).copied(), theme)
+                    })
+                    .collect()
+            };
+            children.extend(context_group_items(root, tokenizer, theme));
+            children.extend(misnamed_group_item(root, theme));
+            let mut label = Line::from(vec![
+                Span::raw(root_name(&root.path, global_dir)),
+                Span::styled(format!(" ({})", root.summary(tokenizer)), theme.detail),
+                repo_badge(root_repo.as_ref(), theme),
+            ]);
+            if !root.misnamed_files.is_empty() {
+                label.push_span(Span::styled(
+                    format!(" [{} misnamed]", root.misnamed_files.len()),
+                    theme.warning,
+                ));
+            }
+            TreeItem::new(root_id, label, children).ok()
+        })
+        .collect()
+}
+
+/// Nests `files` under one node per repository, with files outside any
+/// repository last.
+fn repo_group_items(
+    root: &SourceRoot,
+    files: Vec<(&PathBuf, Option<GitRepo>)>,
+    tokenizer: &dyn Tokenizer,
+    lint: &HashMap<PathBuf, FindingCounts>,
+    theme: &Theme,
+) -> Vec<TreeItem<'static, TreeId>> {
+    let mut groups: BTreeMap<(bool, Option<GitRepo>), Vec<&PathBuf>> = BTreeMap::new();
+    for (file, repo) in files {
+        groups.entry((repo.is_none(), repo)).or_default().push(file);
+    }
+    groups
+        .into_iter()
+        .filter_map(|((_, repo), files)| {
+            let children = files
+                .into_iter()
+                .map(|file| file_item(root, file, None, tokenizer, lint.get(file).copied(), theme))
+                .collect();
+            let label = match &repo {
+                Some(repo) => {
+                    let location = repo.root.strip_prefix(&root.path).unwrap_or(&repo.root);
+                    Line::from(vec![
+                        Span::styled(repo.name(), theme.badge),
+                        Span::styled(format!(" ({})", location.display()), theme.detail),
+                    ])
+                }
+                None => Line::styled(NO_REPO_GROUP, theme.detail),
+            };
+            TreeItem::new(repo_group_id(repo.as_ref()), label, children).ok()
+        })
+        .collect()
+}
+
+/// One node per kind of agent, command and skill file in `root`, holding
+/// those files. Kinds `root` has none of get no node.
+fn context_group_items(
+    root: &SourceRoot,
+    tokenizer: &dyn Tokenizer,
+    theme: &Theme,
+) -> Vec<TreeItem<'static, TreeId>> {
+    ContextKind::ALL
+        .into_iter()
+        .filter_map(|kind| {
+            let children: Vec<_> = root
+                .context_files
                 .iter()
-                .map(|file| {
-                    let file_id = file.display().to_string();
-                    let label = file
-                        .strip_prefix(&root.path)
-                        .unwrap_or(file)
-                        .display()
-                        .to_string();
-                    TreeItem::new_leaf(file_id, label)
-                })
+                .filter(|file| file.kind == kind)
+                .map(|file| file_item(root, &file.path, None, tokenizer, None, theme))
                 .collect();
-            TreeItem::new(root_id, root.path.display().to_string(), children).ok()
+            if children.is_empty() {
+                return None;
+            }
+            let label = Line::from(vec![
+                Span::styled(kind.label(), theme.group_label),
+                Span::styled(format!(" ({})", children.len()), theme.detail),
+            ]);
+            TreeItem::new(context_group_id(kind), label, children).ok()
         })
         .collect()
 }
 
+/// The node holding the misnamed CLAUDE.md files of `root`, each badged
+/// with the name Claude Code would read it under. `None` when there are
+/// none.
+fn misnamed_group_item(root: &SourceRoot, theme: &Theme) -> Option<TreeItem<'static, TreeId>> {
+    if root.misnamed_files.is_empty() {
+        return None;
+    }
+    let children = root
+        .misnamed_files
+        .iter()
+        .map(|file| {
+            let label = file.strip_prefix(&root.path).unwrap_or(file);
+            let canonical = canonical_name_for(file).unwrap_or(CLAUDE_FILE_NAME);
+            TreeItem::new_leaf(
+                file.display().to_string(),
+                Line::from(vec![
+                    Span::raw(label.display().to_string()),
+                    Span::styled(format!(" [→ {canonical}]"), theme.warning),
+                ]),
+            )
+        })
+        .collect();
+    let label = Line::from(vec![
+        Span::styled("Misnamed", theme.warning),
+        Span::styled(format!(" ({})", root.misnamed_files.len()), theme.detail),
+    ]);
+    TreeItem::new(MISNAMED_GROUP.to_string(), label, children).ok()
+}
+
+/// Tree id of the node holding the `kind` files of a root.
+fn context_group_id(kind: ContextKind) -> TreeId {
+    format!("[{}]", kind.dir_name())
+}
+
+/// Whether `id` is the tree id of a node holding files other than the
+/// CLAUDE.md files: agents, commands, skills or misnamed files.
+pub(crate) fn is_file_group_id(id: &str) -> bool {
+    id == MISNAMED_GROUP
+        || ContextKind::ALL
+            .into_iter()
+            .any(|kind| context_group_id(kind) == id)
+}
+
+/// Tree node for `file`, labelled relative to its root and with its token
+/// estimate and lint findings, holding its `@path` imports.
+fn file_item(
+    root: &SourceRoot,
+    file: &Path,
+    repo_badge: Option<Span<'static>>,
+    tokenizer: &dyn Tokenizer,
+    lint: Option<FindingCounts>,
+    theme: &Theme,
+) -> TreeItem<'static, TreeId> {
+    let label = file
+        .strip_prefix(&root.path)
+        .unwrap_or(file)
+        .display()
+        .to_string();
+    let mut spans = vec![Span::raw(label)];
+    if is_local_claude_file(file) {
+        spans.push(Span::styled(LOCAL_BADGE, theme.warning));
+    } else if !is_claude_file(file) {
+        spans.push(Span::styled(PATTERN_BADGE, theme.badge));
+    }
+    spans.extend(repo_badge);
+    if let Some(lint) = lint {
+        if lint.errors > 0 {
+            spans.push(Span::styled(format!(" ✗{}", lint.errors), theme.error));
+        }
+        if lint.warnings > 0 {
+            spans.push(Span::styled(format!(" !{}", lint.warnings), theme.warning));
+        }
+    }
+    spans.push(Span::styled(
+        format!(" {}", format_tokens(tokenizer.count_file(file))),
+        theme.detail,
+    ));
+    let imports = import_items(file, tokenizer, theme);
+    let missing = imports
+        .iter()
+        .filter(|import| is_missing_import_id(import.identifier()))
+        .count();
+    if missing > 0 {
+        let plural = if missing == 1 { "" } else { "s" };
+        spans.push(Span::styled(
+            format!(" [{missing} missing import{plural}]"),
+            theme.error,
+        ));
+    }
+    let id = file.display().to_string();
+    let label = Line::from(spans);
+    match TreeItem::new(id.clone(), label.clone(), imports) {
+        Ok(item) => item,
+        Err(_) => TreeItem::new_leaf(id, label),
+    }
+}
+
+/// Badge naming the repository something belongs to, or marking it as
+/// outside any repository.
+fn repo_badge(repo: Option<&GitRepo>, theme: &Theme) -> Span<'static> {
+    match repo {
+        Some(repo) => Span::styled(format!(" [git: {}]", repo.name()), theme.badge),
+        None => Span::styled(NO_REPO_BADGE, theme.detail),
+    }
+}
+
+/// Tree id of the repository group `repo` files are nested under.
+fn repo_group_id(repo: Option<&GitRepo>) -> TreeId {
+    repo.map_or_else(
+        || NO_REPO_GROUP.to_string(),
+        |repo| repo.root.display().to_string(),
+    )
+}
+
 #[cfg(test)]
 pub(crate) mod test_helpers {
     use std::path::PathBuf;
@@ -790,6 +2062,7 @@ pub(crate) mod test_helpers {
     use ratatui::crossterm::event::KeyEventState;
     use ratatui::crossterm::event::KeyModifiers;
 
+    use crate::model::DiscoveredFile;
     use crate::model::SourceRoot;
 
     use super::App;
@@ -807,14 +2080,20 @@ pub(crate) mod test_helpers {
         vec![
             SourceRoot {
                 path: PathBuf::from("/a"),
-                files: vec![PathBuf::from("/a/CLAUDE.md")],
+                files: vec![DiscoveredFile::new(PathBuf::from("/a/CLAUDE.md"))],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
             },
             SourceRoot {
                 path: PathBuf::from("/b"),
                 files: vec![
-                    PathBuf::from("/b/CLAUDE.md"),
-                    PathBuf::from("/b/sub/CLAUDE.md"),
+                    DiscoveredFile::new(PathBuf::from("/b/CLAUDE.md")),
+                    DiscoveredFile::new(PathBuf::from("/b/sub/CLAUDE.md")),
                 ],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
             },
         ]
     }
@@ -833,10 +2112,95 @@ mod tests {
     use super::test_helpers::*;
     use super::*;
     use crate::config::Config;
+    use crate::discovery::ContextFile;
+    use crate::model::DiscoveredFile;
+    use crate::tokens::ByteHeuristic;
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::KeyCode;
     use ratatui::crossterm::event::KeyEventKind;
     use ratatui::crossterm::event::KeyEventState;
+    use ratatui::crossterm::event::KeyModifiers;
+    use ratatui::style::Color;
     use tempfile::TempDir;
 
+    #[test]
+    fn warnings_show_in_the_status_bar_and_are_kept() {
+        let mut app = App::new(Vec::new(), &Config::default());
+        app.show_warnings(Vec::new());
+        assert!(app.status_message.is_none());
+
+        app.show_warnings(vec!["first".to_string(), "second".to_string()]);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Warning: second (and 1 more)")
+        );
+        assert_eq!(app.warnings, ["first", "second"]);
+    }
+
+    #[test]
+    fn loading_a_file_shows_its_size_and_keeps_it_in_the_status_bar() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "line\n".repeat(1_200)).unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![DiscoveredFile::new(file.clone())],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+        app.select_tree_file(&file);
+        app.load_selected_content();
+        let summary = "1,200 lines · 5.9 KB · ~1.5k tok";
+        assert_eq!(app.status_message.as_deref(), Some(summary));
+
+        app.active_pane = Pane::Content;
+        app.handle_key_event(key_event(KeyCode::Down));
+        assert!(app.status_message.is_none());
+        assert_eq!(app.status_text(), Some(summary));
+    }
+
+    #[test]
+    fn legacy_console_is_drawn_with_ascii_borders() {
+        let mut app = App::new(sample_roots(), &Config::default());
+        app.capabilities.unicode = false;
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+
+        let buf = terminal.backend().buffer();
+        assert!(buf.content().iter().all(|cell| cell.symbol().is_ascii()));
+        assert_eq!(buf[(0, 1)].symbol(), "+");
+    }
+
+    #[test]
+    fn colors_config_recolours_the_tree() {
+        let orange = Color::Rgb(0xff, 0x88, 0x00);
+        let config = Config {
+            colors: Some(BTreeMap::from([(
+                "detail".to_string(),
+                "#ff8800".to_string(),
+            )])),
+            ..Config::default()
+        };
+        let mut app = App::new(sample_roots(), &config);
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let has_orange = |terminal: &Terminal<TestBackend>| {
+            let buf = terminal.backend().buffer();
+            buf.content().iter().any(|cell| cell.fg == orange)
+        };
+
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        assert!(has_orange(&terminal));
+
+        app.handle_key_event(key_event(KeyCode::Char('T')));
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        assert!(!app.theme.is_dark);
+        assert!(has_orange(&terminal));
+    }
+
     #[test]
     fn q_key_sets_exit() {
         let mut app = App::new(vec![], &Config::default());
@@ -844,6 +2208,32 @@ mod tests {
         assert!(app.exit);
     }
 
+    #[test]
+    fn keymap_config_rebinds_keys_and_reports_errors() {
+        let overrides = [
+            ("q".to_string(), "none".to_string()),
+            ("x".to_string(), "quit".to_string()),
+            ("y".to_string(), "fly".to_string()),
+        ];
+        let config = Config {
+            keymap: Some(BTreeMap::from([(
+                "files_tree".to_string(),
+                overrides.into_iter().collect(),
+            )])),
+            ..Config::default()
+        };
+        let mut app = App::new(vec![], &config);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Keymap config: files_tree: unknown action \"fly\"")
+        );
+
+        app.handle_key_event(key_event(KeyCode::Char('q')));
+        assert!(!app.exit);
+        app.handle_key_event(key_event(KeyCode::Char('x')));
+        assert!(app.exit);
+    }
+
     #[test]
     fn other_keys_do_not_exit() {
         let mut app = App::new(vec![], &Config::default());
@@ -854,13 +2244,50 @@ mod tests {
     #[test]
     fn build_tree_items_creates_correct_hierarchy() {
         let roots = sample_roots();
-        let items = build_tree_items(&roots);
+        let items = build_tree_items(
+            &roots,
+            false,
+            &ByteHeuristic,
+            &HashMap::new(),
+            &Theme::dark(),
+        );
 
         assert_eq!(items.len(), 2, "Should have two root nodes");
         assert_eq!(items[0].children().len(), 1, "First root has one file");
         assert_eq!(items[1].children().len(), 2, "Second root has two files");
     }
 
+    #[test]
+    fn agents_commands_and_skills_get_their_own_nodes() {
+        let tmp = TempDir::new().unwrap();
+        let claude = tmp.path().join("CLAUDE.md");
+        let agent = tmp.path().join(".claude/agents/reviewer.md");
+        fs::create_dir_all(agent.parent().unwrap()).unwrap();
+        fs::write(&claude, "rules").unwrap();
+        fs::write(&agent, "Review every diff.").unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![DiscoveredFile::new(claude)],
+            context_files: vec![ContextFile {
+                kind: ContextKind::Agent,
+                path: agent.clone(),
+            }],
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+
+        let children = app.tree_items[0].children();
+        assert_eq!(children.len(), 2);
+        assert_eq!(children[1].identifier(), "[agents]");
+        assert!(app.roots[0].summary(&ByteHeuristic).starts_with("1 file"));
+
+        assert!(app.select_tree_file(&agent));
+        app.load_selected_content();
+        assert_eq!(app.selected_file(), Some(agent));
+        assert_eq!(app.content.text.as_deref(), Some("Review every diff."));
+    }
+
     #[test]
     fn first_file_is_selected_and_loaded_on_startup() {
         let tmp = TempDir::new().unwrap();
@@ -869,7 +2296,10 @@ mod tests {
 
         let roots = vec![SourceRoot {
             path: tmp.path().to_path_buf(),
-            files: vec![file.clone()],
+            files: vec![DiscoveredFile::new(file.clone())],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
         }];
         let app = App::new(roots, &Config::default());
 
@@ -0,0 +1,374 @@
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+
+use super::app::App;
+use super::diff_preview::DiffPreview;
+use super::diff_preview::PREVIEW_CONTEXT_LINES;
+use super::diff_preview::PreviewAction;
+use super::picker::Picker;
+use super::picker::PickerAction;
+use super::picker::PickerItem;
+use crate::backup::backups_dir_in;
+use crate::backup::create_backup;
+use crate::backup::deleted_files_with_backups;
+use crate::backup::format_timestamp;
+use crate::backup::list_backups;
+use crate::diff::collapse_context;
+use crate::diff::diff_lines;
+use crate::diff::has_changes;
+use crate::fsutil::check_writable;
+use crate::fsutil::write_atomic;
+use crate::journal::file_size;
+
+impl App {
+    /// Returns the backup store, or `None` when backups are disabled.
+    pub(crate) fn backups_dir(&self) -> Option<PathBuf> {
+        self.data_dir.as_deref().map(backups_dir_in)
+    }
+
+    /// Copies `path` into the backup store before it is overwritten.
+    /// Returns a warning to show the user if the backup failed.
+    pub(crate) fn backup_before_write(&self, path: &Path) -> Option<String> {
+        let dir = self.backups_dir()?;
+        create_backup(&dir, path)
+            .err()
+            .map(|err| format!("backup failed: {err:#}"))
+    }
+
+    /// Returns true if `path` can be written. Otherwise says why in the status
+    /// line, naming the refused `action` ("edit", "restore", ...).
+    pub(crate) fn ensure_writable(&mut self, path: &Path, action: &str) -> bool {
+        match check_writable(path) {
+            Ok(()) => true,
+            Err(read_only) => {
+                self.status_message =
+                    Some(format!("Cannot {action} {}: {read_only}.", path.display()));
+                false
+            }
+        }
+    }
+
+    /// Backs up `path`, atomically replaces it with `content` and notes the
+    /// change in the journal as `action`. Returns a warning if the backup
+    /// failed but the write succeeded.
+    pub(crate) fn write_with_backup(
+        &mut self,
+        action: &str,
+        path: &Path,
+        content: &str,
+    ) -> io::Result<Option<String>> {
+        let warning = self.backup_before_write(path);
+        let before = file_size(path);
+        write_atomic(path, content)?;
+        self.journal_change(action, path, before);
+        Ok(warning)
+    }
+
+    /// Writes previewed `content` to `path` and refreshes the content pane if
+    /// it shows that file.
+    pub(crate) fn apply_file_write(&mut self, path: &Path, content: &str, success: String) {
+        match self.write_with_backup("write", path, content) {
+            Ok(warning) => {
+                if Path::new(&self.current_source_path()) == path {
+                    self.load_file_content(path);
+                }
+                self.status_message = Some(match warning {
+                    Some(warning) => format!("{success} ({warning})"),
+                    None => success,
+                });
+            }
+            Err(err) => {
+                self.status_message = Some(format!("Write failed: {err}"));
+            }
+        }
+    }
+
+    /// Opens the backups browser for the current selection: versions of the
+    /// selected file, or deleted files under the selected root.
+    pub(crate) fn open_backups(&mut self) {
+        if self.backups_dir().is_none() {
+            self.status_message = Some("Backups are unavailable (HOME is not set).".to_string());
+            return;
+        }
+        if let Some(file) = self.selected_file() {
+            self.open_backup_versions(file);
+        } else if let Some(root) = self.selected_root() {
+            self.open_deleted_files(&root);
+        } else {
+            self.status_message = Some("Select a file or root first.".to_string());
+        }
+    }
+
+    /// Lists files under `root` that were deleted but still have backups.
+    fn open_deleted_files(&mut self, root: &Path) {
+        let Some(dir) = self.backups_dir() else {
+            return;
+        };
+        let files = match deleted_files_with_backups(&dir, root) {
+            Ok(files) => files,
+            Err(err) => {
+                self.status_message = Some(format!("Cannot read backups: {err:#}"));
+                return;
+            }
+        };
+        if files.is_empty() {
+            self.status_message =
+                Some("No deleted files with backups under this root.".to_string());
+            return;
+        }
+
+        let items = files
+            .iter()
+            .map(|f| {
+                let label = f.strip_prefix(root).unwrap_or(f).display().to_string();
+                PickerItem::new(label, "deleted")
+            })
+            .collect();
+        self.open_picker(Picker::new(
+            "Restore deleted file",
+            items,
+            PickerAction::DeletedFile { files },
+        ));
+    }
+
+    /// Lists the backed-up versions of `file`, newest first.
+    pub(crate) fn open_backup_versions(&mut self, file: PathBuf) {
+        let Some(dir) = self.backups_dir() else {
+            return;
+        };
+        let backups = match list_backups(&dir, &file) {
+            Ok(backups) => backups,
+            Err(err) => {
+                self.status_message = Some(format!("Cannot read backups: {err:#}"));
+                return;
+            }
+        };
+        if backups.is_empty() {
+            self.status_message = Some(format!("No backups of {}.", file.display()));
+            return;
+        }
+
+        let items = backups
+            .iter()
+            .map(|b| {
+                let lines = fs::read_to_string(&b.path).map_or(0, |c| c.lines().count());
+                PickerItem::new(b.timestamp(), format!("{lines} lines"))
+            })
+            .collect();
+        let title = format!("Backups of {}", file.display());
+        self.open_picker(Picker::new(
+            title,
+            items,
+            PickerAction::RestoreBackup {
+                file,
+                backups: backups.into_iter().map(|b| b.path).collect(),
+            },
+        ));
+    }
+
+    /// Shows the diff between `file` on disk and `backup`, asking for
+    /// confirmation before restoring.
+    pub(crate) fn preview_restore(&mut self, file: PathBuf, backup: PathBuf) {
+        if !self.ensure_writable(&file, "restore") {
+            return;
+        }
+        let restored = match fs::read_to_string(&backup) {
+            Ok(content) => content,
+            Err(err) => {
+                self.status_message = Some(format!("Cannot read backup: {err}"));
+                return;
+            }
+        };
+        let current = fs::read_to_string(&file).unwrap_or_default();
+        let lines = diff_lines(&current, &restored);
+        if !has_changes(&lines) && file.exists() {
+            self.status_message = Some("Backup matches the current file.".to_string());
+            return;
+        }
+
+        let title = format!("Restore {}? (y/n)", file.display());
+        self.open_diff_preview(DiffPreview::new(
+            title,
+            collapse_context(lines, PREVIEW_CONTEXT_LINES),
+            PreviewAction::RestoreBackup { file, backup },
+        ));
+    }
+
+    /// Writes `backup` back to `file`, backing up the current content first.
+    pub(crate) fn restore_backup(&mut self, file: &Path, backup: &Path) {
+        let content = match fs::read_to_string(backup) {
+            Ok(content) => content,
+            Err(err) => {
+                self.status_message = Some(format!("Cannot read backup: {err}"));
+                return;
+            }
+        };
+        let warning = match self.write_with_backup("restore", file, &content) {
+            Ok(warning) => warning,
+            Err(err) => {
+                self.status_message = Some(format!("Restore failed: {err}"));
+                return;
+            }
+        };
+
+        self.register_file(file);
+        self.load_file_content(file);
+        let when = backup
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .and_then(|s| s.parse::<u64>().ok())
+            .map(format_timestamp)
+            .unwrap_or_default();
+        self.status_message = Some(match warning {
+            Some(warning) => format!("Restored version from {when} ({warning})."),
+            None => format!("Restored version from {when}."),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use crate::backup::backups_dir_in;
+    use crate::backup::create_backup;
+    use crate::backup::list_backups;
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::test_helpers::key_event;
+
+    fn app_with_file(root: &Path, file: &Path, data_dir: &Path) -> App {
+        let mut app = App::new(
+            vec![SourceRoot {
+                path: root.to_path_buf(),
+                files: vec![DiscoveredFile::new(file.to_path_buf())],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        );
+        app.data_dir = Some(data_dir.to_path_buf());
+        app
+    }
+
+    #[test]
+    fn b_without_backups_shows_status() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "now").unwrap();
+        let mut app = app_with_file(tmp.path(), &file, &tmp.path().join("data"));
+
+        app.handle_key_event(key_event(KeyCode::Char('b')));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(
+            app.status_message
+                .as_deref()
+                .unwrap()
+                .contains("No backups")
+        );
+    }
+
+    #[test]
+    fn restore_previews_diff_then_writes_backup() {
+        let tmp = TempDir::new().unwrap();
+        let data = tmp.path().join("data");
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "old line").unwrap();
+        create_backup(&backups_dir_in(&data), &file).unwrap();
+        fs::write(&file, "new line").unwrap();
+        let mut app = app_with_file(tmp.path(), &file, &data);
+
+        app.handle_key_event(key_event(KeyCode::Char('b')));
+        assert_eq!(app.mode, Mode::Picker);
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::DiffPreview);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new line");
+
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "old line");
+        assert_eq!(app.content.text.as_deref(), Some("old line"));
+        // The overwritten version was itself backed up.
+        let backups = list_backups(&backups_dir_in(&data), &file).unwrap();
+        assert_eq!(fs::read_to_string(&backups[0].path).unwrap(), "new line");
+    }
+
+    #[test]
+    fn cancelling_preview_leaves_file_untouched() {
+        let tmp = TempDir::new().unwrap();
+        let data = tmp.path().join("data");
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "old").unwrap();
+        create_backup(&backups_dir_in(&data), &file).unwrap();
+        fs::write(&file, "new").unwrap();
+        let mut app = app_with_file(tmp.path(), &file, &data);
+
+        app.handle_key_event(key_event(KeyCode::Char('b')));
+        app.handle_key_event(key_event(KeyCode::Enter));
+        app.handle_key_event(key_event(KeyCode::Esc));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "new");
+    }
+
+    #[test]
+    fn deleted_file_is_restored_from_root() {
+        let tmp = TempDir::new().unwrap();
+        let data = tmp.path().join("data");
+        let root = tmp.path().join("root");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        let kept = root.join("CLAUDE.md");
+        let gone = root.join("sub").join("CLAUDE.md");
+        fs::write(&kept, "kept").unwrap();
+        fs::write(&gone, "gone").unwrap();
+        create_backup(&backups_dir_in(&data), &gone).unwrap();
+        fs::remove_file(&gone).unwrap();
+        let mut app = app_with_file(&root, &kept, &data);
+        app.tree_state.select(vec![root.display().to_string()]);
+
+        app.handle_key_event(key_event(KeyCode::Char('b')));
+        assert_eq!(app.picker.as_ref().unwrap().items.len(), 1);
+        app.handle_key_event(key_event(KeyCode::Enter)); // choose file
+        app.handle_key_event(key_event(KeyCode::Enter)); // choose version
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert_eq!(fs::read_to_string(&gone).unwrap(), "gone");
+        assert!(app.roots[0].has_file(&gone));
+        assert_eq!(
+            app.tree_state.selected(),
+            vec![root.display().to_string(), gone.display().to_string()]
+        );
+    }
+
+    #[test]
+    fn saving_an_edit_backs_up_previous_content() {
+        let tmp = TempDir::new().unwrap();
+        let data = tmp.path().join("data");
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "before\n").unwrap();
+        let mut app = app_with_file(tmp.path(), &file, &data);
+
+        app.enter_edit_mode_for(&file);
+        if let Some(edit) = &mut app.edit_state {
+            edit.textarea.insert_str("x");
+            edit.invalidate_dirty_cache();
+        }
+        app.save_edit_to(&file);
+
+        let backups = list_backups(&backups_dir_in(&data), &file).unwrap();
+        assert_eq!(backups.len(), 1);
+        assert_eq!(fs::read_to_string(&backups[0].path).unwrap(), "before\n");
+    }
+}
@@ -0,0 +1,298 @@
+use std::env;
+
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::text::Text;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+
+use super::action::Action;
+use super::action::Effect;
+use super::app::App;
+use super::app::Mode;
+use super::picker::centered_rect;
+use crate::budget::Budget;
+use crate::budget::BudgetEntry;
+use crate::budget::format_bytes;
+use crate::config::find_project_config;
+use crate::discovery::find_global_claude_file;
+use crate::settings::discover_settings_files;
+use crate::tokens::format_tokens;
+
+/// Widest path column before paths are cut from the left.
+const MAX_PATH_WIDTH: usize = 60;
+
+/// The context budget dashboard, open while in `Mode::Budget`.
+#[derive(Debug)]
+pub struct BudgetView {
+    /// Rendered rows of the dashboard.
+    pub lines: Vec<Line<'static>>,
+    /// First visible row.
+    pub scroll: u16,
+    /// Captured during draw — visible rows inside the border.
+    pub viewport_height: u16,
+}
+
+impl App {
+    /// Measures the global CLAUDE.md, the scanned files and the project's
+    /// settings files and shows the totals.
+    pub(crate) fn open_budget(&mut self) {
+        let project = env::current_dir().unwrap_or_default();
+        let settings: Vec<_> = discover_settings_files(&project)
+            .files
+            .into_iter()
+            .map(|file| file.path)
+            .collect();
+        let global = find_global_claude_file();
+        let mut budget = Budget::build(
+            global.as_deref(),
+            &self.roots,
+            &settings,
+            self.config.tokenizer(),
+            self.config.oversized_tokens(),
+        );
+        budget.limits = self
+            .roots
+            .iter()
+            .filter_map(|root| {
+                let (_, Ok(project)) = find_project_config(&root.path)? else {
+                    return None;
+                };
+                Some((root.path.clone(), project.oversized_tokens?))
+            })
+            .collect();
+        self.show_budget(&budget);
+    }
+
+    /// Opens the dashboard for an already measured `budget`.
+    pub(crate) fn show_budget(&mut self, budget: &Budget) {
+        self.budget_view = Some(BudgetView {
+            lines: self.budget_lines(budget),
+            scroll: 0,
+            viewport_height: 0,
+        });
+        self.mode = Mode::Budget;
+    }
+
+    /// One row per file under a heading per group, then the oversized files.
+    fn budget_lines(&self, budget: &Budget) -> Vec<Line<'static>> {
+        let heading = self.theme.active_tab;
+        let dim = self.theme.help_desc;
+        let warn = self.theme.diff_removed;
+        let path_width = budget
+            .groups
+            .iter()
+            .flat_map(|group| &group.entries)
+            .map(|entry| entry.path.display().to_string().chars().count())
+            .max()
+            .unwrap_or(0)
+            .clamp(20, MAX_PATH_WIDTH);
+        let row = |label: String, (bytes, lines, tokens): (u64, usize, u64)| {
+            format!(
+                "{label:<width$} {:>9} {:>7} {:>10}",
+                format_bytes(bytes),
+                format!("{lines} ln"),
+                format_tokens(tokens),
+                width = path_width + 3,
+            )
+        };
+
+        let mut out = vec![
+            Line::styled(row(" Total".to_string(), budget.totals()), heading),
+            Line::from(""),
+        ];
+        for group in &budget.groups {
+            out.push(Line::styled(
+                row(format!(" {}", group.title), group.totals()),
+                heading,
+            ));
+            if group.entries.is_empty() {
+                out.push(Line::styled("   none", dim));
+            }
+            for entry in &group.entries {
+                let text = row(format!("   {}", fit_path(entry)), entry_totals(entry));
+                if budget.is_oversized(entry) {
+                    out.push(Line::styled(text, warn));
+                } else {
+                    out.push(Line::raw(text));
+                }
+            }
+            out.push(Line::from(""));
+        }
+
+        let mut threshold = format_tokens(budget.oversized_tokens);
+        if !budget.limits.is_empty() {
+            threshold.push_str(" or its project's limit");
+        }
+        let oversized = budget.oversized();
+        if oversized.is_empty() {
+            out.push(Line::styled(format!(" No file is above {threshold}."), dim));
+        } else {
+            out.push(Line::styled(
+                format!(" ⚠ {} above {threshold}:", oversized.len()),
+                warn,
+            ));
+            for entry in oversized {
+                out.push(Line::from(vec![
+                    Span::raw(format!("   {}", entry.path.display())),
+                    Span::styled(format!("  {}", format_tokens(entry.tokens)), warn),
+                ]));
+            }
+        }
+        out
+    }
+
+    /// Draws the dashboard as a popup over `area`.
+    pub(crate) fn draw_budget(&mut self, frame: &mut Frame, area: Rect) {
+        let border = self.theme.input_border;
+        let Some(view) = &mut self.budget_view else {
+            return;
+        };
+        let popup = centered_rect(90, area.height.saturating_sub(2), area);
+        view.viewport_height = popup.height.saturating_sub(2);
+        view.scroll = view.scroll.min(max_scroll(view));
+        let widget = Paragraph::new(Text::from(view.lines.clone()))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border)
+                    .title("Context budget"),
+            )
+            .scroll((view.scroll, 0));
+        frame.render_widget(Clear, popup);
+        frame.render_widget(widget, popup);
+    }
+
+    /// Applies an action while the dashboard is open.
+    pub(crate) fn update_budget(&mut self, action: Action) -> Vec<Effect> {
+        let Some(view) = &mut self.budget_view else {
+            self.mode = self.mode_after_modal();
+            return Vec::new();
+        };
+        let page = view.viewport_height.max(1);
+        match action {
+            Action::Cancel => {
+                self.budget_view = None;
+                self.mode = self.mode_after_modal();
+            }
+            Action::MoveDown => view.scroll = (view.scroll + 1).min(max_scroll(view)),
+            Action::MoveUp => view.scroll = view.scroll.saturating_sub(1),
+            Action::PageDown => {
+                view.scroll = view.scroll.saturating_add(page).min(max_scroll(view))
+            }
+            Action::PageUp => view.scroll = view.scroll.saturating_sub(page),
+            _ => {}
+        }
+        Vec::new()
+    }
+}
+
+fn max_scroll(view: &BudgetView) -> u16 {
+    let max = view
+        .lines
+        .len()
+        .saturating_sub(view.viewport_height as usize);
+    max.min(u16::MAX as usize) as u16
+}
+
+fn entry_totals(entry: &BudgetEntry) -> (u64, usize, u64) {
+    (entry.bytes, entry.lines, entry.tokens)
+}
+
+/// The entry's path, cut from the left with `…` when too long.
+fn fit_path(entry: &BudgetEntry) -> String {
+    let path = entry.path.display().to_string();
+    let count = path.chars().count();
+    if count <= MAX_PATH_WIDTH {
+        return path;
+    }
+    let tail: String = path.chars().skip(count - MAX_PATH_WIDTH + 1).collect();
+    format!("…{tail}")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use crate::budget::Budget;
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tokens::ByteHeuristic;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::test_helpers::key_event;
+
+    fn budget_app(tmp: &TempDir) -> (App, Budget) {
+        let small = tmp.path().join("CLAUDE.md");
+        let big = tmp.path().join("sub/CLAUDE.md");
+        fs::create_dir_all(tmp.path().join("sub")).unwrap();
+        fs::write(&small, "short\n").unwrap();
+        fs::write(&big, "x".repeat(800)).unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![DiscoveredFile::new(small), DiscoveredFile::new(big)],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let budget = Budget::build(None, &roots, &[], &ByteHeuristic, 100);
+        (App::new(roots, &Config::default()), budget)
+    }
+
+    #[test]
+    fn dashboard_lists_totals_and_oversized_files() {
+        let tmp = TempDir::new().unwrap();
+        let (mut app, budget) = budget_app(&tmp);
+
+        app.show_budget(&budget);
+
+        assert_eq!(app.mode, Mode::Budget);
+        let text: Vec<String> = app
+            .budget_view
+            .as_ref()
+            .unwrap()
+            .lines
+            .iter()
+            .map(|line| line.to_string())
+            .collect();
+        assert!(text[0].starts_with(" Total"));
+        assert!(text[0].ends_with("202 tok"));
+        assert!(
+            text.iter()
+                .any(|line| line.starts_with(" Project CLAUDE.md files"))
+        );
+        assert!(text.iter().any(|line| line == " ⚠ 1 above 100 tok:"));
+    }
+
+    #[test]
+    fn esc_closes_dashboard() {
+        let tmp = TempDir::new().unwrap();
+        let (mut app, budget) = budget_app(&tmp);
+        app.show_budget(&budget);
+
+        app.handle_key_event(key_event(KeyCode::Esc));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.budget_view.is_none());
+    }
+
+    #[test]
+    fn b_toggles_dashboard() {
+        let tmp = TempDir::new().unwrap();
+        let (mut app, _) = budget_app(&tmp);
+
+        app.handle_key_event(key_event(KeyCode::Char('B')));
+
+        assert_eq!(app.mode, Mode::Budget);
+        app.handle_key_event(key_event(KeyCode::Char('B')));
+        assert_eq!(app.mode, Mode::Normal);
+    }
+}
@@ -1,6 +1,4 @@
 use ratatui::Frame;
-use ratatui::crossterm::event::KeyCode;
-use ratatui::crossterm::event::KeyEvent;
 use ratatui::layout::Constraint;
 use ratatui::layout::Direction;
 use ratatui::layout::Layout;
@@ -14,6 +12,8 @@ use ratatui::widgets::Scrollbar;
 use ratatui::widgets::ScrollbarOrientation;
 use ratatui::widgets::ScrollbarState;
 
+use super::action::Action;
+use super::action::Effect;
 use super::app::App;
 use super::app::Mode;
 use super::app::Screen;
@@ -91,7 +91,8 @@ impl App {
     pub(crate) fn enter_compose_screen(&mut self) {
         if self.library.is_none() {
             if let Some(path) = crate::library::library_path() {
-                match crate::library::load_library(&path) {
+                self.project_library = self.current_project_library();
+                match self.load_library_at(&path) {
                     Ok(lib) => self.library = Some(lib),
                     Err(err) => {
                         self.status_message = Some(format!("Failed to load library: {err}"));
@@ -113,7 +114,8 @@ impl App {
 
     /// Enters the Compose screen with a specific library path (for testability).
     pub fn enter_compose_screen_from(&mut self, path: &std::path::Path) {
-        match crate::library::load_library(path) {
+        self.project_library = self.current_project_library();
+        match self.load_library_at(path) {
             Ok(lib) => {
                 self.library = Some(lib);
                 if self.compose_state.is_none() {
@@ -270,144 +272,84 @@ impl App {
         crate::compose::compose_snippets(&library.snippets, &compose.selected)
     }
 
-    pub(crate) fn handle_compose_key(&mut self, key_event: KeyEvent) {
-        let compose = match &self.compose_state {
-            Some(s) => s,
-            None => return,
-        };
-
+    /// Applies an action in Normal mode on the Compose screen.
+    pub(crate) fn update_compose(&mut self, action: Action) -> Vec<Effect> {
+        if action == Action::Back {
+            self.screen = Screen::Files;
+            return Vec::new();
+        }
         let snippet_count = ComposeState::snippet_count(self);
+        let in_preview = self
+            .compose_state
+            .as_ref()
+            .is_some_and(|cs| cs.active_pane == ComposePane::Preview);
+        let line_count = if in_preview {
+            self.composed_text().lines().count()
+        } else {
+            0
+        };
+        let Some(cs) = &mut self.compose_state else {
+            return Vec::new();
+        };
         if snippet_count == 0 {
-            match key_event.code {
-                KeyCode::Esc => {
-                    self.screen = Screen::Files;
-                }
-                KeyCode::Char('q') => {
-                    self.exit = true;
-                }
-                _ => {}
-            }
-            return;
-        }
-
-        // Route based on active pane
-        if compose.active_pane == ComposePane::Preview {
-            self.handle_compose_preview_key(key_event);
-            return;
+            return Vec::new();
         }
 
-        match key_event.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                if let Some(cs) = &mut self.compose_state
-                    && cs.cursor < snippet_count.saturating_sub(1)
-                {
+        match (cs.active_pane, action) {
+            (ComposePane::List, Action::MoveDown) => {
+                if cs.cursor < snippet_count.saturating_sub(1) {
                     cs.cursor += 1;
                     ensure_compose_cursor_visible(cs);
                 }
             }
-            KeyCode::Char('k') | KeyCode::Up => {
-                if let Some(cs) = &mut self.compose_state {
-                    cs.cursor = cs.cursor.saturating_sub(1);
-                    ensure_compose_cursor_visible(cs);
-                }
-            }
-            KeyCode::Char(' ') => {
-                if let Some(cs) = &mut self.compose_state {
-                    let cursor = cs.cursor;
-                    cs.toggle(cursor);
-                }
+            (ComposePane::List, Action::MoveUp) => {
+                cs.cursor = cs.cursor.saturating_sub(1);
+                ensure_compose_cursor_visible(cs);
             }
-            KeyCode::Tab => {
-                if let Some(cs) = &mut self.compose_state {
-                    cs.active_pane = ComposePane::Preview;
-                }
+            (ComposePane::List, Action::ToggleMark) => {
+                let cursor = cs.cursor;
+                cs.toggle(cursor);
             }
-            KeyCode::Char('w') => {
-                if let Some(cs) = &self.compose_state {
-                    if cs.selected.is_empty() {
-                        self.status_message = Some("No snippets selected.".to_string());
-                    } else {
-                        self.mode = Mode::ExportPath;
-                        self.text_input.clear();
-                    }
-                }
+            (ComposePane::List, Action::TogglePane) => {
+                cs.active_pane = ComposePane::Preview;
             }
-            KeyCode::Esc => {
-                self.screen = Screen::Files;
-            }
-            KeyCode::Char('q') => {
-                self.exit = true;
-            }
-            _ => {}
-        }
-    }
-
-    fn handle_compose_preview_key(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Char('j') | KeyCode::Down => {
-                let line_count = self.composed_text().lines().count();
-                if let Some(cs) = &mut self.compose_state {
-                    let max_scroll = line_count.saturating_sub(cs.preview_viewport_height as usize);
-                    if (cs.preview_scroll as usize) < max_scroll {
-                        cs.preview_scroll += 1;
-                    }
-                }
-            }
-            KeyCode::Char('k') | KeyCode::Up => {
-                if let Some(cs) = &mut self.compose_state {
-                    cs.preview_scroll = cs.preview_scroll.saturating_sub(1);
+            (ComposePane::List, Action::Export) => {
+                if cs.selected.is_empty() {
+                    self.status_message = Some("No snippets selected.".to_string());
+                } else {
+                    self.mode = Mode::ExportPath;
+                    self.text_input.clear();
                 }
             }
-            KeyCode::Tab => {
-                if let Some(cs) = &mut self.compose_state {
-                    cs.active_pane = ComposePane::List;
+            (ComposePane::Preview, Action::MoveDown) => {
+                let max_scroll = line_count.saturating_sub(cs.preview_viewport_height as usize);
+                if (cs.preview_scroll as usize) < max_scroll {
+                    cs.preview_scroll += 1;
                 }
             }
-            KeyCode::Esc => {
-                self.screen = Screen::Files;
+            (ComposePane::Preview, Action::MoveUp) => {
+                cs.preview_scroll = cs.preview_scroll.saturating_sub(1);
             }
-            KeyCode::Char('q') => {
-                self.exit = true;
+            (ComposePane::Preview, Action::TogglePane) => {
+                cs.active_pane = ComposePane::List;
             }
             _ => {}
         }
+        Vec::new()
     }
 
-    pub(crate) fn handle_export_path_key(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Enter => {
-                self.execute_export();
-            }
-            KeyCode::Esc => {
-                self.mode = Mode::Normal;
-                self.text_input.clear();
-            }
-            _ => {
-                self.text_input.handle_edit_key(key_event.code);
-            }
-        }
-    }
-
-    fn execute_export(&mut self) {
+    pub(crate) fn execute_export(&mut self) {
         let raw_path = self.text_input.text().trim().to_string();
         if raw_path.is_empty() {
             self.status_message = Some("No path entered.".to_string());
             return;
         }
 
-        let expanded = if raw_path.starts_with('~') {
-            if let Ok(home) = std::env::var("HOME") {
-                raw_path.replacen('~', &home, 1)
-            } else {
-                self.status_message = Some("Cannot expand ~: HOME not set.".to_string());
-                return;
-            }
-        } else {
-            raw_path
+        let Some(path) = crate::fsutil::expand_home(&raw_path) else {
+            self.status_message = Some("Cannot expand ~: HOME not set.".to_string());
+            return;
         };
 
-        let path = std::path::PathBuf::from(&expanded);
-
         if let Some(parent) = path.parent()
             && !parent.as_os_str().is_empty()
             && !parent.exists()
@@ -492,6 +434,12 @@ mod tests {
                     title: title.to_string(),
                     content: content.to_string(),
                     source: String::new(),
+                    format: None,
+                    archived: false,
+                    tags: Vec::new(),
+                    lines: None,
+                    created: None,
+                    scope: Default::default(),
                 })
                 .collect(),
         });
@@ -0,0 +1,351 @@
+use std::path::PathBuf;
+
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Text;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+
+use super::action::Action;
+use super::action::Effect;
+use super::app::App;
+use super::app::Mode;
+use super::picker::centered_rect;
+use crate::diff::DiffLine;
+use crate::extract::SectionExtraction;
+use crate::library::SnippetLibrary;
+use crate::migrate::LocalMigration;
+
+/// Unchanged lines kept around each change in a preview.
+pub(crate) const PREVIEW_CONTEXT_LINES: usize = 3;
+
+/// What happens when the user accepts a diff preview.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PreviewAction {
+    /// Write the content of `backup` back to `file`.
+    RestoreBackup { file: PathBuf, backup: PathBuf },
+    /// Open `file` in the editor with its autosaved draft. Rejecting the
+    /// preview discards the draft and opens the file as saved.
+    RestoreDraft { file: PathBuf },
+    /// Write `content` to `file` and report `success` in the status bar.
+    WriteFile {
+        file: PathBuf,
+        content: String,
+        success: String,
+    },
+    /// Move a `CLAUDE.local.md` into an imported document.
+    MigrateLocal(LocalMigration),
+    /// Move a section into a new document imported in its place.
+    ExtractSection(SectionExtraction),
+    /// Save `library` as the snippet library at `path`.
+    WriteLibrary {
+        path: PathBuf,
+        library: SnippetLibrary,
+        success: String,
+    },
+    /// Back up and delete `file`, dropping it from the tree.
+    DeleteFile { file: PathBuf },
+    /// Write trimmed `content` to `file`, first saving `archive`, the
+    /// library with the removed sections added, when they are archived.
+    TrimFile {
+        file: PathBuf,
+        content: String,
+        archive: Option<(PathBuf, SnippetLibrary)>,
+        success: String,
+    },
+    /// Write `to_content` to `to` and `content`, the file without the
+    /// moved lines, to `from`.
+    MoveLines {
+        from: PathBuf,
+        content: String,
+        to: PathBuf,
+        to_content: String,
+        success: String,
+    },
+}
+
+/// A modal showing the diff of a pending change: `y` accepts, `n` rejects and
+/// `Esc` closes without doing either.
+#[derive(Debug)]
+pub struct DiffPreview {
+    /// Title shown in the popup border.
+    pub title: String,
+    /// Diff lines, already collapsed around the changes.
+    pub lines: Vec<DiffLine>,
+    /// First visible line.
+    pub scroll: u16,
+    /// Captured during draw — visible rows inside the border.
+    pub viewport_height: u16,
+    /// What accepting the preview does.
+    pub action: PreviewAction,
+}
+
+impl DiffPreview {
+    /// Creates a preview scrolled to the top.
+    pub fn new(title: impl Into<String>, lines: Vec<DiffLine>, action: PreviewAction) -> Self {
+        Self {
+            title: title.into(),
+            lines,
+            scroll: 0,
+            viewport_height: 0,
+            action,
+        }
+    }
+
+    fn max_scroll(&self) -> u16 {
+        let max = self
+            .lines
+            .len()
+            .saturating_sub(self.viewport_height as usize);
+        max.min(u16::MAX as usize) as u16
+    }
+
+    /// Scrolls down by `rows`, clamping at the end of the diff.
+    pub fn scroll_down(&mut self, rows: u16) {
+        self.scroll = self.scroll.saturating_add(rows).min(self.max_scroll());
+    }
+
+    /// Scrolls up by `rows`.
+    pub fn scroll_up(&mut self, rows: u16) {
+        self.scroll = self.scroll.saturating_sub(rows);
+    }
+}
+
+impl App {
+    /// Opens `preview` as a modal overlay.
+    pub(crate) fn open_diff_preview(&mut self, preview: DiffPreview) {
+        self.diff_preview = Some(preview);
+        self.mode = Mode::DiffPreview;
+    }
+
+    /// Draws the active diff preview over most of `area`.
+    pub(crate) fn draw_diff_preview(&mut self, frame: &mut Frame, area: Rect) {
+        let theme = &self.theme;
+        let Some(preview) = &mut self.diff_preview else {
+            return;
+        };
+
+        let popup = centered_rect(90, area.height.saturating_sub(2), area);
+        preview.viewport_height = popup.height.saturating_sub(2);
+        preview.scroll = preview.scroll.min(preview.max_scroll());
+
+        let lines: Vec<Line> = preview
+            .lines
+            .iter()
+            .map(|line| match line {
+                DiffLine::Context(text) => Line::styled(format!("  {text}"), Style::default()),
+                DiffLine::Removed(text) => Line::styled(format!("- {text}"), theme.diff_removed),
+                DiffLine::Added(text) => Line::styled(format!("+ {text}"), theme.diff_added),
+                DiffLine::Skipped(count) => Line::styled(
+                    format!(
+                        "  ⋯ {count} unchanged line{}",
+                        if *count == 1 { "" } else { "s" }
+                    ),
+                    theme.help_desc,
+                ),
+            })
+            .collect();
+
+        let widget = Paragraph::new(Text::from(lines))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(theme.input_border)
+                    .title(preview.title.as_str()),
+            )
+            .scroll((preview.scroll, 0));
+        frame.render_widget(Clear, popup);
+        frame.render_widget(widget, popup);
+    }
+
+    /// Applies an action while a diff preview is open.
+    pub(crate) fn update_diff_preview(&mut self, action: Action) -> Vec<Effect> {
+        match action {
+            Action::Cancel => {
+                self.diff_preview = None;
+                self.mode = self.mode_after_modal();
+                self.status_message = Some("Cancelled.".to_string());
+            }
+            Action::Confirm | Action::Reject => {
+                self.mode = self.mode_after_modal();
+                let Some(preview) = self.diff_preview.take() else {
+                    return Vec::new();
+                };
+                return vec![if action == Action::Confirm {
+                    Effect::AcceptPreview(preview.action)
+                } else {
+                    Effect::RejectPreview(preview.action)
+                }];
+            }
+            Action::MoveDown => {
+                if let Some(preview) = &mut self.diff_preview {
+                    preview.scroll_down(1);
+                }
+            }
+            Action::MoveUp => {
+                if let Some(preview) = &mut self.diff_preview {
+                    preview.scroll_up(1);
+                }
+            }
+            Action::PageDown => {
+                if let Some(preview) = &mut self.diff_preview {
+                    preview.scroll_down(preview.viewport_height.max(1));
+                }
+            }
+            Action::PageUp => {
+                if let Some(preview) = &mut self.diff_preview {
+                    preview.scroll_up(preview.viewport_height.max(1));
+                }
+            }
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    /// Carries out an accepted preview.
+    pub(crate) fn accept_preview(&mut self, action: PreviewAction) {
+        match action {
+            PreviewAction::RestoreBackup { file, backup } => {
+                self.restore_backup(&file, &backup);
+            }
+            PreviewAction::RestoreDraft { file } => {
+                self.restore_draft(&file);
+            }
+            PreviewAction::WriteFile {
+                file,
+                content,
+                success,
+            } => {
+                self.apply_file_write(&file, &content, success);
+            }
+            PreviewAction::MigrateLocal(migration) => {
+                self.apply_local_migration(&migration);
+            }
+            PreviewAction::ExtractSection(extraction) => {
+                self.apply_section_extraction(&extraction);
+            }
+            PreviewAction::WriteLibrary {
+                path,
+                library,
+                success,
+            } => {
+                self.apply_library_write(&path, library, success);
+            }
+            PreviewAction::DeleteFile { file } => {
+                self.delete_file(&file);
+            }
+            PreviewAction::TrimFile {
+                file,
+                content,
+                archive,
+                success,
+            } => {
+                self.apply_trim(&file, &content, archive, success);
+            }
+            PreviewAction::MoveLines {
+                from,
+                content,
+                to,
+                to_content,
+                success,
+            } => {
+                self.apply_line_move(&from, &content, &to, &to_content, success);
+            }
+        }
+    }
+
+    /// Handles a rejected preview. Most changes are simply dropped; a
+    /// rejected draft is discarded and the saved file opened instead.
+    pub(crate) fn reject_preview(&mut self, action: PreviewAction) {
+        match action {
+            PreviewAction::RestoreBackup { .. }
+            | PreviewAction::WriteFile { .. }
+            | PreviewAction::MigrateLocal(_)
+            | PreviewAction::ExtractSection(_)
+            | PreviewAction::WriteLibrary { .. }
+            | PreviewAction::DeleteFile { .. }
+            | PreviewAction::TrimFile { .. }
+            | PreviewAction::MoveLines { .. } => {
+                self.status_message = Some("Cancelled.".to_string());
+            }
+            PreviewAction::RestoreDraft { file } => {
+                self.discard_draft(&file);
+                self.open_editor(&file);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use ratatui::crossterm::event::KeyCode;
+
+    use crate::config::Config;
+    use crate::diff::DiffLine;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::test_helpers::key_event;
+    use crate::tui::app::test_helpers::render_once;
+
+    use super::DiffPreview;
+    use super::PreviewAction;
+
+    fn sample_preview(len: usize) -> DiffPreview {
+        DiffPreview::new(
+            "Preview",
+            (0..len).map(|i| DiffLine::Added(i.to_string())).collect(),
+            PreviewAction::RestoreBackup {
+                file: PathBuf::from("/nope/CLAUDE.md"),
+                backup: PathBuf::from("/nope/backup.md"),
+            },
+        )
+    }
+
+    #[test]
+    fn scroll_clamps_to_content() {
+        let mut preview = sample_preview(10);
+        preview.viewport_height = 4;
+
+        preview.scroll_down(100);
+        assert_eq!(preview.scroll, 6);
+
+        preview.scroll_up(100);
+        assert_eq!(preview.scroll, 0);
+    }
+
+    #[test]
+    fn esc_cancels_preview() {
+        let mut app = App::new(vec![], &Config::default());
+        app.open_diff_preview(sample_preview(3));
+
+        app.handle_key_event(key_event(KeyCode::Esc));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.diff_preview.is_none());
+    }
+
+    #[test]
+    fn q_in_preview_does_not_exit() {
+        let mut app = App::new(vec![], &Config::default());
+        app.open_diff_preview(sample_preview(3));
+
+        app.handle_key_event(key_event(KeyCode::Char('q')));
+
+        assert!(!app.exit);
+        assert_eq!(app.mode, Mode::DiffPreview);
+    }
+
+    #[test]
+    fn preview_renders_without_panic() {
+        let mut app = App::new(vec![], &Config::default());
+        app.open_diff_preview(sample_preview(50));
+        render_once(&mut app);
+        assert!(app.diff_preview.as_ref().unwrap().viewport_height > 0);
+    }
+}
@@ -0,0 +1,272 @@
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+
+use tui_textarea::TextArea;
+
+use super::app::App;
+use super::diff_preview::DiffPreview;
+use super::diff_preview::PREVIEW_CONTEXT_LINES;
+use super::diff_preview::PreviewAction;
+use crate::diff::collapse_context;
+use crate::diff::diff_lines;
+use crate::drafts::drafts_dir_in;
+use crate::drafts::read_draft;
+use crate::drafts::remove_draft;
+use crate::drafts::write_draft;
+
+/// How long an unsaved change may go without being written to a draft.
+const DRAFT_INTERVAL: Duration = Duration::from_secs(3);
+
+impl App {
+    /// Returns the drafts directory, or `None` when drafts are disabled.
+    fn drafts_dir(&self) -> Option<PathBuf> {
+        self.data_dir.as_deref().map(drafts_dir_in)
+    }
+
+    /// Writes the editor buffer to its draft once it has had unsaved changes
+    /// for at least [`DRAFT_INTERVAL`]. A buffer edited back to its saved
+    /// state removes the draft instead.
+    pub(crate) fn autosave_draft(&mut self, now: Instant) {
+        if self.editing_snippet_index.is_some() {
+            return;
+        }
+        let Some(dir) = self.drafts_dir() else {
+            return;
+        };
+        let Some(edit) = &mut self.edit_state else {
+            return;
+        };
+        let Some(changed_at) = edit.draft_changed_at else {
+            return;
+        };
+        if now.saturating_duration_since(changed_at) < DRAFT_INTERVAL {
+            return;
+        }
+
+        edit.draft_changed_at = None;
+        let result = if edit.is_dirty() {
+            write_draft(&dir, &edit.file_path, &edit.buffer_text())
+        } else {
+            remove_draft(&dir, &edit.file_path)
+        };
+        if let Err(err) = result {
+            self.status_message = Some(format!("Draft autosave failed: {err:#}"));
+        }
+    }
+
+    /// Deletes the draft of `path`, if any. Failures are ignored: a stale
+    /// draft only causes an extra restore prompt later.
+    pub(crate) fn discard_draft(&self, path: &Path) {
+        if let Some(dir) = self.drafts_dir() {
+            let _ = remove_draft(&dir, path);
+        }
+    }
+
+    /// Returns the draft of `path` if it differs from the file on disk.
+    /// Drafts identical to the file are stale and removed.
+    pub(crate) fn pending_draft(&self, path: &Path) -> Option<String> {
+        let dir = self.drafts_dir()?;
+        let draft = read_draft(&dir, path).ok()??;
+        if fs::read_to_string(path).is_ok_and(|current| current == draft) {
+            let _ = remove_draft(&dir, path);
+            return None;
+        }
+        Some(draft)
+    }
+
+    /// Asks whether to restore `draft`, showing how it differs from `path`.
+    pub(crate) fn offer_draft_restore(&mut self, path: &Path, draft: &str) {
+        let current = fs::read_to_string(path).unwrap_or_default();
+        let title = format!(
+            "Unsaved draft of {} found — y: restore, n: discard",
+            path.display()
+        );
+        self.open_diff_preview(DiffPreview::new(
+            title,
+            collapse_context(diff_lines(&current, draft), PREVIEW_CONTEXT_LINES),
+            PreviewAction::RestoreDraft {
+                file: path.to_path_buf(),
+            },
+        ));
+    }
+
+    /// Opens the editor on `path` with its draft loaded as unsaved changes.
+    pub(crate) fn restore_draft(&mut self, path: &Path) {
+        let draft = self
+            .drafts_dir()
+            .and_then(|dir| read_draft(&dir, path).ok().flatten());
+        self.open_editor(path);
+        let Some(draft) = draft else {
+            return;
+        };
+        let Some(edit) = &mut self.edit_state else {
+            return;
+        };
+        let text = draft.strip_suffix('\n').unwrap_or(&draft);
+        let lines: Vec<String> = text.lines().map(String::from).collect();
+        let lines = if lines.is_empty() {
+            vec![String::new()]
+        } else {
+            lines
+        };
+        let mut textarea = TextArea::new(lines);
+        textarea.set_tab_length(4);
+        textarea.set_cursor_line_style(self.theme.edit_cursor_line);
+        edit.textarea = textarea;
+        edit.invalidate_dirty_cache();
+        self.status_message = Some("Draft restored — Ctrl+S to save it.".to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use ratatui::crossterm::event::KeyCode;
+    use ratatui::crossterm::event::KeyEvent;
+    use ratatui::crossterm::event::KeyEventKind;
+    use ratatui::crossterm::event::KeyEventState;
+    use ratatui::crossterm::event::KeyModifiers;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::drafts::drafts_dir_in;
+    use crate::drafts::read_draft;
+    use crate::drafts::write_draft;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::test_helpers::key_event;
+
+    use super::DRAFT_INTERVAL;
+
+    fn app_with_data(data: &Path) -> App {
+        let mut app = App::new(vec![], &Config::default());
+        app.data_dir = Some(data.to_path_buf());
+        app
+    }
+
+    fn ctrl_s() -> KeyEvent {
+        KeyEvent {
+            code: KeyCode::Char('s'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::empty(),
+        }
+    }
+
+    #[test]
+    fn draft_written_only_after_interval() {
+        let tmp = TempDir::new().unwrap();
+        let data = tmp.path().join("data");
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "saved\n").unwrap();
+        let mut app = app_with_data(&data);
+        app.enter_edit_mode_for(&file);
+
+        app.handle_key_event(key_event(KeyCode::Char('x')));
+        let typed_at = Instant::now();
+        app.tick(typed_at);
+        assert!(
+            read_draft(&drafts_dir_in(&data), &file).unwrap().is_none(),
+            "draft should wait for the interval"
+        );
+
+        app.tick(typed_at + DRAFT_INTERVAL + Duration::from_millis(10));
+        let draft = read_draft(&drafts_dir_in(&data), &file).unwrap().unwrap();
+        assert_eq!(draft, "xsaved\n");
+    }
+
+    #[test]
+    fn saving_removes_draft() {
+        let tmp = TempDir::new().unwrap();
+        let data = tmp.path().join("data");
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "saved").unwrap();
+        let mut app = app_with_data(&data);
+        app.enter_edit_mode_for(&file);
+        app.handle_key_event(key_event(KeyCode::Char('x')));
+        app.tick(Instant::now() + DRAFT_INTERVAL * 2);
+
+        app.handle_key_event(ctrl_s());
+
+        assert!(read_draft(&drafts_dir_in(&data), &file).unwrap().is_none());
+    }
+
+    #[test]
+    fn reopening_offers_draft_and_y_restores_it() {
+        let tmp = TempDir::new().unwrap();
+        let data = tmp.path().join("data");
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "saved").unwrap();
+        write_draft(&drafts_dir_in(&data), &file, "unsaved work").unwrap();
+        let mut app = app_with_data(&data);
+
+        app.enter_edit_mode_for(&file);
+        assert_eq!(app.mode, Mode::DiffPreview);
+
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert_eq!(app.mode, Mode::Edit);
+        let edit = app.edit_state.as_ref().unwrap();
+        assert_eq!(edit.textarea.lines().join("\n"), "unsaved work");
+        assert!(edit.is_dirty());
+        assert_eq!(fs::read_to_string(&file).unwrap(), "saved");
+    }
+
+    #[test]
+    fn n_discards_draft_and_opens_saved_file() {
+        let tmp = TempDir::new().unwrap();
+        let data = tmp.path().join("data");
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "saved").unwrap();
+        write_draft(&drafts_dir_in(&data), &file, "unsaved work").unwrap();
+        let mut app = app_with_data(&data);
+
+        app.enter_edit_mode_for(&file);
+        app.handle_key_event(key_event(KeyCode::Char('n')));
+
+        assert_eq!(app.mode, Mode::Edit);
+        let edit = app.edit_state.as_ref().unwrap();
+        assert_eq!(edit.textarea.lines().join("\n"), "saved");
+        assert!(read_draft(&drafts_dir_in(&data), &file).unwrap().is_none());
+    }
+
+    #[test]
+    fn draft_matching_file_is_dropped_silently() {
+        let tmp = TempDir::new().unwrap();
+        let data = tmp.path().join("data");
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "same").unwrap();
+        write_draft(&drafts_dir_in(&data), &file, "same").unwrap();
+        let mut app = app_with_data(&data);
+
+        app.enter_edit_mode_for(&file);
+
+        assert_eq!(app.mode, Mode::Edit);
+        assert!(read_draft(&drafts_dir_in(&data), &file).unwrap().is_none());
+    }
+
+    #[test]
+    fn discarding_changes_removes_draft() {
+        let tmp = TempDir::new().unwrap();
+        let data = tmp.path().join("data");
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "saved").unwrap();
+        let mut app = app_with_data(&data);
+        app.enter_edit_mode_for(&file);
+        app.handle_key_event(key_event(KeyCode::Char('x')));
+        app.tick(Instant::now() + DRAFT_INTERVAL * 2);
+
+        app.handle_key_event(key_event(KeyCode::Esc));
+        app.handle_key_event(key_event(KeyCode::Esc));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(read_draft(&drafts_dir_in(&data), &file).unwrap().is_none());
+    }
+}
@@ -1,21 +1,27 @@
 use std::cell::Cell;
 use std::fs;
-use std::io::Write;
 use std::path::Path;
-use std::path::PathBuf;
+use std::time::Instant;
 
 use ratatui::Frame;
-use ratatui::crossterm::event::KeyCode;
-use ratatui::crossterm::event::KeyEvent;
-use ratatui::crossterm::event::KeyModifiers;
+use ratatui::layout::Constraint;
+use ratatui::layout::Direction;
+use ratatui::layout::Layout;
 use ratatui::widgets::Block;
 use ratatui::widgets::Borders;
 use tui_textarea::TextArea;
 
+use super::action::Action;
+use super::action::Effect;
 use super::app::App;
 use super::app::EditState;
 use super::app::Mode;
 use super::app::Screen;
+use super::files::SPLIT_PREVIEW_MIN_WIDTH;
+use super::markdown::preview_pane;
+use super::markdown::render_markdown;
+use crate::fsutil::write_atomic;
+use crate::journal::file_size;
 
 impl App {
     pub(crate) fn draw_edit_pane(&mut self, frame: &mut Frame, area: ratatui::layout::Rect) {
@@ -45,22 +51,37 @@ impl App {
                 .title(title),
         );
 
-        frame.render_widget(&edit.textarea, area);
+        if self.split_preview && area.width >= SPLIT_PREVIEW_MIN_WIDTH {
+            let chunks = Layout::default()
+                .direction(Direction::Horizontal)
+                .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .split(area);
+            frame.render_widget(&edit.textarea, chunks[0]);
+
+            // Scroll like the textarea does: just enough to keep the cursor in view
+            let height = usize::from(chunks[1].height.saturating_sub(2)).max(1);
+            let (row, _) = edit.textarea.cursor();
+            if row < edit.preview_top {
+                edit.preview_top = row;
+            } else if row >= edit.preview_top + height {
+                edit.preview_top = row + 1 - height;
+            }
+            let width = chunks[1].width.saturating_sub(2);
+            let lines = render_markdown(&edit.textarea.lines().join("\n"), &self.theme, width);
+            let widget = preview_pane(&lines, edit.preview_top, height, Some(row), &self.theme);
+            frame.render_widget(widget, chunks[1]);
+        } else {
+            frame.render_widget(&edit.textarea, area);
+        }
 
         // Put back
         self.edit_state = Some(edit);
     }
 
     pub(crate) fn enter_edit_mode(&mut self) {
-        let selected = self.tree_state.selected();
-        if selected.len() < 2 {
+        let Some(path) = self.selected_file() else {
             return;
-        }
-        let path_str = match selected.last() {
-            Some(s) => s.clone(),
-            None => return,
         };
-        let path = PathBuf::from(&path_str);
         self.enter_edit_mode_for(&path);
     }
 
@@ -69,7 +90,22 @@ impl App {
     const MAX_EDIT_FILE_SIZE: u64 = 10 * 1024 * 1024; // 10 MB
 
     /// Enters edit mode for a specific file path. Extracted for testability.
+    ///
+    /// If an autosaved draft from an earlier session exists, the user is
+    /// asked whether to restore it before the editor opens.
     pub fn enter_edit_mode_for(&mut self, path: &Path) {
+        if !self.ensure_writable(path, "edit") {
+            return;
+        }
+        if let Some(draft) = self.pending_draft(path) {
+            self.offer_draft_restore(path, &draft);
+            return;
+        }
+        self.open_editor(path);
+    }
+
+    /// Opens the editor on the file content as it is on disk.
+    pub(crate) fn open_editor(&mut self, path: &Path) {
         // Guard against very large files to prevent OOM
         match fs::metadata(path) {
             Ok(meta) if meta.len() > Self::MAX_EDIT_FILE_SIZE => {
@@ -119,31 +155,44 @@ impl App {
             had_trailing_newline,
             discard_confirmed: false,
             dirty_cache: Cell::new(Some(false)),
+            draft_changed_at: None,
+            preview_top: 0,
         });
         self.mode = Mode::Edit;
     }
 
-    pub(crate) fn handle_edit_key(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Char('s') if key_event.modifiers.contains(KeyModifiers::CONTROL) => {
-                self.save_edit();
-            }
-            KeyCode::Esc => {
-                self.exit_edit_mode();
+    /// Applies an action in the editor.
+    pub(crate) fn update_editor(&mut self, action: Action) -> Vec<Effect> {
+        match action {
+            Action::Save => return vec![Effect::SaveEdit],
+            Action::Cancel => {
+                if let Some(edit) = &mut self.edit_state
+                    && edit.is_dirty()
+                    && !edit.discard_confirmed
+                {
+                    edit.discard_confirmed = true;
+                    self.status_message =
+                        Some("You have unsaved changes. Press Esc again to discard.".to_string());
+                    return Vec::new();
+                }
+                return vec![Effect::CloseEditor];
             }
-            _ => {
+            Action::Input(key_event) => {
                 // Forward all other keys to the textarea
                 if let Some(edit) = &mut self.edit_state {
                     edit.textarea.input(key_event);
                     edit.invalidate_dirty_cache();
+                    edit.draft_changed_at.get_or_insert_with(Instant::now);
                     // Any non-Esc key resets the discard confirmation
                     edit.discard_confirmed = false;
                 }
             }
+            _ => {}
         }
+        Vec::new()
     }
 
-    fn save_edit(&mut self) {
+    pub(crate) fn save_edit(&mut self) {
         // If editing a library snippet, save back to library
         if self.editing_snippet_index.is_some() {
             self.save_snippet_edit();
@@ -159,61 +208,58 @@ impl App {
 
     /// Saves the current edit to a specific path. Extracted for testability.
     pub fn save_edit_to(&mut self, path: &Path) {
-        let Some(edit) = &mut self.edit_state else {
+        let Some(edit) = &self.edit_state else {
             return;
         };
 
         let joined = edit.textarea.lines().join("\n");
-        let write_content = if edit.had_trailing_newline {
-            format!("{joined}\n")
-        } else {
-            joined.clone()
-        };
-
-        // Atomic write: write to temp file in the same directory, then rename.
-        let parent = path.parent().unwrap_or(Path::new("."));
-        let result = tempfile::NamedTempFile::new_in(parent).and_then(|mut tmp| {
-            tmp.write_all(write_content.as_bytes())?;
-            tmp.flush()?;
-            tmp.persist(path).map_err(|e| e.error)?;
-            Ok(())
-        });
+        let write_content = edit.buffer_text();
 
-        match result {
-            Ok(()) => {
-                // Update original_text so the dirty flag clears
-                edit.original_text = joined;
-                edit.dirty_cache.set(Some(false));
-                self.status_message = Some("Saved.".to_string());
-            }
-            Err(err) => {
-                self.status_message = Some(format!("Save failed: {err}"));
-            }
-        }
-    }
-
-    fn exit_edit_mode(&mut self) {
-        let Some(edit) = &mut self.edit_state else {
-            self.finalize_exit_edit();
+        let warning = self.backup_before_write(path);
+        let before = file_size(path);
+        if let Err(err) = write_atomic(path, &write_content) {
+            self.status_message = Some(format!("Save failed: {err}"));
             return;
+        }
+        let action = if self.new_file_path.as_deref() == Some(path) {
+            "create"
+        } else {
+            "edit"
         };
+        self.journal_change(action, path, before);
 
-        if edit.is_dirty() && !edit.discard_confirmed {
-            edit.discard_confirmed = true;
-            self.status_message =
-                Some("You have unsaved changes. Press Esc again to discard.".to_string());
-            return;
+        if let Some(edit) = &mut self.edit_state {
+            // Update original_text so the dirty flag clears
+            edit.original_text = joined;
+            edit.dirty_cache.set(Some(false));
+            edit.draft_changed_at = None;
         }
+        self.discard_draft(path);
+        self.status_message = Some(match warning {
+            Some(warning) => format!("Saved ({warning})."),
+            None => "Saved.".to_string(),
+        });
 
-        self.finalize_exit_edit();
+        // A newly created file joins the tree on its first successful save
+        if self.new_file_path.as_deref() == Some(path) {
+            self.new_file_path = None;
+            self.register_file(path);
+        }
     }
 
     pub(crate) fn finalize_exit_edit(&mut self) {
-        // Reload content into the read-only viewer if on Files screen
+        // Reload content into the read-only viewer if on Files screen. A new
+        // file that was never saved has nothing to show, so fall back to the
+        // tree selection.
         if self.screen == Screen::Files
             && let Some(edit) = &self.edit_state
         {
-            self.load_file_content(&edit.file_path.clone());
+            let path = edit.file_path.clone();
+            if path.exists() {
+                self.load_file_content(&path);
+            } else {
+                self.load_selected_content();
+            }
         }
 
         // If on Settings screen, refresh the formatted view
@@ -221,8 +267,16 @@ impl App {
             self.refresh_settings();
         }
 
+        // Saved or deliberately discarded: either way the draft is obsolete
+        if self.editing_snippet_index.is_none()
+            && let Some(edit) = &self.edit_state
+        {
+            self.discard_draft(&edit.file_path);
+        }
+
         self.edit_state = None;
         self.editing_snippet_index = None;
+        self.new_file_path = None;
         self.mode = Mode::Normal;
     }
 }
@@ -246,6 +300,7 @@ mod tests {
     use tempfile::TempDir;
 
     use crate::config::Config;
+    use crate::model::DiscoveredFile;
     use crate::model::SourceRoot;
     use crate::tui::app::App;
     use crate::tui::app::EditState;
@@ -283,6 +338,8 @@ mod tests {
             had_trailing_newline: false,
             discard_confirmed: false,
             dirty_cache: Cell::new(None),
+            draft_changed_at: None,
+            preview_top: 0,
         };
         assert!(!state.is_dirty(), "Unmodified textarea should not be dirty");
 
@@ -294,6 +351,8 @@ mod tests {
             had_trailing_newline: false,
             discard_confirmed: false,
             dirty_cache: Cell::new(None),
+            draft_changed_at: None,
+            preview_top: 0,
         };
         assert!(state2.is_dirty(), "Modified textarea should be dirty");
     }
@@ -306,7 +365,10 @@ mod tests {
 
         let roots = vec![SourceRoot {
             path: tmp.path().to_path_buf(),
-            files: vec![file],
+            files: vec![DiscoveredFile::new(file)],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
         }];
         let mut app = App::new(roots, &Config::default());
         app.active_pane = Pane::Content;
@@ -328,7 +390,10 @@ mod tests {
 
         let roots = vec![SourceRoot {
             path: tmp.path().to_path_buf(),
-            files: vec![file],
+            files: vec![DiscoveredFile::new(file)],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
         }];
         let mut app = App::new(roots, &Config::default());
         app.active_pane = Pane::Content;
@@ -341,6 +406,54 @@ mod tests {
         // If we get here without panic, the test passes
     }
 
+    #[test]
+    fn split_preview_renders_the_unsaved_text() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "item\n").unwrap();
+        let mut app = App::new(vec![], &Config::default());
+        app.split_preview = true;
+        app.enter_edit_mode_for(&file);
+        app.handle_key_event(key_event(KeyCode::Char('-')));
+        app.handle_key_event(key_event(KeyCode::Char(' ')));
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 24)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        assert!(screen.contains("- item"));
+        assert!(screen.contains("• item"));
+    }
+
+    #[test]
+    fn edit_title_marks_unsaved_changes() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "Hello").unwrap();
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_edit_mode_for(&file);
+        let mut terminal = Terminal::new(TestBackend::new(80, 24)).unwrap();
+        let title = |terminal: &Terminal<TestBackend>| -> String {
+            let buf = terminal.backend().buffer();
+            (0..buf.area.width)
+                .map(|x| buf[(x, 1)].symbol().to_string())
+                .collect()
+        };
+
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        assert!(!title(&terminal).contains("[*]"));
+
+        app.handle_key_event(key_event(KeyCode::Char('X')));
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        assert!(title(&terminal).contains("Edit: CLAUDE.md [*]"));
+    }
+
     #[test]
     fn e_in_file_list_does_not_enter_edit() {
         let tmp = TempDir::new().unwrap();
@@ -349,7 +462,10 @@ mod tests {
 
         let roots = vec![SourceRoot {
             path: tmp.path().to_path_buf(),
-            files: vec![file],
+            files: vec![DiscoveredFile::new(file)],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
         }];
         let mut app = App::new(roots, &Config::default());
         app.active_pane = Pane::FileList;
@@ -498,6 +614,48 @@ mod tests {
         assert_eq!(app.mode, Mode::Edit, "Should still be in edit mode");
     }
 
+    #[test]
+    fn read_only_file_is_badged_and_refuses_edit() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "Hello").unwrap();
+        let mut perms = fs::metadata(&file).unwrap().permissions();
+        perms.set_readonly(true);
+        fs::set_permissions(&file, perms).unwrap();
+        let mut app = App::new(
+            vec![SourceRoot {
+                path: tmp.path().to_path_buf(),
+                files: vec![DiscoveredFile::new(file.clone())],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        );
+        app.active_pane = Pane::Content;
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        assert!(screen.contains("Content [read-only]"));
+
+        app.handle_key_event(key_event(KeyCode::Char('e')));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(
+            app.status_message,
+            Some(format!(
+                "Cannot edit {}: the file is read-only.",
+                file.display()
+            ))
+        );
+    }
+
     #[test]
     fn q_in_edit_mode_types_q_not_exit() {
         let tmp = TempDir::new().unwrap();
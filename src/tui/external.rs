@@ -0,0 +1,215 @@
+//! Handing a file to the user's own editor, outside the TUI: the one set
+//! as `editor` in the config, else `$VISUAL` or `$EDITOR`.
+
+use std::env;
+use std::io;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+
+use ratatui::DefaultTerminal;
+use ratatui::crossterm::event::DisableMouseCapture;
+use ratatui::crossterm::event::EnableMouseCapture;
+use ratatui::crossterm::execute;
+use ratatui::crossterm::terminal::EnterAlternateScreen;
+use ratatui::crossterm::terminal::enable_raw_mode;
+
+use super::app::App;
+use super::app::Pane;
+use super::app::Screen;
+
+/// Editors that take the line to open at as a `+N` argument.
+const PLUS_LINE_EDITORS: &[&str] = &[
+    "vi",
+    "vim",
+    "nvim",
+    "nano",
+    "emacs",
+    "emacsclient",
+    "micro",
+    "kak",
+    "joe",
+    "ne",
+    "mg",
+];
+
+/// Editors that take the line as a `path:N` suffix.
+const PATH_LINE_EDITORS: &[&str] = &["hx", "helix", "subl", "zed"];
+
+/// A file to open in the external editor once the current key is handled.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExternalEdit {
+    pub path: PathBuf,
+    /// 1-based line to open at, when the editor supports it.
+    pub line: Option<usize>,
+}
+
+/// The user's editor command: `$VISUAL`, then `$EDITOR`, then `vi`.
+pub fn editor_from_env() -> String {
+    ["VISUAL", "EDITOR"]
+        .into_iter()
+        .filter_map(|var| env::var(var).ok())
+        .find(|value| !value.trim().is_empty())
+        .unwrap_or_else(|| "vi".to_string())
+}
+
+/// The command opening `path` at `line` in `editor`, which may carry its
+/// own arguments (`code -w`). The line is passed in the editor's syntax
+/// when it is a known one and dropped otherwise. `None` for an empty
+/// `editor`.
+pub fn editor_command(editor: &str, path: &Path, line: Option<usize>) -> Option<Command> {
+    let mut words = editor.split_whitespace();
+    let program = words.next()?;
+    let mut command = Command::new(program);
+    command.args(words);
+
+    let name = Path::new(program)
+        .file_stem()
+        .and_then(|stem| stem.to_str())
+        .unwrap_or(program);
+    match line {
+        Some(line) if PLUS_LINE_EDITORS.contains(&name) => {
+            command.arg(format!("+{line}")).arg(path);
+        }
+        Some(line) if PATH_LINE_EDITORS.contains(&name) => {
+            command.arg(format!("{}:{line}", path.display()));
+        }
+        Some(line) if name.starts_with("code") || name == "codium" => {
+            command
+                .arg("--goto")
+                .arg(format!("{}:{line}", path.display()));
+        }
+        _ => {
+            command.arg(path);
+        }
+    }
+    Some(command)
+}
+
+impl App {
+    /// Queues the selected file for the external editor, at the cursor's
+    /// line when the content pane shows the file's lines as they are.
+    pub(crate) fn open_file_in_editor(&mut self) {
+        let Some(path) = self.selected_file() else {
+            self.status_message = Some("No file selected.".to_string());
+            return;
+        };
+        let line = (self.active_pane == Pane::Content
+            && self.content.hidden_comment_lines == 0
+            && self.content.inlined_imports.is_empty())
+        .then_some(self.content.cursor + 1);
+        self.external_edit = Some(ExternalEdit { path, line });
+    }
+
+    /// Leaves the TUI, runs the editor on `edit` until it exits, then
+    /// restores the terminal and reloads what the editor may have changed.
+    pub(crate) fn run_external_edit(
+        &mut self,
+        terminal: &mut DefaultTerminal,
+        edit: ExternalEdit,
+    ) -> io::Result<()> {
+        let editor = self
+            .config
+            .editor
+            .clone()
+            .filter(|editor| !editor.trim().is_empty())
+            .unwrap_or_else(editor_from_env);
+        let Some(mut command) = editor_command(&editor, &edit.path, edit.line) else {
+            self.status_message = Some("No editor set — set $EDITOR.".to_string());
+            return Ok(());
+        };
+
+        execute!(io::stdout(), DisableMouseCapture)?;
+        ratatui::restore();
+        let status = command.status();
+        enable_raw_mode()?;
+        execute!(io::stdout(), EnterAlternateScreen, EnableMouseCapture)?;
+        terminal.clear()?;
+
+        self.status_message = Some(match status {
+            Ok(status) if status.success() => format!("Closed {}.", edit.path.display()),
+            Ok(status) => format!("{editor} exited with {status}."),
+            Err(err) => format!("Cannot run {editor}: {err}"),
+        });
+        if self.screen == Screen::Files && self.selected_file().as_ref() == Some(&edit.path) {
+            self.reload_keeping_position();
+            self.relint(&edit.path);
+        }
+        if self.screen == Screen::Settings {
+            let cursor = self.settings_state.cursor;
+            self.refresh_settings();
+            let last = self.settings_state.lines.len().saturating_sub(1);
+            self.settings_state.cursor = cursor.min(last);
+            self.settings_state.ensure_cursor_visible();
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsStr;
+
+    use ratatui::crossterm::event::KeyCode;
+
+    use super::*;
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::test_helpers::key_event;
+
+    fn args(command: &Command) -> Vec<&OsStr> {
+        command.get_args().collect()
+    }
+
+    #[test]
+    fn vim_style_editors_take_plus_line() {
+        let command = editor_command("nvim", Path::new("/p/settings.json"), Some(12)).unwrap();
+        assert_eq!(command.get_program(), "nvim");
+        assert_eq!(args(&command), ["+12", "/p/settings.json"]);
+    }
+
+    #[test]
+    fn editor_arguments_are_kept() {
+        let command =
+            editor_command("/usr/bin/code -w", Path::new("/p/settings.json"), Some(3)).unwrap();
+        assert_eq!(command.get_program(), "/usr/bin/code");
+        assert_eq!(args(&command), ["-w", "--goto", "/p/settings.json:3"]);
+
+        let command = editor_command("hx", Path::new("/p/s.json"), Some(7)).unwrap();
+        assert_eq!(args(&command), ["/p/s.json:7"]);
+    }
+
+    #[test]
+    fn capital_e_queues_the_shown_file_at_the_cursor_line() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        std::fs::write(&file, "one\ntwo\nthree\n").unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![DiscoveredFile::new(file.clone())],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+        app.show_line(&file, 2);
+
+        app.handle_key_event(key_event(KeyCode::Char('E')));
+
+        assert_eq!(
+            app.external_edit,
+            Some(ExternalEdit {
+                path: file,
+                line: Some(3),
+            })
+        );
+    }
+
+    #[test]
+    fn unknown_editors_get_just_the_path() {
+        let command = editor_command("ed", Path::new("/p/s.json"), Some(7)).unwrap();
+        assert_eq!(args(&command), ["/p/s.json"]);
+        assert!(editor_command("  ", Path::new("/p/s.json"), None).is_none());
+    }
+}
@@ -0,0 +1,178 @@
+use std::fs;
+use std::ops::Range;
+use std::path::PathBuf;
+
+use super::app::App;
+use super::app::Mode;
+use super::diff_preview::DiffPreview;
+use super::diff_preview::PREVIEW_CONTEXT_LINES;
+use super::diff_preview::PreviewAction;
+use crate::diff::collapse_context;
+use crate::diff::diff_lines;
+use crate::extract::SectionExtraction;
+use crate::extract::default_extract_path;
+use crate::extract::plan_section_extraction;
+use crate::fsutil::write_atomic;
+use crate::outline::parse_headings;
+use crate::outline::section_at;
+use crate::outline::section_ranges;
+
+/// A section chosen for extraction while the path of its new file is typed.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingExtraction {
+    pub file: PathBuf,
+    /// 0-based lines of `file`.
+    pub lines: Range<usize>,
+}
+
+impl App {
+    /// Prompts for the file the section under the content cursor is
+    /// extracted to, offering `docs/<slug>.md`.
+    pub(crate) fn start_section_extraction(&mut self) {
+        let (Some(file), Some(text)) = (self.selected_file(), self.content.text.clone()) else {
+            self.status_message = Some("Open a file first.".to_string());
+            return;
+        };
+        // The pane must show the file as it is to change it safely.
+        if !self.content.shows_file_as_is() {
+            self.status_message = Some(
+                "Show the whole file, with its comments and imports collapsed, to extract a section."
+                    .to_string(),
+            );
+            return;
+        }
+        let headings = parse_headings(&text);
+        let Some(index) = section_at(&headings, self.content.cursor) else {
+            self.status_message = Some("Move the cursor into a section to extract it.".to_string());
+            return;
+        };
+        if !self.unchanged_on_disk(&file, &text) || !self.ensure_writable(&file, "edit") {
+            return;
+        }
+        let lines = section_ranges(&headings, text.lines().count())[index].clone();
+        self.text_input
+            .set(&default_extract_path(&headings[index].title));
+        self.pending_extraction = Some(PendingExtraction { file, lines });
+        self.mode = Mode::ExtractPath;
+    }
+
+    /// Previews extracting the pending section to the path typed in.
+    pub(crate) fn submit_extract_path(&mut self) {
+        let Some(pending) = &self.pending_extraction else {
+            self.reset_to_normal();
+            return;
+        };
+        let text = self.content.text.clone().unwrap_or_default();
+        let target = self.text_input.text().trim().to_string();
+        let extraction =
+            match plan_section_extraction(&pending.file, &text, pending.lines.clone(), &target) {
+                Ok(extraction) => extraction,
+                Err(err) => {
+                    self.status_message = Some(format!("Cannot extract: {err:#}"));
+                    return;
+                }
+            };
+        self.pending_extraction = None;
+        self.reset_to_normal();
+        self.open_diff_preview(DiffPreview::new(
+            format!(
+                "Extract the section to {} and import it? (y/n)",
+                extraction.doc.display()
+            ),
+            collapse_context(
+                diff_lines(&text, &extraction.content),
+                PREVIEW_CONTEXT_LINES,
+            ),
+            PreviewAction::ExtractSection(extraction),
+        ));
+    }
+
+    /// Carries out a previewed extraction: writes the new document, then
+    /// puts the import in place of the section, backing the file up first.
+    pub(crate) fn apply_section_extraction(&mut self, extraction: &SectionExtraction) {
+        if let Some(dir) = extraction.doc.parent()
+            && let Err(err) = fs::create_dir_all(dir)
+        {
+            self.status_message = Some(format!("Cannot create {}: {err}", dir.display()));
+            return;
+        }
+        if let Err(err) = write_atomic(&extraction.doc, &extraction.doc_content) {
+            self.status_message = Some(format!("Extraction failed: {err}"));
+            return;
+        }
+        self.journal_change("create", &extraction.doc, 0);
+        let warning = match self.write_with_backup("write", &extraction.file, &extraction.content) {
+            Ok(warning) => warning,
+            Err(err) => {
+                // The section is still in the file; drop the copy.
+                let _ = fs::remove_file(&extraction.doc);
+                self.status_message = Some(format!("Extraction failed: {err}"));
+                return;
+            }
+        };
+        self.rebuild_tree();
+        self.load_file_content(&extraction.file);
+        let done = format!("Extracted the section to {}.", extraction.doc.display());
+        self.status_message = Some(match warning {
+            Some(warning) => format!("{done} ({warning})"),
+            None => done,
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::Pane;
+    use crate::tui::app::test_helpers::key_event;
+
+    #[test]
+    fn x_extracts_the_section_under_the_cursor_to_an_imported_file() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(
+            &file,
+            "# Project\n\n## Build Steps\nmake\n\n## Style\ntabs\n",
+        )
+        .unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![DiscoveredFile::new(file.clone())],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+        app.select_tree_file(&file);
+        app.load_selected_content();
+        app.active_pane = Pane::Content;
+
+        app.content.jump_to_line(3);
+        app.handle_key_event(key_event(KeyCode::Char('x')));
+        assert_eq!(app.mode, Mode::ExtractPath);
+        assert_eq!(app.text_input.text(), "docs/build-steps.md");
+
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::DiffPreview);
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "# Project\n\n@docs/build-steps.md\n\n## Style\ntabs\n"
+        );
+        assert_eq!(
+            fs::read_to_string(tmp.path().join("docs/build-steps.md")).unwrap(),
+            "## Build Steps\nmake\n"
+        );
+        assert!(app.content.text.as_deref().unwrap().contains("@docs/"));
+    }
+}
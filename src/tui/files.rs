@@ -1,12 +1,16 @@
+use std::fs;
+use std::mem;
 use std::path::Path;
 
 use ratatui::Frame;
 use ratatui::layout::Constraint;
 use ratatui::layout::Direction;
 use ratatui::layout::Layout;
+use ratatui::layout::Rect;
 use ratatui::style::Modifier;
 use ratatui::style::Style;
 use ratatui::text::Line;
+use ratatui::text::Span;
 use ratatui::text::Text;
 use ratatui::widgets::Block;
 use ratatui::widgets::Borders;
@@ -15,27 +19,97 @@ use ratatui::widgets::Scrollbar;
 use ratatui::widgets::ScrollbarOrientation;
 use ratatui::widgets::ScrollbarState;
 
-use ratatui::crossterm::event::KeyCode;
-use ratatui::crossterm::event::KeyEvent;
-
 use tui_tree_widget::Tree;
 
+use super::action::Action;
+use super::action::Effect;
 use super::app::App;
 use super::app::Mode;
 use super::app::Pane;
+use super::app::scroll_to_show;
+use super::markdown::preview_pane;
+use super::markdown::render_markdown;
+use super::picker::Picker;
+use super::picker::PickerAction;
+use super::picker::PickerItem;
+use super::picker::TitleChange;
+use super::render_cache::RenderKey;
+use crate::comments::strip_html_comments;
+use crate::lint::Severity;
+use crate::model::DiscoveredFile;
+use crate::tokens::estimate_tokens;
+use crate::tokens::format_tokens;
+
+/// Narrowest Files screen that still fits the tree beside the content.
+const SIDE_BY_SIDE_MIN_WIDTH: u16 = 80;
+/// Narrowest Files screen that can show the tree above the content.
+const STACKED_MIN_WIDTH: u16 = 40;
+/// Shortest Files screen that can show the tree above the content.
+const STACKED_MIN_HEIGHT: u16 = 16;
+
+/// Narrowest content pane that fits the outline beside the text.
+const OUTLINE_BESIDE_MIN_WIDTH: u16 = 60;
+/// Narrowest content pane or editor that is split for the rendered preview.
+pub(crate) const SPLIT_PREVIEW_MIN_WIDTH: u16 = 60;
+/// Most findings the diagnostics footer lists before it scrolls off.
+const DIAGNOSTICS_MAX_ROWS: u16 = 5;
+/// Shortest content pane that keeps room for text above the footer.
+const DIAGNOSTICS_MIN_HEIGHT: u16 = 12;
+/// Shortest content pane that keeps the load order header above it.
+const LOAD_ORDER_MIN_HEIGHT: u16 = 8;
+
+/// How the Files screen arranges its two panes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FilesLayout {
+    /// Tree on the left, content on the right.
+    SideBySide,
+    /// Tree on top, content below.
+    Stacked,
+    /// Only the focused pane; Tab switches between them.
+    SinglePane,
+}
+
+impl FilesLayout {
+    /// Picks the layout that keeps both panes usable in `area`, falling back
+    /// to one pane at a time when neither arrangement fits.
+    pub fn for_area(area: Rect) -> Self {
+        if area.width >= SIDE_BY_SIDE_MIN_WIDTH {
+            FilesLayout::SideBySide
+        } else if area.width >= STACKED_MIN_WIDTH && area.height >= STACKED_MIN_HEIGHT {
+            FilesLayout::Stacked
+        } else {
+            FilesLayout::SinglePane
+        }
+    }
+}
 
 impl App {
-    pub(crate) fn draw_files_screen(&mut self, frame: &mut Frame, area: ratatui::layout::Rect) {
+    pub(crate) fn draw_files_screen(&mut self, frame: &mut Frame, area: Rect) {
         // In edit mode, render the full area as an editor
         if self.mode == Mode::Edit {
             self.draw_edit_pane(frame, area);
             return;
         }
 
-        let chunks = Layout::default()
-            .direction(Direction::Horizontal)
-            .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
-            .split(area);
+        let layout = FilesLayout::for_area(area);
+        let (tree_area, content_area) = match layout {
+            FilesLayout::SideBySide => {
+                let chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(30), Constraint::Percentage(70)])
+                    .split(area);
+                (Some(chunks[0]), Some(chunks[1]))
+            }
+            FilesLayout::Stacked => {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Percentage(35), Constraint::Percentage(65)])
+                    .split(area);
+                (Some(chunks[0]), Some(chunks[1]))
+            }
+            FilesLayout::SinglePane if self.active_pane == Pane::FileList => (Some(area), None),
+            FilesLayout::SinglePane => (None, Some(area)),
+        };
 
         let file_border_style = if self.active_pane == Pane::FileList {
             self.theme.active_border
@@ -49,27 +123,92 @@ impl App {
             self.theme.inactive_border
         };
 
-        if let Ok(tree) = Tree::new(&self.tree_items) {
+        if let Some(tree_area) = tree_area
+            && let Ok(tree) = Tree::new(&self.tree_items)
+        {
+            let mut title = if layout == FilesLayout::SinglePane {
+                "CLAUDE.md files (Tab: content)".to_string()
+            } else {
+                "CLAUDE.md files".to_string()
+            };
+            if self.is_scanning() {
+                title.push_str(" scanning…");
+            }
+            self.pane_areas.tree = tree_area;
             let tree = tree
                 .block(
                     Block::default()
                         .borders(Borders::ALL)
                         .border_style(file_border_style)
-                        .title("CLAUDE.md files"),
+                        .title(title),
                 )
                 .highlight_style(self.theme.highlight);
-            frame.render_stateful_widget(tree, chunks[0], &mut self.tree_state);
+            frame.render_stateful_widget(tree, tree_area, &mut self.tree_state);
         }
 
-        self.draw_content_pane(frame, chunks[1], content_border_style);
+        let (content_area, outline_area) = match content_area {
+            Some(area) if self.outline_visible && area.width >= OUTLINE_BESIDE_MIN_WIDTH => {
+                let chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Min(0), Constraint::Length(area.width / 3)])
+                    .split(area);
+                (Some(chunks[0]), Some(chunks[1]))
+            }
+            // Too narrow for both: the outline takes the pane while focused.
+            Some(area) if self.active_pane == Pane::Outline => (None, Some(area)),
+            area => (area, None),
+        };
+        let (content_area, preview_area) = match content_area {
+            Some(area) if self.split_preview && area.width >= SPLIT_PREVIEW_MIN_WIDTH => {
+                let chunks = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Percentage(50), Constraint::Percentage(50)])
+                    .split(area);
+                (Some(chunks[0]), Some(chunks[1]))
+            }
+            area => (area, None),
+        };
+        if let Some(content_area) = content_area {
+            self.draw_content_pane(frame, content_area, content_border_style);
+        }
+        if let Some(preview_area) = preview_area {
+            self.draw_split_preview(frame, preview_area);
+        }
+        if let Some(outline_area) = outline_area {
+            let outline_border_style = if self.active_pane == Pane::Outline {
+                self.theme.active_border
+            } else {
+                self.theme.inactive_border
+            };
+            self.pane_areas.outline = outline_area;
+            self.draw_outline(frame, outline_area, outline_border_style);
+        }
     }
 
-    fn draw_content_pane(
-        &mut self,
-        frame: &mut Frame,
-        area: ratatui::layout::Rect,
-        border_style: Style,
-    ) {
+    fn draw_content_pane(&mut self, frame: &mut Frame, area: Rect, border_style: Style) {
+        let area = match self.diagnostics_height(area) {
+            0 => area,
+            height => {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Min(0), Constraint::Length(height)])
+                    .split(area);
+                self.draw_diagnostics(frame, chunks[1]);
+                chunks[0]
+            }
+        };
+        let area = match self.content.load_order.clone() {
+            Some(load_order) if area.height > LOAD_ORDER_MIN_HEIGHT => {
+                let chunks = Layout::default()
+                    .direction(Direction::Vertical)
+                    .constraints([Constraint::Length(1), Constraint::Min(0)])
+                    .split(area);
+                let header = Line::styled(format!(" {load_order}"), self.theme.help_desc);
+                frame.render_widget(Paragraph::new(header), chunks[0]);
+                chunks[1]
+            }
+            _ => area,
+        };
         let content_title = match self.mode {
             Mode::VisualSelect | Mode::TitleInput => {
                 if let Some((start, end)) = self.content.selection_range() {
@@ -78,29 +217,97 @@ impl App {
                     "Content [VISUAL]".to_string()
                 }
             }
-            _ => "Content".to_string(),
+            _ => {
+                let mut base = if self.read_only.is_some() {
+                    "Content [read-only]".to_string()
+                } else {
+                    "Content".to_string()
+                };
+                if let Some(tokens) = self.content_tokens() {
+                    base.push_str(&format!(" · {}", format_tokens(tokens)));
+                }
+                if self.hide_comments && self.content.text.is_some() {
+                    base.push_str(" [comments hidden]");
+                }
+                if !self.content.inlined_imports.is_empty() {
+                    base.push_str(" [imports expanded]");
+                }
+                if let Some(details) = self.shown_file_details() {
+                    base.push_str(&format!(" · {details}"));
+                }
+                match self.findings.as_slice() {
+                    [] => base.to_string(),
+                    [finding] => format!("{base} — ⚠ {}", finding.message),
+                    [finding, rest @ ..] => {
+                        format!("{base} — ⚠ {} (+{} more)", finding.message, rest.len())
+                    }
+                }
+            }
         };
 
+        self.pane_areas.content = area;
         // Capture viewport height (content area minus 2 for borders)
         self.content.viewport_height = area.height.saturating_sub(2);
 
-        let display_text = self
-            .content
-            .text
-            .as_deref()
-            .unwrap_or("Select a file to view its content.");
-
         let selection = self.content.selection_range();
         let cursor_line = self.content.cursor;
-        let show_cursor = self.active_pane == Pane::Content;
+        // The landing view shown without a file has no cursor.
+        let show_cursor = self.active_pane == Pane::Content && self.content.text.is_some();
         let cursor_style = self.theme.highlight;
         let highlight_style = self.theme.visual_selection;
+        let flagged: Vec<usize> = self
+            .findings
+            .iter()
+            .filter_map(|finding| finding.line)
+            .map(|line| line.saturating_sub(1))
+            .collect();
+
+        let key = RenderKey {
+            revision: self.content.revision,
+            theme: self.theme.clone(),
+            width: area.width.saturating_sub(2),
+        };
+        let landing;
+        let styled: &[Line<'static>] = match self.content.text.as_deref() {
+            Some(text) => self.content.render_cache.lines(key, || style_content(text)),
+            None => {
+                landing = self.landing_lines();
+                &landing
+            }
+        };
+
+        let wrap_width = usize::from(area.width.saturating_sub(2));
+        let wrap = self.wrap_lines && self.content.text.is_some();
+        self.content.wrapped_rows = if wrap {
+            styled
+                .iter()
+                .map(|line| wrap_line(line, wrap_width).len())
+                .collect()
+        } else {
+            Vec::new()
+        };
+        if wrap {
+            // Wrapping can push the cursor off the bottom of the pane.
+            self.content.scroll = scroll_to_show(
+                self.content.cursor,
+                self.content.scroll,
+                self.content.viewport_height,
+                &self.content.wrapped_rows,
+            );
+        }
 
-        let lines: Vec<Line> = display_text
-            .lines()
-            .enumerate()
-            .map(|(i, line_text)| {
+        // Only the visible lines get the cursor and selection overlay.
+        let height = usize::from(self.content.viewport_height);
+        let first = usize::from(self.content.scroll).min(styled.len());
+        let last = (first + height).min(styled.len());
+        let mut lines: Vec<Line> = styled[first..last]
+            .iter()
+            .zip(first..)
+            .flat_map(|(line, i)| {
                 let mut style = Style::default();
+                if flagged.contains(&i) {
+                    style = self.theme.diff_removed;
+                }
                 if let Some((start, end)) = selection
                     && i >= start
                     && i <= end
@@ -115,119 +322,288 @@ impl App {
                 }
                 // Ensure the cursor line has at least a space so the
                 // REVERSED style is visible even on empty lines.
-                let text = if show_cursor && i == cursor_line && line_text.is_empty() {
-                    " ".to_string()
+                if show_cursor && i == cursor_line && line.width() == 0 {
+                    vec![Line::from(" ").style(style)]
+                } else if wrap {
+                    wrap_line(line, wrap_width)
+                        .into_iter()
+                        .map(|row| row.style(style))
+                        .collect()
                 } else {
-                    line_text.to_string()
-                };
-                Line::from(text).style(style)
+                    vec![line.clone().style(style)]
+                }
             })
             .collect();
-
-        let content_widget = Paragraph::new(Text::from(lines))
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .border_style(border_style)
-                    .title(content_title),
-            )
-            .scroll((self.content.scroll, 0));
+        lines.truncate(height);
+
+        let mut block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(border_style)
+            .title(content_title);
+        if let Some(total) = self.content.truncated_from {
+            let banner = format!(
+                " showing first {} KB of {} KB — press F to load all ",
+                self.config.preview_max_kb(),
+                total.div_ceil(1024)
+            );
+            block = block.title_bottom(Line::styled(banner, self.theme.help_key));
+        }
+        let content_widget = Paragraph::new(Text::from(lines)).block(block);
         frame.render_widget(content_widget, area);
 
-        let mut scrollbar_state =
-            ScrollbarState::new(self.content.line_count()).position(self.content.scroll as usize);
+        let (content_length, position) = self.content.scroll_position();
+        let mut scrollbar_state = ScrollbarState::new(content_length).position(position);
         let scrollbar = Scrollbar::new(ScrollbarOrientation::VerticalRight);
         frame.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
     }
 
-    pub(crate) fn handle_normal_key(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Char('q') => self.exit = true,
-            KeyCode::Tab => {
+    /// Rows the diagnostics footer takes below the content in `area`, or 0
+    /// when there is nothing to list or no room for it.
+    fn diagnostics_height(&self, area: Rect) -> u16 {
+        if self.findings.is_empty() || area.height < DIAGNOSTICS_MIN_HEIGHT {
+            return 0;
+        }
+        (self.findings.len() as u16).min(DIAGNOSTICS_MAX_ROWS) + 2
+    }
+
+    /// Lists the findings for the shown file, with the line each points at.
+    fn draw_diagnostics(&self, frame: &mut Frame, area: Rect) {
+        let lines: Vec<Line> = self
+            .findings
+            .iter()
+            .map(|finding| {
+                let location = finding
+                    .line
+                    .map_or_else(|| "file".to_string(), |line| format!("line {line}"));
+                let style = match finding.severity {
+                    Severity::Error => self.theme.diff_removed,
+                    Severity::Warning => self.theme.input_border,
+                };
+                Line::styled(format!("{location}: {finding}"), style)
+            })
+            .collect();
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.inactive_border)
+            .title(format!("Diagnostics ({})", self.findings.len()));
+        frame.render_widget(Paragraph::new(Text::from(lines)).block(block), area);
+    }
+
+    /// Draws the shown file rendered as Markdown, scrolled with the raw
+    /// pane so the same lines are side by side.
+    fn draw_split_preview(&mut self, frame: &mut Frame, area: Rect) {
+        let width = area.width.saturating_sub(2);
+        let key = RenderKey {
+            revision: self.content.revision,
+            theme: self.theme.clone(),
+            width,
+        };
+        let theme = &self.theme;
+        let lines: &[Line<'static>] = match self.content.text.as_deref() {
+            Some(text) => self
+                .content
+                .preview_cache
+                .lines(key, || render_markdown(text, theme, width)),
+            None => &[],
+        };
+        let cursor = (self.active_pane == Pane::Content).then_some(self.content.cursor);
+        let widget = preview_pane(
+            lines,
+            usize::from(self.content.scroll),
+            usize::from(area.height.saturating_sub(2)),
+            cursor,
+            theme,
+        );
+        frame.render_widget(widget, area);
+    }
+
+    /// Lines, size and modification time of the shown file, as last read,
+    /// when it is one of the discovered files.
+    fn shown_file_details(&self) -> Option<String> {
+        self.content.text.as_ref()?;
+        let path = self.selected_file()?;
+        self.roots
+            .iter()
+            .find_map(|root| root.file(&path))
+            .map(DiscoveredFile::details)
+    }
+
+    /// Token estimate of the shown file. A file shown only in part is
+    /// estimated from its size, since the rest of its text is not loaded.
+    pub(crate) fn content_tokens(&self) -> Option<u64> {
+        if let Some(bytes) = self.content.truncated_from {
+            return Some(estimate_tokens(bytes));
+        }
+        let text = self.content.text.as_deref()?;
+        Some(self.config.tokenizer().count(text))
+    }
+
+    /// `text` as the content pane shows it: without HTML comments when
+    /// [`hide_comments`](App::hide_comments) is on. Also returns how many
+    /// lines that left out.
+    pub(crate) fn comments_filtered(&self, text: String) -> (String, usize) {
+        if self.hide_comments {
+            strip_html_comments(&text)
+        } else {
+            (text, 0)
+        }
+    }
+
+    /// Hides HTML comments in the content pane, or shows them again, and
+    /// reloads the shown file.
+    pub(crate) fn toggle_hidden_comments(&mut self) {
+        self.hide_comments = !self.hide_comments;
+        if let Some(path) = self.selected_file() {
+            self.load_file_content(&path);
+        }
+        self.status_message = Some(if self.hide_comments {
+            hidden_comments_message(self.content.hidden_comment_lines)
+        } else {
+            "Comments shown.".to_string()
+        });
+    }
+
+    /// Applies an action in Normal mode on the Files screen.
+    pub(crate) fn update_files(&mut self, action: Action) -> Vec<Effect> {
+        match (self.active_pane, action) {
+            (_, Action::TogglePane) => {
                 self.active_pane = match self.active_pane {
                     Pane::FileList => Pane::Content,
-                    Pane::Content => Pane::FileList,
+                    Pane::Content if self.outline_visible => Pane::Outline,
+                    Pane::Content | Pane::Outline => Pane::FileList,
                 };
             }
-            KeyCode::Down | KeyCode::Char('j') if self.active_pane == Pane::FileList => {
+            (_, Action::ToggleOutline) => self.toggle_outline(),
+            (_, Action::ToggleSplitPreview) => self.split_preview = !self.split_preview,
+            (_, Action::ToggleComments) => self.toggle_hidden_comments(),
+            (Pane::Content, Action::ToggleWrap) => {
+                self.wrap_lines = !self.wrap_lines;
+                self.status_message = Some(if self.wrap_lines {
+                    "Long lines wrap.".to_string()
+                } else {
+                    "Long lines are cut at the border.".to_string()
+                });
+            }
+            (_, Action::ToggleLoadOrder) => self.toggle_load_order(),
+            (_, Action::LoadFullContent) => return vec![Effect::LoadFullContent],
+            (_, Action::OpenInEditor) => self.open_file_in_editor(),
+            (_, Action::RunTool) => self.open_tools(),
+            (_, Action::RepeatInsert) => self.repeat_insert(0),
+            (_, Action::OpenGlobalSearch) => self.open_global_search(),
+            (Pane::FileList, Action::MoveDown) => {
                 self.tree_state.key_down();
-                self.load_selected_content();
+                return vec![Effect::LoadSelectedContent];
             }
-            KeyCode::Up | KeyCode::Char('k') if self.active_pane == Pane::FileList => {
+            (Pane::FileList, Action::MoveUp) => {
                 self.tree_state.key_up();
-                self.load_selected_content();
+                return vec![Effect::LoadSelectedContent];
             }
-            KeyCode::Left | KeyCode::Char('h') if self.active_pane == Pane::FileList => {
+            (Pane::FileList, Action::MoveLeft) => {
                 let before = self.tree_state.selected().to_vec();
                 self.tree_state.key_left();
                 if self.tree_state.selected().is_empty() {
                     self.tree_state.select(before);
                 }
-                self.load_selected_content();
+                return vec![Effect::LoadSelectedContent];
             }
-            KeyCode::Right | KeyCode::Char('l') if self.active_pane == Pane::FileList => {
+            (Pane::FileList, Action::MoveRight) => {
                 self.tree_state.key_right();
-                self.load_selected_content();
-            }
-            KeyCode::Down | KeyCode::Char('j') if self.active_pane == Pane::Content => {
-                self.content.cursor_down();
+                return vec![Effect::LoadSelectedContent];
             }
-            KeyCode::Up | KeyCode::Char('k') if self.active_pane == Pane::Content => {
-                self.content.cursor_up();
+            (Pane::FileList, Action::NewFile) => return vec![Effect::StartNewFile],
+            (Pane::FileList, Action::OpenBackups) => return vec![Effect::OpenBackups],
+            (Pane::FileList, Action::MigrateLocal) => return vec![Effect::MigrateLocal],
+            (Pane::FileList, Action::ToggleRepoGroups) => return vec![Effect::ToggleRepoGroups],
+            (Pane::FileList, Action::Rescan) => return vec![Effect::Rescan],
+            (Pane::FileList, Action::SimulateSession) => self.start_session_simulation(),
+            (Pane::FileList, Action::Delete) => return vec![Effect::StartDeleteFile],
+            (Pane::FileList, Action::Rename) => return vec![Effect::StartMoveFile],
+            (Pane::FileList, Action::FixName) => return vec![Effect::FixFileName],
+            (_, Action::Trim) => return vec![Effect::OpenTrim],
+            (_, Action::History) => return vec![Effect::OpenHistory],
+            (Pane::FileList, Action::Merge) => return vec![Effect::StartMerge],
+            (_, Action::NextFinding) => self.jump_to_next_finding(),
+            (_, Action::CopyFile) => {
+                if let Some(file) = self.selected_file() {
+                    return vec![Effect::CopyFile(file)];
+                }
             }
-            KeyCode::PageDown if self.active_pane == Pane::Content => {
-                self.content.cursor_page_down();
+            (Pane::Content, Action::MoveDown) => self.content.cursor_down(),
+            (Pane::Content, Action::MoveUp) => self.content.cursor_up(),
+            (Pane::Content, Action::PageDown) => self.content.cursor_page_down(),
+            (Pane::Content, Action::PageUp) => self.content.cursor_page_up(),
+            (Pane::Content, Action::MoveTop) => {
+                self.record_jump();
+                self.content.jump_to_line(0);
             }
-            KeyCode::PageUp if self.active_pane == Pane::Content => {
-                self.content.cursor_page_up();
+            (Pane::Content, Action::MoveBottom) => {
+                self.record_jump();
+                self.content.jump_to_line(usize::MAX);
             }
-            KeyCode::Char('v') if self.active_pane == Pane::Content => {
+            (Pane::Content, Action::StartSelection) => {
                 self.content.visual_anchor = Some(self.content.cursor);
                 self.mode = Mode::VisualSelect;
             }
-            KeyCode::Char('e') if self.active_pane == Pane::Content => {
-                self.enter_edit_mode();
-            }
+            (Pane::Content, Action::Edit) => return vec![Effect::EditFile],
+            (Pane::Content, Action::QuickActions) => self.open_quick_actions(),
+            (Pane::Content, Action::ExtractSection) => self.start_section_extraction(),
+            (Pane::Content, Action::ToggleImport) => self.toggle_import_at_cursor(),
+            (Pane::Content, Action::ToggleAllImports) => self.toggle_all_imports(),
             _ => {}
         }
+        Vec::new()
     }
 
-    pub(crate) fn handle_visual_select_key(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Esc => {
+    /// Applies an action while selecting lines in the content pane.
+    pub(crate) fn update_visual_select(&mut self, action: Action) -> Vec<Effect> {
+        match action {
+            Action::Cancel => {
                 self.content.visual_anchor = None;
                 self.mode = Mode::Normal;
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                self.content.cursor_down();
-            }
-            KeyCode::Up | KeyCode::Char('k') => {
-                self.content.cursor_up();
-            }
-            KeyCode::Char('s') => {
+            Action::MoveDown => self.content.cursor_down(),
+            Action::MoveUp => self.content.cursor_up(),
+            Action::SaveSelection => {
                 self.text_input.clear();
                 self.mode = Mode::TitleInput;
             }
+            Action::MoveSelection => self.start_selection_move(),
+            Action::CopySelection => {
+                let text = self.content.selected_text();
+                self.content.visual_anchor = None;
+                self.mode = Mode::Normal;
+                if let Some(text) = text {
+                    return vec![Effect::Copy(text)];
+                }
+            }
             _ => {}
         }
+        Vec::new()
     }
 
-    pub(crate) fn handle_title_input_key(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Esc => {
-                self.text_input.clear();
-                self.mode = Mode::VisualSelect;
-            }
-            KeyCode::Enter => {
-                self.save_current_snippet();
-            }
-            _ => {
-                self.text_input.handle_edit_key(key_event.code);
+    /// Puts `text` on the clipboard and reports how it got there.
+    pub(crate) fn copy_to_clipboard(&mut self, text: &str) {
+        let lines = text.lines().count();
+        let noun = if lines == 1 { "line" } else { "lines" };
+        self.status_message = Some(
+            match crate::clipboard::copy_to_clipboard(text, self.capabilities.osc52) {
+                Ok(method) => format!("Copied {lines} {noun} ({}).", method.describe()),
+                Err(err) => format!("Could not copy to the clipboard: {err}"),
+            },
+        );
+    }
+
+    /// Copies the whole of `file`, even when the preview is cut short.
+    pub(crate) fn copy_file_to_clipboard(&mut self, file: &Path) {
+        match fs::read_to_string(file) {
+            Ok(text) => self.copy_to_clipboard(&text),
+            Err(err) => {
+                self.status_message = Some(format!("Error reading {}: {err}", file.display()));
             }
         }
     }
 
-    fn save_current_snippet(&mut self) {
+    pub(crate) fn save_current_snippet(&mut self) {
         match crate::library::library_path() {
             Some(path) => self.save_current_snippet_to(&path),
             None => {
@@ -255,15 +631,81 @@ impl App {
         };
 
         let source = self.current_source_path();
+        let lines = self
+            .content
+            .selection_range()
+            .map(|(start, end)| (start + 1, end + 1));
 
         let snippet = crate::library::Snippet {
             title,
+            format: Some(crate::library::SnippetFormat::infer(&selected_text)),
             content: selected_text,
             source,
+            archived: false,
+            tags: Vec::new(),
+            lines,
+            created: Some(crate::backup::now_millis()),
+            scope: crate::library::LibraryScope::Global,
         };
 
-        match crate::library::append_snippet(snippet, path) {
+        self.reset_to_normal();
+        self.project_library = self.current_project_library();
+        match self.project_library.clone() {
+            Some(project) if project.is_file() => self.open_picker(Picker::new(
+                "Save the snippet to",
+                vec![
+                    PickerItem::new("Global library", path.display().to_string()),
+                    PickerItem::new("Project library", project.display().to_string()),
+                ],
+                PickerAction::SnippetScope {
+                    path: path.to_path_buf(),
+                    snippet,
+                },
+            )),
+            _ => self.store_new_snippet(path, snippet),
+        }
+    }
+
+    /// Adds `snippet` to the library at `path` and the project library in
+    /// use, first offering a free title when its own is taken.
+    pub(crate) fn store_new_snippet(&mut self, path: &Path, snippet: crate::library::Snippet) {
+        let lib = match self.load_library_at(path) {
+            Ok(lib) => lib,
+            Err(err) => {
+                self.status_message = Some(format!("Save failed: {err}"));
+                return;
+            }
+        };
+        if lib.has_title(&snippet.title, None) {
+            let suggested = lib.unique_title(&snippet.title, None);
+            self.open_duplicate_title_picker(path, suggested, TitleChange::Save(snippet));
+            return;
+        }
+        self.append_new_snippet(path, snippet);
+    }
+
+    /// Adds `snippet` to the library at `path`, or to the project library
+    /// when that is its scope.
+    pub(crate) fn append_new_snippet(&mut self, path: &Path, snippet: crate::library::Snippet) {
+        let before = match self.load_library_at(path) {
+            Ok(lib) => lib,
+            Err(err) => {
+                self.status_message = Some(format!("Save failed: {err}"));
+                return;
+            }
+        };
+        if before.has_title(&snippet.title, None) {
+            self.status_message = Some(format!(
+                "Save failed: a snippet titled \"{}\" already exists",
+                snippet.title
+            ));
+            return;
+        }
+        let mut lib = before.clone();
+        lib.snippets.push(snippet);
+        match self.save_library_at(&lib, path) {
             Ok(()) => {
+                self.record_library_undo(before, "Snippet saved.");
                 self.status_message = Some("Snippet saved!".to_string());
                 self.compose_state = None;
             }
@@ -271,31 +713,83 @@ impl App {
                 self.status_message = Some(format!("Save failed: {err}"));
             }
         }
+    }
+}
 
-        self.reset_to_normal();
+/// Turns the pane's text into lines, before the cursor and selection are
+/// applied. The result is cached by [`RenderCache`](super::render_cache::RenderCache).
+/// Status message for a file shown with `hidden` comment lines left out.
+pub(crate) fn hidden_comments_message(hidden: usize) -> String {
+    let noun = if hidden == 1 { "line" } else { "lines" };
+    format!("Comments hidden: {hidden} {noun} left out. c shows them.")
+}
+
+fn style_content(text: &str) -> Vec<Line<'static>> {
+    text.lines()
+        .map(|line| Line::from(line.to_string()))
+        .collect()
+}
+
+/// Splits `line` into rows at most `width` cells wide, breaking between any
+/// two characters so a line takes exactly the rows its width needs.
+fn wrap_line(line: &Line<'static>, width: usize) -> Vec<Line<'static>> {
+    if width == 0 || line.width() <= width {
+        return vec![line.clone()];
+    }
+    let mut rows = Vec::new();
+    let mut row: Vec<Span<'static>> = Vec::new();
+    let mut used = 0;
+    for span in &line.spans {
+        let mut piece = String::new();
+        for ch in span.content.chars() {
+            let cells = Span::raw(ch.to_string()).width();
+            if used > 0 && used + cells > width {
+                if !piece.is_empty() {
+                    row.push(Span::styled(mem::take(&mut piece), span.style));
+                }
+                rows.push(Line::from(mem::take(&mut row)));
+                used = 0;
+            }
+            piece.push(ch);
+            used += cells;
+        }
+        if !piece.is_empty() {
+            row.push(Span::styled(piece, span.style));
+        }
     }
+    if !row.is_empty() {
+        rows.push(Line::from(row));
+    }
+    rows
 }
 
 #[cfg(test)]
 mod tests {
     use std::fs;
     use std::path::PathBuf;
+    use std::time::Instant;
 
     use ratatui::Terminal;
     use ratatui::backend::TestBackend;
     use ratatui::crossterm::event::KeyCode;
+    use ratatui::crossterm::event::KeyEvent;
+    use ratatui::crossterm::event::KeyModifiers;
     use ratatui::style::Modifier;
 
     use tempfile::TempDir;
 
     use crate::config::Config;
+    use crate::model::DiscoveredFile;
     use crate::model::SourceRoot;
+    use crate::tui::action::Action;
+    use crate::tui::action::Effect;
     use crate::tui::app::App;
     use crate::tui::app::Mode;
     use crate::tui::app::Pane;
     use crate::tui::app::test_helpers::key_event;
     use crate::tui::app::test_helpers::render_once;
     use crate::tui::app::test_helpers::sample_roots;
+    use crate::tui::keymap::SEQUENCE_TIMEOUT;
 
     #[test]
     fn tab_toggles_pane() {
@@ -346,7 +840,10 @@ mod tests {
 
         let roots = vec![SourceRoot {
             path: tmp.path().to_path_buf(),
-            files: vec![file],
+            files: vec![DiscoveredFile::new(file)],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
         }];
         let mut app = App::new(roots, &Config::default());
 
@@ -426,7 +923,10 @@ mod tests {
 
         let roots = vec![SourceRoot {
             path: tmp.path().to_path_buf(),
-            files: vec![file],
+            files: vec![DiscoveredFile::new(file)],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
         }];
         let mut app = App::new(roots, &Config::default());
         app.active_pane = Pane::Content;
@@ -440,11 +940,12 @@ mod tests {
         terminal.draw(|frame| app.draw(frame)).unwrap();
         let buf = terminal.backend().buffer().clone();
 
-        // The cursor line is row 3 in the buffer (row 0 = tab bar, row 1 = border,
-        // row 2 = first content line, row 3 = empty cursor line).
+        // The cursor line is row 4 in the buffer (row 0 = tab bar, row 1 =
+        // load order, row 2 = border, row 3 = first content line, row 4 =
+        // empty cursor line).
         // Check that the empty line has a non-default style (Reversed modifier).
         let content_x_start = (80u16 * 30 / 100) + 1;
-        let cell = &buf[(content_x_start, 3)];
+        let cell = &buf[(content_x_start, 4)];
         assert!(
             cell.modifier.contains(Modifier::REVERSED),
             "Empty cursor line should use REVERSED style for visibility, got: {:?}",
@@ -460,7 +961,10 @@ mod tests {
 
         let roots = vec![SourceRoot {
             path: tmp.path().to_path_buf(),
-            files: vec![file.clone()],
+            files: vec![DiscoveredFile::new(file.clone())],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
         }];
         let mut app = App::new(roots, &Config::default());
 
@@ -549,7 +1053,13 @@ mod tests {
 
         let roots = vec![SourceRoot {
             path: tmp.path().to_path_buf(),
-            files: vec![file_a, file_b.clone()],
+            files: vec![
+                DiscoveredFile::new(file_a),
+                DiscoveredFile::new(file_b.clone()),
+            ],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
         }];
         let mut app = App::new(roots, &Config::default());
 
@@ -569,7 +1079,10 @@ mod tests {
     fn load_content_handles_missing_file() {
         let roots = vec![SourceRoot {
             path: PathBuf::from("/nonexistent"),
-            files: vec![PathBuf::from("/nonexistent/CLAUDE.md")],
+            files: vec![DiscoveredFile::new(PathBuf::from("/nonexistent/CLAUDE.md"))],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
         }];
         let mut app = App::new(roots, &Config::default());
 
@@ -589,6 +1102,342 @@ mod tests {
         );
     }
 
+    #[test]
+    fn token_estimates_show_in_tree_and_content_title() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "x".repeat(400)).unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![DiscoveredFile::new(file)],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+
+        assert_eq!(app.content_tokens(), Some(100));
+        let backend = ratatui::backend::TestBackend::new(120, 20);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let screen = buffer_text(terminal.backend().buffer());
+        assert!(screen.contains("CLAUDE.md 100 tok"));
+        assert!(screen.contains("Content · 100 tok"));
+    }
+
+    fn buffer_text(buf: &ratatui::buffer::Buffer) -> String {
+        buf.content().iter().map(|c| c.symbol()).collect()
+    }
+
+    #[test]
+    fn large_file_is_capped_until_f_loads_all() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        let line = "x".repeat(99);
+        fs::write(&file, format!("{line}\n").repeat(30)).unwrap();
+        let config = Config {
+            preview_max_kb: Some(1),
+            ..Config::default()
+        };
+        let mut app = App::new(
+            vec![SourceRoot {
+                path: tmp.path().to_path_buf(),
+                files: vec![DiscoveredFile::new(file)],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &config,
+        );
+
+        assert_eq!(app.content.line_count(), 10);
+        assert_eq!(app.content.truncated_from, Some(3000));
+        let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        assert!(buffer_text(terminal.backend().buffer()).contains("press F to load all"));
+
+        app.active_pane = Pane::Content;
+        app.handle_key_event(key_event(KeyCode::Char('j')));
+        app.handle_key_event(key_event(KeyCode::Char('F')));
+
+        assert_eq!(app.content.line_count(), 30);
+        assert_eq!(app.content.truncated_from, None);
+        assert_eq!(app.content.cursor, 1, "cursor is kept");
+    }
+
+    #[test]
+    fn broken_imports_are_red_and_listed_in_the_diagnostics_footer() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "Rules\nSee @gone.md\n").unwrap();
+        let mut app = App::new(
+            vec![SourceRoot {
+                path: tmp.path().to_path_buf(),
+                files: vec![DiscoveredFile::new(file)],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        );
+
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+
+        let buf = terminal.backend().buffer();
+        let screen = buffer_text(buf);
+        assert!(screen.contains("Diagnostics (1)"));
+        assert!(screen.contains("line 2: error[missing-import]: @gone.md points at no file"));
+        let red_see = buf.content().windows(3).any(|cells| {
+            cells.iter().map(|c| c.symbol()).collect::<String>() == "See"
+                && cells[0].fg == app.theme.diff_removed.fg.unwrap()
+        });
+        assert!(red_see, "the line with the broken import is red");
+    }
+
+    #[test]
+    fn layout_follows_terminal_size() {
+        use super::FilesLayout;
+        use ratatui::layout::Rect;
+
+        assert_eq!(
+            FilesLayout::for_area(Rect::new(0, 0, 120, 40)),
+            FilesLayout::SideBySide
+        );
+        assert_eq!(
+            FilesLayout::for_area(Rect::new(0, 0, 60, 30)),
+            FilesLayout::Stacked
+        );
+        assert_eq!(
+            FilesLayout::for_area(Rect::new(0, 0, 60, 12)),
+            FilesLayout::SinglePane
+        );
+        assert_eq!(
+            FilesLayout::for_area(Rect::new(0, 0, 30, 40)),
+            FilesLayout::SinglePane
+        );
+    }
+
+    #[test]
+    fn narrow_terminal_stacks_tree_above_content() {
+        let mut app = App::new(sample_roots(), &Config::default());
+        let mut terminal = Terminal::new(TestBackend::new(60, 30)).unwrap();
+
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+
+        let buf = terminal.backend().buffer();
+        let row = |y: u16| -> String { (0..60).map(|x| buf[(x, y)].symbol()).collect() };
+        let tree_row = (0..30)
+            .find(|&y| row(y).contains("CLAUDE.md files"))
+            .unwrap();
+        let content_row = (0..30).find(|&y| row(y).contains("Content")).unwrap();
+        assert!(tree_row < content_row, "tree is drawn above the content");
+    }
+
+    #[test]
+    fn tiny_terminal_shows_only_the_focused_pane() {
+        let mut app = App::new(sample_roots(), &Config::default());
+        let mut terminal = Terminal::new(TestBackend::new(36, 12)).unwrap();
+
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let text = buffer_text(terminal.backend().buffer());
+        assert!(text.contains("┌CLAUDE.md files"));
+        assert!(!text.contains("┌Content"));
+
+        app.handle_key_event(key_event(KeyCode::Tab));
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let text = buffer_text(terminal.backend().buffer());
+        assert!(text.contains("┌Content"));
+        assert!(!text.contains("┌CLAUDE.md files"));
+    }
+
+    fn multi_repo_root(tmp: &TempDir) -> Vec<SourceRoot> {
+        let mut files = Vec::new();
+        for dir in ["alpha", "beta", "loose"] {
+            let dir = tmp.path().join(dir);
+            fs::create_dir_all(&dir).unwrap();
+            if !dir.ends_with("loose") {
+                fs::create_dir(dir.join(".git")).unwrap();
+            }
+            let file = dir.join("CLAUDE.md");
+            fs::write(&file, "x").unwrap();
+            files.push(DiscoveredFile::new(file));
+        }
+        vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files,
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }]
+    }
+
+    #[test]
+    fn tree_badges_files_with_their_repo() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = App::new(multi_repo_root(&tmp), &Config::default());
+
+        let mut terminal = Terminal::new(TestBackend::new(300, 24)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let text = buffer_text(terminal.backend().buffer());
+
+        assert!(text.contains("alpha/CLAUDE.md [git: alpha]"));
+        assert!(text.contains("beta/CLAUDE.md [git: beta]"));
+        assert!(
+            text.contains("loose/CLAUDE.md "),
+            "same (lack of) repo as the root: no badge"
+        );
+        assert!(!text.contains("loose/CLAUDE.md [no git]"));
+        assert!(text.contains("[no git]"), "root is badged");
+    }
+
+    #[test]
+    fn g_groups_files_by_repo_and_keeps_selection() {
+        let tmp = TempDir::new().unwrap();
+        let roots = multi_repo_root(&tmp);
+        let beta = roots[0].files[1].path.clone();
+        let mut app = App::new(roots, &Config::default());
+        app.tree_state.select(app.tree_path(&beta).unwrap());
+
+        app.handle_key_event(key_event(KeyCode::Char('g')));
+
+        assert!(app.group_by_repo);
+        let groups: Vec<&String> = app.tree_items[0]
+            .children()
+            .iter()
+            .map(|group| group.identifier())
+            .collect();
+        assert_eq!(
+            groups,
+            [
+                &tmp.path().join("alpha").display().to_string(),
+                &tmp.path().join("beta").display().to_string(),
+                &"(no git)".to_string(),
+            ]
+        );
+        assert_eq!(app.tree_state.selected().len(), 3);
+        assert_eq!(app.selected_file(), Some(beta.clone()));
+
+        app.handle_key_event(key_event(KeyCode::Char('g')));
+
+        assert!(!app.group_by_repo);
+        assert_eq!(app.tree_state.selected().len(), 2);
+        assert_eq!(app.selected_file(), Some(beta));
+    }
+
+    #[test]
+    fn selecting_repo_group_shows_no_content() {
+        let tmp = TempDir::new().unwrap();
+        let config = Config {
+            group_by_repo: Some(true),
+            ..Config::default()
+        };
+        let mut app = App::new(multi_repo_root(&tmp), &config);
+        assert!(app.content.text.is_some(), "first file is selected");
+
+        let group = app.tree_state.selected()[..2].to_vec();
+        app.tree_state.select(group);
+        app.load_selected_content();
+
+        assert_eq!(app.selected_file(), None);
+        assert!(app.content.text.is_none());
+    }
+
+    #[test]
+    fn reloaded_text_is_drawn_and_scroll_offsets_lines() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "old").unwrap();
+        let mut app = App::new(
+            vec![SourceRoot {
+                path: tmp.path().to_path_buf(),
+                files: vec![DiscoveredFile::new(file.clone())],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        );
+        let mut terminal = Terminal::new(TestBackend::new(80, 10)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        assert!(buffer_text(terminal.backend().buffer()).contains("old"));
+
+        let text: String = (0..20).map(|i| format!("row{i:02}\n")).collect();
+        fs::write(&file, text).unwrap();
+        app.load_file_content(&file);
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        assert!(buffer_text(terminal.backend().buffer()).contains("row00"));
+
+        app.active_pane = Pane::Content;
+        for _ in 0..15 {
+            app.handle_key_event(key_event(KeyCode::Char('j')));
+        }
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let screen = buffer_text(terminal.backend().buffer());
+        assert!(!screen.contains("row00"), "scrolled out of view");
+        assert!(screen.contains("row15"));
+    }
+
+    #[test]
+    fn p_splits_content_with_rendered_preview_scrolled_alongside() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        let text: String = (0..20).map(|i| format!("- **item{i:02}**\n")).collect();
+        fs::write(&file, text).unwrap();
+        let mut app = App::new(
+            vec![SourceRoot {
+                path: tmp.path().to_path_buf(),
+                files: vec![DiscoveredFile::new(file)],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        );
+        app.active_pane = Pane::Content;
+        let mut terminal = Terminal::new(TestBackend::new(160, 12)).unwrap();
+
+        app.handle_key_event(key_event(KeyCode::Char('p')));
+        assert!(app.split_preview);
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        for _ in 0..15 {
+            app.handle_key_event(key_event(KeyCode::Char('j')));
+        }
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+
+        let screen = buffer_text(terminal.backend().buffer());
+        assert!(screen.contains("Preview"));
+        assert!(screen.contains("- **item15**"));
+        assert!(screen.contains("• item15"));
+        assert!(
+            !screen.contains("• item00"),
+            "preview scrolls with the raw pane"
+        );
+
+        app.handle_key_event(key_event(KeyCode::Char('p')));
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        assert!(!buffer_text(terminal.backend().buffer()).contains("Preview"));
+    }
+
+    #[test]
+    fn small_file_is_not_capped() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "short").unwrap();
+        let app = App::new(
+            vec![SourceRoot {
+                path: tmp.path().to_path_buf(),
+                files: vec![DiscoveredFile::new(file)],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        );
+
+        assert_eq!(app.content.truncated_from, None);
+    }
+
     #[test]
     fn cursor_moves_down_and_scrolls_when_past_viewport() {
         let mut app = App::new(vec![], &Config::default());
@@ -633,6 +1482,64 @@ mod tests {
         assert_eq!(app.content.cursor, 4, "Clamps at last line");
     }
 
+    #[test]
+    fn gg_and_capital_g_jump_to_the_ends() {
+        let mut app = App::new(vec![], &Config::default());
+        app.content.text = Some("Line 0\nLine 1\nLine 2\nLine 3".to_string());
+        app.active_pane = Pane::Content;
+
+        app.handle_key_event(key_event(KeyCode::Char('G')));
+        assert_eq!(app.content.cursor, 3);
+
+        app.handle_key_event(key_event(KeyCode::Char('g')));
+        assert_eq!(app.content.cursor, 3, "Waits for the second g");
+        assert_eq!(app.help_line().spans[0].content, " g ");
+        app.handle_key_event(key_event(KeyCode::Char('g')));
+        assert_eq!(app.content.cursor, 0);
+        assert!(app.pending_keys.is_empty());
+    }
+
+    #[test]
+    fn broken_sequence_runs_the_new_key_alone() {
+        let mut app = App::new(vec![], &Config::default());
+        app.content.text = Some("Line 0\nLine 1\nLine 2".to_string());
+        app.active_pane = Pane::Content;
+
+        app.handle_key_event(key_event(KeyCode::Char('g')));
+        app.handle_key_event(key_event(KeyCode::Char('j')));
+
+        assert_eq!(app.content.cursor, 1);
+        assert!(app.pending_keys.is_empty());
+    }
+
+    #[test]
+    fn pending_keys_expire_and_esc_drops_them() {
+        let mut app = App::new(vec![], &Config::default());
+        app.active_pane = Pane::Content;
+
+        app.handle_key_event(key_event(KeyCode::Char('g')));
+        app.tick(Instant::now());
+        assert_eq!(app.pending_keys.len(), 1);
+        app.tick(Instant::now() + SEQUENCE_TIMEOUT);
+        assert!(app.pending_keys.is_empty());
+
+        app.handle_key_event(key_event(KeyCode::Char('g')));
+        app.handle_key_event(key_event(KeyCode::Esc));
+        assert!(app.pending_keys.is_empty());
+        assert_eq!(app.active_pane, Pane::Content);
+    }
+
+    #[test]
+    fn ctrl_w_v_toggles_outline() {
+        let mut app = App::new(sample_roots(), &Config::default());
+        let ctrl_w = KeyEvent::new(KeyCode::Char('w'), KeyModifiers::CONTROL);
+
+        app.handle_key_event(ctrl_w);
+        app.handle_key_event(key_event(KeyCode::Char('v')));
+
+        assert!(app.outline_visible);
+    }
+
     #[test]
     fn loading_new_content_resets_scroll_and_cursor() {
         let tmp = TempDir::new().unwrap();
@@ -644,7 +1551,10 @@ mod tests {
 
         let roots = vec![SourceRoot {
             path: tmp.path().to_path_buf(),
-            files: vec![file],
+            files: vec![DiscoveredFile::new(file)],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
         }];
         let mut app = App::new(roots, &Config::default());
 
@@ -690,11 +1600,17 @@ mod tests {
         let roots = vec![
             SourceRoot {
                 path: dir_a.clone(),
-                files: vec![file_a.clone()],
+                files: vec![DiscoveredFile::new(file_a.clone())],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
             },
             SourceRoot {
                 path: dir_b.clone(),
-                files: vec![file_b.clone()],
+                files: vec![DiscoveredFile::new(file_b.clone())],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
             },
         ];
         let mut app = App::new(roots, &Config::default());
@@ -760,7 +1676,10 @@ mod tests {
 
         let roots = vec![SourceRoot {
             path: tmp.path().to_path_buf(),
-            files: vec![file.clone()],
+            files: vec![DiscoveredFile::new(file.clone())],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
         }];
         let mut app = App::new(roots, &Config::default());
 
@@ -843,6 +1762,36 @@ mod tests {
         assert_eq!(app.mode, Mode::Normal);
     }
 
+    #[test]
+    fn y_in_visual_select_copies_selected_lines() {
+        let mut app = App::new(vec![], &Config::default());
+        app.content.text = Some("line 0\nline 1\nline 2".to_string());
+        app.mode = Mode::VisualSelect;
+        app.content.visual_anchor = Some(0);
+        app.content.cursor = 1;
+        let action = app
+            .keymap
+            .resolve(app.key_context(), false, key_event(KeyCode::Char('y')))
+            .unwrap();
+
+        let effects = app.update(action);
+
+        assert_eq!(effects, [Effect::Copy("line 0\nline 1".to_string())]);
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(app.content.visual_anchor, None);
+    }
+
+    #[test]
+    fn shift_y_copies_whole_selected_file() {
+        let mut app = App::new(sample_roots(), &Config::default());
+        let file = app.selected_file().unwrap();
+        app.active_pane = Pane::Content;
+
+        let effects = app.update(Action::CopyFile);
+
+        assert_eq!(effects, [Effect::CopyFile(file)]);
+    }
+
     #[test]
     fn esc_in_visual_select_returns_to_normal() {
         let mut app = App::new(vec![], &Config::default());
@@ -893,7 +1842,10 @@ mod tests {
 
         let roots = vec![SourceRoot {
             path: tmp.path().to_path_buf(),
-            files: vec![file.clone()],
+            files: vec![DiscoveredFile::new(file.clone())],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
         }];
         let mut app = App::new(roots, &Config::default());
         app.content.visual_anchor = Some(5);
@@ -995,7 +1947,10 @@ mod tests {
 
         let roots = vec![SourceRoot {
             path: tmp_content.path().to_path_buf(),
-            files: vec![file],
+            files: vec![DiscoveredFile::new(file)],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
         }];
         let mut app = App::new(roots, &Config::default());
 
@@ -1036,4 +1991,76 @@ mod tests {
         assert_eq!(lib.snippets[0].title, "My Rules");
         assert_eq!(lib.snippets[0].content, "# Rules\n- Rule A\n- Rule B");
     }
+
+    #[test]
+    fn c_hides_comment_lines_and_reports_how_many() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "# Rules\n<!-- from team.md -->\n- Use tabs\n").unwrap();
+        let mut app = App::new(
+            vec![SourceRoot {
+                path: tmp.path().to_path_buf(),
+                files: vec![DiscoveredFile::new(file.clone())],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        );
+
+        app.handle_key_event(key_event(KeyCode::Char('c')));
+        assert_eq!(app.content.text.as_deref(), Some("# Rules\n- Use tabs\n"));
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Comments hidden: 1 line left out. c shows them.")
+        );
+        let mut terminal = Terminal::new(TestBackend::new(100, 10)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        assert!(buffer_text(terminal.backend().buffer()).contains("[comments hidden]"));
+
+        app.handle_key_event(key_event(KeyCode::Char('c')));
+        assert!(app.content.text.unwrap().contains("from team.md"));
+        assert_eq!(app.content.hidden_comment_lines, 0);
+    }
+
+    #[test]
+    fn w_wraps_long_lines_and_keeps_the_cursor_on_screen() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        let long = "x".repeat(150);
+        fs::write(
+            &file,
+            format!("# Rules\n{}end\n", format!("{long}\n").repeat(5)),
+        )
+        .unwrap();
+        let mut app = App::new(
+            vec![SourceRoot {
+                path: tmp.path().to_path_buf(),
+                files: vec![DiscoveredFile::new(file)],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        );
+        let mut terminal = Terminal::new(TestBackend::new(100, 10)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        app.handle_key_event(key_event(KeyCode::Tab));
+        app.handle_key_event(key_event(KeyCode::Char('w')));
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let height = usize::from(app.content.viewport_height);
+        assert!(app.content.wrapped_rows[1] > 1);
+        assert!(app.content.scroll_position().0 > app.content.line_count());
+
+        app.handle_key_event(key_event(KeyCode::Char('G')));
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let scroll = usize::from(app.content.scroll);
+        let rows: usize = app.content.wrapped_rows[scroll..=6].iter().sum();
+        assert!(rows <= height, "{rows} rows from line {scroll} do not fit");
+        assert!(buffer_text(terminal.backend().buffer()).contains("end"));
+
+        app.handle_key_event(key_event(KeyCode::Char('w')));
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        assert!(app.content.wrapped_rows.is_empty());
+    }
 }
@@ -0,0 +1,351 @@
+use std::cmp::Reverse;
+use std::path::Path;
+use std::path::PathBuf;
+
+use ratatui::Frame;
+use ratatui::layout::Constraint;
+use ratatui::layout::Direction;
+use ratatui::layout::Layout;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Text;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+
+use super::action::Action;
+use super::action::Effect;
+use super::app::App;
+use super::app::Mode;
+use super::app::Pane;
+use super::app::Screen;
+use super::picker::centered_rect;
+use super::text_input::TextInput;
+use crate::fuzzy::fuzzy_score;
+
+/// Most rows the finder popup grows to before it scrolls.
+const FINDER_MAX_ROWS: u16 = 15;
+
+/// Overlay for jumping to any discovered file by typing part of its path.
+#[derive(Debug, Default)]
+pub struct FileFinder {
+    pub query: TextInput,
+    /// Every discovered file, in tree order.
+    pub files: Vec<PathBuf>,
+    /// Files matching `query`, best first.
+    pub matches: Vec<PathBuf>,
+    /// Index into `matches` of the highlighted row.
+    pub cursor: usize,
+}
+
+impl FileFinder {
+    /// Creates a finder over `files` with an empty query.
+    pub fn new(files: Vec<PathBuf>) -> Self {
+        let mut finder = Self {
+            files,
+            ..Self::default()
+        };
+        finder.refilter();
+        finder
+    }
+
+    /// Recomputes `matches` for the current query and resets the cursor.
+    /// Ties keep shorter paths first, then tree order.
+    pub fn refilter(&mut self) {
+        let mut scored: Vec<(i64, usize, &PathBuf)> = self
+            .files
+            .iter()
+            .filter_map(|file| {
+                let path = file.display().to_string();
+                let score = fuzzy_score(self.query.text(), &path)?;
+                Some((score, path.len(), file))
+            })
+            .collect();
+        // Stable, so equal entries stay in tree order.
+        scored.sort_by_key(|&(score, len, _)| (Reverse(score), len));
+        self.matches = scored
+            .into_iter()
+            .map(|(_, _, file)| file.clone())
+            .collect();
+        self.cursor = 0;
+    }
+
+    /// The highlighted file, if any file matches.
+    pub fn selected(&self) -> Option<&PathBuf> {
+        self.matches.get(self.cursor)
+    }
+}
+
+impl App {
+    /// Opens the fuzzy finder over every file in the tree.
+    pub(crate) fn open_file_finder(&mut self) {
+        let files = self.discovered_files();
+        if files.is_empty() {
+            self.status_message = Some("No CLAUDE.md files loaded.".to_string());
+            return;
+        }
+        self.finder = Some(FileFinder::new(files));
+        self.mode = Mode::FileFinder;
+    }
+
+    /// Applies an action while the finder is open.
+    pub(crate) fn update_file_finder(&mut self, action: Action) -> Vec<Effect> {
+        let Some(finder) = &mut self.finder else {
+            self.mode = Mode::Normal;
+            return Vec::new();
+        };
+        match action {
+            Action::Input(key) => {
+                finder.query.handle_edit_key(key.code);
+                finder.refilter();
+            }
+            Action::MoveDown => {
+                if finder.cursor + 1 < finder.matches.len() {
+                    finder.cursor += 1;
+                }
+            }
+            Action::MoveUp => finder.cursor = finder.cursor.saturating_sub(1),
+            Action::Confirm => {
+                let chosen = finder.selected().cloned();
+                self.finder = None;
+                self.mode = Mode::Normal;
+                if let Some(file) = chosen {
+                    return vec![Effect::ShowFile(file)];
+                }
+            }
+            Action::Cancel => {
+                self.finder = None;
+                self.mode = Mode::Normal;
+            }
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    /// Switches to the Files screen with `file` selected in the tree and
+    /// shown in the content pane.
+    pub(crate) fn show_file(&mut self, file: &Path) {
+        self.screen = Screen::Files;
+        self.record_jump();
+        if self.select_tree_file(file) {
+            self.load_selected_content();
+        }
+    }
+
+    /// Like [`show_file`](Self::show_file), then focuses the content pane
+    /// with the cursor on 0-based `line`.
+    pub(crate) fn show_line(&mut self, file: &Path, line: usize) {
+        self.show_file(file);
+        if self.selected_file().as_deref() == Some(file) {
+            self.active_pane = Pane::Content;
+            self.content.jump_to_line(line);
+        }
+    }
+
+    /// Draws the finder centred over `area`.
+    pub(crate) fn draw_file_finder(&self, frame: &mut Frame, area: Rect) {
+        let Some(finder) = &self.finder else {
+            return;
+        };
+
+        let rows = (finder.matches.len() as u16).clamp(1, FINDER_MAX_ROWS);
+        // Border, query line and separator come on top of the result rows.
+        let popup = centered_rect(70, rows + 4, area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.input_border)
+            .title(format!(
+                "Find file ({}/{})",
+                finder.matches.len(),
+                finder.files.len()
+            ));
+        let inner = block.inner(popup);
+        frame.render_widget(Clear, popup);
+        frame.render_widget(block, popup);
+
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ])
+            .split(inner);
+        frame.render_widget(
+            Paragraph::new(format!("> {}", finder.query.text())),
+            parts[0],
+        );
+        frame.render_widget(
+            Paragraph::new("─".repeat(usize::from(parts[1].width))).style(self.theme.help_desc),
+            parts[1],
+        );
+        frame.set_cursor_position((parts[0].x + 2 + finder.query.cursor() as u16, parts[0].y));
+
+        let viewport = usize::from(parts[2].height);
+        let scroll = (finder.cursor + 1).saturating_sub(viewport);
+        let lines: Vec<Line> = if finder.matches.is_empty() {
+            vec![Line::styled(" No matching files", self.theme.help_desc)]
+        } else {
+            finder
+                .matches
+                .iter()
+                .enumerate()
+                .map(|(i, file)| {
+                    let style = if i == finder.cursor {
+                        self.theme.highlight
+                    } else {
+                        Style::default()
+                    };
+                    Line::styled(format!(" {}", file.display()), style)
+                })
+                .collect()
+        };
+        frame.render_widget(
+            Paragraph::new(Text::from(lines)).scroll((scroll as u16, 0)),
+            parts[2],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::PathBuf;
+
+    use ratatui::crossterm::event::KeyCode;
+    use ratatui::crossterm::event::KeyEvent;
+    use ratatui::crossterm::event::KeyEventKind;
+    use ratatui::crossterm::event::KeyEventState;
+    use ratatui::crossterm::event::KeyModifiers;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::Screen;
+    use crate::tui::app::test_helpers::key_event;
+    use crate::tui::app::test_helpers::render_once;
+    use crate::tui::app::test_helpers::sample_roots;
+
+    use super::FileFinder;
+
+    fn ctrl_p() -> KeyEvent {
+        KeyEvent {
+            code: KeyCode::Char('p'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::empty(),
+        }
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.handle_key_event(key_event(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn query_filters_and_ranks_files() {
+        let mut finder = FileFinder::new(vec![
+            PathBuf::from("/code/rapid/CLAUDE.md"),
+            PathBuf::from("/code/web/CLAUDE.md"),
+            PathBuf::from("/code/api/CLAUDE.md"),
+        ]);
+        assert_eq!(finder.matches.len(), 3);
+
+        finder.query.set("api");
+        finder.refilter();
+
+        assert_eq!(
+            finder.matches,
+            [
+                PathBuf::from("/code/api/CLAUDE.md"),
+                PathBuf::from("/code/rapid/CLAUDE.md"),
+            ]
+        );
+    }
+
+    #[test]
+    fn ctrl_p_opens_finder_from_any_screen() {
+        let mut app = App::new(sample_roots(), &Config::default());
+        app.screen = Screen::Library;
+
+        app.handle_key_event(ctrl_p());
+
+        assert_eq!(app.mode, Mode::FileFinder);
+        assert_eq!(app.finder.as_ref().unwrap().matches.len(), 3);
+        render_once(&mut app);
+    }
+
+    #[test]
+    fn typed_keys_filter_instead_of_running_commands() {
+        let mut app = App::new(sample_roots(), &Config::default());
+        app.handle_key_event(ctrl_p());
+
+        type_text(&mut app, "qsub");
+
+        assert!(!app.exit, "q is typed, not quit");
+        assert_eq!(app.finder.as_ref().unwrap().query.text(), "qsub");
+        assert!(app.finder.as_ref().unwrap().matches.is_empty());
+        app.handle_key_event(key_event(KeyCode::Backspace));
+        app.handle_key_event(key_event(KeyCode::Backspace));
+        app.handle_key_event(key_event(KeyCode::Backspace));
+        app.handle_key_event(key_event(KeyCode::Backspace));
+        type_text(&mut app, "sub");
+        assert_eq!(
+            app.finder.as_ref().unwrap().matches,
+            [PathBuf::from("/b/sub/CLAUDE.md")]
+        );
+    }
+
+    #[test]
+    fn enter_jumps_tree_and_content_to_file() {
+        let tmp = TempDir::new().unwrap();
+        let first = tmp.path().join("CLAUDE.md");
+        let nested = tmp.path().join("deep/CLAUDE.md");
+        fs::create_dir_all(tmp.path().join("deep")).unwrap();
+        fs::write(&first, "top").unwrap();
+        fs::write(&nested, "nested content").unwrap();
+        let mut app = App::new(
+            vec![SourceRoot {
+                path: tmp.path().to_path_buf(),
+                files: vec![
+                    DiscoveredFile::new(first),
+                    DiscoveredFile::new(nested.clone()),
+                ],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        );
+        app.screen = Screen::Settings;
+
+        app.handle_key_event(ctrl_p());
+        type_text(&mut app, "deep");
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(app.screen, Screen::Files);
+        assert_eq!(app.selected_file(), Some(nested));
+        assert_eq!(app.content.text.as_deref(), Some("nested content"));
+    }
+
+    #[test]
+    fn esc_closes_without_moving() {
+        let mut app = App::new(sample_roots(), &Config::default());
+        let before = app.selected_file();
+
+        app.handle_key_event(ctrl_p());
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.handle_key_event(key_event(KeyCode::Esc));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.finder.is_none());
+        assert_eq!(app.selected_file(), before);
+    }
+}
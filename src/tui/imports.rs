@@ -0,0 +1,365 @@
+//! `@path` imports shown under the files in the tree.
+//!
+//! A file that imports others gets one child node per import, nested as
+//! deep as Claude Code follows them. Selecting an import shows the imported
+//! file; imports that point at no file are flagged in red.
+//!
+//! In the content pane an import can also be expanded in place: the lines of
+//! the imported file follow the `@path` line, marked with `┆`. Which imports
+//! are expanded is remembered for the session, per importing file.
+
+use std::collections::HashSet;
+use std::env;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
+
+use ratatui::text::Line;
+use ratatui::text::Span;
+use tui_tree_widget::TreeItem;
+
+use super::app::App;
+use super::app::TreeId;
+use super::theme::Theme;
+use crate::lint::Finding;
+use crate::session::MAX_IMPORT_DEPTH;
+use crate::session::file_imports;
+use crate::tokens::Tokenizer;
+use crate::tokens::format_tokens;
+
+/// Start of the tree id of an import that points at no file; the resolved
+/// path follows.
+const MISSING_IMPORT_PREFIX: &str = "[missing] ";
+
+/// Whether `id` is the tree id of an import that points at no file.
+pub(crate) fn is_missing_import_id(id: &str) -> bool {
+    id.starts_with(MISSING_IMPORT_PREFIX)
+}
+
+/// The path a missing import's tree id points at.
+pub(crate) fn missing_import_path(id: &str) -> Option<&str> {
+    id.strip_prefix(MISSING_IMPORT_PREFIX)
+}
+
+/// One node per `@path` import of `file`, holding the imports of the
+/// imported file in turn. An import of a file already on the way down is
+/// shown without children, so cycles end.
+pub(crate) fn import_items(
+    file: &Path,
+    tokenizer: &dyn Tokenizer,
+    theme: &Theme,
+) -> Vec<TreeItem<'static, TreeId>> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    let mut chain = vec![file.to_path_buf()];
+    imports_below(home.as_deref(), &mut chain, tokenizer, theme)
+}
+
+/// The import nodes of the last file in `chain`.
+fn imports_below(
+    home: Option<&Path>,
+    chain: &mut Vec<PathBuf>,
+    tokenizer: &dyn Tokenizer,
+    theme: &Theme,
+) -> Vec<TreeItem<'static, TreeId>> {
+    let Some(file) = chain.last() else {
+        return Vec::new();
+    };
+    if chain.len() > MAX_IMPORT_DEPTH {
+        return Vec::new();
+    }
+    let Ok(text) = fs::read_to_string(file) else {
+        return Vec::new();
+    };
+    let mut ids = HashSet::new();
+    let mut items = Vec::new();
+    for import in file_imports(home, file, &text) {
+        let exists = import.path.is_file();
+        let id = if exists {
+            import.path.display().to_string()
+        } else {
+            format!("{MISSING_IMPORT_PREFIX}{}", import.path.display())
+        };
+        // A file imported twice is shown once.
+        if !ids.insert(id.clone()) {
+            continue;
+        }
+        let mut spans = vec![Span::styled(format!("@{}", import.target), theme.badge)];
+        if !exists {
+            spans.push(Span::styled(" [missing]", theme.error));
+            items.push(TreeItem::new_leaf(id, Line::from(spans)));
+            continue;
+        }
+        spans.push(Span::styled(
+            format!(" {}", format_tokens(tokenizer.count_file(&import.path))),
+            theme.detail,
+        ));
+        let children = if chain.contains(&import.path) {
+            spans.push(Span::styled(" [cycle]", theme.detail));
+            Vec::new()
+        } else {
+            chain.push(import.path);
+            let children = imports_below(home, chain, tokenizer, theme);
+            chain.pop();
+            children
+        };
+        let label = Line::from(spans);
+        items.push(match TreeItem::new(id.clone(), label.clone(), children) {
+            Ok(item) => item,
+            Err(_) => TreeItem::new_leaf(id, label),
+        });
+    }
+    items
+}
+
+/// Start of each line of an imported file expanded in the content pane.
+const INLINED_MARKER: &str = "┆ ";
+
+impl App {
+    /// `text`, the shown text of `file`, with the lines of each expanded
+    /// import after the line importing it. Moves `findings` to the lines
+    /// they end up on, and returns the ranges of lines added.
+    pub(crate) fn imports_inlined(
+        &self,
+        file: &Path,
+        text: String,
+        findings: &mut [Finding],
+    ) -> (String, Vec<Range<usize>>) {
+        if !self.expanded_imports.iter().any(|(from, _)| from == file) {
+            return (text, Vec::new());
+        }
+        let home = env::var_os("HOME").map(PathBuf::from);
+        let mut out: Vec<String> = Vec::new();
+        let mut inlined = Vec::new();
+        // Where each line of `text` ends up, for moving the findings.
+        let mut moved_to = Vec::new();
+        for line in text.lines() {
+            moved_to.push(out.len());
+            out.push(line.to_string());
+            for import in file_imports(home.as_deref(), file, line) {
+                if !self
+                    .expanded_imports
+                    .contains(&(file.to_path_buf(), import.path.clone()))
+                {
+                    continue;
+                }
+                let start = out.len();
+                match fs::read_to_string(&import.path) {
+                    Ok(imported) => {
+                        let (imported, _) = self.comments_filtered(imported);
+                        out.extend(
+                            imported
+                                .lines()
+                                .map(|line| format!("{INLINED_MARKER}{line}")),
+                        );
+                    }
+                    Err(err) => out.push(format!("{INLINED_MARKER}(cannot read: {err})")),
+                }
+                inlined.push(start..out.len());
+            }
+        }
+        for finding in findings.iter_mut() {
+            if let Some(line) = &mut finding.line
+                && let Some(&to) = moved_to.get(*line - 1)
+            {
+                *line = to + 1;
+            }
+        }
+        let mut shown = out.join("\n");
+        if text.ends_with('\n') {
+            shown.push('\n');
+        }
+        (shown, inlined)
+    }
+
+    /// Expands the imports on the content cursor's line in place, or
+    /// collapses them when they all are expanded already.
+    pub(crate) fn toggle_import_at_cursor(&mut self) {
+        let Some(file) = self.selected_file() else {
+            self.status_message = Some("Open a file first.".to_string());
+            return;
+        };
+        let cursor = self.content.cursor;
+        if self
+            .content
+            .inlined_imports
+            .iter()
+            .any(|r| r.contains(&cursor))
+        {
+            self.status_message =
+                Some("Move the cursor to an @import line of this file.".to_string());
+            return;
+        }
+        let line = self
+            .content
+            .text
+            .as_deref()
+            .and_then(|text| text.lines().nth(cursor))
+            .unwrap_or_default()
+            .to_string();
+        let targets = existing_imports(&file, &line);
+        if targets.is_empty() {
+            self.status_message = Some("No import on this line.".to_string());
+            return;
+        }
+        self.set_imports_expanded(&file, targets);
+    }
+
+    /// Expands every import of the shown file in place, or collapses them
+    /// all when none is left to expand.
+    pub(crate) fn toggle_all_imports(&mut self) {
+        let Some(file) = self.selected_file() else {
+            self.status_message = Some("Open a file first.".to_string());
+            return;
+        };
+        let text = self.content.text.clone().unwrap_or_default();
+        let targets: Vec<PathBuf> = text
+            .lines()
+            .enumerate()
+            .filter(|(i, _)| !self.content.inlined_imports.iter().any(|r| r.contains(i)))
+            .flat_map(|(_, line)| existing_imports(&file, line))
+            .collect();
+        if targets.is_empty() {
+            self.status_message = Some("This file imports nothing.".to_string());
+            return;
+        }
+        self.set_imports_expanded(&file, targets);
+    }
+
+    /// Expands the imports of `file` at `targets`, or collapses them when
+    /// all are expanded, and shows the file again.
+    fn set_imports_expanded(&mut self, file: &Path, targets: Vec<PathBuf>) {
+        let keys: Vec<(PathBuf, PathBuf)> = targets
+            .into_iter()
+            .map(|target| (file.to_path_buf(), target))
+            .collect();
+        let expand = keys.iter().any(|key| !self.expanded_imports.contains(key));
+        let count = keys.len();
+        for key in keys {
+            if expand {
+                self.expanded_imports.insert(key);
+            } else {
+                self.expanded_imports.remove(&key);
+            }
+        }
+        self.reload_keeping_position();
+        let noun = if count == 1 { "import" } else { "imports" };
+        self.status_message = Some(if expand {
+            format!("Expanded {count} {noun} in place.")
+        } else {
+            format!("Collapsed {count} {noun}.")
+        });
+    }
+}
+
+/// Paths of the imports on `line` of `file` that point at a file.
+fn existing_imports(file: &Path, line: &str) -> Vec<PathBuf> {
+    let home = env::var_os("HOME").map(PathBuf::from);
+    file_imports(home.as_deref(), file, line)
+        .into_iter()
+        .map(|import| import.path)
+        .filter(|path| path.is_file())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Pane;
+    use crate::tui::app::test_helpers::key_event;
+    use crate::tui::app::test_helpers::render_once;
+
+    #[test]
+    fn imports_nest_under_their_file_and_missing_ones_are_flagged() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().to_path_buf();
+        fs::create_dir_all(root.join("docs")).unwrap();
+        fs::write(root.join("CLAUDE.md"), "See @docs/style.md and @gone.md\n").unwrap();
+        fs::write(root.join("docs/style.md"), "Use tabs, see @../CLAUDE.md\n").unwrap();
+        let roots = vec![SourceRoot {
+            path: root.clone(),
+            files: vec![DiscoveredFile::new(root.join("CLAUDE.md"))],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+        app.select_tree_file(&root.join("CLAUDE.md"));
+
+        let file = &app.tree_items[0].children()[0];
+        let imports: Vec<&String> = file.children().iter().map(|i| i.identifier()).collect();
+        assert_eq!(imports.len(), 2);
+        assert_eq!(file.children()[0].children().len(), 1);
+
+        app.handle_key_event(key_event(KeyCode::Right));
+        render_once(&mut app);
+        app.handle_key_event(key_event(KeyCode::Down));
+        assert_eq!(app.selected_file(), Some(root.join("docs/style.md")));
+        assert!(app.content.text.as_deref().unwrap().contains("Use tabs"));
+
+        app.handle_key_event(key_event(KeyCode::Down));
+        assert_eq!(app.selected_file(), None);
+        let status = app.status_message.clone().unwrap_or_default();
+        assert!(status.contains("gone.md"), "{status}");
+    }
+
+    #[test]
+    fn each_import_expands_in_place_on_its_own() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().to_path_buf();
+        let file = root.join("CLAUDE.md");
+        let other = root.join("sub/CLAUDE.md");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(&file, "@a.md\n@b.md\nend\n").unwrap();
+        fs::write(root.join("a.md"), "alpha\n").unwrap();
+        fs::write(root.join("b.md"), "beta\n").unwrap();
+        fs::write(&other, "other\n").unwrap();
+        let roots = vec![SourceRoot {
+            path: root.clone(),
+            files: vec![
+                DiscoveredFile::new(file.clone()),
+                DiscoveredFile::new(other.clone()),
+            ],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+        app.select_tree_file(&file);
+        app.load_selected_content();
+        app.active_pane = Pane::Content;
+        let shown = |app: &App| app.content.text.clone().unwrap();
+
+        app.content.jump_to_line(1);
+        app.handle_key_event(key_event(KeyCode::Char('i')));
+        assert_eq!(shown(&app), "@a.md\n@b.md\n┆ beta\nend\n");
+        assert!(!app.content.shows_file_as_is());
+
+        // The cursor is on an expanded line now, which imports nothing here.
+        app.content.jump_to_line(2);
+        app.handle_key_event(key_event(KeyCode::Char('i')));
+        assert_eq!(app.expanded_imports.len(), 1);
+
+        app.handle_key_event(key_event(KeyCode::Char('I')));
+        assert_eq!(shown(&app), "@a.md\n┆ alpha\n@b.md\n┆ beta\nend\n");
+
+        // Kept for the session, across files.
+        app.select_tree_file(&other);
+        app.load_selected_content();
+        app.select_tree_file(&file);
+        app.load_selected_content();
+        assert_eq!(app.content.inlined_imports, [1..2, 3..4]);
+
+        app.handle_key_event(key_event(KeyCode::Char('I')));
+        assert_eq!(shown(&app), "@a.md\n@b.md\nend\n");
+        assert!(app.content.shows_file_as_is());
+    }
+}
@@ -0,0 +1,211 @@
+//! Noting the app's changes in the journal, and the history panel listing
+//! them for the selected file or directory.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::text::Line;
+use ratatui::text::Text;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+
+use super::action::Action;
+use super::action::Effect;
+use super::app::App;
+use super::app::Mode;
+use super::picker::centered_rect;
+use crate::journal::JournalEntry;
+use crate::journal::append;
+use crate::journal::journal_path_in;
+use crate::journal::read_journal;
+use crate::journal::record;
+
+/// The history panel, open while in `Mode::History`.
+#[derive(Debug)]
+pub struct HistoryView {
+    /// The file or directory whose changes are listed.
+    pub path: PathBuf,
+    /// One row per change, newest first.
+    pub lines: Vec<String>,
+    /// First visible row.
+    pub scroll: u16,
+    /// Captured during draw — visible rows inside the border.
+    pub viewport_height: u16,
+}
+
+impl App {
+    /// Notes a change of `path`, `bytes_before` long before it, in the
+    /// journal, and has the file linted again. Without a data directory
+    /// nothing is journaled; a journal that cannot be written does not stop
+    /// the change.
+    pub(crate) fn journal_change(&mut self, action: &str, path: &Path, bytes_before: u64) {
+        self.relint(path);
+        if let Some(dir) = &self.data_dir {
+            let _ = record(&journal_path_in(dir), action, path, bytes_before);
+        }
+    }
+
+    /// Notes that `from` was moved to `to` in the journal, and has `to`
+    /// linted again.
+    pub(crate) fn journal_move(&mut self, action: &str, from: &Path, to: &Path) {
+        self.relint(to);
+        if let Some(dir) = &self.data_dir {
+            let entry = JournalEntry {
+                millis: crate::backup::now_millis(),
+                action: action.to_string(),
+                path: to.to_path_buf(),
+                from: Some(from.to_path_buf()),
+                delta: 0,
+            };
+            let _ = append(&journal_path_in(dir), &entry);
+        }
+    }
+
+    /// Lists the journal entries of the selected file, or of every file
+    /// under the selected root.
+    pub(crate) fn open_history(&mut self) {
+        let Some(path) = self.selected_file().or_else(|| self.selected_root()) else {
+            self.status_message = Some("Select a file or root to see its history.".to_string());
+            return;
+        };
+        let Some(dir) = &self.data_dir else {
+            self.status_message = Some("The journal is off: no config directory.".to_string());
+            return;
+        };
+        let entries = match read_journal(&journal_path_in(dir)) {
+            Ok(entries) => entries,
+            Err(err) => {
+                self.status_message = Some(format!("Cannot read the journal: {err:#}"));
+                return;
+            }
+        };
+        let mut lines: Vec<String> = entries
+            .iter()
+            .rev()
+            .filter(|entry| entry.concerns(&path))
+            .map(JournalEntry::summary)
+            .collect();
+        if lines.is_empty() {
+            lines.push("jigolo has not changed anything here.".to_string());
+        }
+        self.history_view = Some(HistoryView {
+            path,
+            lines,
+            scroll: 0,
+            viewport_height: 0,
+        });
+        self.mode = Mode::History;
+    }
+
+    /// Draws the history panel as a popup over `area`.
+    pub(crate) fn draw_history(&mut self, frame: &mut Frame, area: Rect) {
+        let border = self.theme.input_border;
+        let Some(view) = &mut self.history_view else {
+            return;
+        };
+        let popup = centered_rect(90, area.height.saturating_sub(2), area);
+        view.viewport_height = popup.height.saturating_sub(2);
+        view.scroll = view.scroll.min(max_scroll(view));
+        let lines: Vec<Line> = view
+            .lines
+            .iter()
+            .map(|line| Line::raw(line.as_str()))
+            .collect();
+        let widget = Paragraph::new(Text::from(lines))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border)
+                    .title(format!("Changes by jigolo to {}", view.path.display())),
+            )
+            .scroll((view.scroll, 0));
+        frame.render_widget(Clear, popup);
+        frame.render_widget(widget, popup);
+    }
+
+    /// Applies an action while the history panel is open.
+    pub(crate) fn update_history(&mut self, action: Action) -> Vec<Effect> {
+        let Some(view) = &mut self.history_view else {
+            self.mode = self.mode_after_modal();
+            return Vec::new();
+        };
+        let page = view.viewport_height.max(1);
+        match action {
+            Action::Cancel => {
+                self.history_view = None;
+                self.mode = self.mode_after_modal();
+            }
+            Action::MoveDown => view.scroll = (view.scroll + 1).min(max_scroll(view)),
+            Action::MoveUp => view.scroll = view.scroll.saturating_sub(1),
+            Action::PageDown => {
+                view.scroll = view.scroll.saturating_add(page).min(max_scroll(view))
+            }
+            Action::PageUp => view.scroll = view.scroll.saturating_sub(page),
+            _ => {}
+        }
+        Vec::new()
+    }
+}
+
+fn max_scroll(view: &HistoryView) -> u16 {
+    let max = view
+        .lines
+        .len()
+        .saturating_sub(view.viewport_height as usize);
+    max.min(u16::MAX as usize) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::test_helpers::key_event;
+
+    #[test]
+    fn saves_are_journaled_and_h_lists_them_for_the_file() {
+        let tmp = TempDir::new().unwrap();
+        let data = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "old").unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![DiscoveredFile::new(file.clone())],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+        app.data_dir = Some(data.path().to_path_buf());
+
+        app.enter_edit_mode();
+        app.edit_state
+            .as_mut()
+            .unwrap()
+            .textarea
+            .insert_str(" and new");
+        app.save_edit_to(&file);
+        app.handle_key_event(key_event(KeyCode::Esc));
+
+        app.handle_key_event(key_event(KeyCode::Char('H')));
+        assert_eq!(app.mode, Mode::History);
+        let view = app.history_view.as_ref().unwrap();
+        assert_eq!(view.lines.len(), 1);
+        assert!(view.lines[0].contains("edit"), "{}", view.lines[0]);
+        assert!(view.lines[0].contains("+8 B"), "{}", view.lines[0]);
+
+        app.handle_key_event(key_event(KeyCode::Esc));
+        assert_eq!(app.mode, Mode::Normal);
+    }
+}
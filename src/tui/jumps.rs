@@ -0,0 +1,233 @@
+//! The jump list: where the content cursor was before each jump, so
+//! `Ctrl-O` can walk back through them and `Ctrl-I` (or `Ctrl-N`, where
+//! the terminal sends `Ctrl-I` as `Tab`) forward again, as in vim. Opening a file (from the finder, a search, the recent files or an
+//! import), `gg`/`G`, outline moves and `!` all count as jumps.
+
+use std::path::PathBuf;
+
+use super::app::App;
+use super::app::Pane;
+use super::app::Screen;
+
+/// Most positions kept in the jump list; the oldest go first.
+pub const MAX_JUMPS: usize = 100;
+
+/// A place the content cursor was: a file and a 0-based line.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Jump {
+    pub file: PathBuf,
+    pub line: usize,
+}
+
+/// Positions jumped away from, oldest first, and where `Ctrl-O` and
+/// `Ctrl-I` are in them.
+#[derive(Debug, Clone, Default)]
+pub struct JumpList {
+    jumps: Vec<Jump>,
+    /// Index of the position shown after walking the list; `jumps.len()`
+    /// while not walking it.
+    index: usize,
+}
+
+impl JumpList {
+    /// Records `jump` as the newest position, dropping the positions
+    /// walked back past.
+    pub fn push(&mut self, jump: Jump) {
+        self.jumps.truncate(self.index);
+        if self.jumps.last() != Some(&jump) {
+            self.jumps.push(jump);
+        }
+        if self.jumps.len() > MAX_JUMPS {
+            self.jumps.remove(0);
+        }
+        self.index = self.jumps.len();
+    }
+
+    /// The position before the one shown, `current`. Leaving the newest
+    /// position records it first, so [`forward`](Self::forward) comes back
+    /// to it.
+    pub fn back(&mut self, current: Option<Jump>) -> Option<Jump> {
+        if self.index == 0 {
+            return None;
+        }
+        if self.index == self.jumps.len()
+            && let Some(current) = current
+            && self.jumps.last() != Some(&current)
+        {
+            self.jumps.push(current);
+        }
+        self.index -= 1;
+        Some(self.jumps[self.index].clone())
+    }
+
+    /// The position after the one shown, if `Ctrl-O` walked back.
+    pub fn forward(&mut self) -> Option<Jump> {
+        if self.index + 1 >= self.jumps.len() {
+            return None;
+        }
+        self.index += 1;
+        Some(self.jumps[self.index].clone())
+    }
+}
+
+impl App {
+    /// Where the content cursor is, if a file is shown.
+    fn current_jump(&self) -> Option<Jump> {
+        Some(Jump {
+            file: self.selected_file()?,
+            line: self.content.cursor,
+        })
+    }
+
+    /// Records the cursor's position before a jump moves it.
+    pub(crate) fn record_jump(&mut self) {
+        if let Some(jump) = self.current_jump() {
+            self.jumps.push(jump);
+        }
+    }
+
+    /// Goes back to the position before the last jump.
+    pub(crate) fn jump_back(&mut self) {
+        let current = self.current_jump();
+        match self.jumps.back(current) {
+            Some(jump) => self.go_to_jump(&jump),
+            None => self.status_message = Some("At the oldest jump.".to_string()),
+        }
+    }
+
+    /// Goes forward to the position `Ctrl-O` left.
+    pub(crate) fn jump_forward(&mut self) {
+        match self.jumps.forward() {
+            Some(jump) => self.go_to_jump(&jump),
+            None => self.status_message = Some("At the newest jump.".to_string()),
+        }
+    }
+
+    /// Shows `jump` without recording it as a jump of its own.
+    fn go_to_jump(&mut self, jump: &Jump) {
+        self.screen = Screen::Files;
+        if self.selected_file().as_deref() != Some(jump.file.as_path()) {
+            if !self.select_tree_file(&jump.file) {
+                self.status_message = Some(format!("{} is gone.", jump.file.display()));
+                return;
+            }
+            self.load_selected_content();
+        }
+        self.active_pane = Pane::Content;
+        self.content.jump_to_line(jump.line);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ratatui::crossterm::event::KeyCode;
+    use ratatui::crossterm::event::KeyEvent;
+    use ratatui::crossterm::event::KeyModifiers;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::test_helpers::key_event;
+
+    fn jump(file: &str, line: usize) -> Jump {
+        Jump {
+            file: PathBuf::from(file),
+            line,
+        }
+    }
+
+    #[test]
+    fn back_and_forward_walk_the_list_and_a_new_jump_drops_the_rest() {
+        let mut list = JumpList::default();
+        list.push(jump("/a", 1));
+        list.push(jump("/b", 2));
+
+        assert_eq!(list.back(Some(jump("/c", 3))), Some(jump("/b", 2)));
+        assert_eq!(list.back(Some(jump("/b", 2))), Some(jump("/a", 1)));
+        assert_eq!(list.back(Some(jump("/a", 1))), None);
+        assert_eq!(list.forward(), Some(jump("/b", 2)));
+        assert_eq!(list.forward(), Some(jump("/c", 3)));
+        assert_eq!(list.forward(), None);
+
+        list.back(None);
+        list.push(jump("/d", 4));
+        assert_eq!(list.forward(), None);
+        assert_eq!(list.back(None), Some(jump("/d", 4)));
+        assert_eq!(list.back(None), Some(jump("/a", 1)));
+    }
+
+    #[test]
+    fn ctrl_o_and_ctrl_i_return_across_files() {
+        let tmp = TempDir::new().unwrap();
+        let a = tmp.path().join("CLAUDE.md");
+        let b = tmp.path().join("sub/CLAUDE.md");
+        fs::create_dir(tmp.path().join("sub")).unwrap();
+        fs::write(&a, "one\ntwo\nthree\nfour\n").unwrap();
+        fs::write(&b, "# B\n").unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![
+                DiscoveredFile::new(a.clone()),
+                DiscoveredFile::new(b.clone()),
+            ],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+        app.show_line(&a, 2);
+
+        app.show_file(&b);
+        app.handle_key_event(KeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL));
+        assert_eq!(app.selected_file(), Some(a.clone()));
+        assert_eq!(app.content.cursor, 2);
+
+        app.handle_key_event(KeyEvent::new(KeyCode::Char('i'), KeyModifiers::CONTROL));
+        assert_eq!(app.selected_file(), Some(b));
+        app.handle_key_event(KeyEvent::new(KeyCode::Char('i'), KeyModifiers::CONTROL));
+        assert_eq!(app.status_message.as_deref(), Some("At the newest jump."));
+
+        app.handle_key_event(KeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL));
+        app.handle_key_event(key_event(KeyCode::Char('G')));
+        app.handle_key_event(KeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL));
+        assert_eq!(app.selected_file(), Some(a));
+        assert_eq!(app.content.cursor, 2);
+    }
+
+    #[test]
+    fn tab_switches_panes_and_ctrl_n_goes_forward() {
+        let tmp = TempDir::new().unwrap();
+        let a = tmp.path().join("CLAUDE.md");
+        let b = tmp.path().join("sub/CLAUDE.md");
+        fs::create_dir(tmp.path().join("sub")).unwrap();
+        fs::write(&a, "one\ntwo\nthree\n").unwrap();
+        fs::write(&b, "# B\n").unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![
+                DiscoveredFile::new(a.clone()),
+                DiscoveredFile::new(b.clone()),
+            ],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+        app.show_line(&a, 2);
+        app.show_file(&b);
+        app.handle_key_event(KeyEvent::new(KeyCode::Char('o'), KeyModifiers::CONTROL));
+        assert_eq!(app.active_pane, Pane::Content);
+
+        // What a terminal without keyboard enhancement sends for Ctrl-I.
+        app.handle_key_event(key_event(KeyCode::Tab));
+        assert_eq!(app.active_pane, Pane::FileList);
+        assert_eq!(app.selected_file(), Some(a));
+
+        app.handle_key_event(KeyEvent::new(KeyCode::Char('n'), KeyModifiers::CONTROL));
+        assert_eq!(app.selected_file(), Some(b));
+    }
+}
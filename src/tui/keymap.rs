@@ -0,0 +1,1044 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::time::Duration;
+
+use ratatui::crossterm::event::KeyCode;
+use ratatui::crossterm::event::KeyEvent;
+use ratatui::crossterm::event::KeyModifiers;
+
+use super::action::Action;
+use super::app::Screen;
+use crate::merge::MergeChoice;
+
+/// How long the keys of an unfinished sequence wait for the next one. When it
+/// runs out, the keys typed so far act on their own if they are bound.
+pub const SEQUENCE_TIMEOUT: Duration = Duration::from_millis(1000);
+
+/// Where a key is pressed. Each context has its own bindings, so the same key
+/// can mean different things on different screens.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum KeyContext {
+    /// Applies everywhere, before any other context.
+    Any,
+    /// Applies in Normal mode on every screen, before the screen's bindings.
+    Global,
+    FilesTree,
+    FilesContent,
+    FilesOutline,
+    VisualSelect,
+    /// Single-line prompts (snippet title, rename, export path, new file).
+    TextInput,
+    Editor,
+    Settings,
+    ComposeList,
+    ComposePreview,
+    Library,
+    Picker,
+    DiffPreview,
+    Budget,
+    Trim,
+    /// The simulated session popup.
+    Session,
+    /// Merging two files section by section.
+    Merge,
+    /// The history panel of jigolo's changes.
+    History,
+    /// The log of status bar messages.
+    Messages,
+    FileFinder,
+    GlobalSearch,
+}
+
+impl KeyContext {
+    /// Every context with the name it has in the `[keymap]` config section.
+    pub const NAMES: &[(&str, KeyContext)] = &[
+        ("any", KeyContext::Any),
+        ("global", KeyContext::Global),
+        ("files_tree", KeyContext::FilesTree),
+        ("files_content", KeyContext::FilesContent),
+        ("files_outline", KeyContext::FilesOutline),
+        ("visual_select", KeyContext::VisualSelect),
+        ("text_input", KeyContext::TextInput),
+        ("editor", KeyContext::Editor),
+        ("settings", KeyContext::Settings),
+        ("compose_list", KeyContext::ComposeList),
+        ("compose_preview", KeyContext::ComposePreview),
+        ("library", KeyContext::Library),
+        ("picker", KeyContext::Picker),
+        ("diff_preview", KeyContext::DiffPreview),
+        ("budget", KeyContext::Budget),
+        ("trim", KeyContext::Trim),
+        ("session", KeyContext::Session),
+        ("merge", KeyContext::Merge),
+        ("history", KeyContext::History),
+        ("messages", KeyContext::Messages),
+        ("file_finder", KeyContext::FileFinder),
+        ("global_search", KeyContext::GlobalSearch),
+    ];
+
+    /// The context called `name` in the config.
+    pub fn from_name(name: &str) -> Option<Self> {
+        Self::NAMES
+            .iter()
+            .find(|(n, _)| *n == name)
+            .map(|(_, context)| *context)
+    }
+
+    /// True for contexts where unbound keys are typed text rather than
+    /// ignored.
+    pub fn takes_text(self) -> bool {
+        matches!(
+            self,
+            KeyContext::TextInput
+                | KeyContext::Editor
+                | KeyContext::FileFinder
+                | KeyContext::GlobalSearch
+        )
+    }
+}
+
+/// A key plus the modifiers that must be held.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct KeyBinding {
+    pub code: KeyCode,
+    pub modifiers: KeyModifiers,
+}
+
+impl KeyBinding {
+    /// A key pressed without modifiers.
+    pub const fn plain(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::NONE,
+        }
+    }
+
+    /// A key pressed with Ctrl.
+    pub const fn ctrl(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::CONTROL,
+        }
+    }
+
+    /// A key pressed with Alt.
+    pub const fn alt(code: KeyCode) -> Self {
+        Self {
+            code,
+            modifiers: KeyModifiers::ALT,
+        }
+    }
+
+    /// Returns true if `key` triggers this binding. Shift is part of the
+    /// character itself (`T` vs `t`), so it is ignored for character keys.
+    pub fn matches(&self, key: &KeyEvent) -> bool {
+        let mut modifiers = key.modifiers;
+        if matches!(key.code, KeyCode::Char(_)) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        self.code == key.code && self.modifiers == modifiers
+    }
+}
+
+impl KeyBinding {
+    /// Parses one key as written in the config: a character (`g`, `G`,
+    /// `/`), a named key (`esc`, `enter`, `tab`, `space`, `up`, `pgdn`,
+    /// `f5`, ...) or either with `ctrl+`, `alt+` or `shift+` in front.
+    /// `Ctrl-W` style is accepted too. Names ignore case; characters do not.
+    pub fn parse(text: &str) -> Option<Self> {
+        let mut modifiers = KeyModifiers::NONE;
+        let mut rest = text;
+        loop {
+            let lower = rest.to_ascii_lowercase();
+            let (modifier, len) = if lower.starts_with("ctrl+") || lower.starts_with("ctrl-") {
+                (KeyModifiers::CONTROL, 5)
+            } else if lower.starts_with("alt+") || lower.starts_with("alt-") {
+                (KeyModifiers::ALT, 4)
+            } else if lower.starts_with("shift+") || lower.starts_with("shift-") {
+                (KeyModifiers::SHIFT, 6)
+            } else {
+                break;
+            };
+            modifiers |= modifier;
+            rest = &rest[len..];
+        }
+
+        let mut chars = rest.chars();
+        let code = match (chars.next(), chars.next()) {
+            (Some(c), None) if modifiers.contains(KeyModifiers::CONTROL) => {
+                KeyCode::Char(c.to_ascii_lowercase())
+            }
+            (Some(c), None) => KeyCode::Char(c),
+            _ => match rest.to_ascii_lowercase().as_str() {
+                "esc" | "escape" => KeyCode::Esc,
+                "enter" | "return" => KeyCode::Enter,
+                "tab" => KeyCode::Tab,
+                "space" => KeyCode::Char(' '),
+                "backspace" => KeyCode::Backspace,
+                "delete" | "del" => KeyCode::Delete,
+                "up" => KeyCode::Up,
+                "down" => KeyCode::Down,
+                "left" => KeyCode::Left,
+                "right" => KeyCode::Right,
+                "home" => KeyCode::Home,
+                "end" => KeyCode::End,
+                "pgup" | "pageup" => KeyCode::PageUp,
+                "pgdn" | "pagedown" => KeyCode::PageDown,
+                name => {
+                    let n = name.strip_prefix('f')?.parse().ok()?;
+                    KeyCode::F(n)
+                }
+            },
+        };
+        // Shift is part of a character (`G`), so only named keys keep it.
+        if matches!(code, KeyCode::Char(_)) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Some(Self { code, modifiers })
+    }
+}
+
+impl From<&KeyEvent> for KeyBinding {
+    fn from(key: &KeyEvent) -> Self {
+        let mut modifiers = key.modifiers;
+        if matches!(key.code, KeyCode::Char(_)) {
+            modifiers.remove(KeyModifiers::SHIFT);
+        }
+        Self {
+            code: key.code,
+            modifiers,
+        }
+    }
+}
+
+/// Formats the binding the way the help bar shows keys: `g`, `Ctrl+W`,
+/// `Esc`, `↓`.
+impl fmt::Display for KeyBinding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.modifiers.contains(KeyModifiers::CONTROL) {
+            write!(f, "Ctrl+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::ALT) {
+            write!(f, "Alt+")?;
+        }
+        if self.modifiers.contains(KeyModifiers::SHIFT) {
+            write!(f, "Shift+")?;
+        }
+        match self.code {
+            KeyCode::Char(' ') => write!(f, "Space"),
+            KeyCode::Char(c) if self.modifiers.contains(KeyModifiers::CONTROL) => {
+                write!(f, "{}", c.to_ascii_uppercase())
+            }
+            KeyCode::Char(c) => write!(f, "{c}"),
+            KeyCode::Up => write!(f, "↑"),
+            KeyCode::Down => write!(f, "↓"),
+            KeyCode::Left => write!(f, "←"),
+            KeyCode::Right => write!(f, "→"),
+            KeyCode::PageUp => write!(f, "PgUp"),
+            KeyCode::PageDown => write!(f, "PgDn"),
+            KeyCode::F(n) => write!(f, "F{n}"),
+            other => write!(f, "{other:?}"),
+        }
+    }
+}
+
+/// What the keys pressed so far amount to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum KeyResolution {
+    /// The keys trigger this action.
+    Action(Action),
+    /// The keys start a longer sequence; wait for the next one.
+    Pending,
+    /// Nothing is bound to the keys.
+    Unbound,
+}
+
+/// One entry of the keymap.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Binding {
+    pub context: KeyContext,
+    /// The keys pressed one after the other: a single key, or a sequence
+    /// such as `g g`.
+    pub keys: Vec<KeyBinding>,
+    pub action: Action,
+}
+
+impl Binding {
+    /// Whether `keys` are the first keys of this binding, or all of them.
+    fn starts_with(&self, keys: &[KeyEvent]) -> bool {
+        keys.len() <= self.keys.len() && self.keys.iter().zip(keys).all(|(b, k)| b.matches(k))
+    }
+}
+
+/// Translates key presses into [`Action`]s.
+#[derive(Debug, Clone)]
+pub struct KeyMap {
+    bindings: Vec<Binding>,
+}
+
+impl Default for KeyMap {
+    fn default() -> Self {
+        use Action::*;
+        use KeyCode::Char;
+        use KeyCode::Down;
+        use KeyCode::Enter;
+        use KeyCode::Esc;
+        use KeyCode::Left;
+        use KeyCode::PageDown as PgDn;
+        use KeyCode::PageUp as PgUp;
+        use KeyCode::Right;
+        use KeyCode::Tab;
+        use KeyCode::Up;
+        use KeyContext as C;
+
+        let plain = KeyBinding::plain;
+        let ctrl = KeyBinding::ctrl;
+        let vertical = [
+            (plain(Down), MoveDown),
+            (plain(Char('j')), MoveDown),
+            (plain(Up), MoveUp),
+            (plain(Char('k')), MoveUp),
+        ];
+        let paging = [(plain(PgDn), PageDown), (plain(PgUp), PageUp)];
+        let horizontal = [
+            (plain(Left), MoveLeft),
+            (plain(Char('h')), MoveLeft),
+            (plain(Right), MoveRight),
+            (plain(Char('l')), MoveRight),
+        ];
+
+        let mut map = Self {
+            bindings: Vec::new(),
+        };
+        map.bind(
+            C::Any,
+            [(ctrl(Char('c')), Quit), (plain(KeyCode::F(12)), Snapshot)],
+        );
+        map.bind(
+            C::Global,
+            [
+                (plain(Char('1')), SwitchScreen(Screen::Files)),
+                (plain(Char('2')), SwitchScreen(Screen::Settings)),
+                (plain(Char('3')), SwitchScreen(Screen::Compose)),
+                (plain(Char('4')), SwitchScreen(Screen::Library)),
+                (plain(Char('T')), ToggleTheme),
+                (plain(Char('B')), OpenBudget),
+                (ctrl(Char('p')), OpenFileFinder),
+                (plain(Char('\'')), OpenRecentFiles),
+                (ctrl(Char('g')), OpenMessages),
+                (ctrl(Char('o')), JumpBack),
+                (ctrl(Char('i')), JumpForward),
+                (ctrl(Char('n')), JumpForward),
+            ],
+        );
+        map.bind(
+            C::Global,
+            ('1'..='5')
+                .zip(0..)
+                .map(|(digit, index)| (KeyBinding::alt(Char(digit)), JumpRecent(index))),
+        );
+
+        map.bind(
+            C::FilesTree,
+            [
+                (plain(Char('q')), Quit),
+                (plain(Tab), TogglePane),
+                (plain(Char('n')), NewFile),
+                (plain(Char('b')), OpenBackups),
+                (plain(Char('M')), MigrateLocal),
+                (plain(Char('F')), LoadFullContent),
+                (plain(Char('g')), ToggleRepoGroups),
+                (plain(KeyCode::F(5)), Rescan),
+                (plain(Char('S')), SimulateSession),
+                (plain(Char('o')), ToggleOutline),
+                (plain(Char('p')), ToggleSplitPreview),
+                (plain(Char('c')), ToggleComments),
+                (plain(Char('/')), OpenGlobalSearch),
+                (plain(Char('Y')), CopyFile),
+                (plain(Char('D')), Delete),
+                (plain(Char('R')), Rename),
+                (plain(Char('N')), FixName),
+                (plain(Char('t')), Trim),
+                (plain(Char('m')), Merge),
+                (plain(Char('H')), History),
+                (plain(Char('!')), NextFinding),
+                (plain(Char('E')), OpenInEditor),
+                (plain(Char('X')), RunTool),
+                (plain(Char('&')), RepeatInsert),
+                (plain(Char('L')), ToggleLoadOrder),
+            ],
+        );
+        map.bind(C::FilesTree, vertical.clone());
+        map.bind(C::FilesTree, horizontal.clone());
+        map.bind(
+            C::FilesContent,
+            [
+                (plain(Char('q')), Quit),
+                (plain(Tab), TogglePane),
+                (plain(Char('v')), StartSelection),
+                (plain(Char('e')), Edit),
+                (plain(Char('F')), LoadFullContent),
+                (plain(Char('/')), OpenGlobalSearch),
+                (plain(Char('o')), ToggleOutline),
+                (plain(Char('p')), ToggleSplitPreview),
+                (plain(Char('c')), ToggleComments),
+                (plain(Char('Y')), CopyFile),
+                (plain(Char('t')), Trim),
+                (plain(Char('H')), History),
+                (plain(Char('.')), QuickActions),
+                (plain(Char('w')), ToggleWrap),
+                (plain(Char('x')), ExtractSection),
+                (plain(Char('i')), ToggleImport),
+                (plain(Char('I')), ToggleAllImports),
+                (plain(Char('!')), NextFinding),
+                (plain(Char('E')), OpenInEditor),
+                (plain(Char('X')), RunTool),
+                (plain(Char('&')), RepeatInsert),
+                (plain(Char('L')), ToggleLoadOrder),
+            ],
+        );
+        map.bind(C::FilesContent, vertical.clone());
+        map.bind_sequences(
+            C::FilesContent,
+            [
+                (vec![plain(Char('g')), plain(Char('g'))], MoveTop),
+                (vec![plain(Char('G'))], MoveBottom),
+            ],
+        );
+        for context in [C::FilesTree, C::FilesContent, C::FilesOutline] {
+            map.bind_sequences(
+                context,
+                [
+                    (vec![ctrl(Char('w')), plain(Char('w'))], TogglePane),
+                    (vec![ctrl(Char('w')), plain(Char('v'))], ToggleOutline),
+                ],
+            );
+        }
+        map.bind(
+            C::FilesOutline,
+            [
+                (plain(Char('q')), Quit),
+                (plain(Tab), TogglePane),
+                (plain(Char('o')), ToggleOutline),
+                (plain(Enter), Confirm),
+                (plain(Esc), Cancel),
+            ],
+        );
+        map.bind(C::FilesOutline, vertical.clone());
+        map.bind(C::FilesContent, paging.clone());
+        map.bind(
+            C::VisualSelect,
+            [
+                (plain(Esc), Cancel),
+                (plain(Char('s')), SaveSelection),
+                (plain(Char('y')), CopySelection),
+                (plain(Char('m')), MoveSelection),
+            ],
+        );
+        map.bind(C::VisualSelect, vertical.clone());
+
+        map.bind(
+            C::TextInput,
+            [(plain(Enter), Confirm), (plain(Esc), Cancel)],
+        );
+        map.bind(C::Editor, [(ctrl(Char('s')), Save), (plain(Esc), Cancel)]);
+
+        map.bind(
+            C::Settings,
+            [
+                (plain(Char('q')), Quit),
+                (plain(Char('e')), Edit),
+                (plain(Char('o')), OpenInEditor),
+                (plain(Char('m')), ToggleMergedView),
+            ],
+        );
+        map.bind(C::Settings, vertical.clone());
+        map.bind(C::Settings, paging.clone());
+        map.bind(C::Settings, horizontal.clone());
+        map.bind_sequences(
+            C::Settings,
+            [
+                (vec![plain(Char('g')), plain(Char('g'))], MoveTop),
+                (vec![plain(Char('G'))], MoveBottom),
+                (vec![plain(Char('z')), plain(Char('a'))], ToggleFold),
+            ],
+        );
+
+        for context in [C::ComposeList, C::ComposePreview] {
+            map.bind(
+                context,
+                [
+                    (plain(Char('q')), Quit),
+                    (plain(Esc), Back),
+                    (plain(Tab), TogglePane),
+                ],
+            );
+            map.bind(context, vertical.clone());
+        }
+        map.bind(
+            C::ComposeList,
+            [(plain(Char(' ')), ToggleMark), (plain(Char('w')), Export)],
+        );
+
+        map.bind(
+            C::Library,
+            [
+                (plain(Char('q')), Quit),
+                (plain(Esc), Back),
+                (plain(Char('e')), Edit),
+                (plain(Char('d')), Delete),
+                (plain(Char('r')), Rename),
+                (plain(Char('m')), ExportMemory),
+                (plain(Char('i')), InsertSnippet),
+                (plain(Enter), InsertSnippet),
+                (plain(Char('p')), AppendSnippet),
+                (plain(Char('o')), OpenSnippetSource),
+                (plain(Char('f')), CycleFormat),
+                (plain(Char('t')), Tag),
+                (plain(Char(' ')), ToggleMark),
+                (plain(Char('/')), Filter),
+                (plain(Char('g')), GroupByProject),
+                (plain(Char('a')), Archive),
+                (plain(Char('A')), ToggleArchiveView),
+                (plain(Char('w')), Export),
+                (plain(Char('I')), Import),
+                (plain(Char('u')), Undo),
+                (ctrl(Char('r')), Redo),
+            ],
+        );
+        map.bind(C::Library, vertical.clone());
+        map.bind(C::Library, horizontal.clone());
+
+        map.bind(C::Picker, [(plain(Esc), Cancel), (plain(Enter), Confirm)]);
+        map.bind(C::Picker, vertical.clone());
+        map.bind(
+            C::DiffPreview,
+            [
+                (plain(Esc), Cancel),
+                (plain(Enter), Confirm),
+                (plain(Char('y')), Confirm),
+                (plain(Char('n')), Reject),
+            ],
+        );
+        for context in [C::FileFinder, C::GlobalSearch] {
+            map.bind(
+                context,
+                [
+                    (plain(Esc), Cancel),
+                    (plain(Enter), Confirm),
+                    (plain(Down), MoveDown),
+                    (plain(Up), MoveUp),
+                    (ctrl(Char('n')), MoveDown),
+                    (ctrl(Char('p')), MoveUp),
+                ],
+            );
+        }
+        map.bind(C::DiffPreview, vertical.clone());
+        map.bind(C::DiffPreview, paging.clone());
+        map.bind(
+            C::Budget,
+            [
+                (plain(Esc), Cancel),
+                (plain(Char('q')), Cancel),
+                (plain(Char('B')), Cancel),
+            ],
+        );
+        map.bind(C::Budget, vertical.clone());
+        map.bind(C::Budget, paging.clone());
+        map.bind(
+            C::History,
+            [
+                (plain(Esc), Cancel),
+                (plain(Char('q')), Cancel),
+                (plain(Char('H')), Cancel),
+            ],
+        );
+        map.bind(C::History, vertical.clone());
+        map.bind(C::History, paging.clone());
+        map.bind(
+            C::Messages,
+            [
+                (plain(Esc), Cancel),
+                (plain(Char('q')), Cancel),
+                (ctrl(Char('g')), Cancel),
+            ],
+        );
+        map.bind(C::Messages, vertical.clone());
+        map.bind(C::Messages, paging.clone());
+        map.bind(
+            C::Session,
+            [
+                (plain(Esc), Cancel),
+                (plain(Char('q')), Cancel),
+                (plain(Char('Y')), CopyFile),
+                (plain(Char('w')), Export),
+            ],
+        );
+        map.bind(C::Session, vertical.clone());
+        map.bind(C::Session, paging.clone());
+        map.bind(
+            C::Trim,
+            [
+                (plain(Esc), Cancel),
+                (plain(Char(' ')), ToggleMark),
+                (plain(Char('d')), Delete),
+                (plain(Char('a')), Archive),
+            ],
+        );
+        map.bind(C::Trim, vertical.clone());
+        map.bind(
+            C::Merge,
+            [
+                (plain(Esc), Cancel),
+                (plain(Char('a')), TakeSection(MergeChoice::A)),
+                (plain(Char('b')), TakeSection(MergeChoice::B)),
+                (plain(Char('B')), TakeSection(MergeChoice::Both)),
+                (plain(Char('x')), TakeSection(MergeChoice::Drop)),
+                (plain(Char('w')), Save),
+                (plain(Char('n')), NewFile),
+            ],
+        );
+        map.bind(C::Merge, vertical.clone());
+        map
+    }
+}
+
+/// Every action a key can be bound to in the config, by name.
+fn named_actions() -> Vec<(&'static str, Action)> {
+    use Action::*;
+    vec![
+        ("quit", Quit),
+        ("open_file_finder", OpenFileFinder),
+        ("open_recent_files", OpenRecentFiles),
+        ("jump_back", JumpBack),
+        ("jump_forward", JumpForward),
+        ("open_messages", OpenMessages),
+        ("open_global_search", OpenGlobalSearch),
+        ("open_budget", OpenBudget),
+        ("simulate_session", SimulateSession),
+        ("files_screen", SwitchScreen(Screen::Files)),
+        ("settings_screen", SwitchScreen(Screen::Settings)),
+        ("compose_screen", SwitchScreen(Screen::Compose)),
+        ("library_screen", SwitchScreen(Screen::Library)),
+        ("toggle_theme", ToggleTheme),
+        ("back", Back),
+        ("toggle_pane", TogglePane),
+        ("move_up", MoveUp),
+        ("move_down", MoveDown),
+        ("move_left", MoveLeft),
+        ("move_right", MoveRight),
+        ("page_up", PageUp),
+        ("page_down", PageDown),
+        ("move_top", MoveTop),
+        ("move_bottom", MoveBottom),
+        ("toggle_fold", ToggleFold),
+        ("confirm", Confirm),
+        ("reject", Reject),
+        ("cancel", Cancel),
+        ("start_selection", StartSelection),
+        ("save_selection", SaveSelection),
+        ("copy_selection", CopySelection),
+        ("move_selection", MoveSelection),
+        ("extract_section", ExtractSection),
+        ("toggle_import", ToggleImport),
+        ("toggle_all_imports", ToggleAllImports),
+        ("next_finding", NextFinding),
+        ("run_tool", RunTool),
+        ("repeat_insert", RepeatInsert),
+        ("copy_file", CopyFile),
+        ("edit", Edit),
+        ("open_in_editor", OpenInEditor),
+        ("save", Save),
+        ("new_file", NewFile),
+        ("open_backups", OpenBackups),
+        ("migrate_local", MigrateLocal),
+        ("load_full_content", LoadFullContent),
+        ("toggle_repo_groups", ToggleRepoGroups),
+        ("rescan", Rescan),
+        ("toggle_merged_view", ToggleMergedView),
+        ("toggle_outline", ToggleOutline),
+        ("toggle_split_preview", ToggleSplitPreview),
+        ("toggle_comments", ToggleComments),
+        ("toggle_mark", ToggleMark),
+        ("export", Export),
+        ("import", Import),
+        ("undo", Undo),
+        ("redo", Redo),
+        ("delete", Delete),
+        ("rename", Rename),
+        ("fix_name", FixName),
+        ("export_memory", ExportMemory),
+        ("append_snippet", AppendSnippet),
+        ("open_snippet_source", OpenSnippetSource),
+        ("insert_snippet", InsertSnippet),
+        ("cycle_format", CycleFormat),
+        ("tag", Tag),
+        ("filter", Filter),
+        ("group_by_project", GroupByProject),
+        ("toggle_archive_view", ToggleArchiveView),
+        ("snapshot", Snapshot),
+        ("trim", Trim),
+        ("history", History),
+        ("quick_actions", QuickActions),
+        ("toggle_wrap", ToggleWrap),
+        ("toggle_load_order", ToggleLoadOrder),
+        ("archive", Archive),
+        ("merge", Merge),
+        ("take_a", TakeSection(MergeChoice::A)),
+        ("take_b", TakeSection(MergeChoice::B)),
+        ("take_both", TakeSection(MergeChoice::Both)),
+        ("drop_section", TakeSection(MergeChoice::Drop)),
+    ]
+}
+
+impl KeyMap {
+    /// The default bindings with the `[keymap]` overrides of the config
+    /// applied, and a message for each override that could not be.
+    pub fn with_overrides(
+        overrides: &BTreeMap<String, BTreeMap<String, String>>,
+    ) -> (Self, Vec<String>) {
+        let mut map = Self::default();
+        let actions = named_actions();
+        let mut errors = Vec::new();
+        for (context_name, bindings) in overrides {
+            let Some(context) = KeyContext::from_name(context_name) else {
+                errors.push(format!("unknown key context \"{context_name}\""));
+                continue;
+            };
+            for (keys_text, action_name) in bindings {
+                let keys: Option<Vec<KeyBinding>> = keys_text
+                    .split_whitespace()
+                    .map(KeyBinding::parse)
+                    .collect();
+                let Some(keys) = keys.filter(|keys| !keys.is_empty()) else {
+                    errors.push(format!("{context_name}: cannot read key \"{keys_text}\""));
+                    continue;
+                };
+                if action_name == "none" {
+                    map.bindings
+                        .retain(|b| !(b.context == context && b.keys == keys));
+                    continue;
+                }
+                match actions.iter().find(|(name, _)| name == action_name) {
+                    Some((_, action)) => map.bind_sequences(context, [(keys, action.clone())]),
+                    None => {
+                        errors.push(format!("{context_name}: unknown action \"{action_name}\""))
+                    }
+                }
+            }
+        }
+        (map, errors)
+    }
+
+    /// Adds bindings for `context`. Later bindings of the same key win.
+    pub fn bind(
+        &mut self,
+        context: KeyContext,
+        bindings: impl IntoIterator<Item = (KeyBinding, Action)>,
+    ) {
+        self.bind_sequences(
+            context,
+            bindings
+                .into_iter()
+                .map(|(key, action)| (vec![key], action)),
+        );
+    }
+
+    /// Adds key sequences for `context`, such as `g g`. Later bindings of
+    /// the same sequence win.
+    pub fn bind_sequences(
+        &mut self,
+        context: KeyContext,
+        bindings: impl IntoIterator<Item = (Vec<KeyBinding>, Action)>,
+    ) {
+        for (keys, action) in bindings {
+            self.bindings
+                .retain(|b| !(b.context == context && b.keys == keys));
+            self.bindings.push(Binding {
+                context,
+                keys,
+                action,
+            });
+        }
+    }
+
+    /// Returns the action bound to exactly `keys` in `context` alone.
+    pub fn binding(&self, context: KeyContext, keys: &[KeyEvent]) -> Option<&Action> {
+        self.bindings
+            .iter()
+            .find(|b| b.context == context && b.keys.len() == keys.len() && b.starts_with(keys))
+            .map(|b| &b.action)
+    }
+
+    /// The contexts consulted in `context`, in order: bindings for every
+    /// context, then Normal-mode globals (when `normal_mode`), then the
+    /// context's own.
+    fn lookup_order(context: KeyContext, normal_mode: bool) -> Vec<KeyContext> {
+        let mut order = vec![KeyContext::Any];
+        if normal_mode {
+            order.push(KeyContext::Global);
+        }
+        order.push(context);
+        order
+    }
+
+    /// Resolves `key` pressed on its own in `context`. Keys that only start
+    /// a sequence resolve to nothing; see [`resolve_keys`](Self::resolve_keys).
+    pub fn resolve(&self, context: KeyContext, normal_mode: bool, key: KeyEvent) -> Option<Action> {
+        self.complete(context, normal_mode, &[key])
+    }
+
+    /// Resolves the keys pressed so far in `context`. A sequence that could
+    /// still grow into a longer binding is pending, even if it is bound on
+    /// its own; it acts once the next key or the timeout settles it.
+    pub fn resolve_keys(
+        &self,
+        context: KeyContext,
+        normal_mode: bool,
+        keys: &[KeyEvent],
+    ) -> KeyResolution {
+        let order = Self::lookup_order(context, normal_mode);
+        let longer = self.bindings.iter().any(|b| {
+            order.contains(&b.context) && b.keys.len() > keys.len() && b.starts_with(keys)
+        });
+        if longer {
+            return KeyResolution::Pending;
+        }
+        match self.complete(context, normal_mode, keys) {
+            Some(action) => KeyResolution::Action(action),
+            None => KeyResolution::Unbound,
+        }
+    }
+
+    /// The action bound to exactly `keys`, ignoring longer sequences. A
+    /// single unbound key in a text context becomes [`Action::Input`].
+    pub fn complete(
+        &self,
+        context: KeyContext,
+        normal_mode: bool,
+        keys: &[KeyEvent],
+    ) -> Option<Action> {
+        let bound = Self::lookup_order(context, normal_mode)
+            .into_iter()
+            .find_map(|c| self.binding(c, keys));
+        match (bound, keys) {
+            (Some(action), _) => Some(action.clone()),
+            (None, [key]) if context.takes_text() => Some(Action::Input(*key)),
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::KeyEventKind;
+    use ratatui::crossterm::event::KeyEventState;
+
+    use super::*;
+
+    fn key(code: KeyCode, modifiers: KeyModifiers) -> KeyEvent {
+        KeyEvent {
+            code,
+            modifiers,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::empty(),
+        }
+    }
+
+    #[test]
+    fn same_key_maps_per_context() {
+        let map = KeyMap::default();
+        let m = key(KeyCode::Char('m'), KeyModifiers::NONE);
+
+        assert_eq!(
+            map.resolve(KeyContext::Settings, true, m),
+            Some(Action::ToggleMergedView)
+        );
+        assert_eq!(
+            map.resolve(KeyContext::Library, true, m),
+            Some(Action::ExportMemory)
+        );
+        assert_eq!(map.resolve(KeyContext::FilesContent, true, m), None);
+    }
+
+    #[test]
+    fn globals_apply_only_in_normal_mode() {
+        let map = KeyMap::default();
+        let two = key(KeyCode::Char('2'), KeyModifiers::NONE);
+
+        assert_eq!(
+            map.resolve(KeyContext::FilesTree, true, two),
+            Some(Action::SwitchScreen(Screen::Settings))
+        );
+        assert_eq!(
+            map.resolve(KeyContext::TextInput, false, two),
+            Some(Action::Input(two))
+        );
+    }
+
+    #[test]
+    fn ctrl_c_quits_everywhere() {
+        let map = KeyMap::default();
+        let ctrl_c = key(KeyCode::Char('c'), KeyModifiers::CONTROL);
+
+        assert_eq!(
+            map.resolve(KeyContext::Editor, false, ctrl_c),
+            Some(Action::Quit)
+        );
+        assert_eq!(
+            map.resolve(KeyContext::Picker, false, ctrl_c),
+            Some(Action::Quit)
+        );
+    }
+
+    #[test]
+    fn shift_is_ignored_for_characters_but_ctrl_is_not() {
+        let map = KeyMap::default();
+
+        assert_eq!(
+            map.resolve(
+                KeyContext::FilesTree,
+                true,
+                key(KeyCode::Char('T'), KeyModifiers::SHIFT)
+            ),
+            Some(Action::ToggleTheme)
+        );
+        assert_eq!(
+            map.resolve(
+                KeyContext::FilesTree,
+                true,
+                key(KeyCode::Char('j'), KeyModifiers::CONTROL)
+            ),
+            None
+        );
+    }
+
+    #[test]
+    fn unbound_keys_in_editor_are_input() {
+        let map = KeyMap::default();
+        let ctrl_s = key(KeyCode::Char('s'), KeyModifiers::CONTROL);
+        let s = key(KeyCode::Char('s'), KeyModifiers::NONE);
+
+        assert_eq!(
+            map.resolve(KeyContext::Editor, false, ctrl_s),
+            Some(Action::Save)
+        );
+        assert_eq!(
+            map.resolve(KeyContext::Editor, false, s),
+            Some(Action::Input(s))
+        );
+    }
+
+    #[test]
+    fn sequence_prefix_is_pending_until_complete() {
+        let map = KeyMap::default();
+        let g = key(KeyCode::Char('g'), KeyModifiers::NONE);
+
+        assert_eq!(
+            map.resolve_keys(KeyContext::FilesContent, true, &[g]),
+            KeyResolution::Pending
+        );
+        assert_eq!(
+            map.resolve_keys(KeyContext::FilesContent, true, &[g, g]),
+            KeyResolution::Action(Action::MoveTop)
+        );
+        assert_eq!(
+            map.resolve_keys(KeyContext::FilesTree, true, &[g]),
+            KeyResolution::Action(Action::ToggleRepoGroups)
+        );
+        assert_eq!(map.resolve(KeyContext::FilesContent, true, g), None);
+    }
+
+    #[test]
+    fn keys_display_like_the_help_bar() {
+        let ctrl_w = KeyBinding::ctrl(KeyCode::Char('w'));
+        assert_eq!(ctrl_w.to_string(), "Ctrl+W");
+        assert_eq!(KeyBinding::plain(KeyCode::Char(' ')).to_string(), "Space");
+        assert_eq!(KeyBinding::plain(KeyCode::Esc).to_string(), "Esc");
+        let shifted = key(KeyCode::Char('G'), KeyModifiers::SHIFT);
+        assert_eq!(KeyBinding::from(&shifted).to_string(), "G");
+    }
+
+    #[test]
+    fn config_keys_parse() {
+        assert_eq!(
+            KeyBinding::parse("ctrl+w"),
+            Some(KeyBinding::ctrl(KeyCode::Char('w')))
+        );
+        assert_eq!(
+            KeyBinding::parse("Ctrl-W"),
+            Some(KeyBinding::ctrl(KeyCode::Char('w')))
+        );
+        assert_eq!(
+            KeyBinding::parse("G"),
+            Some(KeyBinding::plain(KeyCode::Char('G')))
+        );
+        assert_eq!(
+            KeyBinding::parse("PgDn"),
+            Some(KeyBinding::plain(KeyCode::PageDown))
+        );
+        assert_eq!(
+            KeyBinding::parse("alt+f5"),
+            Some(KeyBinding::alt(KeyCode::F(5)))
+        );
+        assert_eq!(KeyBinding::parse("hyper+x"), None);
+        assert_eq!(KeyBinding::parse(""), None);
+    }
+
+    #[test]
+    fn overrides_rebind_unbind_and_report_mistakes() {
+        let overrides = BTreeMap::from([
+            (
+                "files_tree".to_string(),
+                BTreeMap::from([
+                    ("N".to_string(), "new_file".to_string()),
+                    ("n".to_string(), "none".to_string()),
+                    ("x".to_string(), "explode".to_string()),
+                ]),
+            ),
+            (
+                "library".to_string(),
+                BTreeMap::from([("d d".to_string(), "delete".to_string())]),
+            ),
+            ("nowhere".to_string(), BTreeMap::new()),
+        ]);
+
+        let (map, errors) = KeyMap::with_overrides(&overrides);
+
+        let press = |c| key(KeyCode::Char(c), KeyModifiers::NONE);
+        assert_eq!(
+            map.resolve(KeyContext::FilesTree, true, press('N')),
+            Some(Action::NewFile)
+        );
+        assert_eq!(map.resolve(KeyContext::FilesTree, true, press('n')), None);
+        assert_eq!(
+            map.resolve_keys(KeyContext::Library, true, &[press('d'), press('d')]),
+            KeyResolution::Action(Action::Delete)
+        );
+        assert_eq!(
+            errors,
+            [
+                "files_tree: unknown action \"explode\"",
+                "unknown key context \"nowhere\"",
+            ]
+        );
+    }
+
+    #[test]
+    fn rebinding_replaces_existing_key() {
+        let mut map = KeyMap::default();
+        map.bind(
+            KeyContext::FilesTree,
+            [(KeyBinding::plain(KeyCode::Char('q')), Action::NewFile)],
+        );
+
+        assert_eq!(
+            map.resolve(
+                KeyContext::FilesTree,
+                true,
+                key(KeyCode::Char('q'), KeyModifiers::NONE)
+            ),
+            Some(Action::NewFile)
+        );
+    }
+}
@@ -0,0 +1,270 @@
+use std::path::Path;
+use std::path::PathBuf;
+
+use ratatui::text::Line;
+use ratatui::text::Span;
+
+use super::action::Effect;
+use super::app::App;
+use super::picker::Picker;
+use super::picker::PickerAction;
+use super::picker::PickerItem;
+
+/// Most files kept in [`App::recent_files`].
+pub const MAX_RECENT: usize = 5;
+
+/// Keys listed on the landing view, in display order.
+const LANDING_KEYS: &[(&str, &str)] = &[
+    ("↑/↓", "select a file"),
+    ("Tab", "switch pane"),
+    ("Ctrl-P", "find a file"),
+    ("'", "recent files"),
+    ("/", "search all files"),
+    ("n", "new CLAUDE.md"),
+    ("4", "snippet library"),
+    ("q", "quit"),
+];
+
+impl App {
+    /// Moves `file` to the front of the recently viewed files.
+    pub(crate) fn note_recent(&mut self, file: &Path) {
+        self.recent_files.retain(|f| f != file);
+        self.recent_files.insert(0, file.to_path_buf());
+        self.recent_files.truncate(MAX_RECENT);
+    }
+
+    /// Shows recent file `index` (0-based) on the Files screen.
+    pub(crate) fn jump_to_recent(&self, index: usize) -> Vec<Effect> {
+        match self.recent_files.get(index) {
+            Some(file) => vec![Effect::ShowFile(file.clone())],
+            None => Vec::new(),
+        }
+    }
+
+    /// Lists the recently viewed files that still exist, to jump to one.
+    pub(crate) fn open_recent_files(&mut self) {
+        let (files, items): (Vec<PathBuf>, Vec<PickerItem>) = self
+            .recent_files
+            .iter()
+            .zip(1..)
+            .filter(|(file, _)| file.is_file())
+            .map(|(file, n)| {
+                let item = PickerItem::new(display_path(file), format!("Alt-{n}"));
+                (file.clone(), item)
+            })
+            .unzip();
+        if files.is_empty() {
+            self.status_message = Some("No recently viewed files.".to_string());
+            return;
+        }
+        self.open_picker(Picker::new(
+            "Recent files",
+            items,
+            PickerAction::ShowRecent { files },
+        ));
+    }
+
+    /// The content pane's landing view, shown while no file is selected:
+    /// the scanned roots with their file counts, recently viewed files with
+    /// the keys that jump to them, and the main keys.
+    pub(crate) fn landing_lines(&self) -> Vec<Line<'static>> {
+        let heading = self.theme.active_tab;
+        let key = self.theme.help_key;
+        let dim = self.theme.help_desc;
+
+        let total: usize = self.roots.iter().map(|r| r.file_count()).sum();
+        let mut lines = vec![
+            Line::styled(
+                format!(
+                    " {total} CLAUDE.md {} in {} {}",
+                    if total == 1 { "file" } else { "files" },
+                    self.roots.len(),
+                    if self.roots.len() == 1 {
+                        "root"
+                    } else {
+                        "roots"
+                    }
+                ),
+                heading,
+            ),
+            Line::from(""),
+        ];
+
+        lines.push(Line::styled(" Roots", heading));
+        for root in &self.roots {
+            lines.push(Line::from(vec![
+                Span::raw(format!("   {}", root.path.display())),
+                Span::styled(format!("  ({})", root.file_count()), dim),
+            ]));
+        }
+        if self.roots.is_empty() {
+            lines.push(Line::styled("   none", dim));
+        }
+
+        if !self.recent_files.is_empty() {
+            lines.push(Line::from(""));
+            lines.push(Line::styled(" Recent", heading));
+            for (i, file) in self.recent_files.iter().enumerate() {
+                lines.push(Line::from(vec![
+                    Span::raw("   "),
+                    Span::styled(format!(" Alt-{} ", i + 1), key),
+                    Span::raw(format!(" {}", display_path(file))),
+                ]));
+            }
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::styled(" Keys", heading));
+        for (k, desc) in LANDING_KEYS {
+            lines.push(Line::from(vec![
+                Span::raw("   "),
+                Span::styled(format!(" {k} "), key),
+                Span::styled(format!(" {desc}"), dim),
+            ]));
+        }
+        lines
+    }
+}
+
+/// `file` with the home directory shortened to `~`.
+pub(crate) fn display_path(file: &Path) -> String {
+    let home = std::env::var_os("HOME").map(PathBuf::from);
+    match home
+        .as_deref()
+        .and_then(|home| file.strip_prefix(home).ok())
+    {
+        Some(rest) => format!("~/{}", rest.display()),
+        None => file.display().to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::KeyCode;
+    use ratatui::crossterm::event::KeyEvent;
+    use ratatui::crossterm::event::KeyEventKind;
+    use ratatui::crossterm::event::KeyEventState;
+    use ratatui::crossterm::event::KeyModifiers;
+    use tempfile::TempDir;
+
+    use super::MAX_RECENT;
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::Screen;
+    use crate::tui::app::test_helpers::key_event;
+    use crate::tui::app::test_helpers::sample_roots;
+
+    fn alt(c: char) -> KeyEvent {
+        KeyEvent {
+            code: KeyCode::Char(c),
+            modifiers: KeyModifiers::ALT,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::empty(),
+        }
+    }
+
+    fn two_file_app(tmp: &TempDir) -> App {
+        let first = tmp.path().join("CLAUDE.md");
+        let second = tmp.path().join("sub/CLAUDE.md");
+        fs::create_dir_all(tmp.path().join("sub")).unwrap();
+        fs::write(&first, "first").unwrap();
+        fs::write(&second, "second").unwrap();
+        App::new(
+            vec![SourceRoot {
+                path: tmp.path().to_path_buf(),
+                files: vec![DiscoveredFile::new(first), DiscoveredFile::new(second)],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        )
+    }
+
+    #[test]
+    fn recent_files_are_most_recent_first_and_capped() {
+        let mut app = App::new(vec![], &Config::default());
+        for i in 0..MAX_RECENT + 2 {
+            app.note_recent(&std::path::PathBuf::from(format!("/f{i}")));
+        }
+        app.note_recent(&std::path::PathBuf::from("/f3"));
+
+        assert_eq!(app.recent_files.len(), MAX_RECENT);
+        assert_eq!(app.recent_files[0], std::path::PathBuf::from("/f3"));
+        assert_eq!(app.recent_files[1], std::path::PathBuf::from("/f6"));
+    }
+
+    #[test]
+    fn landing_view_lists_roots_recent_files_and_keys() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = two_file_app(&tmp);
+        app.tree_state
+            .select(vec![tmp.path().display().to_string()]);
+        app.load_selected_content();
+        let mut terminal = Terminal::new(TestBackend::new(120, 30)).unwrap();
+
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+
+        let text: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|c| c.symbol())
+            .collect();
+        assert!(text.contains("2 CLAUDE.md files in 1 root"));
+        assert!(text.contains("Alt-1"));
+        assert!(text.contains("Ctrl-P"));
+        assert!(!text.contains("Select a file"));
+    }
+
+    #[test]
+    fn alt_digit_jumps_to_recent_file() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = two_file_app(&tmp);
+        app.show_file(&tmp.path().join("sub/CLAUDE.md"));
+        app.screen = Screen::Library;
+
+        app.handle_key_event(alt('2'));
+
+        assert_eq!(app.screen, Screen::Files);
+        assert_eq!(app.selected_file(), Some(tmp.path().join("CLAUDE.md")));
+        assert_eq!(app.content.text.as_deref(), Some("first"));
+    }
+
+    #[test]
+    fn quote_picks_a_recent_file_to_show() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = two_file_app(&tmp);
+        app.show_file(&tmp.path().join("CLAUDE.md"));
+        app.show_file(&tmp.path().join("sub/CLAUDE.md"));
+        fs::remove_file(tmp.path().join("sub/CLAUDE.md")).unwrap();
+
+        app.handle_key_event(key_event(KeyCode::Char('\'')));
+        assert_eq!(app.mode, Mode::Picker);
+        let picker = app.picker.as_ref().unwrap();
+        assert_eq!(picker.items.len(), 1);
+        assert_eq!(picker.items[0].detail, "Alt-2");
+
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.selected_file(), Some(tmp.path().join("CLAUDE.md")));
+        assert_eq!(app.content.text.as_deref(), Some("first"));
+    }
+
+    #[test]
+    fn alt_digit_without_recent_file_does_nothing() {
+        let mut app = App::new(sample_roots(), &Config::default());
+        let before = app.selected_file();
+
+        app.handle_key_event(alt('5'));
+
+        assert_eq!(app.selected_file(), before);
+    }
+}
@@ -1,25 +1,54 @@
 use std::cell::Cell;
+use std::collections::BTreeMap;
+use std::collections::BTreeSet;
 use std::path::Path;
 use std::path::PathBuf;
 
 use ratatui::Frame;
-use ratatui::crossterm::event::KeyCode;
-use ratatui::crossterm::event::KeyEvent;
 use ratatui::layout::Constraint;
 use ratatui::layout::Direction;
 use ratatui::layout::Layout;
 use ratatui::style::Style;
 use ratatui::text::Line;
+use ratatui::text::Span;
 use ratatui::text::Text;
 use ratatui::widgets::Block;
 use ratatui::widgets::Borders;
 use ratatui::widgets::Paragraph;
 use tui_textarea::TextArea;
 
+use super::action::Action;
+use super::action::Effect;
 use super::app::App;
 use super::app::EditState;
 use super::app::Mode;
 use super::app::Screen;
+use super::diff_preview::DiffPreview;
+use super::diff_preview::PREVIEW_CONTEXT_LINES;
+use super::diff_preview::PreviewAction;
+use super::picker::Picker;
+use super::picker::PickerAction;
+use super::picker::PickerItem;
+use super::picker::TitleChange;
+use super::theme::Theme;
+use crate::backup::format_timestamp;
+use crate::diff::collapse_context;
+use crate::diff::diff_lines;
+use crate::fsutil::expand_home;
+use crate::library::LibraryScope;
+use crate::library::Snippet;
+use crate::library::SnippetFormat;
+use crate::library::SnippetLibrary;
+use crate::library::export_bundle;
+use crate::library::grep_snippets;
+use crate::library::import_bundle;
+use crate::library::load_library_set;
+use crate::library::merge_libraries;
+use crate::library::project_library_path;
+use crate::library::read_bundle;
+use crate::library::save_library_set;
+use crate::library::serialize_library;
+use crate::tokens::format_tokens;
 
 impl App {
     /// Switches to the Library screen, loading the library from disk if needed.
@@ -35,10 +64,14 @@ impl App {
     /// Switches to the Library screen using a specific library path. Extracted
     /// for testability.
     pub fn enter_library_screen_from(&mut self, path: &Path) {
-        match crate::library::load_library(path) {
+        self.project_library = self.current_project_library();
+        match self.load_library_at(path) {
             Ok(lib) => {
                 self.library = Some(lib);
                 self.library_selected = 0;
+                self.library_filter.clear();
+                self.library_archive = false;
+                self.library_marked.clear();
                 self.screen = Screen::Library;
                 self.mode = Mode::Normal;
             }
@@ -77,22 +110,100 @@ impl App {
             .direction(Direction::Horizontal)
             .constraints([Constraint::Percentage(40), Constraint::Percentage(60)])
             .split(area);
+        self.pane_areas.library_list = panes[0];
 
-        // Left pane: snippet list
-        let list_title = format!("Library ({} snippets)", lib.snippets.len());
-        let list_lines: Vec<Line> = lib
+        // Left pane: snippet list, narrowed by the filter
+        let visible = self.visible_snippets();
+        let rows = self.library_rows();
+        let view = if self.library_archive {
+            "Archive"
+        } else {
+            "Library"
+        };
+        let in_view = lib
+            .snippets
+            .iter()
+            .filter(|s| s.archived == self.library_archive)
+            .count();
+        let mut list_title = if self.library_filter.is_empty() {
+            format!("{view} ({in_view} snippets)")
+        } else {
+            format!(
+                "{view} ({} of {in_view} snippets matching \"{}\")",
+                visible.len(),
+                self.library_filter
+            )
+        };
+        if !self.library_marked.is_empty() {
+            list_title.push_str(&format!(" [{} marked]", self.library_marked.len()));
+        }
+        let selected_row = self.library_row_position(&rows);
+        // Which library a snippet is in only matters once there are two.
+        let show_scope = lib
             .snippets
+            .iter()
+            .any(|snippet| snippet.scope == LibraryScope::Project);
+        let mut list_lines: Vec<Line> = rows
             .iter()
             .enumerate()
-            .map(|(i, snippet)| {
-                let style = if i == self.library_selected {
-                    self.theme.highlight
-                } else {
-                    Style::default()
+            .map(|(row, entry)| {
+                let line = match entry {
+                    LibraryRow::Project {
+                        project,
+                        count,
+                        collapsed,
+                    } => Line::styled(
+                        format!(
+                            " {} {project} ({count})",
+                            if *collapsed { "▸" } else { "▾" }
+                        ),
+                        self.theme.active_tab,
+                    ),
+                    LibraryRow::Snippet(i) => {
+                        let Some(snippet) = lib.snippets.get(*i) else {
+                            return Line::default();
+                        };
+                        let indent = if self.library_grouped { "    " } else { "  " };
+                        let checkbox = match (
+                            self.library_marked.is_empty(),
+                            self.library_marked.contains(i),
+                        ) {
+                            (true, _) => "",
+                            (false, true) => "[x] ",
+                            (false, false) => "[ ] ",
+                        };
+                        let mut spans = vec![Span::raw(format!("{indent}{checkbox}"))];
+                        if show_scope {
+                            spans.push(Span::styled(
+                                format!("{:<8}", snippet.scope.name()),
+                                self.theme.help_desc,
+                            ));
+                        }
+                        spans.push(Span::raw(snippet.title.clone()));
+                        if !snippet.tags.is_empty() {
+                            spans.push(Span::styled(
+                                format!(" #{}", snippet.tags.join(" #")),
+                                self.theme.help_desc,
+                            ));
+                        }
+                        Line::from(spans)
+                    }
                 };
-                Line::from(format!("  {}", snippet.title)).style(style)
+                if Some(row) == selected_row {
+                    line.style(self.theme.highlight)
+                } else {
+                    line
+                }
             })
             .collect();
+        if visible.is_empty() {
+            let message = match (self.library_filter.is_empty(), self.library_archive) {
+                (false, _) => "  No snippets match",
+                (true, true) => "  No archived snippets",
+                (true, false) => "  Every snippet is archived; press A to see them",
+            };
+            list_lines.push(Line::styled(message, self.theme.help_desc));
+        }
         let list_widget = Paragraph::new(Text::from(list_lines)).block(
             Block::default()
                 .borders(Borders::ALL)
@@ -101,18 +212,21 @@ impl App {
         );
         frame.render_widget(list_widget, panes[0]);
 
-        // Right pane: snippet content
-        let preview_content = lib
-            .snippets
-            .get(self.library_selected)
-            .map(|s| s.content.as_str())
-            .unwrap_or("");
-        let preview_title = lib
-            .snippets
-            .get(self.library_selected)
-            .map(|s| s.title.as_str())
-            .unwrap_or("Content");
-        let preview_widget = Paragraph::new(preview_content).block(
+        // Right pane: snippet content, highlighted for its format
+        let selected = self
+            .selected_snippet_visible()
+            .then(|| lib.snippets.get(self.library_selected))
+            .flatten();
+        let preview_lines = selected.map_or_else(Vec::new, |s| {
+            let mut lines = self.snippet_header(s, panes[1].width.saturating_sub(2));
+            lines.extend(highlight_snippet(&s.content, s.format(), &self.theme));
+            lines
+        });
+        let preview_title = selected.map_or_else(
+            || "Content".to_string(),
+            |s| format!("{} [{}]", s.title, s.format().name()),
+        );
+        let preview_widget = Paragraph::new(Text::from(preview_lines)).block(
             Block::default()
                 .borders(Borders::ALL)
                 .border_style(border_style)
@@ -121,34 +235,110 @@ impl App {
         frame.render_widget(preview_widget, panes[1]);
     }
 
-    /// Handles Normal-mode keys on the Library screen.
-    pub(crate) fn handle_library_key(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Esc => {
-                self.screen = Screen::Files;
+    /// Applies an action in Normal mode on the Library screen.
+    pub(crate) fn update_library(&mut self, action: Action) -> Vec<Effect> {
+        // On a project header, Enter and → fold or unfold the project.
+        if let Some(project) = &self.library_project
+            && matches!(action, Action::MoveRight | Action::InsertSnippet)
+        {
+            if !self.library_collapsed.remove(project) {
+                self.library_collapsed.insert(project.clone());
+            }
+            return Vec::new();
+        }
+        let rows = self.library_rows();
+        let position = self.library_row_position(&rows);
+        // With snippets marked, these act on the marked ones instead.
+        let on_marked = !self.library_marked.is_empty()
+            && matches!(action, Action::Delete | Action::Archive | Action::Tag);
+        let acts_on_selection = !on_marked
+            && matches!(
+                action,
+                Action::Edit
+                    | Action::Delete
+                    | Action::Archive
+                    | Action::ExportMemory
+                    | Action::InsertSnippet
+                    | Action::AppendSnippet
+                    | Action::OpenSnippetSource
+                    | Action::CycleFormat
+                    | Action::Rename
+                    | Action::Tag
+                    | Action::ToggleMark
+            );
+        if acts_on_selection && !self.selected_snippet_visible() {
+            self.status_message = Some(if self.library_project.is_some() {
+                "Select a snippet under the project first.".to_string()
+            } else {
+                "No snippet matches the filter.".to_string()
+            });
+            return Vec::new();
+        }
+        match action {
+            Action::Back if !self.library_marked.is_empty() => self.library_marked.clear(),
+            Action::Back if !self.library_filter.is_empty() => {
+                self.set_library_filter(String::new());
+            }
+            Action::Back if self.library_archive => self.set_library_archive(false),
+            Action::Back => self.screen = Screen::Files,
+            Action::ToggleArchiveView => self.set_library_archive(!self.library_archive),
+            Action::Export => {
+                self.text_input.clear();
+                self.mode = Mode::BundleExportPath;
+            }
+            Action::Import => {
+                self.text_input.clear();
+                self.mode = Mode::BundleImportPath;
             }
-            KeyCode::Char('q') => {
-                self.exit = true;
+            Action::Filter => {
+                let filter = self.library_filter.clone();
+                self.text_input.set(&filter);
+                self.mode = Mode::LibraryFilter;
             }
-            KeyCode::Down | KeyCode::Char('j') => {
-                let max = self
-                    .library
-                    .as_ref()
-                    .map_or(0, |lib| lib.snippets.len().saturating_sub(1));
-                if self.library_selected < max {
-                    self.library_selected += 1;
+            Action::GroupByProject => {
+                self.library_grouped = !self.library_grouped;
+                self.library_project = None;
+                self.keep_library_selection_visible();
+            }
+            Action::MoveDown => {
+                if let Some(next) = position.and_then(|p| rows.get(p + 1)) {
+                    self.select_library_row(next);
+                }
+            }
+            Action::MoveUp => {
+                if let Some(previous) = position
+                    .and_then(|p| p.checked_sub(1))
+                    .and_then(|p| rows.get(p))
+                {
+                    self.select_library_row(previous);
                 }
             }
-            KeyCode::Up | KeyCode::Char('k') => {
-                self.library_selected = self.library_selected.saturating_sub(1);
+            Action::MoveLeft if self.library_grouped => {
+                if let Some(project) = self.selected_library_project() {
+                    self.library_collapsed.insert(project.clone());
+                    self.library_project = Some(project);
+                }
             }
-            KeyCode::Char('e') => {
-                self.enter_snippet_edit();
+            Action::Undo => return vec![Effect::UndoLibraryChange],
+            Action::Redo => return vec![Effect::RedoLibraryChange],
+            Action::Edit => return vec![Effect::EditSnippet],
+            Action::Delete => return vec![Effect::RemoveSnippet],
+            Action::Archive => return vec![Effect::ToggleSnippetArchived],
+            Action::ExportMemory => return vec![Effect::ExportMemory],
+            Action::InsertSnippet => return vec![Effect::InsertSnippet],
+            Action::AppendSnippet => return vec![Effect::AppendSnippet],
+            Action::OpenSnippetSource => return self.open_snippet_source(),
+            Action::CycleFormat => return vec![Effect::CycleSnippetFormat],
+            Action::ToggleMark => {
+                if !self.library_marked.remove(&self.library_selected) {
+                    self.library_marked.insert(self.library_selected);
+                }
             }
-            KeyCode::Char('d') => {
-                self.delete_library_snippet();
+            Action::Tag => {
+                self.text_input.clear();
+                self.mode = Mode::TagInput;
             }
-            KeyCode::Char('r') => {
+            Action::Rename => {
                 if let Some(lib) = &self.library
                     && let Some(snippet) = lib.snippets.get(self.library_selected)
                 {
@@ -158,25 +348,10 @@ impl App {
             }
             _ => {}
         }
+        Vec::new()
     }
 
-    /// Handles RenameInput-mode keys on the Library screen.
-    pub(crate) fn handle_library_rename_key(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Esc => {
-                self.text_input.clear();
-                self.mode = Mode::Normal;
-            }
-            KeyCode::Enter => {
-                self.rename_library_snippet();
-            }
-            _ => {
-                self.text_input.handle_edit_key(key_event.code);
-            }
-        }
-    }
-
-    fn rename_library_snippet(&mut self) {
+    pub(crate) fn rename_library_snippet(&mut self) {
         match crate::library::library_path() {
             Some(path) => self.rename_library_snippet_from(&path),
             None => {
@@ -195,62 +370,302 @@ impl App {
             return;
         }
 
-        match crate::library::rename_snippet(self.library_selected, &new_title, path) {
-            Ok(()) => {
-                if let Ok(lib) = crate::library::load_library(path) {
-                    self.library = Some(lib);
-                }
-                self.compose_state = None;
-                self.status_message = Some("Snippet renamed.".to_string());
+        self.text_input.clear();
+        self.mode = Mode::Normal;
+        let index = self.library_selected;
+        if let Some(lib) = &self.library
+            && lib.has_title(&new_title, Some(index))
+        {
+            let suggested = lib.unique_title(&new_title, Some(index));
+            self.open_duplicate_title_picker(path, suggested, TitleChange::Rename(index));
+            return;
+        }
+        self.apply_snippet_rename(path, index, new_title);
+    }
+
+    /// Retitles snippet `index` of the loaded library and saves it to `path`.
+    pub(crate) fn apply_snippet_rename(&mut self, path: &Path, index: usize, title: String) {
+        let mut updated = self.library.clone().unwrap_or_default();
+        let Some(snippet) = updated.snippets.get_mut(index) else {
+            return;
+        };
+        snippet.title = title;
+        self.write_library_change(path, updated, "Snippet renamed.");
+    }
+
+    /// Asks whether to go ahead with `change` under the free title
+    /// `suggested` instead of the taken one, or to drop it.
+    pub(crate) fn open_duplicate_title_picker(
+        &mut self,
+        path: &Path,
+        suggested: String,
+        change: TitleChange,
+    ) {
+        self.open_picker(Picker::new(
+            "A snippet with that title already exists",
+            vec![
+                PickerItem::new(format!("Use \"{suggested}\""), "titles must be unique"),
+                PickerItem::new("Cancel", "change nothing"),
+            ],
+            PickerAction::DuplicateTitle {
+                path: path.to_path_buf(),
+                title: suggested,
+                change,
+            },
+        ));
+    }
+
+    pub(crate) fn start_snippet_removal(&mut self) {
+        match crate::library::library_path() {
+            Some(path) => self.start_snippet_removal_from(&path),
+            None => {
+                self.status_message = Some("Cannot determine library path.".to_string());
             }
-            Err(err) => {
-                self.status_message = Some(format!("Rename failed: {err}"));
+        }
+    }
+
+    /// Asks whether to archive the selected snippet, the default, or delete
+    /// it for good. In the Archive view the default is to restore it. With
+    /// snippets marked, asks the same for all of them.
+    pub fn start_snippet_removal_from(&mut self, path: &Path) {
+        if !self.library_marked.is_empty() {
+            let count = self.library_marked.len();
+            let keep = if self.library_archive {
+                PickerItem::new("Restore", "move them back to the Library list")
+            } else {
+                PickerItem::new(
+                    "Archive",
+                    "hide them; A lists and restores archived snippets",
+                )
+            };
+            self.open_picker(Picker::new(
+                format!("Remove {count} marked {}", snippets_noun(count)),
+                vec![
+                    keep,
+                    PickerItem::new("Delete permanently", "remove them from the library file"),
+                ],
+                PickerAction::RemoveMarkedSnippets {
+                    path: path.to_path_buf(),
+                },
+            ));
+            return;
+        }
+        let Some(snippet) = self
+            .library
+            .as_ref()
+            .and_then(|lib| lib.snippets.get(self.library_selected))
+        else {
+            return;
+        };
+        let keep = if snippet.archived {
+            PickerItem::new("Restore", "move it back to the Library list")
+        } else {
+            PickerItem::new("Archive", "hide it; A lists and restores archived snippets")
+        };
+        self.open_picker(Picker::new(
+            format!("Remove \"{}\"", snippet.title),
+            vec![
+                keep,
+                PickerItem::new("Delete permanently", "remove it from the library file"),
+            ],
+            PickerAction::RemoveSnippet {
+                path: path.to_path_buf(),
+            },
+        ));
+    }
+
+    pub(crate) fn toggle_snippet_archived(&mut self) {
+        match crate::library::library_path() {
+            Some(path) => self.toggle_snippet_archived_from(&path),
+            None => {
+                self.status_message = Some("Cannot determine library path.".to_string());
             }
         }
+    }
 
-        self.text_input.clear();
-        self.mode = Mode::Normal;
+    /// Archives the selected snippet, or restores it if it is archived.
+    /// Marked snippets are archived, or restored in the Archive view, all
+    /// in one write.
+    pub fn toggle_snippet_archived_from(&mut self, path: &Path) {
+        let mut updated = self.library.clone().unwrap_or_default();
+        if !self.library_marked.is_empty() {
+            let archive = !self.library_archive;
+            let count = updated.set_archived(&self.library_marked, archive);
+            let noun = snippets_noun(count);
+            let success = if archive {
+                format!("{count} {noun} archived.")
+            } else {
+                format!("{count} {noun} restored.")
+            };
+            self.write_library_change(path, updated, &success);
+            return;
+        }
+        let Some(snippet) = updated.snippets.get_mut(self.library_selected) else {
+            return;
+        };
+        snippet.archived = !snippet.archived;
+        let success = if snippet.archived {
+            "Snippet archived. Press A to see the archive."
+        } else {
+            "Snippet restored."
+        };
+        self.write_library_change(path, updated, success);
+    }
+
+    /// Writes the loaded library, or only its marked snippets, to the
+    /// bundle path typed into the prompt.
+    pub(crate) fn export_library_bundle(&mut self) {
+        let Some(file) = self.bundle_path_from_input() else {
+            return;
+        };
+        let lib = self.library.clone().unwrap_or_default();
+        let lib = if self.library_marked.is_empty() {
+            lib
+        } else {
+            lib.subset(&self.library_marked)
+        };
+        self.status_message = Some(match export_bundle(&lib, &file) {
+            Ok(count) => {
+                self.library_marked.clear();
+                format!(
+                    "Exported {count} {} to {}.",
+                    snippets_noun(count),
+                    file.display()
+                )
+            }
+            Err(err) => format!("Export failed: {err:#}"),
+        });
     }
 
-    fn delete_library_snippet(&mut self) {
+    pub(crate) fn import_library_bundle(&mut self) {
         match crate::library::library_path() {
-            Some(path) => self.delete_library_snippet_from(&path),
+            Some(path) => self.import_library_bundle_to(&path),
             None => {
                 self.status_message = Some("Cannot determine library path.".to_string());
+                self.text_input.clear();
+                self.mode = Mode::Normal;
+            }
+        }
+    }
+
+    /// Adds the snippets of the bundle typed into the prompt to the library
+    /// at `path`, skipping ones it already has. Extracted for testability.
+    pub fn import_library_bundle_to(&mut self, path: &Path) {
+        let Some(file) = self.bundle_path_from_input() else {
+            return;
+        };
+        let bundle = match read_bundle(&file) {
+            Ok(bundle) => bundle,
+            Err(err) => {
+                self.status_message = Some(format!("Import failed: {err:#}"));
+                return;
             }
+        };
+        let lib = self.library.clone().unwrap_or_default();
+        let (updated, summary) = import_bundle(&lib, &bundle);
+        if summary.added == 0 {
+            self.status_message = Some(summary.to_string());
+            return;
+        }
+        self.write_library_change(path, updated, &summary.to_string());
+    }
+
+    /// Takes the path typed into a bundle prompt and closes the prompt.
+    fn bundle_path_from_input(&mut self) -> Option<PathBuf> {
+        let raw = self.text_input.text().trim().to_string();
+        self.text_input.clear();
+        self.mode = Mode::Normal;
+        if raw.is_empty() {
+            self.status_message = Some("No path entered.".to_string());
+            return None;
         }
+        let path = expand_home(&raw);
+        if path.is_none() {
+            self.status_message = Some("Cannot expand ~: HOME not set.".to_string());
+        }
+        path
     }
 
     /// Deletes a library snippet at a specific path. Extracted for testability.
     pub fn delete_library_snippet_from(&mut self, path: &Path) {
-        let snippet_count = self.library.as_ref().map_or(0, |lib| lib.snippets.len());
-        if snippet_count == 0 {
+        let mut updated = self.library.clone().unwrap_or_default();
+        if self.library_selected >= updated.snippets.len() {
             return;
         }
+        updated.snippets.remove(self.library_selected);
+        self.write_library_change(path, updated, "Snippet deleted.");
+    }
 
-        match crate::library::delete_snippet(self.library_selected, path) {
-            Ok(()) => {
-                // Reload library from disk
-                if let Ok(lib) = crate::library::load_library(path) {
-                    let new_len = lib.snippets.len();
-                    self.library = Some(lib);
-                    if self.library_selected >= new_len && new_len > 0 {
-                        self.library_selected = new_len - 1;
-                    } else if new_len == 0 {
-                        self.library_selected = 0;
-                    }
-                }
-                self.compose_state = None;
-                self.status_message = Some("Snippet deleted.".to_string());
+    /// Deletes every marked snippet in one write. Extracted for
+    /// testability.
+    pub fn delete_marked_snippets_from(&mut self, path: &Path) {
+        let mut updated = self.library.clone().unwrap_or_default();
+        let count = updated.remove_snippets(&self.library_marked);
+        let success = format!("{count} {} deleted.", snippets_noun(count));
+        self.write_library_change(path, updated, &success);
+    }
+
+    pub(crate) fn tag_library_snippets(&mut self) {
+        match crate::library::library_path() {
+            Some(path) => self.tag_library_snippets_in(&path),
+            None => {
+                self.status_message = Some("Cannot determine library path.".to_string());
+                self.text_input.clear();
+                self.mode = Mode::Normal;
             }
-            Err(err) => {
-                self.status_message = Some(format!("Delete failed: {err}"));
+        }
+    }
+
+    /// Adds the tag typed into the prompt to the marked snippets, or the
+    /// selected one if none are marked. Extracted for testability.
+    pub fn tag_library_snippets_in(&mut self, path: &Path) {
+        let tag = self.text_input.text().trim().to_string();
+        if tag.is_empty() {
+            self.status_message = Some("Tag cannot be empty.".to_string());
+            return;
+        }
+        self.text_input.clear();
+        self.mode = Mode::Normal;
+
+        let indices = if self.library_marked.is_empty() {
+            BTreeSet::from([self.library_selected])
+        } else {
+            self.library_marked.clone()
+        };
+        let mut updated = self.library.clone().unwrap_or_default();
+        let count = updated.tag_snippets(&indices, &tag);
+        if count == 0 {
+            self.status_message = Some(format!("Already tagged #{tag}."));
+            return;
+        }
+        let success = format!("Tagged {count} {} #{tag}.", snippets_noun(count));
+        self.write_library_change(path, updated, &success);
+    }
+
+    pub(crate) fn cycle_snippet_format(&mut self) {
+        match crate::library::library_path() {
+            Some(path) => self.cycle_snippet_format_from(&path),
+            None => {
+                self.status_message = Some("Cannot determine library path.".to_string());
             }
         }
     }
 
+    /// Stores the next format for the selected snippet. Extracted for
+    /// testability.
+    pub fn cycle_snippet_format_from(&mut self, path: &Path) {
+        let mut updated = self.library.clone().unwrap_or_default();
+        let Some(snippet) = updated.snippets.get_mut(self.library_selected) else {
+            return;
+        };
+        let format = snippet.format().next();
+        snippet.format = Some(format);
+        let success = format!("Format set to {}.", format.name());
+        self.write_library_change(path, updated, &success);
+    }
+
     /// Enters edit mode for the currently selected snippet.
-    fn enter_snippet_edit(&mut self) {
+    pub(crate) fn enter_snippet_edit(&mut self) {
         let snippet = match &self.library {
             Some(lib) => match lib.snippets.get(self.library_selected) {
                 Some(s) => s,
@@ -280,6 +695,8 @@ impl App {
             had_trailing_newline: false,
             discard_confirmed: false,
             dirty_cache: Cell::new(Some(false)),
+            draft_changed_at: None,
+            preview_top: 0,
         });
         self.editing_snippet_index = Some(index);
         self.mode = Mode::Edit;
@@ -306,33 +723,394 @@ impl App {
 
     /// Saves snippet edit to a specific path (for testability).
     pub fn save_snippet_edit_to(&mut self, index: usize, new_content: &str, path: &Path) {
-        match crate::library::load_library(path) {
-            Ok(mut lib) => {
-                if let Some(snippet) = lib.snippets.get_mut(index) {
-                    snippet.content = new_content.to_string();
-                    match crate::library::save_library(&lib, path) {
-                        Ok(()) => {
-                            self.library = Some(lib);
-                            self.compose_state = None;
-                            if let Some(edit) = &mut self.edit_state {
-                                edit.original_text = new_content.to_string();
-                                edit.dirty_cache.set(Some(false));
-                            }
-                            self.status_message = Some("Snippet saved.".to_string());
-                        }
-                        Err(err) => {
-                            self.status_message = Some(format!("Save failed: {err}"));
-                        }
-                    }
-                } else {
-                    self.status_message = Some("Snippet no longer exists.".to_string());
-                }
-            }
+        let mut updated = self.library.clone().unwrap_or_default();
+        let Some(snippet) = updated.snippets.get_mut(index) else {
+            self.status_message = Some("Snippet no longer exists.".to_string());
+            return;
+        };
+        snippet.content = new_content.to_string();
+        self.write_library_change(path, updated, "Snippet saved.");
+    }
+
+    /// Writes `updated`, the loaded library with one change applied, to
+    /// `path`. If the file changed on disk since it was loaded, the user
+    /// first picks between their version, the one on disk and a merge.
+    fn write_library_change(&mut self, path: &Path, updated: SnippetLibrary, success: &str) {
+        let theirs = match self.load_library_at(path) {
+            Ok(lib) => lib,
             Err(err) => {
                 self.status_message = Some(format!("Save failed: {err}"));
+                return;
+            }
+        };
+        let loaded = self.library.clone().unwrap_or_default();
+        if theirs != loaded && theirs != updated {
+            self.open_picker(Picker::new(
+                "Library changed on disk since it was loaded".to_string(),
+                vec![
+                    PickerItem::new("Keep mine", "overwrite it with your change"),
+                    PickerItem::new("Take theirs", "drop your change and reload"),
+                    PickerItem::new("Merge", "keep the snippets of both"),
+                ],
+                PickerAction::LibraryConflict {
+                    path: path.to_path_buf(),
+                    mine: updated,
+                    theirs,
+                    success: success.to_string(),
+                },
+            ));
+            return;
+        }
+        self.apply_library_write(path, updated, success.to_string());
+    }
+
+    /// Acts on choice `index` of the library conflict picker. Keeping mine
+    /// or merging is previewed as a diff against the library on disk.
+    pub(crate) fn resolve_library_conflict(
+        &mut self,
+        path: PathBuf,
+        mine: SnippetLibrary,
+        theirs: SnippetLibrary,
+        success: String,
+        index: usize,
+    ) {
+        let chosen = match index {
+            0 => mine,
+            1 => {
+                self.finish_library_change(
+                    theirs,
+                    "Took the library on disk; your change was dropped.".to_string(),
+                );
+                return;
+            }
+            _ => merge_libraries(&theirs, &mine),
+        };
+        let (before, after) = match (serialize_library(&theirs), serialize_library(&chosen)) {
+            (Ok(before), Ok(after)) => (before, after),
+            (Err(err), _) | (_, Err(err)) => {
+                self.status_message = Some(format!("Save failed: {err}"));
+                return;
+            }
+        };
+        self.open_diff_preview(DiffPreview::new(
+            format!("Overwrite {}? (y/n)", path.display()),
+            collapse_context(diff_lines(&before, &after), PREVIEW_CONTEXT_LINES),
+            PreviewAction::WriteLibrary {
+                path,
+                library: chosen,
+                success,
+            },
+        ));
+    }
+
+    /// Saves `library` to `path` and makes it the loaded library. The
+    /// library it replaces can be brought back with `u`.
+    pub(crate) fn apply_library_write(
+        &mut self,
+        path: &Path,
+        library: SnippetLibrary,
+        success: String,
+    ) {
+        let before = self.load_library_at(path);
+        match self.save_library_at(&library, path) {
+            Ok(()) => {
+                if let Ok(before) = before {
+                    self.record_library_undo(before, &success);
+                }
+                self.finish_library_change(library, success);
+            }
+            Err(err) => self.status_message = Some(format!("Save failed: {err}")),
+        }
+    }
+
+    /// Indices of the snippets the Library list shows: those matching
+    /// [`library_filter`](App::library_filter) that are archived exactly
+    /// when the Archive view is open, in library order.
+    pub(crate) fn visible_snippets(&self) -> Vec<usize> {
+        let Some(lib) = &self.library else {
+            return Vec::new();
+        };
+        grep_snippets(lib, &self.library_filter)
+            .into_iter()
+            .filter(|m| m.archived == self.library_archive)
+            .map(|m| m.id - 1)
+            .collect()
+    }
+
+    /// The lines of the Library list: matching snippets in library order, or
+    /// grouped under a header per project when
+    /// [`library_grouped`](App::library_grouped).
+    pub(crate) fn library_rows(&self) -> Vec<LibraryRow> {
+        let visible = self.visible_snippets();
+        if !self.library_grouped {
+            return visible.into_iter().map(LibraryRow::Snippet).collect();
+        }
+        let Some(lib) = &self.library else {
+            return Vec::new();
+        };
+        let mut groups: BTreeMap<String, Vec<usize>> = BTreeMap::new();
+        for i in visible {
+            if let Some(snippet) = lib.snippets.get(i) {
+                groups.entry(project_label(snippet)).or_default().push(i);
+            }
+        }
+        let mut rows = Vec::new();
+        for (project, snippets) in groups {
+            let collapsed = self.library_collapsed.contains(&project);
+            rows.push(LibraryRow::Project {
+                project,
+                count: snippets.len(),
+                collapsed,
+            });
+            if !collapsed {
+                rows.extend(snippets.into_iter().map(LibraryRow::Snippet));
+            }
+        }
+        rows
+    }
+
+    /// Position of the Library cursor in `rows`, if it is on one of them.
+    fn library_row_position(&self, rows: &[LibraryRow]) -> Option<usize> {
+        rows.iter()
+            .position(|row| match (row, &self.library_project) {
+                (LibraryRow::Project { project, .. }, Some(selected)) => project == selected,
+                (LibraryRow::Snippet(i), None) => *i == self.library_selected,
+                _ => false,
+            })
+    }
+
+    /// Selects the entry `row` rows below the top of the list, as a click
+    /// on it does.
+    pub(crate) fn click_library_row(&mut self, row: usize) {
+        if let Some(entry) = self.library_rows().get(row) {
+            self.select_library_row(entry);
+        }
+    }
+
+    fn select_library_row(&mut self, row: &LibraryRow) {
+        match row {
+            LibraryRow::Project { project, .. } => self.library_project = Some(project.clone()),
+            LibraryRow::Snippet(i) => {
+                self.library_project = None;
+                self.library_selected = *i;
             }
         }
     }
+
+    /// Whether the cursor is on a snippet the list shows, so actions on the
+    /// selected snippet may run.
+    fn selected_snippet_visible(&self) -> bool {
+        self.library_project.is_none()
+            && self
+                .library_rows()
+                .contains(&LibraryRow::Snippet(self.library_selected))
+    }
+
+    /// The project under the cursor: the selected header's, or the selected
+    /// snippet's.
+    fn selected_library_project(&self) -> Option<String> {
+        if let Some(project) = &self.library_project {
+            return Some(project.clone());
+        }
+        self.library
+            .as_ref()?
+            .snippets
+            .get(self.library_selected)
+            .map(project_label)
+    }
+
+    /// Switches between the Library list and the Archive view.
+    fn set_library_archive(&mut self, archive: bool) {
+        self.library_archive = archive;
+        self.library_project = None;
+        self.keep_library_selection_visible();
+    }
+
+    /// Sets the Library filter, moving the selection to the first match when
+    /// the selected snippet is filtered out.
+    pub(crate) fn set_library_filter(&mut self, filter: String) {
+        self.library_filter = filter;
+        self.keep_library_selection_visible();
+    }
+
+    fn keep_library_selection_visible(&mut self) {
+        let rows = self.library_rows();
+        if self.library_row_position(&rows).is_none()
+            && let Some(first) = rows.first()
+        {
+            self.select_library_row(first);
+        }
+    }
+
+    /// Replaces the loaded library with `library`, which matches the file on
+    /// disk, and reports `success`.
+    pub(crate) fn finish_library_change(&mut self, library: SnippetLibrary, success: String) {
+        self.library_selected = self
+            .library_selected
+            .min(library.snippets.len().saturating_sub(1));
+        // The editor is clean exactly when it matches what is now stored.
+        if let Some(index) = self.editing_snippet_index
+            && let Some(edit) = &mut self.edit_state
+            && let Some(snippet) = library.snippets.get(index)
+        {
+            edit.original_text = snippet.content.clone();
+            edit.invalidate_dirty_cache();
+        }
+        self.library = Some(library);
+        // Indices shift when snippets go, so marks would point elsewhere.
+        self.library_marked.clear();
+        self.keep_library_selection_visible();
+        self.compose_state = None;
+        self.status_message = Some(success);
+    }
+
+    /// The project library of the selected root, or of the first root when
+    /// none is selected.
+    pub(crate) fn current_project_library(&self) -> Option<PathBuf> {
+        let root = self
+            .selected_root()
+            .or_else(|| self.roots.first().map(|root| root.path.clone()))?;
+        Some(project_library_path(&root))
+    }
+
+    /// Loads the library at `path` followed by the project library in use.
+    pub(crate) fn load_library_at(&self, path: &Path) -> anyhow::Result<SnippetLibrary> {
+        load_library_set(path, self.project_library.as_deref())
+    }
+
+    /// Saves `library` to `path` and the project library in use, each
+    /// snippet to the file of its scope.
+    pub(crate) fn save_library_at(
+        &self,
+        library: &SnippetLibrary,
+        path: &Path,
+    ) -> anyhow::Result<()> {
+        save_library_set(library, path, self.project_library.as_deref())
+    }
+
+    /// The metadata shown above a snippet in the preview: where it came
+    /// from, its tags, when it was saved and its size, then a rule `width`
+    /// wide.
+    fn snippet_header(&self, snippet: &Snippet, width: u16) -> Vec<Line<'static>> {
+        let label = |name: &str| Span::styled(format!("{name:<8}"), self.theme.help_desc);
+        let mut lines = Vec::new();
+        let source = match (snippet.source.is_empty(), snippet.lines) {
+            (true, _) => "(unknown)".to_string(),
+            (false, Some((start, end))) => format!("{}:{start}-{end}", snippet.source),
+            (false, None) => snippet.source.clone(),
+        };
+        let mut spans = vec![label("Source"), Span::raw(source)];
+        if !snippet.source.is_empty() {
+            spans.push(Span::styled("  (o opens it)", self.theme.help_desc));
+        }
+        lines.push(Line::from(spans));
+        if snippet.scope == LibraryScope::Project
+            && let Some(project) = &self.project_library
+        {
+            lines.push(Line::from(vec![
+                label("Library"),
+                Span::raw(format!("project, {}", project.display())),
+            ]));
+        }
+        if !snippet.tags.is_empty() {
+            lines.push(Line::from(vec![
+                label("Tags"),
+                Span::raw(format!("#{}", snippet.tags.join(" #"))),
+            ]));
+        }
+        if let Some(created) = snippet.created {
+            lines.push(Line::from(vec![
+                label("Saved"),
+                Span::raw(format_timestamp(created)),
+            ]));
+        }
+        let line_count = snippet.content.lines().count();
+        lines.push(Line::from(vec![
+            label("Size"),
+            Span::raw(format!(
+                "{}, {line_count} {}",
+                format_tokens(self.config.tokenizer().count(&snippet.content)),
+                if line_count == 1 { "line" } else { "lines" }
+            )),
+        ]));
+        lines.push(Line::styled(
+            "─".repeat(width as usize),
+            self.theme.inactive_border,
+        ));
+        lines
+    }
+
+    /// Shows the file the selected snippet was taken from on the Files
+    /// screen, at its first line when known.
+    fn open_snippet_source(&mut self) -> Vec<Effect> {
+        let Some(snippet) = self
+            .library
+            .as_ref()
+            .and_then(|lib| lib.snippets.get(self.library_selected))
+        else {
+            return Vec::new();
+        };
+        if snippet.source.is_empty() {
+            self.status_message = Some("The snippet has no source file.".to_string());
+            return Vec::new();
+        }
+        let file = PathBuf::from(&snippet.source);
+        if !self.discovered_files().contains(&file) {
+            self.status_message = Some(format!("{} is not loaded.", file.display()));
+            return Vec::new();
+        }
+        match snippet.lines {
+            Some((start, _)) => vec![Effect::ShowLine {
+                file,
+                line: start.saturating_sub(1),
+            }],
+            None => vec![Effect::ShowFile(file)],
+        }
+    }
+}
+
+/// One line of the Library list.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) enum LibraryRow {
+    /// Header over the snippets taken from `project`.
+    Project {
+        project: String,
+        count: usize,
+        collapsed: bool,
+    },
+    /// The snippet at this index of the library.
+    Snippet(usize),
+}
+
+/// `snippet` or `snippets`, to go with `count`.
+fn snippets_noun(count: usize) -> &'static str {
+    if count == 1 { "snippet" } else { "snippets" }
+}
+
+/// Name of the group `snippet` is listed under when grouped by project.
+fn project_label(snippet: &Snippet) -> String {
+    snippet.project().map_or_else(
+        || "(unknown source)".to_string(),
+        |project| project.display().to_string(),
+    )
+}
+
+/// Styles `content` for the library preview: Markdown headings stand out,
+/// and fences and shell comments are dimmed.
+fn highlight_snippet(content: &str, format: SnippetFormat, theme: &Theme) -> Vec<Line<'static>> {
+    content
+        .lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            let style = match format {
+                SnippetFormat::Markdown if trimmed.starts_with('#') => theme.active_tab,
+                SnippetFormat::Markdown if trimmed.starts_with("```") => theme.inactive_tab,
+                SnippetFormat::Shell if trimmed.starts_with('#') => theme.inactive_tab,
+                _ => Style::default(),
+            };
+            Line::styled(line.to_string(), style)
+        })
+        .collect()
 }
 
 #[cfg(test)]
@@ -342,10 +1120,14 @@ mod tests {
     use tempfile::TempDir;
 
     use crate::config::Config;
+    use crate::library::SnippetFormat;
+    use crate::model::DiscoveredFile;
     use crate::tui::app::App;
     use crate::tui::app::Mode;
     use crate::tui::app::Screen;
     use crate::tui::app::test_helpers::key_event;
+    use crate::tui::app::test_helpers::render_once;
+    use crate::tui::library::LibraryRow;
 
     fn library_with_snippets(path: &std::path::Path, titles: &[&str]) {
         for title in titles {
@@ -354,6 +1136,12 @@ mod tests {
                     title: title.to_string(),
                     content: format!("Content of {title}"),
                     source: "/test/CLAUDE.md".to_string(),
+                    format: None,
+                    archived: false,
+                    tags: Vec::new(),
+                    lines: None,
+                    created: None,
+                    scope: Default::default(),
                 },
                 path,
             )
@@ -564,23 +1352,87 @@ mod tests {
     }
 
     #[test]
-    fn rename_with_empty_title_shows_error() {
+    fn w_exports_and_i_imports_a_bundle() {
         let tmp = TempDir::new().unwrap();
         let lib_path = tmp.path().join("library.toml");
-        library_with_snippets(&lib_path, &["Keep Me"]);
+        let other_path = tmp.path().join("other.toml");
+        let bundle = tmp.path().join("team.json");
+        library_with_snippets(&lib_path, &["Style", "Build"]);
+        library_with_snippets(&other_path, &["Style", "Deploy"]);
 
         let mut app = App::new(vec![], &Config::default());
         app.enter_library_screen_from(&lib_path);
-        app.mode = Mode::RenameInput;
-        app.text_input.set("  ");
+        app.handle_key_event(key_event(KeyCode::Char('w')));
+        assert_eq!(app.mode, Mode::BundleExportPath);
+        app.text_input.set(&bundle.display().to_string());
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(
+            app.status_message.take().unwrap(),
+            format!("Exported 2 snippets to {}.", bundle.display())
+        );
 
-        app.rename_library_snippet_from(&lib_path);
+        app.enter_library_screen_from(&other_path);
+        app.handle_key_event(key_event(KeyCode::Char('I')));
+        assert_eq!(app.mode, Mode::BundleImportPath);
+        app.text_input.set(&bundle.display().to_string());
+        app.import_library_bundle_to(&other_path);
 
-        assert_eq!(app.mode, Mode::RenameInput, "Stays in RenameInput on empty");
-        assert!(app.status_message.as_deref().unwrap().contains("empty"));
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Imported 1 snippet (1 duplicate skipped).")
+        );
+        let lib = crate::library::load_library(&other_path).unwrap();
+        let titles: Vec<&str> = lib.snippets.iter().map(|s| s.title.as_str()).collect();
+        assert_eq!(titles, ["Style", "Deploy", "Build"]);
+    }
 
-        // Original title preserved
-        let lib = crate::library::load_library(&lib_path).unwrap();
+    #[test]
+    fn f_cycles_and_stores_snippet_format() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["Plain"]);
+
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+        assert_eq!(
+            app.library.as_ref().unwrap().snippets[0].format(),
+            SnippetFormat::Text
+        );
+
+        app.cycle_snippet_format_from(&lib_path);
+
+        let lib = crate::library::load_library(&lib_path).unwrap();
+        assert_eq!(lib.snippets[0].format, Some(SnippetFormat::Markdown));
+        assert_eq!(
+            app.library.as_ref().unwrap().snippets[0].format,
+            Some(SnippetFormat::Markdown)
+        );
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Format set to markdown.")
+        );
+        render_once(&mut app);
+    }
+
+    #[test]
+    fn rename_with_empty_title_shows_error() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["Keep Me"]);
+
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+        app.mode = Mode::RenameInput;
+        app.text_input.set("  ");
+
+        app.rename_library_snippet_from(&lib_path);
+
+        assert_eq!(app.mode, Mode::RenameInput, "Stays in RenameInput on empty");
+        assert!(app.status_message.as_deref().unwrap().contains("empty"));
+
+        // Original title preserved
+        let lib = crate::library::load_library(&lib_path).unwrap();
         assert_eq!(lib.snippets[0].title, "Keep Me");
     }
 
@@ -718,4 +1570,622 @@ mod tests {
         // Compose state should be invalidated
         assert!(app.compose_state.is_none());
     }
+
+    fn titles(path: &std::path::Path) -> Vec<String> {
+        crate::library::load_library(path)
+            .unwrap()
+            .snippets
+            .into_iter()
+            .map(|s| s.title)
+            .collect()
+    }
+
+    /// Loads a library of A and B, then adds C behind the app's back and
+    /// renames A to X in the app.
+    fn rename_after_external_change(lib_path: &std::path::Path) -> App {
+        library_with_snippets(lib_path, &["A", "B"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(lib_path);
+        library_with_snippets(lib_path, &["C"]);
+        app.text_input.set("X");
+        app.rename_library_snippet_from(lib_path);
+        app
+    }
+
+    #[test]
+    fn external_change_asks_before_overwriting() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+
+        let app = rename_after_external_change(&lib_path);
+
+        assert_eq!(app.mode, Mode::Picker);
+        assert_eq!(titles(&lib_path), ["A", "B", "C"]);
+    }
+
+    #[test]
+    fn keep_mine_overwrites_after_preview() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        let mut app = rename_after_external_change(&lib_path);
+
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::DiffPreview);
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert_eq!(titles(&lib_path), ["X", "B"]);
+        assert_eq!(app.library.as_ref().unwrap().snippets.len(), 2);
+    }
+
+    #[test]
+    fn take_theirs_reloads_without_writing() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        let mut app = rename_after_external_change(&lib_path);
+
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(titles(&lib_path), ["A", "B", "C"]);
+        let loaded: Vec<&str> = app
+            .library
+            .as_ref()
+            .unwrap()
+            .snippets
+            .iter()
+            .map(|s| s.title.as_str())
+            .collect();
+        assert_eq!(loaded, ["A", "B", "C"]);
+    }
+
+    #[test]
+    fn merge_writes_union_of_snippets() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        let mut app = rename_after_external_change(&lib_path);
+
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.handle_key_event(key_event(KeyCode::Enter));
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert_eq!(titles(&lib_path), ["A", "B", "C", "X"]);
+    }
+
+    #[test]
+    fn conflict_during_snippet_edit_returns_to_editor() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["A"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+        app.enter_snippet_edit();
+        library_with_snippets(&lib_path, &["B"]);
+
+        app.save_snippet_edit_to(0, "edited", &lib_path);
+        assert_eq!(app.mode, Mode::Picker);
+        app.handle_key_event(key_event(KeyCode::Enter));
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert_eq!(app.mode, Mode::Edit);
+        let lib = crate::library::load_library(&lib_path).unwrap();
+        assert_eq!(lib.snippets.len(), 1);
+        assert_eq!(lib.snippets[0].content, "edited");
+    }
+
+    fn type_filter(app: &mut App, query: &str) {
+        app.handle_key_event(key_event(KeyCode::Char('/')));
+        for c in query.chars() {
+            app.handle_key_event(key_event(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn filter_narrows_list_and_navigation() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["Build", "Lint", "Build docs", "Test"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+        app.handle_key_event(key_event(KeyCode::Char('j')));
+
+        type_filter(&mut app, "bui");
+
+        assert_eq!(app.mode, Mode::LibraryFilter);
+        assert_eq!(app.visible_snippets(), [0, 2]);
+        assert_eq!(app.library_selected, 0, "hidden selection moves to a match");
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::Normal);
+        app.handle_key_event(key_event(KeyCode::Char('j')));
+        assert_eq!(app.library_selected, 2);
+        app.handle_key_event(key_event(KeyCode::Char('j')));
+        assert_eq!(app.library_selected, 2);
+        render_once(&mut app);
+    }
+
+    #[test]
+    fn filter_matches_content_ignoring_case() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["A", "B"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+
+        type_filter(&mut app, "CONTENT OF B");
+
+        assert_eq!(app.visible_snippets(), [1]);
+    }
+
+    #[test]
+    fn delete_in_filtered_view_removes_the_selected_snippet() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["A", "B", "C"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+        type_filter(&mut app, "of c");
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        app.delete_library_snippet_from(&lib_path);
+
+        assert_eq!(titles(&lib_path), ["A", "B"]);
+    }
+
+    #[test]
+    fn actions_without_a_match_do_nothing() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["A"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+        type_filter(&mut app, "zzz");
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        app.handle_key_event(key_event(KeyCode::Char('r')));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("No snippet matches the filter.")
+        );
+        render_once(&mut app);
+    }
+
+    #[test]
+    fn esc_clears_filter_before_leaving() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["A", "B"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+        type_filter(&mut app, "b");
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        app.handle_key_event(key_event(KeyCode::Esc));
+        assert_eq!(app.screen, Screen::Library);
+        assert_eq!(app.visible_snippets(), [0, 1]);
+
+        app.handle_key_event(key_event(KeyCode::Esc));
+        assert_eq!(app.screen, Screen::Files);
+    }
+
+    fn library_from_projects(path: &std::path::Path) {
+        for (title, source) in [
+            ("api build", "/code/api/CLAUDE.md"),
+            ("web build", "/code/web/.claude/CLAUDE.md"),
+            ("api test", "/code/api/CLAUDE.md"),
+        ] {
+            crate::library::append_snippet(
+                crate::library::Snippet {
+                    title: title.to_string(),
+                    content: title.to_string(),
+                    source: source.to_string(),
+                    format: None,
+                    archived: false,
+                    tags: Vec::new(),
+                    lines: None,
+                    created: None,
+                    scope: Default::default(),
+                },
+                path,
+            )
+            .unwrap();
+        }
+    }
+
+    #[test]
+    fn g_groups_snippets_under_their_project() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_from_projects(&lib_path);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+
+        app.handle_key_event(key_event(KeyCode::Char('g')));
+
+        assert_eq!(
+            app.library_rows(),
+            [
+                LibraryRow::Project {
+                    project: "/code/api".to_string(),
+                    count: 2,
+                    collapsed: false,
+                },
+                LibraryRow::Snippet(0),
+                LibraryRow::Snippet(2),
+                LibraryRow::Project {
+                    project: "/code/web".to_string(),
+                    count: 1,
+                    collapsed: false,
+                },
+                LibraryRow::Snippet(1),
+            ]
+        );
+        app.handle_key_event(key_event(KeyCode::Char('j')));
+        assert_eq!(app.library_selected, 2);
+        app.handle_key_event(key_event(KeyCode::Char('j')));
+        assert_eq!(app.library_project.as_deref(), Some("/code/web"));
+        render_once(&mut app);
+    }
+
+    #[test]
+    fn left_collapses_project_and_enter_expands_it() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_from_projects(&lib_path);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+        app.handle_key_event(key_event(KeyCode::Char('g')));
+
+        app.handle_key_event(key_event(KeyCode::Left));
+
+        assert_eq!(app.library_project.as_deref(), Some("/code/api"));
+        assert_eq!(app.library_rows().len(), 3, "api snippets are hidden");
+        app.handle_key_event(key_event(KeyCode::Char('d')));
+        assert_eq!(titles(&lib_path).len(), 3, "nothing deleted from a header");
+
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.library_rows().len(), 5);
+        assert_eq!(app.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn rename_to_taken_title_offers_suffix() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["A", "B"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+        app.text_input.set("b");
+
+        app.rename_library_snippet_from(&lib_path);
+        assert_eq!(app.mode, Mode::Picker);
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(titles(&lib_path), ["b (2)", "B"]);
+    }
+
+    #[test]
+    fn cancelling_duplicate_rename_changes_nothing() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["A", "B"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+        app.text_input.set("B");
+
+        app.rename_library_snippet_from(&lib_path);
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(titles(&lib_path), ["A", "B"]);
+        assert_eq!(app.mode, Mode::Normal);
+    }
+
+    #[test]
+    fn saving_selection_under_taken_title_offers_suffix() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["A"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.content.text = Some("line".to_string());
+        app.content.visual_anchor = Some(0);
+        app.mode = Mode::TitleInput;
+        app.text_input.set("A");
+
+        app.save_current_snippet_to(&lib_path);
+        assert_eq!(app.mode, Mode::Picker);
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(titles(&lib_path), ["A", "A (2)"]);
+        assert_eq!(app.status_message.as_deref(), Some("Snippet saved!"));
+    }
+
+    fn archived(path: &std::path::Path) -> Vec<bool> {
+        crate::library::load_library(path)
+            .unwrap()
+            .snippets
+            .into_iter()
+            .map(|s| s.archived)
+            .collect()
+    }
+
+    #[test]
+    fn delete_offers_archive_first() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["A", "B"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+
+        app.start_snippet_removal_from(&lib_path);
+        assert_eq!(app.mode, Mode::Picker);
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(titles(&lib_path), ["A", "B"]);
+        assert_eq!(archived(&lib_path), [true, false]);
+        assert_eq!(app.visible_snippets(), [1]);
+        assert_eq!(app.library_selected, 1);
+    }
+
+    #[test]
+    fn delete_permanently_is_the_second_choice() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["A", "B"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+
+        app.start_snippet_removal_from(&lib_path);
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(titles(&lib_path), ["B"]);
+    }
+
+    #[test]
+    fn archive_view_lists_and_restores_archived_snippets() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["A", "B", "C"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+        app.handle_key_event(key_event(KeyCode::Char('j')));
+        app.toggle_snippet_archived_from(&lib_path);
+        assert_eq!(app.visible_snippets(), [0, 2]);
+
+        app.handle_key_event(key_event(KeyCode::Char('A')));
+        assert!(app.library_archive);
+        assert_eq!(app.visible_snippets(), [1]);
+        assert_eq!(app.library_selected, 1);
+
+        app.toggle_snippet_archived_from(&lib_path);
+        assert_eq!(archived(&lib_path), [false, false, false]);
+        assert!(app.visible_snippets().is_empty());
+
+        app.handle_key_event(key_event(KeyCode::Esc));
+        assert!(!app.library_archive);
+        assert_eq!(app.screen, Screen::Library);
+        assert_eq!(app.visible_snippets(), [0, 1, 2]);
+    }
+
+    #[test]
+    fn filter_searches_the_archive_too() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["Alpha", "Beta"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+        app.toggle_snippet_archived_from(&lib_path);
+
+        app.handle_key_event(key_event(KeyCode::Char('A')));
+        type_filter(&mut app, "alp");
+
+        assert_eq!(app.visible_snippets(), [0]);
+    }
+
+    fn screen_text(app: &mut App) -> String {
+        let backend = ratatui::backend::TestBackend::new(120, 20);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let buf = terminal.backend().buffer();
+        buf.content().iter().map(|c| c.symbol()).collect()
+    }
+
+    #[test]
+    fn space_marks_snippets_and_d_removes_them_in_one_undoable_write() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["A", "B", "C"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+
+        app.handle_key_event(key_event(KeyCode::Char(' ')));
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.handle_key_event(key_event(KeyCode::Char(' ')));
+        assert_eq!(app.library_marked, [0, 2].into());
+        let screen = screen_text(&mut app);
+        assert!(screen.contains("[2 marked]"));
+        assert!(screen.contains("[x] A"));
+        assert!(screen.contains("[ ] B"));
+
+        app.start_snippet_removal_from(&lib_path);
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(titles(&lib_path), ["B"]);
+        assert_eq!(app.status_message.as_deref(), Some("2 snippets deleted."));
+        assert!(app.library_marked.is_empty());
+        app.undo_library_change_at(&lib_path);
+        assert_eq!(titles(&lib_path), ["A", "B", "C"]);
+    }
+
+    #[test]
+    fn marked_snippets_are_archived_together_and_esc_clears_marks() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["A", "B", "C"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+        app.library_marked = [1, 2].into();
+
+        app.toggle_snippet_archived_from(&lib_path);
+        assert_eq!(archived(&lib_path), [false, true, true]);
+        assert_eq!(app.status_message.as_deref(), Some("2 snippets archived."));
+
+        app.handle_key_event(key_event(KeyCode::Char(' ')));
+        app.handle_key_event(key_event(KeyCode::Esc));
+        assert!(app.library_marked.is_empty());
+        assert_eq!(app.screen, Screen::Library);
+    }
+
+    #[test]
+    fn w_exports_only_the_marked_snippets() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        let bundle = tmp.path().join("picked.toml");
+        library_with_snippets(&lib_path, &["A", "B", "C"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+        app.library_marked = [0, 2].into();
+
+        app.handle_key_event(key_event(KeyCode::Char('w')));
+        app.text_input.set(&bundle.display().to_string());
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(titles(&bundle), ["A", "C"]);
+        assert!(app.library_marked.is_empty());
+    }
+
+    #[test]
+    fn t_tags_the_marked_snippets_or_the_selected_one() {
+        let tmp = TempDir::new().unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        library_with_snippets(&lib_path, &["A", "B", "C"]);
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(&lib_path);
+        let tags = |path| {
+            crate::library::load_library(path)
+                .unwrap()
+                .snippets
+                .into_iter()
+                .map(|s| s.tags)
+                .collect::<Vec<_>>()
+        };
+
+        app.library_marked = [1, 2].into();
+        app.handle_key_event(key_event(KeyCode::Char('t')));
+        assert_eq!(app.mode, Mode::TagInput);
+        app.text_input.set("style");
+        app.tag_library_snippets_in(&lib_path);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Tagged 2 snippets #style.")
+        );
+
+        app.handle_key_event(key_event(KeyCode::Char('t')));
+        app.text_input.set("build");
+        app.tag_library_snippets_in(&lib_path);
+        assert_eq!(
+            tags(&lib_path),
+            [vec!["build"], vec!["style"], vec!["style"]]
+        );
+        assert!(screen_text(&mut app).contains("B #style"));
+    }
+
+    #[test]
+    fn preview_header_shows_metadata_and_o_jumps_to_the_source_lines() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        std::fs::write(&file, "# Rules\n- tabs\n- short\n").unwrap();
+        let lib_path = tmp.path().join("library.toml");
+        crate::library::append_snippet(
+            crate::library::Snippet {
+                title: "Style".to_string(),
+                content: "- tabs\n- short".to_string(),
+                source: file.display().to_string(),
+                format: None,
+                archived: false,
+                tags: vec!["rust".to_string()],
+                lines: Some((2, 3)),
+                created: Some(0),
+                scope: Default::default(),
+            },
+            &lib_path,
+        )
+        .unwrap();
+        let roots = vec![crate::model::SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![DiscoveredFile::new(file.clone())],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+        app.enter_library_screen_from(&lib_path);
+
+        let screen = screen_text(&mut app);
+        assert!(screen.contains("CLAUDE.md:2-3"), "{screen}");
+        assert!(screen.contains("Tags    #rust"));
+        assert!(screen.contains("Saved"));
+        assert!(screen.contains("2 lines"));
+
+        app.handle_key_event(key_event(KeyCode::Char('o')));
+        assert_eq!(app.screen, Screen::Files);
+        assert_eq!(app.content.cursor, 1);
+    }
+
+    #[test]
+    fn project_library_is_listed_with_its_scope_and_changes_stay_in_it() {
+        let tmp = TempDir::new().unwrap();
+        let config = TempDir::new().unwrap();
+        let global = config.path().join("library.toml");
+        let project = tmp.path().join(crate::library::PROJECT_LIBRARY_PATH);
+        library_with_snippets(&global, &["Mine"]);
+        library_with_snippets(&project, &["Team"]);
+        let file = tmp.path().join("CLAUDE.md");
+        std::fs::write(&file, "# Rules\n- tabs\n").unwrap();
+        let roots = vec![crate::model::SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![DiscoveredFile::new(file)],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+        app.enter_library_screen_from(&global);
+
+        let screen = screen_text(&mut app);
+        assert!(screen.contains("global  Mine"), "{screen}");
+        assert!(screen.contains("project Team"), "{screen}");
+
+        app.library_selected = 1;
+        app.mode = Mode::RenameInput;
+        app.text_input.set("Team rules");
+        app.rename_library_snippet_from(&global);
+        let titles = |path: &std::path::Path| -> Vec<String> {
+            crate::library::load_library(path)
+                .unwrap()
+                .snippets
+                .into_iter()
+                .map(|snippet| snippet.title)
+                .collect()
+        };
+        assert_eq!(titles(&project), ["Team rules"]);
+        assert_eq!(titles(&global), ["Mine"]);
+
+        app.handle_key_event(key_event(KeyCode::Esc));
+        app.content.text = Some("# Rules\n- tabs".to_string());
+        app.content.visual_anchor = Some(1);
+        app.content.cursor = 1;
+        app.text_input.set("Tabs");
+        app.save_current_snippet_to(&global);
+        assert_eq!(app.mode, Mode::Picker);
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(titles(&project), ["Team rules", "Tabs"]);
+        assert_eq!(titles(&global), ["Mine"]);
+    }
 }
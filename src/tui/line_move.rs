@@ -0,0 +1,272 @@
+//! Moving lines from one CLAUDE.md to another: the `m` of visual select,
+//! and the section move of the quick actions menu. Both files are written
+//! with backups, the target first, so the lines are never in neither.
+
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
+
+use super::app::App;
+use super::app::Mode;
+use super::diff_preview::DiffPreview;
+use super::diff_preview::PREVIEW_CONTEXT_LINES;
+use super::diff_preview::PreviewAction;
+use super::picker::Picker;
+use super::picker::PickerAction;
+use super::picker::PickerItem;
+use crate::compose::append_to_section;
+use crate::diff::collapse_context;
+use crate::diff::diff_lines;
+use crate::trim::remove_lines;
+
+/// Selected lines on their way to another file, waiting for the heading
+/// to put over them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PendingLineMove {
+    pub from: PathBuf,
+    /// 0-based lines of `from`.
+    pub lines: Range<usize>,
+    pub to: PathBuf,
+}
+
+impl App {
+    /// Asks which file the selected lines move to.
+    pub(crate) fn start_selection_move(&mut self) {
+        let (Some(file), Some(text), Some((start, end))) = (
+            self.selected_file(),
+            self.content.text.clone(),
+            self.content.selection_range(),
+        ) else {
+            return;
+        };
+        self.reset_to_normal();
+        // The pane must show the file as it is to change it safely.
+        if !self.content.shows_file_as_is() {
+            self.status_message = Some(
+                "Show the whole file, with its comments and imports collapsed, to move lines."
+                    .to_string(),
+            );
+            return;
+        }
+        if !self.unchanged_on_disk(&file, &text) || !self.ensure_writable(&file, "edit") {
+            return;
+        }
+        let targets: Vec<PathBuf> = self
+            .discovered_files()
+            .into_iter()
+            .filter(|other| *other != file)
+            .collect();
+        if targets.is_empty() {
+            self.status_message = Some("No other file to move the lines to.".to_string());
+            return;
+        }
+        let count = end - start + 1;
+        let items = targets
+            .iter()
+            .map(|target| PickerItem::new(target.display().to_string(), ""))
+            .collect();
+        self.open_picker(Picker::new(
+            format!("Move {count} {} to", lines_noun(count)),
+            items,
+            PickerAction::MoveLinesTo {
+                from: file,
+                lines: start..end + 1,
+                targets,
+            },
+        ));
+    }
+
+    /// Asks for the heading to put over the lines moving to `to`.
+    pub(crate) fn choose_line_move_target(
+        &mut self,
+        from: PathBuf,
+        lines: Range<usize>,
+        to: PathBuf,
+    ) {
+        if !self.ensure_writable(&to, "edit") {
+            return;
+        }
+        self.pending_line_move = Some(PendingLineMove { from, lines, to });
+        self.text_input.clear();
+        self.mode = Mode::MoveHeading;
+    }
+
+    /// Previews the pending move under the heading typed in, or under none
+    /// when it was left empty.
+    pub(crate) fn submit_move_heading(&mut self) {
+        let heading = self.text_input.text().trim().to_string();
+        self.reset_to_normal();
+        let Some(pending) = self.pending_line_move.take() else {
+            return;
+        };
+        let text = self.content.text.clone().unwrap_or_default();
+        let heading = match heading.as_str() {
+            "" => None,
+            h if h.starts_with('#') => Some(heading.clone()),
+            h => Some(format!("## {h}")),
+        };
+        let count = pending.lines.len();
+        self.preview_line_move(
+            &pending.from,
+            &text,
+            pending.lines,
+            pending.to,
+            heading.as_deref(),
+            &format!("{count} {}", lines_noun(count)),
+        );
+    }
+
+    /// Previews taking the `lines` of `file`, whose text is `text`, out and
+    /// appending them to `to`, under `heading` when given. `what` names the
+    /// lines in the prompt and the status bar.
+    pub(crate) fn preview_line_move(
+        &mut self,
+        file: &Path,
+        text: &str,
+        lines: Range<usize>,
+        to: PathBuf,
+        heading: Option<&str>,
+        what: &str,
+    ) {
+        if !self.unchanged_on_disk(file, text)
+            || !self.ensure_writable(file, "edit")
+            || !self.ensure_writable(&to, "edit")
+        {
+            return;
+        }
+        let to_text = match fs::read_to_string(&to) {
+            Ok(to_text) => to_text,
+            Err(err) => {
+                self.status_message = Some(format!("Error reading {}: {err}", to.display()));
+                return;
+            }
+        };
+        let moved: Vec<&str> = text.lines().collect();
+        let mut block = moved.get(lines.clone()).unwrap_or_default().join("\n");
+        if let Some(heading) = heading {
+            block = format!("{heading}\n\n{block}");
+        }
+        let to_content = append_to_section(&to_text, None, &block);
+        let content = remove_lines(text, &[lines]);
+        let mut diff = collapse_context(diff_lines(&to_text, &to_content), PREVIEW_CONTEXT_LINES);
+        diff.extend(collapse_context(
+            diff_lines(text, &content),
+            PREVIEW_CONTEXT_LINES,
+        ));
+        self.open_diff_preview(DiffPreview::new(
+            format!("Move {what} to {}? (y/n)", to.display()),
+            diff,
+            PreviewAction::MoveLines {
+                from: file.to_path_buf(),
+                content,
+                success: format!("Moved {what} to {}.", to.display()),
+                to,
+                to_content,
+            },
+        ));
+    }
+
+    /// Writes both files of a previewed move: the target first, so the
+    /// lines are never in neither.
+    pub(crate) fn apply_line_move(
+        &mut self,
+        from: &Path,
+        content: &str,
+        to: &Path,
+        to_content: &str,
+        success: String,
+    ) {
+        if let Err(err) = self.write_with_backup("write", to, to_content) {
+            self.status_message = Some(format!("Write failed: {err}"));
+            return;
+        }
+        self.apply_file_write(from, content, success);
+    }
+
+    /// Whether `file` still holds `text`, the text the pane shows; if not,
+    /// says so in the status bar.
+    pub(crate) fn unchanged_on_disk(&mut self, file: &Path, text: &str) -> bool {
+        if fs::read_to_string(file).is_ok_and(|disk| disk == text) {
+            return true;
+        }
+        self.status_message = Some(format!(
+            "{} changed on disk; reload it first.",
+            file.display()
+        ));
+        false
+    }
+}
+
+fn lines_noun(count: usize) -> &'static str {
+    if count == 1 { "line" } else { "lines" }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::Pane;
+    use crate::tui::app::test_helpers::key_event;
+
+    #[test]
+    fn m_moves_the_selected_lines_under_a_heading_to_the_chosen_file() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        let target = tmp.path().join("docs/CLAUDE.md");
+        fs::create_dir(tmp.path().join("docs")).unwrap();
+        fs::write(&file, "# Project\n\nkeep\nmove one\nmove two\nkeep too\n").unwrap();
+        fs::write(&target, "# Docs\n").unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![
+                DiscoveredFile::new(file.clone()),
+                DiscoveredFile::new(target.clone()),
+            ],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+        app.select_tree_file(&file);
+        app.load_selected_content();
+        app.active_pane = Pane::Content;
+
+        app.content.jump_to_line(3);
+        app.handle_key_event(key_event(KeyCode::Char('v')));
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.handle_key_event(key_event(KeyCode::Char('m')));
+        assert_eq!(app.mode, Mode::Picker);
+        assert_eq!(app.picker.as_ref().unwrap().title, "Move 2 lines to");
+
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::MoveHeading);
+        for c in "Moved".chars() {
+            app.handle_key_event(key_event(KeyCode::Char(c)));
+        }
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::DiffPreview);
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "# Project\n\nkeep\nkeep too\n"
+        );
+        assert_eq!(
+            fs::read_to_string(&target).unwrap(),
+            "# Docs\n\n## Moved\n\nmove one\nmove two\n"
+        );
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some(format!("Moved 2 lines to {}.", target.display()).as_str())
+        );
+    }
+}
@@ -0,0 +1,262 @@
+//! Linting every scanned file on a background thread.
+//!
+//! Once a scan is done the files are linted as `jigolo lint` would, and
+//! each file in the tree is badged with its findings: `✗2` for errors,
+//! `!3` for warnings. `!` walks from one file with findings to the next.
+//! Files jigolo writes, and files that change on disk, are linted again
+//! on the next tick, and the status bar says when their findings changed.
+
+use std::mem;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::TryRecvError;
+use std::thread;
+
+use super::app::App;
+use crate::lint::Finding;
+use crate::lint::FindingCounts;
+use crate::lint::LintTarget;
+use crate::lint::count_findings;
+use crate::lint::lint_files;
+use crate::lint::lint_targets;
+use crate::tokens::TokenizerKind;
+
+/// Lints `files` on a new thread.
+pub fn spawn_lint(
+    files: Vec<LintTarget>,
+    tokenizer: TokenizerKind,
+) -> Receiver<Vec<(PathBuf, Finding)>> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        // The app may have quit; nobody is listening then.
+        let _ = sender.send(lint_files(&files, tokenizer.tokenizer()));
+    });
+    receiver
+}
+
+impl App {
+    /// Lints the discovered files in the background, replacing any lint
+    /// still running.
+    pub(crate) fn start_background_lint(&mut self) {
+        let files = lint_targets(&self.roots, &self.config);
+        self.background_lint = Some(spawn_lint(files, self.config.tokenizer.unwrap_or_default()));
+    }
+
+    /// Has `file` linted again on the next tick, reporting its findings if
+    /// they changed.
+    pub(crate) fn relint(&mut self, file: &Path) {
+        if !self.relint_files.iter().any(|f| f == file) {
+            self.relint_files.push(file.to_path_buf());
+        }
+    }
+
+    /// Starts the lint the files changed since the last tick call for.
+    pub(crate) fn start_due_lint(&mut self) {
+        if self.relint_files.is_empty() {
+            return;
+        }
+        let files = mem::take(&mut self.relint_files);
+        for file in files {
+            if !self.linted_files.contains(&file) {
+                self.linted_files.push(file);
+            }
+        }
+        self.start_background_lint();
+    }
+
+    /// Badges the tree with the findings of the background lint, once it
+    /// is done, and says which of the files linted again have new findings.
+    pub(crate) fn receive_lint_results(&mut self) {
+        let Some(receiver) = &self.background_lint else {
+            return;
+        };
+        let findings = match receiver.try_recv() {
+            Ok(findings) => findings,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => Vec::new(),
+        };
+        self.background_lint = None;
+        let counts = count_findings(&findings);
+        let changed: Vec<(PathBuf, FindingCounts)> = mem::take(&mut self.linted_files)
+            .into_iter()
+            .map(|file| {
+                let now = counts.get(&file).copied().unwrap_or_default();
+                (file, now)
+            })
+            .filter(|(file, now)| {
+                file.is_file() && self.lint_counts.get(file).copied().unwrap_or_default() != *now
+            })
+            .collect();
+        self.lint_counts = counts;
+        self.rebuild_tree();
+        let report = match changed.as_slice() {
+            [] => return,
+            [(file, now)] => format!("Lint: {} has {}.", file.display(), findings_phrase(*now)),
+            _ => format!("Lint: findings changed in {} files.", changed.len()),
+        };
+        self.status_message = Some(match self.status_message.take() {
+            Some(message) => format!("{message} {report}"),
+            None => report,
+        });
+    }
+
+    /// Selects and shows the next file in the tree with lint findings,
+    /// wrapping around at the end.
+    pub(crate) fn jump_to_next_finding(&mut self) {
+        let files: Vec<PathBuf> = self
+            .discovered_files()
+            .into_iter()
+            .filter(|file| self.lint_counts.contains_key(file))
+            .collect();
+        if files.is_empty() {
+            self.status_message = Some(if self.background_lint.is_some() {
+                "Still linting…".to_string()
+            } else {
+                "No file has lint findings.".to_string()
+            });
+            return;
+        }
+        let all = self.discovered_files();
+        let current = self
+            .selected_file()
+            .and_then(|selected| all.iter().position(|file| *file == selected));
+        let next = files
+            .iter()
+            .find(|file| all.iter().position(|f| f == *file) > current)
+            .unwrap_or(&files[0])
+            .clone();
+        self.record_jump();
+        if self.select_tree_file(&next) {
+            self.load_selected_content();
+        }
+    }
+}
+
+/// `1 error and 2 warnings`, `1 warning`, or `no findings`.
+fn findings_phrase(counts: FindingCounts) -> String {
+    let plural = |n: usize, noun: &str| {
+        if n == 1 {
+            format!("1 {noun}")
+        } else {
+            format!("{n} {noun}s")
+        }
+    };
+    match (counts.errors, counts.warnings) {
+        (0, 0) => "no findings".to_string(),
+        (errors, 0) => plural(errors, "error"),
+        (0, warnings) => plural(warnings, "warning"),
+        (errors, warnings) => format!(
+            "{} and {}",
+            plural(errors, "error"),
+            plural(warnings, "warning")
+        ),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::test_helpers::key_event;
+
+    /// Ticks `app` until its background lint is done.
+    fn wait_for_lint(app: &mut App) {
+        let deadline = Instant::now() + Duration::from_secs(5);
+        app.tick(Instant::now());
+        while app.background_lint.is_some() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(5));
+            app.tick(Instant::now());
+        }
+    }
+
+    #[test]
+    fn files_with_findings_are_badged_and_bang_walks_through_them() {
+        let tmp = TempDir::new().unwrap();
+        let clean = tmp.path().join("CLAUDE.md");
+        let broken = tmp.path().join("a/CLAUDE.md");
+        let empty = tmp.path().join("b/CLAUDE.md");
+        fs::create_dir(tmp.path().join("a")).unwrap();
+        fs::create_dir(tmp.path().join("b")).unwrap();
+        fs::write(&clean, "# Clean\n").unwrap();
+        fs::write(&broken, "@missing.md\n").unwrap();
+        fs::write(&empty, "\n").unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![
+                DiscoveredFile::new(clean.clone()),
+                DiscoveredFile::new(broken.clone()),
+                DiscoveredFile::new(empty.clone()),
+            ],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+        app.select_tree_file(&clean);
+        app.load_selected_content();
+
+        app.start_background_lint();
+        wait_for_lint(&mut app);
+        assert_eq!(app.lint_counts[&broken].errors, 1);
+        assert_eq!(app.lint_counts[&empty].warnings, 1);
+        assert!(!app.lint_counts.contains_key(&clean));
+        let mut terminal = Terminal::new(TestBackend::new(100, 24)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let screen: String = terminal
+            .backend()
+            .buffer()
+            .content()
+            .iter()
+            .map(|cell| cell.symbol())
+            .collect();
+        assert!(screen.contains("✗1"));
+        assert!(screen.contains("!1"));
+
+        app.handle_key_event(key_event(KeyCode::Char('!')));
+        assert_eq!(app.selected_file(), Some(broken.clone()));
+        app.handle_key_event(key_event(KeyCode::Char('!')));
+        assert_eq!(app.selected_file(), Some(empty));
+        app.handle_key_event(key_event(KeyCode::Char('!')));
+        assert_eq!(app.selected_file(), Some(broken));
+    }
+
+    #[test]
+    fn a_file_jigolo_writes_is_linted_again_and_new_findings_are_reported() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "# Project\n").unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![DiscoveredFile::new(file.clone())],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+        app.start_background_lint();
+        wait_for_lint(&mut app);
+        assert!(app.lint_counts.is_empty());
+
+        app.apply_file_write(&file, "# Project\n@gone.md\n", "Wrote it.".to_string());
+        wait_for_lint(&mut app);
+
+        assert_eq!(app.lint_counts[&file].errors, 1);
+        assert_eq!(
+            app.status_message,
+            Some(format!("Wrote it. Lint: {} has 1 error.", file.display()))
+        );
+    }
+}
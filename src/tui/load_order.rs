@@ -0,0 +1,108 @@
+//! The load order header of the content pane: where the shown file sits
+//! among the memory files a session started in its directory reads, as
+//! `jigolo which` would list them. `L` hides and shows it.
+
+use std::path::Path;
+
+use super::app::App;
+use super::landing::display_path;
+use crate::session::simulate_session;
+use crate::session::start_dir_for;
+
+/// `n` as an English ordinal: `1st`, `2nd`, `11th`, `23rd`.
+pub fn ordinal(n: usize) -> String {
+    let suffix = match (n % 10, n % 100) {
+        (_, 11..=13) => "th",
+        (1, _) => "st",
+        (2, _) => "nd",
+        (3, _) => "rd",
+        _ => "th",
+    };
+    format!("{n}{suffix}")
+}
+
+/// The header line for `file`: `Loaded 3rd of 5 (after ~/.claude/CLAUDE.md,
+/// /repo/CLAUDE.md)`, or why a session does not load it.
+pub fn load_order_line(file: &Path) -> String {
+    let Some(dir) = start_dir_for(file) else {
+        return "Not loaded by any session".to_string();
+    };
+    let session = simulate_session(dir);
+    let Some(position) = session.files.iter().position(|loaded| loaded.path == file) else {
+        return format!("Not loaded by a session in {}", display_path(dir));
+    };
+    let count = session.files.len();
+    let mut line = format!("Loaded {} of {count}", ordinal(position + 1));
+    if position > 0 {
+        let before: Vec<String> = session.files[..position]
+            .iter()
+            .map(|loaded| display_path(&loaded.path))
+            .collect();
+        line.push_str(&format!(" (after {})", before.join(", ")));
+    }
+    line
+}
+
+impl App {
+    /// Works out the load order header of the shown file, when it is on.
+    pub(crate) fn update_load_order(&mut self) {
+        self.content.load_order = if self.show_load_order {
+            self.selected_file().map(|file| load_order_line(&file))
+        } else {
+            None
+        };
+    }
+
+    /// Shows or hides the load order header.
+    pub(crate) fn toggle_load_order(&mut self) {
+        self.show_load_order = !self.show_load_order;
+        self.update_load_order();
+        self.status_message = Some(if self.show_load_order {
+            "Load order shown.".to_string()
+        } else {
+            "Load order hidden.".to_string()
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use super::*;
+
+    #[test]
+    fn ordinals_take_the_english_suffix() {
+        let ordinals: Vec<String> = [1, 2, 3, 4, 11, 12, 13, 21, 22, 103]
+            .into_iter()
+            .map(ordinal)
+            .collect();
+        assert_eq!(
+            ordinals,
+            [
+                "1st", "2nd", "3rd", "4th", "11th", "12th", "13th", "21st", "22nd", "103rd"
+            ]
+        );
+    }
+
+    #[test]
+    fn the_line_names_the_files_loaded_before() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().canonicalize().unwrap();
+        let sub = root.join("sub");
+        fs::create_dir_all(&sub).unwrap();
+        fs::write(root.join("CLAUDE.md"), "top").unwrap();
+        fs::write(sub.join("CLAUDE.md"), "sub").unwrap();
+        fs::write(sub.join("AGENTS.md"), "agents").unwrap();
+
+        let line = load_order_line(&sub.join("CLAUDE.md"));
+        assert!(line.starts_with("Loaded "), "{line}");
+        assert!(
+            line.ends_with(&format!("{}/CLAUDE.md)", root.display())),
+            "{line}"
+        );
+        assert!(load_order_line(&sub.join("AGENTS.md")).starts_with("Not loaded by a session"));
+    }
+}
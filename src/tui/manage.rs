@@ -0,0 +1,353 @@
+use std::fs;
+use std::path::Path;
+
+use super::app::App;
+use super::app::Mode;
+use super::diff_preview::DiffPreview;
+use super::diff_preview::PREVIEW_CONTEXT_LINES;
+use super::diff_preview::PreviewAction;
+use super::new_file::resolve_under_root;
+use crate::diff::collapse_context;
+use crate::diff::diff_lines;
+use crate::discovery::DEFAULT_FILE_PATTERNS;
+use crate::discovery::canonical_name_for;
+use crate::journal::file_size;
+
+impl App {
+    /// Previews deleting the selected file as a diff removing every line.
+    pub(crate) fn start_delete_file(&mut self) {
+        let Some(file) = self.selected_file() else {
+            self.status_message = Some("Select a file to delete.".to_string());
+            return;
+        };
+        if !self.ensure_writable(&file, "delete") {
+            return;
+        }
+        let content = match fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(err) => {
+                self.status_message = Some(format!("Cannot read {}: {err}", file.display()));
+                return;
+            }
+        };
+        let title = format!("Delete {}? (y/n)", file.display());
+        self.open_diff_preview(DiffPreview::new(
+            title,
+            collapse_context(diff_lines(&content, ""), PREVIEW_CONTEXT_LINES),
+            PreviewAction::DeleteFile { file },
+        ));
+    }
+
+    /// Backs `file` up, deletes it and drops it from the tree, selecting the
+    /// next file of its root.
+    pub(crate) fn delete_file(&mut self, file: &Path) {
+        let warning = self.backup_before_write(file);
+        let before = file_size(file);
+        if let Err(err) = fs::remove_file(file) {
+            self.status_message = Some(format!("Delete failed: {err}"));
+            return;
+        }
+        self.journal_change("delete", file, before);
+        let mut roots = self.roots.clone();
+        for root in &mut roots {
+            root.files.retain(|f| f.path != file);
+            root.misnamed_files.retain(|f| f != file);
+        }
+        self.replace_roots(roots);
+        self.status_message = Some(match warning {
+            Some(warning) => format!("Deleted {} ({warning}).", file.display()),
+            None => format!(
+                "Deleted {}. Press b on its root to restore it.",
+                file.display()
+            ),
+        });
+    }
+
+    /// Prompts for a new path for the selected file, relative to its root.
+    pub(crate) fn start_move_file(&mut self) {
+        let (Some(file), Some(root)) = (self.selected_file(), self.selected_root()) else {
+            self.status_message = Some("Select a file to move.".to_string());
+            return;
+        };
+        if !self.ensure_writable(&file, "move") {
+            return;
+        }
+        let relative = file.strip_prefix(&root).unwrap_or(&file);
+        self.text_input.set(&relative.display().to_string());
+        self.mode = Mode::MovePath;
+    }
+
+    /// Moves the selected file to the typed path under its root, creating
+    /// missing directories, and selects it there.
+    pub(crate) fn submit_move_path(&mut self) {
+        let (Some(file), Some(root)) = (self.selected_file(), self.selected_root()) else {
+            self.reset_to_normal();
+            return;
+        };
+        let Some(target) = resolve_under_root(&root, self.text_input.text().trim()) else {
+            self.status_message =
+                Some("The new path must be inside the selected root.".to_string());
+            return;
+        };
+        if !self.is_scanned_name(&target) {
+            let patterns = &self.scan_options.patterns;
+            let names = if patterns.is_empty() {
+                DEFAULT_FILE_PATTERNS.join(" or ")
+            } else {
+                patterns.join(" or ")
+            };
+            self.status_message = Some(format!(
+                "The file must be named like {names} to stay in the tree."
+            ));
+            return;
+        }
+        if target == file {
+            self.reset_to_normal();
+            return;
+        }
+        if target.exists() {
+            self.status_message = Some(format!("{} already exists.", target.display()));
+            return;
+        }
+
+        self.reset_to_normal();
+        if let Err(err) = move_file(&file, &target) {
+            self.status_message = Some(format!("Move failed: {err}"));
+            return;
+        }
+        self.journal_move("move", &file, &target);
+        for root in &mut self.roots {
+            root.files.retain(|f| f.path != file);
+            root.misnamed_files.retain(|f| f != &file);
+        }
+        self.register_file(&target);
+        self.load_selected_content();
+        self.status_message = Some(format!("Moved {} to {}.", file.display(), target.display()));
+    }
+
+    /// Renames the selected misnamed file to the name Claude Code reads, in
+    /// the same directory, and selects it among the CLAUDE.md files. A file
+    /// already there under that name is left alone.
+    pub(crate) fn fix_misnamed_file(&mut self) {
+        let Some((file, canonical)) = self
+            .selected_file()
+            .and_then(|file| canonical_name_for(&file).map(|name| (file, name)))
+        else {
+            self.status_message = Some("Select a misnamed file to rename.".to_string());
+            return;
+        };
+        if !self.ensure_writable(&file, "rename") {
+            return;
+        }
+        let target = file.with_file_name(canonical);
+        if target.exists() {
+            self.status_message = Some(format!(
+                "{} already exists; merge the two by hand.",
+                target.display()
+            ));
+            return;
+        }
+        if let Err(err) = fs::rename(&file, &target) {
+            self.status_message = Some(format!("Rename failed: {err}"));
+            return;
+        }
+        self.journal_move("rename", &file, &target);
+        for root in &mut self.roots {
+            root.misnamed_files.retain(|f| f != &file);
+        }
+        self.register_file(&target);
+        self.load_selected_content();
+        self.status_message = Some(format!(
+            "Renamed {} to {canonical}; Claude Code reads it now.",
+            file.display()
+        ));
+    }
+}
+
+/// Renames `from` to `to`, creating the directories above `to` first.
+fn move_file(from: &Path, to: &Path) -> std::io::Result<()> {
+    if let Some(dir) = to.parent() {
+        fs::create_dir_all(dir)?;
+    }
+    fs::rename(from, to)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::test_helpers::key_event;
+
+    fn two_file_app(root: &Path) -> App {
+        let first = root.join("CLAUDE.md");
+        let second = root.join("sub/CLAUDE.md");
+        fs::create_dir_all(root.join("sub")).unwrap();
+        fs::write(&first, "first\n").unwrap();
+        fs::write(&second, "second\n").unwrap();
+        App::new(
+            vec![SourceRoot {
+                path: root.to_path_buf(),
+                files: vec![DiscoveredFile::new(first), DiscoveredFile::new(second)],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        )
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.handle_key_event(key_event(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn d_previews_then_deletes_and_selects_next_file() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = two_file_app(tmp.path());
+
+        app.handle_key_event(key_event(KeyCode::Char('D')));
+        assert_eq!(app.mode, Mode::DiffPreview);
+        assert!(tmp.path().join("CLAUDE.md").exists());
+
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert!(!tmp.path().join("CLAUDE.md").exists());
+        assert_eq!(
+            app.roots[0].file_paths().collect::<Vec<_>>(),
+            [&tmp.path().join("sub/CLAUDE.md")]
+        );
+        assert_eq!(app.selected_file(), Some(tmp.path().join("sub/CLAUDE.md")));
+        assert_eq!(app.content.text.as_deref(), Some("second\n"));
+    }
+
+    #[test]
+    fn rejecting_delete_keeps_file() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = two_file_app(tmp.path());
+
+        app.handle_key_event(key_event(KeyCode::Char('D')));
+        app.handle_key_event(key_event(KeyCode::Char('n')));
+
+        assert!(tmp.path().join("CLAUDE.md").exists());
+        assert_eq!(app.roots[0].files.len(), 2);
+    }
+
+    #[test]
+    fn shift_r_moves_file_into_new_directory() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = two_file_app(tmp.path());
+
+        app.handle_key_event(key_event(KeyCode::Char('R')));
+        assert_eq!(app.mode, Mode::MovePath);
+        assert_eq!(app.text_input.text(), "CLAUDE.md");
+        app.text_input.clear();
+        type_text(&mut app, "docs/CLAUDE.md");
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        let moved = tmp.path().join("docs/CLAUDE.md");
+        assert_eq!(fs::read_to_string(&moved).unwrap(), "first\n");
+        assert!(!tmp.path().join("CLAUDE.md").exists());
+        assert_eq!(app.selected_file(), Some(moved.clone()));
+        assert!(app.roots[0].has_file(&moved));
+        assert_eq!(app.roots[0].files.len(), 2);
+    }
+
+    #[test]
+    fn move_refuses_undiscoverable_names_and_existing_files() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = two_file_app(tmp.path());
+        app.handle_key_event(key_event(KeyCode::Char('R')));
+
+        app.text_input.set("notes.md");
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::MovePath);
+
+        app.text_input.set("sub/CLAUDE.md");
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::MovePath);
+        assert!(tmp.path().join("CLAUDE.md").exists());
+    }
+
+    #[test]
+    fn move_accepts_names_the_scan_patterns_match() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = two_file_app(tmp.path());
+        app.scan_options.patterns = vec!["AGENTS.md".to_string()];
+        app.handle_key_event(key_event(KeyCode::Char('R')));
+
+        app.text_input.set("CLAUDE.local.md");
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::MovePath);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("The file must be named like AGENTS.md to stay in the tree.")
+        );
+
+        app.text_input.set("AGENTS.md");
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(tmp.path().join("AGENTS.md").exists());
+        assert_eq!(app.selected_file(), Some(tmp.path().join("AGENTS.md")));
+    }
+
+    #[test]
+    fn n_renames_misnamed_file_to_claude_md() {
+        let tmp = TempDir::new().unwrap();
+        let misnamed = tmp.path().join("sub/claude.md");
+        fs::create_dir_all(tmp.path().join("sub")).unwrap();
+        fs::write(&misnamed, "rules\n").unwrap();
+        let mut app = App::new(
+            vec![SourceRoot {
+                path: tmp.path().to_path_buf(),
+                files: Vec::new(),
+                context_files: Vec::new(),
+                misnamed_files: vec![misnamed.clone()],
+                truncated: false,
+            }],
+            &Config::default(),
+        );
+        assert!(app.select_tree_file(&misnamed));
+        app.load_selected_content();
+        assert_eq!(app.findings[0].rule, crate::lint::RULE_MISNAMED_FILE);
+
+        app.handle_key_event(key_event(KeyCode::Char('N')));
+
+        let fixed = tmp.path().join("sub/CLAUDE.md");
+        assert_eq!(fs::read_to_string(&fixed).unwrap(), "rules\n");
+        assert!(app.roots[0].misnamed_files.is_empty());
+        assert_eq!(app.roots[0].file_paths().collect::<Vec<_>>(), [&fixed]);
+        assert_eq!(app.selected_file(), Some(fixed));
+        assert!(app.findings.is_empty());
+    }
+
+    #[test]
+    fn fixing_a_name_keeps_an_existing_claude_md() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = two_file_app(tmp.path());
+        let backup = tmp.path().join("CLAUDE.md.bak");
+        fs::write(&backup, "old\n").unwrap();
+        app.roots[0].misnamed_files.push(backup.clone());
+        app.rebuild_tree();
+        assert!(app.select_tree_file(&backup));
+
+        app.handle_key_event(key_event(KeyCode::Char('N')));
+
+        assert!(backup.exists());
+        assert_eq!(
+            fs::read_to_string(tmp.path().join("CLAUDE.md")).unwrap(),
+            "first\n"
+        );
+        assert!(app.status_message.unwrap().contains("already exists"));
+    }
+}
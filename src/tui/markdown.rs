@@ -0,0 +1,266 @@
+//! Rendered view of a Markdown file for the split preview.
+//!
+//! Each source line becomes exactly one rendered line, so the raw and the
+//! rendered pane scroll together by sharing one offset. Markup is dropped
+//! and styled instead: headings lose their `#`s, list markers become
+//! bullets, and inline code, emphasis and links get their own styles.
+
+use std::mem;
+
+use ratatui::style::Modifier;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::text::Text;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Paragraph;
+
+use super::theme::Theme;
+
+/// Renders `text` into one styled line per source line, `width` columns
+/// wide for rules.
+pub fn render_markdown(text: &str, theme: &Theme, width: u16) -> Vec<Line<'static>> {
+    let code = theme.help_desc;
+    let mut in_fence = false;
+    text.lines()
+        .map(|line| {
+            let trimmed = line.trim_start();
+            if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+                in_fence = !in_fence;
+                let label = if in_fence {
+                    trimmed.trim_start_matches(['`', '~']).trim()
+                } else {
+                    ""
+                };
+                return Line::styled(rule(label, width), code);
+            }
+            if in_fence {
+                return Line::styled(line.to_string(), code);
+            }
+            render_line(line, theme, width)
+        })
+        .collect()
+}
+
+/// The preview pane showing `height` of the rendered `lines` from `top`,
+/// with the line at `cursor` highlighted.
+pub fn preview_pane<'a>(
+    lines: &[Line<'a>],
+    top: usize,
+    height: usize,
+    cursor: Option<usize>,
+    theme: &Theme,
+) -> Paragraph<'a> {
+    let first = top.min(lines.len());
+    let last = (first + height).min(lines.len());
+    let visible: Vec<Line> = lines[first..last]
+        .iter()
+        .zip(first..)
+        .map(|(line, i)| {
+            if cursor == Some(i) {
+                line.clone().style(theme.highlight)
+            } else {
+                line.clone()
+            }
+        })
+        .collect();
+    Paragraph::new(Text::from(visible)).block(
+        Block::default()
+            .borders(Borders::ALL)
+            .border_style(theme.inactive_border)
+            .title("Preview"),
+    )
+}
+
+/// One line outside code blocks.
+fn render_line(line: &str, theme: &Theme, width: u16) -> Line<'static> {
+    let trimmed = line.trim_start();
+    let indent = &line[..line.len() - trimmed.len()];
+
+    let level = trimmed.chars().take_while(|&c| c == '#').count();
+    let after_hashes = &trimmed[level..];
+    if (1..=6).contains(&level) && (after_hashes.is_empty() || after_hashes.starts_with(' ')) {
+        let mut style = theme.active_tab.add_modifier(Modifier::BOLD);
+        if level == 1 {
+            style = style.add_modifier(Modifier::UNDERLINED);
+        }
+        let title = after_hashes.trim().trim_end_matches('#').trim_end();
+        return Line::from(inline_spans(title, style, theme));
+    }
+
+    if is_rule(trimmed) {
+        return Line::styled(rule("", width), theme.inactive_border);
+    }
+
+    if let Some(quote) = trimmed.strip_prefix('>') {
+        let style = theme.help_desc.add_modifier(Modifier::ITALIC);
+        let mut spans = vec![Span::styled(format!("{indent}│ "), theme.help_desc)];
+        spans.extend(inline_spans(quote.trim_start(), style, theme));
+        return Line::from(spans);
+    }
+
+    if let Some(item) = ["- ", "* ", "+ "]
+        .iter()
+        .find_map(|marker| trimmed.strip_prefix(marker))
+    {
+        let mut spans = vec![Span::raw(indent.to_string())];
+        spans.push(Span::styled("• ", theme.active_border));
+        spans.extend(inline_spans(item, Style::default(), theme));
+        return Line::from(spans);
+    }
+
+    Line::from(inline_spans(line, Style::default(), theme))
+}
+
+/// A thematic break: three or more `-`, `*` or `_`, with optional spaces.
+fn is_rule(trimmed: &str) -> bool {
+    let marks: Vec<char> = trimmed.chars().filter(|c| !c.is_whitespace()).collect();
+    marks.len() >= 3
+        && ['-', '*', '_']
+            .iter()
+            .any(|mark| marks.iter().all(|c| c == mark))
+}
+
+/// A horizontal line `width` wide, with `label` near its start.
+fn rule(label: &str, width: u16) -> String {
+    let width = usize::from(width);
+    if label.is_empty() {
+        return "─".repeat(width);
+    }
+    let used = label.chars().count() + 3;
+    format!("─ {label} {}", "─".repeat(width.saturating_sub(used)))
+}
+
+/// Spans for inline Markdown in `text`: `code`, **bold**, *emphasis* or
+/// _emphasis_, and [links](url), which keep only their text. Anything else
+/// is shown in `base`.
+fn inline_spans(text: &str, base: Style, theme: &Theme) -> Vec<Span<'static>> {
+    let mut spans = Vec::new();
+    let mut plain = String::new();
+    let mut rest = text;
+    while let Some(c) = rest.chars().next() {
+        let styled = if c == '`' {
+            delimited(rest, "`").map(|(inner, len)| (inner, theme.input_border, len))
+        } else if rest.starts_with("**") || rest.starts_with("__") {
+            delimited(rest, &rest[..2])
+                .map(|(inner, len)| (inner, base.add_modifier(Modifier::BOLD), len))
+        } else if (c == '*' || c == '_') && !plain.ends_with(|p: char| p.is_alphanumeric()) {
+            delimited(rest, &rest[..1])
+                .map(|(inner, len)| (inner, base.add_modifier(Modifier::ITALIC), len))
+        } else if c == '[' {
+            link(rest).map(|(inner, len)| (inner, base.add_modifier(Modifier::UNDERLINED), len))
+        } else {
+            None
+        };
+        match styled {
+            Some((inner, style, len)) => {
+                if !plain.is_empty() {
+                    spans.push(Span::styled(mem::take(&mut plain), base));
+                }
+                spans.push(Span::styled(inner.to_string(), style));
+                rest = &rest[len..];
+            }
+            None => {
+                plain.push(c);
+                rest = &rest[c.len_utf8()..];
+            }
+        }
+    }
+    if !plain.is_empty() {
+        spans.push(Span::styled(plain, base));
+    }
+    spans
+}
+
+/// The text between `mark` at the start of `text` and the next `mark`, and
+/// the length of the whole run. `None` when unclosed, empty or padded with
+/// spaces, as in `2 * 3 * 4`.
+fn delimited<'a>(text: &'a str, mark: &str) -> Option<(&'a str, usize)> {
+    let after = &text[mark.len()..];
+    let end = after.find(mark)?;
+    let inner = &after[..end];
+    if inner.is_empty() || (mark != "`" && (inner.starts_with(' ') || inner.ends_with(' '))) {
+        return None;
+    }
+    Some((inner, mark.len() * 2 + end))
+}
+
+/// The text of a `[text](url)` link at the start of `text`, and the length
+/// of the whole link.
+fn link(text: &str) -> Option<(&str, usize)> {
+    let close = text.find("](")?;
+    let url_len = text[close + 2..].find(')')?;
+    let inner = &text[1..close];
+    if inner.is_empty() || inner.contains('[') {
+        return None;
+    }
+    Some((inner, close + 2 + url_len + 1))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn plain(lines: &[Line]) -> Vec<String> {
+        lines.iter().map(|line| line.to_string()).collect()
+    }
+
+    #[test]
+    fn every_source_line_gives_one_rendered_line() {
+        let text = "# Title\n\n- one\n  * two\n> note\n---\n```sh\n# not a heading\n```\n";
+        let lines = render_markdown(text, &Theme::dark(), 12);
+
+        assert_eq!(lines.len(), text.lines().count());
+        assert_eq!(
+            plain(&lines),
+            [
+                "Title",
+                "",
+                "• one",
+                "  • two",
+                "│ note",
+                "────────────",
+                "─ sh ───────",
+                "# not a heading",
+                "────────────",
+            ]
+        );
+        assert!(
+            lines[0].spans[0]
+                .style
+                .add_modifier
+                .contains(Modifier::BOLD)
+        );
+    }
+
+    #[test]
+    fn inline_markup_is_styled_and_dropped() {
+        let theme = Theme::dark();
+        let spans = inline_spans(
+            "Run `make` **now**, see [docs](https://x.y) and _why_",
+            Style::default(),
+            &theme,
+        );
+        let text: String = spans.iter().map(|span| span.content.as_ref()).collect();
+
+        assert_eq!(text, "Run make now, see docs and why");
+        assert_eq!(spans[1].style, theme.input_border);
+        assert!(spans[3].style.add_modifier.contains(Modifier::BOLD));
+        assert!(spans[5].style.add_modifier.contains(Modifier::UNDERLINED));
+        assert!(spans[7].style.add_modifier.contains(Modifier::ITALIC));
+    }
+
+    #[test]
+    fn stray_markers_are_left_alone() {
+        let spans = inline_spans(
+            "snake_case_name and 2 * 3 * 4",
+            Style::default(),
+            &Theme::dark(),
+        );
+        let text: String = spans.iter().map(|span| span.content.as_ref()).collect();
+
+        assert_eq!(text, "snake_case_name and 2 * 3 * 4");
+        assert_eq!(spans.len(), 1);
+    }
+}
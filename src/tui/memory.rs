@@ -0,0 +1,653 @@
+use std::fmt;
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use super::app::App;
+use super::app::Pane;
+use super::diff_preview::DiffPreview;
+use super::diff_preview::PREVIEW_CONTEXT_LINES;
+use super::diff_preview::PreviewAction;
+use super::picker::Picker;
+use super::picker::PickerAction;
+use super::picker::PickerItem;
+use crate::compose::append_to_section;
+use crate::compose::insert_snippet;
+use crate::compose::snippet_block;
+use crate::diff::collapse_context;
+use crate::diff::diff_lines;
+use crate::memory::append_memory;
+use crate::outline::parse_headings;
+
+/// Most snippet inserts kept in [`App::insert_history`].
+pub const MAX_INSERT_HISTORY: usize = 5;
+
+/// Where in a file a snippet was put, so it can go in the same kind of
+/// place in another file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InsertPlace {
+    /// After the content cursor.
+    AfterCursor,
+    /// At the end of the file.
+    End,
+    /// At the end of the section under the heading with this title, or at
+    /// the end of a file without one.
+    UnderHeading(String),
+}
+
+impl fmt::Display for InsertPlace {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InsertPlace::AfterCursor => write!(f, "after the cursor"),
+            InsertPlace::End => write!(f, "at the end"),
+            InsertPlace::UnderHeading(title) => write!(f, "under \"{title}\""),
+        }
+    }
+}
+
+/// A library snippet put into a file, by title, and where it went.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SnippetInsert {
+    pub title: String,
+    pub place: InsertPlace,
+}
+
+impl App {
+    /// Asks which CLAUDE.md the selected library snippet should be appended
+    /// to as a memory entry.
+    pub(crate) fn start_memory_export(&mut self) {
+        let has_snippet = self
+            .library
+            .as_ref()
+            .is_some_and(|lib| self.library_selected < lib.snippets.len());
+        if !has_snippet {
+            self.status_message = Some("No snippet selected.".to_string());
+            return;
+        }
+
+        let files: Vec<PathBuf> = self
+            .roots
+            .iter()
+            .flat_map(|root| root.file_paths().cloned())
+            .collect();
+        if files.is_empty() {
+            self.status_message =
+                Some("No CLAUDE.md files loaded. Start jigolo on a project first.".to_string());
+            return;
+        }
+
+        // Start on the file open in the Files screen, the likeliest target.
+        let current = self.current_source_path();
+        let cursor = files
+            .iter()
+            .position(|f| Path::new(&current) == f)
+            .unwrap_or(0);
+        let items = files
+            .iter()
+            .map(|f| PickerItem::new(f.display().to_string(), ""))
+            .collect();
+        let mut picker = Picker::new(
+            format!(
+                "Append as memory under \"{}\"",
+                self.config.memory_heading()
+            ),
+            items,
+            PickerAction::MemoryTarget {
+                files,
+                snippet: self.library_selected,
+            },
+        );
+        picker.cursor = cursor;
+        self.open_picker(picker);
+    }
+
+    /// Previews appending snippet `snippet` to `file` under the configured
+    /// memory heading.
+    pub(crate) fn preview_memory_append(&mut self, file: PathBuf, snippet: usize) {
+        if !self.ensure_writable(&file, "append to") {
+            return;
+        }
+        let Some(snippet) = self
+            .library
+            .as_ref()
+            .and_then(|lib| lib.snippets.get(snippet))
+        else {
+            return;
+        };
+        let current = match fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(err) => {
+                self.status_message = Some(format!("Cannot read {}: {err}", file.display()));
+                return;
+            }
+        };
+
+        let updated = append_memory(&current, self.config.memory_heading(), &snippet.content);
+        let success = format!("Added \"{}\" to {}.", snippet.title, file.display());
+        let title = format!("Append memory to {}? (y/n)", file.display());
+        self.open_diff_preview(DiffPreview::new(
+            title,
+            collapse_context(diff_lines(&current, &updated), PREVIEW_CONTEXT_LINES),
+            PreviewAction::WriteFile {
+                file,
+                content: updated,
+                success,
+            },
+        ));
+    }
+
+    /// Asks which discovered file the selected library snippet should be
+    /// appended to.
+    pub(crate) fn start_snippet_append(&mut self) {
+        let Some(snippet) = self
+            .library
+            .as_ref()
+            .and_then(|lib| lib.snippets.get(self.library_selected))
+        else {
+            self.status_message = Some("No snippet selected.".to_string());
+            return;
+        };
+        let files = self.discovered_files();
+        if files.is_empty() {
+            self.status_message =
+                Some("No CLAUDE.md files loaded. Start jigolo on a project first.".to_string());
+            return;
+        }
+        let items = files
+            .iter()
+            .map(|f| PickerItem::new(f.display().to_string(), ""))
+            .collect();
+        self.open_picker(Picker::new(
+            format!("Append \"{}\" to", snippet.title),
+            items,
+            PickerAction::AppendTarget {
+                files,
+                snippet: self.library_selected,
+            },
+        ));
+    }
+
+    /// Asks under which heading of `file` snippet `snippet` goes, or at the
+    /// end of the file.
+    pub(crate) fn choose_append_heading(&mut self, file: PathBuf, snippet: usize) {
+        if !self.ensure_writable(&file, "append to") {
+            return;
+        }
+        let content = match fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(err) => {
+                self.status_message = Some(format!("Cannot read {}: {err}", file.display()));
+                return;
+            }
+        };
+        let headings = parse_headings(&content);
+        let mut items = vec![PickerItem::new("(end of file)", "")];
+        let mut lines = vec![None];
+        for heading in headings {
+            items.push(PickerItem::new(
+                format!(
+                    "{}{} {}",
+                    "  ".repeat(heading.level - 1),
+                    "#".repeat(heading.level),
+                    heading.title
+                ),
+                format!("line {}", heading.line + 1),
+            ));
+            lines.push(Some(heading.line));
+        }
+        self.open_picker(Picker::new(
+            format!("Append under which heading of {}?", file.display()),
+            items,
+            PickerAction::AppendUnder {
+                file,
+                snippet,
+                lines,
+            },
+        ));
+    }
+
+    /// Previews appending snippet `snippet` to `file` at the end of the
+    /// section whose heading is on `heading_line`, or at the end of the file.
+    pub(crate) fn preview_snippet_append(
+        &mut self,
+        file: PathBuf,
+        snippet: usize,
+        heading_line: Option<usize>,
+    ) {
+        let Some(snippet) = self
+            .library
+            .as_ref()
+            .and_then(|lib| lib.snippets.get(snippet))
+        else {
+            return;
+        };
+        let current = match fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(err) => {
+                self.status_message = Some(format!("Cannot read {}: {err}", file.display()));
+                return;
+            }
+        };
+        let updated = append_to_section(&current, heading_line, &snippet_block(snippet));
+        let place = heading_line
+            .and_then(|line| {
+                parse_headings(&current)
+                    .into_iter()
+                    .find(|heading| heading.line == line)
+            })
+            .map_or(InsertPlace::End, |heading| {
+                InsertPlace::UnderHeading(heading.title)
+            });
+        let insert = SnippetInsert {
+            title: snippet.title.clone(),
+            place,
+        };
+        let success = format!("Appended \"{}\" to {}.", snippet.title, file.display());
+        let title = format!("Append snippet to {}? (y/n)", file.display());
+        self.note_insert(insert);
+        self.open_diff_preview(DiffPreview::new(
+            title,
+            collapse_context(diff_lines(&current, &updated), PREVIEW_CONTEXT_LINES),
+            PreviewAction::WriteFile {
+                file,
+                content: updated,
+                success,
+            },
+        ));
+    }
+
+    /// Previews inserting library snippet `snippet` into the file open on
+    /// the Files screen: after the content cursor if the content pane has
+    /// focus there, otherwise at the end of the file.
+    pub(crate) fn preview_snippet_insert(&mut self, snippet: usize) {
+        if let Some(file) = self.selected_file()
+            && !self.ensure_writable(&file, "insert into")
+        {
+            return;
+        }
+        let Some(snippet) = self
+            .library
+            .as_ref()
+            .and_then(|lib| lib.snippets.get(snippet))
+        else {
+            self.status_message = Some("No snippet selected.".to_string());
+            return;
+        };
+        let Some(file) = self.selected_file() else {
+            self.status_message = Some("Open a CLAUDE.md on the Files screen first.".to_string());
+            return;
+        };
+        let current = match fs::read_to_string(&file) {
+            Ok(content) => content,
+            Err(err) => {
+                self.status_message = Some(format!("Cannot read {}: {err}", file.display()));
+                return;
+            }
+        };
+
+        let after_cursor = self.active_pane == Pane::Content;
+        // The cursor is a line of the file only while the pane shows the
+        // file as it is.
+        if after_cursor && !self.content.shows_file_as_is() {
+            self.status_message = Some(
+                "Show the whole file, with its comments and imports collapsed, to insert at the cursor."
+                    .to_string(),
+            );
+            return;
+        }
+        let after_line = after_cursor.then_some(self.content.cursor);
+        let updated = insert_snippet(&current, after_line, &snippet_block(snippet));
+        let place = match after_line {
+            Some(line) => format!("after line {}", line + 1),
+            None => "at the end".to_string(),
+        };
+        let insert = SnippetInsert {
+            title: snippet.title.clone(),
+            place: match after_line {
+                Some(_) => InsertPlace::AfterCursor,
+                None => InsertPlace::End,
+            },
+        };
+        let success = format!("Inserted \"{}\" into {}.", snippet.title, file.display());
+        let title = format!("Insert snippet {place} of {}? (y/n)", file.display());
+        self.note_insert(insert);
+        self.open_diff_preview(DiffPreview::new(
+            title,
+            collapse_context(diff_lines(&current, &updated), PREVIEW_CONTEXT_LINES),
+            PreviewAction::WriteFile {
+                file,
+                content: updated,
+                success,
+            },
+        ));
+    }
+
+    /// Moves `insert` to the front of the insert history.
+    fn note_insert(&mut self, insert: SnippetInsert) {
+        self.insert_history.retain(|i| *i != insert);
+        self.insert_history.insert(0, insert);
+        self.insert_history.truncate(MAX_INSERT_HISTORY);
+    }
+
+    /// Previews putting the snippet of insert `index` (0 for the last one)
+    /// into the selected file again, in the same kind of place.
+    pub(crate) fn repeat_insert(&mut self, index: usize) {
+        let Some(insert) = self.insert_history.get(index).cloned() else {
+            self.status_message = Some("No snippet inserted yet.".to_string());
+            return;
+        };
+        let Some(file) = self.selected_file() else {
+            self.status_message = Some("Open a CLAUDE.md on the Files screen first.".to_string());
+            return;
+        };
+        let Some(snippet) = self.library.as_ref().and_then(|lib| {
+            lib.snippets
+                .iter()
+                .position(|snippet| snippet.title == insert.title)
+        }) else {
+            self.status_message =
+                Some(format!("\"{}\" is no longer in the library.", insert.title));
+            return;
+        };
+        if !self.ensure_writable(&file, "insert into") {
+            return;
+        }
+        match insert.place {
+            InsertPlace::AfterCursor => self.preview_snippet_insert(snippet),
+            InsertPlace::End => self.preview_snippet_append(file, snippet, None),
+            InsertPlace::UnderHeading(title) => {
+                let line = fs::read_to_string(&file).ok().and_then(|content| {
+                    parse_headings(&content)
+                        .into_iter()
+                        .find(|heading| heading.title == title)
+                        .map(|heading| heading.line)
+                });
+                self.preview_snippet_append(file, snippet, line);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+    use std::path::PathBuf;
+
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::library::Snippet;
+    use crate::library::SnippetFormat;
+    use crate::library::SnippetLibrary;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::Pane;
+    use crate::tui::app::Screen;
+    use crate::tui::app::test_helpers::key_event;
+
+    fn library_app(root: &Path, files: Vec<PathBuf>, config: &Config) -> App {
+        let mut app = App::new(
+            vec![SourceRoot {
+                path: root.to_path_buf(),
+                files: files.into_iter().map(DiscoveredFile::new).collect(),
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            config,
+        );
+        app.library = Some(SnippetLibrary {
+            snippets: vec![Snippet {
+                title: "Tests".to_string(),
+                content: "Run cargo test before committing".to_string(),
+                source: String::new(),
+                format: None,
+                archived: false,
+                tags: Vec::new(),
+                lines: None,
+                created: None,
+                scope: Default::default(),
+            }],
+        });
+        app.screen = Screen::Library;
+        app
+    }
+
+    #[test]
+    fn m_appends_snippet_under_memory_heading_after_preview() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "# Project\n").unwrap();
+        let mut app = library_app(tmp.path(), vec![file.clone()], &Config::default());
+
+        app.handle_key_event(key_event(KeyCode::Char('m')));
+        assert_eq!(app.mode, Mode::Picker);
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::DiffPreview);
+        assert_eq!(fs::read_to_string(&file).unwrap(), "# Project\n");
+
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "# Project\n\n# Memories\n\n- Run cargo test before committing\n"
+        );
+    }
+
+    #[test]
+    fn p_appends_snippet_under_the_chosen_heading_of_the_chosen_file() {
+        let tmp = TempDir::new().unwrap();
+        let a = tmp.path().join("CLAUDE.md");
+        let b = tmp.path().join("sub/CLAUDE.md");
+        fs::create_dir_all(tmp.path().join("sub")).unwrap();
+        fs::write(&a, "# A\n").unwrap();
+        fs::write(&b, "# Rules\n- tabs\n\n# Build\nmake\n").unwrap();
+        let mut app = library_app(tmp.path(), vec![a.clone(), b.clone()], &Config::default());
+
+        app.handle_key_event(key_event(KeyCode::Char('p')));
+        assert_eq!(app.mode, Mode::Picker);
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::Picker);
+        assert_eq!(app.picker.as_ref().unwrap().items.len(), 3);
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::DiffPreview);
+        assert_eq!(
+            fs::read_to_string(&b).unwrap(),
+            "# Rules\n- tabs\n\n# Build\nmake\n"
+        );
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert_eq!(
+            fs::read_to_string(&b).unwrap(),
+            "# Rules\n- tabs\n\nRun cargo test before committing\n\n# Build\nmake\n"
+        );
+        assert_eq!(fs::read_to_string(&a).unwrap(), "# A\n");
+    }
+
+    #[test]
+    fn ampersand_repeats_the_last_append_under_the_same_heading() {
+        let tmp = TempDir::new().unwrap();
+        let a = tmp.path().join("CLAUDE.md");
+        let b = tmp.path().join("sub/CLAUDE.md");
+        fs::create_dir_all(tmp.path().join("sub")).unwrap();
+        fs::write(
+            &a,
+            "# Build
+make
+",
+        )
+        .unwrap();
+        fs::write(
+            &b,
+            "# Intro
+
+# Build
+cargo build
+
+# Style
+",
+        )
+        .unwrap();
+        let mut app = library_app(tmp.path(), vec![a.clone(), b.clone()], &Config::default());
+        app.handle_key_event(key_event(KeyCode::Char('p')));
+        app.handle_key_event(key_event(KeyCode::Enter));
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.handle_key_event(key_event(KeyCode::Enter));
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        app.show_file(&b);
+        app.handle_key_event(key_event(KeyCode::Char('&')));
+        assert_eq!(app.mode, Mode::DiffPreview);
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert_eq!(
+            fs::read_to_string(&b).unwrap(),
+            "# Intro
+
+# Build
+cargo build
+
+Run cargo test before committing
+
+# Style
+"
+        );
+        assert_eq!(app.insert_history.len(), 1);
+    }
+
+    #[test]
+    fn configured_heading_is_used() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "## Notes\n- old\n").unwrap();
+        let config = Config {
+            memory_heading: Some("## Notes".to_string()),
+            ..Config::default()
+        };
+        let mut app = library_app(tmp.path(), vec![file.clone()], &config);
+
+        app.handle_key_event(key_event(KeyCode::Char('m')));
+        app.handle_key_event(key_event(KeyCode::Enter));
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "## Notes\n- old\n- Run cargo test before committing\n"
+        );
+    }
+
+    #[test]
+    fn no_loaded_files_shows_status() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = library_app(tmp.path(), vec![], &Config::default());
+
+        app.handle_key_event(key_event(KeyCode::Char('m')));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(
+            app.status_message
+                .as_deref()
+                .unwrap()
+                .contains("No CLAUDE.md")
+        );
+    }
+
+    #[test]
+    fn i_inserts_snippet_after_content_cursor() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "# Project\nBody\n").unwrap();
+        let mut app = library_app(tmp.path(), vec![file.clone()], &Config::default());
+        app.active_pane = Pane::Content;
+
+        app.handle_key_event(key_event(KeyCode::Char('i')));
+        assert_eq!(app.mode, Mode::DiffPreview);
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "# Project\n\nRun cargo test before committing\n\nBody\n"
+        );
+    }
+
+    #[test]
+    fn inserting_at_the_cursor_needs_the_file_shown_as_it_is() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "<!-- note -->\n# Project\nBody\n").unwrap();
+        let mut app = library_app(tmp.path(), vec![file.clone()], &Config::default());
+        app.hide_comments = true;
+        app.load_selected_content();
+        app.active_pane = Pane::Content;
+        app.content.cursor = 1;
+
+        app.handle_key_event(key_event(KeyCode::Char('i')));
+
+        assert_ne!(app.mode, Mode::DiffPreview);
+        assert!(
+            app.status_message
+                .as_deref()
+                .unwrap()
+                .starts_with("Show the whole file")
+        );
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "<!-- note -->\n# Project\nBody\n"
+        );
+    }
+
+    #[test]
+    fn enter_appends_when_tree_has_focus() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "# Project\nBody\n").unwrap();
+        let mut app = library_app(tmp.path(), vec![file.clone()], &Config::default());
+
+        app.handle_key_event(key_event(KeyCode::Enter));
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "# Project\nBody\n\nRun cargo test before committing\n"
+        );
+    }
+
+    #[test]
+    fn shell_snippet_is_inserted_in_a_code_fence() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "# Project\n").unwrap();
+        let mut app = library_app(tmp.path(), vec![file.clone()], &Config::default());
+        app.library.as_mut().unwrap().snippets[0].format = Some(SnippetFormat::Shell);
+
+        app.handle_key_event(key_event(KeyCode::Char('i')));
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "# Project\n\n```sh\nRun cargo test before committing\n```\n"
+        );
+    }
+
+    #[test]
+    fn insert_without_open_file_shows_status() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = library_app(tmp.path(), vec![], &Config::default());
+
+        app.handle_key_event(key_event(KeyCode::Char('i')));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(
+            app.status_message
+                .as_deref()
+                .unwrap()
+                .contains("Files screen")
+        );
+    }
+}
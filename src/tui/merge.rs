@@ -0,0 +1,418 @@
+//! Guided merge of two CLAUDE.md files: sections paired by heading, one
+//! row each, with the two versions of the section under the cursor side by
+//! side. The merged result replaces the first file or goes to a new one.
+
+use std::fs;
+use std::path::PathBuf;
+
+use ratatui::Frame;
+use ratatui::layout::Constraint;
+use ratatui::layout::Layout;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::text::Text;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+
+use super::action::Action;
+use super::action::Effect;
+use super::app::App;
+use super::app::Mode;
+use super::diff_preview::DiffPreview;
+use super::diff_preview::PREVIEW_CONTEXT_LINES;
+use super::diff_preview::PreviewAction;
+use super::picker::Picker;
+use super::picker::PickerAction;
+use super::picker::PickerItem;
+use super::picker::centered_rect;
+use crate::diff::collapse_context;
+use crate::diff::diff_lines;
+use crate::fsutil::expand_home;
+use crate::fsutil::write_atomic;
+use crate::merge::MergeChoice;
+use crate::merge::MergeItem;
+use crate::merge::SectionStatus;
+use crate::merge::align_sections;
+use crate::merge::merged_text;
+
+/// The merge of `b` into `a`, open while in `Mode::Merge`.
+#[derive(Debug)]
+pub struct MergeView {
+    pub a: PathBuf,
+    pub b: PathBuf,
+    /// Content of `a` the sections were taken from.
+    pub a_text: String,
+    pub items: Vec<MergeItem>,
+    pub cursor: usize,
+}
+
+impl MergeView {
+    /// The merged file for the choices made so far.
+    pub fn merged(&self) -> String {
+        merged_text(&self.items)
+    }
+}
+
+impl App {
+    /// Asks which file to merge into the selected one.
+    pub(crate) fn start_merge(&mut self) {
+        let Some(file) = self.selected_file() else {
+            self.status_message = Some("Select a file to merge into.".to_string());
+            return;
+        };
+        let others: Vec<PathBuf> = self
+            .discovered_files()
+            .into_iter()
+            .filter(|other| *other != file)
+            .collect();
+        if others.is_empty() {
+            self.status_message = Some("No other file to merge with.".to_string());
+            return;
+        }
+        let items = others
+            .iter()
+            .map(|other| PickerItem::new(other.display().to_string(), ""))
+            .collect();
+        self.open_picker(Picker::new(
+            format!("Merge into {}", file.display()),
+            items,
+            PickerAction::MergeWith {
+                file,
+                files: others,
+            },
+        ));
+    }
+
+    /// Pairs the sections of `a` and `b` and shows them.
+    pub fn open_merge(&mut self, a: PathBuf, b: PathBuf) {
+        let read = |path: &PathBuf| {
+            fs::read_to_string(path).map_err(|err| format!("Cannot read {}: {err}", path.display()))
+        };
+        let (a_text, b_text) = match (read(&a), read(&b)) {
+            (Ok(a_text), Ok(b_text)) => (a_text, b_text),
+            (Err(err), _) | (_, Err(err)) => {
+                self.status_message = Some(err);
+                return;
+            }
+        };
+        let items = align_sections(&a_text, &b_text);
+        let conflicts = items
+            .iter()
+            .filter(|item| item.status() == SectionStatus::Conflict)
+            .count();
+        self.status_message = Some(match conflicts {
+            0 => "No conflicting sections.".to_string(),
+            1 => "1 section differs: pick a, b or both.".to_string(),
+            n => format!("{n} sections differ: pick a, b or both."),
+        });
+        self.merge_view = Some(MergeView {
+            a,
+            b,
+            a_text,
+            items,
+            cursor: 0,
+        });
+        self.mode = Mode::Merge;
+    }
+
+    /// Draws the section list over the two versions of the section under
+    /// the cursor.
+    pub(crate) fn draw_merge(&self, frame: &mut Frame, area: Rect) {
+        let Some(view) = &self.merge_view else {
+            return;
+        };
+        let popup = centered_rect(90, area.height.saturating_sub(2), area);
+        frame.render_widget(Clear, popup);
+        let [list_area, sides_area] =
+            Layout::vertical([Constraint::Percentage(40), Constraint::Percentage(60)]).areas(popup);
+
+        let viewport = list_area.height.saturating_sub(2) as usize;
+        let scroll = (view.cursor + 1).saturating_sub(viewport);
+        let lines: Vec<Line> = view
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let style = if i == view.cursor {
+                    self.theme.highlight
+                } else {
+                    Style::default()
+                };
+                let (status, status_style) = match item.status() {
+                    SectionStatus::Same => ("same", self.theme.help_desc),
+                    SectionStatus::OnlyA => ("only in A", self.theme.diff_added),
+                    SectionStatus::OnlyB => ("only in B", self.theme.diff_added),
+                    SectionStatus::Conflict => ("differs", self.theme.diff_removed),
+                };
+                let choice = match item.choice {
+                    MergeChoice::A => "A  ",
+                    MergeChoice::B => "B  ",
+                    MergeChoice::Both => "A+B",
+                    MergeChoice::Drop => " - ",
+                };
+                Line::from(vec![
+                    Span::styled(format!(" [{choice}] {}", item.heading()), style),
+                    Span::styled(format!("  {status}"), status_style),
+                ])
+            })
+            .collect();
+        let list = Paragraph::new(Text::from(lines))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.input_border)
+                    .title(format!(
+                        "Merge {} into {}",
+                        view.b.display(),
+                        view.a.display()
+                    )),
+            )
+            .scroll((scroll as u16, 0));
+        frame.render_widget(list, list_area);
+
+        let [a_area, b_area] =
+            Layout::horizontal([Constraint::Percentage(50), Constraint::Percentage(50)])
+                .areas(sides_area);
+        let item = view.items.get(view.cursor);
+        let taken = |side: MergeChoice| {
+            item.is_some_and(|item| item.choice == side || item.choice == MergeChoice::Both)
+        };
+        for (area, label, section, side) in [
+            (a_area, "A", item.and_then(|i| i.a.as_ref()), MergeChoice::A),
+            (b_area, "B", item.and_then(|i| i.b.as_ref()), MergeChoice::B),
+        ] {
+            let border = if taken(side) && section.is_some() {
+                self.theme.active_border
+            } else {
+                self.theme.inactive_border
+            };
+            let text = section.map_or("(not in this file)", |s| s.text.as_str());
+            let widget = Paragraph::new(text.to_string()).block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border)
+                    .title(label),
+            );
+            frame.render_widget(widget, area);
+        }
+    }
+
+    /// Applies an action while the merge is open.
+    pub(crate) fn update_merge(&mut self, action: Action) -> Vec<Effect> {
+        let Some(view) = &mut self.merge_view else {
+            self.mode = self.mode_after_modal();
+            return Vec::new();
+        };
+        match action {
+            Action::Cancel => {
+                self.merge_view = None;
+                self.mode = self.mode_after_modal();
+            }
+            Action::MoveDown => {
+                if view.cursor + 1 < view.items.len() {
+                    view.cursor += 1;
+                }
+            }
+            Action::MoveUp => view.cursor = view.cursor.saturating_sub(1),
+            Action::TakeSection(choice) => {
+                if let Some(item) = view.items.get_mut(view.cursor) {
+                    let available = match choice {
+                        MergeChoice::A => item.a.is_some(),
+                        MergeChoice::B => item.b.is_some(),
+                        MergeChoice::Both | MergeChoice::Drop => true,
+                    };
+                    if available {
+                        item.choice = choice;
+                        if view.cursor + 1 < view.items.len() {
+                            view.cursor += 1;
+                        }
+                    }
+                }
+            }
+            Action::Save => self.preview_merge(),
+            Action::NewFile => {
+                let dir = view.a.parent().map(|dir| dir.join("")).unwrap_or_default();
+                self.text_input.set(&dir.display().to_string());
+                self.mode = Mode::MergePath;
+            }
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    /// Previews writing the merged file over A.
+    fn preview_merge(&mut self) {
+        let Some(view) = &self.merge_view else {
+            return;
+        };
+        let a = view.a.clone();
+        if !self.ensure_writable(&a, "merge into") {
+            return;
+        }
+        let Some(view) = self.merge_view.take() else {
+            return;
+        };
+        let content = view.merged();
+        self.mode = self.mode_after_modal();
+        self.open_diff_preview(DiffPreview::new(
+            format!("Write the merge to {}? (y/n)", a.display()),
+            collapse_context(diff_lines(&view.a_text, &content), PREVIEW_CONTEXT_LINES),
+            PreviewAction::WriteFile {
+                file: a,
+                content,
+                success: format!("Merged {} in.", view.b.display()),
+            },
+        ));
+    }
+
+    /// Writes the merged file to the new path typed in. Relative paths are
+    /// taken from A's directory; an existing file is left alone.
+    pub(crate) fn write_merge_to_new_file(&mut self) {
+        let raw = self.text_input.text().trim().to_string();
+        self.text_input.clear();
+        self.mode = Mode::Merge;
+        let Some(view) = &self.merge_view else {
+            self.mode = Mode::Normal;
+            return;
+        };
+        let Some(typed) = expand_home(&raw) else {
+            self.status_message = Some("Cannot expand ~: HOME not set.".to_string());
+            return;
+        };
+        let path = match view.a.parent() {
+            Some(dir) if typed.is_relative() => dir.join(typed),
+            _ => typed,
+        };
+        if raw.is_empty() || path.is_dir() {
+            self.status_message = Some("Type a file name for the merged file.".to_string());
+            return;
+        }
+        if path.exists() {
+            self.status_message = Some(format!(
+                "{} already exists; press w to merge into A instead.",
+                path.display()
+            ));
+            return;
+        }
+        if let Err(err) = write_atomic(&path, &view.merged()) {
+            self.status_message = Some(format!("Cannot write {}: {err}", path.display()));
+            return;
+        }
+        self.journal_change("create", &path, 0);
+        self.merge_view = None;
+        self.mode = self.mode_after_modal();
+        if self.is_scanned_name(&path) {
+            self.register_file(&path);
+            self.load_selected_content();
+        }
+        self.status_message = Some(format!("Wrote the merged file to {}.", path.display()));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::merge::MergeChoice;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::test_helpers::key_event;
+    use crate::tui::app::test_helpers::render_once;
+
+    fn merge_app(tmp: &TempDir) -> App {
+        let root = tmp.path().to_path_buf();
+        fs::create_dir_all(root.join("a")).unwrap();
+        fs::create_dir_all(root.join("b")).unwrap();
+        fs::write(root.join("a/CLAUDE.md"), "# Build\nmake\n").unwrap();
+        fs::write(
+            root.join("b/CLAUDE.md"),
+            "# Build\ncargo build\n\n# Test\ncargo test\n",
+        )
+        .unwrap();
+        let roots = vec![SourceRoot {
+            path: root.clone(),
+            files: vec![
+                DiscoveredFile::new(root.join("a/CLAUDE.md")),
+                DiscoveredFile::new(root.join("b/CLAUDE.md")),
+            ],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        App::new(roots, &Config::default())
+    }
+
+    #[test]
+    fn m_picks_the_other_file_and_w_writes_the_merge_into_a() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = merge_app(&tmp);
+        let a = tmp.path().join("a/CLAUDE.md");
+
+        app.handle_key_event(key_event(KeyCode::Char('m')));
+        assert_eq!(app.mode, Mode::Picker);
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::Merge);
+        render_once(&mut app);
+
+        let view = app.merge_view.as_ref().unwrap();
+        assert_eq!(view.items.len(), 2);
+        assert_eq!(view.items[1].choice, MergeChoice::B);
+        app.handle_key_event(key_event(KeyCode::Char('B')));
+        assert_eq!(
+            app.merge_view.as_ref().unwrap().items[0].choice,
+            MergeChoice::Both
+        );
+
+        app.handle_key_event(key_event(KeyCode::Char('w')));
+        assert_eq!(app.mode, Mode::DiffPreview);
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert_eq!(
+            fs::read_to_string(&a).unwrap(),
+            "# Build\nmake\n\ncargo build\n\n# Test\ncargo test\n"
+        );
+    }
+
+    #[test]
+    fn n_writes_the_merge_to_a_new_file_but_not_over_an_existing_one() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = merge_app(&tmp);
+        app.open_merge(
+            tmp.path().join("a/CLAUDE.md"),
+            tmp.path().join("b/CLAUDE.md"),
+        );
+
+        app.handle_key_event(key_event(KeyCode::Char('n')));
+        assert_eq!(app.mode, Mode::MergePath);
+        app.text_input.set("CLAUDE.md");
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::Merge);
+        assert!(
+            app.status_message
+                .as_ref()
+                .unwrap()
+                .contains("already exists")
+        );
+
+        app.handle_key_event(key_event(KeyCode::Char('b')));
+        app.handle_key_event(key_event(KeyCode::Char('n')));
+        app.text_input.set("merged.md");
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(
+            fs::read_to_string(tmp.path().join("a/merged.md")).unwrap(),
+            "# Build\ncargo build\n\n# Test\ncargo test\n"
+        );
+    }
+}
@@ -0,0 +1,248 @@
+//! The log of status bar messages.
+//!
+//! A status message is gone at the next key press, so every message shown
+//! is also kept here, newest last, and `Ctrl-G` lists them. Messages are
+//! ranked info, warning or error by their wording, which also colours them
+//! in the status bar.
+
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::text::Text;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+
+use super::action::Action;
+use super::action::Effect;
+use super::app::App;
+use super::app::Mode;
+use super::picker::centered_rect;
+use crate::backup::format_timestamp;
+use crate::backup::now_millis;
+
+/// How many messages the log keeps.
+pub const MAX_MESSAGES: usize = 200;
+
+/// How serious a status message is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageLevel {
+    Info,
+    Warning,
+    Error,
+}
+
+impl MessageLevel {
+    /// Ranks a message by its wording: `Error …`, `Cannot …` and `… failed`
+    /// are errors, `Warning: …` a warning, and anything else information.
+    pub fn of(text: &str) -> Self {
+        let lower = text.to_lowercase();
+        if lower.starts_with("error") || lower.starts_with("cannot ") || lower.contains(" failed") {
+            MessageLevel::Error
+        } else if lower.starts_with("warning") {
+            MessageLevel::Warning
+        } else {
+            MessageLevel::Info
+        }
+    }
+
+    fn label(self) -> &'static str {
+        match self {
+            MessageLevel::Info => "info",
+            MessageLevel::Warning => "warn",
+            MessageLevel::Error => "error",
+        }
+    }
+}
+
+/// A message once shown in the status bar.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LoggedMessage {
+    /// When it was shown, in milliseconds since the Unix epoch.
+    pub millis: u64,
+    pub level: MessageLevel,
+    pub text: String,
+}
+
+/// The messages panel, open while in `Mode::Messages`.
+#[derive(Debug, Default)]
+pub struct MessagesView {
+    /// First visible row.
+    pub scroll: u16,
+    /// Captured during draw — visible rows inside the border.
+    pub viewport_height: u16,
+}
+
+impl App {
+    /// Adds the status message to the log unless it is there already.
+    /// Called once per frame, so a message replaced before it was drawn
+    /// is not kept.
+    pub(crate) fn log_status(&mut self) {
+        if self.status_message == self.logged_status {
+            return;
+        }
+        self.logged_status.clone_from(&self.status_message);
+        let Some(text) = &self.status_message else {
+            return;
+        };
+        self.log_message(MessageLevel::of(text), text.clone());
+    }
+
+    /// Adds `text` to the log at `level` without showing it in the status
+    /// bar.
+    pub(crate) fn log_message(&mut self, level: MessageLevel, text: String) {
+        if self.messages.len() == MAX_MESSAGES {
+            self.messages.pop_front();
+        }
+        self.messages.push_back(LoggedMessage {
+            millis: now_millis(),
+            level,
+            text,
+        });
+    }
+
+    /// The style messages of `level` are drawn in.
+    pub(crate) fn message_style(&self, level: MessageLevel) -> Style {
+        match level {
+            MessageLevel::Info => Style::default(),
+            MessageLevel::Warning => self.theme.input_border,
+            MessageLevel::Error => self.theme.diff_removed,
+        }
+    }
+
+    /// Opens the panel listing the logged messages, newest at the bottom.
+    pub(crate) fn open_messages(&mut self) {
+        self.log_status();
+        self.messages_view = Some(MessagesView::default());
+        self.mode = Mode::Messages;
+    }
+
+    /// Draws the messages panel as a popup over `area`, scrolled to the
+    /// newest message when first drawn.
+    pub(crate) fn draw_messages(&mut self, frame: &mut Frame, area: Rect) {
+        let border = self.theme.input_border;
+        let lines: Vec<Line> = if self.messages.is_empty() {
+            vec![Line::raw("No messages yet.")]
+        } else {
+            self.messages
+                .iter()
+                .map(|message| {
+                    let time = format_timestamp(message.millis);
+                    Line::from(vec![
+                        Span::raw(format!("{}  ", time.get(11..19).unwrap_or_default())),
+                        Span::styled(
+                            format!("{:<6}", message.level.label()),
+                            self.message_style(message.level),
+                        ),
+                        Span::raw(message.text.clone()),
+                    ])
+                })
+                .collect()
+        };
+        let count = lines.len();
+        let Some(view) = &mut self.messages_view else {
+            return;
+        };
+        let popup = centered_rect(90, area.height.saturating_sub(2), area);
+        let first_draw = view.viewport_height == 0;
+        view.viewport_height = popup.height.saturating_sub(2);
+        let max = max_scroll(count, view.viewport_height);
+        view.scroll = if first_draw {
+            max
+        } else {
+            view.scroll.min(max)
+        };
+        let widget = Paragraph::new(Text::from(lines))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border)
+                    .title("Messages"),
+            )
+            .scroll((view.scroll, 0));
+        frame.render_widget(Clear, popup);
+        frame.render_widget(widget, popup);
+    }
+
+    /// Applies an action while the messages panel is open.
+    pub(crate) fn update_messages(&mut self, action: Action) -> Vec<Effect> {
+        let count = self.messages.len();
+        let Some(view) = &mut self.messages_view else {
+            self.mode = self.mode_after_modal();
+            return Vec::new();
+        };
+        let page = view.viewport_height.max(1);
+        let max = max_scroll(count, view.viewport_height);
+        match action {
+            Action::Cancel => {
+                self.messages_view = None;
+                self.mode = self.mode_after_modal();
+            }
+            Action::MoveDown => view.scroll = (view.scroll + 1).min(max),
+            Action::MoveUp => view.scroll = view.scroll.saturating_sub(1),
+            Action::PageDown => view.scroll = view.scroll.saturating_add(page).min(max),
+            Action::PageUp => view.scroll = view.scroll.saturating_sub(page),
+            _ => {}
+        }
+        Vec::new()
+    }
+}
+
+fn max_scroll(lines: usize, viewport_height: u16) -> u16 {
+    let max = lines.saturating_sub(viewport_height as usize);
+    max.min(u16::MAX as usize) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::crossterm::event::KeyCode;
+    use ratatui::crossterm::event::KeyModifiers;
+
+    use super::*;
+    use crate::config::Config;
+    use crate::tui::app::test_helpers::key_event;
+    use crate::tui::app::test_helpers::render_once;
+
+    #[test]
+    fn messages_are_ranked_by_their_wording() {
+        assert_eq!(MessageLevel::of("Saved CLAUDE.md."), MessageLevel::Info);
+        assert_eq!(
+            MessageLevel::of("Warning: skipped /x: permission denied"),
+            MessageLevel::Warning
+        );
+        assert_eq!(
+            MessageLevel::of("Write failed: disk full"),
+            MessageLevel::Error
+        );
+        assert_eq!(
+            MessageLevel::of("Cannot edit /x: read-only."),
+            MessageLevel::Error
+        );
+    }
+
+    #[test]
+    fn shown_messages_are_kept_and_ctrl_g_lists_them() {
+        let mut app = App::new(Vec::new(), &Config::default());
+        app.status_message = Some("first".to_string());
+        app.log_status();
+        app.log_status();
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.status_message = Some("Write failed: disk full".to_string());
+        app.log_status();
+
+        let texts: Vec<&str> = app.messages.iter().map(|m| m.text.as_str()).collect();
+        assert_eq!(texts, ["first", "Write failed: disk full"]);
+        assert_eq!(app.messages[1].level, MessageLevel::Error);
+
+        let mut ctrl_g = key_event(KeyCode::Char('g'));
+        ctrl_g.modifiers = KeyModifiers::CONTROL;
+        app.handle_key_event(ctrl_g);
+        assert_eq!(app.mode, Mode::Messages);
+        render_once(&mut app);
+        app.handle_key_event(key_event(KeyCode::Esc));
+        assert_eq!(app.mode, Mode::Normal);
+    }
+}
@@ -0,0 +1,274 @@
+use std::fs;
+use std::path::PathBuf;
+
+use super::app::App;
+use super::diff_preview::DiffPreview;
+use super::diff_preview::PREVIEW_CONTEXT_LINES;
+use super::diff_preview::PreviewAction;
+use crate::diff::collapse_context;
+use crate::diff::diff_lines;
+use crate::discovery::is_local_claude_file;
+use crate::fsutil::write_atomic;
+use crate::journal::file_size;
+use crate::migrate::LocalMigration;
+use crate::migrate::plan_local_migration;
+
+impl App {
+    /// Previews migrating the selected `CLAUDE.local.md` into a separate
+    /// document imported from the sibling CLAUDE.md.
+    pub(crate) fn start_local_migration(&mut self) {
+        let local = PathBuf::from(self.current_source_path());
+        if !is_local_claude_file(&local) {
+            self.status_message = Some("Select a CLAUDE.local.md file to migrate.".to_string());
+            return;
+        }
+        let Some(home) = self.home_dir.clone() else {
+            self.status_message = Some(
+                "Cannot migrate: HOME is not set, so there is nowhere to move it.".to_string(),
+            );
+            return;
+        };
+        let migration = match plan_local_migration(&local, &home) {
+            Ok(migration) => migration,
+            Err(err) => {
+                self.status_message = Some(format!("Cannot migrate: {err:#}"));
+                return;
+            }
+        };
+        if !self.ensure_writable(&migration.local, "migrate")
+            || !self.ensure_writable(&migration.claude, "migrate")
+        {
+            return;
+        }
+
+        let title = format!(
+            "Move {} to {} and import it from {}? (y/n)",
+            local.display(),
+            migration.import,
+            migration.claude.display()
+        );
+        self.open_diff_preview(DiffPreview::new(
+            title,
+            collapse_context(
+                diff_lines(&migration.claude_before, &migration.claude_after),
+                PREVIEW_CONTEXT_LINES,
+            ),
+            PreviewAction::MigrateLocal(migration),
+        ));
+    }
+
+    /// Carries out a previewed migration: writes the new document, adds the
+    /// import to CLAUDE.md and removes the local file. Both CLAUDE.md and the
+    /// local file are backed up before they change. A document that appeared
+    /// since the preview is left alone and nothing is migrated.
+    pub(crate) fn apply_local_migration(&mut self, migration: &LocalMigration) {
+        if migration.doc.exists() {
+            self.status_message = Some(format!(
+                "Not migrated: {} was created since the preview.",
+                migration.doc.display()
+            ));
+            return;
+        }
+        let content = match fs::read_to_string(&migration.local) {
+            Ok(content) => content,
+            Err(err) => {
+                self.status_message =
+                    Some(format!("Cannot read {}: {err}", migration.local.display()));
+                return;
+            }
+        };
+        if let Some(dir) = migration.doc.parent()
+            && let Err(err) = fs::create_dir_all(dir)
+        {
+            self.status_message = Some(format!("Cannot create {}: {err}", dir.display()));
+            return;
+        }
+        if let Err(err) = write_atomic(&migration.doc, &content) {
+            self.status_message = Some(format!("Migration failed: {err}"));
+            return;
+        }
+        self.journal_change("create", &migration.doc, 0);
+        let mut warnings = Vec::new();
+        match self.write_with_backup("migrate", &migration.claude, &migration.claude_after) {
+            Ok(warning) => warnings.extend(warning),
+            Err(err) => {
+                // Leave the local file in place so nothing is lost.
+                let _ = fs::remove_file(&migration.doc);
+                self.status_message = Some(format!("Migration failed: {err}"));
+                return;
+            }
+        }
+        warnings.extend(self.backup_before_write(&migration.local));
+        let local_size = file_size(&migration.local);
+        match fs::remove_file(&migration.local) {
+            Ok(()) => self.journal_change("delete", &migration.local, local_size),
+            Err(err) => warnings.push(format!("could not remove CLAUDE.local.md: {err}")),
+        }
+
+        self.unregister_file(&migration.local);
+        self.register_file(&migration.claude);
+        self.load_file_content(&migration.claude);
+        let done = format!("Migrated to {}, imported from CLAUDE.md.", migration.import);
+        self.status_message = Some(if warnings.is_empty() {
+            done
+        } else {
+            format!("{done} ({})", warnings.join("; "))
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+    use std::path::PathBuf;
+
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::lint::RULE_DEPRECATED_LOCAL_FILE;
+    use crate::migrate::plan_local_migration;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::test_helpers::key_event;
+
+    fn app_on_local(root: &Path, files: Vec<PathBuf>) -> App {
+        let mut app = App::new(
+            vec![SourceRoot {
+                path: root.to_path_buf(),
+                files: files.into_iter().map(DiscoveredFile::new).collect(),
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        );
+        app.home_dir = Some(root.join("home"));
+        app
+    }
+
+    /// The document `local`'s content is migrated to in `app`'s home.
+    fn doc_for(app: &App, local: &Path) -> (PathBuf, String) {
+        let plan = plan_local_migration(local, app.home_dir.as_deref().unwrap()).unwrap();
+        (plan.doc, plan.import)
+    }
+
+    fn is_registered(app: &App, path: &Path) -> bool {
+        app.roots.iter().any(|root| root.has_file(path))
+    }
+
+    #[test]
+    fn local_file_has_lint_finding() {
+        let tmp = TempDir::new().unwrap();
+        let local = tmp.path().join("CLAUDE.local.md");
+        fs::write(&local, "mine").unwrap();
+
+        let app = app_on_local(tmp.path(), vec![local]);
+
+        assert_eq!(app.findings.len(), 1);
+        assert_eq!(app.findings[0].rule, RULE_DEPRECATED_LOCAL_FILE);
+    }
+
+    #[test]
+    fn m_migrates_local_file_after_preview() {
+        let tmp = TempDir::new().unwrap();
+        let local = tmp.path().join("CLAUDE.local.md");
+        let claude = tmp.path().join("CLAUDE.md");
+        fs::write(&local, "my notes\n").unwrap();
+        fs::write(&claude, "# Shared\n").unwrap();
+        let mut app = app_on_local(tmp.path(), vec![local.clone(), claude.clone()]);
+        let (doc, import) = doc_for(&app, &local);
+
+        app.handle_key_event(key_event(KeyCode::Char('M')));
+        assert_eq!(app.mode, Mode::DiffPreview);
+        assert!(local.exists());
+
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert!(!local.exists());
+        assert!(doc.starts_with(tmp.path().join("home/.claude")));
+        assert_eq!(fs::read_to_string(&doc).unwrap(), "my notes\n");
+        assert!(import.starts_with("~/.claude/"));
+        assert_eq!(
+            fs::read_to_string(&claude).unwrap(),
+            format!("# Shared\n\n@{import}\n")
+        );
+        assert!(!is_registered(&app, &local));
+        assert_eq!(app.current_source_path(), claude.display().to_string());
+    }
+
+    #[test]
+    fn migration_creates_missing_claude_md() {
+        let tmp = TempDir::new().unwrap();
+        let local = tmp.path().join("CLAUDE.local.md");
+        fs::write(&local, "my notes\n").unwrap();
+        let mut app = app_on_local(tmp.path(), vec![local.clone()]);
+        let (_, import) = doc_for(&app, &local);
+
+        app.handle_key_event(key_event(KeyCode::Char('M')));
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        let claude = tmp.path().join("CLAUDE.md");
+        assert_eq!(fs::read_to_string(&claude).unwrap(), format!("@{import}\n"));
+        assert!(is_registered(&app, &claude));
+    }
+
+    #[test]
+    fn doc_created_after_the_preview_is_not_overwritten() {
+        let tmp = TempDir::new().unwrap();
+        let local = tmp.path().join("CLAUDE.local.md");
+        fs::write(&local, "my notes\n").unwrap();
+        let mut app = app_on_local(tmp.path(), vec![local.clone()]);
+        let (doc, _) = doc_for(&app, &local);
+
+        app.handle_key_event(key_event(KeyCode::Char('M')));
+        fs::create_dir_all(doc.parent().unwrap()).unwrap();
+        fs::write(&doc, "written meanwhile\n").unwrap();
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        assert_eq!(fs::read_to_string(&doc).unwrap(), "written meanwhile\n");
+        assert!(local.exists());
+        assert!(!tmp.path().join("CLAUDE.md").exists());
+        assert!(
+            app.status_message
+                .as_deref()
+                .unwrap()
+                .starts_with("Not migrated:")
+        );
+    }
+
+    #[test]
+    fn rejecting_preview_leaves_files_alone() {
+        let tmp = TempDir::new().unwrap();
+        let local = tmp.path().join("CLAUDE.local.md");
+        fs::write(&local, "my notes\n").unwrap();
+        let mut app = app_on_local(tmp.path(), vec![local.clone()]);
+
+        app.handle_key_event(key_event(KeyCode::Char('M')));
+        app.handle_key_event(key_event(KeyCode::Char('n')));
+
+        assert!(local.exists());
+        assert!(!tmp.path().join("CLAUDE.md").exists());
+    }
+
+    #[test]
+    fn m_on_regular_file_shows_status() {
+        let tmp = TempDir::new().unwrap();
+        let claude = tmp.path().join("CLAUDE.md");
+        fs::write(&claude, "x").unwrap();
+        let mut app = app_on_local(tmp.path(), vec![claude]);
+
+        app.handle_key_event(key_event(KeyCode::Char('M')));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(
+            app.status_message
+                .as_deref()
+                .unwrap()
+                .contains("CLAUDE.local.md")
+        );
+    }
+}
@@ -1,8 +1,46 @@
+pub mod action;
 pub mod app;
+pub mod backups;
+pub mod budget;
 pub mod compose;
+pub mod diff_preview;
+pub mod drafts;
 pub mod edit;
+pub mod external;
+pub mod extract;
 pub mod files;
+pub mod finder;
+pub mod imports;
+pub mod journal;
+pub mod jumps;
+pub mod keymap;
+pub mod landing;
 pub mod library;
+pub mod line_move;
+pub mod lint_pass;
+pub mod load_order;
+pub mod manage;
+pub mod markdown;
+pub mod memory;
+pub mod merge;
+pub mod messages;
+pub mod migrate;
+pub mod mouse;
+pub mod new_file;
+pub mod outline;
+pub mod picker;
+pub mod quick_actions;
+pub mod render_cache;
+pub mod rescan;
+pub mod scanner;
+pub mod search;
+pub mod session;
 pub mod settings;
+pub mod snapshot;
 pub mod text_input;
 pub mod theme;
+pub mod tools;
+pub mod trim;
+pub mod ui_state;
+pub mod undo;
+pub mod watcher;
@@ -0,0 +1,224 @@
+//! Mouse input: clicking focuses a pane and picks the row under the
+//! pointer, and the wheel moves through the pane under it.
+
+use ratatui::crossterm::event::MouseButton;
+use ratatui::crossterm::event::MouseEvent;
+use ratatui::crossterm::event::MouseEventKind;
+use ratatui::layout::Position;
+use ratatui::layout::Rect;
+
+use super::action::Action;
+use super::app::App;
+use super::app::Mode;
+use super::app::Pane;
+use super::app::Screen;
+
+/// Lines the content cursor moves per step of the wheel.
+const WHEEL_LINES: usize = 3;
+
+/// Where the panes that take clicks were drawn last, so a click can be
+/// matched to one. Panes not drawn are left empty.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct PaneAreas {
+    pub tree: Rect,
+    pub content: Rect,
+    pub outline: Rect,
+    pub library_list: Rect,
+}
+
+impl App {
+    /// Applies a mouse event. Only Normal mode takes the mouse; popups and
+    /// prompts are left to the keyboard.
+    pub(crate) fn handle_mouse_event(&mut self, mouse: MouseEvent) {
+        if self.mode != Mode::Normal {
+            return;
+        }
+        let position = Position::new(mouse.column, mouse.row);
+        match mouse.kind {
+            MouseEventKind::Down(MouseButton::Left) => self.click_at(position),
+            MouseEventKind::ScrollDown => self.scroll_at(position, Action::MoveDown),
+            MouseEventKind::ScrollUp => self.scroll_at(position, Action::MoveUp),
+            _ => {}
+        }
+    }
+
+    fn click_at(&mut self, position: Position) {
+        let areas = self.pane_areas;
+        match self.screen {
+            Screen::Files if areas.tree.contains(position) => {
+                self.active_pane = Pane::FileList;
+                if self.tree_state.click_at(position) {
+                    self.load_selected_content();
+                }
+            }
+            Screen::Files if areas.content.contains(position) => {
+                self.active_pane = Pane::Content;
+                if let Some(row) = row_inside(areas.content, position)
+                    && let Some(line) = self.content.line_at_row(row)
+                {
+                    self.content.jump_to_line(line);
+                }
+            }
+            Screen::Files if areas.outline.contains(position) => {
+                self.active_pane = Pane::Outline;
+            }
+            Screen::Library if areas.library_list.contains(position) => {
+                if let Some(row) = row_inside(areas.library_list, position) {
+                    self.click_library_row(row);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    /// Moves through the pane at `position` with `action`, focusing it.
+    fn scroll_at(&mut self, position: Position, action: Action) {
+        let areas = self.pane_areas;
+        let steps = match self.screen {
+            Screen::Files if areas.tree.contains(position) => {
+                self.active_pane = Pane::FileList;
+                1
+            }
+            Screen::Files if areas.content.contains(position) => {
+                self.active_pane = Pane::Content;
+                WHEEL_LINES
+            }
+            Screen::Files if areas.outline.contains(position) => {
+                self.active_pane = Pane::Outline;
+                1
+            }
+            Screen::Library if areas.library_list.contains(position) => 1,
+            _ => return,
+        };
+        for _ in 0..steps {
+            self.dispatch(action.clone());
+        }
+    }
+}
+
+/// The row of `position` inside the border of `area`, counted from the
+/// first row under the top border.
+fn row_inside(area: Rect, position: Position) -> Option<usize> {
+    let row = position.y.checked_sub(area.y + 1)?;
+    (row < area.height.saturating_sub(2)).then_some(usize::from(row))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ratatui::Terminal;
+    use ratatui::backend::TestBackend;
+    use ratatui::crossterm::event::KeyModifiers;
+    use ratatui::crossterm::event::MouseButton;
+    use ratatui::crossterm::event::MouseEvent;
+    use ratatui::crossterm::event::MouseEventKind;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::library::Snippet;
+    use crate::library::SnippetLibrary;
+    use crate::library::save_library;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Pane;
+
+    fn mouse(kind: MouseEventKind, column: u16, row: u16) -> MouseEvent {
+        MouseEvent {
+            kind,
+            column,
+            row,
+            modifiers: KeyModifiers::empty(),
+        }
+    }
+
+    #[test]
+    fn clicks_focus_panes_and_pick_rows_and_the_wheel_moves_the_cursor() {
+        let tmp = TempDir::new().unwrap();
+        let first = tmp.path().join("CLAUDE.md");
+        let second = tmp.path().join("sub/CLAUDE.md");
+        fs::create_dir_all(tmp.path().join("sub")).unwrap();
+        fs::write(&first, "one\ntwo\nthree\nfour\nfive\nsix\n").unwrap();
+        fs::write(&second, "other\n").unwrap();
+        let mut app = App::new(
+            vec![SourceRoot {
+                path: tmp.path().to_path_buf(),
+                files: vec![
+                    DiscoveredFile::new(first.clone()),
+                    DiscoveredFile::new(second.clone()),
+                ],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        );
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        assert_eq!(app.selected_file(), Some(first.clone()));
+
+        let content = app.pane_areas.content;
+        app.handle_mouse_event(mouse(
+            MouseEventKind::Down(MouseButton::Left),
+            content.x + 2,
+            content.y + 3,
+        ));
+        assert_eq!(app.active_pane, Pane::Content);
+        assert_eq!(app.content.cursor, 2);
+
+        app.handle_mouse_event(mouse(
+            MouseEventKind::ScrollDown,
+            content.x + 2,
+            content.y + 3,
+        ));
+        assert_eq!(app.content.cursor, 5);
+
+        // The tree shows the root, then its two files.
+        let tree = app.pane_areas.tree;
+        app.handle_mouse_event(mouse(
+            MouseEventKind::Down(MouseButton::Left),
+            tree.x + 4,
+            tree.y + 3,
+        ));
+        assert_eq!(app.active_pane, Pane::FileList);
+        assert_eq!(app.selected_file(), Some(second));
+        assert!(app.content.text.as_deref().unwrap().contains("other"));
+    }
+
+    #[test]
+    fn clicking_a_library_row_selects_its_snippet() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("library.toml");
+        let snippet = |title: &str| Snippet {
+            title: title.to_string(),
+            content: "text".to_string(),
+            source: String::new(),
+            format: None,
+            archived: false,
+            tags: Vec::new(),
+            lines: None,
+            created: None,
+            scope: Default::default(),
+        };
+        save_library(
+            &SnippetLibrary {
+                snippets: vec![snippet("First"), snippet("Second")],
+            },
+            &path,
+        )
+        .unwrap();
+        let mut app = App::new(Vec::new(), &Config::default());
+        app.enter_library_screen_from(&path);
+        let mut terminal = Terminal::new(TestBackend::new(100, 20)).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+
+        let list = app.pane_areas.library_list;
+        app.handle_mouse_event(mouse(
+            MouseEventKind::Down(MouseButton::Left),
+            list.x + 3,
+            list.y + 2,
+        ));
+        assert_eq!(app.library_selected, 1);
+    }
+}
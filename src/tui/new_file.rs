@@ -0,0 +1,343 @@
+use std::cell::Cell;
+use std::path::Component;
+use std::path::Path;
+use std::path::PathBuf;
+
+use tui_textarea::TextArea;
+
+use super::app::App;
+use super::app::EditState;
+use super::app::Mode;
+use super::picker::Picker;
+use super::picker::PickerAction;
+use super::picker::PickerItem;
+use crate::templates::BUILTIN_TEMPLATES;
+
+impl App {
+    /// Starts the new-file flow by prompting for a directory under the
+    /// selected root.
+    pub(crate) fn start_new_file(&mut self) {
+        if self.tree_state.selected().is_empty() {
+            self.status_message = Some("Select a root directory first.".to_string());
+            return;
+        }
+        self.text_input.clear();
+        self.mode = Mode::NewFilePath;
+    }
+
+    /// Returns the root directory of the current tree selection.
+    pub(crate) fn selected_root(&self) -> Option<PathBuf> {
+        self.tree_state.selected().first().map(PathBuf::from)
+    }
+
+    /// Resolves the typed directory against the selected root and opens the
+    /// template picker for the new file.
+    pub(crate) fn submit_new_file_path(&mut self) {
+        let Some(root) = self.selected_root() else {
+            self.status_message = Some("Select a root directory first.".to_string());
+            self.reset_to_normal();
+            return;
+        };
+
+        let relative = self.text_input.text().trim().to_string();
+        let Some(dir) = resolve_under_root(&root, &relative) else {
+            self.status_message = Some("Directory must be inside the selected root.".to_string());
+            return;
+        };
+        if !dir.is_dir() {
+            self.status_message = Some("Directory does not exist.".to_string());
+            return;
+        }
+
+        let path = dir.join("CLAUDE.md");
+        if path.exists() {
+            self.status_message = Some("CLAUDE.md already exists in that directory.".to_string());
+            return;
+        }
+
+        self.text_input.clear();
+        self.open_new_file_picker(path);
+    }
+
+    /// Opens the "start from" picker listing built-in templates followed by
+    /// library snippets.
+    pub(crate) fn open_new_file_picker(&mut self, path: PathBuf) {
+        if self.library.is_none()
+            && let Some(lib_path) = crate::library::library_path()
+        {
+            self.project_library = self.current_project_library();
+            self.library = self.load_library_at(&lib_path).ok();
+        }
+
+        let mut items: Vec<PickerItem> = BUILTIN_TEMPLATES
+            .iter()
+            .map(|t| PickerItem::new(format!("Template: {}", t.name), t.description))
+            .collect();
+        if let Some(lib) = &self.library {
+            items.extend(
+                lib.snippets
+                    .iter()
+                    .map(|s| PickerItem::new(format!("Snippet: {}", s.title), s.source.as_str())),
+            );
+        }
+
+        let title = format!("Start {} from", path.display());
+        self.open_picker(Picker::new(
+            title,
+            items,
+            PickerAction::NewFileContent { path },
+        ));
+    }
+
+    /// Opens the editor on a not-yet-existing `path`, pre-filled with the
+    /// template or snippet at `choice` (templates first, then snippets).
+    pub(crate) fn start_new_file_from_choice(&mut self, path: &Path, choice: usize) {
+        let content = match BUILTIN_TEMPLATES.get(choice) {
+            Some(template) => template.content.to_string(),
+            None => {
+                let snippet_index = choice - BUILTIN_TEMPLATES.len();
+                match self
+                    .library
+                    .as_ref()
+                    .and_then(|lib| lib.snippets.get(snippet_index))
+                {
+                    Some(snippet) => snippet.content.clone(),
+                    None => return,
+                }
+            }
+        };
+        self.enter_new_file_edit(path, &content);
+    }
+
+    /// Enters edit mode for a new file. Nothing is written until the first save.
+    pub fn enter_new_file_edit(&mut self, path: &Path, content: &str) {
+        let lines: Vec<String> = content.lines().map(String::from).collect();
+        let lines = if lines.is_empty() {
+            vec![String::new()]
+        } else {
+            lines
+        };
+
+        let mut textarea = TextArea::new(lines);
+        textarea.set_tab_length(4);
+        textarea.set_cursor_line_style(self.theme.edit_cursor_line);
+
+        self.edit_state = Some(EditState {
+            textarea,
+            file_path: path.to_path_buf(),
+            original_text: String::new(),
+            had_trailing_newline: true,
+            discard_confirmed: false,
+            dirty_cache: Cell::new(None),
+            draft_changed_at: None,
+            preview_top: 0,
+        });
+        self.new_file_path = Some(path.to_path_buf());
+        self.mode = Mode::Edit;
+    }
+}
+
+/// Joins `relative` onto `root`, rejecting absolute paths and `..` so the
+/// result always stays inside `root`. An empty input means `root` itself.
+pub(crate) fn resolve_under_root(root: &Path, relative: &str) -> Option<PathBuf> {
+    let relative = Path::new(relative);
+    let escapes = relative
+        .components()
+        .any(|c| !matches!(c, Component::Normal(_) | Component::CurDir));
+    if escapes {
+        return None;
+    }
+    Some(root.join(relative))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use ratatui::crossterm::event::KeyCode;
+    use ratatui::crossterm::event::KeyEvent;
+    use ratatui::crossterm::event::KeyEventKind;
+    use ratatui::crossterm::event::KeyEventState;
+    use ratatui::crossterm::event::KeyModifiers;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::library::Snippet;
+    use crate::library::SnippetLibrary;
+    use crate::model::SourceRoot;
+    use crate::templates::BUILTIN_TEMPLATES;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::test_helpers::key_event;
+
+    use super::resolve_under_root;
+
+    fn app_with_root(root: &Path) -> App {
+        let mut app = App::new(
+            vec![SourceRoot {
+                path: root.to_path_buf(),
+                files: vec![],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        );
+        app.library = Some(SnippetLibrary {
+            snippets: vec![Snippet {
+                title: "Rust rules".to_string(),
+                content: "- No unwrap".to_string(),
+                source: String::new(),
+                format: None,
+                archived: false,
+                tags: Vec::new(),
+                lines: None,
+                created: None,
+                scope: Default::default(),
+            }],
+        });
+        app.tree_state.select(vec![root.display().to_string()]);
+        app
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.handle_key_event(key_event(KeyCode::Char(c)));
+        }
+    }
+
+    fn ctrl_s() -> KeyEvent {
+        KeyEvent {
+            code: KeyCode::Char('s'),
+            modifiers: KeyModifiers::CONTROL,
+            kind: KeyEventKind::Press,
+            state: KeyEventState::empty(),
+        }
+    }
+
+    #[test]
+    fn resolve_under_root_rejects_escapes() {
+        let root = Path::new("/r");
+        assert_eq!(resolve_under_root(root, "").unwrap(), Path::new("/r"));
+        assert_eq!(
+            resolve_under_root(root, "a/b").unwrap(),
+            Path::new("/r/a/b")
+        );
+        assert!(resolve_under_root(root, "../x").is_none());
+        assert!(resolve_under_root(root, "/etc").is_none());
+    }
+
+    #[test]
+    fn n_in_file_list_prompts_for_directory() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = app_with_root(tmp.path());
+
+        app.handle_key_event(key_event(KeyCode::Char('n')));
+
+        assert_eq!(app.mode, Mode::NewFilePath);
+    }
+
+    #[test]
+    fn enter_on_path_opens_template_picker_with_snippets() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = app_with_root(tmp.path());
+
+        app.handle_key_event(key_event(KeyCode::Char('n')));
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(app.mode, Mode::Picker);
+        let picker = app.picker.as_ref().unwrap();
+        assert_eq!(picker.items.len(), BUILTIN_TEMPLATES.len() + 1);
+        assert!(picker.items.last().unwrap().label.contains("Rust rules"));
+    }
+
+    #[test]
+    fn existing_file_is_rejected() {
+        let tmp = TempDir::new().unwrap();
+        fs::write(tmp.path().join("CLAUDE.md"), "exists").unwrap();
+        let mut app = app_with_root(tmp.path());
+
+        app.handle_key_event(key_event(KeyCode::Char('n')));
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(app.mode, Mode::NewFilePath);
+        assert!(app.status_message.as_deref().unwrap().contains("exists"));
+    }
+
+    #[test]
+    fn missing_directory_is_rejected() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = app_with_root(tmp.path());
+
+        app.handle_key_event(key_event(KeyCode::Char('n')));
+        type_text(&mut app, "nope");
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(app.mode, Mode::NewFilePath);
+        assert!(
+            app.status_message
+                .as_deref()
+                .unwrap()
+                .contains("does not exist")
+        );
+    }
+
+    #[test]
+    fn choosing_snippet_prefills_editor_without_writing() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = app_with_root(tmp.path());
+
+        app.handle_key_event(key_event(KeyCode::Char('n')));
+        app.handle_key_event(key_event(KeyCode::Enter));
+        for _ in 0..BUILTIN_TEMPLATES.len() {
+            app.handle_key_event(key_event(KeyCode::Char('j')));
+        }
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(app.mode, Mode::Edit);
+        let edit = app.edit_state.as_ref().unwrap();
+        assert_eq!(edit.textarea.lines().join("\n"), "- No unwrap");
+        assert!(edit.is_dirty());
+        assert!(!tmp.path().join("CLAUDE.md").exists());
+    }
+
+    #[test]
+    fn first_save_creates_file_and_adds_it_to_tree() {
+        let tmp = TempDir::new().unwrap();
+        fs::create_dir_all(tmp.path().join("sub")).unwrap();
+        let mut app = app_with_root(tmp.path());
+
+        app.handle_key_event(key_event(KeyCode::Char('n')));
+        type_text(&mut app, "sub");
+        app.handle_key_event(key_event(KeyCode::Enter));
+        // "Starter" template
+        app.handle_key_event(key_event(KeyCode::Char('j')));
+        app.handle_key_event(key_event(KeyCode::Enter));
+        app.handle_key_event(ctrl_s());
+
+        let path = tmp.path().join("sub").join("CLAUDE.md");
+        let written = fs::read_to_string(&path).unwrap();
+        assert!(written.starts_with("# Project"));
+        assert!(app.roots[0].has_file(&path));
+        assert_eq!(
+            app.tree_state.selected(),
+            vec![tmp.path().display().to_string(), path.display().to_string()]
+        );
+    }
+
+    #[test]
+    fn discarding_new_file_leaves_disk_untouched() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = app_with_root(tmp.path());
+        app.enter_new_file_edit(&tmp.path().join("CLAUDE.md"), "draft");
+
+        app.handle_key_event(key_event(KeyCode::Esc));
+        app.handle_key_event(key_event(KeyCode::Esc));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(!tmp.path().join("CLAUDE.md").exists());
+        assert!(app.new_file_path.is_none());
+        assert!(app.content.text.is_none());
+    }
+}
@@ -0,0 +1,249 @@
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::text::Text;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Paragraph;
+
+use super::action::Action;
+use super::action::Effect;
+use super::app::App;
+use super::app::Pane;
+use crate::outline::Heading;
+use crate::outline::parse_headings;
+use crate::outline::section_at;
+use crate::outline::section_ranges;
+use crate::tokens::format_tokens;
+
+impl App {
+    /// Headings of the text in the content pane.
+    pub(crate) fn outline_headings(&self) -> Vec<Heading> {
+        self.content
+            .text
+            .as_deref()
+            .map(parse_headings)
+            .unwrap_or_default()
+    }
+
+    /// Shows the outline and focuses it, or hides it again.
+    pub(crate) fn toggle_outline(&mut self) {
+        self.outline_visible = !self.outline_visible;
+        self.active_pane = if self.outline_visible {
+            Pane::Outline
+        } else if self.active_pane == Pane::Outline {
+            Pane::Content
+        } else {
+            self.active_pane
+        };
+    }
+
+    /// Applies an action while the outline has focus. Moving through the
+    /// outline moves the content cursor from heading to heading, so the two
+    /// always agree on the current section.
+    pub(crate) fn update_outline(&mut self, action: Action) -> Vec<Effect> {
+        let headings = self.outline_headings();
+        let cursor = self.content.cursor;
+        match action {
+            Action::TogglePane => self.active_pane = Pane::FileList,
+            Action::ToggleOutline => self.toggle_outline(),
+            Action::Confirm | Action::Cancel => self.active_pane = Pane::Content,
+            Action::MoveDown => {
+                if let Some(next) = headings.iter().find(|h| h.line > cursor) {
+                    self.record_jump();
+                    self.content.jump_to_line(next.line);
+                }
+            }
+            Action::MoveUp => {
+                if let Some(previous) = headings.iter().rev().find(|h| h.line < cursor) {
+                    self.record_jump();
+                    self.content.jump_to_line(previous.line);
+                }
+            }
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    /// Token cost of each heading's section, subsections included, and of
+    /// the whole text.
+    pub(crate) fn outline_costs(&self, headings: &[Heading]) -> (Vec<u64>, u64) {
+        let Some(text) = self.content.text.as_deref() else {
+            return (vec![0; headings.len()], 0);
+        };
+        let tokenizer = self.config.tokenizer();
+        let lines: Vec<&str> = text.lines().collect();
+        let costs = section_ranges(headings, lines.len())
+            .into_iter()
+            .map(|range| tokenizer.count(&lines[range].join("\n")))
+            .collect();
+        (costs, tokenizer.count(text))
+    }
+
+    /// Draws the outline, highlighting the section holding the content
+    /// cursor. Each heading shows what its section costs in tokens and its
+    /// share of the file, right-aligned.
+    pub(crate) fn draw_outline(&self, frame: &mut Frame, area: Rect, border_style: Style) {
+        let headings = self.outline_headings();
+        let current = section_at(&headings, self.content.cursor);
+        let (costs, total) = self.outline_costs(&headings);
+        let width = usize::from(area.width.saturating_sub(2));
+        let lines: Vec<Line> = if headings.is_empty() {
+            vec![Line::styled(" No headings", self.theme.help_desc)]
+        } else {
+            headings
+                .iter()
+                .zip(costs)
+                .enumerate()
+                .map(|(i, (heading, cost))| {
+                    let indent = "  ".repeat(heading.level.saturating_sub(1));
+                    let style = if Some(i) == current {
+                        self.theme.highlight
+                    } else {
+                        Style::default()
+                    };
+                    let percent = (cost * 100).checked_div(total).unwrap_or(0);
+                    let title = Span::raw(format!(" {indent}{}", heading.title));
+                    let cost = Span::styled(
+                        format!("{} {percent:>3}% ", format_tokens(cost)),
+                        self.theme.help_desc,
+                    );
+                    let gap = width.saturating_sub(title.width() + cost.width()).max(1);
+                    Line::from(vec![title, Span::raw(" ".repeat(gap)), cost]).style(style)
+                })
+                .collect()
+        };
+
+        // Keep the current section in view.
+        let viewport = usize::from(area.height.saturating_sub(2));
+        let scroll = current.map_or(0, |i| (i + 1).saturating_sub(viewport));
+        let outline = Paragraph::new(Text::from(lines))
+            .scroll((scroll as u16, 0))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border_style)
+                    .title("Outline"),
+            );
+        frame.render_widget(outline, area);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Pane;
+    use crate::tui::app::test_helpers::key_event;
+    use crate::tui::app::test_helpers::render_once;
+
+    fn outlined_app(tmp: &TempDir) -> App {
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(
+            &file,
+            "# Project\nintro\n## Build\ncargo build\n## Test\ncargo test\n",
+        )
+        .unwrap();
+        App::new(
+            vec![SourceRoot {
+                path: tmp.path().to_path_buf(),
+                files: vec![DiscoveredFile::new(file)],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        )
+    }
+
+    #[test]
+    fn o_shows_and_focuses_outline_then_hides_it() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = outlined_app(&tmp);
+
+        app.handle_key_event(key_event(KeyCode::Char('o')));
+        assert!(app.outline_visible);
+        assert_eq!(app.active_pane, Pane::Outline);
+        render_once(&mut app);
+
+        app.handle_key_event(key_event(KeyCode::Char('o')));
+        assert!(!app.outline_visible);
+        assert_eq!(app.active_pane, Pane::Content);
+    }
+
+    #[test]
+    fn moving_in_outline_jumps_between_headings() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = outlined_app(&tmp);
+        app.handle_key_event(key_event(KeyCode::Char('o')));
+
+        app.handle_key_event(key_event(KeyCode::Char('j')));
+        assert_eq!(app.content.cursor, 2);
+        app.handle_key_event(key_event(KeyCode::Char('j')));
+        assert_eq!(app.content.cursor, 4);
+        app.handle_key_event(key_event(KeyCode::Char('k')));
+        assert_eq!(app.content.cursor, 2);
+
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.active_pane, Pane::Content);
+        assert_eq!(app.content.cursor, 2);
+    }
+
+    #[test]
+    fn outline_follows_content_cursor() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = outlined_app(&tmp);
+        app.handle_key_event(key_event(KeyCode::Char('o')));
+        app.active_pane = Pane::Content;
+
+        for _ in 0..5 {
+            app.handle_key_event(key_event(KeyCode::Char('j')));
+        }
+
+        let headings = app.outline_headings();
+        let current = crate::outline::section_at(&headings, app.content.cursor).unwrap();
+        assert_eq!(headings[current].title, "Test");
+    }
+
+    #[test]
+    fn tab_cycles_through_outline_when_shown() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = outlined_app(&tmp);
+        app.handle_key_event(key_event(KeyCode::Char('o')));
+
+        app.handle_key_event(key_event(KeyCode::Tab));
+        assert_eq!(app.active_pane, Pane::FileList);
+        app.handle_key_event(key_event(KeyCode::Tab));
+        assert_eq!(app.active_pane, Pane::Content);
+        app.handle_key_event(key_event(KeyCode::Tab));
+        assert_eq!(app.active_pane, Pane::Outline);
+    }
+
+    #[test]
+    fn outline_shows_section_costs_and_shares() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = outlined_app(&tmp);
+        app.handle_key_event(key_event(KeyCode::Char('o')));
+
+        let headings = app.outline_headings();
+        let (costs, total) = app.outline_costs(&headings);
+        assert_eq!(costs, [14, 5, 5]);
+        assert_eq!(total, 14);
+
+        let backend = ratatui::backend::TestBackend::new(120, 20);
+        let mut terminal = ratatui::Terminal::new(backend).unwrap();
+        terminal.draw(|frame| app.draw(frame)).unwrap();
+        let screen = crate::tui::snapshot::buffer_to_text(terminal.backend().buffer());
+        assert!(screen.contains("14 tok 100%"));
+        assert!(screen.contains("5 tok  35%"));
+    }
+}
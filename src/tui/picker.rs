@@ -0,0 +1,473 @@
+use std::ops::Range;
+use std::path::PathBuf;
+
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::text::Text;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+
+use super::action::Action;
+use super::action::Effect;
+use super::app::App;
+use super::app::Mode;
+use super::quick_actions::QuickAction;
+use crate::library::LibraryScope;
+use crate::library::Snippet;
+use crate::library::SnippetLibrary;
+
+/// What happens when the user confirms a picker entry.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PickerAction {
+    /// Pre-fill a new CLAUDE.md at `path` with the chosen template or snippet.
+    NewFileContent { path: PathBuf },
+    /// Preview restoring `file` from the chosen entry of `backups`.
+    RestoreBackup {
+        file: PathBuf,
+        backups: Vec<PathBuf>,
+    },
+    /// List the backed-up versions of the chosen entry of `files`.
+    DeletedFile { files: Vec<PathBuf> },
+    /// Append library snippet `snippet` as a memory to the chosen entry of `files`.
+    MemoryTarget { files: Vec<PathBuf>, snippet: usize },
+    /// Ask where in the chosen entry of `files` library snippet `snippet` goes.
+    AppendTarget { files: Vec<PathBuf>, snippet: usize },
+    /// Append library snippet `snippet` to `file` under the heading on the
+    /// chosen entry of `lines`, or at the end for `None`.
+    AppendUnder {
+        file: PathBuf,
+        snippet: usize,
+        lines: Vec<Option<usize>>,
+    },
+    /// Resolve a library that changed on disk: keep `mine`, take `theirs`
+    /// or merge the two.
+    LibraryConflict {
+        path: PathBuf,
+        mine: SnippetLibrary,
+        theirs: SnippetLibrary,
+        success: String,
+    },
+    /// Archive (or, in the Archive view, restore) the selected snippet of the
+    /// library at `path` (first entry), or delete it for good (second).
+    RemoveSnippet { path: PathBuf },
+    /// The same for every marked snippet at once.
+    RemoveMarkedSnippets { path: PathBuf },
+    /// Merge the chosen entry of `files` into `file`.
+    MergeWith { file: PathBuf, files: Vec<PathBuf> },
+    /// Carry out the chosen entry of `actions` on `file`, from the quick
+    /// actions menu.
+    QuickAction {
+        file: PathBuf,
+        actions: Vec<QuickAction>,
+    },
+    /// Show the chosen entry of `files`, the recently viewed ones.
+    ShowRecent { files: Vec<PathBuf> },
+    /// Run the chosen entry of `tools`, name and command, on `file`.
+    RunTool {
+        file: PathBuf,
+        tools: Vec<(String, String)>,
+    },
+    /// Scan the whole repository at `root` (first entry) or stay in the
+    /// directory the app was started in (second).
+    ScanRepoRoot { root: PathBuf },
+    /// Move the `lines` of `from` to the chosen entry of `targets`.
+    MoveLinesTo {
+        from: PathBuf,
+        lines: Range<usize>,
+        targets: Vec<PathBuf>,
+    },
+    /// Save `snippet` to the library at `path` (first entry) or to the
+    /// project library (second).
+    SnippetScope { path: PathBuf, snippet: Snippet },
+    /// A snippet title is taken: carry out `change` under the free `title`
+    /// (first entry) or drop it (second).
+    DuplicateTitle {
+        path: PathBuf,
+        title: String,
+        change: TitleChange,
+    },
+}
+
+/// The library change that ran into a taken title.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TitleChange {
+    /// Rename the snippet at this index.
+    Rename(usize),
+    /// Save this new snippet.
+    Save(Snippet),
+}
+
+/// One selectable row in a picker.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PickerItem {
+    /// Primary text.
+    pub label: String,
+    /// Secondary text rendered dimmed after the label.
+    pub detail: String,
+}
+
+impl PickerItem {
+    /// Creates an item from a label and a detail string.
+    pub fn new(label: impl Into<String>, detail: impl Into<String>) -> Self {
+        Self {
+            label: label.into(),
+            detail: detail.into(),
+        }
+    }
+}
+
+/// A modal list overlay for choosing one item from a short list.
+#[derive(Debug)]
+pub struct Picker {
+    /// Title shown in the popup border.
+    pub title: String,
+    /// Rows to choose from.
+    pub items: Vec<PickerItem>,
+    /// Index of the highlighted row.
+    pub cursor: usize,
+    /// What confirming a row does.
+    pub action: PickerAction,
+}
+
+impl Picker {
+    /// Creates a picker with the cursor on the first item.
+    pub fn new(title: impl Into<String>, items: Vec<PickerItem>, action: PickerAction) -> Self {
+        Self {
+            title: title.into(),
+            items,
+            cursor: 0,
+            action,
+        }
+    }
+
+    /// Moves the cursor down, clamping at the last item.
+    pub fn cursor_down(&mut self) {
+        if self.cursor + 1 < self.items.len() {
+            self.cursor += 1;
+        }
+    }
+
+    /// Moves the cursor up, clamping at the first item.
+    pub fn cursor_up(&mut self) {
+        self.cursor = self.cursor.saturating_sub(1);
+    }
+}
+
+/// Returns a rectangle of `width_percent` width and `height` rows, centred in `area`.
+pub(crate) fn centered_rect(width_percent: u16, height: u16, area: Rect) -> Rect {
+    let width = (area.width * width_percent / 100).max(20).min(area.width);
+    let height = height.min(area.height);
+    Rect {
+        x: area.x + (area.width - width) / 2,
+        y: area.y + (area.height - height) / 2,
+        width,
+        height,
+    }
+}
+
+impl App {
+    /// Opens `picker` as a modal overlay.
+    pub(crate) fn open_picker(&mut self, picker: Picker) {
+        self.picker = Some(picker);
+        self.mode = Mode::Picker;
+    }
+
+    /// Draws the active picker centred over `area`.
+    pub(crate) fn draw_picker(&self, frame: &mut Frame, area: Rect) {
+        let Some(picker) = &self.picker else {
+            return;
+        };
+
+        let height = (picker.items.len() as u16).saturating_add(2).max(3);
+        let popup = centered_rect(60, height, area);
+        let viewport = popup.height.saturating_sub(2) as usize;
+        let scroll = (picker.cursor + 1).saturating_sub(viewport);
+
+        let lines: Vec<Line> = picker
+            .items
+            .iter()
+            .enumerate()
+            .map(|(i, item)| {
+                let style = if i == picker.cursor {
+                    self.theme.highlight
+                } else {
+                    Style::default()
+                };
+                let mut spans = vec![Span::styled(format!(" {}", item.label), style)];
+                if !item.detail.is_empty() {
+                    spans.push(Span::styled(
+                        format!("  {}", item.detail),
+                        self.theme.help_desc,
+                    ));
+                }
+                Line::from(spans)
+            })
+            .collect();
+
+        let widget = Paragraph::new(Text::from(lines))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.input_border)
+                    .title(picker.title.as_str()),
+            )
+            .scroll((scroll as u16, 0));
+        frame.render_widget(Clear, popup);
+        frame.render_widget(widget, popup);
+    }
+
+    /// Applies an action while a picker is open.
+    pub(crate) fn update_picker(&mut self, action: Action) -> Vec<Effect> {
+        match action {
+            Action::Cancel => {
+                self.picker = None;
+                self.mode = self.mode_after_modal();
+            }
+            Action::MoveDown => {
+                if let Some(picker) = &mut self.picker {
+                    picker.cursor_down();
+                }
+            }
+            Action::MoveUp => {
+                if let Some(picker) = &mut self.picker {
+                    picker.cursor_up();
+                }
+            }
+            Action::Confirm => {
+                self.mode = self.mode_after_modal();
+                let Some(picker) = self.picker.take() else {
+                    return Vec::new();
+                };
+                if picker.items.is_empty() {
+                    return Vec::new();
+                }
+                return vec![Effect::Pick {
+                    action: picker.action,
+                    index: picker.cursor,
+                }];
+            }
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    /// Carries out `action` for the picker entry at `index`.
+    pub(crate) fn run_picker_action(&mut self, action: PickerAction, index: usize) {
+        match action {
+            PickerAction::NewFileContent { path } => {
+                self.start_new_file_from_choice(&path, index);
+            }
+            PickerAction::RestoreBackup { file, mut backups } => {
+                if index < backups.len() {
+                    let backup = backups.swap_remove(index);
+                    self.preview_restore(file, backup);
+                }
+            }
+            PickerAction::MemoryTarget { mut files, snippet } => {
+                if index < files.len() {
+                    self.preview_memory_append(files.swap_remove(index), snippet);
+                }
+            }
+            PickerAction::AppendTarget { mut files, snippet } => {
+                if index < files.len() {
+                    self.choose_append_heading(files.swap_remove(index), snippet);
+                }
+            }
+            PickerAction::AppendUnder {
+                file,
+                snippet,
+                lines,
+            } => {
+                if let Some(&line) = lines.get(index) {
+                    self.preview_snippet_append(file, snippet, line);
+                }
+            }
+            PickerAction::DeletedFile { mut files } => {
+                if index < files.len() {
+                    self.open_backup_versions(files.swap_remove(index));
+                }
+            }
+            PickerAction::LibraryConflict {
+                path,
+                mine,
+                theirs,
+                success,
+            } => {
+                self.resolve_library_conflict(path, mine, theirs, success, index);
+            }
+            PickerAction::DuplicateTitle {
+                path,
+                title,
+                change,
+            } => match (index, change) {
+                (0, TitleChange::Rename(snippet)) => {
+                    self.apply_snippet_rename(&path, snippet, title);
+                }
+                (0, TitleChange::Save(snippet)) => {
+                    self.append_new_snippet(&path, Snippet { title, ..snippet });
+                }
+                _ => self.status_message = Some("Nothing was changed.".to_string()),
+            },
+            PickerAction::QuickAction { file, mut actions } => {
+                if index < actions.len() {
+                    self.run_quick_action(file, actions.swap_remove(index));
+                }
+            }
+            PickerAction::ShowRecent { files } => {
+                if let Some(file) = files.get(index) {
+                    self.show_file(file);
+                }
+            }
+            PickerAction::RunTool { file, mut tools } => {
+                if index < tools.len() {
+                    let (name, command) = tools.swap_remove(index);
+                    self.run_tool(file, name, &command);
+                }
+            }
+            PickerAction::ScanRepoRoot { root } => {
+                if index == 0 {
+                    self.status_message = Some(format!("Scanning {}…", root.display()));
+                    self.scan_in_background(vec![root]);
+                }
+            }
+            PickerAction::MoveLinesTo {
+                from,
+                lines,
+                mut targets,
+            } => {
+                if index < targets.len() {
+                    self.choose_line_move_target(from, lines, targets.swap_remove(index));
+                }
+            }
+            PickerAction::SnippetScope { path, snippet } => {
+                let scope = match index {
+                    0 => LibraryScope::Global,
+                    _ => LibraryScope::Project,
+                };
+                self.store_new_snippet(&path, Snippet { scope, ..snippet });
+            }
+            PickerAction::RemoveSnippet { path } => match index {
+                0 => self.toggle_snippet_archived_from(&path),
+                _ => self.delete_library_snippet_from(&path),
+            },
+            PickerAction::RemoveMarkedSnippets { path } => match index {
+                0 => self.toggle_snippet_archived_from(&path),
+                _ => self.delete_marked_snippets_from(&path),
+            },
+            PickerAction::MergeWith { file, mut files } => {
+                if index < files.len() {
+                    self.open_merge(file, files.swap_remove(index));
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use ratatui::crossterm::event::KeyCode;
+    use ratatui::layout::Rect;
+
+    use crate::config::Config;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::test_helpers::key_event;
+    use crate::tui::app::test_helpers::render_once;
+
+    use super::Picker;
+    use super::PickerAction;
+    use super::PickerItem;
+    use super::centered_rect;
+
+    fn sample_picker() -> Picker {
+        Picker::new(
+            "Pick",
+            vec![
+                PickerItem::new("One", ""),
+                PickerItem::new("Two", "second"),
+                PickerItem::new("Three", ""),
+            ],
+            PickerAction::NewFileContent {
+                path: PathBuf::from("/tmp/CLAUDE.md"),
+            },
+        )
+    }
+
+    #[test]
+    fn cursor_clamps_at_both_ends() {
+        let mut picker = sample_picker();
+        picker.cursor_up();
+        assert_eq!(picker.cursor, 0);
+
+        picker.cursor_down();
+        picker.cursor_down();
+        picker.cursor_down();
+        assert_eq!(picker.cursor, 2);
+    }
+
+    #[test]
+    fn centered_rect_fits_inside_area() {
+        let area = Rect::new(0, 0, 80, 24);
+        let rect = centered_rect(50, 10, area);
+        assert_eq!(rect.width, 40);
+        assert_eq!(rect.height, 10);
+        assert_eq!(rect.x, 20);
+        assert_eq!(rect.y, 7);
+    }
+
+    #[test]
+    fn centered_rect_clamps_height_to_area() {
+        let area = Rect::new(0, 0, 80, 5);
+        let rect = centered_rect(50, 10, area);
+        assert_eq!(rect.height, 5);
+    }
+
+    #[test]
+    fn esc_closes_picker() {
+        let mut app = App::new(vec![], &Config::default());
+        app.open_picker(sample_picker());
+        assert_eq!(app.mode, Mode::Picker);
+
+        app.handle_key_event(key_event(KeyCode::Esc));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.picker.is_none());
+    }
+
+    #[test]
+    fn jk_moves_picker_cursor() {
+        let mut app = App::new(vec![], &Config::default());
+        app.open_picker(sample_picker());
+
+        app.handle_key_event(key_event(KeyCode::Char('j')));
+        assert_eq!(app.picker.as_ref().unwrap().cursor, 1);
+
+        app.handle_key_event(key_event(KeyCode::Char('k')));
+        assert_eq!(app.picker.as_ref().unwrap().cursor, 0);
+    }
+
+    #[test]
+    fn q_in_picker_does_not_exit() {
+        let mut app = App::new(vec![], &Config::default());
+        app.open_picker(sample_picker());
+
+        app.handle_key_event(key_event(KeyCode::Char('q')));
+
+        assert!(!app.exit);
+        assert_eq!(app.mode, Mode::Picker);
+    }
+
+    #[test]
+    fn picker_renders_without_panic() {
+        let mut app = App::new(vec![], &Config::default());
+        app.open_picker(sample_picker());
+        render_once(&mut app);
+    }
+}
@@ -0,0 +1,327 @@
+//! The `.` menu of the content pane: what can be done with the line under
+//! the cursor and the section it is in, so the keys for each need not be
+//! remembered.
+
+use std::env;
+use std::ops::Range;
+use std::path::Path;
+use std::path::PathBuf;
+
+use super::app::App;
+use super::app::Mode;
+use super::diff_preview::DiffPreview;
+use super::diff_preview::PREVIEW_CONTEXT_LINES;
+use super::diff_preview::PreviewAction;
+use super::picker::Picker;
+use super::picker::PickerAction;
+use super::picker::PickerItem;
+use crate::diff::collapse_context;
+use crate::diff::diff_lines;
+use crate::discovery::find_global_claude_file;
+use crate::outline::parse_headings;
+use crate::outline::section_at;
+use crate::outline::section_ranges;
+use crate::session::file_imports;
+use crate::trim::remove_lines;
+
+/// One entry of the quick actions menu. Line ranges are 0-based lines of
+/// the shown file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum QuickAction {
+    CopyLine,
+    CopySection(Range<usize>),
+    /// Select the lines and ask for a snippet title, offering `title`.
+    SaveSnippet {
+        lines: Range<usize>,
+        title: String,
+    },
+    /// Append the section to the global CLAUDE.md at `global` and remove it
+    /// here.
+    MoveToGlobal {
+        lines: Range<usize>,
+        global: PathBuf,
+    },
+    DeleteSection(Range<usize>),
+    /// Move the section under the cursor to a new file, imported in its
+    /// place.
+    ExtractSection,
+    /// Show the file an `@path` import on the line points at.
+    OpenImport(PathBuf),
+    /// Put the snippet of entry `n` of the insert history here again.
+    RepeatInsert(usize),
+}
+
+impl App {
+    /// Opens the quick actions menu for the line under the content cursor.
+    pub(crate) fn open_quick_actions(&mut self) {
+        self.open_quick_actions_with(find_global_claude_file());
+    }
+
+    /// Like [`open_quick_actions`](Self::open_quick_actions), sections
+    /// being moved to `global` when there is one.
+    pub(crate) fn open_quick_actions_with(&mut self, global: Option<PathBuf>) {
+        let (Some(file), Some(text)) = (self.selected_file(), self.content.text.clone()) else {
+            self.status_message = Some("Open a file first.".to_string());
+            return;
+        };
+        let cursor = self.content.cursor;
+        let Some(line) = text.lines().nth(cursor) else {
+            return;
+        };
+
+        let mut entries = vec![(PickerItem::new("Copy line", ""), QuickAction::CopyLine)];
+        let headings = parse_headings(&text);
+        let ranges = section_ranges(&headings, text.lines().count());
+        match section_at(&headings, cursor) {
+            Some(index) => {
+                let heading = &headings[index];
+                let lines = ranges[index].clone();
+                let name = format!("{} {}", "#".repeat(heading.level), heading.title);
+                let count = format!("{} lines", lines.len());
+                entries.push((
+                    PickerItem::new("Copy section", name.as_str()),
+                    QuickAction::CopySection(lines.clone()),
+                ));
+                entries.push((
+                    PickerItem::new("Save section as snippet", name.as_str()),
+                    QuickAction::SaveSnippet {
+                        lines: lines.clone(),
+                        title: heading.title.clone(),
+                    },
+                ));
+                // The pane must show the file as it is to change it safely.
+                let whole = self.content.shows_file_as_is();
+                if whole
+                    && let Some(global) = global
+                    && global != file
+                {
+                    entries.push((
+                        PickerItem::new(
+                            "Move section to the global file",
+                            global.display().to_string(),
+                        ),
+                        QuickAction::MoveToGlobal {
+                            lines: lines.clone(),
+                            global,
+                        },
+                    ));
+                }
+                if whole {
+                    entries.push((
+                        PickerItem::new("Extract section to a file", "x"),
+                        QuickAction::ExtractSection,
+                    ));
+                    entries.push((
+                        PickerItem::new("Delete section", count),
+                        QuickAction::DeleteSection(lines),
+                    ));
+                }
+            }
+            None => entries.push((
+                PickerItem::new("Save line as snippet", ""),
+                QuickAction::SaveSnippet {
+                    lines: cursor..cursor + 1,
+                    title: String::new(),
+                },
+            )),
+        }
+        let home = env::var_os("HOME").map(PathBuf::from);
+        for import in file_imports(home.as_deref(), &file, line) {
+            if import.path.is_file() {
+                entries.push((
+                    PickerItem::new(
+                        format!("Open @{}", import.target),
+                        import.path.display().to_string(),
+                    ),
+                    QuickAction::OpenImport(import.path),
+                ));
+            }
+        }
+
+        for (n, insert) in self.insert_history.iter().enumerate() {
+            entries.push((
+                PickerItem::new(
+                    format!("Insert \"{}\" again", insert.title),
+                    if n == 0 {
+                        format!("{}, &", insert.place)
+                    } else {
+                        insert.place.to_string()
+                    },
+                ),
+                QuickAction::RepeatInsert(n),
+            ));
+        }
+
+        let (items, actions) = entries.into_iter().unzip();
+        self.open_picker(Picker::new(
+            format!("Line {}", cursor + 1),
+            items,
+            PickerAction::QuickAction { file, actions },
+        ));
+    }
+
+    /// Carries out `action`, chosen from the quick actions menu of `file`.
+    pub(crate) fn run_quick_action(&mut self, file: PathBuf, action: QuickAction) {
+        let text = self.content.text.clone().unwrap_or_default();
+        let lines: Vec<&str> = text.lines().collect();
+        match action {
+            QuickAction::CopyLine => {
+                let line = lines.get(self.content.cursor).copied().unwrap_or_default();
+                self.copy_to_clipboard(line);
+            }
+            QuickAction::CopySection(range) => {
+                let section = lines.get(range).unwrap_or_default().join("\n");
+                self.copy_to_clipboard(&section);
+            }
+            QuickAction::SaveSnippet { lines, title } => {
+                self.content.visual_anchor = Some(lines.start);
+                self.content.cursor = lines.end.saturating_sub(1).max(lines.start);
+                self.text_input.set(&title);
+                self.mode = Mode::TitleInput;
+            }
+            QuickAction::MoveToGlobal { lines, global } => {
+                self.preview_line_move(&file, &text, lines, global, None, "the section");
+            }
+            QuickAction::DeleteSection(range) => {
+                if !self.unchanged_on_disk(&file, &text) || !self.ensure_writable(&file, "edit") {
+                    return;
+                }
+                let content = remove_lines(&text, &[range]);
+                self.open_diff_preview(DiffPreview::new(
+                    format!("Delete the section from {}? (y/n)", file.display()),
+                    collapse_context(diff_lines(&text, &content), PREVIEW_CONTEXT_LINES),
+                    PreviewAction::WriteFile {
+                        file: file.clone(),
+                        content,
+                        success: format!("Deleted the section from {}.", file.display()),
+                    },
+                ));
+            }
+            QuickAction::ExtractSection => self.start_section_extraction(),
+            QuickAction::OpenImport(path) => self.open_import(&path),
+            QuickAction::RepeatInsert(n) => self.repeat_insert(n),
+        }
+    }
+
+    /// Selects the import node for `path` below the shown file and shows it.
+    fn open_import(&mut self, path: &Path) {
+        let parent = self.tree_state.selected().to_vec();
+        let mut selection = parent.clone();
+        selection.push(path.display().to_string());
+        self.tree_state.open(parent);
+        self.tree_state.select(selection);
+        self.load_selected_content();
+        if self.selected_file().as_deref() != Some(path) {
+            self.show_file(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::Pane;
+    use crate::tui::app::test_helpers::key_event;
+
+    fn labels(app: &App) -> Vec<String> {
+        app.picker
+            .as_ref()
+            .unwrap()
+            .items
+            .iter()
+            .map(|item| item.label.clone())
+            .collect()
+    }
+
+    #[test]
+    fn dot_offers_what_fits_the_line_and_runs_it() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        let home = TempDir::new().unwrap();
+        let global = home.path().join("CLAUDE.md");
+        fs::write(&global, "Mine\n").unwrap();
+        fs::write(tmp.path().join("docs.md"), "Docs\n").unwrap();
+        fs::write(
+            &file,
+            "Intro\n\n## Build\nSee @docs.md\nmake\n\n## Style\n- tabs\n",
+        )
+        .unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![DiscoveredFile::new(file.clone())],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+        app.select_tree_file(&file);
+        app.load_selected_content();
+        app.active_pane = Pane::Content;
+
+        app.handle_key_event(key_event(KeyCode::Char('.')));
+        assert_eq!(app.mode, Mode::Picker);
+        assert_eq!(labels(&app), ["Copy line", "Save line as snippet"]);
+        app.handle_key_event(key_event(KeyCode::Esc));
+
+        app.content.jump_to_line(3);
+        app.open_quick_actions_with(Some(global.clone()));
+        assert_eq!(
+            labels(&app),
+            [
+                "Copy line",
+                "Copy section",
+                "Save section as snippet",
+                "Move section to the global file",
+                "Extract section to a file",
+                "Delete section",
+                "Open @docs.md",
+            ]
+        );
+        for _ in 0..6 {
+            app.handle_key_event(key_event(KeyCode::Down));
+        }
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.selected_file(), Some(tmp.path().join("docs.md")));
+
+        app.select_tree_file(&file);
+        app.load_selected_content();
+        app.content.jump_to_line(3);
+        app.open_quick_actions_with(Some(global.clone()));
+        for _ in 0..3 {
+            app.handle_key_event(key_event(KeyCode::Down));
+        }
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::DiffPreview);
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+        assert_eq!(
+            fs::read_to_string(&file).unwrap(),
+            "Intro\n\n## Style\n- tabs\n"
+        );
+        assert!(
+            fs::read_to_string(&global)
+                .unwrap()
+                .contains("Mine\n\n## Build\nSee @docs.md\nmake")
+        );
+
+        app.content.jump_to_line(3);
+        app.open_quick_actions_with(None);
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.handle_key_event(key_event(KeyCode::Enter));
+        assert_eq!(app.mode, Mode::TitleInput);
+        assert_eq!(app.text_input.text(), "Style");
+        assert_eq!(
+            app.content.selected_text().as_deref(),
+            Some("## Style\n- tabs")
+        );
+    }
+}
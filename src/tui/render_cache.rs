@@ -0,0 +1,91 @@
+//! Cache of the styled lines shown in the content pane.
+//!
+//! Turning the file text into styled lines is done once per content
+//! revision, theme and pane width, and reused on frames where only the
+//! cursor, selection or scroll position moved. Those are overlaid on the
+//! visible lines at draw time.
+
+use ratatui::text::Line;
+
+use super::theme::Theme;
+
+/// Everything the styled lines depend on.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderKey {
+    /// [`ContentState::revision`](super::app::ContentState::revision) of the
+    /// text being shown.
+    pub revision: u64,
+    pub theme: Theme,
+    /// Width of the pane inside its border.
+    pub width: u16,
+}
+
+/// Styled lines for the most recently drawn [`RenderKey`].
+#[derive(Debug, Default)]
+pub struct RenderCache {
+    key: Option<RenderKey>,
+    lines: Vec<Line<'static>>,
+}
+
+impl RenderCache {
+    /// Returns the lines for `key`, calling `build` only if they are not
+    /// cached already.
+    pub fn lines(
+        &mut self,
+        key: RenderKey,
+        build: impl FnOnce() -> Vec<Line<'static>>,
+    ) -> &[Line<'static>] {
+        if self.key.as_ref() != Some(&key) {
+            self.lines = build();
+            self.key = Some(key);
+        }
+        &self.lines
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::cell::Cell;
+
+    use super::*;
+
+    fn key(revision: u64, theme: Theme, width: u16) -> RenderKey {
+        RenderKey {
+            revision,
+            theme,
+            width,
+        }
+    }
+
+    #[test]
+    fn same_key_reuses_lines() {
+        let mut cache = RenderCache::default();
+        let builds = Cell::new(0);
+        let build = || {
+            builds.set(builds.get() + 1);
+            vec![Line::from("a")]
+        };
+
+        cache.lines(key(1, Theme::dark(), 80), build);
+        let lines = cache.lines(key(1, Theme::dark(), 80), build);
+
+        assert_eq!(lines, [Line::from("a")]);
+        assert_eq!(builds.get(), 1);
+    }
+
+    #[test]
+    fn any_key_change_rebuilds() {
+        let mut cache = RenderCache::default();
+        let builds = Cell::new(0);
+        let build = || {
+            builds.set(builds.get() + 1);
+            Vec::new()
+        };
+
+        cache.lines(key(1, Theme::dark(), 80), build);
+        cache.lines(key(2, Theme::dark(), 80), build);
+        cache.lines(key(2, Theme::light(), 80), build);
+        cache.lines(key(2, Theme::light(), 100), build);
+        assert_eq!(builds.get(), 4);
+    }
+}
@@ -0,0 +1,306 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+
+use super::app::App;
+use super::app::Mode;
+use super::app::TreeId;
+use super::app::is_file_group_id;
+use crate::discovery::ScanOptions;
+use crate::discovery::context_files_in;
+use crate::discovery::discovered_file;
+use crate::discovery::find_global_claude_file;
+use crate::model::SourceRoot;
+
+impl App {
+    /// Scans every root again with the options it was first scanned with
+    /// and applies the result through [`replace_roots`](Self::replace_roots).
+    pub(crate) fn rescan(&mut self) {
+        let roots = self.scanned_roots();
+        let before: HashSet<PathBuf> = self.discovered_files().into_iter().collect();
+        self.replace_roots(roots);
+        let after: HashSet<PathBuf> = self.discovered_files().into_iter().collect();
+        self.start_background_lint();
+        self.status_message = Some(format!(
+            "Rescanned: {} files ({} added, {} removed).",
+            after.len(),
+            after.difference(&before).count(),
+            before.difference(&after).count()
+        ));
+    }
+
+    /// The current roots as a fresh scan finds them now.
+    pub(crate) fn scanned_roots(&self) -> Vec<SourceRoot> {
+        rescan_roots(&self.roots, &self.scan_options)
+    }
+
+    /// Swaps in freshly scanned `roots` and carries the tree and content
+    /// state over. Roots and repo groups stay expanded or collapsed as they
+    /// were, and ones that did not exist before start expanded. The selected
+    /// file stays selected; if it is gone, the next file of its root is
+    /// selected instead. When the same file is still shown, its text is
+    /// reloaded but the cursor, scroll and visual selection are kept.
+    pub(crate) fn replace_roots(&mut self, roots: Vec<SourceRoot>) {
+        let old_nodes = self.tree_nodes();
+        let opened = self.tree_state.opened().clone();
+        let old_selection = self.tree_state.selected().to_vec();
+        let old_file = self.selected_file();
+
+        self.roots = roots;
+        self.rebuild_tree();
+
+        self.tree_state.close_all();
+        for node in self.tree_nodes() {
+            if !old_nodes.contains(&node) {
+                self.tree_state.open(node);
+            }
+        }
+        // Files with imports open too, and stay open as they were.
+        for node in opened {
+            if self.tree_contains(&node) {
+                self.tree_state.open(node);
+            }
+        }
+
+        let kept_file = old_file
+            .as_deref()
+            .filter(|file| {
+                self.discovered_files().iter().any(|f| f == file)
+                    || self.roots.iter().any(|root| {
+                        root.context_files.iter().any(|c| c.path == *file)
+                            || root.misnamed_files.iter().any(|m| m == file)
+                    })
+            })
+            .map(Path::to_path_buf);
+        if kept_file.is_some() && self.tree_contains(&old_selection) {
+            self.tree_state.select(old_selection);
+        } else if let Some(file) = &kept_file {
+            self.select_tree_file(file);
+        } else if let Some(file) = &old_file {
+            self.select_neighbour_of(file);
+        } else if self.tree_nodes().contains(&old_selection) {
+            self.tree_state.select(old_selection);
+        } else {
+            self.tree_state.select_first();
+        }
+
+        if kept_file.is_some() {
+            self.reload_keeping_position();
+        } else {
+            if self.mode == Mode::VisualSelect {
+                self.mode = Mode::Normal;
+            }
+            self.load_selected_content();
+        }
+    }
+
+    /// Identifier paths of every root, repo group and agents, commands or
+    /// skills node.
+    fn tree_nodes(&self) -> HashSet<Vec<TreeId>> {
+        let mut nodes = HashSet::new();
+        for root in &self.tree_items {
+            let root_id = root.identifier().clone();
+            for group in root.children() {
+                if self.group_by_repo || is_file_group_id(group.identifier()) {
+                    nodes.insert(vec![root_id.clone(), group.identifier().clone()]);
+                }
+            }
+            nodes.insert(vec![root_id]);
+        }
+        nodes
+    }
+
+    /// Whether `path` leads to a node of the tree.
+    fn tree_contains(&self, path: &[TreeId]) -> bool {
+        let mut items = self.tree_items.as_slice();
+        for id in path {
+            let Some(item) = items.iter().find(|item| item.identifier() == id) else {
+                return false;
+            };
+            items = item.children();
+        }
+        !path.is_empty()
+    }
+
+    /// Selects the first file of the root that held `removed` that sorts
+    /// after it, or the root's last file, or the root itself when it has
+    /// no files left.
+    fn select_neighbour_of(&mut self, removed: &Path) {
+        let Some(root) = self
+            .roots
+            .iter()
+            .filter(|root| removed.starts_with(&root.path))
+            .max_by_key(|root| root.path.as_os_str().len())
+        else {
+            self.tree_state.select_first();
+            return;
+        };
+        let neighbour = root
+            .file_paths()
+            .find(|f| f.as_path() > removed)
+            .or_else(|| root.file_paths().last())
+            .cloned();
+        match neighbour {
+            Some(file) => {
+                self.select_tree_file(&file);
+            }
+            None => {
+                self.tree_state
+                    .select(vec![root.path.display().to_string()]);
+            }
+        }
+    }
+
+    /// Reloads the shown file, keeping the cursor, scroll and visual anchor
+    /// where they were as far as the new text allows.
+    pub(crate) fn reload_keeping_position(&mut self) {
+        let cursor = self.content.cursor;
+        let scroll = self.content.scroll;
+        let anchor = self.content.visual_anchor;
+        self.load_selected_content();
+        let last = self.content.line_count().saturating_sub(1);
+        self.content.cursor = cursor.min(last);
+        self.content.scroll = scroll.min(last as u16);
+        self.content.visual_anchor = anchor.map(|a| a.min(last));
+    }
+}
+
+/// `roots` as a fresh scan with `options` finds them now. Runs on the
+/// watcher's thread as well as for a rescan.
+pub(crate) fn rescan_roots(roots: &[SourceRoot], options: &ScanOptions) -> Vec<SourceRoot> {
+    let global = find_global_claude_file();
+    roots
+        .iter()
+        .map(|root| {
+            // The root added for the global CLAUDE.md holds that one
+            // file; scanning its directory would pull in everything
+            // else under ~/.claude.
+            let is_global_root = global
+                .as_ref()
+                .is_some_and(|global| root.files.len() == 1 && root.files[0].path == *global);
+            if !is_global_root {
+                return options.scan_root(&root.path);
+            }
+            SourceRoot {
+                path: root.path.clone(),
+                files: root
+                    .file_paths()
+                    .filter(|f| f.is_file())
+                    .map(|f| discovered_file(f.clone()))
+                    .collect(),
+                context_files: context_files_in(&root.path),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::test_helpers::key_event;
+
+    fn root_with(tmp: &TempDir, names: &[&str]) -> SourceRoot {
+        let mut files = Vec::new();
+        for name in names {
+            let file = tmp.path().join(name).join("CLAUDE.md");
+            fs::create_dir_all(file.parent().unwrap()).unwrap();
+            fs::write(
+                &file,
+                format!("{name} line 1\n{name} line 2\n{name} line 3\n"),
+            )
+            .unwrap();
+            files.push(DiscoveredFile::new(file));
+        }
+        SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files,
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }
+    }
+
+    #[test]
+    fn selection_cursor_and_collapsed_roots_survive_rescan() {
+        let tmp = TempDir::new().unwrap();
+        let other = TempDir::new().unwrap();
+        let mut app = App::new(
+            vec![root_with(&tmp, &["a", "b"]), root_with(&other, &["x"])],
+            &Config::default(),
+        );
+        let b = tmp.path().join("b/CLAUDE.md");
+        app.select_tree_file(&b);
+        app.load_selected_content();
+        app.content.cursor = 2;
+        app.tree_state.close(&[other.path().display().to_string()]);
+
+        app.replace_roots(vec![
+            root_with(&tmp, &["a", "b", "c"]),
+            root_with(&other, &["x"]),
+        ]);
+
+        assert_eq!(app.selected_file(), Some(b));
+        assert_eq!(app.content.cursor, 2);
+        let opened = app.tree_state.opened();
+        assert!(opened.contains(&vec![tmp.path().display().to_string()]));
+        assert!(!opened.contains(&vec![other.path().display().to_string()]));
+    }
+
+    #[test]
+    fn new_roots_start_expanded() {
+        let tmp = TempDir::new().unwrap();
+        let added = TempDir::new().unwrap();
+        let mut app = App::new(vec![root_with(&tmp, &["a"])], &Config::default());
+
+        app.replace_roots(vec![root_with(&tmp, &["a"]), root_with(&added, &["y"])]);
+
+        assert!(
+            app.tree_state
+                .opened()
+                .contains(&vec![added.path().display().to_string()])
+        );
+    }
+
+    #[test]
+    fn removed_file_selects_next_file_of_its_root() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = App::new(vec![root_with(&tmp, &["a", "b", "c"])], &Config::default());
+        app.select_tree_file(&tmp.path().join("b/CLAUDE.md"));
+        app.load_selected_content();
+        app.content.cursor = 2;
+
+        app.replace_roots(vec![root_with(&tmp, &["a", "c"])]);
+
+        assert_eq!(app.selected_file(), Some(tmp.path().join("c/CLAUDE.md")));
+        assert_eq!(app.content.cursor, 0);
+        assert!(app.content.text.as_deref().unwrap().starts_with("c line 1"));
+    }
+
+    #[test]
+    fn f5_rescans_and_reports_changes() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = App::new(vec![root_with(&tmp, &["a"])], &Config::default());
+        fs::create_dir_all(tmp.path().join("new")).unwrap();
+        fs::write(tmp.path().join("new/CLAUDE.md"), "fresh").unwrap();
+
+        app.handle_key_event(key_event(KeyCode::F(5)));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert_eq!(app.roots[0].files.len(), 2);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Rescanned: 2 files (1 added, 0 removed).")
+        );
+    }
+}
@@ -0,0 +1,337 @@
+//! Scanning the roots on a background thread.
+//!
+//! The TUI opens at once with an empty node per directory, and each root
+//! fills in with the files its walk finds, a batch at a time, until the
+//! finished root replaces it. The event loop picks up the results between
+//! key presses, so the app stays usable while large trees are walked.
+
+use std::mem;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::TryRecvError;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+use super::app::App;
+use super::picker::Picker;
+use super::picker::PickerAction;
+use super::picker::PickerItem;
+use crate::config::PROJECT_CONFIG_FILE;
+use crate::config::find_project_config;
+use crate::discovery::ScanOptions;
+use crate::discovery::global_root;
+use crate::model::DiscoveredFile;
+use crate::model::SourceRoot;
+use crate::warnings::warn;
+
+/// What the scanning thread reports. Problems worth telling the user
+/// about, such as an unreadable project config, go to
+/// [`warn`](crate::warnings::warn).
+#[derive(Debug)]
+pub enum ScanMessage {
+    /// CLAUDE.md files the walk of the directory at this position found
+    /// since the last batch.
+    Files(usize, Vec<PathBuf>),
+    /// The scanned root for the directory at this position.
+    Root(usize, SourceRoot),
+}
+
+/// A scan running on its own thread, present while scanning.
+#[derive(Debug)]
+pub struct BackgroundScan {
+    receiver: Receiver<ScanMessage>,
+    /// Index in `App::roots` of the first scanned directory; the global
+    /// root may come before it.
+    first: usize,
+    /// Whether the global root still has to be added if no scan finds the
+    /// global CLAUDE.md.
+    global_pending: bool,
+}
+
+/// How long the scanning thread collects files before sending a batch.
+const BATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Scans `dirs` in order on a new thread, sending the files of each in
+/// batches as they are found and then the root when it is done.
+pub fn spawn_scan(dirs: Vec<PathBuf>, options: ScanOptions) -> Receiver<ScanMessage> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        for (index, dir) in dirs.iter().enumerate() {
+            if let Some((base, Err(err))) = find_project_config(dir) {
+                warn(format!(
+                    "ignoring {}: {err:#}",
+                    base.join(PROJECT_CONFIG_FILE).display()
+                ));
+            }
+            let mut batch = Vec::new();
+            let mut sent = Instant::now();
+            let root = options.scan_root_reporting(dir, |file| {
+                batch.push(file.to_path_buf());
+                if sent.elapsed() >= BATCH_INTERVAL {
+                    // A closed channel is noticed when the root is sent.
+                    let _ = sender.send(ScanMessage::Files(index, mem::take(&mut batch)));
+                    sent = Instant::now();
+                }
+            });
+            // The app may have quit; nobody is listening then.
+            if sender.send(ScanMessage::Root(index, root)).is_err() {
+                return;
+            }
+        }
+    });
+    receiver
+}
+
+impl App {
+    /// Shows an empty root for each of `dirs` and starts scanning them on a
+    /// background thread with the app's scan options.
+    pub fn scan_in_background(&mut self, dirs: Vec<PathBuf>) {
+        let mut roots: Vec<SourceRoot> = dirs
+            .iter()
+            .map(|dir| SourceRoot {
+                path: dir.clone(),
+                files: Vec::new(),
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            })
+            .collect();
+        // The global root goes first, as after a blocking scan. When a
+        // scanned directory holds the global CLAUDE.md, whether the scan
+        // reaches it is only known at the end.
+        let global = global_root().filter(|_| self.scan_options.include_global);
+        let global_pending = global
+            .as_ref()
+            .is_some_and(|global| dirs.iter().any(|dir| global.files[0].path.starts_with(dir)));
+        let mut first = 0;
+        if let Some(global) = global.filter(|_| !global_pending) {
+            roots.insert(0, global);
+            first = 1;
+        }
+        self.replace_roots(roots);
+        self.select_first_file();
+        self.background_scan = Some(BackgroundScan {
+            receiver: spawn_scan(dirs, self.scan_options.clone()),
+            first,
+            global_pending,
+        });
+    }
+
+    /// Asks whether to scan the whole repository at `root` instead of the
+    /// directory below it that the app was started in.
+    pub fn offer_repo_root(&mut self, root: PathBuf) {
+        let started_in = self
+            .roots
+            .last()
+            .map(|started_in| started_in.path.display().to_string())
+            .unwrap_or_default();
+        let items = vec![
+            PickerItem::new(format!("Scan {}", root.display()), "the whole repository"),
+            PickerItem::new(format!("Stay in {started_in}"), "only below here"),
+        ];
+        self.open_picker(Picker::new(
+            "Inside a git repository",
+            items,
+            PickerAction::ScanRepoRoot { root },
+        ));
+    }
+
+    /// Whether a background scan is still running.
+    pub fn is_scanning(&self) -> bool {
+        self.background_scan.is_some()
+    }
+
+    /// Applies the files and roots the background scan sent since the last
+    /// call. The tree is only rebuilt when something arrived.
+    pub(crate) fn receive_scan_results(&mut self) {
+        let Some(scan) = &self.background_scan else {
+            return;
+        };
+        let (first, global_pending) = (scan.first, scan.global_pending);
+        let mut messages = Vec::new();
+        let finished = loop {
+            match scan.receiver.try_recv() {
+                Ok(message) => messages.push(message),
+                Err(TryRecvError::Empty) => break false,
+                Err(TryRecvError::Disconnected) => break true,
+            }
+        };
+        if messages.is_empty() && !finished {
+            return;
+        }
+
+        let mut roots = self.roots.clone();
+        for message in messages {
+            match message {
+                ScanMessage::Files(index, files) => {
+                    if let Some(slot) = roots.get_mut(first + index) {
+                        slot.files
+                            .extend(files.into_iter().map(DiscoveredFile::new));
+                        slot.files.sort_unstable();
+                    }
+                }
+                ScanMessage::Root(index, root) => {
+                    if let Some(slot) = roots.get_mut(first + index) {
+                        *slot = root;
+                    }
+                }
+            }
+        }
+        if finished
+            && global_pending
+            && let Some(global) = global_root()
+            && !roots
+                .iter()
+                .any(|root| root.has_file(&global.files[0].path))
+        {
+            roots.insert(0, global);
+        }
+        if finished {
+            self.background_scan = None;
+            let count = roots.iter().map(|root| root.files.len()).sum::<usize>();
+            let message = match self.scan_options.file_limit_note(&roots) {
+                Some(note) => format!("Scan complete: {count} files. {note}"),
+                None => format!("Scan complete: {count} files."),
+            };
+            self.status_message.get_or_insert(message);
+        }
+        let had_file = self.selected_file().is_some();
+        self.replace_roots(roots);
+        if !had_file {
+            self.select_first_file();
+        }
+        self.try_restore_ui_state();
+        if finished {
+            self.start_background_lint();
+        }
+    }
+
+    /// Selects the first CLAUDE.md file in the tree, if there is one.
+    fn select_first_file(&mut self) {
+        let first = self
+            .roots
+            .iter()
+            .find_map(|root| root.file_paths().next())
+            .cloned();
+        if let Some(file) = first {
+            self.select_tree_file(&file);
+            self.load_selected_content();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::sync::mpsc;
+    use std::thread;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use super::BackgroundScan;
+    use super::ScanMessage;
+    use crate::config::Config;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::test_helpers::key_event;
+
+    #[test]
+    fn roots_fill_in_as_the_background_scan_finishes() {
+        let tmp = TempDir::new().unwrap();
+        let a = tmp.path().join("a");
+        let b = tmp.path().join("b");
+        fs::create_dir_all(a.join("sub")).unwrap();
+        fs::create_dir_all(&b).unwrap();
+        fs::write(a.join("sub/CLAUDE.md"), "A").unwrap();
+        fs::write(b.join("CLAUDE.md"), "B").unwrap();
+        let mut app = App::new(Vec::new(), &Config::default());
+
+        app.scan_in_background(vec![a.clone(), b.clone()]);
+        let first = app.roots.len() - 2;
+        assert!(app.is_scanning());
+        assert_eq!(app.roots[first].path, a);
+        assert!(app.roots[first].files.is_empty());
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while app.is_scanning() && Instant::now() < deadline {
+            app.receive_scan_results();
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        assert!(!app.is_scanning());
+        assert_eq!(
+            app.roots[first].file_paths().collect::<Vec<_>>(),
+            [&a.join("sub/CLAUDE.md")]
+        );
+        assert_eq!(
+            app.roots[first + 1].file_paths().collect::<Vec<_>>(),
+            [&b.join("CLAUDE.md")]
+        );
+        assert!(app.selected_file().is_some());
+    }
+
+    #[test]
+    fn batches_of_files_show_before_the_root_is_done() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "A").unwrap();
+        let mut app = App::new(Vec::new(), &Config::default());
+        app.scan_in_background(Vec::new());
+        let (sender, receiver) = mpsc::channel();
+        app.roots.push(SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: Vec::new(),
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        });
+        app.background_scan = Some(BackgroundScan {
+            receiver,
+            first: app.roots.len() - 1,
+            global_pending: false,
+        });
+
+        app.receive_scan_results();
+        assert!(app.roots.last().unwrap().files.is_empty());
+
+        sender
+            .send(ScanMessage::Files(0, vec![file.clone()]))
+            .unwrap();
+        app.receive_scan_results();
+
+        assert!(app.is_scanning());
+        assert!(app.roots.last().unwrap().has_file(&file));
+        assert_eq!(app.selected_file(), Some(file));
+    }
+
+    #[test]
+    fn choosing_the_repo_root_scans_the_whole_repository() {
+        let tmp = TempDir::new().unwrap();
+        let repo = tmp.path().join("repo");
+        let deep = repo.join("crates/deep");
+        fs::create_dir_all(repo.join(".git")).unwrap();
+        fs::create_dir_all(&deep).unwrap();
+        fs::write(repo.join("CLAUDE.md"), "top").unwrap();
+        let mut app = App::new(Vec::new(), &Config::default());
+        app.scan_in_background(vec![deep.clone()]);
+
+        app.offer_repo_root(repo.clone());
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(app.roots.last().map(|root| &root.path), Some(&repo));
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while app.is_scanning() && Instant::now() < deadline {
+            app.receive_scan_results();
+            thread::sleep(Duration::from_millis(5));
+        }
+        assert_eq!(
+            app.roots.last().unwrap().file_paths().collect::<Vec<_>>(),
+            [&repo.join("CLAUDE.md")]
+        );
+    }
+}
@@ -0,0 +1,323 @@
+use std::path::Path;
+
+use ratatui::Frame;
+use ratatui::layout::Constraint;
+use ratatui::layout::Direction;
+use ratatui::layout::Layout;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Text;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+
+use super::action::Action;
+use super::action::Effect;
+use super::app::App;
+use super::app::Mode;
+use super::picker::centered_rect;
+use super::text_input::TextInput;
+use crate::search::FileHits;
+use crate::search::FileText;
+use crate::search::read_texts;
+use crate::search::search_texts;
+
+/// Most rows the search popup grows to before it scrolls.
+const SEARCH_MAX_ROWS: u16 = 20;
+
+/// Overlay searching the text of every discovered file.
+#[derive(Debug, Default)]
+pub struct GlobalSearch {
+    pub query: TextInput,
+    /// The discovered files as they were when the overlay opened, read
+    /// once so typing does not read them all again.
+    pub texts: Vec<FileText>,
+    /// Hits for `query`, grouped by file in tree order.
+    pub results: Vec<FileHits>,
+    /// Index of the highlighted hit, counting across all files.
+    pub cursor: usize,
+}
+
+impl GlobalSearch {
+    /// Number of matching lines across all files.
+    pub fn hit_count(&self) -> usize {
+        self.results.iter().map(|r| r.hits.len()).sum()
+    }
+
+    /// File and 0-based line of the highlighted hit.
+    pub fn selected(&self) -> Option<(&Path, usize)> {
+        self.results
+            .iter()
+            .flat_map(|r| r.hits.iter().map(move |hit| (r.file.as_path(), hit.line)))
+            .nth(self.cursor)
+    }
+}
+
+impl App {
+    /// Opens the search overlay with an empty query, reading the text of
+    /// every discovered file.
+    pub(crate) fn open_global_search(&mut self) {
+        let files = self.discovered_files();
+        if files.is_empty() {
+            self.status_message = Some("No CLAUDE.md files loaded.".to_string());
+            return;
+        }
+        self.search = Some(GlobalSearch {
+            texts: read_texts(&files),
+            ..GlobalSearch::default()
+        });
+        self.mode = Mode::GlobalSearch;
+    }
+
+    /// Applies an action while the search overlay is open.
+    pub(crate) fn update_global_search(&mut self, action: Action) -> Vec<Effect> {
+        let Some(search) = &mut self.search else {
+            self.mode = Mode::Normal;
+            return Vec::new();
+        };
+        match action {
+            Action::Input(key) => {
+                let before = search.query.text().to_string();
+                search.query.handle_edit_key(key.code);
+                // Moving the cursor in the query changes nothing to search.
+                if search.query.text() != before {
+                    return vec![Effect::RunGlobalSearch];
+                }
+            }
+            Action::MoveDown => {
+                if search.cursor + 1 < search.hit_count() {
+                    search.cursor += 1;
+                }
+            }
+            Action::MoveUp => search.cursor = search.cursor.saturating_sub(1),
+            Action::Confirm => {
+                let chosen = search
+                    .selected()
+                    .map(|(file, line)| (file.to_path_buf(), line));
+                self.search = None;
+                self.mode = Mode::Normal;
+                if let Some((file, line)) = chosen {
+                    return vec![Effect::ShowLine { file, line }];
+                }
+            }
+            Action::Cancel => {
+                self.search = None;
+                self.mode = Mode::Normal;
+            }
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    /// Reruns the search for the current query over the files read when
+    /// the overlay opened.
+    pub(crate) fn run_global_search(&mut self) {
+        if let Some(search) = &mut self.search {
+            search.results = search_texts(&search.texts, search.query.text());
+            search.cursor = 0;
+        }
+    }
+
+    /// Draws the search overlay centred over `area`.
+    pub(crate) fn draw_global_search(&self, frame: &mut Frame, area: Rect) {
+        let Some(search) = &self.search else {
+            return;
+        };
+
+        let mut lines = Vec::new();
+        let mut cursor_row = 0;
+        let mut hit_index = 0;
+        for result in &search.results {
+            lines.push(Line::styled(
+                format!(" {}", result.file.display()),
+                self.theme.active_tab,
+            ));
+            for hit in &result.hits {
+                let style = if hit_index == search.cursor {
+                    cursor_row = lines.len();
+                    self.theme.highlight
+                } else {
+                    Style::default()
+                };
+                lines.push(Line::styled(
+                    format!("   {:>4}: {}", hit.line + 1, hit.text),
+                    style,
+                ));
+                hit_index += 1;
+            }
+        }
+        if lines.is_empty() {
+            let message = if search.query.text().trim().is_empty() {
+                " Type to search every CLAUDE.md"
+            } else {
+                " No matches"
+            };
+            lines.push(Line::styled(message, self.theme.help_desc));
+        }
+
+        let rows = (lines.len() as u16).clamp(1, SEARCH_MAX_ROWS);
+        // Border, query line and separator come on top of the result rows.
+        let popup = centered_rect(80, rows + 4, area);
+        let block = Block::default()
+            .borders(Borders::ALL)
+            .border_style(self.theme.input_border)
+            .title(format!(
+                "Search ({} lines in {} files)",
+                search.hit_count(),
+                search.results.len()
+            ));
+        let inner = block.inner(popup);
+        frame.render_widget(Clear, popup);
+        frame.render_widget(block, popup);
+
+        let parts = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([
+                Constraint::Length(1),
+                Constraint::Length(1),
+                Constraint::Min(0),
+            ])
+            .split(inner);
+        frame.render_widget(
+            Paragraph::new(format!("/ {}", search.query.text())),
+            parts[0],
+        );
+        frame.render_widget(
+            Paragraph::new("─".repeat(usize::from(parts[1].width))).style(self.theme.help_desc),
+            parts[1],
+        );
+        frame.set_cursor_position((parts[0].x + 2 + search.query.cursor() as u16, parts[0].y));
+
+        let viewport = usize::from(parts[2].height);
+        let scroll = (cursor_row + 1).saturating_sub(viewport);
+        frame.render_widget(
+            Paragraph::new(Text::from(lines)).scroll((scroll as u16, 0)),
+            parts[2],
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::action::Action;
+    use crate::tui::action::Effect;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::Pane;
+    use crate::tui::app::Screen;
+    use crate::tui::app::test_helpers::key_event;
+    use crate::tui::app::test_helpers::render_once;
+
+    fn search_app(tmp: &TempDir) -> App {
+        let first = tmp.path().join("CLAUDE.md");
+        let nested = tmp.path().join("api/CLAUDE.md");
+        fs::create_dir_all(tmp.path().join("api")).unwrap();
+        fs::write(&first, "# Root\nUse cargo nextest\n").unwrap();
+        fs::write(&nested, "# API\n\nDeploy steps\nRun cargo build\n").unwrap();
+        App::new(
+            vec![SourceRoot {
+                path: tmp.path().to_path_buf(),
+                files: vec![DiscoveredFile::new(first), DiscoveredFile::new(nested)],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        )
+    }
+
+    fn type_text(app: &mut App, text: &str) {
+        for c in text.chars() {
+            app.handle_key_event(key_event(KeyCode::Char(c)));
+        }
+    }
+
+    #[test]
+    fn typing_searches_every_file() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = search_app(&tmp);
+
+        app.handle_key_event(key_event(KeyCode::Char('/')));
+        assert_eq!(app.mode, Mode::GlobalSearch);
+        type_text(&mut app, "cargo");
+
+        let search = app.search.as_ref().unwrap();
+        assert_eq!(search.results.len(), 2);
+        assert_eq!(search.hit_count(), 2);
+        render_once(&mut app);
+    }
+
+    #[test]
+    fn enter_jumps_to_file_and_line() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = search_app(&tmp);
+        app.screen = Screen::Files;
+
+        app.handle_key_event(key_event(KeyCode::Char('/')));
+        type_text(&mut app, "cargo");
+        app.handle_key_event(key_event(KeyCode::Down));
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.search.is_none());
+        assert_eq!(app.selected_file(), Some(tmp.path().join("api/CLAUDE.md")));
+        assert_eq!(app.active_pane, Pane::Content);
+        assert_eq!(app.content.cursor, 3);
+    }
+
+    #[test]
+    fn letters_are_typed_not_commands() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = search_app(&tmp);
+
+        app.handle_key_event(key_event(KeyCode::Char('/')));
+        type_text(&mut app, "q");
+
+        assert!(!app.exit);
+        assert_eq!(app.search.as_ref().unwrap().query.text(), "q");
+    }
+
+    #[test]
+    fn files_are_read_once_and_only_edits_search_again() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = search_app(&tmp);
+
+        app.handle_key_event(key_event(KeyCode::Char('/')));
+        fs::write(tmp.path().join("CLAUDE.md"), "cargo cargo\n").unwrap();
+        type_text(&mut app, "cargo");
+        assert_eq!(app.search.as_ref().unwrap().hit_count(), 2);
+        app.handle_key_event(key_event(KeyCode::Down));
+
+        let moved = app.update_global_search(Action::Input(key_event(KeyCode::Left)));
+        assert!(moved.is_empty());
+        assert_eq!(app.search.as_ref().unwrap().cursor, 1);
+
+        let edited = app.update_global_search(Action::Input(key_event(KeyCode::Backspace)));
+        assert_eq!(edited, [Effect::RunGlobalSearch]);
+    }
+
+    #[test]
+    fn esc_closes_without_moving() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = search_app(&tmp);
+        let before = app.selected_file();
+
+        app.handle_key_event(key_event(KeyCode::Char('/')));
+        type_text(&mut app, "deploy");
+        app.handle_key_event(key_event(KeyCode::Esc));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.search.is_none());
+        assert_eq!(app.selected_file(), before);
+    }
+}
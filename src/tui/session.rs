@@ -0,0 +1,226 @@
+//! The session simulation popup: what Claude Code would load if started in
+//! a chosen directory, as one document to read, copy or write to a file.
+
+use std::path::Path;
+use std::path::PathBuf;
+
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::text::Text;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+
+use super::action::Action;
+use super::action::Effect;
+use super::app::App;
+use super::app::Mode;
+use super::markdown::render_markdown;
+use super::picker::centered_rect;
+use crate::fsutil::expand_home;
+use crate::fsutil::write_atomic;
+use crate::session::render_session;
+use crate::session::simulate_session;
+
+/// The simulated session, open while in `Mode::Session`.
+#[derive(Debug)]
+pub struct SessionView {
+    /// Directory the session starts in.
+    pub dir: PathBuf,
+    /// The Markdown document, as `jigolo session` prints it.
+    pub document: String,
+    /// First visible row.
+    pub scroll: u16,
+    /// Captured during draw — visible rows inside the border.
+    pub viewport_height: u16,
+}
+
+impl App {
+    /// Asks for the directory to simulate a session in, starting from the
+    /// one selected in the tree.
+    pub(crate) fn start_session_simulation(&mut self) {
+        let dir = self.selected_directory().unwrap_or_default();
+        self.text_input.set(&dir.display().to_string());
+        self.mode = Mode::SessionDir;
+    }
+
+    /// The directory of the tree selection: a file's own directory, or the
+    /// root or repository selected.
+    fn selected_directory(&self) -> Option<PathBuf> {
+        if let Some(file) = self.selected_file() {
+            return file.parent().map(Path::to_path_buf);
+        }
+        let selected = self.tree_state.selected();
+        selected
+            .iter()
+            .rev()
+            .map(PathBuf::from)
+            .find(|path| path.is_dir())
+    }
+
+    /// Simulates a session in the directory typed in. Relative paths are
+    /// taken from the selected root.
+    pub(crate) fn simulate_session_from_input(&mut self) {
+        let raw = self.text_input.text().trim().to_string();
+        self.text_input.clear();
+        self.mode = Mode::Normal;
+        let Some(typed) = expand_home(&raw) else {
+            self.status_message = Some("Cannot expand ~: HOME not set.".to_string());
+            return;
+        };
+        let dir = match self.selected_root() {
+            Some(root) if typed.is_relative() => root.join(typed),
+            _ => typed,
+        };
+        if !dir.is_dir() {
+            self.status_message = Some(format!("Not a directory: {}", dir.display()));
+            return;
+        }
+        let dir = dir.canonicalize().unwrap_or(dir);
+        self.open_session(&dir);
+    }
+
+    /// Opens the popup on a session started in `dir`.
+    pub fn open_session(&mut self, dir: &Path) {
+        let session = simulate_session(dir);
+        self.session_view = Some(SessionView {
+            dir: dir.to_path_buf(),
+            document: render_session(&session, self.config.tokenizer()),
+            scroll: 0,
+            viewport_height: 0,
+        });
+        self.mode = Mode::Session;
+    }
+
+    /// Writes the document to the path typed in and goes back to it.
+    pub(crate) fn export_session_from_input(&mut self) {
+        let raw = self.text_input.text().trim().to_string();
+        self.text_input.clear();
+        self.mode = Mode::Session;
+        let Some(view) = &self.session_view else {
+            self.mode = Mode::Normal;
+            return;
+        };
+        if raw.is_empty() {
+            self.status_message = Some("No path entered.".to_string());
+            return;
+        }
+        let Some(path) = expand_home(&raw) else {
+            self.status_message = Some("Cannot expand ~: HOME not set.".to_string());
+            return;
+        };
+        self.status_message = Some(match write_atomic(&path, &view.document) {
+            Ok(()) => format!("Wrote the session to {}.", path.display()),
+            Err(err) => format!("Cannot write {}: {err}", path.display()),
+        });
+    }
+
+    /// Draws the document as a popup over `area`.
+    pub(crate) fn draw_session(&mut self, frame: &mut Frame, area: Rect) {
+        let border = self.theme.input_border;
+        let popup = centered_rect(90, area.height.saturating_sub(2), area);
+        let Some(view) = &mut self.session_view else {
+            return;
+        };
+        let lines = render_markdown(&view.document, &self.theme, popup.width.saturating_sub(2));
+        view.viewport_height = popup.height.saturating_sub(2);
+        view.scroll = view.scroll.min(max_scroll(lines.len(), view));
+        let widget = Paragraph::new(Text::from(lines))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(border)
+                    .title(format!("Session in {}", view.dir.display())),
+            )
+            .scroll((view.scroll, 0));
+        frame.render_widget(Clear, popup);
+        frame.render_widget(widget, popup);
+    }
+
+    /// Applies an action while the popup is open.
+    pub(crate) fn update_session(&mut self, action: Action) -> Vec<Effect> {
+        let Some(view) = &mut self.session_view else {
+            self.mode = Mode::Normal;
+            return Vec::new();
+        };
+        let page = view.viewport_height.max(1);
+        let max = max_scroll(view.document.lines().count(), view);
+        match action {
+            Action::Cancel => {
+                self.session_view = None;
+                self.mode = Mode::Normal;
+            }
+            Action::CopyFile => return vec![Effect::Copy(view.document.clone())],
+            Action::Export => {
+                self.text_input.clear();
+                self.mode = Mode::SessionExportPath;
+            }
+            Action::MoveDown => view.scroll = (view.scroll + 1).min(max),
+            Action::MoveUp => view.scroll = view.scroll.saturating_sub(1),
+            Action::PageDown => view.scroll = view.scroll.saturating_add(page).min(max),
+            Action::PageUp => view.scroll = view.scroll.saturating_sub(page),
+            _ => {}
+        }
+        Vec::new()
+    }
+}
+
+fn max_scroll(line_count: usize, view: &SessionView) -> u16 {
+    let max = line_count.saturating_sub(view.viewport_height as usize);
+    max.min(u16::MAX as usize) as u16
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::test_helpers::key_event;
+
+    #[test]
+    fn s_simulates_a_session_in_the_typed_directory_and_w_writes_it() {
+        let tmp = TempDir::new().unwrap();
+        let root = tmp.path().join("p");
+        fs::create_dir_all(root.join("src")).unwrap();
+        fs::write(root.join("CLAUDE.md"), "Use tabs\n").unwrap();
+        let roots = vec![SourceRoot {
+            path: root.clone(),
+            files: vec![DiscoveredFile::new(root.join("CLAUDE.md"))],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+
+        app.handle_key_event(key_event(KeyCode::Char('S')));
+        assert_eq!(app.mode, Mode::SessionDir);
+        assert_eq!(app.text_input.text(), root.display().to_string());
+        app.text_input.set("src");
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(app.mode, Mode::Session);
+        let view = app.session_view.as_ref().unwrap();
+        assert_eq!(view.dir, root.join("src").canonicalize().unwrap());
+        assert!(view.document.contains("Use tabs"));
+
+        let out = tmp.path().join("session.md");
+        app.handle_key_event(key_event(KeyCode::Char('w')));
+        assert_eq!(app.mode, Mode::SessionExportPath);
+        app.text_input.set(&out.display().to_string());
+        app.handle_key_event(key_event(KeyCode::Enter));
+
+        assert_eq!(app.mode, Mode::Session);
+        assert!(fs::read_to_string(&out).unwrap().contains("Use tabs"));
+        app.handle_key_event(key_event(KeyCode::Esc));
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.session_view.is_none());
+    }
+}
@@ -1,9 +1,7 @@
+use std::fs;
 use std::path::Path;
-use std::path::PathBuf;
 
 use ratatui::Frame;
-use ratatui::crossterm::event::KeyCode;
-use ratatui::crossterm::event::KeyEvent;
 use ratatui::style::Style;
 use ratatui::text::Line;
 use ratatui::text::Text;
@@ -14,11 +12,17 @@ use ratatui::widgets::Scrollbar;
 use ratatui::widgets::ScrollbarOrientation;
 use ratatui::widgets::ScrollbarState;
 
+use super::action::Action;
+use super::action::Effect;
 use super::app::App;
 use super::app::Mode;
 use super::app::Screen;
+use super::external::ExternalEdit;
 use crate::settings::SettingsCollection;
-use crate::settings::SettingsFile;
+use crate::settings::display_line_keys;
+use crate::settings::effective_settings;
+use crate::settings::find_json_line;
+use crate::settings::format_effective_settings_with_map;
 use crate::settings::format_settings_with_map;
 
 impl App {
@@ -67,33 +71,43 @@ impl App {
         frame.render_stateful_widget(scrollbar, area, &mut scrollbar_state);
     }
 
-    pub(crate) fn handle_settings_key(&mut self, key_event: KeyEvent) {
-        match key_event.code {
-            KeyCode::Char('e') if !self.settings_state.merged_view => {
-                self.enter_settings_edit_mode();
-            }
-            KeyCode::Char('e') => {
+    /// Applies an action on the Settings screen.
+    pub(crate) fn update_settings(&mut self, action: Action) -> Vec<Effect> {
+        match action {
+            Action::Edit if !self.settings_state.merged_view => return vec![Effect::EditSettings],
+            Action::Edit => {
                 self.status_message =
                     Some("Edit not available in merged view — press m to switch.".to_string());
             }
-            KeyCode::Char('m') => {
-                self.settings_state.merged_view = !self.settings_state.merged_view;
-                self.rebuild_settings_display();
-            }
-            KeyCode::Char('q') => self.exit = true,
-            KeyCode::Down | KeyCode::Char('j') => {
-                self.settings_state.cursor_down();
+            Action::OpenInEditor if !self.settings_state.merged_view => {
+                return vec![Effect::OpenSettingsInEditor];
             }
-            KeyCode::Up | KeyCode::Char('k') => {
-                self.settings_state.cursor_up();
+            Action::OpenInEditor => {
+                self.status_message =
+                    Some("Open not available in merged view — press m to switch.".to_string());
             }
-            KeyCode::PageDown => {
-                self.settings_state.cursor_page_down();
+            Action::ToggleMergedView => {
+                self.settings_state.merged_view = !self.settings_state.merged_view;
+                return vec![Effect::RebuildSettings];
             }
-            KeyCode::PageUp => {
-                self.settings_state.cursor_page_up();
+            Action::MoveDown => self.settings_state.cursor_down(),
+            Action::MoveUp => self.settings_state.cursor_up(),
+            Action::PageDown => self.settings_state.cursor_page_down(),
+            Action::PageUp => self.settings_state.cursor_page_up(),
+            Action::MoveTop => self.settings_state.cursor_top(),
+            Action::MoveBottom => self.settings_state.cursor_bottom(),
+            Action::ToggleFold => {
+                let cursor = self.settings_state.cursor;
+                if self.settings_state.is_foldable(cursor) {
+                    self.settings_state.toggle_fold(cursor);
+                } else if let Some(parent) = self.settings_state.parent_for(cursor) {
+                    // On a child line: fold the section it is in
+                    self.settings_state.toggle_fold(parent);
+                    self.settings_state.cursor = parent;
+                    self.settings_state.ensure_cursor_visible();
+                }
             }
-            KeyCode::Left | KeyCode::Char('h') => {
+            Action::MoveLeft => {
                 let cursor = self.settings_state.cursor;
                 if self.settings_state.is_foldable(cursor)
                     && !self.settings_state.collapsed.contains(&cursor)
@@ -106,7 +120,7 @@ impl App {
                     self.settings_state.ensure_cursor_visible();
                 }
             }
-            KeyCode::Right | KeyCode::Char('l') => {
+            Action::MoveRight => {
                 let cursor = self.settings_state.cursor;
                 if self.settings_state.collapsed.contains(&cursor) {
                     // On a collapsed line: expand it
@@ -115,6 +129,7 @@ impl App {
             }
             _ => {}
         }
+        Vec::new()
     }
 
     pub(crate) fn switch_to_settings(&mut self) {
@@ -146,20 +161,12 @@ impl App {
     ///
     /// Uses per-file formatting or merged formatting depending on
     /// `settings_state.merged_view`.
-    fn rebuild_settings_display(&mut self) {
+    pub(crate) fn rebuild_settings_display(&mut self) {
         let Some(collection) = &self.settings_collection else {
             return;
         };
         let (lines, line_map) = if self.settings_state.merged_view {
-            let merged = crate::settings::merge_settings(collection);
-            let synthetic = SettingsCollection {
-                files: vec![SettingsFile {
-                    label: "Effective".to_string(),
-                    path: PathBuf::new(),
-                    value: merged,
-                }],
-            };
-            format_settings_with_map(&synthetic)
+            format_effective_settings_with_map(&effective_settings(collection))
         } else {
             format_settings_with_map(collection)
         };
@@ -189,7 +196,35 @@ impl App {
         self.apply_settings_collection(collection);
     }
 
-    fn enter_settings_edit_mode(&mut self) {
+    /// The JSON keys leading to the line under the cursor, outermost first.
+    pub(crate) fn settings_keys_at_cursor(&self) -> Vec<String> {
+        let state = &self.settings_state;
+        let mut lines = vec![state.cursor];
+        while let Some(parent) = lines.last().and_then(|&line| state.parent_for(line)) {
+            lines.push(parent);
+        }
+        lines
+            .iter()
+            .rev()
+            .filter_map(|&line| state.lines.get(line))
+            .flat_map(|line| display_line_keys(line))
+            .collect()
+    }
+
+    /// Queues the settings file under the cursor for `$EDITOR`, at about
+    /// the line of the selected key.
+    pub(crate) fn open_settings_in_editor(&mut self) {
+        let Some(path) = self.settings_file_at_cursor().map(Path::to_path_buf) else {
+            self.status_message = Some("No settings file at cursor.".to_string());
+            return;
+        };
+        let line = fs::read_to_string(&path)
+            .ok()
+            .and_then(|text| find_json_line(&text, &self.settings_keys_at_cursor()));
+        self.external_edit = Some(ExternalEdit { path, line });
+    }
+
+    pub(crate) fn enter_settings_edit_mode(&mut self) {
         let path = match self.settings_file_at_cursor() {
             Some(p) => p.to_path_buf(),
             None => {
@@ -323,6 +358,35 @@ mod tests {
         assert_eq!(edit.file_path, settings_file);
     }
 
+    #[test]
+    fn o_queues_settings_file_for_editor_at_selected_key() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let settings_file = tmp.path().join("settings.json");
+        let json = "{\n  \"model\": \"opus\",\n  \"permissions\": {\n    \"allow\": [\n      \"Bash\"\n    ]\n  }\n}\n";
+        fs::write(&settings_file, json).unwrap();
+        let collection = crate::settings::SettingsCollection {
+            files: vec![crate::settings::SettingsFile {
+                label: "Test".to_string(),
+                path: settings_file.clone(),
+                value: serde_json::from_str(json).unwrap(),
+            }],
+        };
+        let mut app = App::new(vec![], &Config::default());
+        app.switch_to_settings_with(&collection);
+        app.settings_state.cursor = app
+            .settings_state
+            .lines
+            .iter()
+            .position(|line| line.trim() == "Bash")
+            .unwrap();
+
+        app.handle_key_event(key_event(KeyCode::Char('o')));
+
+        let edit = app.external_edit.take().unwrap();
+        assert_eq!(edit.path, settings_file);
+        assert_eq!(edit.line, Some(5));
+    }
+
     #[test]
     fn exiting_settings_edit_refreshes_formatted_view() {
         let tmp = tempfile::TempDir::new().unwrap();
@@ -715,6 +779,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn za_folds_the_section_under_the_cursor() {
+        let mut app = settings_app_with_lines(vec![
+            "▾ Global (/path)",
+            "  ▾ MCP Servers:",
+            "    rust-cargo: npx",
+            "  Model: opus",
+        ]);
+        app.settings_state.cursor = 2;
+
+        app.handle_key_event(key_event(KeyCode::Char('z')));
+        app.handle_key_event(key_event(KeyCode::Char('a')));
+        assert!(app.settings_state.collapsed.contains(&1));
+        assert_eq!(app.settings_state.cursor, 1);
+
+        app.handle_key_event(key_event(KeyCode::Char('z')));
+        app.handle_key_event(key_event(KeyCode::Char('a')));
+        assert!(app.settings_state.collapsed.is_empty());
+
+        app.handle_key_event(key_event(KeyCode::Char('G')));
+        assert_eq!(app.settings_state.cursor, 3);
+        app.handle_key_event(key_event(KeyCode::Char('g')));
+        app.handle_key_event(key_event(KeyCode::Char('g')));
+        assert_eq!(app.settings_state.cursor, 0);
+    }
+
     #[test]
     fn left_arrow_on_leaf_jumps_to_parent() {
         let mut app = settings_app_with_lines(vec![
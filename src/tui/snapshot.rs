@@ -0,0 +1,251 @@
+//! Saving the rendered view as plain text and SVG, for documentation and bug
+//! reports.
+//!
+//! The app is drawn into an off-screen buffer the size of the terminal, so
+//! the snapshot matches what is on screen cell for cell.
+
+use std::fmt::Write as _;
+use std::io;
+use std::path::Path;
+
+use ratatui::Terminal;
+use ratatui::backend::TestBackend;
+use ratatui::buffer::Buffer;
+use ratatui::buffer::Cell;
+use ratatui::crossterm::terminal;
+use ratatui::style::Color;
+use ratatui::style::Modifier;
+
+use super::app::App;
+use crate::backup::now_millis;
+use crate::fsutil::write_atomic;
+use crate::terminal::color_rgb;
+
+/// Width of one terminal cell in the SVG, in pixels.
+const CELL_WIDTH: u16 = 9;
+/// Height of one terminal row in the SVG, in pixels.
+const CELL_HEIGHT: u16 = 18;
+/// Size used when the terminal cannot be measured.
+const FALLBACK_SIZE: (u16, u16) = (80, 24);
+
+impl App {
+    /// Draws the current view off screen and saves it to `dir` as
+    /// `jigolo-view-<millis>.txt` and `.svg`.
+    pub(crate) fn save_snapshot(&mut self, dir: &Path) {
+        let (width, height) = terminal::size().unwrap_or(FALLBACK_SIZE);
+        let stem = dir.join(format!("jigolo-view-{}", now_millis()));
+        match self.render_snapshot(width, height).and_then(|buffer| {
+            let text = stem.with_extension("txt");
+            let svg = stem.with_extension("svg");
+            write_atomic(&text, &buffer_to_text(&buffer))?;
+            write_atomic(&svg, &buffer_to_svg(&buffer, self.theme.is_dark))?;
+            Ok(text)
+        }) {
+            Ok(text) => {
+                self.status_message =
+                    Some(format!("Saved the view to {} and .svg.", text.display()));
+            }
+            Err(err) => self.status_message = Some(format!("Snapshot failed: {err}")),
+        }
+    }
+
+    /// Draws the app into a `width` x `height` buffer.
+    fn render_snapshot(&mut self, width: u16, height: u16) -> io::Result<Buffer> {
+        let mut terminal = Terminal::new(TestBackend::new(width, height))?;
+        terminal.draw(|frame| self.draw(frame))?;
+        Ok(terminal.backend().buffer().clone())
+    }
+}
+
+/// The buffer as plain text, one line per row with trailing spaces removed.
+pub fn buffer_to_text(buffer: &Buffer) -> String {
+    let mut out = String::new();
+    for row in rows(buffer) {
+        let line: String = row.iter().map(|cell| cell.symbol()).collect();
+        out.push_str(line.trim_end());
+        out.push('\n');
+    }
+    out
+}
+
+/// The buffer as a standalone SVG. Runs of cells sharing a style become one
+/// background rectangle and one text element; `dark` picks the default
+/// colours for cells that keep the terminal's own.
+pub fn buffer_to_svg(buffer: &Buffer, dark: bool) -> String {
+    let (default_fg, default_bg) = if dark {
+        ("#d4d4d4", "#1e1e1e")
+    } else {
+        ("#1e1e1e", "#fafafa")
+    };
+    let width = buffer.area.width * CELL_WIDTH;
+    let height = buffer.area.height * CELL_HEIGHT;
+    let mut out = String::new();
+    let _ = writeln!(
+        out,
+        r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" font-family="ui-monospace, Menlo, Consolas, monospace" font-size="15">"#
+    );
+    let _ = writeln!(
+        out,
+        r#"<rect width="100%" height="100%" fill="{default_bg}"/>"#
+    );
+    for (y, row) in rows(buffer).enumerate() {
+        let mut x = 0;
+        while x < row.len() {
+            let style = CellStyle::of(&row[x], default_fg, default_bg);
+            let end = (x..row.len())
+                .find(|&i| CellStyle::of(&row[i], default_fg, default_bg) != style)
+                .unwrap_or(row.len());
+            write_run(&mut out, &row[x..end], x, y, &style, default_bg);
+            x = end;
+        }
+    }
+    out.push_str("</svg>\n");
+    out
+}
+
+/// Appends the background and text of one run of equally styled cells.
+fn write_run(out: &mut String, cells: &[Cell], x: usize, y: usize, style: &CellStyle, page: &str) {
+    let left = x * usize::from(CELL_WIDTH);
+    let top = y * usize::from(CELL_HEIGHT);
+    let run_width = cells.len() * usize::from(CELL_WIDTH);
+    if style.bg != page {
+        let _ = writeln!(
+            out,
+            r#"<rect x="{left}" y="{top}" width="{run_width}" height="{CELL_HEIGHT}" fill="{}"/>"#,
+            style.bg
+        );
+    }
+    let text: String = cells.iter().map(|cell| cell.symbol()).collect();
+    let trimmed = text.trim_end();
+    if trimmed.is_empty() {
+        return;
+    }
+    let mut attributes = format!(r#" fill="{}""#, style.fg);
+    if style.bold {
+        attributes.push_str(r#" font-weight="bold""#);
+    }
+    if style.italic {
+        attributes.push_str(r#" font-style="italic""#);
+    }
+    if style.underlined {
+        attributes.push_str(r#" text-decoration="underline""#);
+    }
+    let text_width = trimmed.chars().count() * usize::from(CELL_WIDTH);
+    let baseline = top + usize::from(CELL_HEIGHT) - 5;
+    let _ = writeln!(
+        out,
+        r#"<text x="{left}" y="{baseline}" textLength="{text_width}" lengthAdjust="spacingAndGlyphs" xml:space="preserve"{attributes}>{}</text>"#,
+        escape_xml(trimmed)
+    );
+}
+
+/// The drawn style of a cell, with colours resolved to hex.
+#[derive(Debug, PartialEq, Eq)]
+struct CellStyle {
+    fg: String,
+    bg: String,
+    bold: bool,
+    italic: bool,
+    underlined: bool,
+}
+
+impl CellStyle {
+    fn of(cell: &Cell, default_fg: &str, default_bg: &str) -> Self {
+        let mut fg = color_hex(cell.fg).unwrap_or_else(|| default_fg.to_string());
+        let mut bg = color_hex(cell.bg).unwrap_or_else(|| default_bg.to_string());
+        if cell.modifier.contains(Modifier::REVERSED) {
+            std::mem::swap(&mut fg, &mut bg);
+        }
+        Self {
+            fg,
+            bg,
+            bold: cell.modifier.contains(Modifier::BOLD),
+            italic: cell.modifier.contains(Modifier::ITALIC),
+            underlined: cell.modifier.contains(Modifier::UNDERLINED),
+        }
+    }
+}
+
+/// Hex colour for `color`, or `None` for the terminal's default.
+fn color_hex(color: Color) -> Option<String> {
+    color_rgb(color).map(|(r, g, b)| format!("#{r:02x}{g:02x}{b:02x}"))
+}
+
+/// The buffer's rows, skipping the blank cells that trail wide characters.
+fn rows(buffer: &Buffer) -> impl Iterator<Item = Vec<Cell>> + '_ {
+    let width = usize::from(buffer.area.width.max(1));
+    buffer.content.chunks(width).map(|row| {
+        row.iter()
+            .filter(|cell| !cell.skip && !cell.symbol().is_empty())
+            .cloned()
+            .collect()
+    })
+}
+
+/// Escapes the characters that are special in XML text.
+fn escape_xml(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use ratatui::buffer::Buffer;
+    use ratatui::layout::Rect;
+    use ratatui::style::Color;
+    use ratatui::style::Style;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::config::Config;
+    use crate::tui::app::test_helpers::sample_roots;
+
+    #[test]
+    fn text_trims_trailing_spaces() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 6, 2));
+        buffer.set_string(0, 0, "ab", Style::default());
+        buffer.set_string(1, 1, "c", Style::default());
+        assert_eq!(buffer_to_text(&buffer), "ab\n c\n");
+    }
+
+    #[test]
+    fn svg_groups_runs_and_escapes_text() {
+        let mut buffer = Buffer::empty(Rect::new(0, 0, 8, 1));
+        buffer.set_string(0, 0, "a<b", Style::default().fg(Color::Red));
+        buffer.set_string(3, 0, "ok", Style::default().bg(Color::Blue));
+        let svg = buffer_to_svg(&buffer, true);
+
+        assert!(svg.starts_with("<svg "));
+        assert!(svg.contains(r##"fill="#cd3131""##));
+        assert!(svg.contains(">a&lt;b</text>"));
+        assert!(svg.contains(r##"<rect x="27" y="0" width="18" height="18" fill="#2472c8"/>"##));
+        assert_eq!(svg.matches("<text ").count(), 2);
+    }
+
+    #[test]
+    fn save_snapshot_writes_text_and_svg() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = App::new(sample_roots(), &Config::default());
+
+        app.save_snapshot(tmp.path());
+
+        let mut names: Vec<String> = std::fs::read_dir(tmp.path())
+            .unwrap()
+            .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort_unstable();
+        assert_eq!(names.len(), 2);
+        assert!(names[0].ends_with(".svg") && names[1].ends_with(".txt"));
+        let text = std::fs::read_to_string(tmp.path().join(&names[1])).unwrap();
+        assert!(text.contains("Files"));
+        assert!(app.status_message.unwrap().starts_with("Saved the view"));
+    }
+}
@@ -2,7 +2,11 @@
 ///
 /// Maps semantic UI roles to `ratatui::style::Style` values. Provides
 /// built-in dark and light palettes and a `toggle()` method to swap between
-/// them at runtime.
+/// them at runtime. Individual roles can be recoloured from the config
+/// file's `[colors]` table.
+use std::collections::BTreeMap;
+use std::str::FromStr;
+
 use ratatui::style::Color;
 use ratatui::style::Modifier;
 use ratatui::style::Style;
@@ -32,9 +36,31 @@ pub struct Theme {
     pub input_border: Style,
     /// Active line in the text editor.
     pub edit_cursor_line: Style,
+    /// Added lines in diff previews.
+    pub diff_added: Style,
+    /// Removed lines in diff previews.
+    pub diff_removed: Style,
+    /// Token counts, paths and other details beside tree labels.
+    pub detail: Style,
+    /// Repository badges and names, and `@path` imports in the tree.
+    pub badge: Style,
+    /// Labels of the agents, commands and skills nodes.
+    pub group_label: Style,
+    /// Lint warnings, local files and misnamed files in the tree.
+    pub warning: Style,
+    /// Lint errors and missing imports in the tree.
+    pub error: Style,
 }
 
 impl Theme {
+    /// The built-in palette called `name`; anything but `"light"` is dark.
+    pub fn named(name: Option<&str>) -> Self {
+        match name {
+            Some("light") => Self::light(),
+            _ => Self::dark(),
+        }
+    }
+
     /// Returns the built-in dark palette matching the original hardcoded styles.
     pub fn dark() -> Self {
         Self {
@@ -54,6 +80,13 @@ impl Theme {
             visual_selection: Style::default().bg(Color::DarkGray),
             input_border: Style::default().fg(Color::Yellow),
             edit_cursor_line: Style::default().add_modifier(Modifier::UNDERLINED),
+            diff_added: Style::default().fg(Color::Green),
+            diff_removed: Style::default().fg(Color::Red),
+            detail: Style::default().fg(Color::DarkGray),
+            badge: Style::default().fg(Color::Cyan),
+            group_label: Style::default().fg(Color::Magenta),
+            warning: Style::default().fg(Color::Yellow),
+            error: Style::default().fg(Color::Red),
         }
     }
 
@@ -77,9 +110,67 @@ impl Theme {
             visual_selection: Style::default().bg(Color::LightYellow),
             input_border: Style::default().fg(Color::Magenta),
             edit_cursor_line: Style::default().add_modifier(Modifier::UNDERLINED),
+            diff_added: Style::default().fg(Color::Green),
+            diff_removed: Style::default().fg(Color::Red),
+            detail: Style::default().fg(Color::DarkGray),
+            badge: Style::default().fg(Color::Blue),
+            group_label: Style::default().fg(Color::Magenta),
+            warning: Style::default().fg(Color::Rgb(0xaf, 0x5f, 0x00)),
+            error: Style::default().fg(Color::Red),
         }
     }
 
+    /// This theme with the roles in `colors` recoloured, and a message for
+    /// each entry that could not be applied. Values are a foreground colour,
+    /// `"<fg> on <bg>"` or `"on <bg>"`, where a colour is a name (`cyan`,
+    /// `dark gray`), a palette index (`208`) or `#rrggbb`.
+    pub fn with_overrides(mut self, colors: &BTreeMap<String, String>) -> (Self, Vec<String>) {
+        let mut errors = Vec::new();
+        for (role, value) in colors {
+            let Some(style) = self.role_mut(role) else {
+                errors.push(format!("unknown color role \"{role}\""));
+                continue;
+            };
+            match parse_colors(value) {
+                Some((fg, bg)) => {
+                    if let Some(fg) = fg {
+                        *style = style.fg(fg);
+                    }
+                    if let Some(bg) = bg {
+                        *style = style.bg(bg);
+                    }
+                }
+                None => errors.push(format!("{role}: cannot read color \"{value}\"")),
+            }
+        }
+        (self, errors)
+    }
+
+    /// The style of the role called `name`.
+    fn role_mut(&mut self, name: &str) -> Option<&mut Style> {
+        let style = match name {
+            "active_border" => &mut self.active_border,
+            "inactive_border" => &mut self.inactive_border,
+            "active_tab" => &mut self.active_tab,
+            "inactive_tab" => &mut self.inactive_tab,
+            "help_key" => &mut self.help_key,
+            "help_desc" => &mut self.help_desc,
+            "highlight" => &mut self.highlight,
+            "visual_selection" => &mut self.visual_selection,
+            "input_border" => &mut self.input_border,
+            "edit_cursor_line" => &mut self.edit_cursor_line,
+            "diff_added" => &mut self.diff_added,
+            "diff_removed" => &mut self.diff_removed,
+            "detail" => &mut self.detail,
+            "badge" => &mut self.badge,
+            "group_label" => &mut self.group_label,
+            "warning" => &mut self.warning,
+            "error" => &mut self.error,
+            _ => return None,
+        };
+        Some(style)
+    }
+
     /// Returns the opposite theme (dark ↔ light).
     pub fn toggle(&self) -> Self {
         if self.is_dark {
@@ -90,6 +181,27 @@ impl Theme {
     }
 }
 
+/// Reads `"<fg>"`, `"<fg> on <bg>"` or `"on <bg>"` into its colours.
+fn parse_colors(value: &str) -> Option<(Option<Color>, Option<Color>)> {
+    let value = value.trim();
+    let (fg, bg) = match value.strip_prefix("on ") {
+        Some(bg) => ("", bg),
+        None => value.split_once(" on ").unwrap_or((value, "")),
+    };
+    let color = |text: &str| -> Option<Option<Color>> {
+        let text = text.trim();
+        if text.is_empty() {
+            return Some(None);
+        }
+        Color::from_str(text).ok().map(Some)
+    };
+    let (fg, bg) = (color(fg)?, color(bg)?);
+    if fg.is_none() && bg.is_none() {
+        return None;
+    }
+    Some((fg, bg))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -140,6 +252,11 @@ mod tests {
             t.edit_cursor_line,
             Style::default().add_modifier(Modifier::UNDERLINED)
         );
+        assert_eq!(t.detail, Style::default().fg(Color::DarkGray));
+        assert_eq!(t.badge, Style::default().fg(Color::Cyan));
+        assert_eq!(t.group_label, Style::default().fg(Color::Magenta));
+        assert_eq!(t.warning, Style::default().fg(Color::Yellow));
+        assert_eq!(t.error, Style::default().fg(Color::Red));
     }
 
     #[test]
@@ -149,6 +266,8 @@ mod tests {
         assert_ne!(dark.active_border, light.active_border);
         assert_ne!(dark.input_border, light.input_border);
         assert_ne!(dark.visual_selection, light.visual_selection);
+        assert_ne!(dark.badge, light.badge);
+        assert_ne!(dark.warning, light.warning);
     }
 
     #[test]
@@ -173,4 +292,46 @@ mod tests {
         let round_trip = original.toggle().toggle();
         assert_eq!(original, round_trip);
     }
+
+    #[test]
+    fn overrides_recolor_roles_and_keep_modifiers() {
+        let colors = BTreeMap::from([
+            ("active_tab".to_string(), "#ff8800".to_string()),
+            ("help_key".to_string(), "white on blue".to_string()),
+            ("visual_selection".to_string(), "on 236".to_string()),
+        ]);
+
+        let (theme, errors) = Theme::dark().with_overrides(&colors);
+
+        assert!(errors.is_empty());
+        assert_eq!(
+            theme.active_tab,
+            Style::default()
+                .fg(Color::Rgb(0xff, 0x88, 0x00))
+                .add_modifier(Modifier::BOLD)
+        );
+        assert_eq!(theme.help_key.fg, Some(Color::White));
+        assert_eq!(theme.help_key.bg, Some(Color::Blue));
+        assert_eq!(theme.visual_selection.bg, Some(Color::Indexed(236)));
+        assert_eq!(theme.active_border, Theme::dark().active_border);
+    }
+
+    #[test]
+    fn bad_overrides_are_reported_and_skipped() {
+        let colors = BTreeMap::from([
+            ("borders".to_string(), "red".to_string()),
+            ("diff_added".to_string(), "greenish".to_string()),
+        ]);
+
+        let (theme, errors) = Theme::light().with_overrides(&colors);
+
+        assert_eq!(theme, Theme::light());
+        assert_eq!(
+            errors,
+            [
+                "unknown color role \"borders\"",
+                "diff_added: cannot read color \"greenish\"",
+            ]
+        );
+    }
 }
@@ -0,0 +1,275 @@
+//! Running the team's own Markdown tools on the shown file.
+//!
+//! The `[tools]` table of the config names commands such as
+//! `prettier --write --parser markdown` or `markdownlint`. `X` lists them
+//! and runs the chosen one on the selected file in the background. What
+//! the tool prints goes to the message log (`Ctrl-G`), and the file is
+//! reloaded and linted again when the tool changed it.
+
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+use std::process::Command;
+use std::process::Stdio;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::TryRecvError;
+use std::thread;
+
+use super::app::App;
+use super::messages::MessageLevel;
+use super::picker::Picker;
+use super::picker::PickerAction;
+use super::picker::PickerItem;
+
+/// Placeholder in a tool command for the file's path. Commands without it
+/// get the path as their last argument.
+pub const FILE_PLACEHOLDER: &str = "{file}";
+
+/// Most lines of a tool's output kept in the message log.
+const MAX_OUTPUT_LINES: usize = 50;
+
+/// A tool running on its own thread.
+#[derive(Debug)]
+pub struct RunningTool {
+    pub name: String,
+    pub file: PathBuf,
+    /// The file's bytes when the tool started, to tell whether it changed.
+    before: Option<Vec<u8>>,
+    receiver: Receiver<ToolOutput>,
+}
+
+/// How a tool run ended.
+#[derive(Debug)]
+struct ToolOutput {
+    /// Whether it exited successfully, or why it could not run.
+    status: Result<bool, String>,
+    /// Its stdout and then its stderr.
+    output: String,
+}
+
+/// The command running tool `command` on `path`, in the file's directory.
+/// `None` for an empty `command`.
+pub fn tool_command(command: &str, path: &Path) -> Option<Command> {
+    let mut words = command.split_whitespace();
+    let program = words.next()?;
+    let mut tool = Command::new(program);
+    let mut placed = false;
+    for word in words {
+        if word == FILE_PLACEHOLDER {
+            tool.arg(path);
+            placed = true;
+        } else {
+            tool.arg(word);
+        }
+    }
+    if !placed {
+        tool.arg(path);
+    }
+    if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        tool.current_dir(dir);
+    }
+    Some(tool)
+}
+
+/// Runs `tool` on a new thread, capturing what it prints.
+fn spawn_tool(mut tool: Command) -> Receiver<ToolOutput> {
+    let (sender, receiver) = mpsc::channel();
+    thread::spawn(move || {
+        let result = tool
+            .stdin(Stdio::null())
+            .output()
+            .map(|output| ToolOutput {
+                status: Ok(output.status.success()),
+                output: format!(
+                    "{}{}",
+                    String::from_utf8_lossy(&output.stdout),
+                    String::from_utf8_lossy(&output.stderr)
+                ),
+            })
+            .unwrap_or_else(|err| ToolOutput {
+                status: Err(err.to_string()),
+                output: String::new(),
+            });
+        // The app may have quit; nobody is listening then.
+        let _ = sender.send(result);
+    });
+    receiver
+}
+
+impl App {
+    /// Lists the tools of the config, to run one on the selected file.
+    pub(crate) fn open_tools(&mut self) {
+        let Some(file) = self.selected_file() else {
+            self.status_message = Some("No file selected.".to_string());
+            return;
+        };
+        let tools: Vec<(String, String)> = self
+            .config
+            .tools
+            .iter()
+            .flatten()
+            .map(|(name, command)| (name.clone(), command.clone()))
+            .collect();
+        if tools.is_empty() {
+            self.status_message =
+                Some("No tools set — add a [tools] table to the config.".to_string());
+            return;
+        }
+        let items = tools
+            .iter()
+            .map(|(name, command)| PickerItem::new(name.clone(), command.clone()))
+            .collect();
+        self.open_picker(Picker::new(
+            "Run tool",
+            items,
+            PickerAction::RunTool { file, tools },
+        ));
+    }
+
+    /// Starts tool `name`, `command`, on `file` in the background.
+    pub(crate) fn run_tool(&mut self, file: PathBuf, name: String, command: &str) {
+        if let Some(running) = &self.running_tool {
+            self.status_message = Some(format!("{} is still running.", running.name));
+            return;
+        }
+        let Some(tool) = tool_command(command, &file) else {
+            self.status_message = Some(format!("Tool {name} has no command."));
+            return;
+        };
+        self.status_message = Some(format!("Running {name} on {}…", file.display()));
+        self.running_tool = Some(RunningTool {
+            before: fs::read(&file).ok(),
+            name,
+            file,
+            receiver: spawn_tool(tool),
+        });
+    }
+
+    /// Logs the output of the running tool once it is done, and reloads
+    /// the file when the tool changed it.
+    pub(crate) fn receive_tool_result(&mut self) {
+        let Some(running) = &self.running_tool else {
+            return;
+        };
+        let result = match running.receiver.try_recv() {
+            Ok(result) => result,
+            Err(TryRecvError::Empty) => return,
+            Err(TryRecvError::Disconnected) => ToolOutput {
+                status: Err("it stopped unexpectedly".to_string()),
+                output: String::new(),
+            },
+        };
+        let Some(running) = self.running_tool.take() else {
+            return;
+        };
+        let name = &running.name;
+        // Lines logged before the status message, so they come first.
+        self.log_status();
+        let level = match result.status {
+            Ok(true) => MessageLevel::Info,
+            _ => MessageLevel::Warning,
+        };
+        for line in result
+            .output
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .take(MAX_OUTPUT_LINES)
+        {
+            self.log_message(level, format!("{name}: {line}"));
+        }
+
+        let changed = fs::read(&running.file).ok() != running.before;
+        if changed {
+            if self.selected_file().as_ref() == Some(&running.file) {
+                self.reload_keeping_position();
+            }
+            self.relint(&running.file);
+        }
+        let file = running.file.display();
+        self.status_message = Some(match (result.status, changed) {
+            (Err(err), _) => format!("Cannot run {name}: {err}"),
+            (Ok(true), true) => format!("{name} changed {file}."),
+            (Ok(true), false) => format!("{name} found nothing to change in {file}."),
+            (Ok(false), true) => format!("{name} failed, but changed {file}; see Ctrl-G."),
+            (Ok(false), false) => format!("{name} failed on {file}; see Ctrl-G."),
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::OsStr;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use super::*;
+
+    fn args(command: &Command) -> Vec<&OsStr> {
+        command.get_args().collect()
+    }
+
+    #[test]
+    fn the_path_goes_last_or_at_the_placeholder() {
+        let path = Path::new("/p/CLAUDE.md");
+        let command = tool_command("prettier --write --parser markdown", path).unwrap();
+        assert_eq!(command.get_program(), "prettier");
+        assert_eq!(
+            args(&command),
+            ["--write", "--parser", "markdown", "/p/CLAUDE.md"]
+        );
+        assert_eq!(command.get_current_dir(), Some(Path::new("/p")));
+
+        let command = tool_command("markdownlint {file} --fix", path).unwrap();
+        assert_eq!(args(&command), ["/p/CLAUDE.md", "--fix"]);
+        assert!(tool_command(" ", path).is_none());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn a_tool_that_changes_the_file_is_logged_and_the_file_reloaded() {
+        use std::os::unix::fs::PermissionsExt;
+
+        use crate::config::Config;
+        use crate::model::DiscoveredFile;
+        use crate::model::SourceRoot;
+
+        let tmp = tempfile::TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "# old\n").unwrap();
+        let script = tmp.path().join("fix.sh");
+        fs::write(
+            &script,
+            "#!/bin/sh\necho '# new' > \"$1\"\necho fixed 1 file\n",
+        )
+        .unwrap();
+        fs::set_permissions(&script, fs::Permissions::from_mode(0o755)).unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![DiscoveredFile::new(file.clone())],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+        let mut app = App::new(roots, &Config::default());
+        app.show_file(&file);
+
+        app.run_tool(file.clone(), "fix".to_string(), script.to_str().unwrap());
+        let deadline = Instant::now() + Duration::from_secs(5);
+        while app.running_tool.is_some() && Instant::now() < deadline {
+            std::thread::sleep(Duration::from_millis(5));
+            app.receive_tool_result();
+        }
+
+        assert_eq!(app.content.text.as_deref(), Some("# new\n"));
+        assert_eq!(
+            app.status_message,
+            Some(format!("fix changed {}.", file.display()))
+        );
+        assert!(
+            app.messages
+                .iter()
+                .any(|message| message.text == "fix: fixed 1 file")
+        );
+    }
+}
@@ -0,0 +1,377 @@
+use std::fs;
+use std::path::Path;
+use std::path::PathBuf;
+
+use ratatui::Frame;
+use ratatui::layout::Rect;
+use ratatui::style::Style;
+use ratatui::text::Line;
+use ratatui::text::Span;
+use ratatui::text::Text;
+use ratatui::widgets::Block;
+use ratatui::widgets::Borders;
+use ratatui::widgets::Clear;
+use ratatui::widgets::Paragraph;
+
+use super::action::Action;
+use super::action::Effect;
+use super::app::App;
+use super::app::Mode;
+use super::diff_preview::DiffPreview;
+use super::diff_preview::PREVIEW_CONTEXT_LINES;
+use super::diff_preview::PreviewAction;
+use super::picker::centered_rect;
+use crate::diff::collapse_context;
+use crate::diff::diff_lines;
+use crate::library::LibraryScope;
+use crate::library::Snippet;
+use crate::library::SnippetFormat;
+use crate::library::SnippetLibrary;
+use crate::library::load_library;
+use crate::library::save_library;
+use crate::tokens::format_tokens;
+use crate::trim::TrimCandidate;
+use crate::trim::find_candidates;
+use crate::trim::remove_lines;
+
+/// The trim checklist for one file, open while in `Mode::Trim`.
+#[derive(Debug)]
+pub struct TrimView {
+    pub file: PathBuf,
+    /// Content of `file` the candidates were found in.
+    pub text: String,
+    /// Suggested sections, highest score first.
+    pub candidates: Vec<TrimCandidate>,
+    /// Whether each candidate is checked for removal.
+    pub checked: Vec<bool>,
+    pub cursor: usize,
+}
+
+impl TrimView {
+    /// The checked candidates.
+    fn chosen(&self) -> Vec<&TrimCandidate> {
+        self.candidates
+            .iter()
+            .zip(&self.checked)
+            .filter(|(_, checked)| **checked)
+            .map(|(candidate, _)| candidate)
+            .collect()
+    }
+}
+
+impl App {
+    /// Looks for sections of the selected file worth trimming and lists them.
+    pub(crate) fn open_trim(&mut self) {
+        let Some(file) = self.selected_file() else {
+            self.status_message = Some("Select a file to trim.".to_string());
+            return;
+        };
+        let text = match fs::read_to_string(&file) {
+            Ok(text) => text,
+            Err(err) => {
+                self.status_message = Some(format!("Cannot read {}: {err}", file.display()));
+                return;
+            }
+        };
+        let others: Vec<(PathBuf, String)> = self
+            .roots
+            .iter()
+            .flat_map(|root| root.file_paths())
+            .filter(|other| **other != file)
+            .filter_map(|other| Some((other.clone(), fs::read_to_string(other).ok()?)))
+            .collect();
+        let candidates = find_candidates(&file, &text, &others, self.config.tokenizer());
+        if candidates.is_empty() {
+            self.status_message = Some(format!("Nothing to trim in {}.", file.display()));
+            return;
+        }
+        self.trim_view = Some(TrimView {
+            file,
+            text,
+            checked: vec![false; candidates.len()],
+            candidates,
+            cursor: 0,
+        });
+        self.mode = Mode::Trim;
+    }
+
+    /// Draws the checklist centred over `area`.
+    pub(crate) fn draw_trim(&self, frame: &mut Frame, area: Rect) {
+        let Some(view) = &self.trim_view else {
+            return;
+        };
+        let height = (view.candidates.len() as u16 * 2).saturating_add(2);
+        let popup = centered_rect(80, height, area);
+        let viewport = popup.height.saturating_sub(2) as usize;
+        let scroll = (view.cursor * 2 + 2).saturating_sub(viewport);
+
+        let mut lines = Vec::new();
+        for (i, candidate) in view.candidates.iter().enumerate() {
+            let style = if i == view.cursor {
+                self.theme.highlight
+            } else {
+                Style::default()
+            };
+            let mark = if view.checked[i] { "[x]" } else { "[ ]" };
+            lines.push(Line::from(vec![
+                Span::styled(format!(" {mark} {}", candidate.title), style),
+                Span::styled(
+                    format!(
+                        "  lines {}–{} · {}",
+                        candidate.lines.start + 1,
+                        candidate.lines.end,
+                        format_tokens(candidate.tokens)
+                    ),
+                    self.theme.help_desc,
+                ),
+            ]));
+            let reasons: Vec<String> = candidate.reasons.iter().map(|r| r.to_string()).collect();
+            lines.push(Line::styled(
+                format!("       {}", reasons.join("; ")),
+                self.theme.help_desc,
+            ));
+        }
+
+        let title = format!("Trim {}", view.file.display());
+        let widget = Paragraph::new(Text::from(lines))
+            .block(
+                Block::default()
+                    .borders(Borders::ALL)
+                    .border_style(self.theme.input_border)
+                    .title(title),
+            )
+            .scroll((scroll as u16, 0));
+        frame.render_widget(Clear, popup);
+        frame.render_widget(widget, popup);
+    }
+
+    /// Applies an action while the trim checklist is open.
+    pub(crate) fn update_trim(&mut self, action: Action) -> Vec<Effect> {
+        let Some(view) = &mut self.trim_view else {
+            self.mode = self.mode_after_modal();
+            return Vec::new();
+        };
+        match action {
+            Action::Cancel => {
+                self.trim_view = None;
+                self.mode = self.mode_after_modal();
+            }
+            Action::MoveDown => {
+                if view.cursor + 1 < view.candidates.len() {
+                    view.cursor += 1;
+                }
+            }
+            Action::MoveUp => view.cursor = view.cursor.saturating_sub(1),
+            Action::ToggleMark => {
+                if let Some(checked) = view.checked.get_mut(view.cursor) {
+                    *checked = !*checked;
+                }
+            }
+            Action::Delete => self.preview_trim(None),
+            Action::Archive => match crate::library::library_path() {
+                Some(path) => self.preview_trim(Some(&path)),
+                None => {
+                    self.status_message = Some("Cannot determine library path.".to_string());
+                }
+            },
+            _ => {}
+        }
+        Vec::new()
+    }
+
+    /// Previews the file without the checked sections. With `library`, the
+    /// sections are also saved there as snippets when the preview is
+    /// accepted.
+    pub(crate) fn preview_trim(&mut self, library: Option<&Path>) {
+        let Some(view) = self.trim_view.take() else {
+            return;
+        };
+        if !view.checked.contains(&true) {
+            self.status_message = Some("Check sections with Space first.".to_string());
+            self.trim_view = Some(view);
+            return;
+        }
+        let chosen = view.chosen();
+        let count = chosen.len();
+        let sections = if count == 1 { "section" } else { "sections" };
+        let ranges: Vec<_> = chosen.iter().map(|c| c.lines.clone()).collect();
+        let content = remove_lines(&view.text, &ranges);
+
+        let archive = match library {
+            None => None,
+            Some(path) => {
+                let mut lib = match load_library(path) {
+                    Ok(lib) => lib,
+                    Err(err) => {
+                        self.status_message = Some(format!("Cannot load library: {err:#}"));
+                        self.trim_view = Some(view);
+                        return;
+                    }
+                };
+                let lines: Vec<&str> = view.text.lines().collect();
+                for candidate in &chosen {
+                    let text = lines[candidate.lines.clone()].join("\n");
+                    lib.snippets.push(Snippet {
+                        title: lib.unique_title(&candidate.title, None),
+                        format: Some(SnippetFormat::infer(&text)),
+                        content: text,
+                        source: view.file.display().to_string(),
+                        archived: true,
+                        tags: Vec::new(),
+                        lines: Some((candidate.lines.start + 1, candidate.lines.end)),
+                        created: Some(crate::backup::now_millis()),
+                        scope: LibraryScope::Global,
+                    });
+                }
+                Some((path.to_path_buf(), lib))
+            }
+        };
+        let (title, success) = match archive {
+            Some(_) => (
+                format!("Move {count} {sections} to the library? (y/n)"),
+                format!(
+                    "Moved {count} {sections} from {} to the library.",
+                    view.file.display()
+                ),
+            ),
+            None => (
+                format!("Remove {count} {sections}? (y/n)"),
+                format!("Removed {count} {sections} from {}.", view.file.display()),
+            ),
+        };
+
+        self.mode = self.mode_after_modal();
+        if !self.ensure_writable(&view.file, "trim") {
+            return;
+        }
+        self.open_diff_preview(DiffPreview::new(
+            title,
+            collapse_context(diff_lines(&view.text, &content), PREVIEW_CONTEXT_LINES),
+            PreviewAction::TrimFile {
+                file: view.file,
+                content,
+                archive,
+                success,
+            },
+        ));
+    }
+
+    /// Saves the archived sections, if any, then writes the trimmed file.
+    pub(crate) fn apply_trim(
+        &mut self,
+        file: &Path,
+        content: &str,
+        archive: Option<(PathBuf, SnippetLibrary)>,
+        success: String,
+    ) {
+        if let Some((path, library)) = archive {
+            if let Err(err) = save_library(&library, &path) {
+                self.status_message = Some(format!("Save failed: {err:#}"));
+                return;
+            }
+            self.finish_library_change(library, String::new());
+        }
+        self.apply_file_write(file, content, success);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use ratatui::crossterm::event::KeyCode;
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::library::load_library;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Mode;
+    use crate::tui::app::test_helpers::key_event;
+
+    const TEXT: &str = "# Project\n\n## Style\n- Write clean code.\n- Follow best practices.\n\n## Build\nRun `make release`.\n";
+
+    fn trim_app(root: &Path) -> App {
+        let file = root.join("CLAUDE.md");
+        fs::write(&file, TEXT).unwrap();
+        App::new(
+            vec![SourceRoot {
+                path: root.to_path_buf(),
+                files: vec![DiscoveredFile::new(file)],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        )
+    }
+
+    #[test]
+    fn t_lists_candidates_and_d_removes_checked_ones() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = trim_app(tmp.path());
+
+        app.handle_key_event(key_event(KeyCode::Char('t')));
+        assert_eq!(app.mode, Mode::Trim);
+        let view = app.trim_view.as_ref().unwrap();
+        assert_eq!(view.candidates.len(), 1);
+        assert_eq!(view.candidates[0].title, "Style");
+
+        app.handle_key_event(key_event(KeyCode::Char('d')));
+        assert_eq!(app.mode, Mode::Trim);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Check sections with Space first.")
+        );
+
+        app.handle_key_event(key_event(KeyCode::Char(' ')));
+        app.handle_key_event(key_event(KeyCode::Char('d')));
+        assert_eq!(app.mode, Mode::DiffPreview);
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        let trimmed = fs::read_to_string(tmp.path().join("CLAUDE.md")).unwrap();
+        assert_eq!(trimmed, "# Project\n\n## Build\nRun `make release`.\n");
+        assert_eq!(app.content.text.as_deref(), Some(trimmed.as_str()));
+    }
+
+    #[test]
+    fn archiving_moves_sections_into_the_library() {
+        let tmp = TempDir::new().unwrap();
+        let library = tmp.path().join("library.toml");
+        let mut app = trim_app(tmp.path());
+        app.open_trim();
+        app.handle_key_event(key_event(KeyCode::Char(' ')));
+
+        app.preview_trim(Some(&library));
+        assert!(!library.exists());
+        app.handle_key_event(key_event(KeyCode::Char('y')));
+
+        let lib = load_library(&library).unwrap();
+        assert_eq!(lib.snippets.len(), 1);
+        assert_eq!(lib.snippets[0].title, "Style");
+        assert!(lib.snippets[0].archived);
+        assert!(
+            lib.snippets[0]
+                .content
+                .starts_with("## Style\n- Write clean code.")
+        );
+        let trimmed = fs::read_to_string(tmp.path().join("CLAUDE.md")).unwrap();
+        assert!(!trimmed.contains("Style"));
+        assert!(app.status_message.unwrap().starts_with("Moved 1 section"));
+    }
+
+    #[test]
+    fn clean_file_has_nothing_to_trim() {
+        let tmp = TempDir::new().unwrap();
+        let file = tmp.path().join("CLAUDE.md");
+        let mut app = trim_app(tmp.path());
+        fs::write(&file, "## Build\nRun `make release`.\n").unwrap();
+
+        app.handle_key_event(key_event(KeyCode::Char('t')));
+
+        assert_eq!(app.mode, Mode::Normal);
+        assert!(app.status_message.unwrap().starts_with("Nothing to trim"));
+    }
+}
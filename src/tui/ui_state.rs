@@ -0,0 +1,151 @@
+//! Saving where the TUI was left on exit and restoring it on the next run.
+
+use super::app::App;
+use super::app::Pane;
+use crate::state::UiState;
+use crate::state::load_state;
+use crate::state::save_state;
+use crate::state::state_path_in;
+use crate::warnings::warn;
+
+impl App {
+    /// Where the TUI is now, to be restored on the next run.
+    pub fn ui_state(&self) -> UiState {
+        let mut open: Vec<Vec<String>> = self.tree_state.opened().iter().cloned().collect();
+        open.sort();
+        UiState {
+            selected: self.selected_file(),
+            open,
+            pane: match self.active_pane {
+                Pane::FileList => "files",
+                Pane::Content => "content",
+                Pane::Outline => "outline",
+            }
+            .to_string(),
+            cursor: self.content.cursor,
+            scroll: self.content.scroll,
+            recent_files: self.recent_files.clone(),
+        }
+    }
+
+    /// Restores the state saved by the last run, if there is one. While a
+    /// background scan runs it is restored once the selected file is found.
+    pub fn load_ui_state(&mut self) {
+        let Some(dir) = &self.data_dir else {
+            return;
+        };
+        match load_state(&state_path_in(dir)) {
+            Ok(state) => self.restore_ui_state(state),
+            Err(err) => warn(format!("{err:#}")),
+        }
+    }
+
+    /// Saves where the TUI is now for the next run.
+    pub fn save_ui_state(&self) {
+        if let Some(dir) = &self.data_dir
+            && let Err(err) = save_state(&state_path_in(dir), &self.ui_state())
+        {
+            warn(format!("{err:#}"));
+        }
+    }
+
+    /// Restores `state` now, or once the scan has found its file.
+    pub fn restore_ui_state(&mut self, state: UiState) {
+        self.recent_files = state.recent_files.clone();
+        self.pending_restore = Some(state);
+        self.try_restore_ui_state();
+    }
+
+    /// Restores the pending state if its file is in the tree, or drops it
+    /// when no scan can bring the file any more.
+    pub(crate) fn try_restore_ui_state(&mut self) {
+        let Some(state) = &self.pending_restore else {
+            return;
+        };
+        let found = state
+            .selected
+            .as_ref()
+            .is_some_and(|file| self.discovered_files().contains(file));
+        if !found {
+            if !self.is_scanning() {
+                self.pending_restore = None;
+            }
+            return;
+        }
+        let Some(state) = self.pending_restore.take() else {
+            return;
+        };
+        for node in state.open {
+            self.tree_state.open(node);
+        }
+        if let Some(file) = &state.selected
+            && self.select_tree_file(file)
+        {
+            self.load_selected_content();
+            self.content.jump_to_line(state.cursor);
+            self.content.scroll = state.scroll.min(self.content.cursor as u16);
+        }
+        self.active_pane = match state.pane.as_str() {
+            "content" => Pane::Content,
+            "outline" => {
+                self.outline_visible = true;
+                Pane::Outline
+            }
+            _ => Pane::FileList,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use tempfile::TempDir;
+
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+    use crate::tui::app::Pane;
+
+    #[test]
+    fn the_next_run_opens_where_the_last_one_was_left() {
+        let tmp = TempDir::new().unwrap();
+        let data = TempDir::new().unwrap();
+        let first = tmp.path().join("CLAUDE.md");
+        let second = tmp.path().join("sub/CLAUDE.md");
+        fs::create_dir(tmp.path().join("sub")).unwrap();
+        fs::write(&first, "one\n").unwrap();
+        let text: String = (0..50).map(|i| format!("line {i}\n")).collect();
+        fs::write(&second, text).unwrap();
+        let roots = vec![SourceRoot {
+            path: tmp.path().to_path_buf(),
+            files: vec![
+                DiscoveredFile::new(first.clone()),
+                DiscoveredFile::new(second.clone()),
+            ],
+            context_files: Vec::new(),
+            misnamed_files: Vec::new(),
+            truncated: false,
+        }];
+
+        let mut app = App::new(roots.clone(), &Config::default());
+        app.data_dir = Some(data.path().to_path_buf());
+        app.select_tree_file(&second);
+        app.load_selected_content();
+        app.active_pane = Pane::Content;
+        app.content.jump_to_line(30);
+        app.content.scroll = 20;
+        app.save_ui_state();
+
+        let mut next = App::new(roots, &Config::default());
+        next.data_dir = Some(data.path().to_path_buf());
+        next.load_ui_state();
+        assert_eq!(next.selected_file(), Some(second.clone()));
+        assert_eq!(next.active_pane, Pane::Content);
+        assert_eq!(next.content.cursor, 30);
+        assert_eq!(next.content.scroll, 20);
+        assert_eq!(next.recent_files, app.recent_files);
+        assert!(next.pending_restore.is_none());
+    }
+}
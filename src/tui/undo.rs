@@ -0,0 +1,251 @@
+//! Undo and redo of library changes.
+//!
+//! Every write of the library file made from the TUI remembers the library
+//! as it was on disk just before. `u` on the Library screen writes the most
+//! recent of those back, and `Ctrl-R` redoes what was undone. The history
+//! lives only as long as the app does.
+
+use std::path::Path;
+
+use super::app::App;
+use crate::library::SnippetLibrary;
+use crate::library::load_library_set;
+use crate::library::save_library_set;
+
+/// How many library changes can be undone.
+pub const LIBRARY_UNDO_LIMIT: usize = 20;
+
+/// The library file as it was before, or after, one change.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LibraryUndo {
+    pub library: SnippetLibrary,
+    /// Status message of the change, such as `Snippet deleted.`.
+    pub change: String,
+}
+
+impl App {
+    /// Remembers `before`, the library on disk before the change described
+    /// by `change` was written. A new change cannot be redone past, so the
+    /// redo history is dropped.
+    pub(crate) fn record_library_undo(&mut self, before: SnippetLibrary, change: &str) {
+        if self.library_undo.len() == LIBRARY_UNDO_LIMIT {
+            self.library_undo.remove(0);
+        }
+        self.library_undo.push(LibraryUndo {
+            library: before,
+            change: change.to_string(),
+        });
+        self.library_redo.clear();
+    }
+
+    pub(crate) fn undo_library_change(&mut self) {
+        match crate::library::library_path() {
+            Some(path) => self.undo_library_change_at(&path),
+            None => self.status_message = Some("Cannot determine library path.".to_string()),
+        }
+    }
+
+    /// Writes the library at `path` back to how it was before the last
+    /// change.
+    pub fn undo_library_change_at(&mut self, path: &Path) {
+        self.step_library_history(path, true);
+    }
+
+    pub(crate) fn redo_library_change(&mut self) {
+        match crate::library::library_path() {
+            Some(path) => self.redo_library_change_at(&path),
+            None => self.status_message = Some("Cannot determine library path.".to_string()),
+        }
+    }
+
+    /// Writes the last undone change to the library at `path` again.
+    pub fn redo_library_change_at(&mut self, path: &Path) {
+        self.step_library_history(path, false);
+    }
+
+    /// Moves one step back (`undo`) or forward through the history. The
+    /// state replaced goes onto the opposite stack. Nothing is written when
+    /// the file changed on disk since it was loaded, since that change would
+    /// be lost without a trace.
+    fn step_library_history(&mut self, path: &Path, undo: bool) {
+        let verb = if undo { "undo" } else { "redo" };
+        let project = self.project_library.clone();
+        let stack = if undo {
+            &mut self.library_undo
+        } else {
+            &mut self.library_redo
+        };
+        let Some(step) = stack.pop() else {
+            self.status_message = Some(format!("Nothing to {verb}."));
+            return;
+        };
+        let current = match load_library_set(path, project.as_deref()) {
+            Ok(lib) => lib,
+            Err(err) => {
+                self.status_message = Some(format!("Cannot {verb}: {err}"));
+                stack.push(step);
+                return;
+            }
+        };
+        if self
+            .library
+            .as_ref()
+            .is_some_and(|loaded| *loaded != current)
+        {
+            self.status_message = Some(format!(
+                "Cannot {verb}: the library changed on disk. Reopen the Library screen first."
+            ));
+            stack.push(step);
+            return;
+        }
+        if let Err(err) = save_library_set(&step.library, path, project.as_deref()) {
+            self.status_message = Some(format!("Cannot {verb}: {err}"));
+            stack.push(step);
+            return;
+        }
+
+        let message = if undo {
+            format!("Undid: {}", step.change)
+        } else {
+            format!("Redid: {}", step.change)
+        };
+        let opposite = if undo {
+            &mut self.library_redo
+        } else {
+            &mut self.library_undo
+        };
+        opposite.push(LibraryUndo {
+            library: current,
+            change: step.change,
+        });
+        self.finish_library_change(step.library, message);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::path::Path;
+
+    use ratatui::crossterm::event::KeyCode;
+    use ratatui::crossterm::event::KeyEvent;
+    use ratatui::crossterm::event::KeyModifiers;
+    use tempfile::TempDir;
+
+    use super::*;
+    use crate::config::Config;
+    use crate::library::Snippet;
+    use crate::library::append_snippet;
+    use crate::library::load_library;
+    use crate::tui::app::test_helpers::key_event;
+
+    fn titles(path: &Path) -> Vec<String> {
+        load_library(path)
+            .unwrap()
+            .snippets
+            .into_iter()
+            .map(|s| s.title)
+            .collect()
+    }
+
+    fn library_app(path: &Path) -> App {
+        for title in ["A", "B"] {
+            append_snippet(
+                Snippet {
+                    title: title.to_string(),
+                    content: format!("Content of {title}"),
+                    source: "/p/CLAUDE.md".to_string(),
+                    format: None,
+                    archived: false,
+                    tags: Vec::new(),
+                    lines: None,
+                    created: None,
+                    scope: Default::default(),
+                },
+                path,
+            )
+            .unwrap();
+        }
+        let mut app = App::new(vec![], &Config::default());
+        app.enter_library_screen_from(path);
+        app
+    }
+
+    #[test]
+    fn undo_restores_deleted_snippet_and_redo_deletes_it_again() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("library.toml");
+        let mut app = library_app(&path);
+
+        app.delete_library_snippet_from(&path);
+        assert_eq!(titles(&path), ["B"]);
+
+        app.undo_library_change_at(&path);
+        assert_eq!(titles(&path), ["A", "B"]);
+        assert_eq!(app.library.as_ref().unwrap().snippets.len(), 2);
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Undid: Snippet deleted.")
+        );
+
+        app.redo_library_change_at(&path);
+        assert_eq!(titles(&path), ["B"]);
+        app.redo_library_change_at(&path);
+        assert_eq!(app.status_message.as_deref(), Some("Nothing to redo."));
+    }
+
+    #[test]
+    fn undo_steps_back_through_several_changes() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("library.toml");
+        let mut app = library_app(&path);
+
+        app.text_input.set("Renamed");
+        app.rename_library_snippet_from(&path);
+        app.delete_library_snippet_from(&path);
+        assert_eq!(titles(&path), ["B"]);
+
+        app.undo_library_change_at(&path);
+        assert_eq!(titles(&path), ["Renamed", "B"]);
+        app.undo_library_change_at(&path);
+        assert_eq!(titles(&path), ["A", "B"]);
+        app.undo_library_change_at(&path);
+        assert_eq!(app.status_message.as_deref(), Some("Nothing to undo."));
+    }
+
+    #[test]
+    fn undo_refuses_to_clobber_changes_made_on_disk() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("library.toml");
+        let mut app = library_app(&path);
+        app.delete_library_snippet_from(&path);
+        fs::write(&path, "").unwrap();
+
+        app.undo_library_change_at(&path);
+
+        assert!(titles(&path).is_empty());
+        assert!(app.status_message.unwrap().contains("changed on disk"));
+        assert_eq!(app.library_undo.len(), 1);
+    }
+
+    #[test]
+    fn history_is_capped() {
+        let mut app = App::new(vec![], &Config::default());
+        for _ in 0..LIBRARY_UNDO_LIMIT + 5 {
+            app.record_library_undo(SnippetLibrary::default(), "Snippet saved.");
+        }
+        assert_eq!(app.library_undo.len(), LIBRARY_UNDO_LIMIT);
+    }
+
+    #[test]
+    fn u_and_ctrl_r_map_to_undo_and_redo_on_library_screen() {
+        let tmp = TempDir::new().unwrap();
+        let path = tmp.path().join("library.toml");
+        let mut app = library_app(&path);
+
+        app.handle_key_event(key_event(KeyCode::Char('u')));
+        assert_eq!(app.status_message.as_deref(), Some("Nothing to undo."));
+        app.handle_key_event(KeyEvent::new(KeyCode::Char('r'), KeyModifiers::CONTROL));
+        assert_eq!(app.status_message.as_deref(), Some("Nothing to redo."));
+    }
+}
@@ -0,0 +1,329 @@
+use std::collections::HashSet;
+use std::path::Path;
+use std::path::PathBuf;
+use std::sync::mpsc;
+use std::sync::mpsc::Receiver;
+use std::sync::mpsc::TryRecvError;
+use std::thread;
+use std::time::Duration;
+use std::time::Instant;
+
+use super::app::App;
+use super::rescan::rescan_roots;
+use crate::model::SourceRoot;
+use crate::watch::Change;
+use crate::watch::ChangeKind;
+use crate::watch::Debouncer;
+use crate::watch::PollSnapshot;
+
+/// How often the known files are stat'ed for changes.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// How often the roots are walked again, on a thread of their own, for
+/// files added or removed.
+const RESCAN_INTERVAL: Duration = Duration::from_secs(10);
+
+/// Notices CLAUDE.md files changing on disk while the TUI is open.
+///
+/// Each poll only stats the files already in the tree, which stays cheap
+/// however large the scanned trees are. Finding new files takes a walk, so
+/// that runs less often and off the UI thread.
+#[derive(Debug)]
+pub struct FileWatcher {
+    debouncer: Debouncer,
+    snapshot: PollSnapshot,
+    /// When the next poll is due; `None` until the first poll, which only
+    /// records the starting state.
+    next_poll: Option<Instant>,
+    /// When the next walk for added and removed files is due.
+    next_rescan: Option<Instant>,
+    rescan: Option<BackgroundRescan>,
+}
+
+/// A walk of the roots running on its own thread.
+#[derive(Debug)]
+struct BackgroundRescan {
+    receiver: Receiver<Vec<SourceRoot>>,
+    /// The root directories and files when the walk started. A result for
+    /// roots that changed since is stale and dropped.
+    roots: Vec<PathBuf>,
+    files: HashSet<PathBuf>,
+}
+
+impl FileWatcher {
+    /// Creates a watcher that debounces changes over `window`.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            debouncer: Debouncer::new(window),
+            snapshot: PollSnapshot::default(),
+            next_poll: None,
+            next_rescan: None,
+            rescan: None,
+        }
+    }
+}
+
+impl App {
+    /// Stats the known files when a poll is due, starts or picks up the
+    /// walk for added and removed files, and applies the changes the
+    /// debouncer releases.
+    pub(crate) fn watch_files(&mut self, now: Instant) {
+        let Some(watcher) = &self.watcher else {
+            return;
+        };
+        let poll_due = watcher.next_poll.is_none_or(|due| now >= due);
+        let rescan_due =
+            watcher.rescan.is_none() && watcher.next_rescan.is_some_and(|due| now >= due);
+        let rescanned =
+            watcher
+                .rescan
+                .as_ref()
+                .and_then(|rescan| match rescan.receiver.try_recv() {
+                    Ok(roots) => Some(Some(roots)),
+                    Err(TryRecvError::Empty) => None,
+                    Err(TryRecvError::Disconnected) => Some(None),
+                });
+        // Only listed when needed: the tick runs far more often than polls.
+        let (files, root_paths): (Vec<PathBuf>, Vec<PathBuf>) =
+            if poll_due || rescan_due || rescanned.is_some() {
+                let roots = self.roots.iter().map(|root| root.path.clone()).collect();
+                (self.discovered_files(), roots)
+            } else {
+                (Vec::new(), Vec::new())
+            };
+        let to_rescan = rescan_due.then(|| (self.roots.clone(), self.scan_options.clone()));
+        let Some(watcher) = &mut self.watcher else {
+            return;
+        };
+
+        if poll_due {
+            let changes = watcher.snapshot.update(&files);
+            if watcher.next_poll.is_some() {
+                // Files new to the snapshot came from a scan, not from disk.
+                for (path, kind) in changes {
+                    if kind != ChangeKind::Created {
+                        watcher.debouncer.push(path, kind, now);
+                    }
+                }
+            }
+            watcher.next_poll = Some(now + POLL_INTERVAL);
+            watcher.next_rescan.get_or_insert(now + RESCAN_INTERVAL);
+        }
+
+        if let Some((roots, options)) = to_rescan {
+            watcher.next_rescan = Some(now + RESCAN_INTERVAL);
+            let (sender, receiver) = mpsc::channel();
+            thread::spawn(move || {
+                // The app may have quit; nobody is listening then.
+                let _ = sender.send(rescan_roots(&roots, &options));
+            });
+            watcher.rescan = Some(BackgroundRescan {
+                receiver,
+                roots: root_paths.clone(),
+                files: files.iter().cloned().collect(),
+            });
+        }
+
+        let mut fresh_roots = None;
+        if let Some(result) = rescanned
+            && let Some(rescan) = watcher.rescan.take()
+        {
+            let current =
+                rescan.roots == root_paths && rescan.files == files.iter().cloned().collect();
+            fresh_roots = result.filter(|_| current);
+        }
+
+        let changes = watcher.debouncer.drain_ready(now);
+        if !changes.is_empty() {
+            self.apply_disk_changes(&changes);
+        }
+        if let Some(roots) = fresh_roots {
+            self.apply_rescanned_roots(roots);
+        }
+    }
+
+    /// Drops removed files from the tree, and reloads the shown file when
+    /// it changed, keeping the cursor where it was.
+    fn apply_disk_changes(&mut self, changes: &[Change]) {
+        for change in changes {
+            self.relint(&change.path);
+        }
+        let removed: Vec<&Path> = changes
+            .iter()
+            .filter(|c| c.kind == ChangeKind::Removed)
+            .map(|c| c.path.as_path())
+            .collect();
+        if !removed.is_empty() {
+            let mut roots = self.roots.clone();
+            for root in &mut roots {
+                root.files
+                    .retain(|file| !removed.contains(&file.path.as_path()));
+            }
+            self.replace_roots(roots);
+            self.status_message = Some(format!(
+                "Files changed on disk: 0 added, {} removed.",
+                removed.len()
+            ));
+            return;
+        }
+
+        let old_text = self.content.text.clone();
+        let shown = self.selected_file();
+        // Sizes changed, so the token estimates on the root labels did too.
+        self.rebuild_tree();
+        if let Some(file) = &shown
+            && changes.iter().any(|c| &c.path == file)
+        {
+            self.reload_keeping_position();
+        }
+        if let Some(file) = shown
+            && self.selected_file().as_ref() == Some(&file)
+            && self.content.text != old_text
+        {
+            self.status_message = Some(format!(
+                "Reloaded {} after it changed on disk.",
+                file.display()
+            ));
+        }
+    }
+
+    /// Swaps in the roots the watcher's walk found when files were added
+    /// or removed since.
+    fn apply_rescanned_roots(&mut self, roots: Vec<SourceRoot>) {
+        let before: HashSet<PathBuf> = self.discovered_files().into_iter().collect();
+        let after: HashSet<PathBuf> = roots
+            .iter()
+            .flat_map(|root| root.file_paths().cloned())
+            .collect();
+        if before == after {
+            return;
+        }
+        for file in after.difference(&before) {
+            self.relint(file);
+        }
+        self.replace_roots(roots);
+        self.status_message = Some(format!(
+            "Files changed on disk: {} added, {} removed.",
+            after.difference(&before).count(),
+            before.difference(&after).count()
+        ));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+    use std::thread;
+    use std::time::Duration;
+    use std::time::Instant;
+
+    use tempfile::TempDir;
+
+    use super::FileWatcher;
+    use super::RESCAN_INTERVAL;
+    use crate::config::Config;
+    use crate::model::DiscoveredFile;
+    use crate::model::SourceRoot;
+    use crate::tui::app::App;
+
+    fn watched_app(tmp: &TempDir) -> App {
+        let file = tmp.path().join("CLAUDE.md");
+        fs::write(&file, "one\ntwo\nthree\n").unwrap();
+        let mut app = App::new(
+            vec![SourceRoot {
+                path: tmp.path().to_path_buf(),
+                files: vec![DiscoveredFile::new(file)],
+                context_files: Vec::new(),
+                misnamed_files: Vec::new(),
+                truncated: false,
+            }],
+            &Config::default(),
+        );
+        app.watcher = Some(FileWatcher::new(Duration::ZERO));
+        app
+    }
+
+    #[test]
+    fn shown_file_reloads_when_edited_elsewhere() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = watched_app(&tmp);
+        let start = Instant::now();
+        app.tick(start);
+        app.content.cursor = 2;
+
+        fs::write(tmp.path().join("CLAUDE.md"), "one\ntwo\nthree\nfour\n").unwrap();
+        app.tick(start + Duration::from_secs(2));
+
+        assert_eq!(app.content.text.as_deref(), Some("one\ntwo\nthree\nfour\n"));
+        assert_eq!(app.content.cursor, 2);
+        assert!(
+            app.status_message
+                .as_deref()
+                .unwrap()
+                .starts_with("Reloaded")
+        );
+    }
+
+    #[test]
+    fn added_file_appears_after_the_background_walk() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = watched_app(&tmp);
+        let start = Instant::now();
+        app.tick(start);
+
+        fs::create_dir_all(tmp.path().join("sub")).unwrap();
+        fs::write(tmp.path().join("sub/CLAUDE.md"), "new").unwrap();
+        app.tick(start + Duration::from_secs(2));
+        assert_eq!(app.roots[0].files.len(), 1, "polls only stat known files");
+
+        let later = start + RESCAN_INTERVAL;
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while app.roots[0].files.len() == 1 && Instant::now() < deadline {
+            app.tick(later);
+            thread::sleep(Duration::from_millis(5));
+        }
+
+        assert_eq!(app.roots[0].files.len(), 2);
+        assert_eq!(
+            app.selected_file(),
+            Some(tmp.path().join("CLAUDE.md")),
+            "selection stays put"
+        );
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Files changed on disk: 1 added, 0 removed.")
+        );
+    }
+
+    #[test]
+    fn removed_file_leaves_the_tree_on_the_next_poll() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = watched_app(&tmp);
+        let start = Instant::now();
+        app.tick(start);
+
+        fs::remove_file(tmp.path().join("CLAUDE.md")).unwrap();
+        app.tick(start + Duration::from_secs(2));
+
+        assert!(app.roots[0].files.is_empty());
+        assert_eq!(
+            app.status_message.as_deref(),
+            Some("Files changed on disk: 0 added, 1 removed.")
+        );
+    }
+
+    #[test]
+    fn nothing_happens_between_polls() {
+        let tmp = TempDir::new().unwrap();
+        let mut app = watched_app(&tmp);
+        let start = Instant::now();
+        app.tick(start);
+        let status = app.status_message.clone();
+
+        fs::write(tmp.path().join("CLAUDE.md"), "changed\n").unwrap();
+        app.tick(start + Duration::from_millis(100));
+
+        assert_eq!(app.content.text.as_deref(), Some("one\ntwo\nthree\n"));
+        assert_eq!(app.status_message, status);
+    }
+}
@@ -0,0 +1,72 @@
+//! Warnings raised while jigolo runs: a directory the scan cannot read, a
+//! project config that does not parse.
+//!
+//! Code that hits such a problem calls [`warn`] and carries on. From the
+//! command line the warning goes straight to stderr. While the TUI is open
+//! stderr would draw over the alternate screen, so the TUI [`capture`]s
+//! warnings instead and shows them in its status bar as it [`take`]s them.
+
+use std::mem;
+use std::sync::Mutex;
+use std::sync::PoisonError;
+
+/// Warnings held back while captured; `None` when they go to stderr.
+static CAPTURED: Mutex<Option<Vec<String>>> = Mutex::new(None);
+
+/// Reports `message`: printed to stderr as `Warning: …`, or held for the
+/// TUI while warnings are captured.
+pub fn warn(message: impl Into<String>) {
+    let message = message.into();
+    let mut captured = CAPTURED.lock().unwrap_or_else(PoisonError::into_inner);
+    match captured.as_mut() {
+        Some(pending) => pending.push(message),
+        None => eprintln!("Warning: {message}"),
+    }
+}
+
+/// Holds warnings back from stderr until [`release`].
+pub fn capture() {
+    CAPTURED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .get_or_insert_with(Vec::new);
+}
+
+/// The warnings held back since the last call, oldest first.
+pub fn take() -> Vec<String> {
+    CAPTURED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .as_mut()
+        .map(mem::take)
+        .unwrap_or_default()
+}
+
+/// Sends warnings to stderr again, returning the ones never taken.
+pub fn release() -> Vec<String> {
+    CAPTURED
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .take()
+        .unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn captured_warnings_are_held_until_taken() {
+        capture();
+        warn("first");
+        warn("second");
+        let warnings = take();
+        release();
+
+        // Other tests may warn while these are captured.
+        let first = warnings.iter().position(|w| w == "first");
+        let second = warnings.iter().position(|w| w == "second");
+        assert!(first.is_some() && first < second, "{warnings:?}");
+        assert!(take().is_empty());
+    }
+}
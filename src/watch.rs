@@ -0,0 +1,359 @@
+//! Debouncing and coalescing of file-system change events.
+//!
+//! A build or `git checkout` can touch hundreds of files in a burst. Feeding
+//! each raw event to the TUI would trigger a rescan per event, so events are
+//! collected per path and only released once that path has been quiet for
+//! the debounce window. Opposing events cancel out (a temp file created and
+//! removed within the window produces nothing).
+//!
+//! Events come from [`PollSnapshot`], which compares file sizes and
+//! modification times between polls, so no platform watcher is needed.
+
+use std::collections::HashMap;
+use std::fs;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use std::time::Duration;
+use std::time::Instant;
+use std::time::SystemTime;
+
+use crate::warnings::warn;
+
+/// Debounce window used when the config does not set `watch_debounce_ms`.
+pub const DEFAULT_DEBOUNCE_MS: u64 = 250;
+
+/// A path that keeps changing is still released after this many windows, so
+/// a constantly rewritten file cannot starve the UI of updates.
+const MAX_DELAY_WINDOWS: u32 = 4;
+
+/// What happened to a path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ChangeKind {
+    Created,
+    Modified,
+    Removed,
+}
+
+/// A coalesced change ready to be applied.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Change {
+    pub path: PathBuf,
+    pub kind: ChangeKind,
+}
+
+/// Pending state for one path.
+#[derive(Debug, Clone, Copy)]
+struct Pending {
+    /// Net effect so far; `None` when events cancelled out.
+    kind: Option<ChangeKind>,
+    first_seen: Instant,
+    last_seen: Instant,
+}
+
+/// Collects raw change events and releases them in per-path batches.
+#[derive(Debug)]
+pub struct Debouncer {
+    window: Duration,
+    pending: HashMap<PathBuf, Pending>,
+}
+
+impl Debouncer {
+    /// Creates a debouncer that waits `window` of quiet per path.
+    pub fn new(window: Duration) -> Self {
+        Self {
+            window,
+            pending: HashMap::new(),
+        }
+    }
+
+    /// Records an event for `path` observed at `now`.
+    pub fn push(&mut self, path: PathBuf, kind: ChangeKind, now: Instant) {
+        match self.pending.get_mut(&path) {
+            Some(pending) => {
+                pending.kind = coalesce(pending.kind, kind);
+                pending.last_seen = now;
+            }
+            None => {
+                self.pending.insert(
+                    path,
+                    Pending {
+                        kind: Some(kind),
+                        first_seen: now,
+                        last_seen: now,
+                    },
+                );
+            }
+        }
+    }
+
+    /// Removes and returns the changes whose paths have been quiet for the
+    /// window (or pending for too long), sorted by path.
+    pub fn drain_ready(&mut self, now: Instant) -> Vec<Change> {
+        let max_delay = self.window * MAX_DELAY_WINDOWS;
+        let ready: Vec<PathBuf> = self
+            .pending
+            .iter()
+            .filter(|(_, p)| {
+                now.saturating_duration_since(p.last_seen) >= self.window
+                    || now.saturating_duration_since(p.first_seen) >= max_delay
+            })
+            .map(|(path, _)| path.clone())
+            .collect();
+
+        let mut changes: Vec<Change> = ready
+            .into_iter()
+            .filter_map(|path| {
+                let pending = self.pending.remove(&path)?;
+                Some(Change {
+                    path,
+                    kind: pending.kind?,
+                })
+            })
+            .collect();
+        changes.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+        changes
+    }
+
+    /// Returns the earliest instant at which [`drain_ready`](Self::drain_ready)
+    /// may release something, or `None` when nothing is pending.
+    pub fn next_deadline(&self) -> Option<Instant> {
+        let max_delay = self.window * MAX_DELAY_WINDOWS;
+        self.pending
+            .values()
+            .map(|p| (p.last_seen + self.window).min(p.first_seen + max_delay))
+            .min()
+    }
+
+    /// Returns true if no events are waiting.
+    pub fn is_empty(&self) -> bool {
+        self.pending.is_empty()
+    }
+}
+
+/// Size and modification time of a file, compared between polls.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Stamp {
+    modified: Option<SystemTime>,
+    len: u64,
+}
+
+/// The files seen by the last poll, with their stamps.
+#[derive(Debug, Default)]
+pub struct PollSnapshot {
+    stamps: HashMap<PathBuf, Stamp>,
+}
+
+impl PollSnapshot {
+    /// Stats `files`, the complete set of files that exist now, and returns
+    /// how it differs from the previous call, sorted by path. Files that
+    /// cannot be stat'ed count as removed, with a warning when they still
+    /// exist.
+    pub fn update(&mut self, files: &[PathBuf]) -> Vec<(PathBuf, ChangeKind)> {
+        let mut stamps = HashMap::new();
+        for file in files {
+            match fs::metadata(file) {
+                Ok(meta) => {
+                    let stamp = Stamp {
+                        modified: meta.modified().ok(),
+                        len: meta.len(),
+                    };
+                    stamps.insert(file.clone(), stamp);
+                }
+                Err(err) if err.kind() != ErrorKind::NotFound && self.stamps.contains_key(file) => {
+                    warn(format!("cannot watch {}: {err}", file.display()));
+                }
+                Err(_) => {}
+            }
+        }
+
+        let mut changes: Vec<(PathBuf, ChangeKind)> = stamps
+            .iter()
+            .filter_map(|(path, stamp)| match self.stamps.get(path) {
+                None => Some((path.clone(), ChangeKind::Created)),
+                Some(old) if old != stamp => Some((path.clone(), ChangeKind::Modified)),
+                Some(_) => None,
+            })
+            .collect();
+        changes.extend(
+            self.stamps
+                .keys()
+                .filter(|path| !stamps.contains_key(*path))
+                .map(|path| (path.clone(), ChangeKind::Removed)),
+        );
+        changes.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+        self.stamps = stamps;
+        changes
+    }
+}
+
+/// Combines the net effect so far with a newer event.
+fn coalesce(previous: Option<ChangeKind>, next: ChangeKind) -> Option<ChangeKind> {
+    use ChangeKind::Created;
+    use ChangeKind::Modified;
+    use ChangeKind::Removed;
+
+    match (previous, next) {
+        // Created then removed inside one window: nothing to report.
+        (Some(Created), Removed) => None,
+        (Some(Created), _) => Some(Created),
+        // Removed then recreated: the file is still there, with new content.
+        (Some(Removed), Created | Modified) => Some(Modified),
+        (None, Removed) => None,
+        (None, Created | Modified) => Some(Created),
+        (Some(Modified), kind) | (Some(Removed), kind) => Some(kind),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const WINDOW: Duration = Duration::from_millis(250);
+
+    fn ms(n: u64) -> Duration {
+        Duration::from_millis(n)
+    }
+
+    #[test]
+    fn event_is_held_until_window_passes() {
+        let start = Instant::now();
+        let mut d = Debouncer::new(WINDOW);
+        d.push(PathBuf::from("/a"), ChangeKind::Modified, start);
+
+        assert!(d.drain_ready(start + ms(100)).is_empty());
+        let ready = d.drain_ready(start + ms(250));
+
+        assert_eq!(
+            ready,
+            vec![Change {
+                path: PathBuf::from("/a"),
+                kind: ChangeKind::Modified
+            }]
+        );
+        assert!(d.is_empty());
+    }
+
+    #[test]
+    fn repeated_events_extend_the_window_and_coalesce() {
+        let start = Instant::now();
+        let mut d = Debouncer::new(WINDOW);
+        for i in 0..5 {
+            d.push(
+                PathBuf::from("/a"),
+                ChangeKind::Modified,
+                start + ms(i * 100),
+            );
+        }
+
+        assert!(d.drain_ready(start + ms(500)).is_empty());
+        assert_eq!(d.drain_ready(start + ms(650)).len(), 1);
+    }
+
+    #[test]
+    fn burst_across_many_paths_is_released_as_one_sorted_batch() {
+        let start = Instant::now();
+        let mut d = Debouncer::new(WINDOW);
+        for i in (0..300).rev() {
+            d.push(
+                PathBuf::from(format!("/repo/{i:03}")),
+                ChangeKind::Modified,
+                start,
+            );
+        }
+
+        let ready = d.drain_ready(start + WINDOW);
+
+        assert_eq!(ready.len(), 300);
+        assert_eq!(ready[0].path, PathBuf::from("/repo/000"));
+        assert_eq!(ready[299].path, PathBuf::from("/repo/299"));
+    }
+
+    #[test]
+    fn create_then_remove_cancels_out() {
+        let start = Instant::now();
+        let mut d = Debouncer::new(WINDOW);
+        d.push(PathBuf::from("/tmp.md"), ChangeKind::Created, start);
+        d.push(
+            PathBuf::from("/tmp.md"),
+            ChangeKind::Removed,
+            start + ms(10),
+        );
+
+        assert!(d.drain_ready(start + ms(500)).is_empty());
+        assert!(d.is_empty());
+    }
+
+    #[test]
+    fn remove_then_create_is_a_modification() {
+        let start = Instant::now();
+        let mut d = Debouncer::new(WINDOW);
+        d.push(PathBuf::from("/a"), ChangeKind::Removed, start);
+        d.push(PathBuf::from("/a"), ChangeKind::Created, start + ms(10));
+
+        let ready = d.drain_ready(start + ms(500));
+        assert_eq!(ready[0].kind, ChangeKind::Modified);
+    }
+
+    #[test]
+    fn constantly_changing_path_is_released_after_max_delay() {
+        let start = Instant::now();
+        let mut d = Debouncer::new(WINDOW);
+        let mut now = start;
+        let mut released = false;
+        for _ in 0..20 {
+            d.push(PathBuf::from("/busy"), ChangeKind::Modified, now);
+            now += ms(100);
+            if !d.drain_ready(now).is_empty() {
+                released = true;
+                break;
+            }
+        }
+        assert!(released);
+        assert!(now - start <= WINDOW * MAX_DELAY_WINDOWS);
+    }
+
+    #[test]
+    fn next_deadline_tracks_earliest_pending_path() {
+        let start = Instant::now();
+        let mut d = Debouncer::new(WINDOW);
+        assert!(d.next_deadline().is_none());
+
+        d.push(PathBuf::from("/a"), ChangeKind::Modified, start);
+        d.push(PathBuf::from("/b"), ChangeKind::Modified, start + ms(100));
+
+        assert_eq!(d.next_deadline(), Some(start + WINDOW));
+    }
+
+    #[test]
+    fn snapshot_reports_created_modified_and_removed_files() {
+        let tmp = tempfile::TempDir::new().unwrap();
+        let kept = tmp.path().join("kept.md");
+        let edited = tmp.path().join("edited.md");
+        let gone = tmp.path().join("gone.md");
+        for file in [&kept, &edited, &gone] {
+            fs::write(file, "one").unwrap();
+        }
+        let mut snapshot = PollSnapshot::default();
+        assert_eq!(
+            snapshot
+                .update(&[kept.clone(), edited.clone(), gone.clone()])
+                .len(),
+            3
+        );
+
+        fs::write(&edited, "one two").unwrap();
+        fs::remove_file(&gone).unwrap();
+        let added = tmp.path().join("added.md");
+        fs::write(&added, "new").unwrap();
+        let changes = snapshot.update(&[kept, edited.clone(), added.clone()]);
+
+        assert_eq!(
+            changes,
+            [
+                (added, ChangeKind::Created),
+                (edited, ChangeKind::Modified),
+                (gone, ChangeKind::Removed),
+            ]
+        );
+    }
+}
@@ -53,6 +53,171 @@ fn finds_claude_md_in_temp_dir() {
         .stdout(predicate::str::contains("1 file"));
 }
 
+#[test]
+fn no_global_leaves_out_the_global_claude_md() {
+    let tmp = TempDir::new().unwrap();
+    let project = tmp.path().join("project");
+    std::fs::create_dir_all(&project).unwrap();
+    std::fs::write(project.join("CLAUDE.md"), "rules").unwrap();
+    std::fs::create_dir_all(tmp.path().join("home/.claude")).unwrap();
+    std::fs::write(tmp.path().join("home/.claude/CLAUDE.md"), "mine").unwrap();
+
+    cmd()
+        .env("HOME", tmp.path().join("home"))
+        .arg(&project)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Found 2 CLAUDE.md files"));
+    cmd()
+        .env("HOME", tmp.path().join("home"))
+        .arg("--no-global")
+        .arg(&project)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Found 1 CLAUDE.md file in"));
+}
+
+#[test]
+fn repo_root_scans_from_the_top_of_the_repository() {
+    let tmp = TempDir::new().unwrap();
+    let repo = tmp.path().join("repo");
+    let deep = repo.join("crates/deep");
+    std::fs::create_dir_all(repo.join(".git")).unwrap();
+    std::fs::create_dir_all(&deep).unwrap();
+    std::fs::write(repo.join("CLAUDE.md"), "top").unwrap();
+    std::fs::write(deep.join("CLAUDE.md"), "deep").unwrap();
+
+    cmd()
+        .env("HOME", tmp.path().join("home"))
+        .arg(&deep)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Found 1 CLAUDE.md file in"));
+    cmd()
+        .env("HOME", tmp.path().join("home"))
+        .arg("--repo-root")
+        .arg(&deep)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Found 2 CLAUDE.md files"));
+}
+
+#[test]
+fn exclude_and_include_change_the_skipped_directories() {
+    let tmp = TempDir::new().unwrap();
+    for dir in ["fixtures", "vendor"] {
+        std::fs::create_dir_all(tmp.path().join(dir)).unwrap();
+        std::fs::write(tmp.path().join(dir).join("CLAUDE.md"), dir).unwrap();
+    }
+
+    cmd()
+        .env("HOME", tmp.path().join("home"))
+        .args(["--exclude", "fixtures", "--include", "vendor"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("vendor/CLAUDE.md"))
+        .stdout(predicate::str::contains("fixtures").not());
+}
+
+#[test]
+fn max_files_and_max_depth_cut_the_scan_short() {
+    let tmp = TempDir::new().unwrap();
+    for dir in ["a", "b", "c/deep"] {
+        std::fs::create_dir_all(tmp.path().join(dir)).unwrap();
+        std::fs::write(tmp.path().join(dir).join("CLAUDE.md"), dir).unwrap();
+    }
+
+    cmd()
+        .env("HOME", tmp.path().join("home"))
+        .args(["--max-files", "2"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Found 2 CLAUDE.md files"))
+        .stdout(predicate::str::contains("Stopped at --max-files 2 in"));
+    cmd()
+        .env("HOME", tmp.path().join("home"))
+        .args(["--max-depth", "2"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Found 2 CLAUDE.md files"))
+        .stdout(predicate::str::contains("Stopped at").not());
+}
+
+#[test]
+fn pattern_adds_file_names_to_the_scan() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("CLAUDE.md"), "rules").unwrap();
+    std::fs::write(tmp.path().join("AGENTS.md"), "agents").unwrap();
+
+    cmd()
+        .env("HOME", tmp.path().join("home"))
+        .args(["--pattern", "AGENTS.md"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("AGENTS.md"))
+        .stdout(predicate::str::contains("Found 2 CLAUDE.md files"));
+}
+
+#[test]
+fn list_shows_agents_commands_and_skills() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("CLAUDE.md"), "rules").unwrap();
+    std::fs::create_dir_all(tmp.path().join(".claude/agents")).unwrap();
+    std::fs::write(tmp.path().join(".claude/agents/reviewer.md"), "agent").unwrap();
+
+    cmd()
+        .env("HOME", tmp.path().join("home"))
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            ".claude/agents/reviewer.md [agent]",
+        ));
+}
+
+#[test]
+fn list_reports_misnamed_files() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("claude.md"), "rules").unwrap();
+
+    cmd()
+        .env("HOME", tmp.path().join("home"))
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "claude.md [misnamed, ignored; rename to CLAUDE.md]",
+        ))
+        .stdout(predicate::str::contains("Found 0 CLAUDE.md files"));
+}
+
+#[test]
+fn list_stats_shows_root_totals_and_summary_table() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join("sub")).unwrap();
+    std::fs::write(tmp.path().join("CLAUDE.md"), "a\nb\n").unwrap();
+    std::fs::write(tmp.path().join("sub/CLAUDE.md"), "x".repeat(400)).unwrap();
+
+    cmd()
+        .env("HOME", tmp.path().join("home"))
+        .arg("--stats")
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("  = 404 B · 3 lines · 101 tok"))
+        .stdout(predicate::str::is_match(r"(?m)^Root +Files +Size +Lines +Tokens$").unwrap())
+        .stdout(predicate::str::is_match(r"(?m)^Total +2 +404 B +3 +101 tok$").unwrap());
+}
+
+#[test]
+fn stats_needs_list() {
+    cargo_bin_cmd!("jigolo").arg("--stats").assert().failure();
+}
+
 #[test]
 fn no_claude_files_prints_friendly_message() {
     let tmp = TempDir::new().unwrap();
@@ -93,3 +258,473 @@ fn mixed_valid_and_invalid_paths_still_succeeds() {
         .stdout(predicate::str::contains("1 file"))
         .stderr(predicate::str::contains("Warning"));
 }
+
+#[test]
+fn stop_at_repo_boundaries_skips_nested_repos() {
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("CLAUDE.md"), "root").unwrap();
+    let nested = tmp.path().join("libs/other");
+    std::fs::create_dir_all(nested.join(".git")).unwrap();
+    std::fs::write(nested.join("CLAUDE.md"), "nested").unwrap();
+
+    cmd()
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("2 files"));
+    cmd()
+        .arg("--stop-at-repo-boundaries")
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 file").and(predicate::str::contains("libs").not()));
+}
+
+#[test]
+fn library_subcommand_is_listed_in_help() {
+    cargo_bin_cmd!("jigolo")
+        .arg("--help")
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("library"));
+}
+
+#[test]
+fn library_subcommand_rejects_scan_flags() {
+    cargo_bin_cmd!("jigolo")
+        .args(["--list", "library"])
+        .assert()
+        .failure();
+}
+
+fn library_home() -> TempDir {
+    let home = TempDir::new().unwrap();
+    let dir = home.path().join(".config/jigolo");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("library.toml"),
+        r#"
+[[snippets]]
+title = "Style"
+content = "Prefer small functions"
+
+[[snippets]]
+title = "Build"
+content = "cargo build --release"
+"#,
+    )
+    .unwrap();
+    home
+}
+
+#[test]
+fn snippets_grep_prints_id_title_and_format() {
+    let home = library_home();
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .args(["snippets", "grep", "CARGO"])
+        .assert()
+        .success()
+        .stdout("2\tBuild\tshell\n");
+}
+
+#[test]
+fn snippets_grep_json_lists_matches() {
+    let home = library_home();
+
+    let output = cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .args(["snippets", "grep", "--json", "small"])
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let matches: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(matches[0]["id"], 1);
+    assert_eq!(matches[0]["title"], "Style");
+    assert_eq!(matches.as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn snippets_grep_without_matches_exits_1() {
+    let home = library_home();
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .args(["snippets", "grep", "nothing"])
+        .assert()
+        .code(1)
+        .stdout("");
+}
+
+#[test]
+fn library_export_then_import_skips_duplicates() {
+    let home = library_home();
+    let bundle = home.path().join("team.json");
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .args(["library", "export"])
+        .arg(&bundle)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Exported 2 snippets"));
+    let exported: serde_json::Value =
+        serde_json::from_str(&std::fs::read_to_string(&bundle).unwrap()).unwrap();
+    assert_eq!(exported["snippets"][1]["title"], "Build");
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .args(["library", "import"])
+        .arg(&bundle)
+        .assert()
+        .success()
+        .stdout("Imported 0 snippets (2 duplicates skipped).\n");
+}
+
+#[test]
+fn library_import_of_missing_bundle_fails() {
+    let home = library_home();
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .args(["library", "import", "/missing/team.toml"])
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("/missing/team.toml"));
+}
+
+#[test]
+fn report_prints_markdown_with_masked_config() {
+    let home = TempDir::new().unwrap();
+    let dir = home.path().join(".config/jigolo");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(
+        dir.join("config.toml"),
+        "theme = \"light\"\ngithub_token = \"s3cret\"\n",
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .arg("report")
+        .assert()
+        .success()
+        .stdout(predicate::str::starts_with("## jigolo bug report"))
+        .stdout(predicate::str::contains("github_token = \"***\""))
+        .stdout(predicate::str::contains("s3cret").not());
+}
+
+#[test]
+fn report_output_flag_writes_file() {
+    let home = TempDir::new().unwrap();
+    let out = home.path().join("report.md");
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .args(["report", "--output"])
+        .arg(&out)
+        .assert()
+        .success()
+        .stdout("");
+
+    let report = std::fs::read_to_string(&out).unwrap();
+    assert!(report.contains("### Last error"));
+}
+
+#[test]
+fn export_prints_files_under_path_headers() {
+    let home = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join("sub")).unwrap();
+    std::fs::write(tmp.path().join("CLAUDE.md"), "# Root rules\n").unwrap();
+    std::fs::write(tmp.path().join("sub/CLAUDE.md"), "Sub rules\n").unwrap();
+    let root = tmp.path().canonicalize().unwrap();
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .arg("export")
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(format!(
+            "# {root}/CLAUDE.md\n\n# Root rules\n\n# {root}/sub/CLAUDE.md\n\nSub rules\n",
+            root = root.display()
+        ));
+}
+
+#[test]
+fn export_json_lists_root_path_and_content() {
+    let home = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    std::fs::write(tmp.path().join("CLAUDE.md"), "rules\n").unwrap();
+
+    let output = cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .args(["export", "--format", "json"])
+        .arg(tmp.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    let files: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let root = tmp.path().canonicalize().unwrap();
+    assert_eq!(files.as_array().unwrap().len(), 1);
+    assert_eq!(files[0]["root"], root.display().to_string());
+    assert_eq!(files[0]["content"], "rules\n");
+}
+
+#[test]
+fn export_of_missing_path_fails() {
+    cargo_bin_cmd!("jigolo")
+        .args(["export", "/nonexistent/path/that/does/not/exist"])
+        .assert()
+        .code(1)
+        .stdout("");
+}
+
+#[test]
+fn session_lists_what_a_session_in_dir_loads() {
+    let home = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join("src/deep")).unwrap();
+    std::fs::write(tmp.path().join("CLAUDE.md"), "Root rules @docs.md\n").unwrap();
+    std::fs::write(tmp.path().join("docs.md"), "Imported docs\n").unwrap();
+    let root = tmp.path().canonicalize().unwrap();
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .arg("session")
+        .arg(tmp.path().join("src/deep"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!(
+            "1. `{}/CLAUDE.md` — project memory",
+            root.display()
+        )))
+        .stdout(predicate::str::contains(format!(
+            "2. `{root}/docs.md` — imported by `{root}/CLAUDE.md`",
+            root = root.display()
+        )))
+        .stdout(predicate::str::contains("Imported docs"));
+}
+
+#[test]
+fn which_prints_the_context_chain_in_load_order() {
+    let home = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    std::fs::create_dir_all(home.path().join(".claude")).unwrap();
+    std::fs::write(home.path().join(".claude/CLAUDE.md"), "Mine\n").unwrap();
+    std::fs::create_dir_all(tmp.path().join("src")).unwrap();
+    std::fs::write(tmp.path().join("CLAUDE.md"), "Root rules @docs.md\n").unwrap();
+    std::fs::write(tmp.path().join("docs.md"), "Imported docs\n").unwrap();
+    std::fs::write(tmp.path().join("src/CLAUDE.local.md"), "Local\n").unwrap();
+    let root = tmp.path().canonicalize().unwrap();
+    let user = home
+        .path()
+        .canonicalize()
+        .unwrap()
+        .join(".claude/CLAUDE.md");
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path().canonicalize().unwrap())
+        .arg("which")
+        .arg(tmp.path().join("src"))
+        .assert()
+        .success()
+        .stdout(format!(
+            "{}\n{root}/CLAUDE.md\n{root}/docs.md\n{root}/src/CLAUDE.local.md\n",
+            user.display(),
+            root = root.display()
+        ));
+
+    let output = cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path().canonicalize().unwrap())
+        .args(["which", "--json"])
+        .arg(tmp.path().join("src"))
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let chain: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let reasons: Vec<&str> = chain
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|file| file["reason"].as_str().unwrap())
+        .collect();
+    assert_eq!(reasons, ["user", "project", "import", "local"]);
+    assert_eq!(
+        chain[2]["imported_by"],
+        format!("{}/CLAUDE.md", root.display())
+    );
+}
+
+#[test]
+fn init_creates_claude_md_from_a_team_template_and_keeps_existing_files() {
+    let home = TempDir::new().unwrap();
+    let team = TempDir::new().unwrap();
+    let project = TempDir::new().unwrap();
+    std::fs::write(
+        team.path().join("service.md"),
+        "<!-- Service with CI -->\n# Service\n",
+    )
+    .unwrap();
+    let config_dir = home.path().join(".config/jigolo");
+    std::fs::create_dir_all(&config_dir).unwrap();
+    std::fs::write(
+        config_dir.join("config.toml"),
+        format!("template_source = \"{}\"\n", team.path().display()),
+    )
+    .unwrap();
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .args(["init", "--list"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("Starter"))
+        .stdout(predicate::str::contains("service          Service with CI"));
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .args(["init", "--template", "service"])
+        .arg(project.path())
+        .assert()
+        .success();
+    let created = std::fs::read_to_string(project.path().join("CLAUDE.md")).unwrap();
+    assert_eq!(created, "# Service\n");
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .arg("init")
+        .arg(project.path())
+        .assert()
+        .failure()
+        .stderr(predicate::str::contains("already exists"));
+}
+
+#[test]
+fn lint_reports_problems_and_fails_on_errors() {
+    let home = TempDir::new().unwrap();
+    let tmp = TempDir::new().unwrap();
+    std::fs::create_dir_all(tmp.path().join("sub")).unwrap();
+    std::fs::write(tmp.path().join("CLAUDE.md"), "Rules @gone.md\n").unwrap();
+    std::fs::write(tmp.path().join("sub/CLAUDE.md"), "").unwrap();
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .arg("lint")
+        .arg(tmp.path())
+        .assert()
+        .code(3)
+        .stdout(predicate::str::contains(
+            "CLAUDE.md:1: error[missing-import]: @gone.md points at no file",
+        ))
+        .stdout(predicate::str::contains("warning[empty-file]"))
+        .stderr(predicate::str::contains(
+            "Checked 2 files: 1 error(s), 1 warning(s).",
+        ));
+
+    std::fs::write(tmp.path().join("CLAUDE.md"), "Rules\n").unwrap();
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .args(["lint", "--format", "json"])
+        .arg(tmp.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("\"rule\": \"empty-file\""));
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .args(["lint", "--strict"])
+        .arg(tmp.path())
+        .assert()
+        .code(3);
+    cargo_bin_cmd!("jigolo")
+        .args(["lint", "--help"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "with status 3 when there are errors",
+        ));
+}
+
+#[test]
+fn journal_lists_files_created_by_init() {
+    let home = TempDir::new().unwrap();
+    let project = TempDir::new().unwrap();
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .arg("journal")
+        .assert()
+        .success()
+        .stderr(predicate::str::contains("No changes recorded."));
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .arg("init")
+        .arg(project.path())
+        .assert()
+        .success();
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .arg("journal")
+        .arg(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("create"))
+        .stdout(predicate::str::contains("CLAUDE.md"));
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .arg("journal")
+        .arg(home.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn dry_run_prints_the_diff_without_writing() {
+    let home = library_home();
+    let project = TempDir::new().unwrap();
+    let bundle = project.path().join("team.toml");
+    std::fs::write(
+        &bundle,
+        "[[snippets]]\ntitle = \"Deploy\"\ncontent = \"make deploy\"\n",
+    )
+    .unwrap();
+    let library = home.path().join(".config/jigolo/library.toml");
+    let before = std::fs::read_to_string(&library).unwrap();
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .args(["library", "import", "--dry-run"])
+        .arg(&bundle)
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+title = \"Deploy\""))
+        .stdout(predicate::str::contains("Dry run: Imported 1 snippet"));
+    assert_eq!(std::fs::read_to_string(&library).unwrap(), before);
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .args(["library", "export", "--dry-run"])
+        .arg(project.path().join("out.json"))
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+++ "))
+        .stdout(predicate::str::contains("Would export 2 snippets"));
+    assert!(!project.path().join("out.json").exists());
+
+    cargo_bin_cmd!("jigolo")
+        .env("HOME", home.path())
+        .args(["init", "--dry-run"])
+        .arg(project.path())
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("+# "))
+        .stderr(predicate::str::contains("Would create"));
+    assert!(!project.path().join("CLAUDE.md").exists());
+}